@@ -0,0 +1,29 @@
+#![no_main]
+
+use {
+    libfuzzer_sys::fuzz_target,
+    solana_transaction_tui::{
+        ingest, message_format::FORMATS, transaction_byte_sections::get_transaction_byte_sections,
+    },
+};
+
+// Arbitrary bytes must either be rejected or parse into a transaction whose
+// sections cover exactly the input, without panicking along the way. This
+// holds for the detected message format as well as any forced one.
+fuzz_target!(|data: &[u8]| {
+    let forced = FORMATS
+        .iter()
+        .map(|format| ingest::parse_transaction_as(data, *format));
+    for transaction in std::iter::once(ingest::parse_transaction(data)).chain(forced) {
+        let Ok(transaction) = transaction else {
+            continue;
+        };
+        let mut sections = vec![];
+        get_transaction_byte_sections(&transaction, &mut sections);
+        let section_bytes = sections
+            .iter()
+            .flat_map(|section| section.bytes.iter().copied())
+            .collect::<Vec<_>>();
+        assert_eq!(section_bytes, data);
+    }
+});
@@ -0,0 +1,154 @@
+//! How each instruction uses each account, as a matrix with accounts as rows
+//! and instructions as columns, to see the access pattern at a glance.
+
+use {
+    crate::transaction_byte_sections::{section_starts, SectionKind, TransactionByteSection},
+    solana_sdk::transaction::VersionedTransaction,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+    /// The program the instruction invokes.
+    Program,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub access: Access,
+    pub signer: bool,
+}
+
+impl Cell {
+    pub fn symbol(&self) -> &'static str {
+        match (self.access, self.signer) {
+            (Access::Read, false) => "R",
+            (Access::Read, true) => "RS",
+            (Access::Write, false) => "W",
+            (Access::Write, true) => "WS",
+            (Access::Program, _) => "P",
+        }
+    }
+}
+
+pub struct AccessMatrix {
+    /// The cells of each account, by instruction.
+    pub rows: Vec<Vec<Option<Cell>>>,
+}
+
+impl AccessMatrix {
+    /// The matrix of `num_accounts` accounts, the static keys followed by
+    /// those loaded from lookup tables, the first `num_loaded_writable` of
+    /// them writable.
+    pub fn new(
+        transaction: &VersionedTransaction,
+        num_accounts: usize,
+        num_loaded_writable: usize,
+    ) -> Self {
+        let message = &transaction.message;
+        let num_static = message.static_account_keys().len();
+        let is_writable = |index: usize| match index.checked_sub(num_static) {
+            None => message.is_maybe_writable(index),
+            Some(loaded_index) => loaded_index < num_loaded_writable,
+        };
+        let instructions = message.instructions();
+        let rows = (0..num_accounts)
+            .map(|index| {
+                instructions
+                    .iter()
+                    .map(|instruction| {
+                        let access = if instruction.program_id_index as usize == index {
+                            Access::Program
+                        } else if instruction.accounts.contains(&(index as u8)) {
+                            if is_writable(index) {
+                                Access::Write
+                            } else {
+                                Access::Read
+                            }
+                        } else {
+                            return None;
+                        };
+                        Some(Cell {
+                            access,
+                            signer: message.is_signer(index),
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { rows }
+    }
+
+    pub fn num_instructions(&self) -> usize {
+        self.rows.first().map(Vec::len).unwrap_or(0)
+    }
+
+    /// The offset of the byte indexing the account of `row` in the
+    /// instruction of `column`, if the instruction uses it.
+    pub fn byte_offset(
+        &self,
+        transaction: &VersionedTransaction,
+        sections: &[TransactionByteSection],
+        row: usize,
+        column: usize,
+    ) -> Option<usize> {
+        let cell = (*self.rows.get(row)?.get(column)?)?;
+        let instruction = transaction.message.instructions().get(column)?;
+        let (kind, position) = match cell.access {
+            Access::Program => (SectionKind::InstructionProgramIdIndex(column), 0),
+            Access::Read | Access::Write => (
+                SectionKind::InstructionAccounts(column),
+                instruction
+                    .accounts
+                    .iter()
+                    .position(|index| *index as usize == row)?,
+            ),
+        };
+        section_starts(sections)
+            .find(|(_, section)| section.kind == kind)
+            .map(|(start, _)| start + position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::transaction_byte_sections::get_transaction_byte_sections,
+        solana_sdk::{
+            hash::Hash, message::Message, pubkey::Pubkey, system_instruction,
+            transaction::Transaction,
+        },
+    };
+
+    #[test]
+    fn marks_access_and_locates_index_bytes() {
+        let (from, to) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instruction = system_instruction::transfer(&from, &to, 1);
+        let message = Message::new_with_blockhash(&[instruction], Some(&from), &Hash::default());
+        let transaction = VersionedTransaction::from(Transaction::new_unsigned(message));
+        let matrix = AccessMatrix::new(&transaction, 3, 0);
+
+        let symbols = matrix
+            .rows
+            .iter()
+            .map(|row| row[0].map(|cell| cell.symbol()))
+            .collect::<Vec<_>>();
+        assert_eq!(symbols, [Some("WS"), Some("W"), Some("P")]);
+
+        let mut sections = vec![];
+        get_transaction_byte_sections(&transaction, &mut sections);
+        // The signature, header, 3 keys, blockhash and instruction count.
+        let program_index = 1 + 64 + 3 + 1 + 3 * 32 + 32 + 1;
+        assert_eq!(
+            matrix.byte_offset(&transaction, &sections, 2, 0),
+            Some(program_index)
+        );
+        // After the program index and the number of accounts.
+        assert_eq!(
+            matrix.byte_offset(&transaction, &sections, 1, 0),
+            Some(program_index + 3)
+        );
+    }
+}
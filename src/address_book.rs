@@ -0,0 +1,222 @@
+//! Named contacts, shown in place of their pubkeys wherever pubkeys appear,
+//! persisted between sessions.
+
+use {
+    crate::storage::{self, FileChange},
+    serde::{Deserialize, Serialize},
+    solana_sdk::{
+        pubkey::Pubkey,
+        signature::{read_keypair_file, Signer},
+    },
+    std::{
+        collections::BTreeMap,
+        fs, io,
+        path::{Path, PathBuf},
+        str::FromStr,
+    },
+};
+
+const ADDRESS_BOOK_FILE: &str = "address_book.json";
+/// The tag of contacts imported from keypair files.
+const KEYPAIR_TAG: &str = "keypair";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contact {
+    pub name: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Where the Solana CLI keeps its config and default keypair.
+pub fn default_keypair_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config").join("solana"))
+}
+
+/// Parse a contact typed as its pubkey, its name, then its tags prefixed
+/// with `#`, e.g. `<pubkey> hot wallet #mine #trading`.
+pub fn parse_contact(text: &str) -> Result<(Pubkey, Contact), String> {
+    let mut words = text.split_whitespace();
+    let pubkey = words
+        .next()
+        .ok_or_else(|| "Enter a pubkey, a name and optionally #tags".to_string())?;
+    let pubkey =
+        Pubkey::from_str(pubkey).map_err(|err| format!("Invalid pubkey {pubkey}: {err}"))?;
+    let (tags, name): (Vec<_>, Vec<_>) = words.partition(|word| word.starts_with('#'));
+    if name.is_empty() {
+        return Err(format!("Enter a name for {pubkey}"));
+    }
+    let contact = Contact {
+        name: name.join(" "),
+        tags: tags.iter().map(|tag| tag[1..].to_string()).collect(),
+    };
+    Ok((pubkey, contact))
+}
+
+/// The text [`parse_contact`] parses into the contact, to edit it.
+pub fn contact_text(pubkey: &Pubkey, contact: &Contact) -> String {
+    let tags = contact.tags.iter().map(|tag| format!(" #{tag}"));
+    format!("{pubkey} {}{}", contact.name, tags.collect::<String>())
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AddressBook {
+    /// Keyed by base58 pubkey.
+    contacts: BTreeMap<String, Contact>,
+    /// Why the address book failed to load, in which case it starts empty
+    /// and the file is not saved over.
+    #[serde(skip)]
+    load_error: Option<String>,
+}
+
+impl AddressBook {
+    pub fn load() -> Self {
+        Self::loaded(storage::try_load_json(ADDRESS_BOOK_FILE))
+    }
+
+    /// The loaded address book, or an empty one along with why it failed to
+    /// load.
+    fn loaded(result: Result<Self, String>) -> Self {
+        result.unwrap_or_else(|err| Self {
+            load_error: Some(err),
+            ..Self::default()
+        })
+    }
+
+    pub fn load_error(&self) -> Option<&str> {
+        self.load_error.as_deref()
+    }
+
+    pub fn save(&self) -> io::Result<FileChange> {
+        if let Some(err) = &self.load_error {
+            return Err(io::Error::other(format!("{err}, not saving over it")));
+        }
+        storage::save_json(ADDRESS_BOOK_FILE, self)
+    }
+
+    pub fn name(&self, pubkey: &Pubkey) -> Option<&str> {
+        self.get(pubkey).map(|contact| contact.name.as_str())
+    }
+
+    pub fn get(&self, pubkey: &Pubkey) -> Option<&Contact> {
+        self.contacts.get(&pubkey.to_string())
+    }
+
+    /// The contacts sorted by name.
+    pub fn contacts(&self) -> Vec<(Pubkey, &Contact)> {
+        let mut contacts = self
+            .contacts
+            .iter()
+            .filter_map(|(pubkey, contact)| Some((Pubkey::from_str(pubkey).ok()?, contact)))
+            .collect::<Vec<_>>();
+        contacts.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+        contacts
+    }
+
+    pub fn insert(&mut self, pubkey: &Pubkey, contact: Contact) {
+        self.contacts.insert(pubkey.to_string(), contact);
+    }
+
+    pub fn remove(&mut self, pubkey: &Pubkey) {
+        self.contacts.remove(&pubkey.to_string());
+    }
+
+    /// Add a contact for each of the `keypairs` read from a directory.
+    /// Existing contacts are kept. Returns the number of contacts added.
+    pub fn import_keypairs(&mut self, keypairs: Vec<(Pubkey, String)>) -> usize {
+        let mut num_added = 0;
+        for (pubkey, name) in keypairs {
+            if self.get(&pubkey).is_some() {
+                continue;
+            }
+            let contact = Contact {
+                name,
+                tags: vec![KEYPAIR_TAG.to_string()],
+            };
+            self.insert(&pubkey, contact);
+            num_added += 1;
+        }
+        num_added
+    }
+}
+
+/// The pubkeys of the keypair files in `dir`, each named after its file,
+/// e.g. `id` for the Solana CLI's default `id.json`.
+pub fn read_keypair_dir(dir: &Path) -> Result<Vec<(Pubkey, String)>, String> {
+    let entries =
+        fs::read_dir(dir).map_err(|err| format!("Failed to read {}: {err}", dir.display()))?;
+    let mut keypairs = vec![];
+    for path in entries.filter_map(|entry| Some(entry.ok()?.path())) {
+        if path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+        // Other JSON files, e.g. the CLI's config, are not keypairs.
+        let Ok(keypair) = read_keypair_file(&path) else {
+            continue;
+        };
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        keypairs.push((keypair.pubkey(), name));
+    }
+    Ok(keypairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::signature::{write_keypair_file, Keypair},
+    };
+
+    #[test]
+    fn parses_contacts_and_imports_keypairs() {
+        let pubkey = Pubkey::new_unique();
+        let text = format!("{pubkey} hot #mine wallet #trading");
+        let (parsed, contact) = parse_contact(&text).unwrap();
+        assert_eq!(parsed, pubkey);
+        assert_eq!(contact.name, "hot wallet");
+        assert_eq!(contact.tags, ["mine", "trading"]);
+        assert_eq!(
+            contact_text(&pubkey, &contact),
+            format!("{pubkey} hot wallet #mine #trading")
+        );
+        assert!(parse_contact(&pubkey.to_string()).is_err());
+        assert!(parse_contact("not-a-pubkey wallet").is_err());
+
+        let dir = std::env::temp_dir().join(format!("address-book-{pubkey}"));
+        fs::create_dir_all(&dir).unwrap();
+        let (id, named) = (Keypair::new(), Keypair::new());
+        write_keypair_file(&id, dir.join("id.json")).unwrap();
+        write_keypair_file(&named, dir.join("cold.json")).unwrap();
+        fs::write(dir.join("config.json"), "{}").unwrap();
+
+        let mut address_book = AddressBook::default();
+        address_book.insert(&named.pubkey(), contact);
+        let keypairs = read_keypair_dir(&dir).unwrap();
+        assert_eq!(keypairs.len(), 2);
+        assert_eq!(address_book.import_keypairs(keypairs), 1);
+        assert_eq!(address_book.name(&id.pubkey()), Some("id"));
+        assert_eq!(address_book.name(&named.pubkey()), Some("hot wallet"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn does_not_save_over_a_corrupt_file() {
+        let path = std::env::temp_dir().join(format!("address-book-{}.json", std::process::id()));
+        // A hand edit missing a closing brace.
+        let corrupt = r#"{"contacts": {"11111111111111111111111111111111": {"name": "system""#;
+        fs::write(&path, corrupt).unwrap();
+        let mut address_book = AddressBook::loaded(storage::try_load_json_at(&path));
+        let err = address_book.load_error().unwrap().to_string();
+        assert!(err.starts_with(&format!("Invalid {}", path.display())));
+        assert!(address_book.contacts().is_empty());
+
+        let (pubkey, contact) = parse_contact(&format!("{} wallet", Pubkey::new_unique())).unwrap();
+        address_book.insert(&pubkey, contact);
+        let save_err = address_book.save().unwrap_err();
+        assert_eq!(save_err.to_string(), format!("{err}, not saving over it"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), corrupt);
+        fs::remove_file(&path).unwrap();
+    }
+}
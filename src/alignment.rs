@@ -0,0 +1,252 @@
+//! Alignment of two transactions shown side by side, so that corresponding
+//! sections stay next to each other when their lengths differ. Sections
+//! correspond when they are the same field of the transaction, with keys and
+//! instructions paired by the structural diff, and the shorter of two
+//! corresponding sections is padded with gap cells.
+
+use {
+    solana_transaction_tui::{
+        transaction_byte_sections::TransactionByteSection, transaction_diff::TransactionDiff,
+    },
+    std::{iter, ops::Range},
+};
+
+/// The cells of one pane: a byte offset, relative to the first section, or
+/// `None` for a gap.
+pub struct AlignedPane {
+    cells: Vec<Option<usize>>,
+    gaps: Vec<(usize, usize)>,
+}
+
+impl AlignedPane {
+    fn new(cells: Vec<Option<usize>>) -> Self {
+        let mut gaps = vec![];
+        let mut num_gaps = 0;
+        for cell in &cells {
+            match cell {
+                None => num_gaps += 1,
+                Some(offset) if num_gaps > 0 => {
+                    gaps.push((*offset, num_gaps));
+                    num_gaps = 0;
+                }
+                Some(_) => {}
+            }
+        }
+        if num_gaps > 0 {
+            gaps.push((cells.iter().flatten().count(), num_gaps));
+        }
+        Self { cells, gaps }
+    }
+
+    /// Runs of gap cells as `(offset, count)`, each rendered before the byte
+    /// at `offset`.
+    pub fn gaps(&self) -> &[(usize, usize)] {
+        &self.gaps
+    }
+
+    /// The cell showing the byte at `offset`.
+    pub fn cell_of(&self, offset: usize) -> Option<usize> {
+        self.cells.iter().position(|cell| *cell == Some(offset))
+    }
+
+    /// The offset of the byte shown in `cell`, if it is not a gap.
+    pub fn offset_at(&self, cell: usize) -> Option<usize> {
+        self.cells.get(cell).copied().flatten()
+    }
+}
+
+pub struct PaneAlignment {
+    pub left: AlignedPane,
+    pub right: AlignedPane,
+}
+
+impl PaneAlignment {
+    pub fn new(
+        left: &[TransactionByteSection],
+        right: &[TransactionByteSection],
+        diff: &TransactionDiff,
+    ) -> Self {
+        let mut left_cells = vec![];
+        let mut right_cells = vec![];
+        let mut left_offset = 0;
+        let mut right_offset = 0;
+        for (left_index, right_index) in corresponding_sections(left, right, diff) {
+            let left_len = left_index.map_or(0, |index| left[index].bytes.len());
+            let right_len = right_index.map_or(0, |index| right[index].bytes.len());
+            let len = left_len.max(right_len);
+            left_cells.extend(pad(left_offset..left_offset + left_len, len));
+            right_cells.extend(pad(right_offset..right_offset + right_len, len));
+            left_offset += left_len;
+            right_offset += right_len;
+        }
+        Self {
+            left: AlignedPane::new(left_cells),
+            right: AlignedPane::new(right_cells),
+        }
+    }
+
+    /// The ranges of each pane's bytes which differ from the byte beside
+    /// them, or which have a gap beside them.
+    pub fn differing_ranges(
+        &self,
+        left: &[u8],
+        right: &[u8],
+    ) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+        let mut left_ranges = vec![];
+        let mut right_ranges = vec![];
+        for (left_offset, right_offset) in self.left.cells.iter().zip(&self.right.cells) {
+            let byte = |bytes: &[u8], offset: &Option<usize>| offset.map(|offset| bytes[offset]);
+            if byte(left, left_offset) == byte(right, right_offset) {
+                continue;
+            }
+            extend_ranges(&mut left_ranges, *left_offset);
+            extend_ranges(&mut right_ranges, *right_offset);
+        }
+        (left_ranges, right_ranges)
+    }
+}
+
+fn extend_ranges(ranges: &mut Vec<Range<usize>>, offset: Option<usize>) {
+    let Some(offset) = offset else {
+        return;
+    };
+    match ranges.last_mut() {
+        Some(range) if range.end == offset => range.end += 1,
+        _ => ranges.push(offset..offset + 1),
+    }
+}
+
+fn pad(offsets: Range<usize>, len: usize) -> impl Iterator<Item = Option<usize>> {
+    let num_gaps = len - offsets.len();
+    offsets.map(Some).chain(iter::repeat_n(None, num_gaps))
+}
+
+/// Pair the sections of the same field, by a longest common subsequence of
+/// section kinds. Sections without a counterpart are paired with `None`.
+fn corresponding_sections(
+    left: &[TransactionByteSection],
+    right: &[TransactionByteSection],
+    diff: &TransactionDiff,
+) -> Vec<(Option<usize>, Option<usize>)> {
+    let right = right
+        .iter()
+        .map(|section| diff.left_kind(section.kind))
+        .collect::<Vec<_>>();
+    // common[i][j] is the length of the longest common subsequence of
+    // left[i..] and right[j..].
+    let mut common = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            common[i][j] = if left[i].kind == right[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut pairs = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() || j < right.len() {
+        if i < left.len() && j < right.len() && left[i].kind == right[j] {
+            pairs.push((Some(i), Some(j)));
+            i += 1;
+            j += 1;
+        } else if j == right.len() || (i < left.len() && common[i + 1][j] >= common[i][j + 1]) {
+            pairs.push((Some(i), None));
+            i += 1;
+        } else {
+            pairs.push((None, Some(j)));
+            j += 1;
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash,
+            instruction::Instruction,
+            message::{Message, VersionedMessage},
+            pubkey::Pubkey,
+            transaction::VersionedTransaction,
+        },
+        solana_transaction_tui::transaction_byte_sections::get_transaction_byte_sections,
+    };
+
+    fn transaction(data: &[&[u8]]) -> (VersionedTransaction, Vec<TransactionByteSection>) {
+        let program_id = Pubkey::new_from_array([1; 32]);
+        let payer = Pubkey::new_from_array([2; 32]);
+        let instructions = data
+            .iter()
+            .map(|data| Instruction::new_with_bytes(program_id, data, vec![]))
+            .collect::<Vec<_>>();
+        let message = Message::new_with_blockhash(&instructions, Some(&payer), &Hash::default());
+        let transaction = VersionedTransaction {
+            signatures: vec![Default::default()],
+            message: VersionedMessage::Legacy(message),
+        };
+        let mut sections = vec![];
+        get_transaction_byte_sections(&transaction, &mut sections);
+        (transaction, sections)
+    }
+
+    #[test]
+    fn pads_shorter_sections_and_missing_instructions() {
+        let (left_transaction, left) = transaction(&[&[1, 2]]);
+        let (right_transaction, right) = transaction(&[&[1, 2, 3, 4], &[5]]);
+        let diff = TransactionDiff::new(&left_transaction, &right_transaction);
+        let alignment = PaneAlignment::new(&left, &right, &diff);
+        let num_left = left
+            .iter()
+            .map(|section| section.bytes.len())
+            .sum::<usize>();
+        let num_right = right
+            .iter()
+            .map(|section| section.bytes.len())
+            .sum::<usize>();
+
+        assert!(alignment.right.gaps().is_empty());
+        // The first instruction's data is padded by 2 cells, followed by the
+        // gap for the second instruction, all at the end of the left pane.
+        let second_instruction = 4;
+        assert_eq!(alignment.left.gaps(), [(num_left, 2 + second_instruction)]);
+        assert_eq!(num_left + 2 + second_instruction, num_right);
+
+        for offset in 0..num_left {
+            let cell = alignment.left.cell_of(offset).unwrap();
+            assert_eq!(alignment.right.offset_at(cell), Some(offset));
+        }
+    }
+
+    #[test]
+    fn differing_ranges_skip_bytes_beside_their_equals() {
+        let (left_transaction, left) = transaction(&[&[1, 2]]);
+        let (right_transaction, right) = transaction(&[&[1, 3, 4]]);
+        let diff = TransactionDiff::new(&left_transaction, &right_transaction);
+        let alignment = PaneAlignment::new(&left, &right, &diff);
+        let bytes = |sections: &[TransactionByteSection]| {
+            sections
+                .iter()
+                .flat_map(|section| section.bytes.clone())
+                .collect::<Vec<_>>()
+        };
+        let (left_bytes, right_bytes) = (bytes(&left), bytes(&right));
+
+        let (left_ranges, right_ranges) = alignment.differing_ranges(&left_bytes, &right_bytes);
+        // The data length and the second byte differ, and the third byte has
+        // a gap beside it.
+        let data_len = left_bytes.len() - 3;
+        assert_eq!(
+            left_ranges,
+            [data_len..data_len + 1, data_len + 2..data_len + 3]
+        );
+        assert_eq!(
+            right_ranges,
+            [data_len..data_len + 1, data_len + 2..data_len + 4]
+        );
+    }
+}
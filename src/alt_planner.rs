@@ -0,0 +1,355 @@
+use {
+    crate::{
+        rpc::RpcApi,
+        transaction_byte_sections::{get_transaction_byte_sections, TransactionByteSection},
+    },
+    solana_client::rpc_config::RpcSendTransactionConfig,
+    solana_sdk::{
+        address_lookup_table::{
+            instruction::{create_lookup_table, extend_lookup_table},
+            AddressLookupTableAccount,
+        },
+        commitment_config::CommitmentConfig,
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::{v0, Message, VersionedMessage},
+        pubkey::Pubkey,
+        signature::{Keypair, Signature},
+        transaction::VersionedTransaction,
+    },
+    std::{
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// Addresses added by the transaction which creates the table, which also
+/// carries the create instruction.
+const ADDRESSES_PER_CREATE: usize = 20;
+/// Addresses added by each following extend transaction, keeping each one
+/// within the packet size.
+const ADDRESSES_PER_EXTEND: usize = 28;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// About how long the blockhash of a submitted transaction stays valid.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// One transaction of the plan, along with its byte sections for display.
+pub struct AltPlanStep {
+    pub description: String,
+    pub transaction: VersionedTransaction,
+    pub sections: Vec<TransactionByteSection>,
+}
+
+impl AltPlanStep {
+    fn new(description: String, transaction: VersionedTransaction) -> Self {
+        let mut sections = vec![];
+        get_transaction_byte_sections(&transaction, &mut sections);
+        Self {
+            description,
+            transaction,
+            sections,
+        }
+    }
+}
+
+/// The transactions needed to put the accounts of a transaction into a new
+/// address lookup table, followed by the transaction rewritten as v0 using
+/// that table.
+pub struct AltPlan {
+    pub lookup_table: Pubkey,
+    pub authority: Pubkey,
+    /// The create and extend transactions, in submission order.
+    pub setup: Vec<AltPlanStep>,
+    pub rewritten: AltPlanStep,
+}
+
+impl AltPlan {
+    /// Every step of the plan, with the rewritten transaction last.
+    pub fn steps(&self) -> impl Iterator<Item = &AltPlanStep> {
+        self.setup.iter().chain(std::iter::once(&self.rewritten))
+    }
+
+    pub fn step(&self, index: usize) -> &AltPlanStep {
+        self.setup.get(index).unwrap_or(&self.rewritten)
+    }
+
+    pub fn num_steps(&self) -> usize {
+        self.setup.len() + 1
+    }
+}
+
+/// Plan the lookup table for a transaction. The fee payer of the transaction
+/// pays for and owns the table, which is derived from `recent_slot`.
+pub fn plan(
+    transaction: &VersionedTransaction,
+    recent_slot: u64,
+    recent_blockhash: Hash,
+) -> Result<AltPlan, String> {
+    let message = &transaction.message;
+    if message
+        .address_table_lookups()
+        .is_some_and(|lookups| !lookups.is_empty())
+    {
+        return Err("Transaction already uses address lookup tables".to_string());
+    }
+    let account_keys = message.static_account_keys();
+    let authority = *account_keys.first().ok_or("Transaction has no fee payer")?;
+
+    // Signers and invoked programs must remain static keys.
+    let addresses = account_keys
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !message.is_signer(*index) && !message.is_invoked(*index))
+        .map(|(_, key)| *key)
+        .collect::<Vec<_>>();
+    if addresses.is_empty() {
+        return Err("Transaction has no accounts to put in a lookup table".to_string());
+    }
+
+    let (create_instruction, lookup_table) = create_lookup_table(authority, authority, recent_slot);
+    let mut setup = vec![];
+    let (first_chunk, remaining) = addresses.split_at(ADDRESSES_PER_CREATE.min(addresses.len()));
+    setup.push(AltPlanStep::new(
+        format!("Create table, add {} addresses", first_chunk.len()),
+        unsigned_transaction(
+            &[
+                create_instruction,
+                extend_lookup_table(
+                    lookup_table,
+                    authority,
+                    Some(authority),
+                    first_chunk.to_vec(),
+                ),
+            ],
+            &authority,
+            recent_blockhash,
+        ),
+    ));
+    for chunk in remaining.chunks(ADDRESSES_PER_EXTEND) {
+        setup.push(AltPlanStep::new(
+            format!("Extend table, add {} addresses", chunk.len()),
+            unsigned_transaction(
+                &[extend_lookup_table(
+                    lookup_table,
+                    authority,
+                    Some(authority),
+                    chunk.to_vec(),
+                )],
+                &authority,
+                recent_blockhash,
+            ),
+        ));
+    }
+
+    let rewritten_message = v0::Message::try_compile(
+        &authority,
+        &decompile_instructions(message)?,
+        &[AddressLookupTableAccount {
+            key: lookup_table,
+            addresses,
+        }],
+        *message.recent_blockhash(),
+    )
+    .map_err(|err| format!("Failed to compile v0 message: {err}"))?;
+    let num_required_signatures = rewritten_message.header.num_required_signatures as usize;
+    let rewritten = AltPlanStep::new(
+        "Rewritten transaction (v0)".to_string(),
+        VersionedTransaction {
+            signatures: vec![Signature::default(); num_required_signatures],
+            message: VersionedMessage::V0(rewritten_message),
+        },
+    );
+
+    Ok(AltPlan {
+        lookup_table,
+        authority,
+        setup,
+        rewritten,
+    })
+}
+
+/// Plan the lookup table for a transaction with a recent slot and blockhash
+/// from the endpoint.
+pub fn fetch_plan(rpc: &dyn RpcApi, transaction: &VersionedTransaction) -> Result<AltPlan, String> {
+    // The slot must be in the slot hashes sysvar when the table is created.
+    let recent_slot = rpc
+        .get_slot(CommitmentConfig::finalized())
+        .map_err(|err| format!("Failed to get slot: {err}"))?;
+    let recent_blockhash = rpc
+        .get_latest_blockhash(CommitmentConfig::finalized())
+        .map_err(|err| format!("Failed to get latest blockhash: {err}"))?;
+    plan(transaction, recent_slot, recent_blockhash)
+}
+
+/// Sign the setup messages of a plan with the authority keypair under the
+/// latest blockhash, and submit them in order, each once the previous one is
+/// confirmed.
+pub fn submit(
+    rpc: &dyn RpcApi,
+    setup: Vec<VersionedMessage>,
+    authority: &Keypair,
+) -> Result<(), String> {
+    let blockhash = rpc
+        .get_latest_blockhash(CommitmentConfig::finalized())
+        .map_err(|err| format!("Failed to get latest blockhash: {err}"))?;
+    for (index, mut message) in setup.into_iter().enumerate() {
+        message.set_recent_blockhash(blockhash);
+        let transaction = VersionedTransaction::try_new(message, &[authority])
+            .map_err(|err| format!("Failed to sign transaction {index}: {err}"))?;
+        send_and_confirm(rpc, &transaction)
+            .map_err(|err| format!("Transaction {index} failed: {err}"))?;
+    }
+    Ok(())
+}
+
+/// Send a transaction with preflight and poll its status until it is
+/// confirmed, or its blockhash has most likely expired.
+fn send_and_confirm(rpc: &dyn RpcApi, transaction: &VersionedTransaction) -> Result<(), String> {
+    let signature = rpc.send_transaction(transaction, RpcSendTransactionConfig::default())?;
+    let started = Instant::now();
+    while started.elapsed() < CONFIRM_TIMEOUT {
+        thread::sleep(POLL_INTERVAL);
+        let Ok(statuses) = rpc.get_signature_statuses(&[signature]) else {
+            continue;
+        };
+        let Some(status) = statuses.into_iter().flatten().next() else {
+            continue;
+        };
+        if let Some(err) = status.err {
+            return Err(err.to_string());
+        }
+        if status.satisfies_commitment(CommitmentConfig::confirmed()) {
+            return Ok(());
+        }
+    }
+    Err(format!("{signature} was not confirmed in time"))
+}
+
+fn unsigned_transaction(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    recent_blockhash: Hash,
+) -> VersionedTransaction {
+    let message = Message::new_with_blockhash(instructions, Some(payer), &recent_blockhash);
+    VersionedTransaction {
+        signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+        message: VersionedMessage::Legacy(message),
+    }
+}
+
+/// Expand the compiled instructions of a message without lookups back into
+/// instructions with full account metas.
+fn decompile_instructions(message: &VersionedMessage) -> Result<Vec<Instruction>, String> {
+    let account_keys = message.static_account_keys();
+    let account_key = |index: usize| {
+        account_keys
+            .get(index)
+            .copied()
+            .ok_or_else(|| format!("Account index {index} is out of range"))
+    };
+    message
+        .instructions()
+        .iter()
+        .map(|instruction| {
+            Ok(Instruction {
+                program_id: account_key(instruction.program_id_index as usize)?,
+                accounts: instruction
+                    .accounts
+                    .iter()
+                    .map(|index| {
+                        let index = *index as usize;
+                        Ok(AccountMeta {
+                            pubkey: account_key(index)?,
+                            is_signer: message.is_signer(index),
+                            is_writable: message.is_maybe_writable(index),
+                        })
+                    })
+                    .collect::<Result<_, String>>()?,
+                data: instruction.data.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{instruction::CompiledInstruction, system_program},
+    };
+
+    /// A transaction of one instruction passing `num_accounts` writable
+    /// accounts, signed by the fee payer.
+    fn transaction(num_accounts: usize) -> VersionedTransaction {
+        let payer = Pubkey::new_unique();
+        let accounts = (0..num_accounts)
+            .map(|_| AccountMeta::new(Pubkey::new_unique(), false))
+            .collect();
+        let instruction = Instruction::new_with_bytes(system_program::id(), &[], accounts);
+        unsigned_transaction(&[instruction], &payer, Hash::new_unique())
+    }
+
+    #[test]
+    fn chunks_the_addresses_of_the_setup_transactions() {
+        let transaction = transaction(ADDRESSES_PER_CREATE + ADDRESSES_PER_EXTEND + 1);
+        let alt_plan = plan(&transaction, 1, Hash::new_unique()).unwrap();
+        let descriptions = alt_plan
+            .setup
+            .iter()
+            .map(|step| step.description.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            descriptions,
+            [
+                "Create table, add 20 addresses",
+                "Extend table, add 28 addresses",
+                "Extend table, add 1 addresses",
+            ]
+        );
+        assert_eq!(
+            alt_plan.authority,
+            transaction.message.static_account_keys()[0]
+        );
+        assert_eq!(alt_plan.num_steps(), 4);
+
+        // Only the fee payer and the program stay static keys.
+        let VersionedMessage::V0(rewritten) = &alt_plan.rewritten.transaction.message else {
+            panic!("The rewritten transaction is not v0");
+        };
+        assert_eq!(rewritten.account_keys.len(), 2);
+        assert_eq!(
+            rewritten.address_table_lookups[0].account_key,
+            alt_plan.lookup_table
+        );
+    }
+
+    #[test]
+    fn fits_few_addresses_in_the_create_transaction() {
+        let alt_plan = plan(&transaction(ADDRESSES_PER_CREATE), 1, Hash::new_unique()).unwrap();
+        assert_eq!(alt_plan.setup.len(), 1);
+        assert!(plan(&transaction(0), 1, Hash::new_unique()).is_err());
+    }
+
+    #[test]
+    fn rejects_account_indexes_out_of_range() {
+        let error = |program_id_index, accounts| {
+            let mut transaction = transaction(1);
+            if let VersionedMessage::Legacy(message) = &mut transaction.message {
+                message.instructions = vec![CompiledInstruction::new_from_raw_parts(
+                    program_id_index,
+                    vec![],
+                    accounts,
+                )];
+            }
+            plan(&transaction, 1, Hash::new_unique()).err()
+        };
+        assert_eq!(
+            error(2, vec![9]).as_deref(),
+            Some("Account index 9 is out of range")
+        );
+        assert_eq!(
+            error(7, vec![1]).as_deref(),
+            Some("Account index 7 is out of range")
+        );
+    }
+}
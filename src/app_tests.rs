@@ -0,0 +1,1461 @@
+//! Headless tests driving the app through its update loop with synthetic
+//! key events and canned fetch results, rendering to a test backend.
+
+use {
+    super::*,
+    crate::rpc::FetchEncoding,
+    ratatui::{backend::TestBackend, crossterm::event::KeyModifiers, Terminal},
+    solana_sdk::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        signature::Keypair,
+        system_instruction,
+        transaction::{Transaction, TransactionError},
+    },
+    solana_transaction_status::{
+        Encodable, EncodedTransactionWithStatusMeta, TransactionConfirmationStatus,
+        UiTransactionEncoding,
+    },
+};
+
+fn app() -> TransactionApp {
+    // Mock mode, so no request reaches the network.
+    let mut app = TransactionApp::new(
+        Settings::default(),
+        Bookmarks::default(),
+        RpcSource::Mock(Arc::new(MockRpc::new(DEFAULT_MOCK_DIR))),
+    );
+    // The size of the frames `render` draws.
+    app.update(Message::Event(Event::Resize(160, 40)));
+    app
+}
+
+fn press(app: &mut TransactionApp, code: KeyCode) {
+    let key_event = KeyEvent::new(code, KeyModifiers::NONE);
+    app.update(Message::Event(Event::Key(key_event)));
+}
+
+fn enter_signature(app: &mut TransactionApp, text: &str) {
+    for c in text.chars() {
+        press(app, KeyCode::Char(c));
+    }
+    press(app, KeyCode::Enter);
+}
+
+/// Render a frame and return its text, one line per row.
+fn render(app: &TransactionApp) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(160, 40)).unwrap();
+    terminal.draw(|frame| app.render_frame(frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    buffer
+        .content()
+        .chunks(buffer.area.width as usize)
+        .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn transfer() -> Transaction {
+    let payer = Keypair::new();
+    // A random recipient, as the bytes of a unique pubkey can recur in a
+    // unique blockhash and match a watchlist.
+    let recipient = Keypair::new().pubkey();
+    let instruction = system_instruction::transfer(&payer.pubkey(), &recipient, 1);
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::new_unique(),
+    )
+}
+
+fn fetched(transaction: &Transaction) -> Message {
+    Message::TransactionFetched {
+        signature: transaction.signatures[0],
+        result: Ok(Box::new(EncodedConfirmedTransactionWithStatusMeta {
+            slot: 42,
+            transaction: EncodedTransactionWithStatusMeta {
+                transaction: transaction.encode(UiTransactionEncoding::Base64),
+                meta: None,
+                version: None,
+            },
+            block_time: None,
+        })),
+        elapsed: Duration::ZERO,
+    }
+}
+
+/// Take the queued fetch, which a test answers instead of the endpoint.
+fn take_fetch(app: &mut TransactionApp) -> Signature {
+    match app.commands.pop_front() {
+        Some(Command::FetchTransaction { signature, .. }) => signature,
+        _ => panic!("expected a fetch command"),
+    }
+}
+
+/// Run the next queued command, which a test lets reach the file system.
+fn run_command(app: &mut TransactionApp) {
+    let command = app.commands.pop_front().expect("a queued command");
+    app.update(command.execute());
+}
+
+/// Apply the queued file changes, dropping the other commands.
+fn change_files(app: &mut TransactionApp) {
+    for command in app.commands.drain(..) {
+        if let Command::ChangeFile { change, .. } = command {
+            change.apply().unwrap();
+        }
+    }
+}
+
+#[test]
+fn fetch_and_render_transaction() {
+    let mut app = app();
+    let transaction = transfer();
+    let signature = transaction.signatures[0];
+    assert!(render(&app).contains("Enter a transaction signature"));
+
+    enter_signature(&mut app, &signature.to_string());
+    assert!(matches!(app.screen, Screen::Loading(loading) if loading == signature));
+    assert_eq!(take_fetch(&mut app), signature);
+    assert!(app.commands.is_empty());
+    assert!(render(&app).contains(&format!("Loading {signature}...")));
+
+    app.update(fetched(&transaction));
+    let loaded = app.loaded().expect("transaction is loaded");
+    assert_eq!(loaded.signature, signature);
+    assert_eq!(loaded.slot, Some(42));
+    let num_bytes = bincode::serialized_size(&transaction).unwrap() as usize;
+    assert_eq!(app.num_bytes(), num_bytes);
+    assert!(render(&app).contains(&format!("{signature} | slot 42")));
+
+    press(&mut app, KeyCode::Tab);
+    assert!(matches!(app.mode, Mode::ByteNavigation));
+    press(&mut app, KeyCode::End);
+    assert_eq!(app.cursor, num_bytes - 1);
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(app.mode, Mode::SignatureEntry));
+}
+
+#[test]
+fn jump_between_landmarks() {
+    let mut app = app();
+    let transaction = transfer();
+    enter_signature(&mut app, &transaction.signatures[0].to_string());
+    take_fetch(&mut app);
+    app.update(fetched(&transaction));
+    press(&mut app, KeyCode::Tab);
+
+    // The signature count, then the signature.
+    press(&mut app, KeyCode::Char(']'));
+    assert_eq!(app.cursor, 1);
+    press(&mut app, KeyCode::Char(']'));
+    assert_eq!(app.cursor, 65);
+    press(&mut app, KeyCode::Char('['));
+    assert_eq!(app.cursor, 1);
+
+    // The header, 3 account keys, the blockhash and the instruction count.
+    let instruction_start = 65 + 3 + 1 + 3 * 32 + 32 + 1;
+    press(&mut app, KeyCode::Char('}'));
+    assert_eq!(app.cursor, instruction_start);
+    press(&mut app, KeyCode::Char('}'));
+    assert_eq!(app.cursor, instruction_start);
+
+    press(&mut app, KeyCode::Char('g'));
+    assert!(matches!(app.mode, Mode::Jump));
+    press(&mut app, KeyCode::Char('k'));
+    assert!(matches!(app.mode, Mode::ByteNavigation));
+    assert_eq!(app.cursor, 65 + 3);
+    press(&mut app, KeyCode::Char('{'));
+    assert_eq!(app.cursor, 65 + 3);
+}
+
+#[test]
+fn restores_cursor_of_transactions_viewed_before() {
+    let mut app = app();
+    let (first, second) = (transfer(), transfer());
+    let view = |app: &mut TransactionApp, transaction: &Transaction| {
+        enter_signature(app, &transaction.signatures[0].to_string());
+        take_fetch(app);
+        app.update(fetched(transaction));
+        app.commands.clear();
+        press(app, KeyCode::Tab);
+    };
+
+    view(&mut app, &first);
+    press(&mut app, KeyCode::End);
+    press(&mut app, KeyCode::Left);
+    let (cursor, byte_scroll) = (app.cursor, app.byte_scroll);
+    press(&mut app, KeyCode::Esc);
+
+    view(&mut app, &second);
+    assert_eq!(app.cursor, 0);
+    press(&mut app, KeyCode::Esc);
+
+    view(&mut app, &first);
+    assert_eq!(app.cursor, cursor);
+    assert_eq!(app.byte_scroll, byte_scroll);
+}
+
+#[test]
+fn pivots_to_related_transactions() {
+    let mut app = app();
+    app.rpc_source = RpcSource::Endpoint;
+    let transaction = transfer();
+    enter_signature(&mut app, &transaction.signatures[0].to_string());
+    take_fetch(&mut app);
+    app.update(fetched(&transaction));
+    app.commands.clear();
+    press(&mut app, KeyCode::Tab);
+    // Onto the payer, past the number of account keys.
+    press(&mut app, KeyCode::Char('g'));
+    press(&mut app, KeyCode::Char('k'));
+    press(&mut app, KeyCode::Right);
+
+    press(&mut app, KeyCode::Char('R'));
+    assert!(matches!(app.mode, Mode::Related(_)));
+    let payer = transaction.message.account_keys[0];
+    match app.commands.pop_front() {
+        Some(Command::FetchRelated {
+            address,
+            slot,
+            window,
+            ..
+        }) => assert_eq!(
+            (address, slot, window),
+            (payer, 42, related::DEFAULT_WINDOW)
+        ),
+        _ => panic!("expected a related fetch command"),
+    }
+
+    let other = Signature::new_unique();
+    app.update(Message::RelatedFetched {
+        result: Ok(Related {
+            address: payer,
+            slot: 42,
+            window: related::DEFAULT_WINDOW,
+            candidates: vec![
+                related::Candidate {
+                    signature: other,
+                    slot: 40,
+                    err: Some(TransactionError::AccountInUse),
+                },
+                related::Candidate {
+                    signature: transaction.signatures[0],
+                    slot: 42,
+                    err: None,
+                },
+            ],
+            truncated: false,
+        }),
+    });
+    let text = render(&app);
+    assert!(text.contains(&format!("{other} failed: AccountInUse")));
+    assert!(text.contains(&format!("{} (viewed)", transaction.signatures[0])));
+
+    // Only failures are shown once filtered.
+    press(&mut app, KeyCode::Char('f'));
+    let text = render(&app);
+    assert!(text.contains("1  AccountInUse"));
+    assert!(!text.contains("(viewed)"));
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(take_fetch(&mut app), other);
+}
+
+#[test]
+fn switching_endpoints_offers_to_refetch() {
+    let mut app = app();
+    // Fetches are answered by the test, as if by the endpoint.
+    app.rpc_source = RpcSource::Endpoint;
+    let transaction = transfer();
+    enter_signature(&mut app, &transaction.signatures[0].to_string());
+    take_fetch(&mut app);
+    app.update(fetched(&transaction));
+    app.commands.clear();
+    assert_eq!(app.loaded().unwrap().endpoint, Some(DEFAULT_ENDPOINT));
+
+    let devnet = "https://api.devnet.solana.com";
+    app.perform_menu_item(AppMenuItem::Endpoint(devnet));
+    assert!(matches!(app.mode, Mode::ConfirmRefetch));
+    assert!(render(&app).contains(&format!("from {devnet}?")));
+    // The health of the new endpoint is checked right away.
+    assert!(matches!(
+        app.commands.pop_front(),
+        Some(Command::CheckEndpointHealth { delay, .. }) if delay.is_zero()
+    ));
+
+    // The stale data is kept, still marked with its endpoint.
+    press(&mut app, KeyCode::Char('n'));
+    assert!(app.commands.is_empty());
+    assert_eq!(app.loaded().unwrap().endpoint, Some(DEFAULT_ENDPOINT));
+
+    app.perform_menu_item(AppMenuItem::Endpoint(DEFAULT_ENDPOINT));
+    assert!(matches!(app.mode, Mode::SignatureEntry));
+    app.perform_menu_item(AppMenuItem::Endpoint(devnet));
+    app.commands.clear();
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(take_fetch(&mut app), transaction.signatures[0]);
+    assert!(matches!(app.screen, Screen::Loading(_)));
+}
+
+#[test]
+fn looks_up_programs_again_on_another_endpoint() {
+    let mut app = app();
+    app.rpc_source = RpcSource::Endpoint;
+    let payer = Keypair::new();
+    let program_id = Pubkey::new_unique();
+    let transaction = Transaction::new_signed_with_payer(
+        &[Instruction::new_with_bytes(program_id, &[], vec![])],
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::new_unique(),
+    );
+    let looked_up = |app: &mut TransactionApp| {
+        let (mut builds, mut security_txts) = (false, false);
+        for command in app.commands.drain(..) {
+            match command {
+                Command::CheckVerifiedBuilds { program_ids } => {
+                    assert_eq!(program_ids, [program_id]);
+                    builds = true;
+                }
+                Command::FetchSecurityTxts { program_ids, .. } => {
+                    assert_eq!(program_ids, [program_id]);
+                    security_txts = true;
+                }
+                _ => {}
+            }
+        }
+        (builds, security_txts)
+    };
+
+    enter_signature(&mut app, &transaction.signatures[0].to_string());
+    take_fetch(&mut app);
+    app.update(fetched(&transaction));
+    assert_eq!(looked_up(&mut app), (true, true));
+    app.update(Message::BuildsChecked {
+        statuses: vec![(program_id, Err("unreachable".to_string()))],
+    });
+    app.update(Message::SecurityTxtsFetched {
+        results: vec![(program_id, Ok(None))],
+    });
+    enter_signature(&mut app, &transaction.signatures[0].to_string());
+    take_fetch(&mut app);
+    app.update(fetched(&transaction));
+    assert_eq!(looked_up(&mut app), (false, false));
+
+    // The registry only knows mainnet programs.
+    app.perform_menu_item(AppMenuItem::Endpoint("https://api.devnet.solana.com"));
+    app.commands.clear();
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(take_fetch(&mut app), transaction.signatures[0]);
+    app.update(fetched(&transaction));
+    assert_eq!(looked_up(&mut app), (false, true));
+}
+
+#[test]
+fn fetches_with_the_chosen_options() {
+    let mut app = app();
+    app.rpc_source = RpcSource::Endpoint;
+    let ctrl_o = KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL);
+    app.update(Message::Event(Event::Key(ctrl_o)));
+    assert!(render(&app).contains("Loaded addresses: on"));
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Esc);
+
+    let transaction = transfer();
+    enter_signature(&mut app, &transaction.signatures[0].to_string());
+    let expected = FetchOptions {
+        encoding: FetchEncoding::Base64,
+        loaded_addresses: true,
+        rewards: false,
+    };
+    match app.commands.pop_front() {
+        Some(Command::FetchTransaction { options, .. }) => assert_eq!(options, expected),
+        _ => panic!("expected a fetch command"),
+    }
+    app.update(fetched(&transaction));
+    assert_eq!(app.loaded().unwrap().fetch_options, Some(expected));
+}
+
+#[test]
+fn refetch_shows_what_changed() {
+    let mut app = app();
+    let transaction = transfer();
+    enter_signature(&mut app, &transaction.signatures[0].to_string());
+    take_fetch(&mut app);
+    app.update(fetched(&transaction));
+    app.commands.clear();
+    press(&mut app, KeyCode::Tab);
+    press(&mut app, KeyCode::Right);
+
+    press(&mut app, KeyCode::Char('r'));
+    assert_eq!(take_fetch(&mut app), transaction.signatures[0]);
+    let mut refetched = fetched(&transaction);
+    if let Message::TransactionFetched {
+        result: Ok(fetched),
+        ..
+    } = &mut refetched
+    {
+        fetched.slot = 43;
+    }
+    app.update(refetched);
+    assert_eq!(app.cursor, 1);
+    let text = render(&app);
+    assert!(text.contains("Changed since the last fetch"));
+    assert!(text.contains("slot: 42 -> 43"));
+}
+
+#[test]
+fn diffs_the_viewed_transaction_with_another() {
+    let mut app = app();
+    let transaction = transfer();
+    let other = transfer();
+    enter_signature(&mut app, &transaction.signatures[0].to_string());
+    take_fetch(&mut app);
+    app.update(fetched(&transaction));
+    app.commands.clear();
+    press(&mut app, KeyCode::Tab);
+
+    press(&mut app, KeyCode::Char('d'));
+    assert!(matches!(app.mode, Mode::DiffSignatureEntry));
+    enter_signature(&mut app, &other.signatures[0].to_string());
+    assert!(matches!(app.mode, Mode::Diff));
+    assert_eq!(take_fetch(&mut app), other.signatures[0]);
+    assert!(render(&app).contains("Fetching the transaction to compare with"));
+
+    app.update(fetched(&other));
+    let Screen::Diff { compared, .. } = &app.screen else {
+        panic!("expected the diff screen");
+    };
+    let Compared::Fetched(_, diff) = compared else {
+        panic!("expected the compared transaction");
+    };
+    // Different signers, recipients and blockhashes.
+    assert!(!diff.changes.is_empty());
+    let text = render(&app);
+    assert!(text.contains(&format!("Changes ({})", diff.changes.len())));
+    assert!(text.contains("modified recent blockhash"));
+
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(app.mode, Mode::ByteNavigation));
+    assert_eq!(app.current_signature(), Some(transaction.signatures[0]));
+}
+
+#[test]
+fn edits_a_copy_of_the_viewed_transaction() {
+    let mut app = app();
+    let transaction = transfer();
+    enter_signature(&mut app, &transaction.signatures[0].to_string());
+    assert!(matches!(app.screen, Screen::Loading(_)));
+    take_fetch(&mut app);
+    app.update(fetched(&transaction));
+    assert!(matches!(app.screen, Screen::Viewing(_)));
+    press(&mut app, KeyCode::Tab);
+
+    press(&mut app, KeyCode::Char('e'));
+    assert!(matches!(app.mode, Mode::Edit));
+    press(&mut app, KeyCode::Char('C'));
+    let Screen::Edit {
+        loaded, session, ..
+    } = &app.screen
+    else {
+        panic!("expected the edit screen");
+    };
+    assert_eq!(session.edited().signatures, vec![Signature::default()]);
+    assert_eq!(loaded.transaction.signatures, transaction.signatures);
+
+    // Leaving the edit discards it.
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(app.mode, Mode::ByteNavigation));
+    let Screen::Viewing(loaded) = &app.screen else {
+        panic!("expected the viewing screen");
+    };
+    assert_eq!(loaded.transaction.signatures, transaction.signatures);
+}
+
+#[test]
+fn bookmarked_transactions_reopen_from_snapshots() {
+    let mut app = app();
+    let dir = std::env::temp_dir().join(format!("app-snapshots-{}", Pubkey::new_unique()));
+    app.snapshots = Snapshots {
+        dir: Some(dir.clone()),
+    };
+    let transaction = transfer();
+    let signature = transaction.signatures[0];
+    app.bookmarks.toggle(&signature);
+    // Without a snapshot yet, the transaction is fetched.
+    enter_signature(&mut app, &signature.to_string());
+    run_command(&mut app);
+    take_fetch(&mut app);
+    app.update(fetched(&transaction));
+    change_files(&mut app);
+
+    // Reopening does not reach the endpoint, which may have pruned it.
+    enter_signature(&mut app, &signature.to_string());
+    assert!(matches!(app.screen, Screen::Loading(_)));
+    run_command(&mut app);
+    assert!(!app
+        .commands
+        .iter()
+        .any(|command| matches!(command, Command::FetchTransaction { .. })));
+    let loaded = app.loaded().unwrap();
+    assert_eq!(loaded.transaction, VersionedTransaction::from(transaction));
+    assert_eq!(loaded.slot, Some(42));
+    assert_eq!(loaded.source.as_deref(), Some("bookmarked snapshot"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn finds_pubkeys_across_bookmarked_transactions() {
+    let mut app = app();
+    let dir = std::env::temp_dir().join(format!("app-find-{}", Pubkey::new_unique()));
+    app.snapshots = Snapshots {
+        dir: Some(dir.clone()),
+    };
+    let (bookmarked, viewed) = (transfer(), transfer());
+    app.bookmarks.toggle(&bookmarked.signatures[0]);
+    for transaction in [&bookmarked, &viewed] {
+        enter_signature(&mut app, &transaction.signatures[0].to_string());
+        if transaction == &bookmarked {
+            run_command(&mut app);
+        }
+        take_fetch(&mut app);
+        app.update(fetched(transaction));
+        change_files(&mut app);
+    }
+
+    let ctrl_f = KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL);
+    app.update(Message::Event(Event::Key(ctrl_f)));
+    run_command(&mut app);
+    let payer = bookmarked.message.account_keys[0];
+    for c in payer.to_string().chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    let Mode::Find(find) = &app.mode else {
+        panic!("expected the find view");
+    };
+    let hits = find.hits.as_ref().unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].location, find::Location::Bookmark);
+    let offset = hits[0].offsets[0];
+    assert!(render(&app).contains("Found in 1 of the viewed"));
+
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(app.mode, Mode::ByteNavigation));
+    assert_eq!(app.current_signature(), Some(bookmarked.signatures[0]));
+    assert_eq!(app.cursor, offset);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn access_matrix_jumps_to_index_bytes() {
+    let mut app = app();
+    let transaction = transfer();
+    enter_signature(&mut app, &transaction.signatures[0].to_string());
+    take_fetch(&mut app);
+    app.update(fetched(&transaction));
+    press(&mut app, KeyCode::Tab);
+
+    press(&mut app, KeyCode::Char('X'));
+    assert!(matches!(app.mode, Mode::AccessMatrix(0, 0)));
+    assert!(render(&app).contains("WS"));
+
+    // The recipient is the second account of the transfer, after the
+    // program index and the number of accounts.
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(app.mode, Mode::ByteNavigation));
+    let program_index = 1 + 64 + 3 + 1 + 3 * 32 + 32 + 1;
+    assert_eq!(app.cursor, program_index + 3);
+}
+
+#[test]
+fn fetches_multisig_transactions_in_a_command() {
+    let mut app = app();
+    let payer = Keypair::new();
+    let accounts = (0..4)
+        .map(|_| AccountMeta::new_readonly(Pubkey::new_unique(), false))
+        .collect();
+    let instruction = Instruction::new_with_bytes(
+        decoders::multisig::SQUADS_V4,
+        &schema::anchor_discriminator("vault_transaction_execute"),
+        accounts,
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::new_unique(),
+    );
+    enter_signature(&mut app, &transaction.signatures[0].to_string());
+    take_fetch(&mut app);
+    app.update(fetched(&transaction));
+    press(&mut app, KeyCode::Tab);
+    press(&mut app, KeyCode::End);
+    app.commands.clear();
+    press(&mut app, KeyCode::Char('D'));
+    assert!(matches!(app.mode, Mode::InnerTransaction { .. }));
+    assert!(render(&app).contains("Squads v4: vault_transaction_execute | fetching…"));
+
+    // The fetch goes through the mock source rather than the endpoint.
+    let command = app.commands.pop_front().expect("a fetch command");
+    assert!(matches!(command, Command::FetchStoredTransaction { .. }));
+    app.update(command.execute());
+    assert!(render(&app).contains("Failed to get accounts: No canned response at mock/getMulti"));
+}
+
+#[test]
+fn maps_sensitivity_in_a_command() {
+    let mut app = app();
+    let transaction = transfer();
+    enter_signature(&mut app, &transaction.signatures[0].to_string());
+    take_fetch(&mut app);
+    app.update(fetched(&transaction));
+    press(&mut app, KeyCode::Tab);
+    app.commands.clear();
+
+    press(&mut app, KeyCode::Char('F'));
+    assert!(matches!(app.mode, Mode::SensitivityMap { .. }));
+    assert!(render(&app).contains("Mapping the sensitivity of every byte"));
+    run_command(&mut app);
+    assert!(render(&app).contains("Corruption Sensitivity"));
+}
+
+#[test]
+fn invalid_signature_shows_error() {
+    let mut app = app();
+    enter_signature(&mut app, "not a signature");
+    assert!(matches!(&app.screen, Screen::Error(err) if err == "Invalid signature"));
+    assert!(app.commands.is_empty());
+    assert!(render(&app).contains("Invalid signature"));
+
+    // Nothing is loaded to inspect.
+    press(&mut app, KeyCode::Tab);
+    assert!(matches!(app.mode, Mode::SignatureEntry));
+}
+
+#[test]
+fn failed_fetch_shows_not_found() {
+    let mut app = app();
+    let signature = Signature::new_unique();
+    enter_signature(&mut app, &signature.to_string());
+    take_fetch(&mut app);
+    app.update(Message::TransactionFetched {
+        signature,
+        result: Err("Transaction not found".to_string()),
+        elapsed: Duration::ZERO,
+    });
+    assert!(app.loaded().is_none());
+    assert!(render(&app).contains(&format!("{signature} not found")));
+}
+
+#[test]
+fn waits_for_a_transaction_not_found_to_land() {
+    let mut app = app();
+    let transaction = transfer();
+    let signature = transaction.signatures[0];
+    enter_signature(&mut app, &signature.to_string());
+    take_fetch(&mut app);
+    app.update(Message::TransactionFetched {
+        signature,
+        result: Err("Transaction not found".to_string()),
+        elapsed: Duration::ZERO,
+    });
+    app.rpc_source = RpcSource::Endpoint;
+    let ctrl_w = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
+    app.update(Message::Event(Event::Key(ctrl_w)));
+    assert!(matches!(app.screen, Screen::Landing(_)));
+
+    let mut delays = vec![];
+    let statuses = [
+        None,
+        Some(TransactionStatus {
+            slot: 42,
+            confirmations: Some(0),
+            status: Ok(()),
+            err: None,
+            confirmation_status: Some(TransactionConfirmationStatus::Processed),
+        }),
+        Some(TransactionStatus {
+            slot: 42,
+            confirmations: Some(1),
+            status: Ok(()),
+            err: None,
+            confirmation_status: Some(TransactionConfirmationStatus::Confirmed),
+        }),
+    ];
+    for status in statuses {
+        match app.commands.pop_front() {
+            Some(Command::PollLanding { delay, .. }) => delays.push(delay.as_millis()),
+            _ => panic!("expected a poll"),
+        }
+        let result = Ok(status);
+        app.update(Message::LandingPolled { signature, result });
+    }
+    assert_eq!(delays, [0, 500, 1000]);
+    // Loaded once confirmed.
+    assert!(matches!(app.screen, Screen::Loading(loading) if loading == signature));
+    assert_eq!(take_fetch(&mut app), signature);
+}
+
+#[test]
+fn stale_fetch_is_ignored() {
+    let mut app = app();
+    let first = transfer();
+    let second = Signature::new_unique();
+    enter_signature(&mut app, &first.signatures[0].to_string());
+    take_fetch(&mut app);
+    enter_signature(&mut app, &second.to_string());
+    take_fetch(&mut app);
+
+    app.update(fetched(&first));
+    assert!(matches!(app.screen, Screen::Loading(loading) if loading == second));
+}
+
+#[test]
+fn mock_fetch_serves_canned_transaction() {
+    let mut app = app();
+    let signature = Signature::from_str(
+        "4KqgyPKYD26K1k4xieVVgk2AisLtAJbmgzuiCSko14mjB3LdUY33ttEDy4Eka1j4Hdrv9nrGFcFyCqDM6YUmnUwU",
+    )
+    .unwrap();
+    enter_signature(&mut app, &signature.to_string());
+    let command = app.commands.pop_front().expect("a fetch is queued");
+    app.update(command.execute());
+    let loaded = app.loaded().expect("canned transaction is loaded");
+    assert_eq!(loaded.slot, Some(287_654_321));
+    assert_eq!(loaded.meta.as_ref().map(|meta| meta.fee), Some(5_006));
+
+    enter_signature(&mut app, &Signature::new_unique().to_string());
+    let command = app.commands.pop_front().expect("a fetch is queued");
+    app.update(command.execute());
+    assert!(app.loaded().is_none());
+}
+
+#[test]
+fn watch_flags_and_opens_matching_transactions() {
+    let mut app = app();
+    app.rpc_source = RpcSource::Endpoint;
+    let watched = transfer();
+    let recipient = watched.message.account_keys[1];
+    app.settings.watchlist = vec![recipient.to_string()];
+    let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+    app.update(Message::Event(Event::Key(ctrl_s)));
+    assert!(matches!(app.mode, Mode::Watch(0)));
+    assert!(matches!(
+        app.commands.pop_front(),
+        Some(Command::PollWatch {
+            after_slot: None,
+            ..
+        })
+    ));
+
+    let streamed = |transaction: Transaction| watch::StreamedTransaction {
+        slot: 42,
+        block_time: None,
+        transaction: transaction.into(),
+        meta: None,
+        matches: vec![],
+    };
+    app.update(Message::WatchPolled {
+        generation: app.watch.stream.generation,
+        result: Ok(watch::Batch {
+            last_slot: 42,
+            transactions: vec![streamed(watched.clone()), streamed(transfer())],
+            num_undecoded: 0,
+        }),
+        elapsed: Duration::ZERO,
+    });
+    // Polling continues from the last slot fetched.
+    assert!(matches!(
+        app.commands.pop_front(),
+        Some(Command::PollWatch {
+            after_slot: Some(42),
+            ..
+        })
+    ));
+    let text = render(&app);
+    assert!(text.contains("1 matches"));
+    assert!(text.contains(&format!("matches {recipient}")));
+    assert!(text.contains("Watch: live, 0 dropped"));
+
+    // New transactions wait in the buffer while paused.
+    press(&mut app, KeyCode::Char(' '));
+    app.update(Message::WatchPolled {
+        generation: app.watch.stream.generation,
+        result: Ok(watch::Batch {
+            last_slot: 43,
+            transactions: vec![streamed(transfer())],
+            num_undecoded: 0,
+        }),
+        elapsed: Duration::ZERO,
+    });
+    assert_eq!(app.watch.stream.transactions.len(), 2);
+    assert!(render(&app).contains("Watch: paused, 1 buffered"));
+    press(&mut app, KeyCode::Char(' '));
+    assert_eq!(app.watch.stream.transactions.len(), 3);
+    app.commands.clear();
+
+    press(&mut app, KeyCode::Char('o'));
+    app.update(Message::WatchPolled {
+        generation: app.watch.stream.generation,
+        result: Ok(watch::Batch {
+            last_slot: 44,
+            transactions: vec![streamed(watched.clone())],
+            num_undecoded: 0,
+        }),
+        elapsed: Duration::ZERO,
+    });
+    assert!(matches!(app.mode, Mode::ByteNavigation));
+    assert_eq!(app.loaded().unwrap().signature, watched.signatures[0]);
+    // Polling stops once the watch is left.
+    app.commands.clear();
+    app.update(Message::WatchPolled {
+        generation: app.watch.stream.generation,
+        result: Err("late".to_string()),
+        elapsed: Duration::ZERO,
+    });
+    assert!(app.commands.is_empty());
+}
+
+#[test]
+fn watch_drops_polls_of_an_earlier_watch() {
+    let mut app = app();
+    app.rpc_source = RpcSource::Endpoint;
+    let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+    app.update(Message::Event(Event::Key(ctrl_s)));
+    let stale = app.watch.stream.generation;
+    // Leaving and re-entering while the first poll is in flight.
+    press(&mut app, KeyCode::Esc);
+    app.update(Message::Event(Event::Key(ctrl_s)));
+    app.commands.clear();
+
+    let batch = || {
+        Ok(watch::Batch {
+            last_slot: 42,
+            transactions: vec![],
+            num_undecoded: 0,
+        })
+    };
+    app.update(Message::WatchPolled {
+        generation: stale,
+        result: batch(),
+        elapsed: Duration::ZERO,
+    });
+    assert!(app.commands.is_empty());
+    assert_eq!(app.watch.stream.last_slot, None);
+    app.update(Message::WatchPolled {
+        generation: app.watch.stream.generation,
+        result: batch(),
+        elapsed: Duration::ZERO,
+    });
+    assert_eq!(app.commands.len(), 1);
+    assert_eq!(app.watch.stream.last_slot, Some(42));
+}
+
+#[test]
+fn watch_records_metrics_of_each_batch() {
+    let mut app = app();
+    app.rpc_source = RpcSource::Endpoint;
+    let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+    app.update(Message::Event(Event::Key(ctrl_s)));
+    app.commands.clear();
+
+    let transaction = transfer();
+    let num_bytes = bincode::serialized_size(&transaction).unwrap();
+    app.update(Message::WatchPolled {
+        generation: app.watch.stream.generation,
+        result: Ok(watch::Batch {
+            last_slot: 42,
+            transactions: vec![watch::StreamedTransaction {
+                slot: 42,
+                block_time: None,
+                transaction: transaction.into(),
+                meta: None,
+                matches: vec![],
+            }],
+            num_undecoded: 2,
+        }),
+        elapsed: Duration::from_millis(300),
+    });
+    app.update(Message::WatchPolled {
+        generation: app.watch.stream.generation,
+        result: Err("timed out".to_string()),
+        elapsed: Duration::from_secs(3),
+    });
+
+    let rendered = app.metrics.render();
+    let lines = rendered.lines().collect::<Vec<_>>();
+    assert!(lines.contains(&"solana_transaction_tui_transactions_seen_total 1"));
+    assert!(lines.contains(&"solana_transaction_tui_decode_failures_total 2"));
+    assert!(lines.contains(&"solana_transaction_tui_poll_failures_total 1"));
+    assert!(lines.contains(&"solana_transaction_tui_fetch_failures_total 0"));
+    let size_sum = format!("solana_transaction_tui_transaction_size_bytes_sum {num_bytes}");
+    assert!(lines.contains(&size_sum.as_str()));
+    assert!(lines.contains(&"solana_transaction_tui_poll_latency_seconds_bucket{le=\"0.25\"} 0"));
+    assert!(lines.contains(&"solana_transaction_tui_poll_latency_seconds_bucket{le=\"0.5\"} 1"));
+    assert!(lines.contains(&"solana_transaction_tui_poll_latency_seconds_bucket{le=\"5\"} 2"));
+    assert!(lines.contains(&"solana_transaction_tui_poll_latency_seconds_count 2"));
+}
+
+#[test]
+fn watch_exports_matches_in_a_command() {
+    let mut app = app();
+    app.rpc_source = RpcSource::Endpoint;
+    let watched = transfer();
+    app.settings.watchlist = vec![watched.message.account_keys[1].to_string()];
+    app.settings.export_stream_matches = true;
+    let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+    app.update(Message::Event(Event::Key(ctrl_s)));
+    app.commands.clear();
+
+    let streamed = |transaction: Transaction| watch::StreamedTransaction {
+        slot: 42,
+        block_time: None,
+        transaction: transaction.into(),
+        meta: None,
+        matches: vec![],
+    };
+    app.update(Message::WatchPolled {
+        generation: app.watch.stream.generation,
+        result: Ok(watch::Batch {
+            last_slot: 42,
+            transactions: vec![streamed(watched.clone()), streamed(transfer())],
+            num_undecoded: 0,
+        }),
+        elapsed: Duration::ZERO,
+    });
+    let Some(Command::ExportStreamMatches { matches, .. }) = app.commands.pop_front() else {
+        panic!("The matches are not exported");
+    };
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].signature(), watched.signatures[0]);
+
+    app.update(Message::StreamMatchesExported {
+        result: Err("disk full".to_string()),
+    });
+    assert!(render(&app).contains("Failed to save matches: disk full"));
+}
+
+#[test]
+fn watch_streams_only_subscribed_transactions() {
+    let mut app = app();
+    app.rpc_source = RpcSource::Endpoint;
+    let subscribed = transfer();
+    let payer = subscribed.message.account_keys[0];
+    app.settings.subscriptions = vec![watch::Subscription {
+        address: payer,
+        enabled: true,
+    }];
+    let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+    app.update(Message::Event(Event::Key(ctrl_s)));
+    let streamed = |transaction: &Transaction| watch::StreamedTransaction {
+        slot: 42,
+        block_time: None,
+        transaction: transaction.clone().into(),
+        meta: None,
+        matches: vec![],
+    };
+    let batch = || watch::Batch {
+        last_slot: 42,
+        transactions: vec![streamed(&subscribed), streamed(&transfer())],
+        num_undecoded: 0,
+    };
+    app.update(Message::WatchPolled {
+        generation: app.watch.stream.generation,
+        result: Ok(batch()),
+        elapsed: Duration::ZERO,
+    });
+    assert_eq!(app.watch.stream.transactions.len(), 1);
+    assert_eq!(
+        app.watch.stream.transactions[0].signature(),
+        subscribed.signatures[0]
+    );
+
+    press(&mut app, KeyCode::Char('s'));
+    assert!(matches!(app.mode, Mode::Subscriptions(0, 0)));
+    let text = render(&app);
+    assert!(text.contains("Subscriptions (enabled)"));
+    assert!(text.contains("[x]"));
+
+    // With the only subscription disabled, every transaction streams.
+    app.settings.subscriptions[0].enabled = false;
+    app.update(Message::WatchPolled {
+        generation: app.watch.stream.generation,
+        result: Ok(batch()),
+        elapsed: Duration::ZERO,
+    });
+    assert_eq!(app.watch.stream.transactions.len(), 3);
+    assert_eq!(app.watch.stream.subscription_counts[&payer], 1);
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(app.mode, Mode::Watch(0)));
+}
+
+#[test]
+fn follows_the_latest_transaction_of_an_address() {
+    let mut app = app();
+    app.rpc_source = RpcSource::Endpoint;
+    let address = Pubkey::new_unique();
+    for c in address.to_string().chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    let ctrl_l = KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL);
+    app.update(Message::Event(Event::Key(ctrl_l)));
+    assert!(matches!(
+        app.commands.pop_front(),
+        Some(Command::PollLatest { address: polled, .. }) if polled == address
+    ));
+
+    let first = transfer();
+    let latest = |transaction: &Transaction, slot| Message::LatestPolled {
+        address,
+        result: Ok(Some(follow::Latest {
+            signature: transaction.signatures[0],
+            slot,
+        })),
+    };
+    app.update(latest(&first, 42));
+    assert_eq!(take_fetch(&mut app), first.signatures[0]);
+    app.update(fetched(&first));
+    assert!(matches!(
+        app.commands.pop_front(),
+        Some(Command::PollLatest { .. })
+    ));
+    assert!(render(&app).contains(&format!("Following {address} (0 updates")));
+    app.commands.clear();
+
+    // Nothing changes until a newer transaction confirms.
+    app.update(latest(&first, 42));
+    assert!(matches!(
+        app.commands.pop_front(),
+        Some(Command::PollLatest { .. })
+    ));
+    let second = transfer();
+    app.update(latest(&second, 43));
+    assert_eq!(take_fetch(&mut app), second.signatures[0]);
+    app.commands.clear();
+
+    // Following stops without an address.
+    app.update(Message::Event(Event::Key(ctrl_l)));
+    app.update(latest(&second, 44));
+    assert!(app.commands.is_empty());
+}
+
+#[test]
+fn watch_filters_and_groups_failures() {
+    use solana_transaction_status::option_serializer::OptionSerializer;
+    let mut app = app();
+    app.rpc_source = RpcSource::Endpoint;
+    let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+    app.update(Message::Event(Event::Key(ctrl_s)));
+    let streamed = |err: Option<TransactionError>| {
+        let meta = UiTransactionStatusMeta {
+            status: err.clone().map_or(Ok(()), Err),
+            err,
+            fee: 5000,
+            pre_balances: vec![],
+            post_balances: vec![],
+            inner_instructions: OptionSerializer::None,
+            log_messages: OptionSerializer::None,
+            pre_token_balances: OptionSerializer::None,
+            post_token_balances: OptionSerializer::None,
+            rewards: OptionSerializer::None,
+            loaded_addresses: OptionSerializer::Skip,
+            return_data: OptionSerializer::Skip,
+            compute_units_consumed: OptionSerializer::Skip,
+        };
+        watch::StreamedTransaction {
+            slot: 42,
+            block_time: None,
+            transaction: transfer().into(),
+            meta: Some(meta),
+            matches: vec![],
+        }
+    };
+    let custom = || TransactionError::InstructionError(0, InstructionError::Custom(1));
+    app.update(Message::WatchPolled {
+        generation: app.watch.stream.generation,
+        result: Ok(watch::Batch {
+            last_slot: 42,
+            transactions: vec![
+                streamed(None),
+                streamed(Some(custom())),
+                streamed(Some(TransactionError::AccountInUse)),
+                streamed(Some(custom())),
+            ],
+            num_undecoded: 0,
+        }),
+        elapsed: Duration::ZERO,
+    });
+
+    press(&mut app, KeyCode::Char('f'));
+    assert_eq!(app.watch_visible().len(), 3);
+    let text = render(&app);
+    assert!(text.contains("Failures by error"));
+    assert!(text.contains("2  Custom(1) in system"));
+    assert!(text.contains("1  AccountInUse"));
+    // The most frequent kind of failure comes first.
+    press(&mut app, KeyCode::Char('f'));
+    assert_eq!(app.watch_visible().len(), 2);
+    assert!(render(&app).contains("showing Custom(1) in system"));
+}
+
+/// The fetch of a transaction which failed with `err`.
+fn fetched_failed(transaction: &Transaction, err: TransactionError) -> Message {
+    use solana_transaction_status::option_serializer::OptionSerializer;
+    let Message::TransactionFetched {
+        signature,
+        result: Ok(mut fetched),
+        elapsed,
+    } = fetched(transaction)
+    else {
+        unreachable!()
+    };
+    fetched.transaction.meta = Some(UiTransactionStatusMeta {
+        status: Err(err.clone()),
+        err: Some(err),
+        fee: 5000,
+        pre_balances: vec![],
+        post_balances: vec![],
+        inner_instructions: OptionSerializer::None,
+        log_messages: OptionSerializer::None,
+        pre_token_balances: OptionSerializer::None,
+        post_token_balances: OptionSerializer::None,
+        rewards: OptionSerializer::None,
+        loaded_addresses: OptionSerializer::Skip,
+        return_data: OptionSerializer::Skip,
+        compute_units_consumed: OptionSerializer::Skip,
+    });
+    Message::TransactionFetched {
+        signature,
+        result: Ok(fetched),
+        elapsed,
+    }
+}
+
+#[test]
+fn names_custom_errors_of_failed_transactions() {
+    let mut app = app();
+    let system = solana_sdk::system_program::id().to_string();
+    app.settings.error_codes = BTreeMap::from([(system, BTreeMap::from([(1, "Slippage".into())]))]);
+    app.error_codes = ErrorCodes::new(&app.settings.error_codes);
+    let transaction = transfer();
+    enter_signature(&mut app, &transaction.signatures[0].to_string());
+    take_fetch(&mut app);
+    let err = TransactionError::InstructionError(0, InstructionError::Custom(1));
+    app.update(fetched_failed(&transaction, err));
+    let text = render(&app);
+    assert!(text
+        .contains("failed: Error processing Instruction 0: custom program error: 0x1 (Slippage)"));
+}
+
+#[test]
+fn selects_the_failed_instruction_on_load() {
+    let mut app = app();
+    let payer = Keypair::new();
+    let instructions = [
+        system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1),
+        system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 2),
+    ];
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::new_unique(),
+    );
+    enter_signature(&mut app, &transaction.signatures[0].to_string());
+    take_fetch(&mut app);
+    let err = TransactionError::InstructionError(1, InstructionError::Custom(1));
+    app.update(fetched_failed(&transaction, err));
+
+    let selection = app.selection();
+    assert_eq!(app.cursor, selection.start);
+    assert_eq!(
+        app.cursor_section_kind(),
+        Some(SectionKind::InstructionProgramIdIndex(1))
+    );
+    // The failed instruction is the last, so its data ends the transaction.
+    assert_eq!(selection.end, app.num_bytes());
+    assert_eq!(app.failed_instruction_bytes(), Some(selection));
+}
+
+#[test]
+fn compares_a_failed_transaction_with_its_simulation() {
+    let mut app = app();
+    let transaction = transfer();
+    let signature = transaction.signatures[0];
+    enter_signature(&mut app, &signature.to_string());
+    take_fetch(&mut app);
+    let err = TransactionError::InstructionError(0, InstructionError::Custom(1));
+    app.update(fetched_failed(&transaction, err.clone()));
+    app.rpc_source = RpcSource::Endpoint;
+    app.commands.clear();
+    press(&mut app, KeyCode::Tab);
+
+    press(&mut app, KeyCode::Char('E'));
+    assert!(matches!(app.mode, Mode::Preflight { .. }));
+    match app.commands.pop_front() {
+        Some(Command::SimulatePreflight {
+            signature: simulated,
+            ..
+        }) => assert_eq!(simulated, signature),
+        _ => panic!("expected a simulation command"),
+    }
+    assert!(render(&app).contains("Simulating..."));
+
+    app.update(Message::PreflightSimulated {
+        signature,
+        result: Ok(Preflight {
+            slot: 50,
+            err: Some(err),
+            logs: vec!["Program log: slippage".to_string()],
+            units_consumed: Some(150),
+        }),
+    });
+    let text = render(&app);
+    assert!(text.contains("Fails the same way now"));
+    assert!(text.contains("Simulated at slot 50, 8 slots after the transaction landed"));
+    assert!(text.contains("The logs diverge from line 1"));
+    assert!(text.contains("Program log: slippage"));
+
+    press(&mut app, KeyCode::Esc);
+    assert!(matches!(app.mode, Mode::ByteNavigation));
+}
+
+#[test]
+fn views_a_piped_transaction() {
+    let mut app = app();
+    let transaction = transfer();
+    let bytes = bincode::serialize(&transaction).unwrap();
+    let piped = ingest::parse_transaction(&bytes).unwrap();
+    app.open_transaction(piped, "stdin".to_string());
+    assert!(matches!(app.mode, Mode::ByteNavigation));
+    assert_eq!(app.current_signature(), Some(transaction.signatures[0]));
+    assert_eq!(app.num_bytes(), bytes.len());
+    assert!(app
+        .commands
+        .iter()
+        .all(|command| !matches!(command, Command::FetchTransaction { .. })));
+    assert!(render(&app).contains("| stdin"));
+}
+
+#[test]
+fn recalls_entered_inputs() {
+    let mut app = app();
+    let (first, second) = (Signature::new_unique(), Signature::new_unique());
+    enter_signature(&mut app, &first.to_string());
+    enter_signature(&mut app, &second.to_string());
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.input.value(), second.to_string());
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.input.value(), first.to_string());
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Down);
+    assert_eq!(app.input.value(), "");
+
+    // Entering a recalled input makes it the most recent.
+    press(&mut app, KeyCode::Up);
+    press(&mut app, KeyCode::Up);
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.input.value(), first.to_string());
+    // The oldest input stays, and the menu has a key of its own.
+    press(&mut app, KeyCode::Up);
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.input.value(), second.to_string());
+    assert!(matches!(app.mode, Mode::SignatureEntry));
+    press(&mut app, KeyCode::F(10));
+    assert!(matches!(app.mode, Mode::MenuSelection));
+}
+
+#[test]
+fn keeps_the_input_without_an_input_history() {
+    let mut app = app();
+    press(&mut app, KeyCode::Char('a'));
+    press(&mut app, KeyCode::Up);
+    assert!(matches!(app.mode, Mode::SignatureEntry));
+    assert_eq!(app.input.value(), "a");
+    assert_eq!(app.input_history_index, None);
+}
+
+#[test]
+fn lists_the_transactions_of_a_ledger_slot() {
+    let mut app = app();
+    app.mode = Mode::LedgerSlotEntry;
+    app.prompt_input = Input::new("latest".to_string());
+    press(&mut app, KeyCode::Enter);
+    assert!(app.commands.is_empty());
+
+    app.prompt_input = Input::new("7".to_string());
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(
+        app.commands.front(),
+        Some(Command::FetchSlotEntries { slot: 7, .. })
+    ));
+    // Only a local ledger serves entries.
+    run_command(&mut app);
+    assert!(matches!(&app.screen, Screen::Error(err) if err.contains("local ledger")));
+
+    let transaction = VersionedTransaction::from(transfer());
+    let entries = vec![entries::Entry {
+        num_hashes: 1,
+        hash: Hash::new_unique(),
+        transactions: vec![transaction.clone()],
+    }];
+    app.update(Message::SlotEntriesFetched {
+        slot: 7,
+        result: Ok(bincode::serialize(&entries).unwrap()),
+    });
+    assert!(matches!(app.mode, Mode::EntryPicker(0)));
+    press(&mut app, KeyCode::Enter);
+    let loaded = app.loaded().expect("transaction is loaded");
+    assert_eq!(loaded.transaction, transaction);
+    // Past the entry count, the number of hashes, the hash and the
+    // transaction count.
+    assert_eq!(
+        loaded.source.as_deref(),
+        Some("slot 7 entry 0 transaction 0 at offset 0x38")
+    );
+}
+
+#[test]
+fn fetches_the_transaction_of_an_explorer_link() {
+    let mut app = app();
+    let signature = Signature::new_unique();
+    enter_signature(
+        &mut app,
+        &format!("https://solscan.io/tx/{signature}?cluster=devnet"),
+    );
+    assert!(matches!(app.screen, Screen::Loading(loading) if loading == signature));
+    assert_eq!(app.endpoint, "https://api.devnet.solana.com");
+    assert!(matches!(
+        app.commands.back(),
+        Some(Command::FetchTransaction { signature: fetching, .. }) if *fetching == signature
+    ));
+
+    // A link to another kind of page is reported rather than fetched.
+    enter_signature(
+        &mut app,
+        "https://explorer.solana.com/address/11111111111111111111111111111111",
+    );
+    assert!(matches!(&app.screen, Screen::Error(err) if err.contains("not to a transaction")));
+}
+
+#[test]
+fn views_a_pasted_hex_transaction() {
+    let mut app = app();
+    let transaction = transfer();
+    let bytes = bincode::serialize(&transaction).unwrap();
+    let hex = hex::encode(&bytes);
+    let (first, rest) = hex.split_at(40);
+    app.update(Message::Event(Event::Paste(format!(
+        "0x{first}\n  {rest} "
+    ))));
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(app.mode, Mode::ByteNavigation));
+    assert_eq!(app.current_signature(), Some(transaction.signatures[0]));
+    assert_eq!(app.num_bytes(), bytes.len());
+    assert!(render(&app).contains("| hex"));
+
+    app.mode = Mode::SignatureEntry;
+    app.update(Message::Event(Event::Paste(hex[..40].to_string())));
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(&app.screen, Screen::Error(err) if err.starts_with("Invalid transaction")));
+}
+
+#[test]
+fn parses_source_flags() {
+    let parse = |args: &[&str]| parse_args(args.iter().map(ToString::to_string));
+    let args = parse(&["--mock", "--metrics", "metrics.prom"]).unwrap();
+    assert!(matches!(args.rpc_source, RpcSource::Mock(_)));
+    assert_eq!(args.metrics_path.as_deref(), Some("metrics.prom"));
+    assert_eq!(
+        parse(&["--mock", "--replay", "traffic.json"])
+            .err()
+            .as_deref(),
+        Some("--replay cannot be combined with --mock")
+    );
+    assert_eq!(
+        parse(&["--mock", "mock", "--ledger-dir", "ledger"])
+            .err()
+            .as_deref(),
+        Some("--ledger-dir cannot be combined with --mock")
+    );
+    // Neither the bundle nor stdin is read before the arguments are checked.
+    assert_eq!(
+        parse(&["--import", "missing.bundle", "--stdin"])
+            .err()
+            .as_deref(),
+        Some("--stdin cannot be combined with --import")
+    );
+}
+
+#[test]
+fn offers_the_commands_of_the_current_mode_first() {
+    let palette = Palette::new(Mode::SignatureEntry);
+    let commands = palette.commands();
+    let num_available = commands
+        .iter()
+        .take_while(|(_, command)| {
+            matches!(command, PaletteCommand::Key(binding) if palette.is_available(binding))
+        })
+        .count();
+    assert!(num_available > 0);
+    // Then the commands of other modes, each action once per mode, and the
+    // menu items last.
+    let mut seen = vec![];
+    for (label, command) in &commands[num_available..] {
+        match command {
+            PaletteCommand::Key(binding) => {
+                assert!(!palette.is_available(binding), "{label}");
+                assert!(
+                    !seen.contains(&(binding.context, binding.action)),
+                    "{label}"
+                );
+                seen.push((binding.context, binding.action));
+            }
+            PaletteCommand::Menu(_) => assert!(label.contains(": "), "{label}"),
+        }
+    }
+    assert!(matches!(
+        commands.last(),
+        Some((_, PaletteCommand::Menu(_)))
+    ));
+
+    let mut palette = palette;
+    palette.query = Input::new("endpoint devnet".to_string());
+    let labels = palette
+        .commands()
+        .into_iter()
+        .map(|(label, _)| label)
+        .collect::<Vec<_>>();
+    assert_eq!(labels, ["Endpoint: devnet"]);
+}
+
+#[test]
+fn masks_secrets_by_character_and_derives_their_keypair() {
+    let mut app = app();
+    app.perform_menu_item(AppMenuItem::EnterSeedPhrase);
+    let num_masked = |app: &TransactionApp| render(app).matches('*').count();
+    let before = num_masked(&app);
+    // Multi-byte characters are masked by one `*` each.
+    for c in "süß 日本".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    assert_eq!(num_masked(&app), before + 6);
+    assert!(!render(&app).contains("süß"));
+
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(
+        app.mode,
+        Mode::KeypairEntry(KeypairPrompt::Passphrase(_))
+    ));
+    assert_eq!(num_masked(&app), before);
+    enter_signature(&mut app, "pässword");
+    assert!(matches!(app.mode, Mode::SignatureEntry));
+    assert_eq!(app.keypairs.signers().count(), 1);
+}
@@ -0,0 +1,64 @@
+//! Hex digits two lines tall, drawn with half block characters, readable
+//! from the back of a room when projected.
+
+/// Width of each digit, without spacing.
+pub const DIGIT_WIDTH: usize = 3;
+
+/// The pixels of each hex digit, 3 wide and 4 tall, as the bits of each row
+/// from the top, the leftmost pixel in the highest bit.
+const FONT: [[u8; 4]; 16] = [
+    [0b111, 0b101, 0b101, 0b111], // 0
+    [0b010, 0b110, 0b010, 0b010], // 1
+    [0b110, 0b001, 0b010, 0b111], // 2
+    [0b110, 0b011, 0b001, 0b110], // 3
+    [0b101, 0b101, 0b111, 0b001], // 4
+    [0b111, 0b110, 0b001, 0b110], // 5
+    [0b100, 0b111, 0b101, 0b111], // 6
+    [0b111, 0b001, 0b010, 0b100], // 7
+    [0b111, 0b111, 0b101, 0b111], // 8
+    [0b111, 0b101, 0b111, 0b001], // 9
+    [0b010, 0b101, 0b111, 0b101], // a
+    [0b100, 0b110, 0b101, 0b110], // b
+    [0b011, 0b100, 0b100, 0b011], // c
+    [0b001, 0b011, 0b101, 0b011], // d
+    [0b111, 0b110, 0b100, 0b111], // e
+    [0b111, 0b100, 0b110, 0b100], // f
+];
+
+/// The two lines of a byte in hex, its digits one column apart.
+pub fn byte(byte: u8) -> [String; 2] {
+    let [high, low] = [digit(byte >> 4), digit(byte & 0xf)];
+    [0, 1].map(|line| format!("{} {}", high[line], low[line]))
+}
+
+/// The two lines of a hex digit, each line two rows of pixels.
+fn digit(value: u8) -> [String; 2] {
+    let rows = FONT[value as usize];
+    [0, 1].map(|line| {
+        (0..DIGIT_WIDTH)
+            .map(|column| {
+                let bit = 1 << (DIGIT_WIDTH - 1 - column);
+                match (rows[2 * line] & bit != 0, rows[2 * line + 1] & bit != 0) {
+                    (false, false) => ' ',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (true, true) => '█',
+                }
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_bytes_in_half_blocks() {
+        assert_eq!(byte(0x0a), ["█▀█ ▄▀▄", "█▄█ █▀█"]);
+        assert_eq!(byte(0x7f), ["▀▀█ █▀▀", "▄▀  █▀ "]);
+        // Every digit is drawn differently.
+        let digits = (0..16).map(digit).collect::<std::collections::HashSet<_>>();
+        assert_eq!(digits.len(), 16);
+    }
+}
@@ -0,0 +1,80 @@
+//! A local validator ledger as a data source, so transactions of a node
+//! without RPC enabled can be inspected. Needs the `blockstore` feature.
+
+use {
+    crate::rpc::{FetchOptions, Request, RpcApi},
+    serde_json::Value,
+    solana_ledger::{
+        blockstore::Blockstore,
+        blockstore_options::{AccessType, BlockstoreOptions},
+    },
+    solana_sdk::signature::Signature,
+    solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta,
+    std::path::PathBuf,
+};
+
+/// Answers requests from the blockstore of a ledger directory. It is opened
+/// with secondary access, which works alongside a running validator but
+/// only sees what was written before opening.
+pub struct LocalLedger {
+    pub path: PathBuf,
+    blockstore: Blockstore,
+}
+
+impl LocalLedger {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let options = BlockstoreOptions {
+            access_type: AccessType::Secondary,
+            ..BlockstoreOptions::default()
+        };
+        let blockstore = Blockstore::open_with_options(&path, options)
+            .map_err(|err| format!("Failed to open ledger {}: {err}", path.display()))?;
+        Ok(Self { path, blockstore })
+    }
+}
+
+impl RpcApi for LocalLedger {
+    fn send(&self, request: Request) -> Result<Value, String> {
+        Err(format!(
+            "{} is only available from an endpoint",
+            request.method()
+        ))
+    }
+
+    fn get_transaction(
+        &self,
+        signature: &Signature,
+        options: &FetchOptions,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, String> {
+        // Include confirmed slots which are not rooted yet.
+        let highest_slot = self
+            .blockstore
+            .highest_slot()
+            .map_err(|err| err.to_string())?
+            .unwrap_or_else(|| self.blockstore.max_root());
+        self.blockstore
+            .get_complete_transaction(*signature, highest_slot)
+            .map_err(|err| err.to_string())?
+            .ok_or_else(|| format!("Transaction not found in {}", self.path.display()))?
+            .encode(
+                options.encoding.encoding(),
+                options.max_supported_transaction_version(),
+            )
+            .map_err(|err| err.to_string())
+    }
+
+    fn get_slot_entries(&self, slot: u64) -> Result<Vec<u8>, String> {
+        let entries = self
+            .blockstore
+            .get_slot_entries(slot, 0)
+            .map_err(|err| err.to_string())?;
+        if entries.is_empty() {
+            return Err(format!(
+                "No entries for slot {slot} in {}",
+                self.path.display()
+            ));
+        }
+        bincode::serialize(&entries).map_err(|err| err.to_string())
+    }
+}
@@ -0,0 +1,286 @@
+use {
+    crate::{
+        address_book::AddressBook,
+        storage::{self, FileChange},
+    },
+    serde::{Deserialize, Serialize},
+    solana_sdk::{
+        address_lookup_table, bpf_loader_upgradeable, compute_budget, pubkey::Pubkey,
+        signature::Signature, stake, system_program, transaction::VersionedTransaction, vote,
+    },
+    std::{collections::BTreeMap, io, ops::Range, str::FromStr},
+};
+
+const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+/// A named note attached to a range of transaction bytes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    pub start: usize,
+    pub end: usize,
+    pub note: String,
+}
+
+impl Annotation {
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    pub fn contains(&self, offset: usize) -> bool {
+        self.range().contains(&offset)
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub annotations: Vec<Annotation>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Tags of the programs the transaction invokes, set automatically.
+    #[serde(default)]
+    pub program_tags: Vec<String>,
+}
+
+impl Bookmark {
+    /// Whether every word of `filter` is part of a tag, ignoring case and a
+    /// leading `#`.
+    pub fn matches(&self, filter: &str) -> bool {
+        filter.split_whitespace().all(|word| {
+            let word = word.trim_start_matches('#').to_lowercase();
+            self.tags
+                .iter()
+                .chain(&self.program_tags)
+                .any(|tag| tag.to_lowercase().contains(&word))
+        })
+    }
+}
+
+/// Parse tags typed separated by spaces, optionally prefixed with `#`.
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags = Vec::<String>::new();
+    for tag in text
+        .split_whitespace()
+        .map(|tag| tag.trim_start_matches('#'))
+    {
+        if !tag.is_empty() && !tags.iter().any(|existing| existing == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+/// A tag for each program the transaction invokes: its name in the address
+/// book, the name of a native program, or its pubkey.
+pub fn program_tags(transaction: &VersionedTransaction, address_book: &AddressBook) -> Vec<String> {
+    let message = &transaction.message;
+    let mut tags = Vec::<String>::new();
+    for instruction in message.instructions() {
+        let Some(program_id) = message
+            .static_account_keys()
+            .get(instruction.program_id_index as usize)
+        else {
+            continue;
+        };
+        let tag = address_book
+            .name(program_id)
+            .or_else(|| native_program_name(program_id))
+            .map(str::to_string)
+            .unwrap_or_else(|| program_id.to_string());
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// The name of a builtin program, which has no deployment of its own.
+pub fn native_program_name(program_id: &Pubkey) -> Option<&'static str> {
+    [
+        (system_program::id(), "system"),
+        (vote::program::id(), "vote"),
+        (stake::program::id(), "stake"),
+        (compute_budget::id(), "compute-budget"),
+        (address_lookup_table::program::id(), "address-lookup-table"),
+        (bpf_loader_upgradeable::id(), "bpf-loader-upgradeable"),
+    ]
+    .into_iter()
+    .find(|(id, _)| id == program_id)
+    .map(|(_, name)| name)
+}
+
+/// Bookmarked signatures along with their annotations, persisted between
+/// sessions.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Bookmarks {
+    entries: BTreeMap<String, Bookmark>,
+    /// Why the bookmarks file failed to load, in which case no bookmarks are
+    /// shown and the file is not saved over.
+    #[serde(skip)]
+    load_error: Option<String>,
+}
+
+impl Bookmarks {
+    pub fn load() -> Self {
+        Self::loaded(storage::try_load_json(BOOKMARKS_FILE))
+    }
+
+    /// The loaded bookmarks, or none along with why they failed to load.
+    fn loaded(result: Result<Self, String>) -> Self {
+        result.unwrap_or_else(|err| Self {
+            load_error: Some(err),
+            ..Self::default()
+        })
+    }
+
+    pub fn load_error(&self) -> Option<&str> {
+        self.load_error.as_deref()
+    }
+
+    pub fn save(&self) -> io::Result<FileChange> {
+        if let Some(err) = &self.load_error {
+            return Err(io::Error::other(format!("{err}, not saving over it")));
+        }
+        storage::save_json(BOOKMARKS_FILE, self)
+    }
+
+    pub fn get(&self, signature: &Signature) -> Option<&Bookmark> {
+        self.entries.get(&signature.to_string())
+    }
+
+    pub fn is_bookmarked(&self, signature: &Signature) -> bool {
+        self.get(signature).is_some()
+    }
+
+    /// Annotations for the signature, or an empty slice if not bookmarked.
+    pub fn annotations(&self, signature: &Signature) -> &[Annotation] {
+        self.get(signature)
+            .map(|bookmark| bookmark.annotations.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The bookmarks matching `filter`, see [`Bookmark::matches`].
+    pub fn filtered(&self, filter: &str) -> Vec<(Signature, &Bookmark)> {
+        self.entries
+            .iter()
+            .filter(|(_, bookmark)| bookmark.matches(filter))
+            .filter_map(|(signature, bookmark)| {
+                Some((Signature::from_str(signature).ok()?, bookmark))
+            })
+            .collect()
+    }
+
+    /// Set the tags of a signature, bookmarking it if necessary.
+    pub fn set_tags(&mut self, signature: &Signature, tags: Vec<String>) {
+        self.entries.entry(signature.to_string()).or_default().tags = tags;
+    }
+
+    /// Set the program tags of a signature, if it is bookmarked.
+    pub fn set_program_tags(&mut self, signature: &Signature, program_tags: Vec<String>) {
+        if let Some(bookmark) = self.entries.get_mut(&signature.to_string()) {
+            bookmark.program_tags = program_tags;
+        }
+    }
+
+    /// Toggle the bookmark for a signature. Returns true if the signature is
+    /// now bookmarked.
+    pub fn toggle(&mut self, signature: &Signature) -> bool {
+        let key = signature.to_string();
+        if self.entries.remove(&key).is_some() {
+            false
+        } else {
+            self.entries.insert(key, Bookmark::default());
+            true
+        }
+    }
+
+    /// Add an annotation, bookmarking the signature if necessary.
+    pub fn annotate(&mut self, signature: &Signature, annotation: Annotation) {
+        self.entries
+            .entry(signature.to_string())
+            .or_default()
+            .annotations
+            .push(annotation);
+    }
+
+    /// Remove all annotations covering `offset`. Returns true if any were
+    /// removed.
+    pub fn remove_annotations_at(&mut self, signature: &Signature, offset: usize) -> bool {
+        let Some(bookmark) = self.entries.get_mut(&signature.to_string()) else {
+            return false;
+        };
+        let num_annotations = bookmark.annotations.len();
+        bookmark
+            .annotations
+            .retain(|annotation| !annotation.contains(offset));
+        bookmark.annotations.len() != num_annotations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::address_book::parse_contact,
+        solana_sdk::{
+            compute_budget::ComputeBudgetInstruction,
+            instruction::Instruction,
+            message::{Message, VersionedMessage},
+            system_instruction,
+        },
+    };
+
+    #[test]
+    fn tags_programs_and_filters_by_tag() {
+        let (payer, program_id) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instructions = [
+            ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+            system_instruction::transfer(&payer, &program_id, 1),
+            Instruction::new_with_bytes(program_id, &[], vec![]),
+        ];
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::Legacy(Message::new(&instructions, Some(&payer))),
+        };
+        let mut address_book = AddressBook::default();
+        let tags = program_tags(&transaction, &address_book);
+        assert_eq!(tags, ["compute-budget", "system", &program_id.to_string()]);
+        let (pubkey, contact) = parse_contact(&format!("{program_id} Swap Router")).unwrap();
+        address_book.insert(&pubkey, contact);
+        assert_eq!(program_tags(&transaction, &address_book)[2], "Swap Router");
+
+        let mut bookmarks = Bookmarks::default();
+        let (tagged, untagged) = (Signature::new_unique(), Signature::new_unique());
+        bookmarks.set_tags(&tagged, parse_tags("#incident  swap #incident"));
+        bookmarks.set_program_tags(&tagged, tags);
+        bookmarks.toggle(&untagged);
+        bookmarks.set_program_tags(&Signature::new_unique(), vec!["vote".to_string()]);
+        let signatures = |filter| {
+            bookmarks
+                .filtered(filter)
+                .into_iter()
+                .map(|(signature, _)| signature)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(bookmarks.get(&tagged).unwrap().tags, ["incident", "swap"]);
+        assert_eq!(signatures("#INCIDENT budget"), [tagged]);
+        assert_eq!(signatures("vote"), []);
+        assert_eq!(signatures("").len(), 2);
+    }
+
+    #[test]
+    fn refuses_to_save_over_a_file_which_failed_to_load() {
+        let path = std::env::temp_dir().join(format!("bookmarks-{}.json", std::process::id()));
+        std::fs::write(&path, r##"{"entries": {"sig": {"tags": "#incident"}}}"##).unwrap();
+        let mut bookmarks = Bookmarks::loaded(storage::try_load_json_at(&path));
+        std::fs::remove_file(&path).unwrap();
+        let err = bookmarks.load_error().unwrap().to_string();
+        assert!(err.starts_with(&format!("Invalid {}", path.display())));
+
+        // Tagging and annotating still work for the session.
+        let signature = Signature::new_unique();
+        bookmarks.set_tags(&signature, parse_tags("#incident"));
+        assert!(bookmarks.is_bookmarked(&signature));
+        let save_err = bookmarks.save().unwrap_err();
+        assert_eq!(save_err.to_string(), format!("{err}, not saving over it"));
+    }
+}
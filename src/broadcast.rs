@@ -0,0 +1,287 @@
+//! Sending a signed transaction to several endpoints at once, to compare how
+//! each of them accepts it and how soon it lands through each.
+
+use {
+    crate::{rpc::RpcApi, slot_timing::Attempt},
+    chrono::Utc,
+    solana_client::rpc_config::RpcSendTransactionConfig,
+    solana_sdk::{
+        commitment_config::CommitmentConfig, signature::Signature,
+        transaction::VersionedTransaction,
+    },
+    std::{
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Give up on transactions which do not land, e.g. dropped ones.
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How sending to one endpoint went. Times are since the broadcast started.
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    Sending,
+    Rejected {
+        elapsed: Duration,
+        err: String,
+    },
+    /// Accepted, but not seen confirmed before the timeout.
+    Dropped {
+        accepted: Duration,
+        submitted_slot: Option<u64>,
+    },
+    Landed {
+        accepted: Duration,
+        landed: Duration,
+        /// The slot of the endpoint just before sending, if it answered.
+        submitted_slot: Option<u64>,
+        slot: u64,
+        err: Option<String>,
+    },
+}
+
+impl Outcome {
+    pub fn summary(&self) -> String {
+        let secs = |duration: &Duration| format!("{:.2}s", duration.as_secs_f64());
+        match self {
+            Self::Sending => "sending...".to_string(),
+            Self::Rejected { elapsed, err } => format!("rejected after {}: {err}", secs(elapsed)),
+            Self::Dropped { accepted, .. } => format!(
+                "accepted after {}, not confirmed within {}",
+                secs(accepted),
+                secs(&TIMEOUT)
+            ),
+            Self::Landed {
+                accepted,
+                landed,
+                submitted_slot,
+                slot,
+                err,
+            } => {
+                let mut summary = format!(
+                    "accepted after {}, landed in slot {slot} after {}",
+                    secs(accepted),
+                    secs(landed)
+                );
+                if let Some(submitted_slot) = submitted_slot {
+                    summary = format!("{summary} ({} slots)", slot.saturating_sub(*submitted_slot));
+                }
+                match err {
+                    Some(err) => format!("{summary} with error {err}"),
+                    None => summary,
+                }
+            }
+        }
+    }
+}
+
+impl Outcome {
+    /// The attempt to record for the slot timing, once sending finished.
+    pub fn attempt(&self, endpoint: &str, submitted_at: i64) -> Option<Attempt> {
+        let millis = |duration: &Duration| submitted_at + duration.as_millis() as i64;
+        let (submitted_slot, landed) = match self {
+            Self::Sending | Self::Rejected { .. } => return None,
+            Self::Dropped { submitted_slot, .. } => (*submitted_slot, None),
+            Self::Landed {
+                landed,
+                submitted_slot,
+                slot,
+                ..
+            } => (*submitted_slot, Some((*slot, millis(landed)))),
+        };
+        Some(Attempt {
+            submitted_at,
+            endpoint: Some(endpoint.to_string()),
+            submitted_slot,
+            landed_slot: landed.map(|(slot, _)| slot),
+            landed_at: landed.map(|(_, landed_at)| landed_at),
+        })
+    }
+}
+
+pub struct Broadcast {
+    pub signature: Signature,
+    /// When the broadcast started, as a unix timestamp in milliseconds.
+    pub submitted_at: i64,
+    pub outcomes: Vec<(String, Outcome)>,
+}
+
+impl Broadcast {
+    pub fn new(signature: Signature, endpoints: &[String]) -> Self {
+        Self {
+            signature,
+            submitted_at: Utc::now().timestamp_millis(),
+            outcomes: endpoints
+                .iter()
+                .map(|endpoint| (endpoint.clone(), Outcome::Sending))
+                .collect(),
+        }
+    }
+
+    pub fn record(&mut self, endpoint: &str, outcome: Outcome) {
+        if let Some((_, current)) = self.outcomes.iter_mut().find(|(url, _)| url == endpoint) {
+            *current = outcome;
+        }
+    }
+
+    pub fn num_done(&self) -> usize {
+        self.outcomes
+            .iter()
+            .filter(|(_, outcome)| !matches!(outcome, Outcome::Sending))
+            .count()
+    }
+}
+
+/// Send the transaction to `endpoint` and poll the endpoint until it sees the
+/// transaction confirmed, blocking meanwhile. Preflight is skipped, so that a
+/// rejection is the endpoint's rather than the simulation's.
+pub fn send(rpc: &dyn RpcApi, transaction: &VersionedTransaction, started: Instant) -> Outcome {
+    let config = RpcSendTransactionConfig {
+        skip_preflight: true,
+        ..RpcSendTransactionConfig::default()
+    };
+    let submitted_slot = rpc.get_slot(CommitmentConfig::confirmed()).ok();
+    let signature = match rpc.send_transaction(transaction, config) {
+        Ok(signature) => signature,
+        Err(err) => {
+            return Outcome::Rejected {
+                elapsed: started.elapsed(),
+                err,
+            }
+        }
+    };
+    let accepted = started.elapsed();
+    while started.elapsed() < TIMEOUT {
+        thread::sleep(POLL_INTERVAL);
+        let Ok(statuses) = rpc.get_signature_statuses(&[signature]) else {
+            continue;
+        };
+        let Some(status) = statuses.into_iter().flatten().next() else {
+            continue;
+        };
+        if status.satisfies_commitment(CommitmentConfig::confirmed()) {
+            return Outcome::Landed {
+                accepted,
+                landed: started.elapsed(),
+                submitted_slot,
+                slot: status.slot,
+                err: status.err.map(|err| err.to_string()),
+            };
+        }
+    }
+    Outcome::Dropped {
+        accepted,
+        submitted_slot,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::rpc::Request,
+        serde_json::{json, Value},
+    };
+
+    fn landed(err: Option<&str>) -> Outcome {
+        Outcome::Landed {
+            accepted: Duration::from_millis(120),
+            landed: Duration::from_millis(1_500),
+            submitted_slot: Some(100),
+            slot: 103,
+            err: err.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn summarizes_each_outcome() {
+        assert_eq!(
+            landed(None).summary(),
+            "accepted after 0.12s, landed in slot 103 after 1.50s (3 slots)"
+        );
+        assert_eq!(
+            landed(Some("InsufficientFundsForFee")).summary(),
+            "accepted after 0.12s, landed in slot 103 after 1.50s (3 slots) with error \
+             InsufficientFundsForFee"
+        );
+        let rejected = Outcome::Rejected {
+            elapsed: Duration::from_millis(40),
+            err: "Blockhash not found".to_string(),
+        };
+        assert_eq!(
+            rejected.summary(),
+            "rejected after 0.04s: Blockhash not found"
+        );
+        let dropped = Outcome::Dropped {
+            accepted: Duration::from_millis(90),
+            submitted_slot: None,
+        };
+        assert_eq!(
+            dropped.summary(),
+            "accepted after 0.09s, not confirmed within 60.00s"
+        );
+    }
+
+    #[test]
+    fn records_attempts_of_accepted_transactions() {
+        let attempt = landed(None).attempt("https://a.example", 1_000).unwrap();
+        assert_eq!(attempt.endpoint.as_deref(), Some("https://a.example"));
+        assert_eq!(attempt.submitted_slot, Some(100));
+        assert_eq!(attempt.landed_slot, Some(103));
+        assert_eq!(attempt.landed_at, Some(2_500));
+
+        let dropped = Outcome::Dropped {
+            accepted: Duration::ZERO,
+            submitted_slot: Some(7),
+        };
+        let attempt = dropped.attempt("https://a.example", 1_000).unwrap();
+        assert_eq!(attempt.submitted_slot, Some(7));
+        assert_eq!(attempt.landed_at, None);
+
+        assert!(Outcome::Sending
+            .attempt("https://a.example", 1_000)
+            .is_none());
+    }
+
+    #[test]
+    fn counts_the_endpoints_done() {
+        let endpoints = [
+            "https://a.example".to_string(),
+            "https://b.example".to_string(),
+        ];
+        let mut broadcast = Broadcast::new(Signature::default(), &endpoints);
+        assert_eq!(broadcast.num_done(), 0);
+        broadcast.record("https://b.example", landed(None));
+        // Outcomes of endpoints not broadcast to are ignored.
+        broadcast.record("https://c.example", landed(None));
+        assert_eq!(broadcast.num_done(), 1);
+        assert!(matches!(broadcast.outcomes[0].1, Outcome::Sending));
+        assert!(matches!(broadcast.outcomes[1].1, Outcome::Landed { .. }));
+    }
+
+    struct Rejecting;
+
+    impl RpcApi for Rejecting {
+        fn send(&self, request: Request) -> Result<Value, String> {
+            match request {
+                Request::GetSlot { .. } => Ok(json!(100)),
+                Request::SendTransaction { config, .. } => {
+                    assert!(config.skip_preflight);
+                    Err("Transaction signature verification failure".to_string())
+                }
+                request => Err(format!("unexpected {}", request.method())),
+            }
+        }
+    }
+
+    #[test]
+    fn reports_rejections_without_polling() {
+        let outcome = send(&Rejecting, &VersionedTransaction::default(), Instant::now());
+        assert!(matches!(
+            outcome,
+            Outcome::Rejected { err, .. } if err == "Transaction signature verification failure"
+        ));
+    }
+}
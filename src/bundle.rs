@@ -0,0 +1,228 @@
+//! Self-contained session bundles, which reproduce the view of a transaction
+//! without a network. A bundle is a zstd compressed tar archive holding the
+//! serialized transaction, its status meta (with the addresses its lookup
+//! tables resolved to), the accounts fetched while viewing it and its
+//! annotations.
+
+use {
+    crate::bookmarks::Annotation,
+    serde::{de::DeserializeOwned, Deserialize, Serialize},
+    solana_sdk::{
+        account::Account, pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction,
+    },
+    solana_transaction_status::UiTransactionStatusMeta,
+    solana_transaction_tui::ingest,
+    std::{
+        collections::{BTreeMap, HashMap},
+        io::Read,
+        str::FromStr,
+    },
+};
+
+const TRANSACTION_FILE: &str = "transaction.bin";
+const DETAILS_FILE: &str = "details.json";
+const ACCOUNTS_FILE: &str = "accounts.json";
+const ANNOTATIONS_FILE: &str = "annotations.json";
+
+pub struct Bundle {
+    pub signature: Signature,
+    pub transaction: VersionedTransaction,
+    pub meta: Option<UiTransactionStatusMeta>,
+    pub slot: Option<u64>,
+    pub block_time: Option<i64>,
+    pub source: Option<String>,
+    pub accounts: BTreeMap<Pubkey, Account>,
+    pub annotations: Vec<Annotation>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Details {
+    signature: String,
+    meta: Option<UiTransactionStatusMeta>,
+    slot: Option<u64>,
+    block_time: Option<i64>,
+    source: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AccountFile {
+    lamports: u64,
+    owner: String,
+    executable: bool,
+    rent_epoch: u64,
+    /// Hex encoded.
+    data: String,
+}
+
+impl Bundle {
+    pub fn archive(&self) -> Result<Vec<u8>, String> {
+        let details = Details {
+            signature: self.signature.to_string(),
+            meta: self.meta.clone(),
+            slot: self.slot,
+            block_time: self.block_time,
+            source: self.source.clone(),
+        };
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|(pubkey, account)| {
+                let account = AccountFile {
+                    lamports: account.lamports,
+                    owner: account.owner.to_string(),
+                    executable: account.executable,
+                    rent_epoch: account.rent_epoch,
+                    data: hex::encode(&account.data),
+                };
+                (pubkey.to_string(), account)
+            })
+            .collect::<BTreeMap<_, _>>();
+        let files = [
+            (
+                TRANSACTION_FILE,
+                bincode::serialize(&self.transaction).map_err(|err| err.to_string())?,
+            ),
+            (DETAILS_FILE, to_json(&details)?),
+            (ACCOUNTS_FILE, to_json(&accounts)?),
+            (ANNOTATIONS_FILE, to_json(&self.annotations)?),
+        ];
+
+        let mut builder = tar::Builder::new(vec![]);
+        for (name, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, contents.as_slice())
+                .map_err(|err| format!("Failed to archive {name}: {err}"))?;
+        }
+        let tar = builder.into_inner().map_err(|err| err.to_string())?;
+        zstd::encode_all(tar.as_slice(), 0).map_err(|err| format!("Failed to compress: {err}"))
+    }
+
+    pub fn unarchive(bytes: &[u8]) -> Result<Self, String> {
+        let tar = zstd::decode_all(bytes).map_err(|err| format!("Invalid bundle: {err}"))?;
+        let mut files = HashMap::new();
+        let mut archive = tar::Archive::new(tar.as_slice());
+        for entry in archive
+            .entries()
+            .map_err(|err| format!("Invalid bundle: {err}"))?
+        {
+            let mut entry = entry.map_err(|err| format!("Invalid bundle: {err}"))?;
+            let name = entry
+                .path()
+                .map_err(|err| format!("Invalid bundle: {err}"))?
+                .to_string_lossy()
+                .into_owned();
+            let mut contents = vec![];
+            entry
+                .read_to_end(&mut contents)
+                .map_err(|err| format!("Invalid bundle: {err}"))?;
+            files.insert(name, contents);
+        }
+        let file = |name: &str| {
+            files
+                .get(name)
+                .map(Vec::as_slice)
+                .ok_or_else(|| format!("Invalid bundle: missing {name}"))
+        };
+
+        let transaction = ingest::parse_transaction(file(TRANSACTION_FILE)?)?;
+        let details = from_json::<Details>(DETAILS_FILE, file(DETAILS_FILE)?)?;
+        let signature = Signature::from_str(&details.signature)
+            .map_err(|err| format!("Invalid bundle signature: {err}"))?;
+        let accounts =
+            from_json::<BTreeMap<String, AccountFile>>(ACCOUNTS_FILE, file(ACCOUNTS_FILE)?)?
+                .into_iter()
+                .map(|(pubkey, account)| {
+                    let invalid = |err: String| format!("Invalid bundle account {pubkey}: {err}");
+                    let account = Account {
+                        lamports: account.lamports,
+                        owner: Pubkey::from_str(&account.owner)
+                            .map_err(|err| invalid(err.to_string()))?,
+                        executable: account.executable,
+                        rent_epoch: account.rent_epoch,
+                        data: hex::decode(&account.data).map_err(|err| invalid(err.to_string()))?,
+                    };
+                    let pubkey =
+                        Pubkey::from_str(&pubkey).map_err(|err| invalid(err.to_string()))?;
+                    Ok((pubkey, account))
+                })
+                .collect::<Result<_, String>>()?;
+        let annotations = from_json(ANNOTATIONS_FILE, file(ANNOTATIONS_FILE)?)?;
+        Ok(Self {
+            signature,
+            transaction,
+            meta: details.meta,
+            slot: details.slot,
+            block_time: details.block_time,
+            source: details.source,
+            accounts,
+            annotations,
+        })
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|err| err.to_string())
+}
+
+fn from_json<T: DeserializeOwned>(name: &str, contents: &[u8]) -> Result<T, String> {
+    serde_json::from_slice(contents).map_err(|err| format!("Invalid bundle {name}: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash, signature::Keypair, signer::Signer, system_instruction,
+            transaction::Transaction,
+        },
+    };
+
+    #[test]
+    fn archive_round_trip() {
+        let payer = Keypair::new();
+        let instruction = system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+        let transaction = VersionedTransaction::from(Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[&payer],
+            Hash::new_unique(),
+        ));
+        let account = Account {
+            lamports: 42,
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 7,
+            data: vec![1, 2, 3],
+        };
+        let bundle = Bundle {
+            signature: transaction.signatures[0],
+            transaction,
+            meta: None,
+            slot: Some(287_654_321),
+            block_time: Some(1_700_000_000),
+            source: None,
+            accounts: BTreeMap::from([(Pubkey::new_unique(), account)]),
+            annotations: vec![Annotation {
+                start: 1,
+                end: 65,
+                note: "fee payer signature".to_string(),
+            }],
+        };
+
+        let unarchived = Bundle::unarchive(&bundle.archive().unwrap()).unwrap();
+        assert_eq!(unarchived.signature, bundle.signature);
+        assert_eq!(unarchived.transaction, bundle.transaction);
+        assert_eq!(unarchived.slot, bundle.slot);
+        assert_eq!(unarchived.block_time, bundle.block_time);
+        assert_eq!(unarchived.accounts, bundle.accounts);
+        assert_eq!(unarchived.annotations.len(), 1);
+        assert_eq!(unarchived.annotations[0].range(), 1..65);
+
+        assert!(Bundle::unarchive(b"not a bundle").is_err());
+    }
+}
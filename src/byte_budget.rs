@@ -0,0 +1,113 @@
+//! Projects the size of a transaction with signatures, account keys or
+//! instruction data hypothetically added or removed, for quick feasibility
+//! checks against the packet size without editing the transaction.
+
+use {
+    solana_sdk::{short_vec::ShortU16, transaction::VersionedTransaction},
+    std::cmp::Ordering,
+};
+
+/// The adjustable quantities, in the order they are listed.
+pub const LABELS: [&str; 3] = ["Signatures", "Account keys", "Largest instruction data"];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ByteBudget {
+    /// Changes to the number of each quantity of `LABELS`.
+    pub deltas: [i64; 3],
+}
+
+impl ByteBudget {
+    /// The current number of each quantity of `LABELS`.
+    pub fn counts(transaction: &VersionedTransaction) -> [usize; 3] {
+        let message = &transaction.message;
+        let largest_data = message
+            .instructions()
+            .iter()
+            .map(|instruction| instruction.data.len())
+            .max()
+            .unwrap_or(0);
+        [
+            transaction.signatures.len(),
+            message.static_account_keys().len(),
+            largest_data,
+        ]
+    }
+
+    pub fn adjust(&mut self, index: usize, delta: i64) {
+        self.deltas[index] += delta;
+    }
+
+    /// The number of each quantity once adjusted.
+    pub fn adjusted_counts(&self, transaction: &VersionedTransaction) -> [usize; 3] {
+        let counts = Self::counts(transaction);
+        [0, 1, 2].map(|index| {
+            let count = counts[index] as i64 + self.deltas[index];
+            count.clamp(0, u16::MAX as i64) as usize
+        })
+    }
+
+    /// The size of the transaction once adjusted, including the change of
+    /// the compact-u16 length prefixes. Data is added to or removed from the
+    /// largest instruction, which is what a protocol grows first.
+    pub fn projected_size(&self, transaction: &VersionedTransaction) -> usize {
+        let size = bincode::serialized_size(transaction).unwrap() as i64;
+        let counts = Self::counts(transaction);
+        let adjusted = self.adjusted_counts(transaction);
+        let item_sizes = [64, 32, 1];
+        let change = (0..3)
+            .map(|index| {
+                let (before, after) = (counts[index] as i64, adjusted[index] as i64);
+                (after - before) * item_sizes[index] + prefix_len(adjusted[index]) as i64
+                    - prefix_len(counts[index]) as i64
+            })
+            .sum::<i64>();
+        (size + change).max(0) as usize
+    }
+
+    /// A description of the change of the quantity at `index`.
+    pub fn delta_text(&self, index: usize) -> String {
+        let delta = self.deltas[index];
+        match delta.cmp(&0) {
+            Ordering::Greater => format!("+{delta}"),
+            Ordering::Less => delta.to_string(),
+            Ordering::Equal => "±0".to_string(),
+        }
+    }
+}
+
+/// The length of the compact-u16 encoding of `len`.
+fn prefix_len(len: usize) -> usize {
+    bincode::serialized_size(&ShortU16(len as u16)).unwrap() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash, instruction::Instruction, message::Message, pubkey::Pubkey,
+            transaction::Transaction,
+        },
+    };
+
+    #[test]
+    fn projects_sizes_with_length_prefixes() {
+        let payer = Pubkey::new_unique();
+        let instruction = Instruction::new_with_bytes(Pubkey::new_unique(), &[0; 100], vec![]);
+        let message = Message::new_with_blockhash(&[instruction], Some(&payer), &Hash::default());
+        let transaction = VersionedTransaction::from(Transaction::new_unsigned(message));
+        let size = bincode::serialized_size(&transaction).unwrap() as usize;
+
+        let mut budget = ByteBudget::default();
+        assert_eq!(budget.projected_size(&transaction), size);
+        budget.adjust(0, 2);
+        budget.adjust(1, -1);
+        assert_eq!(budget.projected_size(&transaction), size + 2 * 64 - 32);
+
+        // 128 bytes of data need a two byte length prefix.
+        let mut budget = ByteBudget::default();
+        budget.adjust(2, 28);
+        assert_eq!(budget.projected_size(&transaction), size + 28 + 1);
+        assert_eq!(budget.delta_text(2), "+28");
+    }
+}
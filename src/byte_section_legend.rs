@@ -1,19 +1,47 @@
 use {
-    crate::transaction_byte_sections::TransactionByteSection,
+    crate::{
+        address_book::AddressBook,
+        runtime_accounts,
+        settings::{LegendOrder, PubkeyDisplay},
+        transaction_byte_sections::{SectionCategory, TransactionByteSection},
+        verified_builds::BuildStatus,
+    },
     ratatui::{
         buffer::Buffer,
         layout::{Constraint, Direction, Layout, Rect},
         prelude::BlockExt,
-        style::Style,
+        style::{Color, Modifier, Style},
         text::Text,
         widgets::{Block, Widget},
     },
-    std::collections::HashSet,
+    solana_sdk::pubkey::Pubkey,
+    std::collections::HashMap,
 };
 
+/// Narrower columns would truncate most labels, so the legend stays in one.
+const MIN_COLUMN_WIDTH: u16 = 24;
+
 pub struct ByteSectionLegend<'a> {
     sections: &'a [TransactionByteSection],
     block: Option<Block<'a>>,
+    pubkey_display: Option<PubkeyDisplay>,
+    address_book: Option<&'a AddressBook>,
+    pda_labels: Option<&'a HashMap<Pubkey, String>>,
+    hook_roles: Option<&'a HashMap<Pubkey, String>>,
+    domains: Option<&'a HashMap<Pubkey, String>>,
+    build_statuses: Option<&'a HashMap<Pubkey, Result<BuildStatus, String>>>,
+    order: LegendOrder,
+    monochrome: bool,
+}
+
+/// The sections sharing a label.
+struct LabelGroup {
+    label: String,
+    /// The color of the first section.
+    color: Color,
+    category: SectionCategory,
+    num_sections: usize,
+    num_bytes: usize,
 }
 
 impl<'a> ByteSectionLegend<'a> {
@@ -21,6 +49,14 @@ impl<'a> ByteSectionLegend<'a> {
         Self {
             sections: transaction_byte_sections,
             block: None,
+            pubkey_display: None,
+            address_book: None,
+            pda_labels: None,
+            hook_roles: None,
+            domains: None,
+            build_statuses: None,
+            order: LegendOrder::Document,
+            monochrome: false,
         }
     }
 
@@ -35,32 +71,261 @@ impl<'a> ByteSectionLegend<'a> {
         self
     }
 
-    fn render_inner(&self, area: Rect, buf: &mut Buffer) {
-        let mut unique_lines = self
-            .sections
-            .iter()
-            .filter(|section| !section.bytes.is_empty())
-            .filter(|section| section.label.is_some())
-            .map(|section: &TransactionByteSection| &section.label)
-            .collect::<HashSet<_>>();
-        let num_unique_lines = unique_lines.len();
-        let legend_lines = self
+    /// Show the pubkey of account key and lookup table address sections next
+    /// to their label.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn pubkey_display(mut self, pubkey_display: PubkeyDisplay) -> Self {
+        self.pubkey_display = Some(pubkey_display);
+        self
+    }
+
+    /// Show the names of pubkeys in the address book instead of the pubkeys.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn address_book(mut self, address_book: &'a AddressBook) -> Self {
+        self.address_book = Some(address_book);
+        self
+    }
+
+    /// Show what the pubkeys which are PDAs are derived from.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn pda_labels(mut self, pda_labels: &'a HashMap<Pubkey, String>) -> Self {
+        self.pda_labels = Some(pda_labels);
+        self
+    }
+
+    /// Mark the accounts which transfer hooks added to transfers.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn hook_roles(mut self, hook_roles: &'a HashMap<Pubkey, String>) -> Self {
+        self.hook_roles = Some(hook_roles);
+        self
+    }
+
+    /// Show the `.sol` domains of pubkeys next to them.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn domains(mut self, domains: &'a HashMap<Pubkey, String>) -> Self {
+        self.domains = Some(domains);
+        self
+    }
+
+    /// Mark the programs whose builds were checked as verified or not.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn build_statuses(
+        mut self,
+        build_statuses: &'a HashMap<Pubkey, Result<BuildStatus, String>>,
+    ) -> Self {
+        self.build_statuses = Some(build_statuses);
+        self
+    }
+
+    /// Orders the labels, and shows their sizes unless in transaction order.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn order(mut self, order: LegendOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Renders the labels without background colors.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn monochrome(mut self, monochrome: bool) -> Self {
+        self.monochrome = monochrome;
+        self
+    }
+
+    fn label(&self, section: &TransactionByteSection) -> Option<String> {
+        let label = section.label.as_ref()?;
+        match (self.pubkey_display, section.pubkey()) {
+            (Some(pubkey_display), Some(pubkey)) => {
+                let name = self
+                    .address_book
+                    .and_then(|address_book| address_book.name(&pubkey));
+                let pda_label = self
+                    .pda_labels
+                    .and_then(|pda_labels| pda_labels.get(&pubkey));
+                let hook_role = self
+                    .hook_roles
+                    .and_then(|hook_roles| hook_roles.get(&pubkey));
+                let domain = self.domains.and_then(|domains| domains.get(&pubkey));
+                let build_status = self
+                    .build_statuses
+                    .and_then(|build_statuses| build_statuses.get(&pubkey)?.as_ref().ok());
+                let runtime_account = runtime_accounts::classify(&pubkey);
+                let pubkey = match name {
+                    Some(name) => name.to_string(),
+                    None => pubkey_display.format(&pubkey),
+                };
+                let mut label = format!("{label}: {pubkey}");
+                if let Some(runtime_account) = runtime_account {
+                    label = format!("{label} [{}]", runtime_account.tag());
+                }
+                if let Some(hook_role) = hook_role {
+                    label = format!("{label} [{hook_role}]");
+                }
+                if let Some(domain) = domain {
+                    label = format!("{label} ({domain})");
+                }
+                if let Some(pda_label) = pda_label {
+                    label = format!("{label} = {pda_label}");
+                }
+                if let Some(build_status) = build_status {
+                    label = format!("{label} {}", build_status.mark());
+                }
+                Some(label)
+            }
+            _ => Some(label.clone()),
+        }
+    }
+
+    /// The distinct labels in transaction order.
+    fn label_groups(&self) -> Vec<LabelGroup> {
+        let mut groups = Vec::<LabelGroup>::new();
+        let mut indexes = HashMap::<String, usize>::new();
+        let sections = self
             .sections
             .iter()
-            .filter(|section| unique_lines.remove(&section.label))
-            .map(|section| {
-                Text::styled(
-                    section.label.as_ref().unwrap(),
-                    Style::default().bg(section.color),
-                )
-            });
-        let legend_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints((0..num_unique_lines).map(|_| Constraint::Length(1)))
+            .filter(|section| !section.bytes.is_empty());
+        for section in sections {
+            let Some(label) = self.label(section) else {
+                continue;
+            };
+            match indexes.get(&label) {
+                Some(index) => {
+                    groups[*index].num_sections += 1;
+                    groups[*index].num_bytes += section.bytes.len();
+                }
+                None => {
+                    indexes.insert(label.clone(), groups.len());
+                    groups.push(LabelGroup {
+                        label,
+                        color: section.color,
+                        category: section.kind.category(),
+                        num_sections: 1,
+                        num_bytes: section.bytes.len(),
+                    });
+                }
+            }
+        }
+        groups
+    }
+
+    /// The lines of the legend in its order, each with the color of its
+    /// labels, or none for the category headings.
+    fn lines(&self) -> Vec<(String, Option<Color>)> {
+        self.lines_with_sizes(self.order != LegendOrder::Document)
+    }
+
+    fn lines_with_sizes(&self, with_sizes: bool) -> Vec<(String, Option<Color>)> {
+        let mut groups = self.label_groups();
+        let line = |group: &LabelGroup| {
+            let mut line = match group.num_sections {
+                1 => group.label.clone(),
+                num_sections => format!("{} ×{num_sections}", group.label),
+            };
+            if with_sizes {
+                line = format!("{line} · {} B", group.num_bytes);
+            }
+            (line, Some(group.color))
+        };
+        match self.order {
+            LegendOrder::Document => groups.iter().map(line).collect(),
+            LegendOrder::Size => {
+                groups.sort_by_key(|group| std::cmp::Reverse(group.num_bytes));
+                groups.iter().map(line).collect()
+            }
+            LegendOrder::Category => {
+                groups.sort_by_key(|group| group.category);
+                let mut lines = vec![];
+                for (index, group) in groups.iter().enumerate() {
+                    if index == 0 || groups[index - 1].category != group.category {
+                        let subtotal = groups
+                            .iter()
+                            .filter(|other| other.category == group.category)
+                            .map(|other| other.num_bytes)
+                            .sum::<usize>();
+                        lines.push((format!("{} · {subtotal} B", group.category.name()), None));
+                    }
+                    lines.push(line(group));
+                }
+                lines
+            }
+        }
+    }
+
+    /// The legend as plain text, each label after its color and with its
+    /// size, to accompany screenshots of the byte grid.
+    pub fn export_text(&self) -> String {
+        self.lines_with_sizes(true)
+            .into_iter()
+            .map(|(line, color)| match color {
+                Some(color) => format!("{} {line}\n", css_color(color)),
+                None => format!("\n{line}\n"),
+            })
+            .collect::<String>()
+            .trim_start()
+            .to_string()
+    }
+
+    /// The legend as a standalone HTML table, each label next to a swatch of
+    /// its color.
+    pub fn export_html(&self) -> String {
+        let rows = self
+            .lines_with_sizes(true)
+            .into_iter()
+            .map(|(line, color)| {
+                let line = html_escape(&line);
+                match color {
+                    Some(color) => format!(
+                        "<tr><td style=\"background: {}; width: 2em\"></td><td>{line}</td></tr>\n",
+                        css_color(color)
+                    ),
+                    None => format!("<tr><th colspan=\"2\">{line}</th></tr>\n"),
+                }
+            })
+            .collect::<String>();
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Legend</title></head>\n\
+             <body>\n<table>\n{rows}</table>\n</body>\n</html>\n"
+        )
+    }
+
+    /// Lay the labels out in one column, or two if they do not fit, each
+    /// truncated to its column. Labels which still do not fit are counted on
+    /// the last line.
+    fn render_inner(&self, area: Rect, buf: &mut Buffer) {
+        let height = area.height as usize;
+        if height == 0 {
+            return;
+        }
+        let mut lines = self.lines();
+        let num_columns = if lines.len() > height && area.width >= 2 * MIN_COLUMN_WIDTH {
+            2
+        } else {
+            1
+        };
+        let capacity = height * num_columns;
+        if lines.len() > capacity {
+            let num_hidden = lines.len() - (capacity - 1);
+            lines.truncate(capacity - 1);
+            lines.push((format!("… {num_hidden} more"), None));
+        }
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints((0..num_columns).map(|_| Constraint::Ratio(1, num_columns as u32)))
+            .spacing(1)
             .split(area);
 
-        for (line, layout) in legend_lines.zip(legend_layout.iter()) {
-            line.render(*layout, buf);
+        for (index, (label, color)) in lines.into_iter().enumerate() {
+            let column = columns[index / height];
+            let row = Rect {
+                y: column.y + (index % height) as u16,
+                height: 1,
+                ..column
+            };
+            let style = match color {
+                Some(color) if !self.monochrome => Style::default().bg(color),
+                Some(_) => Style::default(),
+                None => Style::default().add_modifier(Modifier::BOLD),
+            };
+            Text::styled(truncate(&label, row.width as usize), style).render(row, buf);
         }
     }
 }
@@ -72,3 +337,169 @@ impl<'a> Widget for &ByteSectionLegend<'a> {
         self.render_inner(inner, buf);
     }
 }
+
+/// Cut a label to `width` characters, ending it with an ellipsis if cut.
+fn truncate(label: &str, width: usize) -> String {
+    if label.chars().count() <= width {
+        return label.to_string();
+    }
+    let mut truncated = label
+        .chars()
+        .take(width.saturating_sub(1))
+        .collect::<String>();
+    truncated.push('…');
+    truncated
+}
+
+/// A color as CSS, the named colors as in the default xterm palette.
+fn css_color(color: Color) -> String {
+    let (r, g, b) = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::Gray => (229, 229, 229),
+        Color::DarkGray => (127, 127, 127),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (92, 92, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        Color::White => (255, 255, 255),
+        // The terminal's own colors are unknown.
+        Color::Reset | Color::Indexed(_) => return "inherit".to_string(),
+    };
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_transaction_tui::transaction_byte_sections::SectionKind};
+
+    fn section(label: &str, kind: SectionKind) -> TransactionByteSection {
+        TransactionByteSection {
+            label: Some(label.to_string()),
+            bytes: vec![0],
+            color: Color::Blue,
+            kind,
+        }
+    }
+
+    fn rendered(legend: &ByteSectionLegend, width: u16, height: u16) -> Vec<String> {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        legend.render(area, &mut buf);
+        (0..height)
+            .map(|y| {
+                let row = (0..width).map(|x| buf[(x, y)].symbol()).collect::<String>();
+                row.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn counts_orders_and_overflows_labels() {
+        let mut sections = vec![section("Recent Blockhash", SectionKind::RecentBlockhash)];
+        sections.extend(
+            (0..5).map(|index| section("Instruction Data", SectionKind::InstructionData(index))),
+        );
+        sections.extend((0..5).map(|index| {
+            section(
+                &format!("Static Account Key ({index})"),
+                SectionKind::StaticAccountKey(index),
+            )
+        }));
+        let legend = ByteSectionLegend::new(&sections);
+
+        assert_eq!(
+            rendered(&legend, 60, 3),
+            [
+                "Recent Blockhash               Static Account Key (1)",
+                "Instruction Data ×5            Static Account Key (2)",
+                "Static Account Key (0)         … 2 more",
+            ]
+        );
+        assert_eq!(rendered(&legend, 12, 2), ["Recent Bloc…", "… 6 more"]);
+
+        let legend = ByteSectionLegend::new(&sections[..4]).order(LegendOrder::Size);
+        assert_eq!(
+            rendered(&legend, 40, 2),
+            ["Instruction Data ×3 · 3 B", "Recent Blockhash · 1 B"]
+        );
+
+        let legend = ByteSectionLegend::new(&sections[..3]).order(LegendOrder::Category);
+        assert_eq!(
+            rendered(&legend, 40, 5),
+            [
+                "Header · 1 B",
+                "Recent Blockhash · 1 B",
+                "Instructions · 2 B",
+                "Instruction Data ×2 · 2 B",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn shows_the_pubkeys_of_account_keys() {
+        let sections = [
+            TransactionByteSection {
+                bytes: vec![1; 32],
+                ..section("Static Account Key (0)", SectionKind::StaticAccountKey(0))
+            },
+            section("Recent Blockhash", SectionKind::RecentBlockhash),
+        ];
+        let legend = ByteSectionLegend::new(&sections).pubkey_display(PubkeyDisplay::Truncated);
+        assert_eq!(
+            rendered(&legend, 40, 2),
+            ["Static Account Key (0): 4vJ9…kLKi", "Recent Blockhash"]
+        );
+    }
+
+    #[test]
+    fn leaves_out_colors_in_monochrome() {
+        let sections = [section("Recent Blockhash", SectionKind::RecentBlockhash)];
+        let area = Rect::new(0, 0, 20, 1);
+        for (monochrome, bg) in [(false, Color::Blue), (true, Color::Reset)] {
+            let mut buf = Buffer::empty(area);
+            ByteSectionLegend::new(&sections)
+                .monochrome(monochrome)
+                .render(area, &mut buf);
+            assert_eq!(buf[(0, 0)].bg, bg);
+        }
+    }
+
+    #[test]
+    fn exports_labels_with_colors_and_sizes() {
+        let sections = [
+            section("Recent Blockhash", SectionKind::RecentBlockhash),
+            TransactionByteSection {
+                color: Color::Rgb(255, 228, 196),
+                ..section("Data <&>", SectionKind::InstructionData(0))
+            },
+        ];
+        let legend = ByteSectionLegend::new(&sections).order(LegendOrder::Category);
+        assert_eq!(
+            legend.export_text(),
+            "Header · 1 B\n#0000ee Recent Blockhash · 1 B\n\n\
+             Instructions · 1 B\n#ffe4c4 Data <&> · 1 B\n"
+        );
+        let html = legend.export_html();
+        assert!(html.contains("<tr><th colspan=\"2\">Header · 1 B</th></tr>"));
+        assert!(html.contains(
+            "<tr><td style=\"background: #ffe4c4; width: 2em\"></td><td>Data &lt;&amp;&gt; · 1 B</td></tr>"
+        ));
+    }
+}
@@ -0,0 +1,135 @@
+//! Tagging each byte of a transaction with the field it belongs to, for
+//! fuzzing corpora and differential tests of other serializers. The tags are
+//! a byte per transaction byte, numbered as in [`TAG_NAMES`], so that the
+//! raw bytes and their tags can be stored side by side.
+
+use {
+    crate::transaction_byte_sections::{SectionKind, TransactionByteSection},
+    serde_json::json,
+};
+
+/// The name of each tag, by its number. Tags are only ever appended, so the
+/// numbers of exported tags stay valid.
+pub const TAG_NAMES: [&str; 21] = [
+    "signature_count",
+    "signature",
+    "version",
+    "num_required_signatures",
+    "num_readonly_signed_accounts",
+    "num_readonly_unsigned_accounts",
+    "account_keys_count",
+    "account_key",
+    "recent_blockhash",
+    "instructions_count",
+    "program_id_index",
+    "instruction_accounts_count",
+    "instruction_accounts",
+    "instruction_data_length",
+    "instruction_data",
+    "address_table_lookups_count",
+    "lookup_table_address",
+    "lookup_write_count",
+    "lookup_write_indexes",
+    "lookup_read_count",
+    "lookup_read_indexes",
+];
+
+/// The tag of a section's bytes and the index of the signature, key,
+/// instruction or lookup it belongs to, if any.
+pub fn tag(kind: &SectionKind) -> (u8, Option<usize>) {
+    match *kind {
+        SectionKind::SignatureCount => (0, None),
+        SectionKind::Signature(index) => (1, Some(index)),
+        SectionKind::VersionByte => (2, None),
+        SectionKind::NumRequiredSignatures => (3, None),
+        SectionKind::NumReadonlySignedAccounts => (4, None),
+        SectionKind::NumReadonlyUnsignedAccounts => (5, None),
+        SectionKind::StaticAccountKeysCount => (6, None),
+        SectionKind::StaticAccountKey(index) => (7, Some(index)),
+        SectionKind::RecentBlockhash => (8, None),
+        SectionKind::InstructionsCount => (9, None),
+        SectionKind::InstructionProgramIdIndex(index) => (10, Some(index)),
+        SectionKind::InstructionAccountsCount(index) => (11, Some(index)),
+        SectionKind::InstructionAccounts(index) => (12, Some(index)),
+        SectionKind::InstructionDataLength(index) => (13, Some(index)),
+        SectionKind::InstructionData(index) => (14, Some(index)),
+        SectionKind::AddressTableLookupsCount => (15, None),
+        SectionKind::AddressTableLookupAddress(index) => (16, Some(index)),
+        SectionKind::AddressTableLookupWriteCount(index) => (17, Some(index)),
+        SectionKind::AddressTableLookupWriteSet(index) => (18, Some(index)),
+        SectionKind::AddressTableLookupReadCount(index) => (19, Some(index)),
+        SectionKind::AddressTableLookupReadSet(index) => (20, Some(index)),
+    }
+}
+
+/// The tag of each byte of the sections.
+pub fn tags(sections: &[TransactionByteSection]) -> Vec<u8> {
+    sections
+        .iter()
+        .flat_map(|section| vec![tag(&section.kind).0; section.bytes.len()])
+        .collect()
+}
+
+/// The bytes of the sections with the tag and index of each, along with the
+/// tag names, as JSON.
+pub fn export_json(sections: &[TransactionByteSection]) -> String {
+    let bytes = sections
+        .iter()
+        .flat_map(|section| section.bytes.iter().copied())
+        .collect::<Vec<_>>();
+    let indexes = sections
+        .iter()
+        .flat_map(|section| vec![tag(&section.kind).1; section.bytes.len()])
+        .collect::<Vec<_>>();
+    let export = json!({
+        "tag_names": TAG_NAMES,
+        "bytes": bytes,
+        "tags": tags(sections),
+        "indexes": indexes,
+    });
+    export.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::transaction_byte_sections::get_transaction_byte_sections,
+        serde_json::Value,
+        solana_sdk::{
+            hash::Hash,
+            message::{Message, VersionedMessage},
+            pubkey::Pubkey,
+            system_instruction,
+            transaction::VersionedTransaction,
+        },
+    };
+
+    #[test]
+    fn tags_every_byte_with_its_field() {
+        let (from, to) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instruction = system_instruction::transfer(&from, &to, 1);
+        let message = Message::new_with_blockhash(&[instruction], Some(&from), &Hash::default());
+        let transaction = VersionedTransaction {
+            signatures: vec![Default::default()],
+            message: VersionedMessage::Legacy(message),
+        };
+        let mut sections = vec![];
+        get_transaction_byte_sections(&transaction, &mut sections);
+        let bytes = bincode::serialize(&transaction).unwrap();
+
+        let tags = tags(&sections);
+        assert_eq!(tags.len(), bytes.len());
+        let name = |offset: usize| TAG_NAMES[tags[offset] as usize];
+        assert_eq!(name(0), "signature_count");
+        assert_eq!(name(64), "signature");
+        assert_eq!(name(65), "num_required_signatures");
+        assert_eq!(name(bytes.len() - 1), "instruction_data");
+
+        let export = serde_json::from_str::<Value>(&export_json(&sections)).unwrap();
+        assert_eq!(export["bytes"].as_array().unwrap().len(), bytes.len());
+        assert_eq!(export["indexes"][1], 0);
+        assert_eq!(export["indexes"][0], Value::Null);
+        assert_eq!(export["tag_names"][14], "instruction_data");
+    }
+}
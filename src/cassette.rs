@@ -0,0 +1,287 @@
+//! Recording of endpoint traffic into cassette files and replaying it, so a
+//! session can be reproduced exactly from the responses it saw. A cassette
+//! holds one JSON object per line with a request, the endpoint it was sent
+//! to and its response. Every RPC request goes through `RpcApi::send`, so
+//! all of them are recorded, from fetches to simulations and health checks.
+
+use {
+    crate::rpc::{Request, RpcApi},
+    serde::{Deserialize, Serialize},
+    serde_json::Value,
+    solana_client::rpc_client::RpcClient,
+    std::{
+        fs::File,
+        io::{self, Write},
+        path::PathBuf,
+        sync::{Arc, Mutex},
+    },
+};
+
+#[derive(Serialize, Deserialize)]
+struct Interaction {
+    endpoint: String,
+    method: String,
+    params: Value,
+    response: Result<Value, String>,
+}
+
+/// Appends every exchange with the endpoint to a cassette file.
+pub struct Recorder {
+    pub path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl Recorder {
+    pub fn create(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let file = File::create(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    fn record(&self, endpoint: &str, request: &Request, response: &Result<Value, String>) {
+        let interaction = Interaction {
+            endpoint: endpoint.to_string(),
+            method: request.method().to_string(),
+            params: request.params(),
+            response: response.clone(),
+        };
+        let Ok(line) = serde_json::to_string(&interaction) else {
+            return;
+        };
+        // Failing to record should not interrupt the session.
+        let _ = writeln!(self.file.lock().unwrap(), "{line}");
+    }
+}
+
+/// An endpoint, with each exchange recorded.
+pub struct Recording {
+    pub endpoint: String,
+    pub client: RpcClient,
+    pub recorder: Arc<Recorder>,
+}
+
+impl RpcApi for Recording {
+    fn send(&self, request: Request) -> Result<Value, String> {
+        let response = RpcApi::send(&self.client, request.clone());
+        self.recorder.record(&self.endpoint, &request, &response);
+        response
+    }
+}
+
+/// Answers requests with the responses of a cassette.
+pub struct Replayer {
+    pub path: PathBuf,
+    interactions: Vec<Interaction>,
+    replayed: Mutex<Vec<bool>>,
+}
+
+impl Replayer {
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let cassette = std::fs::read_to_string(&path)
+            .map_err(|err| format!("Failed to read {}: {err}", path.display()))?;
+        let interactions = cassette
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|err| {
+                    format!("Invalid line {} of {}: {err}", index + 1, path.display())
+                })
+            })
+            .collect::<Result<Vec<Interaction>, _>>()?;
+        let replayed = Mutex::new(vec![false; interactions.len()]);
+        Ok(Self {
+            path,
+            interactions,
+            replayed,
+        })
+    }
+
+    /// Repeated requests are answered in recorded order, and with the last
+    /// recorded response once all were replayed. The params include the
+    /// config of a request, so a request recorded with other options, such
+    /// as another commitment or encoding, is not answered.
+    fn replay(&self, endpoint: &str, request: &Request) -> Result<Value, String> {
+        let (method, params) = (request.method(), request.params());
+        let mut replayed = self.replayed.lock().unwrap();
+        let matching = self
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, interaction)| {
+                interaction.endpoint == endpoint
+                    && interaction.method == method
+                    && interaction.params == params
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        let index = matching
+            .iter()
+            .find(|index| !replayed[**index])
+            .or(matching.last())
+            .copied()
+            .ok_or_else(|| self.missing(endpoint, method, &params))?;
+        replayed[index] = true;
+        self.interactions[index].response.clone()
+    }
+
+    /// Names the options a request was recorded with, when it only differs
+    /// from the replayed one in those. The config is the last param of each
+    /// request that takes one.
+    fn missing(&self, endpoint: &str, method: &str, params: &Value) -> String {
+        let path = self.path.display();
+        let subject = |params: &Value| {
+            params
+                .as_array()
+                .map(|params| params[..params.len().saturating_sub(1)].to_vec())
+        };
+        let recorded = self.interactions.iter().find(|interaction| {
+            interaction.endpoint == endpoint
+                && interaction.method == method
+                && subject(&interaction.params) == subject(params)
+        });
+        match recorded {
+            Some(recorded) => format!(
+                "{method} was recorded in {path} with params {}, not {params}",
+                recorded.params
+            ),
+            None => format!("No recorded {method} response of {endpoint} in {path}"),
+        }
+    }
+}
+
+/// The recorded exchanges with an endpoint, so that requests sent to several
+/// endpoints, such as broadcasts, are answered as each endpoint answered.
+pub struct Replaying {
+    pub endpoint: String,
+    pub replayer: Arc<Replayer>,
+}
+
+impl RpcApi for Replaying {
+    fn send(&self, request: Request) -> Result<Value, String> {
+        self.replayer.replay(&self.endpoint, &request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::rpc::{FetchEncoding, FetchOptions},
+        serde_json::json,
+        solana_client::rpc_config::RpcTransactionConfig,
+        solana_sdk::{commitment_config::CommitmentConfig, signature::Signature},
+    };
+
+    const MAINNET: &str = "https://api.mainnet-beta.solana.com";
+    const DEVNET: &str = "https://api.devnet.solana.com";
+
+    #[test]
+    fn replays_recorded_responses_in_order() {
+        let path = std::env::temp_dir().join(format!("cassette-{}.jsonl", std::process::id()));
+        let recorder = Recorder::create(&path).unwrap();
+        let confirmed = CommitmentConfig::confirmed();
+        let request = Request::GetSlot {
+            commitment: confirmed,
+        };
+        recorder.record(MAINNET, &request, &Err("timed out".to_string()));
+        recorder.record(MAINNET, &request, &Ok(Value::from(42)));
+        recorder.record(DEVNET, &request, &Ok(Value::from(7)));
+
+        let replayer = Arc::new(Replayer::load(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+        let replaying = |endpoint: &str| Replaying {
+            endpoint: endpoint.to_string(),
+            replayer: replayer.clone(),
+        };
+        let (mainnet, devnet) = (replaying(MAINNET), replaying(DEVNET));
+        assert_eq!(mainnet.get_slot(confirmed), Err("timed out".to_string()));
+        assert_eq!(devnet.get_slot(confirmed), Ok(7));
+        assert_eq!(mainnet.get_slot(confirmed), Ok(42));
+        assert_eq!(mainnet.get_slot(confirmed), Ok(42));
+        assert_eq!(
+            mainnet.get_slot(CommitmentConfig::finalized()),
+            Err(format!(
+                "getSlot was recorded in {} with params \
+                 [{{\"commitment\":\"confirmed\"}}], not [{{\"commitment\":\"finalized\"}}]",
+                path.display()
+            ))
+        );
+        assert!(replaying("http://localhost:8899")
+            .get_slot(confirmed)
+            .is_err());
+    }
+
+    #[test]
+    fn replays_requests_of_every_method() {
+        let path = std::env::temp_dir().join(format!("cassette-all-{}.jsonl", std::process::id()));
+        let recorder = Recorder::create(&path).unwrap();
+        let signature = Signature::new_unique();
+        let statuses = json!({"context": {"slot": 43}, "value": [null]});
+        let request = Request::GetSignatureStatuses {
+            signatures: vec![signature],
+        };
+        recorder.record(MAINNET, &request, &Ok(statuses));
+        let request = Request::Custom {
+            method: "getBalance".to_string(),
+            params: json!([signature.to_string()]),
+        };
+        recorder.record(MAINNET, &request, &Ok(json!({"value": 5})));
+
+        let replaying = Replaying {
+            endpoint: MAINNET.to_string(),
+            replayer: Arc::new(Replayer::load(&path).unwrap()),
+        };
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            replaying.get_signature_statuses(&[signature]),
+            Ok(vec![None])
+        );
+        assert_eq!(replaying.send(request), Ok(json!({"value": 5})));
+    }
+
+    #[test]
+    fn refuses_requests_recorded_with_other_options() {
+        let path =
+            std::env::temp_dir().join(format!("cassette-options-{}.jsonl", std::process::id()));
+        let recorder = Recorder::create(&path).unwrap();
+        let signature = Signature::new_unique();
+        let options = FetchOptions::default();
+        let request = Request::GetTransaction {
+            signature,
+            config: RpcTransactionConfig {
+                encoding: Some(options.encoding.encoding()),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: options.max_supported_transaction_version(),
+            },
+        };
+        recorder.record(MAINNET, &request, &Ok(Value::Null));
+
+        let replaying = Replaying {
+            endpoint: MAINNET.to_string(),
+            replayer: Arc::new(Replayer::load(&path).unwrap()),
+        };
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            replaying.get_transaction(&signature, &options),
+            Err(format!("Transaction {signature} not found"))
+        );
+        let base64 = FetchOptions {
+            encoding: FetchEncoding::Base64,
+            ..options
+        };
+        let err = replaying.get_transaction(&signature, &base64).unwrap_err();
+        assert!(err.starts_with("getTransaction was recorded in"), "{err}");
+        assert!(err.contains(r#""encoding":"binary""#), "{err}");
+        assert!(err.contains(r#""encoding":"base64""#), "{err}");
+        let other = Signature::new_unique();
+        assert!(replaying
+            .get_transaction(&other, &options)
+            .unwrap_err()
+            .starts_with("No recorded getTransaction response"));
+    }
+}
@@ -0,0 +1,874 @@
+//! Messages and commands of the app's update loop. Terminal events and the
+//! results of commands become messages for the app to update on, and side
+//! effects on the network, the file system and devices are requested as
+//! commands which run on background threads. Only loading the state at
+//! startup and writing metrics at exit touch files outside of commands.
+
+use {
+    crate::{
+        address_book,
+        alt_planner::{self, AltPlan},
+        broadcast::{self, Outcome},
+        commitment_timeline::{self, CommitmentPoll},
+        decoders::{
+            multisig::{self, StoredTransaction},
+            token_2022,
+        },
+        derivation,
+        endpoint_health::{self, HealthReport},
+        follow::{self, Latest},
+        keypairs, landing,
+        leaders::Leaders,
+        ledger,
+        ownership::Ownership,
+        preflight::Preflight,
+        related::Related,
+        rpc::{FetchOptions, RpcApi},
+        rpc_console,
+        sandwich::MevContext,
+        schema::{ProgramPdas, Schema},
+        security_txt::SecurityTxt,
+        sensitivity::{self, SensitivityMap},
+        simulation::Simulation,
+        snapshots::{Snapshot, Snapshots},
+        sns,
+        storage::FileChange,
+        stream_exports::StreamExports,
+        verified_builds::{self, BuildStatus},
+        watch::{self, Batch, StreamedTransaction},
+    },
+    ratatui::crossterm::event::Event,
+    serde_json::Value,
+    solana_client::nonce_utils,
+    solana_sdk::{
+        account::Account,
+        commitment_config::CommitmentConfig,
+        derivation_path::DerivationPath,
+        hash::Hash,
+        instruction::CompiledInstruction,
+        message::VersionedMessage,
+        pubkey::Pubkey,
+        signature::{Keypair, Signature},
+        transaction::VersionedTransaction,
+    },
+    solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, TransactionStatus},
+    std::{
+        collections::{BTreeMap, HashMap},
+        path::PathBuf,
+        sync::{
+            mpsc::{self, Receiver, Sender},
+            Arc,
+        },
+        thread::{self, JoinHandle},
+        time::{Duration, Instant, SystemTime},
+    },
+};
+
+/// Something the app updates its state on.
+pub enum Message {
+    Event(Event),
+    TransactionFetched {
+        signature: Signature,
+        result: Result<Box<EncodedConfirmedTransactionWithStatusMeta>, String>,
+        elapsed: Duration,
+    },
+    SlotEntriesFetched {
+        slot: u64,
+        result: Result<Vec<u8>, String>,
+    },
+    StoredTransactionFetched {
+        signature: Signature,
+        title: String,
+        result: Result<VersionedTransaction, String>,
+        accounts: BTreeMap<Pubkey, Account>,
+        elapsed: Duration,
+    },
+    LandingPolled {
+        signature: Signature,
+        result: Result<Option<TransactionStatus>, String>,
+    },
+    LatestPolled {
+        address: Pubkey,
+        result: Result<Option<Latest>, String>,
+    },
+    CommitmentPolled {
+        signature: Signature,
+        poll: CommitmentPoll,
+    },
+    EndpointChecked {
+        generation: u64,
+        report: HealthReport,
+    },
+    FileWritten {
+        path: String,
+        result: Result<(), String>,
+    },
+    FileChanged {
+        name: &'static str,
+        result: Result<(), String>,
+    },
+    Broadcasted {
+        signature: Signature,
+        endpoint: String,
+        outcome: Outcome,
+    },
+    LeadersFetched {
+        result: Result<Leaders, String>,
+    },
+    RelatedFetched {
+        result: Result<Related, String>,
+    },
+    MevContextFetched {
+        signature: Signature,
+        result: Result<MevContext, String>,
+    },
+    WatchPolled {
+        generation: u64,
+        result: Result<Batch, String>,
+        elapsed: Duration,
+    },
+    StreamMatchesExported {
+        result: Result<(), String>,
+    },
+    PreflightSimulated {
+        signature: Signature,
+        result: Result<Preflight, String>,
+    },
+    Simulated {
+        result: Result<Simulation, String>,
+    },
+    BlockhashFetched {
+        result: Result<Hash, String>,
+    },
+    LookupTablePlanned {
+        signature: Signature,
+        result: Result<Box<AltPlan>, String>,
+    },
+    LookupTableSubmitted {
+        lookup_table: Pubkey,
+        result: Result<(), String>,
+        elapsed: Duration,
+    },
+    RpcResponded {
+        result: Result<Value, String>,
+    },
+    PdasLabeled {
+        signature: Signature,
+        labels: HashMap<Pubkey, String>,
+        hook_roles: HashMap<Pubkey, String>,
+    },
+    OwnershipResolved {
+        signature: Signature,
+        result: Result<Ownership, String>,
+    },
+    BuildsChecked {
+        statuses: Vec<(Pubkey, Result<BuildStatus, String>)>,
+    },
+    SecurityTxtsFetched {
+        results: Vec<(Pubkey, Result<Option<SecurityTxt>, String>)>,
+    },
+    DomainsResolved {
+        signature: Signature,
+        result: Result<HashMap<Pubkey, String>, String>,
+    },
+    DomainResolved {
+        domain: String,
+        result: Result<Pubkey, String>,
+    },
+    EntriesFileRead {
+        path: String,
+        result: Result<Vec<u8>, String>,
+    },
+    SnapshotLoaded {
+        signature: Signature,
+        result: Option<Result<Snapshot, String>>,
+    },
+    SnapshotsLoaded {
+        snapshots: Vec<(Signature, Snapshot)>,
+    },
+    SchemaLoaded {
+        index: usize,
+        result: Result<Schema, String>,
+    },
+    KeypairFileRead {
+        result: Result<(String, Keypair), String>,
+    },
+    KeypairDirRead {
+        dir: PathBuf,
+        result: Result<Vec<(Pubkey, String)>, String>,
+    },
+    SensitivityMapped {
+        signature: Signature,
+        sensitivity_map: SensitivityMap,
+        elapsed: Duration,
+    },
+    LedgerConnected {
+        derivation_path: DerivationPath,
+        result: Result<Pubkey, String>,
+    },
+    LedgerSigned {
+        pubkey: Pubkey,
+        result: Result<Signature, String>,
+    },
+}
+
+/// A side effect requested by an update, reporting back with a message.
+pub enum Command {
+    FetchTransaction {
+        rpc: Arc<dyn RpcApi>,
+        signature: Signature,
+        options: FetchOptions,
+    },
+    FetchSlotEntries {
+        rpc: Arc<dyn RpcApi>,
+        slot: u64,
+    },
+    /// Fetch the accounts holding a transaction stored by a multisig,
+    /// reusing `accounts` fetched before for the transaction `signature`.
+    FetchStoredTransaction {
+        rpc: Arc<dyn RpcApi>,
+        signature: Signature,
+        title: String,
+        stored: StoredTransaction,
+        accounts: BTreeMap<Pubkey, Account>,
+    },
+    /// Poll the status of a signature waiting to land, after `delay`.
+    PollLanding {
+        rpc: Arc<dyn RpcApi>,
+        signature: Signature,
+        delay: Duration,
+    },
+    /// Poll the newest transaction of a followed address, after `delay`.
+    PollLatest {
+        rpc: Arc<dyn RpcApi>,
+        address: Pubkey,
+        delay: Duration,
+    },
+    PollCommitment {
+        rpc: Arc<dyn RpcApi>,
+        signature: Signature,
+        delay: Duration,
+    },
+    /// Check the health of an endpoint after `delay`, comparing its slot
+    /// with that of `reference`.
+    CheckEndpointHealth {
+        rpc: Arc<dyn RpcApi>,
+        reference: Option<Arc<dyn RpcApi>>,
+        generation: u64,
+        delay: Duration,
+    },
+    WriteFile {
+        path: String,
+        contents: Vec<u8>,
+    },
+    /// Change a file persisting the `name`d state. Changes are applied one
+    /// at a time in the order requested, so the latest state is kept.
+    ChangeFile {
+        name: &'static str,
+        change: FileChange,
+    },
+    Broadcast {
+        rpc: Arc<dyn RpcApi>,
+        endpoint: String,
+        transaction: Box<VersionedTransaction>,
+        started: Instant,
+    },
+    FetchLeaders {
+        rpc: Arc<dyn RpcApi>,
+        landed_slot: Option<u64>,
+    },
+    /// Fetch the transactions touching `address` within `window` slots of
+    /// `slot`.
+    FetchRelated {
+        rpc: Arc<dyn RpcApi>,
+        address: Pubkey,
+        slot: u64,
+        window: u64,
+    },
+    /// Fetch the block of `slot` to find the neighbors of `signature`.
+    FetchMevContext {
+        rpc: Arc<dyn RpcApi>,
+        slot: u64,
+        signature: Signature,
+    },
+    /// Fetch the blocks after `after_slot`, or the latest if `None`, once
+    /// `delay` has passed.
+    PollWatch {
+        rpc: Arc<dyn RpcApi>,
+        generation: u64,
+        after_slot: Option<u64>,
+        delay: Duration,
+    },
+    /// Save streamed transactions matching the watchlist, keeping the
+    /// latest `retention` of each pattern.
+    ExportStreamMatches {
+        exports: StreamExports,
+        matches: Vec<StreamedTransaction>,
+        now: SystemTime,
+        retention: usize,
+    },
+    Simulate {
+        rpc: Arc<dyn RpcApi>,
+        transaction: Box<VersionedTransaction>,
+    },
+    /// Fetch the latest blockhash, or the blockhash stored in the nonce
+    /// account of a transaction using a durable nonce.
+    FetchBlockhash {
+        rpc: Arc<dyn RpcApi>,
+        nonce_account: Option<Pubkey>,
+    },
+    /// Simulate a failed transaction to compare with its on-chain outcome.
+    SimulatePreflight {
+        rpc: Arc<dyn RpcApi>,
+        signature: Signature,
+        transaction: Box<VersionedTransaction>,
+    },
+    /// Plan a lookup table for the accounts of `signature`'s transaction.
+    PlanLookupTable {
+        rpc: Arc<dyn RpcApi>,
+        signature: Signature,
+        transaction: Box<VersionedTransaction>,
+    },
+    /// Sign and submit the setup messages of a lookup table plan in order.
+    SubmitLookupTable {
+        rpc: Arc<dyn RpcApi>,
+        lookup_table: Pubkey,
+        setup: Vec<VersionedMessage>,
+        authority: Box<Keypair>,
+        started: Instant,
+    },
+    RpcCall {
+        rpc: Arc<dyn RpcApi>,
+        method: String,
+        params: Value,
+    },
+    LabelPdas {
+        signature: Signature,
+        account_keys: Vec<Pubkey>,
+        instructions: Vec<CompiledInstruction>,
+        programs: Vec<ProgramPdas>,
+    },
+    ResolveOwnership {
+        signature: Signature,
+        rpc: Arc<dyn RpcApi>,
+        account_keys: Vec<Pubkey>,
+    },
+    CheckVerifiedBuilds {
+        program_ids: Vec<Pubkey>,
+    },
+    FetchSecurityTxts {
+        rpc: Arc<dyn RpcApi>,
+        program_ids: Vec<Pubkey>,
+    },
+    ResolveDomains {
+        signature: Signature,
+        rpc: Arc<dyn RpcApi>,
+        account_keys: Vec<Pubkey>,
+    },
+    ResolveDomain {
+        rpc: Arc<dyn RpcApi>,
+        domain: String,
+    },
+    ReadEntriesFile {
+        path: String,
+    },
+    LoadSnapshot {
+        snapshots: Snapshots,
+        signature: Signature,
+    },
+    /// Load the intact snapshots of `signatures`, skipping the others.
+    LoadSnapshots {
+        snapshots: Snapshots,
+        signatures: Vec<Signature>,
+    },
+    /// Load a schema for the template of instruction `index`.
+    LoadSchema {
+        path: String,
+        index: usize,
+    },
+    ReadKeypairFile {
+        path: String,
+    },
+    ReadKeypairDir {
+        dir: PathBuf,
+    },
+    MapSensitivity {
+        signature: Signature,
+        transaction: Box<VersionedTransaction>,
+    },
+    /// Connect to a Ledger device for the pubkey at the derivation path.
+    ConnectLedger {
+        derivation_path: DerivationPath,
+    },
+    /// Sign a message with a Ledger device, waiting for its confirmation.
+    SignWithLedger {
+        derivation_path: DerivationPath,
+        pubkey: Pubkey,
+        message: Vec<u8>,
+    },
+}
+
+impl Command {
+    /// Perform the command, blocking until it is done.
+    pub fn execute(self) -> Message {
+        match self {
+            Self::FetchTransaction {
+                rpc,
+                signature,
+                options,
+            } => {
+                let started = Instant::now();
+                let result = rpc
+                    .get_transaction(&signature, &options)
+                    .map(|mut transaction| {
+                        options.apply(&mut transaction);
+                        Box::new(transaction)
+                    });
+                Message::TransactionFetched {
+                    signature,
+                    result,
+                    elapsed: started.elapsed(),
+                }
+            }
+            Self::FetchSlotEntries { rpc, slot } => Message::SlotEntriesFetched {
+                slot,
+                result: rpc.get_slot_entries(slot),
+            },
+            Self::FetchStoredTransaction {
+                rpc,
+                signature,
+                title,
+                stored,
+                mut accounts,
+            } => {
+                let started = Instant::now();
+                let result = multisig::fetch(
+                    &mut |pubkeys| get_cached_accounts(rpc.as_ref(), &mut accounts, pubkeys),
+                    &stored,
+                );
+                Message::StoredTransactionFetched {
+                    signature,
+                    title,
+                    result,
+                    accounts,
+                    elapsed: started.elapsed(),
+                }
+            }
+            Self::PollLanding {
+                rpc,
+                signature,
+                delay,
+            } => {
+                thread::sleep(delay);
+                Message::LandingPolled {
+                    signature,
+                    result: landing::poll(rpc.as_ref(), &signature),
+                }
+            }
+            Self::PollLatest {
+                rpc,
+                address,
+                delay,
+            } => {
+                thread::sleep(delay);
+                Message::LatestPolled {
+                    address,
+                    result: follow::poll(rpc.as_ref(), &address),
+                }
+            }
+            Self::PollCommitment {
+                rpc,
+                signature,
+                delay,
+            } => {
+                thread::sleep(delay);
+                Message::CommitmentPolled {
+                    signature,
+                    poll: commitment_timeline::poll(rpc.as_ref(), &signature),
+                }
+            }
+            Self::CheckEndpointHealth {
+                rpc,
+                reference,
+                generation,
+                delay,
+            } => {
+                thread::sleep(delay);
+                Message::EndpointChecked {
+                    generation,
+                    report: endpoint_health::check(rpc.as_ref(), reference.as_deref()),
+                }
+            }
+            Self::WriteFile { path, contents } => {
+                let result = std::fs::write(&path, contents).map_err(|err| err.to_string());
+                Message::FileWritten { path, result }
+            }
+            Self::ChangeFile { name, change } => Message::FileChanged {
+                name,
+                result: change.apply().map_err(|err| err.to_string()),
+            },
+            Self::Broadcast {
+                rpc,
+                endpoint,
+                transaction,
+                started,
+            } => Message::Broadcasted {
+                signature: transaction.signatures[0],
+                outcome: broadcast::send(rpc.as_ref(), &transaction, started),
+                endpoint,
+            },
+            Self::FetchLeaders { rpc, landed_slot } => Message::LeadersFetched {
+                result: Leaders::fetch(rpc.as_ref(), landed_slot),
+            },
+            Self::FetchRelated {
+                rpc,
+                address,
+                slot,
+                window,
+            } => Message::RelatedFetched {
+                result: Related::fetch(rpc.as_ref(), address, slot, window),
+            },
+            Self::FetchMevContext {
+                rpc,
+                slot,
+                signature,
+            } => Message::MevContextFetched {
+                signature,
+                result: MevContext::fetch(rpc.as_ref(), slot, signature),
+            },
+            Self::PollWatch {
+                rpc,
+                generation,
+                after_slot,
+                delay,
+            } => {
+                thread::sleep(delay);
+                let started = Instant::now();
+                Message::WatchPolled {
+                    generation,
+                    result: watch::poll(rpc.as_ref(), after_slot),
+                    elapsed: started.elapsed(),
+                }
+            }
+            Self::ExportStreamMatches {
+                exports,
+                matches,
+                now,
+                retention,
+            } => Message::StreamMatchesExported {
+                result: matches
+                    .iter()
+                    .try_for_each(|streamed| exports.save(streamed, now, retention))
+                    .map_err(|err| err.to_string()),
+            },
+            Self::Simulate { rpc, transaction } => Message::Simulated {
+                result: Simulation::run(rpc.as_ref(), &transaction),
+            },
+            Self::FetchBlockhash { rpc, nonce_account } => Message::BlockhashFetched {
+                result: fetch_blockhash(rpc.as_ref(), nonce_account.as_ref()),
+            },
+            Self::SimulatePreflight {
+                rpc,
+                signature,
+                transaction,
+            } => Message::PreflightSimulated {
+                signature,
+                result: Preflight::run(rpc.as_ref(), &transaction),
+            },
+            Self::PlanLookupTable {
+                rpc,
+                signature,
+                transaction,
+            } => Message::LookupTablePlanned {
+                signature,
+                result: alt_planner::fetch_plan(rpc.as_ref(), &transaction).map(Box::new),
+            },
+            Self::SubmitLookupTable {
+                rpc,
+                lookup_table,
+                setup,
+                authority,
+                started,
+            } => Message::LookupTableSubmitted {
+                lookup_table,
+                result: alt_planner::submit(rpc.as_ref(), setup, &authority),
+                elapsed: started.elapsed(),
+            },
+            Self::RpcCall {
+                rpc,
+                method,
+                params,
+            } => Message::RpcResponded {
+                result: rpc_console::call(rpc.as_ref(), method, params),
+            },
+            Self::LabelPdas {
+                signature,
+                account_keys,
+                instructions,
+                programs,
+            } => Message::PdasLabeled {
+                signature,
+                labels: derivation::label_pdas(&account_keys, &instructions, &programs),
+                hook_roles: token_2022::transfer_hook_roles(&account_keys, &instructions),
+            },
+            Self::ResolveOwnership {
+                signature,
+                rpc,
+                account_keys,
+            } => Message::OwnershipResolved {
+                signature,
+                result: Ownership::resolve(rpc.as_ref(), &account_keys),
+            },
+            Self::CheckVerifiedBuilds { program_ids } => Message::BuildsChecked {
+                statuses: verified_builds::check(&program_ids),
+            },
+            Self::FetchSecurityTxts { rpc, program_ids } => Message::SecurityTxtsFetched {
+                results: program_ids
+                    .into_iter()
+                    .map(|program_id| (program_id, SecurityTxt::fetch(rpc.as_ref(), &program_id)))
+                    .collect(),
+            },
+            Self::ResolveDomains {
+                signature,
+                rpc,
+                account_keys,
+            } => Message::DomainsResolved {
+                signature,
+                result: sns::reverse_lookup(rpc.as_ref(), &account_keys),
+            },
+            Self::ResolveDomain { rpc, domain } => Message::DomainResolved {
+                result: sns::resolve(rpc.as_ref(), &domain),
+                domain,
+            },
+            Self::ReadEntriesFile { path } => Message::EntriesFileRead {
+                result: std::fs::read(&path).map_err(|err| format!("Failed to read {path}: {err}")),
+                path,
+            },
+            Self::LoadSnapshot {
+                snapshots,
+                signature,
+            } => Message::SnapshotLoaded {
+                signature,
+                result: snapshots.load(&signature),
+            },
+            Self::LoadSnapshots {
+                snapshots,
+                signatures,
+            } => Message::SnapshotsLoaded {
+                snapshots: signatures
+                    .into_iter()
+                    .filter_map(|signature| Some((signature, snapshots.load(&signature)?.ok()?)))
+                    .collect(),
+            },
+            Self::LoadSchema { path, index } => Message::SchemaLoaded {
+                index,
+                result: Schema::load(&path),
+            },
+            Self::ReadKeypairFile { path } => Message::KeypairFileRead {
+                result: keypairs::read_file(&path),
+            },
+            Self::ReadKeypairDir { dir } => Message::KeypairDirRead {
+                result: address_book::read_keypair_dir(&dir),
+                dir,
+            },
+            Self::MapSensitivity {
+                signature,
+                transaction,
+            } => {
+                let started = Instant::now();
+                Message::SensitivityMapped {
+                    signature,
+                    sensitivity_map: sensitivity::map(&transaction),
+                    elapsed: started.elapsed(),
+                }
+            }
+            Self::ConnectLedger { derivation_path } => Message::LedgerConnected {
+                result: ledger::connect(derivation_path.clone())
+                    .map(|remote_keypair| remote_keypair.pubkey),
+                derivation_path,
+            },
+            Self::SignWithLedger {
+                derivation_path,
+                pubkey,
+                message,
+            } => Message::LedgerSigned {
+                pubkey,
+                result: ledger::sign(derivation_path, &pubkey, &message),
+            },
+        }
+    }
+}
+
+fn fetch_blockhash(rpc: &dyn RpcApi, nonce_account: Option<&Pubkey>) -> Result<Hash, String> {
+    let Some(nonce_account) = nonce_account else {
+        return rpc
+            .get_latest_blockhash(CommitmentConfig::finalized())
+            .map_err(|err| format!("Failed to get latest blockhash: {err}"));
+    };
+    let account = rpc
+        .get_multiple_accounts(&[*nonce_account])
+        .map_err(|err| format!("Failed to get nonce account {nonce_account}: {err}"))?
+        .into_iter()
+        .flatten()
+        .next()
+        .ok_or_else(|| format!("Nonce account {nonce_account} not found"))?;
+    let data = nonce_utils::data_from_account(&account)
+        .map_err(|err| format!("Invalid nonce account {nonce_account}: {err}"))?;
+    Ok(data.blockhash())
+}
+
+/// Get accounts from `cache`, fetching and caching those missing so that
+/// exported bundles hold them.
+fn get_cached_accounts(
+    rpc: &dyn RpcApi,
+    cache: &mut BTreeMap<Pubkey, Account>,
+    pubkeys: &[Pubkey],
+) -> Result<Vec<Option<Account>>, String> {
+    let missing = pubkeys
+        .iter()
+        .filter(|pubkey| !cache.contains_key(pubkey))
+        .copied()
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        let fetched = rpc.get_multiple_accounts(&missing)?;
+        for (pubkey, account) in missing.into_iter().zip(fetched) {
+            if let Some(account) = account {
+                cache.insert(pubkey, account);
+            }
+        }
+    }
+    Ok(pubkeys
+        .iter()
+        .map(|pubkey| cache.get(pubkey).cloned())
+        .collect())
+}
+
+/// Runs commands on background threads and collects their messages.
+pub struct CommandRunner {
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+    in_flight: usize,
+    /// File changes, applied in order on a thread of their own.
+    file_changes: Option<Sender<Command>>,
+    file_changer: Option<JoinHandle<()>>,
+}
+
+impl Default for CommandRunner {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let (file_changes, queued) = mpsc::channel::<Command>();
+        let file_changed = sender.clone();
+        let file_changer = thread::spawn(move || {
+            for command in queued {
+                let _ = file_changed.send(command.execute());
+            }
+        });
+        Self {
+            sender,
+            receiver,
+            in_flight: 0,
+            file_changes: Some(file_changes),
+            file_changer: Some(file_changer),
+        }
+    }
+}
+
+impl CommandRunner {
+    pub fn spawn(&mut self, command: Command) {
+        self.in_flight += 1;
+        if matches!(command, Command::ChangeFile { .. }) {
+            if let Some(file_changes) = &self.file_changes {
+                let _ = file_changes.send(command);
+            }
+            return;
+        }
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let _ = sender.send(command.execute());
+        });
+    }
+
+    /// Whether any command has not reported back yet.
+    pub fn is_busy(&self) -> bool {
+        self.in_flight > 0
+    }
+
+    /// Take the messages of the commands which finished.
+    pub fn finished(&mut self) -> Vec<Message> {
+        let messages = self.receiver.try_iter().collect::<Vec<_>>();
+        self.in_flight -= messages.len();
+        messages
+    }
+}
+
+impl Drop for CommandRunner {
+    /// Wait for the file changes requested, so none is lost on exit.
+    fn drop(&mut self) {
+        self.file_changes = None;
+        if let Some(file_changer) = self.file_changer.take() {
+            let _ = file_changer.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::fs};
+
+    #[test]
+    fn applies_file_changes_in_order_before_exiting() {
+        let path = std::env::temp_dir().join(format!("changes-{}.json", Pubkey::new_unique()));
+        let mut runner = CommandRunner::default();
+        for contents in ["first", "second", "last"] {
+            runner.spawn(Command::ChangeFile {
+                name: "test",
+                change: FileChange::Write {
+                    path: path.clone(),
+                    contents: contents.into(),
+                },
+            });
+        }
+        drop(runner);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "last");
+
+        FileChange::Remove(path.clone()).apply().unwrap();
+        assert!(!path.exists());
+        // Removing a file which does not exist is not an error.
+        FileChange::Remove(path).apply().unwrap();
+    }
+
+    #[test]
+    fn stays_busy_until_each_command_reports_back() {
+        let dir = std::env::temp_dir().join(format!("commands-{}", Pubkey::new_unique()));
+        let mut runner = CommandRunner::default();
+        assert!(!runner.is_busy());
+        runner.spawn(Command::WriteFile {
+            path: dir.join("written").display().to_string(),
+            contents: "written".into(),
+        });
+        runner.spawn(Command::ChangeFile {
+            name: "test",
+            change: FileChange::Remove(dir.join("removed")),
+        });
+        assert!(runner.is_busy());
+
+        let mut messages = Vec::new();
+        while runner.is_busy() {
+            messages.extend(runner.finished());
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(messages.len(), 2);
+        for message in messages {
+            match message {
+                // The directory does not exist.
+                Message::FileWritten { result, .. } => assert!(result.is_err()),
+                Message::FileChanged { name, result } => {
+                    assert_eq!(name, "test");
+                    assert!(result.is_ok());
+                }
+                _ => panic!("unexpected message"),
+            }
+        }
+        assert!(runner.finished().is_empty());
+    }
+}
@@ -0,0 +1,301 @@
+use {
+    crate::rpc::{FetchEncoding, FetchOptions, RpcApi},
+    solana_sdk::{commitment_config::CommitmentConfig, signature::Signature},
+    solana_transaction_status::UiTransactionStatusMeta,
+    std::time::{Duration, Instant},
+};
+
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Give up on signatures which do not finalize, e.g. dropped transactions.
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+pub struct TimelineEntry {
+    pub elapsed: Duration,
+    pub event: String,
+}
+
+/// Tracks how a signature progresses through the commitment levels, recording
+/// each change of its status and of its transaction meta.
+pub struct CommitmentTimeline {
+    pub signature: Signature,
+    pub entries: Vec<TimelineEntry>,
+    started: Instant,
+    status: Option<String>,
+    confirmed_meta: Option<String>,
+    finalized_meta: Option<String>,
+}
+
+impl CommitmentTimeline {
+    pub fn new(signature: Signature) -> Self {
+        Self {
+            signature,
+            entries: vec![],
+            started: Instant::now(),
+            status: None,
+            confirmed_meta: None,
+            finalized_meta: None,
+        }
+    }
+
+    /// Whether polling has stopped, either because the meta is finalized or
+    /// because of the timeout.
+    pub fn is_done(&self) -> bool {
+        self.finalized_meta.is_some() || self.started.elapsed() > TIMEOUT
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Apply a poll of the endpoint, recording the changes it shows.
+    pub fn apply(&mut self, poll: CommitmentPoll) {
+        let status_summary = match poll.status {
+            Ok(status_summary) => status_summary,
+            Err(err) => {
+                self.record(format!("Status request failed: {err}"));
+                return;
+            }
+        };
+        if status_summary != self.status {
+            self.record(match &status_summary {
+                Some(summary) => format!("Status: {summary}"),
+                None => "Status: not found".to_string(),
+            });
+            self.status = status_summary;
+        }
+        if let Some(event) = poll
+            .confirmed_meta
+            .and_then(|meta| meta_change("confirmed", &mut self.confirmed_meta, meta))
+        {
+            self.record(event);
+        }
+        if let Some(event) = poll
+            .finalized_meta
+            .and_then(|meta| meta_change("finalized", &mut self.finalized_meta, meta))
+        {
+            self.record(event);
+        }
+    }
+
+    fn record(&mut self, event: String) {
+        // Avoid flooding the timeline with repeated failures.
+        if self
+            .entries
+            .last()
+            .is_some_and(|entry| entry.event == event)
+        {
+            return;
+        }
+        self.entries.push(TimelineEntry {
+            elapsed: self.started.elapsed(),
+            event,
+        });
+    }
+}
+
+/// The status and transaction metas of a signature at one point in time.
+pub struct CommitmentPoll {
+    status: Result<Option<String>, String>,
+    /// `None` if the status has not reached the commitment level.
+    confirmed_meta: Option<Result<Option<String>, String>>,
+    finalized_meta: Option<Result<Option<String>, String>>,
+}
+
+/// Fetch the status of the signature and, once it reaches each commitment
+/// level, the meta of its transaction at that level.
+pub fn poll(rpc: &dyn RpcApi, signature: &Signature) -> CommitmentPoll {
+    let status = match rpc.get_signature_statuses(&[*signature]) {
+        Ok(statuses) => statuses.into_iter().flatten().next(),
+        Err(err) => {
+            return CommitmentPoll {
+                status: Err(err),
+                confirmed_meta: None,
+                finalized_meta: None,
+            };
+        }
+    };
+    let status_summary = status.as_ref().map(|status| {
+        let level = status
+            .confirmation_status
+            .as_ref()
+            .map(|level| format!("{level:?}").to_lowercase())
+            .unwrap_or_else(|| "unknown commitment".to_string());
+        match &status.err {
+            Some(err) => format!("{level} at slot {} with error {err}", status.slot),
+            None => format!("{level} at slot {}", status.slot),
+        }
+    });
+
+    // Transactions can only be fetched at confirmed or finalized, and only
+    // once the status reaches that level.
+    let meta_at = |commitment: CommitmentConfig| {
+        status
+            .as_ref()
+            .filter(|status| status.satisfies_commitment(commitment))
+            .map(|_| fetch_meta_summary(rpc, signature, commitment))
+    };
+    CommitmentPoll {
+        confirmed_meta: meta_at(CommitmentConfig::confirmed()),
+        finalized_meta: meta_at(CommitmentConfig::finalized()),
+        status: Ok(status_summary),
+    }
+}
+
+/// Update the meta last seen at a level, returning the event of a change. A
+/// failed fetch is its own event and keeps the meta last seen.
+fn meta_change(
+    level: &str,
+    current: &mut Option<String>,
+    fetched: Result<Option<String>, String>,
+) -> Option<String> {
+    let meta = match fetched {
+        Ok(meta) => meta,
+        Err(err) => return Some(format!("Meta request failed at {level}: {err}")),
+    };
+    if meta == *current {
+        return None;
+    }
+    let event = match (&current, &meta) {
+        (None, Some(new)) => format!("Meta appeared at {level}: {new}"),
+        (Some(_), Some(new)) => format!("Meta changed at {level}: {new}"),
+        (_, None) => format!("Meta disappeared at {level}"),
+    };
+    *current = meta;
+    Some(event)
+}
+
+fn fetch_meta_summary(
+    rpc: &dyn RpcApi,
+    signature: &Signature,
+    commitment: CommitmentConfig,
+) -> Result<Option<String>, String> {
+    let options = FetchOptions {
+        encoding: FetchEncoding::Base64,
+        ..FetchOptions::default()
+    };
+    let transaction = rpc.get_transaction_with_commitment(signature, &options, commitment)?;
+    Ok(transaction.transaction.meta.as_ref().map(meta_summary))
+}
+
+fn meta_summary(meta: &UiTransactionStatusMeta) -> String {
+    let compute_units = Option::<&u64>::from(meta.compute_units_consumed.as_ref())
+        .map(|units| format!("{units} CUs"))
+        .unwrap_or_else(|| "unknown CUs".to_string());
+    let num_logs = Option::<&Vec<String>>::from(meta.log_messages.as_ref()).map_or(0, Vec::len);
+    let result = match &meta.err {
+        Some(err) => format!("failed: {err}"),
+        None => "succeeded".to_string(),
+    };
+    format!(
+        "{result}, fee {}, {compute_units}, {num_logs} logs",
+        meta.fee
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poll(
+        status: Result<Option<&str>, &str>,
+        confirmed_meta: Option<Result<Option<&str>, &str>>,
+    ) -> CommitmentPoll {
+        let owned = |meta: Result<Option<&str>, &str>| {
+            meta.map(|meta| meta.map(str::to_string))
+                .map_err(str::to_string)
+        };
+        CommitmentPoll {
+            status: owned(status),
+            confirmed_meta: confirmed_meta.map(owned),
+            finalized_meta: None,
+        }
+    }
+
+    fn events(timeline: &CommitmentTimeline) -> Vec<&str> {
+        timeline
+            .entries
+            .iter()
+            .map(|entry| entry.event.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn records_changes_of_status_and_meta() {
+        let mut timeline = CommitmentTimeline::new(Signature::new_unique());
+        // Not found is where a timeline starts.
+        timeline.apply(poll(Ok(None), None));
+        assert!(timeline.entries.is_empty());
+        timeline.apply(poll(Ok(Some("processed at slot 5")), None));
+        let confirmed = Ok(Some("succeeded, fee 5000"));
+        timeline.apply(poll(Ok(Some("confirmed at slot 5")), Some(confirmed)));
+        timeline.apply(poll(Ok(Some("confirmed at slot 5")), Some(confirmed)));
+        timeline.apply(poll(
+            Ok(Some("confirmed at slot 6")),
+            Some(Ok(Some("succeeded, fee 6000"))),
+        ));
+        timeline.apply(poll(Ok(Some("confirmed at slot 6")), Some(Ok(None))));
+        assert_eq!(
+            events(&timeline),
+            [
+                "Status: processed at slot 5",
+                "Status: confirmed at slot 5",
+                "Meta appeared at confirmed: succeeded, fee 5000",
+                "Status: confirmed at slot 6",
+                "Meta changed at confirmed: succeeded, fee 6000",
+                "Meta disappeared at confirmed",
+            ]
+        );
+        assert!(!timeline.is_done());
+
+        timeline.apply(CommitmentPoll {
+            status: Ok(Some("finalized at slot 6".to_string())),
+            confirmed_meta: Some(Ok(None)),
+            finalized_meta: Some(Ok(Some("succeeded, fee 6000".to_string()))),
+        });
+        assert_eq!(
+            events(&timeline)[6..],
+            [
+                "Status: finalized at slot 6",
+                "Meta appeared at finalized: succeeded, fee 6000",
+            ]
+        );
+        assert!(timeline.is_done());
+    }
+
+    #[test]
+    fn reports_failed_requests_without_losing_the_meta() {
+        let mut timeline = CommitmentTimeline::new(Signature::new_unique());
+        let status = Ok(Some("confirmed at slot 5"));
+        timeline.apply(poll(status, Some(Ok(Some("succeeded")))));
+        timeline.apply(poll(status, Some(Err("timed out"))));
+        timeline.apply(poll(status, Some(Ok(Some("succeeded")))));
+        timeline.apply(poll(Err("connection refused"), None));
+        timeline.apply(poll(status, None));
+        assert_eq!(
+            events(&timeline),
+            [
+                "Status: confirmed at slot 5",
+                "Meta appeared at confirmed: succeeded",
+                "Meta request failed at confirmed: timed out",
+                "Status request failed: connection refused",
+            ]
+        );
+    }
+
+    #[test]
+    fn records_repeated_failures_once() {
+        let mut timeline = CommitmentTimeline::new(Signature::new_unique());
+        timeline.apply(poll(Err("connection refused"), None));
+        timeline.apply(poll(Err("connection refused"), None));
+        timeline.apply(poll(Err("timed out"), None));
+        timeline.apply(poll(Err("connection refused"), None));
+        assert_eq!(
+            events(&timeline),
+            [
+                "Status request failed: connection refused",
+                "Status request failed: timed out",
+                "Status request failed: connection refused",
+            ]
+        );
+    }
+}
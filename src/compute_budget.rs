@@ -0,0 +1,58 @@
+use solana_sdk::{compute_budget, message::VersionedMessage};
+
+/// Compute units requested for each instruction when the transaction does not
+/// set a limit.
+const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+/// Discriminators of the compute budget program instructions.
+pub const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+pub const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// The compute budget requested by a message.
+pub struct ComputeBudget {
+    pub unit_limit: u32,
+    pub unit_price: u64,
+}
+
+impl ComputeBudget {
+    pub fn from_message(message: &VersionedMessage) -> Self {
+        let account_keys = message.static_account_keys();
+        let mut unit_limit = None;
+        let mut unit_price = 0;
+        let mut num_other_instructions = 0;
+        for instruction in message.instructions() {
+            let program_id = account_keys.get(instruction.program_id_index as usize);
+            if program_id != Some(&compute_budget::id()) {
+                num_other_instructions += 1;
+                continue;
+            }
+            match instruction.data.split_first() {
+                Some((&SET_COMPUTE_UNIT_LIMIT, units)) => {
+                    unit_limit = units.try_into().ok().map(u32::from_le_bytes)
+                }
+                Some((&SET_COMPUTE_UNIT_PRICE, micro_lamports)) => {
+                    unit_price = micro_lamports
+                        .try_into()
+                        .map(u64::from_le_bytes)
+                        .unwrap_or_default()
+                }
+                _ => {}
+            }
+        }
+        let unit_limit = unit_limit
+            .unwrap_or(DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT * num_other_instructions)
+            .min(MAX_COMPUTE_UNIT_LIMIT);
+        Self {
+            unit_limit,
+            unit_price,
+        }
+    }
+
+    /// The priority fee in lamports paid on top of the signature fees.
+    pub fn priority_fee(&self) -> u64 {
+        let micro_lamports = self.unit_price as u128 * self.unit_limit as u128;
+        micro_lamports.div_ceil(MICRO_LAMPORTS_PER_LAMPORT) as u64
+    }
+}
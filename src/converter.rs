@@ -0,0 +1,116 @@
+//! Converting bytes between base58, base64, hex and byte arrays, with
+//! checks of whether they make a valid pubkey, signature or keypair.
+
+use {
+    base64::{engine::general_purpose::STANDARD, Engine},
+    solana_sdk::{bs58, pubkey::Pubkey, signature::Keypair, signer::Signer},
+};
+
+/// The encodings the input is valid in, with the bytes it decodes to.
+pub fn readings(input: &str) -> Vec<(&'static str, Vec<u8>)> {
+    let input = input.trim();
+    if input.is_empty() {
+        return vec![];
+    }
+    let mut readings = vec![];
+    if let Some(bytes) = parse_byte_array(input) {
+        readings.push(("byte array", bytes));
+    }
+    let hex = input.trim_start_matches("0x").replace(' ', "");
+    if let Ok(bytes) = hex::decode(hex) {
+        readings.push(("hex", bytes));
+    }
+    if let Ok(bytes) = bs58::decode(input).into_vec() {
+        readings.push(("base58", bytes));
+    }
+    if let Ok(bytes) = STANDARD.decode(input) {
+        readings.push(("base64", bytes));
+    }
+    readings
+}
+
+/// Bytes written as `[1, 2, 3]`, the format of keypair files.
+fn parse_byte_array(input: &str) -> Option<Vec<u8>> {
+    let list = input.strip_prefix('[')?.strip_suffix(']')?;
+    list.split(',')
+        .map(str::trim)
+        .filter(|byte| !byte.is_empty())
+        .map(|byte| byte.parse::<u8>().ok())
+        .collect()
+}
+
+/// Each reading of the input in every encoding, and what its bytes are
+/// valid as.
+pub fn evaluate(input: &str) -> Vec<String> {
+    let mut lines = vec![];
+    for (encoding, bytes) in readings(input) {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(format!("As {encoding}, {} bytes", bytes.len()));
+        lines.push(format!("  hex         {}", hex::encode(&bytes)));
+        lines.push(format!(
+            "  base58      {}",
+            bs58::encode(&bytes).into_string()
+        ));
+        lines.push(format!("  base64      {}", STANDARD.encode(&bytes)));
+        lines.push(format!("  byte array  {bytes:?}"));
+        lines.push(format!("  valid as    {}", validity(&bytes)));
+    }
+    lines
+}
+
+fn validity(bytes: &[u8]) -> String {
+    match bytes.len() {
+        32 => {
+            let pubkey = Pubkey::try_from(bytes).unwrap();
+            if pubkey.is_on_curve() {
+                "a pubkey on the curve, which can sign".to_string()
+            } else {
+                "a pubkey off the curve, e.g. a program derived address".to_string()
+            }
+        }
+        64 => match Keypair::from_bytes(bytes) {
+            Ok(keypair) => format!("a signature, or the keypair of {}", keypair.pubkey()),
+            Err(_) => "a signature".to_string(),
+        },
+        len => format!("neither a pubkey (32 bytes) nor a signature (64 bytes), at {len} bytes"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk::pubkey};
+
+    #[test]
+    fn converts_between_encodings() {
+        let program_id = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+        // 44 characters of base58 also read as base64.
+        let readings = readings(&program_id.to_string());
+        assert_eq!(readings[0], ("base58", program_id.to_bytes().to_vec()));
+        assert_eq!(readings[1].0, "base64");
+
+        let readings = super::readings("[1, 2, 255]");
+        assert_eq!(readings, [("byte array", vec![1, 2, 255])]);
+
+        let lines = evaluate("0x01ff");
+        assert_eq!(
+            lines,
+            [
+                "As hex, 2 bytes",
+                "  hex         01ff",
+                "  base58      9p",
+                "  base64      Af8=",
+                "  byte array  [1, 255]",
+                "  valid as    neither a pubkey (32 bytes) nor a signature (64 bytes), at 2 bytes",
+            ]
+        );
+
+        let keypair = Keypair::new();
+        let lines = evaluate(&format!("{:?}", keypair.to_bytes()));
+        assert!(lines.contains(&format!(
+            "  valid as    a signature, or the keypair of {}",
+            keypair.pubkey()
+        )));
+    }
+}
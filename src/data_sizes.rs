@@ -0,0 +1,116 @@
+//! The data size of each instruction as a bar chart, to see which instruction
+//! dominates the payload of a transaction.
+
+use {
+    crate::{address_book::AddressBook, bookmarks::native_program_name, settings::Settings},
+    ratatui::{
+        layout::Direction,
+        style::{Color, Style},
+        widgets::{Bar, BarChart, BarGroup},
+    },
+    solana_sdk::transaction::VersionedTransaction,
+};
+
+pub struct InstructionSize {
+    pub index: usize,
+    pub program: String,
+    pub data_len: usize,
+}
+
+/// The data size of each instruction, with its program's name.
+pub fn instruction_sizes(
+    transaction: &VersionedTransaction,
+    settings: &Settings,
+    address_book: &AddressBook,
+) -> Vec<InstructionSize> {
+    let message = &transaction.message;
+    message
+        .instructions()
+        .iter()
+        .enumerate()
+        .map(|(index, instruction)| {
+            let program = match message
+                .static_account_keys()
+                .get(instruction.program_id_index as usize)
+            {
+                Some(program_id) => address_book
+                    .name(program_id)
+                    .or_else(|| native_program_name(program_id))
+                    .map(str::to_string)
+                    .unwrap_or_else(|| settings.pubkey_display.format(program_id)),
+                None => format!("invalid program index {}", instruction.program_id_index),
+            };
+            InstructionSize {
+                index,
+                program,
+                data_len: instruction.data.len(),
+            }
+        })
+        .collect()
+}
+
+/// A horizontal bar per instruction, labelled with its index and program,
+/// and the largest highlighted.
+pub fn bar_chart(sizes: &[InstructionSize], monochrome: bool) -> BarChart<'static> {
+    let total = sizes.iter().map(|size| size.data_len).sum::<usize>();
+    let largest = sizes.iter().map(|size| size.data_len).max().unwrap_or(0);
+    let bars = sizes
+        .iter()
+        .map(|size| {
+            let share = (size.data_len * 100).checked_div(total).unwrap_or(0);
+            let color = match () {
+                _ if monochrome => Color::Reset,
+                _ if size.data_len == largest && largest > 0 => Color::Yellow,
+                _ => Color::Cyan,
+            };
+            Bar::default()
+                .label(format!("#{} {}", size.index, size.program).into())
+                .value(size.data_len as u64)
+                .text_value(format!("{} B ({share}%)", size.data_len))
+                .style(Style::default().fg(color))
+                .value_style(Style::default().fg(Color::Black).bg(color))
+        })
+        .collect::<Vec<_>>();
+    BarChart::default()
+        .direction(Direction::Horizontal)
+        .bar_width(1)
+        .bar_gap(0)
+        .data(BarGroup::default().bars(&bars))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::address_book::Contact,
+        solana_sdk::{
+            hash::Hash, instruction::Instruction, message::Message, pubkey::Pubkey,
+            system_instruction, transaction::Transaction,
+        },
+    };
+
+    #[test]
+    fn sizes_instruction_data_by_program() {
+        let payer = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let instructions = [
+            system_instruction::transfer(&payer, &Pubkey::new_unique(), 1),
+            Instruction::new_with_bytes(program_id, &[7; 40], vec![]),
+        ];
+        let message = Message::new_with_blockhash(&instructions, Some(&payer), &Hash::default());
+        let transaction = VersionedTransaction::from(Transaction::new_unsigned(message));
+        let mut address_book = AddressBook::default();
+        let contact = Contact {
+            name: "my program".to_string(),
+            tags: vec![],
+        };
+        address_book.insert(&program_id, contact);
+
+        let sizes = instruction_sizes(&transaction, &Settings::default(), &address_book);
+        let sizes = sizes
+            .iter()
+            .map(|size| (size.index, size.program.as_str(), size.data_len))
+            .collect::<Vec<_>>();
+        assert_eq!(sizes, [(0, "system", 12), (1, "my program", 40)]);
+    }
+}
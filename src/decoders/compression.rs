@@ -0,0 +1,414 @@
+//! SPL Account Compression instructions, which maintain the concurrent
+//! merkle trees of compressed NFTs. Their arguments are split into fields,
+//! and the proof path, passed as the instruction's remaining accounts, is
+//! checked against the root where the instruction names the proved leaf.
+
+use {
+    super::{read_field, DataField, DecodedInstruction, Reader},
+    crate::schema::anchor_discriminator,
+    solana_sdk::{keccak::hashv, pubkey, pubkey::Pubkey},
+};
+
+pub const SPL_ACCOUNT_COMPRESSION: Pubkey = pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+#[derive(Clone, Copy)]
+enum Arg {
+    U32,
+    Node,
+    Pubkey,
+    /// A borsh `Vec` of nodes.
+    Nodes,
+}
+
+/// Which leaf the proof path of an instruction is for.
+#[derive(Clone, Copy)]
+enum Proof {
+    None,
+    /// A leaf not in the arguments, so the proof cannot be checked.
+    Unchecked,
+    /// The leaf and index arguments the proof is for.
+    Of {
+        leaf: &'static str,
+        index: &'static str,
+    },
+}
+
+struct Layout {
+    name: &'static str,
+    args: &'static [(&'static str, Arg)],
+    accounts: &'static [&'static str],
+    proof: Proof,
+}
+
+const TREE_ACCOUNTS: &[&str] = &["merkle_tree", "authority", "noop"];
+
+const INSTRUCTIONS: &[Layout] = &[
+    Layout {
+        name: "init_empty_merkle_tree",
+        args: &[("max_depth", Arg::U32), ("max_buffer_size", Arg::U32)],
+        accounts: TREE_ACCOUNTS,
+        proof: Proof::None,
+    },
+    Layout {
+        name: "prepare_batch_merkle_tree",
+        args: &[("max_depth", Arg::U32), ("max_buffer_size", Arg::U32)],
+        accounts: TREE_ACCOUNTS,
+        proof: Proof::None,
+    },
+    Layout {
+        name: "append_canopy_nodes",
+        args: &[("start_index", Arg::U32), ("canopy_nodes", Arg::Nodes)],
+        accounts: TREE_ACCOUNTS,
+        proof: Proof::None,
+    },
+    Layout {
+        name: "init_prepared_tree_with_root",
+        args: &[
+            ("root", Arg::Node),
+            ("rightmost_leaf", Arg::Node),
+            ("rightmost_index", Arg::U32),
+        ],
+        accounts: TREE_ACCOUNTS,
+        proof: Proof::Of {
+            leaf: "rightmost_leaf",
+            index: "rightmost_index",
+        },
+    },
+    Layout {
+        name: "replace_leaf",
+        args: &[
+            ("root", Arg::Node),
+            ("previous_leaf", Arg::Node),
+            ("new_leaf", Arg::Node),
+            ("index", Arg::U32),
+        ],
+        accounts: TREE_ACCOUNTS,
+        proof: Proof::Of {
+            leaf: "previous_leaf",
+            index: "index",
+        },
+    },
+    Layout {
+        name: "transfer_authority",
+        args: &[("new_authority", Arg::Pubkey)],
+        accounts: &["merkle_tree", "authority"],
+        proof: Proof::None,
+    },
+    Layout {
+        name: "verify_leaf",
+        args: &[
+            ("root", Arg::Node),
+            ("leaf", Arg::Node),
+            ("index", Arg::U32),
+        ],
+        accounts: &["merkle_tree"],
+        proof: Proof::Of {
+            leaf: "leaf",
+            index: "index",
+        },
+    },
+    Layout {
+        name: "append",
+        args: &[("leaf", Arg::Node)],
+        accounts: TREE_ACCOUNTS,
+        proof: Proof::None,
+    },
+    Layout {
+        name: "insert_or_append",
+        args: &[
+            ("root", Arg::Node),
+            ("leaf", Arg::Node),
+            ("index", Arg::U32),
+        ],
+        accounts: TREE_ACCOUNTS,
+        proof: Proof::Unchecked,
+    },
+    Layout {
+        name: "close_empty_tree",
+        args: &[],
+        accounts: &["merkle_tree", "authority", "recipient"],
+        proof: Proof::None,
+    },
+];
+
+pub fn decode(
+    program_id: &Pubkey,
+    accounts: &[Option<Pubkey>],
+    data: &[u8],
+) -> Option<DecodedInstruction> {
+    if *program_id != SPL_ACCOUNT_COMPRESSION {
+        return None;
+    }
+    let Some(layout) = data.get(..8).and_then(|discriminator| {
+        INSTRUCTIONS
+            .iter()
+            .find(|layout| anchor_discriminator(layout.name) == discriminator)
+    }) else {
+        return Some(DecodedInstruction {
+            program: "SPL Account Compression",
+            name: "unknown".to_string(),
+            inner: None,
+            fields: vec![],
+            account_names: vec![],
+        });
+    };
+
+    let mut fields = vec![DataField {
+        name: "discriminator".to_string(),
+        range: 0..8,
+    }];
+    let mut values = vec![];
+    let mut reader = Reader::new(&data[8..]);
+    for (name, arg) in layout.args {
+        let complete = match arg {
+            Arg::U32 => read_field(&mut reader, &mut fields, name.to_string(), 4),
+            Arg::Node | Arg::Pubkey => read_field(&mut reader, &mut fields, name.to_string(), 32),
+            // The value is the length, followed by a field for each node.
+            Arg::Nodes => {
+                read_field(&mut reader, &mut fields, format!("{name} length"), 4).filter(|len| {
+                    let len = <[u8; 4]>::try_from(*len).map_or(0, u32::from_le_bytes);
+                    (0..len).all(|index| {
+                        read_field(&mut reader, &mut fields, format!("{name}[{index}]"), 32)
+                            .is_some()
+                    })
+                })
+            }
+        };
+        match complete {
+            Some(value) => values.push((*name, value)),
+            None => break,
+        }
+    }
+
+    let proof = accounts.get(layout.accounts.len()..).unwrap_or_default();
+    let mut account_names = layout
+        .accounts
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    let mut name = layout.name.to_string();
+    if !matches!(layout.proof, Proof::None) {
+        account_names.extend((0..proof.len()).map(|index| format!("proof[{index}]")));
+        name = format!("{name} with a proof of {} nodes", proof.len());
+    }
+    if let Proof::Of { leaf, index } = layout.proof {
+        let value = |name| {
+            values
+                .iter()
+                .find(|(value, _)| *value == name)
+                .map(|(_, value)| *value)
+        };
+        let node = |name| <[u8; 32]>::try_from(value(name)?).ok();
+        let index = value(index)
+            .and_then(|index| index.try_into().ok())
+            .map(u32::from_le_bytes);
+        let proof = proof.iter().copied().collect::<Option<Vec<_>>>();
+        if let (Some(root), Some(leaf), Some(index), Some(proof)) =
+            (node("root"), node(leaf), index, proof)
+        {
+            name = if recompute_root(leaf, &proof, index) == root {
+                format!("{name}, hashing to the root")
+            } else {
+                format!("{name}, completed by the canopy or invalid")
+            };
+        }
+    }
+    Some(DecodedInstruction {
+        program: "SPL Account Compression",
+        name,
+        inner: None,
+        fields,
+        account_names,
+    })
+}
+
+/// The root a proof path leads to from a leaf, hashing each node with its
+/// sibling from the leaf's level up.
+fn recompute_root(leaf: [u8; 32], proof: &[Pubkey], index: u32) -> [u8; 32] {
+    proof
+        .iter()
+        .enumerate()
+        .fold(leaf, |node, (level, sibling)| {
+            let sibling = sibling.as_ref();
+            let parent = if index >> level & 1 == 0 {
+                hashv(&[&node, sibling])
+            } else {
+                hashv(&[sibling, &node])
+            };
+            parent.to_bytes()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::decoders::tests::{decode_prefixes, fields},
+    };
+
+    /// The data of an instruction: its discriminator, then its arguments.
+    fn data(name: &str, args: &[&[u8]]) -> Vec<u8> {
+        [&anchor_discriminator(name)[..], &args.concat()].concat()
+    }
+
+    /// An `append_canopy_nodes` of `len` nodes, followed by `nodes`.
+    fn canopy(len: u32, nodes: &[u8]) -> Vec<u8> {
+        data(
+            "append_canopy_nodes",
+            &[&0u32.to_le_bytes(), &len.to_le_bytes(), nodes],
+        )
+    }
+
+    #[test]
+    fn decodes_fields_and_checks_proofs() {
+        let leaf = [7; 32];
+        let proof = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let index = 2u32;
+        let root = recompute_root(leaf, &proof, index);
+        let verify_leaf =
+            |root: [u8; 32]| data("verify_leaf", &[&root, &leaf, &index.to_le_bytes()]);
+        let accounts = [Some(Pubkey::new_unique()), Some(proof[0]), Some(proof[1])];
+        let decoded = decode(&SPL_ACCOUNT_COMPRESSION, &accounts, &verify_leaf(root)).unwrap();
+        assert_eq!(
+            decoded.name,
+            "verify_leaf with a proof of 2 nodes, hashing to the root"
+        );
+        assert_eq!(
+            decoded.account_names,
+            ["merkle_tree", "proof[0]", "proof[1]"]
+        );
+        assert_eq!(
+            fields(&decoded),
+            [
+                ("discriminator", 0..8),
+                ("root", 8..40),
+                ("leaf", 40..72),
+                ("index", 72..76)
+            ]
+        );
+        let decoded = decode(&SPL_ACCOUNT_COMPRESSION, &accounts, &verify_leaf([0; 32])).unwrap();
+        assert!(decoded.name.ends_with("completed by the canopy or invalid"));
+
+        let decoded = decode(&SPL_ACCOUNT_COMPRESSION, &accounts, &canopy(2, &[1; 64])).unwrap();
+        let last = decoded.fields.last().unwrap();
+        assert_eq!(
+            (last.name.as_str(), last.range.clone()),
+            ("canopy_nodes[1]", 48..80)
+        );
+    }
+
+    #[test]
+    fn names_unknown_instructions() {
+        let accounts = [Some(Pubkey::new_unique())];
+        assert!(decode(
+            &Pubkey::new_unique(),
+            &accounts,
+            &data("append", &[&[1; 32]])
+        )
+        .is_none());
+        // Short of a discriminator, or with one of no known instruction.
+        for data in [&[][..], &anchor_discriminator("append")[..7], &[0; 40]] {
+            let decoded = decode(&SPL_ACCOUNT_COMPRESSION, &accounts, data).unwrap();
+            assert_eq!(decoded.name, "unknown");
+            assert!(decoded.fields.is_empty());
+        }
+    }
+
+    #[test]
+    fn reads_as_many_canopy_nodes_as_the_data_holds() {
+        let accounts = [];
+        let names = |data: &[u8]| {
+            let decoded = decode(&SPL_ACCOUNT_COMPRESSION, &accounts, data).unwrap();
+            let names = decoded.fields.iter().map(|field| field.name.clone());
+            names.collect::<Vec<_>>()
+        };
+        let header = ["discriminator", "start_index", "canopy_nodes length"];
+        assert_eq!(names(&canopy(0, &[])), header);
+        // A length claiming more nodes than there are keeps those which
+        // are whole.
+        assert_eq!(
+            names(&canopy(u32::MAX, &[1; 64 + 16])),
+            [&header[..], &["canopy_nodes[0]", "canopy_nodes[1]"]].concat()
+        );
+        assert_eq!(names(&canopy(1, &[1; 31])), header);
+    }
+
+    #[test]
+    fn checks_proofs_only_of_whole_arguments_and_resolved_nodes() {
+        let (previous_leaf, new_leaf) = ([7; 32], [8; 32]);
+        let proof = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let index = 1u32;
+        let root = recompute_root(previous_leaf, &proof, index);
+        let replace_leaf = data(
+            "replace_leaf",
+            &[&root, &previous_leaf, &new_leaf, &index.to_le_bytes()],
+        );
+        let tree = [Some(Pubkey::new_unique()); 3];
+        let accounts = [&tree[..], &[Some(proof[0]), Some(proof[1])]].concat();
+        let decode_with = |accounts: &[Option<Pubkey>], data: &[u8]| {
+            decode(&SPL_ACCOUNT_COMPRESSION, accounts, data).unwrap()
+        };
+        assert_eq!(
+            decode_with(&accounts, &replace_leaf).name,
+            "replace_leaf with a proof of 2 nodes, hashing to the root"
+        );
+        assert_eq!(
+            fields(&decode_with(&accounts, &replace_leaf))[4],
+            ("index", 104..108)
+        );
+
+        // The index orders each node with its sibling.
+        let mut other_index = replace_leaf.clone();
+        other_index[104..].copy_from_slice(&2u32.to_le_bytes());
+        assert!(decode_with(&accounts, &other_index)
+            .name
+            .ends_with("completed by the canopy or invalid"));
+
+        // Without the index, or a node of the proof, there is nothing to
+        // check.
+        let prefixes = decode_prefixes(&replace_leaf, |data| {
+            decode(&SPL_ACCOUNT_COMPRESSION, &accounts, data)
+        });
+        let prefix = prefixes[replace_leaf.len() - 1].as_ref().unwrap();
+        assert_eq!(prefix.name, "replace_leaf with a proof of 2 nodes");
+        assert!(prefixes
+            .iter()
+            .flatten()
+            .all(|decoded| !decoded.name.contains(',')));
+        let unresolved = [&tree[..], &[Some(proof[0]), None]].concat();
+        assert_eq!(
+            decode_with(&unresolved, &replace_leaf).name,
+            "replace_leaf with a proof of 2 nodes"
+        );
+
+        // The leaf of `insert_or_append` is not the one proved.
+        let insert = data(
+            "insert_or_append",
+            &[&root, &new_leaf, &index.to_le_bytes()],
+        );
+        assert_eq!(
+            decode_with(&accounts, &insert).name,
+            "insert_or_append with a proof of 2 nodes"
+        );
+    }
+
+    #[test]
+    fn names_the_accounts_of_instructions_without_proofs() {
+        let new_authority = Pubkey::new_unique();
+        let accounts = [Some(Pubkey::new_unique()); 3];
+        let transfer = data("transfer_authority", &[new_authority.as_ref()]);
+        let decoded = decode(&SPL_ACCOUNT_COMPRESSION, &accounts, &transfer).unwrap();
+        assert_eq!(decoded.name, "transfer_authority");
+        assert_eq!(decoded.account_names, ["merkle_tree", "authority"]);
+        assert_eq!(fields(&decoded)[1], ("new_authority", 8..40));
+
+        let close = data("close_empty_tree", &[]);
+        let decoded = decode(&SPL_ACCOUNT_COMPRESSION, &accounts, &close).unwrap();
+        assert_eq!(decoded.name, "close_empty_tree");
+        assert_eq!(
+            decoded.account_names,
+            ["merkle_tree", "authority", "recipient"]
+        );
+        assert_eq!(fields(&decoded), [("discriminator", 0..8)]);
+    }
+}
@@ -0,0 +1,166 @@
+use {
+    solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction},
+    std::ops::Range,
+};
+
+pub mod compression;
+pub mod multisig;
+pub mod token_2022;
+pub mod wormhole;
+pub mod zk;
+
+/// A human readable description of an instruction of a known program.
+pub struct DecodedInstruction {
+    pub program: &'static str,
+    pub name: String,
+    /// Where the transaction wrapped by this instruction can be found, for
+    /// instructions which create or execute a stored transaction.
+    pub inner: Option<InnerTransactionSource>,
+    /// The fields of the instruction data, for programs whose layouts are
+    /// known.
+    pub fields: Vec<DataField>,
+    /// The roles of the instruction's accounts, by position, where known.
+    pub account_names: Vec<String>,
+}
+
+/// A named range of bytes of an instruction's data.
+pub struct DataField {
+    pub name: String,
+    pub range: Range<usize>,
+}
+
+impl DecodedInstruction {
+    /// The name of the data field holding the byte at `offset` in the data.
+    pub fn field_at(&self, offset: usize) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|field| field.range.contains(&offset))
+            .map(|field| field.name.as_str())
+    }
+}
+
+/// The location of a transaction wrapped by an instruction.
+pub enum InnerTransactionSource {
+    /// The transaction is encoded in the instruction data.
+    Inline(VersionedTransaction),
+    /// The transaction is stored in accounts of the instruction, which must
+    /// be fetched.
+    Accounts(multisig::StoredTransaction),
+}
+
+/// Decode an instruction of a known program, or else recognize a proof in
+/// its data. `accounts` are the instruction's account keys, where they could
+/// be resolved.
+pub fn decode_instruction(
+    program_id: &Pubkey,
+    accounts: &[Option<Pubkey>],
+    data: &[u8],
+) -> Option<DecodedInstruction> {
+    multisig::decode(program_id, accounts, data)
+        .or_else(|| compression::decode(program_id, accounts, data))
+        .or_else(|| token_2022::decode(program_id, accounts, data))
+        .or_else(|| wormhole::decode(program_id, data))
+        .or_else(|| zk::decode(data))
+}
+
+/// Read `len` bytes as the field `name`, following the fields read so far.
+pub fn read_field<'a>(
+    reader: &mut Reader<'a>,
+    fields: &mut Vec<DataField>,
+    name: String,
+    len: usize,
+) -> Option<&'a [u8]> {
+    let start = fields.last().map_or(0, |field| field.range.end);
+    let bytes = reader.bytes(len)?;
+    fields.push(DataField {
+        name,
+        range: start..start + len,
+    });
+    Some(bytes)
+}
+
+/// Sequential little-endian reader over borsh encoded bytes.
+pub struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if len > self.data.len() {
+            return None;
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Some(bytes)
+    }
+
+    pub fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.bytes(N)?.try_into().ok()
+    }
+
+    pub fn u8(&mut self) -> Option<u8> {
+        self.array().map(u8::from_le_bytes)
+    }
+
+    pub fn u16(&mut self) -> Option<u16> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    pub fn bool(&mut self) -> Option<bool> {
+        self.u8().map(|byte| byte != 0)
+    }
+
+    pub fn pubkey(&mut self) -> Option<Pubkey> {
+        self.array().map(Pubkey::new_from_array)
+    }
+
+    /// A borsh `Vec<u8>`, prefixed by a u32 length.
+    pub fn vec_u8(&mut self) -> Option<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.bytes(len)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// The names and ranges of the fields of a decoded instruction.
+    pub fn fields(decoded: &DecodedInstruction) -> Vec<(&str, Range<usize>)> {
+        decoded
+            .fields
+            .iter()
+            .map(|field| (field.name.as_str(), field.range.clone()))
+            .collect()
+    }
+
+    /// Decode each prefix of `data` short of all of it, checking that no
+    /// field reaches past the bytes the prefix holds. The decodings are
+    /// returned by length, for what each decoder makes of partial data.
+    pub fn decode_prefixes(
+        data: &[u8],
+        decode: impl Fn(&[u8]) -> Option<DecodedInstruction>,
+    ) -> Vec<Option<DecodedInstruction>> {
+        (0..data.len())
+            .map(|len| {
+                let decoded = decode(&data[..len]);
+                for field in decoded.iter().flat_map(|decoded| &decoded.fields) {
+                    assert!(
+                        field.range.end <= len,
+                        "{} ends past {len} bytes",
+                        field.name
+                    );
+                }
+                decoded
+            })
+            .collect()
+    }
+}
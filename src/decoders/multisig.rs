@@ -0,0 +1,823 @@
+//! Squads v3, Squads v4 and SPL Governance instructions, including the
+//! transactions which multisig proposals wrap.
+
+use {
+    super::{DecodedInstruction, InnerTransactionSource, Reader},
+    crate::schema::anchor_discriminator,
+    solana_sdk::{
+        account::Account,
+        hash::{hash, Hash},
+        instruction::{AccountMeta, CompiledInstruction, Instruction},
+        message::{v0, Message, MessageHeader, VersionedMessage},
+        pubkey,
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::VersionedTransaction,
+    },
+};
+
+pub const SQUADS_V3: Pubkey = pubkey!("SMPLecH534NA9acpos4G6x7uf3LWbCAwZQE9e8ZekMu");
+pub const SQUADS_V4: Pubkey = pubkey!("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf");
+pub const SPL_GOVERNANCE: Pubkey = pubkey!("GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw");
+
+const SQUADS_V3_INSTRUCTIONS: &[&str] = &[
+    "create",
+    "add_member",
+    "remove_member",
+    "remove_member_and_change_threshold",
+    "add_member_and_change_threshold",
+    "change_threshold",
+    "add_authority",
+    "set_external_execute",
+    "create_transaction",
+    "add_instruction",
+    "activate_transaction",
+    "approve_transaction",
+    "reject_transaction",
+    "cancel_transaction",
+    "execute_transaction",
+    "execute_instruction",
+];
+
+const SQUADS_V4_INSTRUCTIONS: &[&str] = &[
+    "program_config_init",
+    "program_config_set_authority",
+    "program_config_set_multisig_creation_fee",
+    "program_config_set_treasury",
+    "multisig_create",
+    "multisig_create_v2",
+    "multisig_add_member",
+    "multisig_remove_member",
+    "multisig_set_time_lock",
+    "multisig_change_threshold",
+    "multisig_set_config_authority",
+    "multisig_set_rent_collector",
+    "multisig_add_spending_limit",
+    "multisig_remove_spending_limit",
+    "config_transaction_create",
+    "config_transaction_execute",
+    "vault_transaction_create",
+    "transaction_buffer_create",
+    "transaction_buffer_close",
+    "transaction_buffer_extend",
+    "vault_transaction_create_from_buffer",
+    "vault_transaction_execute",
+    "batch_create",
+    "batch_add_transaction",
+    "batch_execute_transaction",
+    "proposal_create",
+    "proposal_activate",
+    "proposal_approve",
+    "proposal_reject",
+    "proposal_cancel",
+    "proposal_cancel_v2",
+    "spending_limit_use",
+    "config_transaction_accounts_close",
+    "vault_transaction_accounts_close",
+    "vault_batch_transaction_account_close",
+    "batch_accounts_close",
+];
+
+/// Variants of the SPL Governance instruction enum, by index.
+const GOVERNANCE_INSTRUCTIONS: &[&str] = &[
+    "CreateRealm",
+    "DepositGoverningTokens",
+    "WithdrawGoverningTokens",
+    "SetGovernanceDelegate",
+    "CreateGovernance",
+    "CreateProgramGovernance",
+    "CreateProposal",
+    "AddSignatory",
+    "RemoveSignatory",
+    "InsertTransaction",
+    "RemoveTransaction",
+    "CancelProposal",
+    "SignOffProposal",
+    "CastVote",
+    "FinalizeVote",
+    "RelinquishVote",
+    "ExecuteTransaction",
+    "CreateMintGovernance",
+    "CreateTokenGovernance",
+    "SetGovernanceConfig",
+    "FlagTransactionError",
+    "SetRealmAuthority",
+    "SetRealmConfig",
+    "CreateTokenOwnerRecord",
+    "UpdateProgramMetadata",
+    "CreateNativeTreasury",
+    "RevokeGoverningTokens",
+    "RefundProposalDeposit",
+    "CompleteProposal",
+    "AddRequiredSignatory",
+    "RemoveRequiredSignatory",
+];
+
+/// A proposal transaction stored in program accounts.
+pub enum StoredTransaction {
+    SquadsV4VaultTransaction(Pubkey),
+    SquadsV4BatchTransaction(Pubkey),
+    /// Accounts which may be the instructions of a Squads v3 transaction.
+    SquadsV3Instructions(Vec<Pubkey>),
+    GovernanceProposalTransaction(Pubkey),
+}
+
+pub fn decode(
+    program_id: &Pubkey,
+    accounts: &[Option<Pubkey>],
+    data: &[u8],
+) -> Option<DecodedInstruction> {
+    if *program_id == SQUADS_V4 {
+        Some(decode_squads_v4(accounts, data))
+    } else if *program_id == SQUADS_V3 {
+        Some(decode_squads_v3(accounts, data))
+    } else if *program_id == SPL_GOVERNANCE {
+        Some(decode_governance(accounts, data))
+    } else {
+        None
+    }
+}
+
+fn decode_squads_v4(accounts: &[Option<Pubkey>], data: &[u8]) -> DecodedInstruction {
+    let name = anchor_instruction_name(SQUADS_V4_INSTRUCTIONS, data);
+    let inner = match name {
+        Some("vault_transaction_create") => {
+            // Args: vault index, ephemeral signers, then the message bytes.
+            let mut reader = Reader::new(&data[8..]);
+            reader
+                .bytes(2)
+                .and_then(|_| reader.vec_u8())
+                .and_then(|message| {
+                    read_squads_v4_message(&mut Reader::new(message), LengthPrefix::Small)
+                })
+                .map(|message| InnerTransactionSource::Inline(unsigned_transaction(message)))
+        }
+        Some("vault_transaction_execute") => account(accounts, 2).map(|transaction| {
+            InnerTransactionSource::Accounts(StoredTransaction::SquadsV4VaultTransaction(
+                transaction,
+            ))
+        }),
+        Some("batch_execute_transaction") => account(accounts, 4).map(|transaction| {
+            InnerTransactionSource::Accounts(StoredTransaction::SquadsV4BatchTransaction(
+                transaction,
+            ))
+        }),
+        _ => None,
+    };
+    DecodedInstruction {
+        program: "Squads v4",
+        name: name.unwrap_or("unknown").to_string(),
+        inner,
+        fields: vec![],
+        account_names: vec![],
+    }
+}
+
+fn decode_squads_v3(accounts: &[Option<Pubkey>], data: &[u8]) -> DecodedInstruction {
+    let name = anchor_instruction_name(SQUADS_V3_INSTRUCTIONS, data);
+    let inner = match name {
+        Some("add_instruction") => {
+            read_instruction(&mut Reader::new(&data[8..])).map(|instruction| {
+                InnerTransactionSource::Inline(instructions_transaction(&[instruction]))
+            })
+        }
+        // Instruction accounts follow the multisig, transaction and member.
+        Some("execute_transaction") => Some(InnerTransactionSource::Accounts(
+            StoredTransaction::SquadsV3Instructions(
+                accounts.iter().skip(3).copied().flatten().collect(),
+            ),
+        )),
+        Some("execute_instruction") => account(accounts, 2).map(|instruction| {
+            InnerTransactionSource::Accounts(StoredTransaction::SquadsV3Instructions(vec![
+                instruction,
+            ]))
+        }),
+        _ => None,
+    };
+    DecodedInstruction {
+        program: "Squads v3",
+        name: name.unwrap_or("unknown").to_string(),
+        inner,
+        fields: vec![],
+        account_names: vec![],
+    }
+}
+
+fn decode_governance(accounts: &[Option<Pubkey>], data: &[u8]) -> DecodedInstruction {
+    let mut reader = Reader::new(data);
+    let name = reader
+        .u8()
+        .and_then(|index| GOVERNANCE_INSTRUCTIONS.get(index as usize))
+        .copied();
+    let inner = match name {
+        Some("InsertTransaction") => {
+            // Args: option index, transaction index, hold up time.
+            reader
+                .bytes(7)
+                .and_then(|_| read_instructions(&mut reader))
+                .map(|instructions| {
+                    InnerTransactionSource::Inline(instructions_transaction(&instructions))
+                })
+        }
+        Some("ExecuteTransaction") => account(accounts, 2).map(|proposal_transaction| {
+            InnerTransactionSource::Accounts(StoredTransaction::GovernanceProposalTransaction(
+                proposal_transaction,
+            ))
+        }),
+        _ => None,
+    };
+    DecodedInstruction {
+        program: "SPL Governance",
+        name: name.unwrap_or("unknown").to_string(),
+        inner,
+        fields: vec![],
+        account_names: vec![],
+    }
+}
+
+/// Gets accounts by pubkey, `None` for those which do not exist.
+pub type GetAccounts<'a> = dyn FnMut(&[Pubkey]) -> Result<Vec<Option<Account>>, String> + 'a;
+
+/// Fetch the accounts holding a stored transaction with `get_accounts` and
+/// reconstruct it.
+pub fn fetch(
+    get_accounts: &mut GetAccounts,
+    stored: &StoredTransaction,
+) -> Result<VersionedTransaction, String> {
+    let mut fetch_data = |pubkey: &Pubkey| {
+        get_accounts(&[*pubkey])?
+            .pop()
+            .flatten()
+            .map(|account| account.data)
+            .ok_or_else(|| format!("Account {pubkey} not found"))
+    };
+    let invalid = |pubkey: &Pubkey| format!("Account {pubkey} is not a valid proposal transaction");
+    match stored {
+        StoredTransaction::SquadsV4VaultTransaction(pubkey) => {
+            let data = fetch_data(pubkey)?;
+            let mut reader = Reader::new(&data);
+            // Discriminator, multisig, creator, index, bump, vault index and
+            // vault bump, then the ephemeral signer bumps.
+            reader
+                .bytes(8 + 32 + 32 + 8 + 3)
+                .and_then(|_| reader.vec_u8())
+                .and_then(|_| read_squads_v4_message(&mut reader, LengthPrefix::Borsh))
+                .map(unsigned_transaction)
+                .ok_or_else(|| invalid(pubkey))
+        }
+        StoredTransaction::SquadsV4BatchTransaction(pubkey) => {
+            let data = fetch_data(pubkey)?;
+            let mut reader = Reader::new(&data);
+            // Discriminator and bump, then the ephemeral signer bumps.
+            reader
+                .bytes(8 + 1)
+                .and_then(|_| reader.vec_u8())
+                .and_then(|_| read_squads_v4_message(&mut reader, LengthPrefix::Borsh))
+                .map(unsigned_transaction)
+                .ok_or_else(|| invalid(pubkey))
+        }
+        StoredTransaction::SquadsV3Instructions(pubkeys) => {
+            let discriminator = &hash(b"account:MsInstruction").to_bytes()[..8];
+            let accounts = get_accounts(pubkeys)?;
+            let mut instructions = accounts
+                .into_iter()
+                .flatten()
+                .filter(|account| {
+                    account.owner == SQUADS_V3 && account.data.starts_with(discriminator)
+                })
+                .filter_map(|account| {
+                    let mut reader = Reader::new(&account.data[8..]);
+                    let instruction = read_instruction(&mut reader)?;
+                    let instruction_index = reader.u8()?;
+                    Some((instruction_index, instruction))
+                })
+                .collect::<Vec<_>>();
+            if instructions.is_empty() {
+                return Err("No Squads v3 instruction accounts found".to_string());
+            }
+            instructions.sort_by_key(|(instruction_index, _)| *instruction_index);
+            let instructions = instructions
+                .into_iter()
+                .map(|(_, instruction)| instruction)
+                .collect::<Vec<_>>();
+            Ok(instructions_transaction(&instructions))
+        }
+        StoredTransaction::GovernanceProposalTransaction(pubkey) => {
+            let data = fetch_data(pubkey)?;
+            let mut reader = Reader::new(&data);
+            // Account type, proposal, option index, transaction index and
+            // hold up time.
+            reader
+                .bytes(1 + 32 + 1 + 2 + 4)
+                .and_then(|_| read_instructions(&mut reader))
+                .map(|instructions| instructions_transaction(&instructions))
+                .ok_or_else(|| invalid(pubkey))
+        }
+    }
+}
+
+fn account(accounts: &[Option<Pubkey>], index: usize) -> Option<Pubkey> {
+    accounts.get(index).copied().flatten()
+}
+
+fn anchor_instruction_name(names: &[&'static str], data: &[u8]) -> Option<&'static str> {
+    let discriminator = data.get(..8)?;
+    names
+        .iter()
+        .find(|name| anchor_discriminator(name) == discriminator)
+        .copied()
+}
+
+/// How the vectors of a Squads v4 message are length prefixed: compact
+/// (u8, or u16 for instruction data) in instruction arguments, and borsh
+/// (u32) once stored in an account.
+#[derive(Clone, Copy)]
+enum LengthPrefix {
+    Small,
+    Borsh,
+}
+
+impl LengthPrefix {
+    fn read(self, reader: &mut Reader, is_data: bool) -> Option<usize> {
+        match (self, is_data) {
+            (Self::Small, false) => reader.u8().map(usize::from),
+            (Self::Small, true) => reader.u16().map(usize::from),
+            (Self::Borsh, _) => reader.u32().map(|len| len as usize),
+        }
+    }
+
+    fn read_bytes<'a>(self, reader: &mut Reader<'a>, is_data: bool) -> Option<&'a [u8]> {
+        let len = self.read(reader, is_data)?;
+        reader.bytes(len)
+    }
+}
+
+fn read_squads_v4_message(
+    reader: &mut Reader,
+    length_prefix: LengthPrefix,
+) -> Option<VersionedMessage> {
+    let num_signers = reader.u8()?;
+    let num_writable_signers = reader.u8()?;
+    let num_writable_non_signers = reader.u8()?;
+    let account_keys = (0..length_prefix.read(reader, false)?)
+        .map(|_| reader.pubkey())
+        .collect::<Option<Vec<_>>>()?;
+    let instructions = (0..length_prefix.read(reader, false)?)
+        .map(|_| {
+            Some(CompiledInstruction {
+                program_id_index: reader.u8()?,
+                accounts: length_prefix.read_bytes(reader, false)?.to_vec(),
+                data: length_prefix.read_bytes(reader, true)?.to_vec(),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let address_table_lookups = (0..length_prefix.read(reader, false)?)
+        .map(|_| {
+            Some(v0::MessageAddressTableLookup {
+                account_key: reader.pubkey()?,
+                writable_indexes: length_prefix.read_bytes(reader, false)?.to_vec(),
+                readonly_indexes: length_prefix.read_bytes(reader, false)?.to_vec(),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let num_readonly_unsigned_accounts = u8::try_from(account_keys.len())
+        .ok()?
+        .checked_sub(num_signers)?
+        .checked_sub(num_writable_non_signers)?;
+    let header = MessageHeader {
+        num_required_signatures: num_signers,
+        num_readonly_signed_accounts: num_signers.checked_sub(num_writable_signers)?,
+        num_readonly_unsigned_accounts,
+    };
+    // The blockhash is provided by the executing transaction.
+    Some(if address_table_lookups.is_empty() {
+        VersionedMessage::Legacy(Message {
+            header,
+            account_keys,
+            recent_blockhash: Hash::default(),
+            instructions,
+        })
+    } else {
+        VersionedMessage::V0(v0::Message {
+            header,
+            account_keys,
+            recent_blockhash: Hash::default(),
+            instructions,
+            address_table_lookups,
+        })
+    })
+}
+
+/// A borsh instruction: program id, account metas and data.
+fn read_instruction(reader: &mut Reader) -> Option<Instruction> {
+    let program_id = reader.pubkey()?;
+    let accounts = (0..reader.u32()?)
+        .map(|_| {
+            Some(AccountMeta {
+                pubkey: reader.pubkey()?,
+                is_signer: reader.bool()?,
+                is_writable: reader.bool()?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let data = reader.vec_u8()?.to_vec();
+    Some(Instruction {
+        program_id,
+        accounts,
+        data,
+    })
+}
+
+fn read_instructions(reader: &mut Reader) -> Option<Vec<Instruction>> {
+    (0..reader.u32()?)
+        .map(|_| read_instruction(reader))
+        .collect()
+}
+
+fn instructions_transaction(instructions: &[Instruction]) -> VersionedTransaction {
+    unsigned_transaction(VersionedMessage::Legacy(Message::new(instructions, None)))
+}
+
+fn unsigned_transaction(message: VersionedMessage) -> VersionedTransaction {
+    let num_required_signatures = message.header().num_required_signatures as usize;
+    VersionedTransaction {
+        signatures: vec![Signature::default(); num_required_signatures],
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::decoders::tests::decode_prefixes};
+
+    /// A borsh `Vec<u8>`.
+    fn borsh_bytes(bytes: &[u8]) -> Vec<u8> {
+        [&(bytes.len() as u32).to_le_bytes(), bytes].concat()
+    }
+
+    /// A borsh instruction, as Squads v3 and SPL Governance store them.
+    fn borsh_instruction(instruction: &Instruction) -> Vec<u8> {
+        let mut bytes = instruction.program_id.to_bytes().to_vec();
+        bytes.extend((instruction.accounts.len() as u32).to_le_bytes());
+        for meta in &instruction.accounts {
+            bytes.extend(meta.pubkey.to_bytes());
+            bytes.extend([meta.is_signer as u8, meta.is_writable as u8]);
+        }
+        bytes.extend(borsh_bytes(&instruction.data));
+        bytes
+    }
+
+    fn instruction() -> Instruction {
+        Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1, 2, 3],
+            vec![
+                AccountMeta::new(Pubkey::new_unique(), true),
+                AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            ],
+        )
+    }
+
+    /// A Squads v4 message of a vault signing an instruction of `program`,
+    /// with compact length prefixes as in instruction arguments.
+    fn squads_v4_message(vault: Pubkey, account: Pubkey, program: Pubkey) -> Vec<u8> {
+        let mut bytes = vec![1, 1, 1, 3];
+        for key in [vault, account, program] {
+            bytes.extend(key.to_bytes());
+        }
+        bytes.extend([1, 2, 2, 0, 1]);
+        bytes.extend(3u16.to_le_bytes());
+        bytes.extend([7, 8, 9, 0]);
+        bytes
+    }
+
+    /// The single instruction of a decoded inline transaction.
+    fn inline_instruction(decoded: &DecodedInstruction) -> (Vec<Pubkey>, CompiledInstruction) {
+        let Some(InnerTransactionSource::Inline(transaction)) = &decoded.inner else {
+            panic!("{} has no inline transaction", decoded.name);
+        };
+        let account_keys = transaction.message.static_account_keys().to_vec();
+        (account_keys, transaction.message.instructions()[0].clone())
+    }
+
+    #[test]
+    fn decodes_squads_v4_vault_transactions() {
+        let (vault, account, program) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let message = squads_v4_message(vault, account, program);
+        let data = [
+            &anchor_discriminator("vault_transaction_create")[..],
+            &[0, 0],
+            &borsh_bytes(&message),
+        ]
+        .concat();
+        let decoded = decode(&SQUADS_V4, &[], &data).unwrap();
+        assert_eq!(decoded.name, "vault_transaction_create");
+        let (account_keys, instruction) = inline_instruction(&decoded);
+        assert_eq!(account_keys, [vault, account, program]);
+        assert_eq!(instruction.accounts, [0, 1]);
+        assert_eq!(instruction.data, [7, 8, 9]);
+
+        // Stored in an account, the message's vectors are borsh prefixed.
+        let mut stored = vec![0; 8 + 32 + 32 + 8 + 3];
+        stored.extend(borsh_bytes(&[]));
+        stored.extend([1, 1, 1]);
+        stored.extend(3u32.to_le_bytes());
+        for key in [vault, account, program] {
+            stored.extend(key.to_bytes());
+        }
+        stored.extend(1u32.to_le_bytes());
+        stored.extend([2]);
+        stored.extend(borsh_bytes(&[0, 1]));
+        stored.extend(borsh_bytes(&[7, 8, 9]));
+        stored.extend(0u32.to_le_bytes());
+        let vault_transaction = Pubkey::new_unique();
+        let execute = anchor_discriminator("vault_transaction_execute");
+        let decoded = decode(&SQUADS_V4, &[None, None, Some(vault_transaction)], &execute).unwrap();
+        let Some(InnerTransactionSource::Accounts(stored_transaction)) = &decoded.inner else {
+            panic!("vault_transaction_execute has no stored transaction");
+        };
+        let mut get_accounts = |_: &[Pubkey]| {
+            Ok(vec![Some(Account {
+                data: stored.clone(),
+                ..Account::default()
+            })])
+        };
+        let transaction = fetch(&mut get_accounts, stored_transaction).unwrap();
+        assert_eq!(
+            transaction.message.static_account_keys(),
+            [vault, account, program]
+        );
+        assert_eq!(transaction.message.instructions()[0].data, [7, 8, 9]);
+    }
+
+    #[test]
+    fn decodes_squads_v3_instructions() {
+        let inner = instruction();
+        let data = [
+            &anchor_discriminator("add_instruction")[..],
+            &borsh_instruction(&inner),
+        ]
+        .concat();
+        let decoded = decode(&SQUADS_V3, &[], &data).unwrap();
+        assert_eq!(decoded.program, "Squads v3");
+        let (account_keys, instruction) = inline_instruction(&decoded);
+        assert_eq!(
+            account_keys[instruction.program_id_index as usize],
+            inner.program_id
+        );
+        assert_eq!(instruction.data, inner.data);
+
+        // Executed instructions are read from their accounts, in order.
+        let stored = [
+            &hash(b"account:MsInstruction").to_bytes()[..8],
+            &borsh_instruction(&inner),
+            &[0],
+        ]
+        .concat();
+        let mut get_accounts = |pubkeys: &[Pubkey]| {
+            Ok(vec![
+                Some(Account {
+                    owner: SQUADS_V3,
+                    data: stored.clone(),
+                    ..Account::default()
+                });
+                pubkeys.len()
+            ])
+        };
+        let stored_transaction =
+            StoredTransaction::SquadsV3Instructions(vec![Pubkey::new_unique()]);
+        let transaction = fetch(&mut get_accounts, &stored_transaction).unwrap();
+        assert_eq!(transaction.message.instructions()[0].data, inner.data);
+    }
+
+    #[test]
+    fn decodes_governance_proposal_transactions() {
+        let inner = instruction();
+        let mut data = vec![9, 0, 0, 0, 0, 0, 0, 0];
+        data.extend(1u32.to_le_bytes());
+        data.extend(borsh_instruction(&inner));
+        let decoded = decode(&SPL_GOVERNANCE, &[], &data).unwrap();
+        assert_eq!(decoded.name, "InsertTransaction");
+        let (account_keys, instruction) = inline_instruction(&decoded);
+        assert_eq!(
+            account_keys[instruction.accounts[0] as usize],
+            inner.accounts[0].pubkey
+        );
+
+        let mut stored = vec![0; 1 + 32 + 1 + 2 + 4];
+        stored.extend(1u32.to_le_bytes());
+        stored.extend(borsh_instruction(&inner));
+        let mut get_accounts = |_: &[Pubkey]| {
+            Ok(vec![Some(Account {
+                data: stored.clone(),
+                ..Account::default()
+            })])
+        };
+        let stored_transaction =
+            StoredTransaction::GovernanceProposalTransaction(Pubkey::new_unique());
+        let transaction = fetch(&mut get_accounts, &stored_transaction).unwrap();
+        assert_eq!(transaction.message.instructions()[0].data, inner.data);
+    }
+
+    #[test]
+    fn names_unknown_instructions() {
+        let accounts = [Some(Pubkey::new_unique()); 5];
+        for program_id in [SQUADS_V4, SQUADS_V3] {
+            for data in [
+                &[][..],
+                &[0; 8],
+                &anchor_discriminator("add_instruction")[..7],
+            ] {
+                let decoded = decode(&program_id, &accounts, data).unwrap();
+                assert_eq!(decoded.name, "unknown");
+                assert!(decoded.inner.is_none());
+            }
+        }
+        // Governance tags its instructions by index.
+        let past_the_last = [GOVERNANCE_INSTRUCTIONS.len() as u8];
+        for data in [&[][..], &past_the_last] {
+            assert_eq!(decode(&SPL_GOVERNANCE, &[], data).unwrap().name, "unknown");
+        }
+        assert!(decode(&Pubkey::new_unique(), &[], &[0; 8]).is_none());
+    }
+
+    #[test]
+    fn reads_squads_v4_messages_with_lookups_and_long_data() {
+        let (vault, program, table) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        // Instruction data is prefixed by a u16, so it may exceed 255 bytes.
+        let mut message = vec![1, 1, 0, 2];
+        message.extend(vault.to_bytes());
+        message.extend(program.to_bytes());
+        message.extend([1, 1, 1, 0]);
+        message.extend(300u16.to_le_bytes());
+        message.extend([5; 300]);
+        message.push(1);
+        message.extend(table.to_bytes());
+        message.extend([1, 4, 2, 5, 6]);
+        let data = [
+            &anchor_discriminator("vault_transaction_create")[..],
+            &[0, 0],
+            &borsh_bytes(&message),
+        ]
+        .concat();
+        let decoded = decode(&SQUADS_V4, &[], &data).unwrap();
+        let Some(InnerTransactionSource::Inline(transaction)) = &decoded.inner else {
+            panic!("vault_transaction_create has no inline transaction");
+        };
+        let VersionedMessage::V0(message) = &transaction.message else {
+            panic!("a message with lookups is not a v0 message");
+        };
+        assert_eq!(message.instructions[0].data.len(), 300);
+        assert_eq!(message.address_table_lookups[0].account_key, table);
+        assert_eq!(message.address_table_lookups[0].writable_indexes, [4]);
+        assert_eq!(message.address_table_lookups[0].readonly_indexes, [5, 6]);
+        assert_eq!(message.header.num_readonly_unsigned_accounts, 1);
+
+        // A header counting more signers than there are keys is no message.
+        let mut too_many_signers = data.clone();
+        too_many_signers[8 + 2 + 4] = 3;
+        assert!(decode(&SQUADS_V4, &[], &too_many_signers)
+            .unwrap()
+            .inner
+            .is_none());
+    }
+
+    #[test]
+    fn finds_stored_transactions_among_resolved_accounts() {
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let execute = anchor_discriminator("vault_transaction_execute");
+        // The transaction account is the third, and must be resolved.
+        for accounts in [&[Some(first), Some(second)][..], &[None, None, None]] {
+            assert!(decode(&SQUADS_V4, accounts, &execute)
+                .unwrap()
+                .inner
+                .is_none());
+        }
+
+        // Squads v3 instruction accounts follow the multisig, transaction
+        // and member, skipping those not resolved.
+        let accounts = [None, None, None, Some(first), None, Some(second)];
+        let data = anchor_discriminator("execute_transaction");
+        let decoded = decode(&SQUADS_V3, &accounts, &data).unwrap();
+        let Some(InnerTransactionSource::Accounts(StoredTransaction::SquadsV3Instructions(
+            pubkeys,
+        ))) = decoded.inner
+        else {
+            panic!("execute_transaction has no stored instructions");
+        };
+        assert_eq!(pubkeys, [first, second]);
+    }
+
+    #[test]
+    fn orders_squads_v3_instructions_and_skips_other_accounts() {
+        let (first, second) = (instruction(), instruction());
+        let stored = |instruction: &Instruction, index: u8| {
+            [
+                &hash(b"account:MsInstruction").to_bytes()[..8],
+                &borsh_instruction(instruction),
+                &[index],
+            ]
+            .concat()
+        };
+        let account = |owner, data| {
+            Some(Account {
+                owner,
+                data,
+                ..Account::default()
+            })
+        };
+        let accounts = vec![
+            account(SQUADS_V3, stored(&second, 1)),
+            account(Pubkey::new_unique(), stored(&first, 0)),
+            None,
+            account(SQUADS_V3, stored(&first, 0)),
+        ];
+        let pubkeys = StoredTransaction::SquadsV3Instructions(vec![Pubkey::new_unique(); 4]);
+        let transaction = fetch(&mut |_| Ok(accounts.clone()), &pubkeys).unwrap();
+        let data = transaction
+            .message
+            .instructions()
+            .iter()
+            .map(|instruction| instruction.data.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(data, [first.data.clone(), second.data]);
+
+        let not_squads = vec![account(Pubkey::new_unique(), stored(&first, 0))];
+        assert_eq!(
+            fetch(&mut |_| Ok(not_squads.clone()), &pubkeys),
+            Err("No Squads v3 instruction accounts found".to_string())
+        );
+    }
+
+    #[test]
+    fn inlines_only_whole_instructions() {
+        let inner = borsh_instruction(&instruction());
+        let message = squads_v4_message(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let instructions = [
+            (
+                SQUADS_V4,
+                [
+                    &anchor_discriminator("vault_transaction_create")[..],
+                    &[0, 0],
+                    &borsh_bytes(&message),
+                ]
+                .concat(),
+            ),
+            (
+                SQUADS_V3,
+                [&anchor_discriminator("add_instruction")[..], &inner].concat(),
+            ),
+            (
+                SPL_GOVERNANCE,
+                [&[9, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0][..], &inner].concat(),
+            ),
+        ];
+        for (program_id, data) in instructions {
+            let prefixes = decode_prefixes(&data, |data| decode(&program_id, &[], data));
+            assert!(prefixes
+                .iter()
+                .flatten()
+                .all(|decoded| decoded.inner.is_none()));
+            assert!(decode(&program_id, &[], &data).unwrap().inner.is_some());
+        }
+
+        // A stored account of only a discriminator holds no transaction.
+        let pubkey = Pubkey::new_unique();
+        let discriminator_only = vec![Some(Account {
+            data: vec![0; 8],
+            ..Account::default()
+        })];
+        for stored_transaction in [
+            StoredTransaction::SquadsV4VaultTransaction(pubkey),
+            StoredTransaction::SquadsV4BatchTransaction(pubkey),
+            StoredTransaction::GovernanceProposalTransaction(pubkey),
+        ] {
+            assert_eq!(
+                fetch(&mut |_| Ok(discriminator_only.clone()), &stored_transaction),
+                Err(format!(
+                    "Account {pubkey} is not a valid proposal transaction"
+                ))
+            );
+        }
+        assert_eq!(
+            fetch(
+                &mut |_| Ok(vec![None]),
+                &StoredTransaction::SquadsV4VaultTransaction(pubkey)
+            ),
+            Err(format!("Account {pubkey} not found"))
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Token-2022 transfers, including the accounts a mint's transfer hook adds.
+//! Clients resolve a hook's extra accounts from its `extra-account-metas`
+//! PDA and append them to the transfer, followed by the hook program and
+//! that PDA, which Token-2022 passes on when it invokes the hook.
+
+use {
+    super::{read_field, DataField, DecodedInstruction, Reader},
+    solana_sdk::{instruction::CompiledInstruction, pubkey, pubkey::Pubkey},
+    std::{collections::HashMap, ops::Range},
+};
+
+pub const TOKEN_2022: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+const TRANSFER_CHECKED: u8 = 12;
+const TRANSFER_FEE_EXTENSION: u8 = 26;
+const TRANSFER_CHECKED_WITH_FEE: u8 = 1;
+
+const TRANSFER_ACCOUNTS: [&str; 4] = ["source", "mint", "destination", "authority"];
+
+/// The positions of a transfer's accounts which its mint's transfer hook
+/// added.
+pub struct HookAccounts {
+    /// The accounts resolved from the extra account metas, along with any
+    /// multisig signers, which precede them.
+    pub extras: Range<usize>,
+    pub program: usize,
+    pub validation: usize,
+}
+
+/// The address of the account holding the extra account metas of a mint's
+/// transfer hook.
+pub fn extra_account_metas_address(mint: &Pubkey, hook_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], hook_program).0
+}
+
+/// Find the hook program of a transfer by the extra account metas PDA which
+/// follows it.
+pub fn hook_accounts(accounts: &[Option<Pubkey>]) -> Option<HookAccounts> {
+    let mint = accounts.get(1).copied().flatten()?;
+    let first_extra = TRANSFER_ACCOUNTS.len();
+    (first_extra..accounts.len().saturating_sub(1)).find_map(|program| {
+        let (hook_program, validation) = (accounts[program]?, accounts[program + 1]?);
+        (extra_account_metas_address(&mint, &hook_program) == validation).then_some(HookAccounts {
+            extras: first_extra..program,
+            program,
+            validation: program + 1,
+        })
+    })
+}
+
+pub fn decode(
+    program_id: &Pubkey,
+    accounts: &[Option<Pubkey>],
+    data: &[u8],
+) -> Option<DecodedInstruction> {
+    if *program_id != TOKEN_2022 {
+        return None;
+    }
+    let (name, tag_len, args): (_, _, &[_]) = match data {
+        [TRANSFER_CHECKED, ..] => ("transfer_checked", 1, &[("amount", 8), ("decimals", 1)]),
+        [TRANSFER_FEE_EXTENSION, TRANSFER_CHECKED_WITH_FEE, ..] => (
+            "transfer_checked_with_fee",
+            2,
+            &[("amount", 8), ("decimals", 1), ("fee", 8)],
+        ),
+        _ => return None,
+    };
+
+    let mut fields = vec![DataField {
+        name: "instruction".to_string(),
+        range: 0..tag_len,
+    }];
+    let mut reader = Reader::new(&data[tag_len..]);
+    for (name, len) in args {
+        if read_field(&mut reader, &mut fields, name.to_string(), *len).is_none() {
+            break;
+        }
+    }
+
+    let mut account_names = TRANSFER_ACCOUNTS.map(ToString::to_string).to_vec();
+    let mut name = name.to_string();
+    if let Some(hook) = hook_accounts(accounts) {
+        account_names.extend(
+            hook.extras
+                .clone()
+                .map(|position| format!("hook extra[{}]", position - hook.extras.start)),
+        );
+        account_names.push("transfer_hook_program".to_string());
+        account_names.push("extra_account_metas".to_string());
+        name = format!(
+            "{name} through a transfer hook, with extra accounts from its extra account metas: {}",
+            hook.extras.len()
+        );
+    }
+    Some(DecodedInstruction {
+        program: "Token-2022",
+        name,
+        inner: None,
+        fields,
+        account_names,
+    })
+}
+
+/// The roles of the accounts which transfer hooks added to the
+/// transaction's Token-2022 transfers, by account key.
+pub fn transfer_hook_roles(
+    account_keys: &[Pubkey],
+    instructions: &[CompiledInstruction],
+) -> HashMap<Pubkey, String> {
+    let mut roles = HashMap::new();
+    for (index, instruction) in instructions.iter().enumerate() {
+        let key = |index: &u8| account_keys.get(*index as usize).copied();
+        let accounts = instruction.accounts.iter().map(key).collect::<Vec<_>>();
+        let is_transfer = key(&instruction.program_id_index)
+            .is_some_and(|program_id| decode(&program_id, &accounts, &instruction.data).is_some());
+        let Some(hook) = hook_accounts(&accounts).filter(|_| is_transfer) else {
+            continue;
+        };
+        let mut role = |position: usize, role: &str| {
+            if let Some(pubkey) = accounts[position] {
+                roles
+                    .entry(pubkey)
+                    .or_insert_with(|| format!("{role} of #{index}"));
+            }
+        };
+        for position in hook.extras {
+            role(position, "transfer hook extra");
+        }
+        role(hook.program, "transfer hook");
+        role(hook.validation, "extra account metas");
+    }
+    roles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_the_accounts_of_transfer_hooks() {
+        let keys = (0..6).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        let (mint, hook_program, extra) = (keys[1], keys[4], keys[5]);
+        let validation = extra_account_metas_address(&mint, &hook_program);
+        let account_keys = [keys.as_slice(), &[validation, TOKEN_2022]].concat();
+        let data = [&[TRANSFER_CHECKED][..], &5u64.to_le_bytes(), &[6]].concat();
+        let instruction =
+            CompiledInstruction::new_from_raw_parts(7, data, vec![0, 1, 2, 3, 5, 4, 6]);
+
+        let accounts = instruction
+            .accounts
+            .iter()
+            .map(|index| Some(account_keys[*index as usize]))
+            .collect::<Vec<_>>();
+        let decoded = decode(&TOKEN_2022, &accounts, &instruction.data).unwrap();
+        assert_eq!(
+            decoded.name,
+            "transfer_checked through a transfer hook, with extra accounts from its extra account \
+             metas: 1"
+        );
+        assert_eq!(
+            decoded.account_names[4..],
+            [
+                "hook extra[0]",
+                "transfer_hook_program",
+                "extra_account_metas"
+            ]
+        );
+        assert_eq!(decoded.field_at(9), Some("decimals"));
+
+        let roles = transfer_hook_roles(&account_keys, &[instruction]);
+        assert_eq!(roles[&extra], "transfer hook extra of #0");
+        assert_eq!(roles[&hook_program], "transfer hook of #0");
+        assert_eq!(roles[&validation], "extra account metas of #0");
+        assert_eq!(roles.len(), 3);
+
+        // Without a hook, a transfer has only its own accounts.
+        let decoded = decode(&TOKEN_2022, &accounts[..4], &[TRANSFER_CHECKED]).unwrap();
+        assert_eq!(decoded.account_names.len(), 4);
+        assert_eq!(decoded.name, "transfer_checked");
+    }
+}
@@ -0,0 +1,518 @@
+//! Wormhole Core Bridge and Token Bridge instructions, and the secp256k1
+//! instructions carrying guardian signatures. A VAA reaches Solana in three
+//! instructions: a secp256k1 instruction holding the guardians' signatures
+//! of its hash, `verify_signatures` recording which guardians signed, and
+//! `post_vaa` carrying its body, whose payload the Token Bridge redeems.
+
+use {
+    super::{read_field, DataField, DecodedInstruction, Reader},
+    solana_sdk::{
+        pubkey,
+        pubkey::Pubkey,
+        secp256k1_instruction::{SecpSignatureOffsets, SIGNATURE_OFFSETS_SERIALIZED_SIZE},
+        secp256k1_program,
+    },
+};
+
+pub const CORE_BRIDGE: Pubkey = pubkey!("worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth");
+pub const TOKEN_BRIDGE: Pubkey = pubkey!("wormDTUJ6AWPNvk59vGQbDvGJmqbDTdgWgAqcLBCgUb");
+
+#[derive(Clone, Copy)]
+enum Arg {
+    U8,
+    U16,
+    U32,
+    U64,
+    Bytes(usize),
+    /// A borsh `Vec<u8>`.
+    Payload,
+    /// A borsh `Option<Pubkey>`.
+    OptionalPubkey,
+}
+
+struct Layout {
+    name: &'static str,
+    args: &'static [(&'static str, Arg)],
+    accounts: &'static [&'static str],
+}
+
+const fn layout(name: &'static str) -> Layout {
+    Layout {
+        name,
+        args: &[],
+        accounts: &[],
+    }
+}
+
+const POST_MESSAGE_ARGS: &[(&str, Arg)] = &[
+    ("nonce", Arg::U32),
+    ("payload", Arg::Payload),
+    ("consistency_level", Arg::U8),
+];
+
+const POST_MESSAGE_ACCOUNTS: &[&str] = &[
+    "bridge",
+    "message",
+    "emitter",
+    "sequence",
+    "payer",
+    "fee_collector",
+    "clock",
+];
+
+/// Core Bridge instructions, by their tag.
+const CORE_BRIDGE_INSTRUCTIONS: &[Layout] = &[
+    layout("initialize"),
+    Layout {
+        name: "post_message",
+        args: POST_MESSAGE_ARGS,
+        accounts: POST_MESSAGE_ACCOUNTS,
+    },
+    Layout {
+        name: "post_vaa",
+        args: &[
+            ("version", Arg::U8),
+            ("guardian_set_index", Arg::U32),
+            ("timestamp", Arg::U32),
+            ("nonce", Arg::U32),
+            ("emitter_chain", Arg::U16),
+            ("emitter_address", Arg::Bytes(32)),
+            ("sequence", Arg::U64),
+            ("consistency_level", Arg::U8),
+            ("payload", Arg::Payload),
+        ],
+        accounts: &[
+            "guardian_set",
+            "bridge",
+            "signature_set",
+            "posted_vaa",
+            "payer",
+            "clock",
+            "rent",
+            "system_program",
+        ],
+    },
+    layout("set_fees"),
+    layout("transfer_fees"),
+    layout("upgrade_contract"),
+    layout("upgrade_guardian_set"),
+    Layout {
+        name: "verify_signatures",
+        args: &[("signers", Arg::Bytes(19))],
+        accounts: &[
+            "payer",
+            "guardian_set",
+            "signature_set",
+            "instructions",
+            "rent",
+            "system_program",
+        ],
+    },
+    Layout {
+        name: "post_message_unreliable",
+        args: POST_MESSAGE_ARGS,
+        accounts: POST_MESSAGE_ACCOUNTS,
+    },
+];
+
+const TRANSFER_ARGS: &[(&str, Arg)] = &[
+    ("nonce", Arg::U32),
+    ("amount", Arg::U64),
+    ("fee", Arg::U64),
+    ("target_address", Arg::Bytes(32)),
+    ("target_chain", Arg::U16),
+];
+
+const TRANSFER_WITH_PAYLOAD_ARGS: &[(&str, Arg)] = &[
+    ("nonce", Arg::U32),
+    ("amount", Arg::U64),
+    ("target_address", Arg::Bytes(32)),
+    ("target_chain", Arg::U16),
+    ("payload", Arg::Payload),
+    ("cpi_program_id", Arg::OptionalPubkey),
+];
+
+/// Token Bridge instructions, by their tag. Redeeming instructions take the
+/// posted VAA as an account rather than in their data.
+const TOKEN_BRIDGE_INSTRUCTIONS: &[Layout] = &[
+    layout("initialize"),
+    Layout {
+        name: "attest_token",
+        args: &[("nonce", Arg::U32)],
+        accounts: &[],
+    },
+    layout("complete_native"),
+    layout("complete_wrapped"),
+    Layout {
+        name: "transfer_wrapped",
+        args: TRANSFER_ARGS,
+        accounts: &[],
+    },
+    Layout {
+        name: "transfer_native",
+        args: TRANSFER_ARGS,
+        accounts: &[],
+    },
+    layout("register_chain"),
+    layout("create_wrapped"),
+    layout("upgrade_contract"),
+    layout("complete_native_with_payload"),
+    layout("complete_wrapped_with_payload"),
+    Layout {
+        name: "transfer_wrapped_with_payload",
+        args: TRANSFER_WITH_PAYLOAD_ARGS,
+        accounts: &[],
+    },
+    Layout {
+        name: "transfer_native_with_payload",
+        args: TRANSFER_WITH_PAYLOAD_ARGS,
+        accounts: &[],
+    },
+];
+
+/// The name of a Wormhole chain id.
+fn chain_name(chain: u16) -> String {
+    let name = match chain {
+        1 => "Solana",
+        2 => "Ethereum",
+        4 => "BNB Chain",
+        5 => "Polygon",
+        6 => "Avalanche",
+        10 => "Fantom",
+        14 => "Celo",
+        16 => "Moonbeam",
+        21 => "Sui",
+        22 => "Aptos",
+        23 => "Arbitrum",
+        24 => "Optimism",
+        30 => "Base",
+        _ => return format!("chain {chain}"),
+    };
+    name.to_string()
+}
+
+pub fn decode(program_id: &Pubkey, data: &[u8]) -> Option<DecodedInstruction> {
+    let (program, instructions) = match *program_id {
+        CORE_BRIDGE => ("Wormhole Core Bridge", CORE_BRIDGE_INSTRUCTIONS),
+        TOKEN_BRIDGE => ("Wormhole Token Bridge", TOKEN_BRIDGE_INSTRUCTIONS),
+        id if id == secp256k1_program::id() => return decode_signatures(data),
+        _ => return None,
+    };
+    let Some(layout) = data.first().and_then(|tag| instructions.get(*tag as usize)) else {
+        return Some(DecodedInstruction {
+            program,
+            name: "unknown".to_string(),
+            inner: None,
+            fields: vec![],
+            account_names: vec![],
+        });
+    };
+
+    let mut fields = vec![DataField {
+        name: "instruction".to_string(),
+        range: 0..1,
+    }];
+    let mut values = vec![];
+    let mut reader = Reader::new(&data[1..]);
+    for (name, arg) in layout.args {
+        let mut field = |name, len| read_field(&mut reader, &mut fields, name, len);
+        let value = match arg {
+            Arg::U8 => field(name.to_string(), 1),
+            Arg::U16 => field(name.to_string(), 2),
+            Arg::U32 => field(name.to_string(), 4),
+            Arg::U64 => field(name.to_string(), 8),
+            Arg::Bytes(len) => field(name.to_string(), *len),
+            Arg::Payload => field(format!("{name} length"), 4).and_then(|len| {
+                let len = u32::from_le_bytes(len.try_into().ok()?);
+                field(name.to_string(), len as usize)
+            }),
+            Arg::OptionalPubkey => field(format!("{name} option"), 1).and_then(|tag| match tag {
+                [0] => Some(tag),
+                _ => field(name.to_string(), 32),
+            }),
+        };
+        match value {
+            Some(value) => values.push((*name, value)),
+            None => break,
+        }
+    }
+
+    let value = |name| {
+        values
+            .iter()
+            .find(|(value, _)| *value == name)
+            .map(|(_, value)| *value)
+    };
+    let chain = |name| {
+        let chain = value(name)?.try_into().ok().map(u16::from_le_bytes)?;
+        Some(chain_name(chain))
+    };
+    let mut name = layout.name.to_string();
+    if let (Some(chain), Some(sequence)) = (chain("emitter_chain"), value("sequence")) {
+        let sequence = sequence.try_into().map_or(0, u64::from_le_bytes);
+        name = format!("{name} from {chain}, sequence {sequence}");
+    }
+    if let Some(chain) = chain("target_chain") {
+        name = format!("{name} to {chain}");
+    }
+    if let Some(signers) = value("signers") {
+        // Guardians which did not sign are marked -1.
+        let count = signers
+            .iter()
+            .filter(|signer| (**signer as i8) >= 0)
+            .count();
+        name = format!("{name} of {count} guardians");
+    }
+    Some(DecodedInstruction {
+        program,
+        name,
+        inner: None,
+        fields,
+        account_names: layout.accounts.iter().map(ToString::to_string).collect(),
+    })
+}
+
+/// Decode a secp256k1 instruction's signature offsets, and the signatures,
+/// Ethereum addresses and messages they point to, assuming they are in the
+/// instruction's own data, as Wormhole clients place them.
+fn decode_signatures(data: &[u8]) -> Option<DecodedInstruction> {
+    let count = *data.first()? as usize;
+    let mut fields = vec![DataField {
+        name: "count".to_string(),
+        range: 0..1,
+    }];
+    let mut pointed = vec![];
+    for index in 0..count {
+        let start = 1 + index * SIGNATURE_OFFSETS_SERIALIZED_SIZE;
+        let range = start..start + SIGNATURE_OFFSETS_SERIALIZED_SIZE;
+        let offsets =
+            bincode::deserialize::<SecpSignatureOffsets>(data.get(range.clone())?).ok()?;
+        fields.push(DataField {
+            name: format!("offsets[{index}]"),
+            range,
+        });
+        let signature = offsets.signature_offset as usize;
+        let address = offsets.eth_address_offset as usize;
+        let message = offsets.message_data_offset as usize;
+        pointed.extend([
+            (format!("signature[{index}]"), signature..signature + 65),
+            (format!("eth_address[{index}]"), address..address + 20),
+            (
+                format!("message[{index}]"),
+                message..message + offsets.message_data_size as usize,
+            ),
+        ]);
+    }
+    for (name, range) in pointed {
+        // Signatures of the same message share it.
+        let is_labeled = fields.iter().any(|field| field.range == range);
+        if range.end <= data.len() && !is_labeled {
+            fields.push(DataField { name, range });
+        }
+    }
+    fields.sort_by_key(|field| field.range.start);
+    Some(DecodedInstruction {
+        program: "Secp256k1",
+        name: format!("verify {count} signatures"),
+        inner: None,
+        fields,
+        account_names: vec![],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::decoders::tests::{decode_prefixes, fields},
+    };
+
+    /// A `post_vaa` from Ethereum of sequence 42 with a payload of three
+    /// bytes.
+    fn post_vaa() -> Vec<u8> {
+        [
+            &[2, 1][..],
+            &3u32.to_le_bytes(),
+            &1_700_000_000u32.to_le_bytes(),
+            &7u32.to_le_bytes(),
+            &2u16.to_le_bytes(),
+            &[9; 32],
+            &42u64.to_le_bytes(),
+            &[15],
+            &3u32.to_le_bytes(),
+            &[1, 2, 3],
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn decodes_the_instructions_of_a_vaa() {
+        let decoded = decode(&CORE_BRIDGE, &post_vaa()).unwrap();
+        assert_eq!(decoded.name, "post_vaa from Ethereum, sequence 42");
+        assert_eq!(
+            fields(&decoded),
+            [
+                ("instruction", 0..1),
+                ("version", 1..2),
+                ("guardian_set_index", 2..6),
+                ("timestamp", 6..10),
+                ("nonce", 10..14),
+                ("emitter_chain", 14..16),
+                ("emitter_address", 16..48),
+                ("sequence", 48..56),
+                ("consistency_level", 56..57),
+                ("payload length", 57..61),
+                ("payload", 61..64),
+            ]
+        );
+        assert_eq!(decoded.account_names[3], "posted_vaa");
+
+        let mut signers = [0xff; 19];
+        signers[..13].copy_from_slice(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+        let decoded = decode(&CORE_BRIDGE, &[&[7][..], &signers].concat()).unwrap();
+        assert_eq!(decoded.name, "verify_signatures of 13 guardians");
+
+        // Two signatures of one message, laid out after the offsets.
+        let offsets = |index: u16| SecpSignatureOffsets {
+            signature_offset: 23 + index * 85,
+            signature_instruction_index: 0,
+            eth_address_offset: 23 + index * 85 + 65,
+            eth_address_instruction_index: 0,
+            message_data_offset: 193,
+            message_data_size: 32,
+            message_instruction_index: 0,
+        };
+        let secp = [
+            vec![2],
+            bincode::serialize(&offsets(0)).unwrap(),
+            bincode::serialize(&offsets(1)).unwrap(),
+            vec![0; 85 * 2 + 32],
+        ]
+        .concat();
+        let decoded = decode(&secp256k1_program::id(), &secp).unwrap();
+        assert_eq!(decoded.name, "verify 2 signatures");
+        assert_eq!(
+            fields(&decoded),
+            [
+                ("count", 0..1),
+                ("offsets[0]", 1..12),
+                ("offsets[1]", 12..23),
+                ("signature[0]", 23..88),
+                ("eth_address[0]", 88..108),
+                ("signature[1]", 108..173),
+                ("eth_address[1]", 173..193),
+                ("message[0]", 193..225),
+            ]
+        );
+    }
+
+    #[test]
+    fn names_unknown_instructions() {
+        for program_id in [CORE_BRIDGE, TOKEN_BRIDGE] {
+            let past_the_last = [TOKEN_BRIDGE_INSTRUCTIONS.len() as u8];
+            for data in [&[][..], &past_the_last] {
+                let decoded = decode(&program_id, data).unwrap();
+                assert_eq!(decoded.name, "unknown");
+                assert!(decoded.fields.is_empty());
+            }
+        }
+        assert!(decode(&Pubkey::new_unique(), &post_vaa()).is_none());
+    }
+
+    #[test]
+    fn names_the_vaa_once_its_emitter_and_sequence_are_read() {
+        let data = post_vaa();
+        let prefixes = decode_prefixes(&data, |data| decode(&CORE_BRIDGE, data));
+        let named = |len: usize| prefixes[len].as_ref().unwrap().name.clone();
+        // The sequence ends at byte 56.
+        assert_eq!(named(55), "post_vaa");
+        assert_eq!(named(56), "post_vaa from Ethereum, sequence 42");
+        assert_eq!(named(data.len() - 1), named(56));
+
+        // A payload longer than the data is left out, an empty one is kept.
+        let mut oversized = data.clone();
+        oversized[57..61].copy_from_slice(&u32::MAX.to_le_bytes());
+        let decoded = decode(&CORE_BRIDGE, &oversized).unwrap();
+        assert_eq!(fields(&decoded).last().unwrap().0, "payload length");
+        let empty = [&data[..57], &0u32.to_le_bytes()].concat();
+        let decoded = decode(&CORE_BRIDGE, &empty).unwrap();
+        assert_eq!(fields(&decoded).last().unwrap(), &("payload", 61..61));
+    }
+
+    #[test]
+    fn decodes_transfers_with_payloads() {
+        let transfer = |cpi_program_id: &[u8]| {
+            [
+                &[12][..],
+                &1u32.to_le_bytes(),
+                &100u64.to_le_bytes(),
+                &[3; 32],
+                &9999u16.to_le_bytes(),
+                &2u32.to_le_bytes(),
+                &[4, 5],
+                cpi_program_id,
+            ]
+            .concat()
+        };
+        let decoded = decode(&TOKEN_BRIDGE, &transfer(&[0])).unwrap();
+        assert_eq!(decoded.name, "transfer_native_with_payload to chain 9999");
+        assert_eq!(
+            fields(&decoded)[5..],
+            [
+                ("payload length", 47..51),
+                ("payload", 51..53),
+                ("cpi_program_id option", 53..54),
+            ]
+        );
+
+        let cpi_program_id = [&[1][..], Pubkey::new_unique().as_ref()].concat();
+        let decoded = decode(&TOKEN_BRIDGE, &transfer(&cpi_program_id)).unwrap();
+        assert_eq!(
+            fields(&decoded).last().unwrap(),
+            &("cpi_program_id", 54..86)
+        );
+        // A pubkey cut short is left out.
+        let decoded = decode(&TOKEN_BRIDGE, &transfer(&cpi_program_id[..32])).unwrap();
+        assert_eq!(fields(&decoded).last().unwrap().0, "cpi_program_id option");
+    }
+
+    #[test]
+    fn counts_guardians_only_of_a_whole_signer_set() {
+        let mut signers = [0xff; 19];
+        signers[..2].copy_from_slice(&[4, 0]);
+        let decoded = decode(&CORE_BRIDGE, &[&[7][..], &signers].concat()).unwrap();
+        assert_eq!(decoded.name, "verify_signatures of 2 guardians");
+        let decoded = decode(&CORE_BRIDGE, &[&[7][..], &signers[..18]].concat()).unwrap();
+        assert_eq!(decoded.name, "verify_signatures");
+    }
+
+    #[test]
+    fn labels_only_what_signature_offsets_point_to_within_the_data() {
+        let offsets = SecpSignatureOffsets {
+            signature_offset: 12,
+            eth_address_offset: 77,
+            message_data_offset: 97,
+            message_data_size: u16::MAX,
+            ..SecpSignatureOffsets::default()
+        };
+        let secp = [vec![1], bincode::serialize(&offsets).unwrap(), vec![0; 85]].concat();
+        let decoded = decode(&secp256k1_program::id(), &secp).unwrap();
+        assert_eq!(
+            fields(&decoded),
+            [
+                ("count", 0..1),
+                ("offsets[0]", 1..12),
+                ("signature[0]", 12..77),
+                ("eth_address[0]", 77..97),
+            ]
+        );
+
+        // Offsets cut short are not decoded.
+        let prefixes = decode_prefixes(&secp, |data| decode(&secp256k1_program::id(), data));
+        assert!(prefixes[..12].iter().all(Option::is_none));
+        assert_eq!(
+            decode(&secp256k1_program::id(), &[0]).unwrap().name,
+            "verify 0 signatures"
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! Groth16 proofs over BN254 in instruction data, as verified with the
+//! alt_bn128 syscalls by ZK programs, e.g. those of Light Protocol. The data
+//! is recognized as a proof followed by its public inputs, after an optional
+//! discriminator, when every coordinate and input is a valid field element,
+//! which arbitrary bytes rarely all are.
+
+use super::{DataField, DecodedInstruction};
+
+/// The modulus of the base field of BN254, big-endian.
+const BASE_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d, 0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
+
+/// The modulus of the scalar field of BN254, which public inputs are in,
+/// big-endian.
+const SCALAR_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/// The lengths of the discriminators a proof may follow: none, an enum tag
+/// or an Anchor discriminator.
+const PREFIX_LENS: [usize; 3] = [0, 1, 8];
+
+#[derive(Clone, Copy)]
+enum Encoding {
+    /// Points as both coordinates, as the alt_bn128 syscalls take them.
+    Uncompressed,
+    /// Points as their x coordinate, flagged in its top bits, as gnark
+    /// serializes them and Light Protocol passes them.
+    Compressed,
+}
+
+impl Encoding {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Uncompressed => "uncompressed",
+            Self::Compressed => "compressed",
+        }
+    }
+
+    /// The points of a proof with their lengths: A and C in G1, B in G2.
+    fn points(self) -> [(&'static str, usize); 3] {
+        match self {
+            Self::Uncompressed => [("proof.a", 64), ("proof.b", 128), ("proof.c", 64)],
+            Self::Compressed => [("proof.a", 32), ("proof.b", 64), ("proof.c", 32)],
+        }
+    }
+
+    fn is_point(self, bytes: &[u8]) -> bool {
+        bytes.chunks(32).enumerate().all(|(index, coordinate)| {
+            let mut coordinate = <[u8; 32]>::try_from(coordinate).unwrap_or([0xff; 32]);
+            if let (Self::Compressed, 0) = (self, index) {
+                // The top bit flags a point which is not infinity.
+                if coordinate[0] & 0x80 == 0 {
+                    return false;
+                }
+                coordinate[0] &= 0x3f;
+            }
+            coordinate < BASE_MODULUS
+        })
+    }
+}
+
+pub fn decode(data: &[u8]) -> Option<DecodedInstruction> {
+    for encoding in [Encoding::Uncompressed, Encoding::Compressed] {
+        for prefix_len in PREFIX_LENS {
+            if let Some(decoded) = decode_as(data, prefix_len, encoding) {
+                return Some(decoded);
+            }
+        }
+    }
+    None
+}
+
+fn decode_as(data: &[u8], prefix_len: usize, encoding: Encoding) -> Option<DecodedInstruction> {
+    let points = encoding.points();
+    let proof_len = points.iter().map(|(_, len)| len).sum::<usize>();
+    let inputs_len = data.len().checked_sub(prefix_len + proof_len)?;
+    let num_inputs = inputs_len / 32;
+    // Compressed proofs are short enough for other data to pass for one
+    // without an input.
+    if inputs_len % 32 != 0 || (num_inputs == 0 && matches!(encoding, Encoding::Compressed)) {
+        return None;
+    }
+
+    let mut fields = vec![];
+    if prefix_len > 0 {
+        fields.push(DataField {
+            name: "discriminator".to_string(),
+            range: 0..prefix_len,
+        });
+    }
+    let mut offset = prefix_len;
+    for (name, len) in points {
+        if !encoding.is_point(&data[offset..offset + len]) {
+            return None;
+        }
+        fields.push(DataField {
+            name: name.to_string(),
+            range: offset..offset + len,
+        });
+        offset += len;
+    }
+    for (index, input) in data[offset..].chunks(32).enumerate() {
+        if input >= SCALAR_MODULUS.as_slice() {
+            return None;
+        }
+        fields.push(DataField {
+            name: format!("public_input[{index}]"),
+            range: offset..offset + 32,
+        });
+        offset += 32;
+    }
+    Some(DecodedInstruction {
+        program: "Groth16 proof",
+        name: format!("{}, public inputs: {num_inputs}", encoding.name()),
+        inner: None,
+        fields,
+        account_names: vec![],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::decoders::tests::{decode_prefixes, fields},
+    };
+
+    /// A field element of value `value`, big-endian.
+    fn element(value: u8) -> Vec<u8> {
+        [[0; 31].as_slice(), &[value]].concat()
+    }
+
+    /// The x coordinate of a point flagged as compressed.
+    fn flagged(value: u8) -> Vec<u8> {
+        [[0x80].as_slice(), &[0; 30], &[value]].concat()
+    }
+
+    /// A compressed proof followed by `num_inputs` public inputs.
+    fn compressed(num_inputs: usize) -> Vec<u8> {
+        [
+            flagged(1),
+            flagged(2),
+            element(3),
+            flagged(4),
+            element(5).repeat(num_inputs),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn recognizes_proofs_of_field_elements() {
+        let uncompressed = [&[1][..], &element(7).repeat(8 + 2)].concat();
+        let decoded = decode(&uncompressed).unwrap();
+        assert_eq!(decoded.name, "uncompressed, public inputs: 2");
+        assert_eq!(
+            fields(&decoded),
+            [
+                ("discriminator", 0..1),
+                ("proof.a", 1..65),
+                ("proof.b", 65..193),
+                ("proof.c", 193..257),
+                ("public_input[0]", 257..289),
+                ("public_input[1]", 289..321),
+            ]
+        );
+
+        assert_eq!(
+            decode(&compressed(1)).unwrap().name,
+            "compressed, public inputs: 1"
+        );
+        // Without the flag, or with a coordinate out of the field, it is
+        // not a proof.
+        let mut unflagged = compressed(1);
+        unflagged[0] = 0;
+        assert!(decode(&unflagged).is_none());
+        let mut out_of_field = uncompressed.clone();
+        out_of_field[1] = 0xff;
+        assert!(decode(&out_of_field).is_none());
+        assert!(decode(&[0xff; 8 + 256]).is_none());
+    }
+
+    #[test]
+    fn finds_the_discriminator_by_the_length_left_over() {
+        let proof = element(7).repeat(8 + 1);
+        for prefix_len in PREFIX_LENS {
+            let data = [vec![0xaa; prefix_len], proof.clone()].concat();
+            let decoded = decode(&data).unwrap();
+            let first = fields(&decoded)[0].clone();
+            if prefix_len == 0 {
+                assert_eq!(first, ("proof.a", 0..64));
+            } else {
+                assert_eq!(first, ("discriminator", 0..prefix_len));
+            }
+            assert_eq!(fields(&decoded).last().unwrap().1.end, data.len());
+        }
+        // Any other length leaves a partial input.
+        for prefix_len in [2, 7, 9, 31] {
+            assert!(decode(&[vec![0; prefix_len], proof.clone()].concat()).is_none());
+        }
+    }
+
+    #[test]
+    fn tells_the_encodings_apart() {
+        // A compressed proof of five inputs is as long as an uncompressed
+        // proof of one, but its flagged coordinates are out of the field.
+        let data = compressed(5);
+        assert_eq!(data.len(), 256 + 32);
+        assert_eq!(decode(&data).unwrap().name, "compressed, public inputs: 5");
+
+        // An uncompressed proof needs no input, but a compressed one does,
+        // its 128 bytes being too easily matched by other data.
+        assert_eq!(
+            decode(&element(7).repeat(8)).unwrap().name,
+            "uncompressed, public inputs: 0"
+        );
+        assert!(decode(&compressed(0)).is_none());
+
+        // The second flag bit, marking the sign of y, is not part of x.
+        let mut negative = compressed(1);
+        negative[0] |= 0x40;
+        assert!(decode(&negative).is_some());
+    }
+
+    #[test]
+    fn bounds_coordinates_and_inputs_by_their_moduli() {
+        let below = |modulus: [u8; 32]| {
+            let mut element = modulus;
+            element[31] -= 1;
+            element.to_vec()
+        };
+        let proof = |coordinate: Vec<u8>| [coordinate, element(7).repeat(7)].concat();
+        // Coordinates may exceed the scalar field, being in the base field.
+        assert!(decode(&proof(below(BASE_MODULUS))).is_some());
+        assert!(decode(&proof(BASE_MODULUS.to_vec())).is_none());
+
+        let with_input = |input: Vec<u8>| [element(7).repeat(8), input].concat();
+        assert!(decode(&with_input(below(SCALAR_MODULUS))).is_some());
+        assert!(decode(&with_input(SCALAR_MODULUS.to_vec())).is_none());
+    }
+
+    #[test]
+    fn rejects_partial_proofs() {
+        let data = [&[1][..], &element(7).repeat(8 + 2)].concat();
+        let prefixes = decode_prefixes(&data, decode);
+        // Only lengths leaving whole inputs after one of the discriminators
+        // are proofs.
+        let lens = prefixes
+            .iter()
+            .enumerate()
+            .filter(|(_, decoded)| decoded.is_some())
+            .map(|(len, _)| len)
+            .collect::<Vec<_>>();
+        assert_eq!(lens, [256, 257, 264, 288, 289, 296, 320]);
+    }
+}
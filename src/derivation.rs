@@ -0,0 +1,291 @@
+//! Deriving program addresses from seeds, to tell which account keys of a
+//! transaction are PDAs, and of what.
+
+use {
+    crate::schema::{ProgramPdas, Seed},
+    solana_sdk::{instruction::CompiledInstruction, pubkey, pubkey::Pubkey},
+    std::{collections::HashMap, str::FromStr},
+};
+
+const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+const TOKEN_PROGRAM_IDS: [(&str, Pubkey); 2] = [
+    (
+        "Token",
+        pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+    ),
+    (
+        "Token-2022",
+        pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"),
+    ),
+];
+const TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+pub struct Derived {
+    pub description: String,
+    pub address: Pubkey,
+    pub bump: u8,
+}
+
+/// Parse a seed typed as a pubkey, as hex prefixed with `0x`, as a little
+/// endian integer such as `u64:5`, or else as text.
+pub fn parse_seed(text: &str) -> Result<Vec<u8>, String> {
+    if let Ok(pubkey) = Pubkey::from_str(text) {
+        return Ok(pubkey.to_bytes().to_vec());
+    }
+    if let Some(hex) = text.strip_prefix("0x") {
+        return hex::decode(hex).map_err(|err| format!("Invalid hex seed {text}: {err}"));
+    }
+    let Some((ty, value)) = text.split_once(':') else {
+        return Ok(text.as_bytes().to_vec());
+    };
+    let bytes = match ty {
+        "u8" => value.parse::<u8>().map(|value| vec![value]),
+        "u16" => value
+            .parse::<u16>()
+            .map(|value| value.to_le_bytes().to_vec()),
+        "u32" => value
+            .parse::<u32>()
+            .map(|value| value.to_le_bytes().to_vec()),
+        "u64" => value
+            .parse::<u64>()
+            .map(|value| value.to_le_bytes().to_vec()),
+        _ => return Ok(text.as_bytes().to_vec()),
+    };
+    bytes.map_err(|err| format!("Invalid {ty} seed {value}: {err}"))
+}
+
+/// Derive the addresses of a request typed as a program id followed by
+/// seeds, or as `ata <wallet> <mint>` for associated token addresses.
+pub fn derive(text: &str) -> Result<Vec<Derived>, String> {
+    let words = text.split_whitespace().collect::<Vec<_>>();
+    let pubkey =
+        |text: &str| Pubkey::from_str(text).map_err(|err| format!("Invalid pubkey {text}: {err}"));
+    match words.as_slice() {
+        [] => Err("Enter a program id then seeds, or ata <wallet> <mint>".to_string()),
+        ["ata", wallet, mint] => {
+            let (wallet, mint) = (pubkey(wallet)?, pubkey(mint)?);
+            Ok(TOKEN_PROGRAM_IDS
+                .iter()
+                .map(|(name, token_program_id)| {
+                    let (address, bump) = Pubkey::find_program_address(
+                        &[wallet.as_ref(), token_program_id.as_ref(), mint.as_ref()],
+                        &ASSOCIATED_TOKEN_PROGRAM_ID,
+                    );
+                    Derived {
+                        description: format!("{name} associated token address"),
+                        address,
+                        bump,
+                    }
+                })
+                .collect())
+        }
+        ["ata", ..] => Err("Enter ata <wallet> <mint>".to_string()),
+        [program_id, seeds @ ..] => {
+            let program_id = pubkey(program_id)?;
+            let seeds = seeds
+                .iter()
+                .map(|seed| parse_seed(seed))
+                .collect::<Result<Vec<_>, _>>()?;
+            let seeds = seeds.iter().map(Vec::as_slice).collect::<Vec<_>>();
+            // Fails for more than 16 seeds or seeds longer than 32 bytes.
+            let (address, bump) = Pubkey::try_find_program_address(&seeds, &program_id)
+                .ok_or_else(|| "Invalid seeds for a program address".to_string())?;
+            Ok(vec![Derived {
+                description: "Program derived address".to_string(),
+                address,
+                bump,
+            }])
+        }
+    }
+}
+
+/// Label the account keys which are PDAs of seeds made of other account
+/// keys, e.g. `ATA(owner=key3, mint=key5)`, trying associated token
+/// addresses, token metadata accounts and the PDAs of the IDLs in
+/// `programs`. Keys are referred to by their index in `account_keys`.
+pub fn label_pdas(
+    account_keys: &[Pubkey],
+    instructions: &[CompiledInstruction],
+    programs: &[ProgramPdas],
+) -> HashMap<Pubkey, String> {
+    let mut labels = HashMap::new();
+    let index_of = |pubkey: &Pubkey| account_keys.iter().position(|key| key == pubkey);
+    let mut label = |address: Pubkey, label: String| {
+        if index_of(&address).is_some() {
+            labels.entry(address).or_insert(label);
+        }
+    };
+
+    for instruction in instructions {
+        let key = |index: &u8| account_keys.get(*index as usize);
+        let Some(program_id) = key(&instruction.program_id_index) else {
+            continue;
+        };
+        let idl_instructions = programs
+            .iter()
+            .filter(|program| program.program_id == *program_id)
+            .flat_map(|program| &program.instructions)
+            .filter(|idl_instruction| {
+                !idl_instruction.discriminator.is_empty()
+                    && instruction.data.starts_with(&idl_instruction.discriminator)
+            });
+        for idl_instruction in idl_instructions {
+            let account = |name: &str| {
+                let position = idl_instruction
+                    .accounts
+                    .iter()
+                    .position(|account| account == name)?;
+                key(instruction.accounts.get(position)?)
+            };
+            for (name, seeds) in &idl_instruction.pdas {
+                let mut seed_bytes = vec![];
+                let mut arguments = vec![];
+                for seed in seeds {
+                    match seed {
+                        Seed::Const(bytes) => seed_bytes.push(bytes.as_slice()),
+                        Seed::Account(path) => {
+                            let Some(pubkey) = account(path) else {
+                                break;
+                            };
+                            seed_bytes.push(pubkey.as_ref());
+                            if let Some(index) = index_of(pubkey) {
+                                arguments.push(format!("{path}=key{index}"));
+                            }
+                        }
+                    }
+                }
+                if seed_bytes.len() != seeds.len() {
+                    continue;
+                }
+                if let Some((address, _)) =
+                    Pubkey::try_find_program_address(&seed_bytes, program_id)
+                {
+                    label(address, format!("{name}({})", arguments.join(", ")));
+                }
+            }
+        }
+    }
+
+    for (index, mint) in account_keys.iter().enumerate() {
+        let seeds = [
+            b"metadata".as_ref(),
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+        ];
+        let (metadata, _) = Pubkey::find_program_address(&seeds, &TOKEN_METADATA_PROGRAM_ID);
+        label(metadata, format!("Metadata(mint=key{index})"));
+        let seeds = [seeds.as_slice(), &[b"edition"]].concat();
+        let (edition, _) = Pubkey::find_program_address(&seeds, &TOKEN_METADATA_PROGRAM_ID);
+        label(edition, format!("Edition(mint=key{index})"));
+    }
+
+    // Every pair of keys could be an owner and a mint, so only try the token
+    // programs the transaction refers to.
+    for (_, token_program_id) in TOKEN_PROGRAM_IDS
+        .iter()
+        .filter(|(_, token_program_id)| account_keys.contains(token_program_id))
+    {
+        for (owner_index, owner) in account_keys.iter().enumerate() {
+            for (mint_index, mint) in account_keys.iter().enumerate() {
+                if owner_index == mint_index {
+                    continue;
+                }
+                let (address, _) = Pubkey::find_program_address(
+                    &[owner.as_ref(), token_program_id.as_ref(), mint.as_ref()],
+                    &ASSOCIATED_TOKEN_PROGRAM_ID,
+                );
+                label(
+                    address,
+                    format!("ATA(owner=key{owner_index}, mint=key{mint_index})"),
+                );
+            }
+        }
+    }
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_seeds_and_derives_addresses() {
+        let (program_id, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_eq!(parse_seed("0x00ff").unwrap(), [0, 255]);
+        assert_eq!(parse_seed("u16:258").unwrap(), [2, 1]);
+        assert_eq!(parse_seed("vault").unwrap(), b"vault");
+        assert_eq!(parse_seed(&owner.to_string()).unwrap(), owner.to_bytes());
+        assert!(parse_seed("u8:256").is_err());
+
+        let derived = derive(&format!("{program_id} vault {owner} u64:7")).unwrap();
+        let expected = Pubkey::find_program_address(
+            &[b"vault", owner.as_ref(), &7u64.to_le_bytes()],
+            &program_id,
+        );
+        assert_eq!((derived[0].address, derived[0].bump), expected);
+        assert!(derive(&format!("{program_id} {}", "a".repeat(33))).is_err());
+        assert_eq!(
+            derive(&format!("ata {owner} {program_id}")).unwrap().len(),
+            2
+        );
+        assert!(derive(&format!("ata {owner}")).is_err());
+    }
+
+    #[test]
+    fn labels_pdas_by_their_seeds() {
+        let (owner, mint, program_id) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let idl = serde_json::json!({
+            "address": program_id.to_string(),
+            "metadata": { "name": "vaults", "version": "0.1.0" },
+            "instructions": [{
+                "name": "deposit",
+                "discriminator": [1, 2, 3, 4, 5, 6, 7, 8],
+                "accounts": [
+                    { "name": "owner", "signer": true },
+                    {
+                        "name": "vault",
+                        "pda": { "seeds": [
+                            { "kind": "const", "value": b"vault" },
+                            { "kind": "account", "path": "owner" }
+                        ] }
+                    }
+                ],
+                "args": []
+            }]
+        });
+        let schema = serde_json::from_value::<crate::schema::Schema>(idl).unwrap();
+        let (vault, _) = Pubkey::find_program_address(&[b"vault", owner.as_ref()], &program_id);
+        let ata = derive(&format!("ata {owner} {mint}")).unwrap()[0].address;
+        let metadata = Pubkey::find_program_address(
+            &[
+                b"metadata",
+                TOKEN_METADATA_PROGRAM_ID.as_ref(),
+                mint.as_ref(),
+            ],
+            &TOKEN_METADATA_PROGRAM_ID,
+        )
+        .0;
+        let account_keys = [
+            owner,
+            vault,
+            mint,
+            ata,
+            TOKEN_PROGRAM_IDS[0].1,
+            program_id,
+            metadata,
+            Pubkey::new_unique(),
+        ];
+        let instruction =
+            CompiledInstruction::new_from_raw_parts(5, vec![1, 2, 3, 4, 5, 6, 7, 8, 9], vec![0, 1]);
+
+        let labels = label_pdas(&account_keys, &[instruction], &[schema.pdas().unwrap()]);
+        assert_eq!(labels[&vault], "vault(owner=key0)");
+        assert_eq!(labels[&ata], "ATA(owner=key0, mint=key2)");
+        assert_eq!(labels[&metadata], "Metadata(mint=key2)");
+        assert_eq!(labels.len(), 3);
+    }
+}
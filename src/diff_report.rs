@@ -0,0 +1,163 @@
+//! Headless diffing of many transaction pairs, with `--diff-report`, to check
+//! at scale that two ways of building transactions (say, before and after an
+//! SDK migration) produce the same bytes.
+//!
+//! The pairs file has one pair per line, `<left> <right>`, each a signature
+//! to fetch or the path of a file holding a serialized transaction. Blank
+//! lines and lines starting with `#` are skipped.
+
+use {
+    crate::rpc::{FetchOptions, RpcApi},
+    serde::Serialize,
+    solana_sdk::{signature::Signature, transaction::VersionedTransaction},
+    solana_transaction_tui::{ingest, transaction_diff::TransactionDiff},
+    std::{path::Path, str::FromStr},
+};
+
+#[derive(Serialize)]
+pub struct PairReport {
+    pub line: usize,
+    pub left: String,
+    pub right: String,
+    pub byte_identical: bool,
+    pub changes: Vec<String>,
+    /// Why either transaction could not be loaded, in which case the pair
+    /// was not compared.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Report {
+    pub identical: usize,
+    pub different: usize,
+    pub failed: usize,
+    pub pairs: Vec<PairReport>,
+}
+
+impl Report {
+    /// Whether every pair was loaded and byte identical.
+    pub fn all_identical(&self) -> bool {
+        self.different == 0 && self.failed == 0
+    }
+}
+
+/// Diff each pair listed in `pairs`, fetching signatures from `rpc`.
+pub fn run(pairs: &str, rpc: &dyn RpcApi) -> Result<Report, String> {
+    let mut report = Report {
+        identical: 0,
+        different: 0,
+        failed: 0,
+        pairs: vec![],
+    };
+    for (index, line) in pairs.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let [left, right] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+            return Err(format!(
+                "Line {line_number}: expected `<left> <right>`, got `{line}`"
+            ));
+        };
+
+        let mut pair = PairReport {
+            line: line_number,
+            left: left.to_string(),
+            right: right.to_string(),
+            byte_identical: false,
+            changes: vec![],
+            error: None,
+        };
+        match load(left, rpc).and_then(|left| Ok((left, load(right, rpc)?))) {
+            Ok((left, right)) => {
+                pair.byte_identical = serialize(&left)? == serialize(&right)?;
+                pair.changes = TransactionDiff::new(&left, &right)
+                    .changes
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
+                if pair.byte_identical {
+                    report.identical += 1;
+                } else {
+                    report.different += 1;
+                }
+            }
+            Err(err) => {
+                pair.error = Some(err);
+                report.failed += 1;
+            }
+        }
+        report.pairs.push(pair);
+    }
+    Ok(report)
+}
+
+/// Load a transaction by signature, or from a file if `input` is not one.
+fn load(input: &str, rpc: &dyn RpcApi) -> Result<VersionedTransaction, String> {
+    match Signature::from_str(input) {
+        Ok(signature) => rpc
+            .get_transaction(&signature, &FetchOptions::default())?
+            .transaction
+            .transaction
+            .decode()
+            .ok_or_else(|| format!("Failed to decode {signature}")),
+        Err(_) => {
+            let bytes = std::fs::read(Path::new(input))
+                .map_err(|err| format!("Failed to read {input}: {err}"))?;
+            ingest::parse_transaction(&bytes)
+        }
+    }
+}
+
+fn serialize(transaction: &VersionedTransaction) -> Result<Vec<u8>, String> {
+    bincode::serialize(transaction).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::rpc::MockRpc,
+        solana_sdk::{
+            hash::Hash, message::Message, pubkey::Pubkey, system_instruction,
+            transaction::Transaction,
+        },
+    };
+
+    #[test]
+    fn reports_each_pair() {
+        let dir = std::env::temp_dir().join(format!("diff-report-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let payer = Pubkey::new_unique();
+        let write = |name: &str, lamports: u64| {
+            let instruction = system_instruction::transfer(&payer, &Pubkey::default(), lamports);
+            let transaction = VersionedTransaction::from(Transaction::new_unsigned(
+                Message::new_with_blockhash(&[instruction], Some(&payer), &Hash::default()),
+            ));
+            let path = dir.join(name);
+            std::fs::write(&path, serialize(&transaction).unwrap()).unwrap();
+            path.display().to_string()
+        };
+        let (a, b, c) = (write("a", 1), write("b", 1), write("c", 2));
+        let pairs = format!(
+            "# before after\n{a} {b}\n\n{a} {c}\n{a} {}\n",
+            dir.display()
+        );
+
+        let report = run(&pairs, &MockRpc::new(&dir)).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            (report.identical, report.different, report.failed),
+            (1, 1, 1)
+        );
+        assert!(!report.all_identical());
+        assert_eq!(report.pairs[0].line, 2);
+        assert!(report.pairs[0].changes.is_empty());
+        assert_eq!(report.pairs[1].changes, ["modified instruction (0)"]);
+        assert!(report.pairs[2].error.is_some());
+
+        assert!(run("just-one", &MockRpc::new(".")).is_err());
+    }
+}
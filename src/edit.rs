@@ -0,0 +1,735 @@
+use {
+    crate::{
+        compute_budget::{SET_COMPUTE_UNIT_LIMIT, SET_COMPUTE_UNIT_PRICE},
+        ingest,
+        transaction_byte_sections::{get_transaction_byte_sections, TransactionByteSection},
+        transaction_diff::TransactionDiff,
+    },
+    solana_sdk::{
+        compute_budget::{self, ComputeBudgetInstruction},
+        hash::Hash,
+        instruction::CompiledInstruction,
+        message::VersionedMessage,
+        pubkey::Pubkey,
+        signature::Signature,
+        signer::Signer,
+        transaction::VersionedTransaction,
+    },
+    std::{fmt, ops::Range},
+};
+
+#[derive(Debug)]
+pub enum EditError {
+    InstructionOutOfRange(usize),
+    AccountKeyOutOfRange(usize),
+    NotASigner(Pubkey),
+    TooManyAccountKeys,
+    ByteOutOfRange(usize),
+    Unparseable(String),
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InstructionOutOfRange(index) => write!(f, "instruction {index} does not exist"),
+            Self::AccountKeyOutOfRange(index) => write!(f, "account key {index} does not exist"),
+            Self::NotASigner(pubkey) => write!(f, "{pubkey} is not a required signer"),
+            Self::TooManyAccountKeys => write!(f, "no room for another account key"),
+            Self::ByteOutOfRange(offset) => write!(f, "byte {offset} does not exist"),
+            Self::Unparseable(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+/// A single applied edit, along with the transaction as it was before the
+/// edit so that it can be undone.
+struct EditRecord {
+    description: String,
+    transaction: VersionedTransaction,
+}
+
+/// An in-progress edit of a transaction.
+///
+/// The original transaction is kept untouched so that it can be displayed
+/// alongside the edited version.
+pub struct EditSession {
+    original: VersionedTransaction,
+    edited: VersionedTransaction,
+    original_sections: Vec<TransactionByteSection>,
+    edited_sections: Vec<TransactionByteSection>,
+    differences: Vec<Range<usize>>,
+    diff: TransactionDiff,
+    undo_stack: Vec<EditRecord>,
+    redo_stack: Vec<EditRecord>,
+}
+
+impl EditSession {
+    pub fn new(transaction: VersionedTransaction) -> Self {
+        let mut original_sections = vec![];
+        get_transaction_byte_sections(&transaction, &mut original_sections);
+        let mut session = Self {
+            diff: TransactionDiff::new(&transaction, &transaction),
+            original: transaction.clone(),
+            edited: transaction,
+            original_sections,
+            edited_sections: vec![],
+            differences: vec![],
+            undo_stack: vec![],
+            redo_stack: vec![],
+        };
+        session.refresh();
+        session
+    }
+
+    pub fn edited(&self) -> &VersionedTransaction {
+        &self.edited
+    }
+
+    pub fn original_sections(&self) -> &[TransactionByteSection] {
+        &self.original_sections
+    }
+
+    pub fn edited_sections(&self) -> &[TransactionByteSection] {
+        &self.edited_sections
+    }
+
+    /// Byte ranges of the edited transaction that differ from the original.
+    pub fn differences(&self) -> &[Range<usize>] {
+        &self.differences
+    }
+
+    /// The structural changes from the original to the edited transaction.
+    pub fn diff(&self) -> &TransactionDiff {
+        &self.diff
+    }
+
+    /// Descriptions of the applied edits (oldest first) followed by the
+    /// undone edits which can still be redone (next redo first).
+    pub fn history(&self) -> (Vec<&str>, Vec<&str>) {
+        let applied = self
+            .undo_stack
+            .iter()
+            .map(|record| record.description.as_str())
+            .collect();
+        let undone = self
+            .redo_stack
+            .iter()
+            .rev()
+            .map(|record| record.description.as_str())
+            .collect();
+        (applied, undone)
+    }
+
+    /// Revert the most recent edit. Returns its description, if any.
+    pub fn undo(&mut self) -> Option<String> {
+        let record = self.undo_stack.pop()?;
+        let description = record.description.clone();
+        self.redo_stack.push(EditRecord {
+            description: record.description,
+            transaction: std::mem::replace(&mut self.edited, record.transaction),
+        });
+        self.refresh();
+        Some(description)
+    }
+
+    /// Re-apply the most recently undone edit. Returns its description, if
+    /// any.
+    pub fn redo(&mut self) -> Option<String> {
+        let record = self.redo_stack.pop()?;
+        let description = record.description.clone();
+        self.undo_stack.push(EditRecord {
+            description: record.description,
+            transaction: std::mem::replace(&mut self.edited, record.transaction),
+        });
+        self.refresh();
+        Some(description)
+    }
+
+    pub fn remove_instruction(&mut self, index: usize) -> Result<(), EditError> {
+        self.edit(format!("Remove instruction {index}"), |transaction| {
+            let instructions = instructions_mut(&mut transaction.message);
+            if index >= instructions.len() {
+                return Err(EditError::InstructionOutOfRange(index));
+            }
+            instructions.remove(index);
+            Ok(())
+        })
+    }
+
+    pub fn replace_account_key(&mut self, index: usize, pubkey: Pubkey) -> Result<(), EditError> {
+        self.edit(format!("Replace account key {index}"), |transaction| {
+            let account_key = account_keys_mut(&mut transaction.message)
+                .get_mut(index)
+                .ok_or(EditError::AccountKeyOutOfRange(index))?;
+            *account_key = pubkey;
+            Ok(())
+        })
+    }
+
+    pub fn set_instruction_data(&mut self, index: usize, data: Vec<u8>) -> Result<(), EditError> {
+        self.edit(format!("Set instruction {index} data"), |transaction| {
+            let instruction = instructions_mut(&mut transaction.message)
+                .get_mut(index)
+                .ok_or(EditError::InstructionOutOfRange(index))?;
+            instruction.data = data;
+            Ok(())
+        })
+    }
+
+    pub fn set_recent_blockhash(&mut self, blockhash: Hash) -> Result<(), EditError> {
+        self.edit(format!("Set recent blockhash {blockhash}"), |transaction| {
+            transaction.message.set_recent_blockhash(blockhash);
+            Ok(())
+        })
+    }
+
+    pub fn set_compute_unit_limit(&mut self, units: u32) -> Result<(), EditError> {
+        let instruction = ComputeBudgetInstruction::set_compute_unit_limit(units);
+        self.edit(format!("Set compute unit limit {units}"), |transaction| {
+            set_compute_budget_instruction(transaction, instruction.data)
+        })
+    }
+
+    pub fn set_compute_unit_price(&mut self, micro_lamports: u64) -> Result<(), EditError> {
+        let instruction = ComputeBudgetInstruction::set_compute_unit_price(micro_lamports);
+        self.edit(
+            format!("Set compute unit price {micro_lamports}"),
+            |transaction| set_compute_budget_instruction(transaction, instruction.data),
+        )
+    }
+
+    /// Overwrite a single byte of the serialized transaction. The modified
+    /// bytes must still parse as a transaction.
+    pub fn set_byte(&mut self, offset: usize, value: u8) -> Result<(), EditError> {
+        self.edit(
+            format!("Set byte {offset:#04x} to {value:02x}"),
+            |transaction| {
+                let mut bytes = bincode::serialize(transaction).unwrap();
+                *bytes
+                    .get_mut(offset)
+                    .ok_or(EditError::ByteOutOfRange(offset))? = value;
+                *transaction = ingest::parse_transaction(&bytes).map_err(EditError::Unparseable)?;
+                Ok(())
+            },
+        )
+    }
+
+    /// Reset every signature to the default (all zero) signature.
+    pub fn clear_signatures(&mut self) -> Result<(), EditError> {
+        self.edit("Clear signatures".to_string(), |transaction| {
+            let num_required_signatures =
+                transaction.message.header().num_required_signatures as usize;
+            transaction.signatures = vec![Signature::default(); num_required_signatures];
+            Ok(())
+        })
+    }
+
+    /// Sign the edited message with each signer, placing the signatures at
+    /// the index of the corresponding signer key.
+    pub fn sign(&mut self, signers: &[&dyn Signer]) -> Result<(), EditError> {
+        self.edit(
+            format!("Sign with {} signer(s)", signers.len()),
+            |transaction| {
+                let num_required_signatures =
+                    transaction.message.header().num_required_signatures as usize;
+                transaction
+                    .signatures
+                    .resize(num_required_signatures, Signature::default());
+                let message_bytes = transaction.message.serialize();
+                let account_keys = transaction.message.static_account_keys();
+                let signer_keys = &account_keys[..num_required_signatures.min(account_keys.len())];
+                for signer in signers {
+                    let signer_index = signer_keys
+                        .iter()
+                        .position(|key| *key == signer.pubkey())
+                        .ok_or(EditError::NotASigner(signer.pubkey()))?;
+                    transaction.signatures[signer_index] = signer.sign_message(&message_bytes);
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// The account keys which must sign the edited transaction.
+    pub fn required_signers(&self) -> &[Pubkey] {
+        let message = &self.edited.message;
+        let num_required_signatures = message.header().num_required_signatures as usize;
+        let account_keys = message.static_account_keys();
+        &account_keys[..num_required_signatures.min(account_keys.len())]
+    }
+
+    /// The nonce account used by the edited transaction, if it uses a durable
+    /// nonce instead of a recent blockhash.
+    pub fn nonce_account(&self) -> Option<Pubkey> {
+        if !self.edited.uses_durable_nonce() {
+            return None;
+        }
+        let message = &self.edited.message;
+        let nonce_account_index = *message.instructions().first()?.accounts.first()?;
+        message
+            .static_account_keys()
+            .get(nonce_account_index as usize)
+            .copied()
+    }
+
+    /// Apply an edit to a copy of the edited transaction, recording it in the
+    /// undo history if it succeeds.
+    fn edit(
+        &mut self,
+        description: String,
+        edit: impl FnOnce(&mut VersionedTransaction) -> Result<(), EditError>,
+    ) -> Result<(), EditError> {
+        let mut transaction = self.edited.clone();
+        edit(&mut transaction)?;
+        self.undo_stack.push(EditRecord {
+            description,
+            transaction: std::mem::replace(&mut self.edited, transaction),
+        });
+        self.redo_stack.clear();
+        self.refresh();
+        Ok(())
+    }
+
+    /// Recompute the sections and differences after an edit.
+    fn refresh(&mut self) {
+        get_transaction_byte_sections(&self.edited, &mut self.edited_sections);
+
+        let original_bytes = bincode::serialize(&self.original).unwrap();
+        let edited_bytes = bincode::serialize(&self.edited).unwrap();
+        self.differences = differing_ranges(&original_bytes, &edited_bytes);
+        self.diff = TransactionDiff::new(&self.original, &self.edited);
+    }
+}
+
+/// Replace the data of the compute budget instruction of the same kind, or
+/// insert a new compute budget instruction if there is none.
+fn set_compute_budget_instruction(
+    transaction: &mut VersionedTransaction,
+    data: Vec<u8>,
+) -> Result<(), EditError> {
+    // The nonce advance instruction must stay first.
+    let index = usize::from(transaction.uses_durable_nonce());
+    let message = &mut transaction.message;
+    debug_assert!(matches!(
+        data.first(),
+        Some(&SET_COMPUTE_UNIT_LIMIT | &SET_COMPUTE_UNIT_PRICE)
+    ));
+    let account_keys = message.static_account_keys().to_vec();
+    let existing = instructions_mut(message).iter_mut().find(|instruction| {
+        account_keys.get(instruction.program_id_index as usize) == Some(&compute_budget::id())
+            && instruction.data.first() == data.first()
+    });
+    if let Some(instruction) = existing {
+        instruction.data = data;
+        return Ok(());
+    }
+
+    let program_id_index = account_key_index_or_insert(message, compute_budget::id())?;
+    instructions_mut(message).insert(
+        index,
+        CompiledInstruction {
+            program_id_index,
+            accounts: vec![],
+            data,
+        },
+    );
+    Ok(())
+}
+
+/// The index of a static account key, appending it as a readonly unsigned
+/// account if it is not present.
+fn account_key_index_or_insert(
+    message: &mut VersionedMessage,
+    pubkey: Pubkey,
+) -> Result<u8, EditError> {
+    if let Some(index) = message
+        .static_account_keys()
+        .iter()
+        .position(|key| *key == pubkey)
+    {
+        return Ok(index as u8);
+    }
+    let index = message.static_account_keys().len();
+    let new_index = u8::try_from(index).map_err(|_| EditError::TooManyAccountKeys)?;
+    if let VersionedMessage::V0(message) = message {
+        // Keys loaded from lookup tables are indexed after the static keys,
+        // so they shift by one.
+        let num_loaded_keys = message
+            .address_table_lookups
+            .iter()
+            .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+            .sum::<usize>();
+        if index + num_loaded_keys > usize::from(u8::MAX) {
+            return Err(EditError::TooManyAccountKeys);
+        }
+        for instruction in &mut message.instructions {
+            for account_index in
+                std::iter::once(&mut instruction.program_id_index).chain(&mut instruction.accounts)
+            {
+                if *account_index >= new_index {
+                    *account_index += 1;
+                }
+            }
+        }
+    }
+    account_keys_mut(message).push(pubkey);
+    match message {
+        VersionedMessage::Legacy(message) => message.header.num_readonly_unsigned_accounts += 1,
+        VersionedMessage::V0(message) => message.header.num_readonly_unsigned_accounts += 1,
+    }
+    Ok(new_index)
+}
+
+fn instructions_mut(message: &mut VersionedMessage) -> &mut Vec<CompiledInstruction> {
+    match message {
+        VersionedMessage::Legacy(message) => &mut message.instructions,
+        VersionedMessage::V0(message) => &mut message.instructions,
+    }
+}
+
+fn account_keys_mut(message: &mut VersionedMessage) -> &mut Vec<Pubkey> {
+    match message {
+        VersionedMessage::Legacy(message) => &mut message.account_keys,
+        VersionedMessage::V0(message) => &mut message.account_keys,
+    }
+}
+
+/// Positional comparison of two byte buffers, returning the ranges of `b`
+/// which differ from `a` (including any bytes past the end of `a`).
+pub fn differing_ranges(a: &[u8], b: &[u8]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = vec![];
+    for (offset, byte) in b.iter().enumerate() {
+        if a.get(offset) == Some(byte) {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(range) if range.end == offset => range.end += 1,
+            _ => ranges.push(offset..offset + 1),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::compute_budget::ComputeBudget,
+        solana_sdk::{
+            instruction::{AccountMeta, Instruction},
+            message::{v0, Message, MessageHeader},
+            signature::Keypair,
+            system_instruction, system_program,
+        },
+    };
+
+    /// An unsigned legacy transaction of `num_instructions` system program
+    /// instructions, each passing a writable account of its own.
+    fn transaction(num_instructions: usize) -> VersionedTransaction {
+        let payer = Pubkey::new_unique();
+        let instructions = (0..num_instructions)
+            .map(|index| {
+                Instruction::new_with_bytes(
+                    system_program::id(),
+                    &[index as u8],
+                    vec![AccountMeta::new(Pubkey::new_unique(), false)],
+                )
+            })
+            .collect::<Vec<_>>();
+        let message = Message::new(&instructions, Some(&payer));
+        VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::Legacy(message),
+        }
+    }
+
+    #[test]
+    fn highlights_the_differing_ranges() {
+        let original = [1, 2, 3, 4, 5];
+        let differing = |edited: &[u8]| {
+            differing_ranges(&original, edited)
+                .into_iter()
+                .map(|range| (range.start, range.end))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(differing(&original), []);
+        assert!(differing_ranges(&[], &[]).is_empty());
+
+        assert_eq!(differing(&[1, 2, 9, 4, 5]), [(2, 3)]);
+        assert_eq!(differing(&[9, 2, 3, 4, 9]), [(0, 1), (4, 5)]);
+        assert_eq!(differing(&[1, 9, 9, 4, 5]), [(1, 3)]);
+
+        // Bytes past the end of the original differ, adjacent changes merge
+        // with them, and bytes cut from the end are not part of the edit.
+        assert_eq!(differing(&[1, 2, 3, 4, 5, 6, 7]), [(5, 7)]);
+        assert_eq!(differing(&[1, 2, 3, 4, 9, 6]), [(4, 6)]);
+        assert_eq!(differing(&[1, 2, 3]), []);
+        // An inserted byte shifts the rest, which then all differ.
+        assert_eq!(differing(&[1, 0, 2, 3, 4, 5]), [(1, 6)]);
+    }
+
+    #[test]
+    fn undoes_and_redoes_edits() {
+        let transaction = transaction(2);
+        let mut session = EditSession::new(transaction.clone());
+        session.set_instruction_data(0, vec![7]).unwrap();
+        session.remove_instruction(1).unwrap();
+        assert_eq!(session.edited().message.instructions().len(), 1);
+        assert_eq!(
+            session.history(),
+            (
+                vec!["Set instruction 0 data", "Remove instruction 1"],
+                vec![]
+            )
+        );
+
+        assert_eq!(session.undo().as_deref(), Some("Remove instruction 1"));
+        assert_eq!(session.undo().as_deref(), Some("Set instruction 0 data"));
+        assert_eq!(session.undo(), None);
+        assert_eq!(session.edited(), &transaction);
+        assert!(session.differences().is_empty());
+        assert_eq!(
+            session.history(),
+            (
+                vec![],
+                vec!["Set instruction 0 data", "Remove instruction 1"]
+            )
+        );
+
+        assert_eq!(session.redo().as_deref(), Some("Set instruction 0 data"));
+        assert_eq!(session.edited().message.instructions()[0].data, [7]);
+        assert_eq!(session.edited().message.instructions().len(), 2);
+        assert!(!session.differences().is_empty());
+    }
+
+    #[test]
+    fn clears_the_redo_stack_on_a_new_edit() {
+        let mut session = EditSession::new(transaction(2));
+        session.set_instruction_data(0, vec![7]).unwrap();
+        session.set_instruction_data(1, vec![8]).unwrap();
+        session.undo();
+        session.undo();
+        session.remove_instruction(0).unwrap();
+        assert_eq!(session.redo(), None);
+        assert_eq!(session.history(), (vec!["Remove instruction 0"], vec![]));
+
+        // A failed edit changes nothing, not even the redo stack.
+        session.undo();
+        assert!(session.remove_instruction(5).is_err());
+        assert_eq!(session.redo().as_deref(), Some("Remove instruction 0"));
+    }
+
+    /// The pubkey an account index resolves to, with the keys loaded by the
+    /// lookups listed writable first, as the runtime loads them.
+    fn resolve(message: &v0::Message, loaded: &[Pubkey], index: u8) -> Pubkey {
+        let static_keys = &message.account_keys;
+        let index = usize::from(index);
+        static_keys
+            .get(index)
+            .or_else(|| loaded.get(index - static_keys.len()))
+            .copied()
+            .unwrap()
+    }
+
+    #[test]
+    fn shifts_loaded_account_indexes_on_insert() {
+        let static_keys = vec![Pubkey::new_unique(), system_program::id()];
+        let (writable, readonly) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut message = VersionedMessage::V0(v0::Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: static_keys,
+            recent_blockhash: Hash::new_unique(),
+            instructions: vec![
+                CompiledInstruction::new_from_raw_parts(1, vec![1], vec![0, 2]),
+                CompiledInstruction::new_from_raw_parts(1, vec![2], vec![3, 2, 0]),
+                CompiledInstruction::new_from_raw_parts(1, vec![3], vec![]),
+            ],
+            address_table_lookups: vec![v0::MessageAddressTableLookup {
+                account_key: Pubkey::new_unique(),
+                writable_indexes: vec![0],
+                readonly_indexes: vec![1],
+            }],
+        });
+        let loaded = [writable, readonly];
+        let VersionedMessage::V0(before) = message.clone() else {
+            unreachable!();
+        };
+
+        let inserted = Pubkey::new_unique();
+        assert_eq!(
+            account_key_index_or_insert(&mut message, inserted).unwrap(),
+            2
+        );
+        let VersionedMessage::V0(after) = &message else {
+            unreachable!();
+        };
+        assert_eq!(resolve(after, &loaded, 2), inserted);
+        assert_eq!(after.header.num_readonly_unsigned_accounts, 2);
+        let resolved = |message, instruction: &CompiledInstruction| {
+            std::iter::once(instruction.program_id_index)
+                .chain(instruction.accounts.iter().copied())
+                .map(|index| resolve(message, &loaded, index))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(after.instructions.len(), before.instructions.len());
+        for (old, new) in before.instructions.iter().zip(&after.instructions) {
+            assert_eq!(resolved(after, new), resolved(&before, old));
+        }
+
+        // Present keys are found rather than inserted again.
+        assert_eq!(
+            account_key_index_or_insert(&mut message, system_program::id()).unwrap(),
+            1
+        );
+        assert_eq!(message.static_account_keys().len(), 3);
+    }
+
+    /// The compute budget instructions of a message, as their data.
+    fn compute_budget_data(message: &VersionedMessage) -> Vec<Vec<u8>> {
+        let account_keys = message.static_account_keys();
+        message
+            .instructions()
+            .iter()
+            .filter(|instruction| {
+                account_keys[instruction.program_id_index as usize] == compute_budget::id()
+            })
+            .map(|instruction| instruction.data.clone())
+            .collect()
+    }
+
+    #[test]
+    fn inserts_compute_budget_instructions() {
+        let mut session = EditSession::new(transaction(2));
+        session.set_compute_unit_limit(50_000).unwrap();
+        session.set_compute_unit_price(1_000).unwrap();
+        let message = &session.edited().message;
+        assert_eq!(message.instructions().len(), 4);
+        assert_eq!(
+            compute_budget_data(message),
+            [
+                ComputeBudgetInstruction::set_compute_unit_price(1_000).data,
+                ComputeBudgetInstruction::set_compute_unit_limit(50_000).data,
+            ]
+        );
+        // The program is appended as a readonly key, leaving the others be.
+        assert_eq!(message.static_account_keys().len(), 5);
+        assert_eq!(message.header().num_readonly_unsigned_accounts, 2);
+        let budget = ComputeBudget::from_message(message);
+        assert_eq!((budget.unit_limit, budget.unit_price), (50_000, 1_000));
+    }
+
+    #[test]
+    fn updates_existing_compute_budget_instructions() {
+        let mut session = EditSession::new(transaction(1));
+        session.set_compute_unit_limit(50_000).unwrap();
+        session.set_compute_unit_limit(80_000).unwrap();
+        session.set_compute_unit_price(1_000).unwrap();
+        session.set_compute_unit_price(2_000).unwrap();
+        let message = &session.edited().message;
+        assert_eq!(message.instructions().len(), 3);
+        assert_eq!(message.static_account_keys().len(), 4);
+        let budget = ComputeBudget::from_message(message);
+        assert_eq!((budget.unit_limit, budget.unit_price), (80_000, 2_000));
+
+        // Undoing an update restores the previous value in place.
+        session.undo();
+        let budget = ComputeBudget::from_message(&session.edited().message);
+        assert_eq!(budget.unit_price, 1_000);
+        assert_eq!(session.edited().message.instructions().len(), 3);
+    }
+
+    /// A transfer paid by `payer` and signed by both it and `from`.
+    fn transfer(payer: &Pubkey, from: &Pubkey) -> VersionedTransaction {
+        let instruction = system_instruction::transfer(from, &Pubkey::new_unique(), 1);
+        let message = Message::new(&[instruction], Some(payer));
+        VersionedTransaction {
+            signatures: vec![Signature::default(); 2],
+            message: VersionedMessage::Legacy(message),
+        }
+    }
+
+    #[test]
+    fn signs_at_the_index_of_each_signer() {
+        let (payer, from) = (Keypair::new(), Keypair::new());
+        let mut session = EditSession::new(transfer(&payer.pubkey(), &from.pubkey()));
+        assert_eq!(session.required_signers(), [payer.pubkey(), from.pubkey()]);
+
+        session.sign(&[&from]).unwrap();
+        let signatures = &session.edited().signatures;
+        assert_eq!(signatures[0], Signature::default());
+        let message_bytes = session.edited().message.serialize();
+        assert!(signatures[1].verify(from.pubkey().as_ref(), &message_bytes));
+
+        session.sign(&[&payer]).unwrap();
+        assert_eq!(session.edited().verify_with_results(), [true, true]);
+        assert_eq!(
+            session.history().0,
+            ["Sign with 1 signer(s)", "Sign with 1 signer(s)"]
+        );
+
+        // A keypair which need not sign is rejected without an edit.
+        let stranger = Keypair::new();
+        assert!(matches!(
+            session.sign(&[&stranger]),
+            Err(EditError::NotASigner(pubkey)) if pubkey == stranger.pubkey()
+        ));
+        assert_eq!(session.history().0.len(), 2);
+
+        session.clear_signatures().unwrap();
+        assert_eq!(session.edited().signatures, [Signature::default(); 2]);
+    }
+
+    #[test]
+    fn sets_the_blockhash_invalidating_signatures() {
+        let payer = Keypair::new();
+        let mut session = EditSession::new(transfer(&payer.pubkey(), &payer.pubkey()));
+        session.sign(&[&payer]).unwrap();
+        let blockhash = Hash::new_unique();
+        session.set_recent_blockhash(blockhash).unwrap();
+        assert_eq!(*session.edited().message.recent_blockhash(), blockhash);
+        assert_eq!(session.edited().verify_with_results(), [false]);
+        assert_eq!(session.nonce_account(), None);
+    }
+
+    #[test]
+    fn finds_the_nonce_account_of_durable_transactions() {
+        let (payer, nonce) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instruction = system_instruction::transfer(&payer, &Pubkey::new_unique(), 1);
+        let message = Message::new_with_nonce(vec![instruction], Some(&payer), &nonce, &payer);
+        let session = EditSession::new(VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::Legacy(message),
+        });
+        assert_eq!(session.nonce_account(), Some(nonce));
+    }
+
+    #[test]
+    fn overwrites_bytes_which_still_parse() {
+        let mut session = EditSession::new(transaction(1));
+        let bytes = bincode::serialize(session.edited()).unwrap();
+        // The last byte is the instruction data.
+        let last = bytes.len() - 1;
+        session.set_byte(last, 0xab).unwrap();
+        assert_eq!(session.edited().message.instructions()[0].data, [0xab]);
+        assert_eq!(
+            session.differences(),
+            std::slice::from_ref(&(last..last + 1))
+        );
+        assert_eq!(session.history().0, [format!("Set byte {last:#04x} to ab")]);
+
+        assert!(matches!(
+            session.set_byte(bytes.len(), 0),
+            Err(EditError::ByteOutOfRange(offset)) if offset == bytes.len()
+        ));
+        // A signature count with a continuation bit leaves too few bytes.
+        assert!(matches!(
+            session.set_byte(0, 0x81),
+            Err(EditError::Unparseable(_))
+        ));
+        assert_eq!(session.history().0.len(), 1);
+    }
+}
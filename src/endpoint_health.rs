@@ -0,0 +1,181 @@
+use {
+    crate::rpc::RpcApi,
+    solana_sdk::commitment_config::CommitmentConfig,
+    std::time::{Duration, Instant},
+};
+
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// Slot lag behind the reference endpoint above which the endpoint is
+/// considered to be lagging.
+pub const LAGGING_SLOTS: u64 = 50;
+
+/// The result of a single health check of an endpoint.
+pub struct HealthReport {
+    pub health: Result<(), String>,
+    pub latency: Duration,
+    pub slot: Option<u64>,
+    /// The slot of the configured reference endpoint, if one is configured
+    /// and it is not the checked endpoint.
+    pub reference_slot: Option<u64>,
+}
+
+impl HealthReport {
+    /// How many slots the endpoint is behind the reference endpoint.
+    pub fn slot_lag(&self) -> Option<u64> {
+        Some(self.reference_slot?.saturating_sub(self.slot?))
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.health.is_ok() && self.slot_lag().unwrap_or_default() <= LAGGING_SLOTS
+    }
+
+    pub fn summary(&self) -> String {
+        let health = match &self.health {
+            Ok(()) => "ok".to_string(),
+            Err(err) => err.clone(),
+        };
+        let mut summary = format!("{health}, {}ms", self.latency.as_millis());
+        if let Some(slot) = self.slot {
+            summary = format!("{summary}, slot {slot}");
+        }
+        if let Some(slot_lag) = self.slot_lag() {
+            summary = format!("{summary}, lag {slot_lag}");
+        }
+        summary
+    }
+}
+
+/// The latest health report of the active endpoint. Each check is a
+/// command, and the next is requested once its report arrives.
+#[derive(Default)]
+pub struct EndpointHealth {
+    /// Advanced on each switch of endpoint, so the checks of the previous
+    /// endpoint stop.
+    pub generation: u64,
+    pub report: Option<HealthReport>,
+}
+
+impl EndpointHealth {
+    /// Switch the checked endpoint, discarding the report of the previous
+    /// one. Returns the generation of the checks of the new endpoint.
+    pub fn restart(&mut self) -> u64 {
+        self.generation += 1;
+        self.report = None;
+        self.generation
+    }
+
+    /// Keep a report unless the endpoint was switched since its check was
+    /// requested. Returns whether it was kept.
+    pub fn record(&mut self, generation: u64, report: HealthReport) -> bool {
+        if generation != self.generation {
+            return false;
+        }
+        self.report = Some(report);
+        true
+    }
+}
+
+/// Check an endpoint, comparing its slot with that of `reference`, if any.
+pub fn check(rpc: &dyn RpcApi, reference: Option<&dyn RpcApi>) -> HealthReport {
+    let start = Instant::now();
+    let health = rpc.get_health().map_err(|err| format!("unhealthy: {err}"));
+    let latency = start.elapsed();
+    let slot = rpc.get_slot(CommitmentConfig::finalized()).ok();
+    let reference_slot =
+        reference.and_then(|reference| reference.get_slot(CommitmentConfig::finalized()).ok());
+    HealthReport {
+        health,
+        latency,
+        slot,
+        reference_slot,
+    }
+}
+
+/// The public endpoint of the cluster an endpoint most likely belongs to.
+pub fn public_endpoint(endpoint: &str) -> &'static str {
+    if endpoint.contains("devnet") {
+        "https://api.devnet.solana.com"
+    } else if endpoint.contains("testnet") {
+        "https://api.testnet.solana.com"
+    } else {
+        "https://api.mainnet-beta.solana.com"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::rpc::Request,
+        serde_json::{json, Value},
+    };
+
+    struct Node {
+        health: Result<Value, String>,
+        slot: u64,
+    }
+
+    impl RpcApi for Node {
+        fn send(&self, request: Request) -> Result<Value, String> {
+            match request {
+                Request::GetHealth => self.health.clone(),
+                Request::GetSlot { .. } => Ok(json!(self.slot)),
+                request => Err(format!("unexpected {}", request.method())),
+            }
+        }
+    }
+
+    fn node(slot: u64) -> Node {
+        Node {
+            health: Ok(json!("ok")),
+            slot,
+        }
+    }
+
+    #[test]
+    fn compares_the_slot_with_the_reference() {
+        let report = check(&node(1_000), None);
+        assert!(report.is_healthy());
+        assert_eq!(report.slot_lag(), None);
+        assert!(report.summary().starts_with("ok, "));
+        assert!(report.summary().ends_with("ms, slot 1000"));
+
+        let report = check(&node(1_000), Some(&node(1_000 + LAGGING_SLOTS)));
+        assert_eq!(report.slot_lag(), Some(LAGGING_SLOTS));
+        assert!(report.is_healthy());
+        assert!(report.summary().ends_with("slot 1000, lag 50"));
+
+        let report = check(&node(1_000), Some(&node(1_001 + LAGGING_SLOTS)));
+        assert!(!report.is_healthy());
+
+        // A reference behind the endpoint is no lag.
+        let report = check(&node(1_000), Some(&node(900)));
+        assert_eq!(report.slot_lag(), Some(0));
+    }
+
+    #[test]
+    fn reports_an_unhealthy_endpoint() {
+        let unhealthy = Node {
+            health: Err("Node is behind by 120 slots".to_string()),
+            slot: 1_000,
+        };
+        let report = check(&unhealthy, Some(&node(1_000)));
+        assert!(!report.is_healthy());
+        assert!(report
+            .summary()
+            .starts_with("unhealthy: Node is behind by 120 slots, "));
+    }
+
+    #[test]
+    fn ignores_reports_of_a_previous_endpoint() {
+        let mut endpoint_health = EndpointHealth::default();
+        let previous = endpoint_health.restart();
+        assert!(endpoint_health.record(previous, check(&node(1), None)));
+        let current = endpoint_health.restart();
+        assert!(endpoint_health.report.is_none());
+        assert!(!endpoint_health.record(previous, check(&node(2), None)));
+        assert!(endpoint_health.report.is_none());
+        assert!(endpoint_health.record(current, check(&node(3), None)));
+        assert_eq!(endpoint_health.report.unwrap().slot, Some(3));
+    }
+}
@@ -0,0 +1,125 @@
+//! Ledger entries, the batches of transactions which validators shred and
+//! store. An entries blob is a bincode serialized `Vec<Entry>`, as held by
+//! the data shreds of a slot.
+
+use {
+    crate::ingest,
+    serde::{de::DeserializeOwned, Deserialize, Serialize},
+    solana_sdk::{hash::Hash, transaction::VersionedTransaction},
+};
+
+/// The serialized layout of a ledger entry.
+#[derive(Serialize, Deserialize)]
+pub struct Entry {
+    pub num_hashes: u64,
+    pub hash: Hash,
+    pub transactions: Vec<VersionedTransaction>,
+}
+
+/// A transaction found in an entries blob.
+pub struct EntryTransaction {
+    pub entry_index: usize,
+    /// The index of the transaction within its entry.
+    pub index: usize,
+    /// The offset of the first byte of the transaction in the blob.
+    pub offset: usize,
+    pub transaction: VersionedTransaction,
+}
+
+/// Locate every transaction of an entries blob.
+pub fn parse_entries(bytes: &[u8]) -> Result<Vec<EntryTransaction>, String> {
+    let mut reader = bytes;
+    let mut transactions = vec![];
+    let num_entries = read::<u64>(&mut reader, "entry count")?;
+    for entry_index in 0..num_entries as usize {
+        read::<u64>(&mut reader, "number of hashes")?;
+        read::<Hash>(&mut reader, "entry hash")?;
+        let num_transactions = read::<u64>(&mut reader, "transaction count")?;
+        for index in 0..num_transactions as usize {
+            let offset = bytes.len() - reader.len();
+            read::<VersionedTransaction>(&mut reader, "transaction")?;
+            // Parse the transaction again from its own bytes, so that its
+            // sections are known to cover exactly those bytes.
+            let end = bytes.len() - reader.len();
+            let transaction = ingest::parse_transaction(&bytes[offset..end])
+                .map_err(|err| format!("Entry {entry_index} transaction {index}: {err}"))?;
+            transactions.push(EntryTransaction {
+                entry_index,
+                index,
+                offset,
+                transaction,
+            });
+        }
+    }
+    if !reader.is_empty() {
+        return Err(format!("Invalid entries: {} trailing bytes", reader.len()));
+    }
+    Ok(transactions)
+}
+
+fn read<T: DeserializeOwned>(reader: &mut &[u8], what: &str) -> Result<T, String> {
+    bincode::deserialize_from(reader).map_err(|err| format!("Invalid {what}: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            message::{legacy, VersionedMessage},
+            pubkey::Pubkey,
+            signature::Signature,
+        },
+    };
+
+    fn transaction(num_signatures: usize) -> VersionedTransaction {
+        let message = legacy::Message::new(&[], Some(&Pubkey::new_unique()));
+        VersionedTransaction {
+            signatures: vec![Signature::new_unique(); num_signatures],
+            message: VersionedMessage::Legacy(message),
+        }
+    }
+
+    #[test]
+    fn locates_transactions() {
+        let entries = vec![
+            Entry {
+                num_hashes: 12,
+                hash: Hash::new_unique(),
+                transactions: vec![transaction(1), transaction(2)],
+            },
+            Entry {
+                num_hashes: 1,
+                hash: Hash::new_unique(),
+                transactions: vec![],
+            },
+            Entry {
+                num_hashes: 3,
+                hash: Hash::new_unique(),
+                transactions: vec![transaction(1)],
+            },
+        ];
+        let bytes = bincode::serialize(&entries).unwrap();
+
+        let located = parse_entries(&bytes).unwrap();
+        let positions = located
+            .iter()
+            .map(|located| (located.entry_index, located.index))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, [(0, 0), (0, 1), (2, 0)]);
+        for located in located {
+            let expected = &entries[located.entry_index].transactions[located.index];
+            let serialized = bincode::serialize(expected).unwrap();
+            assert_eq!(
+                &bytes[located.offset..located.offset + serialized.len()],
+                serialized
+            );
+            assert_eq!(&located.transaction, expected);
+        }
+
+        assert!(parse_entries(&bytes[..bytes.len() - 1]).is_err());
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(parse_entries(&trailing).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! Names for the custom error codes of programs, from the errors of loaded
+//! Anchor IDLs or from the `error_codes` of the settings file, which take
+//! precedence, e.g.
+//!
+//! ```json
+//! "error_codes": {
+//!     "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4": { "6001": "SlippageToleranceExceeded" }
+//! }
+//! ```
+
+use {
+    serde::{Deserialize, Deserializer},
+    serde_json::Value,
+    solana_sdk::{instruction::InstructionError, pubkey::Pubkey, transaction::TransactionError},
+    std::{
+        collections::{BTreeMap, HashMap},
+        str::FromStr,
+    },
+};
+
+/// Error names by code, by program id as written in the settings file.
+pub type ErrorMap = BTreeMap<String, BTreeMap<u32, String>>;
+
+/// Deserialize the valid entries of an error map, skipping codes which are
+/// not numbers and names which are not strings, so one mistyped entry does
+/// not fail the whole settings file.
+pub fn deserialize_lenient<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<ErrorMap, D::Error> {
+    let programs = BTreeMap::<String, Value>::deserialize(deserializer)?;
+    Ok(programs
+        .into_iter()
+        .filter_map(|(program_id, codes)| {
+            let codes = codes
+                .as_object()?
+                .iter()
+                .filter_map(|(code, name)| Some((code.parse().ok()?, name.as_str()?.to_string())))
+                .collect();
+            Some((program_id, codes))
+        })
+        .collect())
+}
+
+#[derive(Debug, Default)]
+pub struct ErrorCodes {
+    user: HashMap<Pubkey, BTreeMap<u32, String>>,
+    idl: HashMap<Pubkey, BTreeMap<u32, String>>,
+}
+
+impl ErrorCodes {
+    /// The codes of the user's map, skipping invalid program ids.
+    pub fn new(user: &ErrorMap) -> Self {
+        Self {
+            user: user
+                .iter()
+                .filter_map(|(program_id, codes)| {
+                    Some((Pubkey::from_str(program_id).ok()?, codes.clone()))
+                })
+                .collect(),
+            idl: HashMap::new(),
+        }
+    }
+
+    /// Add the codes of a program's IDL, replacing those of an earlier one.
+    pub fn insert_idl(&mut self, program_id: Pubkey, codes: BTreeMap<u32, String>) {
+        self.idl.insert(program_id, codes);
+    }
+
+    pub fn resolve(&self, program_id: &Pubkey, code: u32) -> Option<&str> {
+        [&self.user, &self.idl]
+            .into_iter()
+            .find_map(|codes| codes.get(program_id)?.get(&code))
+            .map(String::as_str)
+    }
+
+    /// The description of a transaction error, naming a custom error code
+    /// if it resolves. `program_id` gives the program of an instruction by
+    /// its index.
+    pub fn describe(
+        &self,
+        err: &TransactionError,
+        program_id: impl Fn(u8) -> Option<Pubkey>,
+    ) -> String {
+        let description = err.to_string();
+        if let TransactionError::InstructionError(index, InstructionError::Custom(code)) = err {
+            if let Some(name) = program_id(*index).and_then(|id| self.resolve(&id, *code)) {
+                return format!("{description} ({name})");
+            }
+        }
+        description
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_the_user_map_to_idls() {
+        let (program_id, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let user: ErrorMap = serde_json::from_str(&format!(
+            r#"{{ "{program_id}": {{ "6001": "Slippage" }}, "not a pubkey": {{ "1": "x" }} }}"#
+        ))
+        .unwrap();
+        let mut error_codes = ErrorCodes::new(&user);
+        let idl = |name: &str| BTreeMap::from([(6000, name.to_string()), (6001, name.to_string())]);
+        error_codes.insert_idl(program_id, idl("FromIdl"));
+        error_codes.insert_idl(other, idl("Other"));
+        assert_eq!(error_codes.resolve(&program_id, 6001), Some("Slippage"));
+        assert_eq!(error_codes.resolve(&program_id, 6000), Some("FromIdl"));
+        assert_eq!(error_codes.resolve(&program_id, 6002), None);
+
+        let err = TransactionError::InstructionError(1, InstructionError::Custom(6001));
+        assert_eq!(
+            error_codes.describe(&err, |index| (index == 1).then_some(program_id)),
+            "Error processing Instruction 1: custom program error: 0x1771 (Slippage)"
+        );
+        assert_eq!(
+            error_codes.describe(&err, |_| None),
+            "Error processing Instruction 1: custom program error: 0x1771"
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! Explorer links entered in place of a signature, e.g.
+//! `https://explorer.solana.com/tx/<signature>?cluster=devnet`, from the
+//! Solana Explorer, Solscan or Solana FM. The cluster of the link selects
+//! the endpoint to fetch from, mainnet-beta if it names none.
+
+use {solana_sdk::signature::Signature, std::str::FromStr};
+
+const HOSTS: &[&str] = &["explorer.solana.com", "solscan.io", "solana.fm"];
+
+pub struct ExplorerLink {
+    pub signature: Signature,
+    /// The public endpoint of the link's cluster.
+    pub endpoint: &'static str,
+}
+
+/// Parse an explorer link to a transaction. None if the text is not a link
+/// to a known explorer.
+pub fn parse(text: &str) -> Option<Result<ExplorerLink, String>> {
+    let text = text.trim();
+    let address = ["https://", "http://"]
+        .iter()
+        .find_map(|scheme| text.strip_prefix(scheme))
+        .unwrap_or(text);
+    let (host, rest) = address.split_once('/')?;
+    if !HOSTS.contains(&host.trim_start_matches("www.")) {
+        return None;
+    }
+    let rest = rest.split('#').next().unwrap_or_default();
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    Some(link(host, path, query))
+}
+
+fn link(host: &str, path: &str, query: &str) -> Result<ExplorerLink, String> {
+    let signature = path
+        .strip_prefix("tx/")
+        .ok_or_else(|| format!("The {host} link is not to a transaction"))?;
+    let signature = Signature::from_str(signature.trim_end_matches('/'))
+        .map_err(|_| format!("The {host} link has an invalid signature"))?;
+    let cluster = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("cluster="));
+    // Solana FM suffixes its cluster names, e.g. `devnet-solana`.
+    let endpoint = match cluster.map(|cluster| cluster.split('-').next().unwrap_or_default()) {
+        None | Some("mainnet") => "https://api.mainnet-beta.solana.com",
+        Some("testnet") => "https://api.testnet.solana.com",
+        Some("devnet") => "https://api.devnet.solana.com",
+        Some(_) => {
+            return Err(format!(
+                "The {host} link is to an unsupported cluster; choose its endpoint in the menu"
+            ))
+        }
+    };
+    Ok(ExplorerLink {
+        signature,
+        endpoint,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_links_of_each_explorer() {
+        let signature = Signature::new_unique();
+        let endpoint = |text: String| parse(&text).unwrap().map(|link| link.endpoint);
+        assert_eq!(
+            endpoint(format!("https://explorer.solana.com/tx/{signature}")),
+            Ok("https://api.mainnet-beta.solana.com")
+        );
+        assert_eq!(
+            endpoint(format!(
+                "solscan.io/tx/{signature}?cluster=devnet#instructions"
+            )),
+            Ok("https://api.devnet.solana.com")
+        );
+        assert_eq!(
+            endpoint(format!(
+                "https://solana.fm/tx/{signature}?cluster=testnet-solana"
+            )),
+            Ok("https://api.testnet.solana.com")
+        );
+        let link = parse(&format!("https://www.solscan.io/tx/{signature}/")).unwrap();
+        assert_eq!(link.unwrap().signature, signature);
+
+        assert!(endpoint(format!(
+            "https://explorer.solana.com/tx/{signature}?cluster=custom&customUrl=x"
+        ))
+        .is_err());
+        assert!(
+            endpoint("https://solscan.io/account/11111111111111111111111111111111".into()).is_err()
+        );
+        assert!(parse(&signature.to_string()).is_none());
+        assert!(parse(&format!("https://example.com/tx/{signature}")).is_none());
+    }
+}
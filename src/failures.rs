@@ -0,0 +1,193 @@
+//! Triage of the failed transactions of list views: failures are grouped by
+//! kind of error, and lists filtered to failures or to a single kind.
+
+use {
+    solana_sdk::{instruction::InstructionError, pubkey::Pubkey, transaction::TransactionError},
+    std::collections::HashMap,
+};
+
+/// What a transaction failed with, without the details which differ between
+/// failures of the same cause, e.g. the index of the failing instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FailureKind {
+    /// The program of the failing instruction, for instruction errors whose
+    /// program is known. Custom error codes are specific to it.
+    pub program: Option<Pubkey>,
+    /// The error variant, with the code of custom errors.
+    pub error: String,
+    pub code: Option<u32>,
+}
+
+impl FailureKind {
+    /// The kind of an error, with `program_id` giving the program of an
+    /// instruction by its index.
+    pub fn new(err: &TransactionError, program_id: impl Fn(u8) -> Option<Pubkey>) -> Self {
+        match err {
+            TransactionError::InstructionError(index, error) => Self {
+                program: program_id(*index),
+                error: match error {
+                    InstructionError::Custom(code) => format!("Custom({code})"),
+                    error => variant(&format!("{error:?}")),
+                },
+                code: match error {
+                    InstructionError::Custom(code) => Some(*code),
+                    _ => None,
+                },
+            },
+            err => Self {
+                program: None,
+                error: variant(&format!("{err:?}")),
+                code: None,
+            },
+        }
+    }
+
+    /// The error and its program, with `name` naming the program and
+    /// `error_name` the custom error codes of a program.
+    pub fn describe(
+        &self,
+        name: impl Fn(&Pubkey) -> String,
+        error_name: impl Fn(&Pubkey, u32) -> Option<String>,
+    ) -> String {
+        let Some(program) = &self.program else {
+            return self.error.clone();
+        };
+        let error = match self.code.and_then(|code| error_name(program, code)) {
+            Some(error_name) => format!("{} ({error_name})", self.error),
+            None => self.error.clone(),
+        };
+        format!("{error} in {}", name(program))
+    }
+}
+
+/// The name of a variant from its debug output.
+fn variant(debug: &str) -> String {
+    debug
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// The kinds of failures with their counts, most frequent first.
+pub fn group(kinds: impl IntoIterator<Item = FailureKind>) -> Vec<(FailureKind, usize)> {
+    let mut counts = HashMap::<FailureKind, usize>::new();
+    for kind in kinds {
+        *counts.entry(kind).or_default() += 1;
+    }
+    let mut groups = counts.into_iter().collect::<Vec<_>>();
+    groups.sort_by(|(a, a_count), (b, b_count)| {
+        b_count
+            .cmp(a_count)
+            .then_with(|| a.error.cmp(&b.error))
+            .then_with(|| a.program.cmp(&b.program))
+    });
+    groups
+}
+
+/// Which transactions a list view shows.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FailureFilter {
+    #[default]
+    All,
+    Failed,
+    Kind(FailureKind),
+}
+
+impl FailureFilter {
+    /// Whether a transaction which failed with `kind`, if at all, is shown.
+    pub fn matches(&self, kind: Option<&FailureKind>) -> bool {
+        match self {
+            Self::All => true,
+            Self::Failed => kind.is_some(),
+            Self::Kind(filtered) => kind == Some(filtered),
+        }
+    }
+
+    /// The next filter: failures, then each of their kinds in turn, then
+    /// all transactions again.
+    pub fn next(&self, groups: &[(FailureKind, usize)]) -> Self {
+        let next_kind = match self {
+            Self::All => return Self::Failed,
+            Self::Failed => groups.first(),
+            Self::Kind(kind) => groups
+                .iter()
+                .position(|(grouped, _)| grouped == kind)
+                .and_then(|index| groups.get(index + 1)),
+        };
+        next_kind.map_or(Self::All, |(kind, _)| Self::Kind(kind.clone()))
+    }
+
+    pub fn describe(
+        &self,
+        name: impl Fn(&Pubkey) -> String,
+        error_name: impl Fn(&Pubkey, u32) -> Option<String>,
+    ) -> String {
+        match self {
+            Self::All => "all".to_string(),
+            Self::Failed => "failed".to_string(),
+            Self::Kind(kind) => kind.describe(name, error_name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_failures_by_kind() {
+        let program = Pubkey::new_unique();
+        let program_id = |_| Some(program);
+        let custom =
+            |index, code| TransactionError::InstructionError(index, InstructionError::Custom(code));
+        let kinds = [
+            custom(0, 6001),
+            custom(2, 6001),
+            custom(1, 6002),
+            TransactionError::InsufficientFundsForRent { account_index: 3 },
+        ]
+        .iter()
+        .map(|err| FailureKind::new(err, program_id))
+        .collect::<Vec<_>>();
+        assert_eq!(kinds[0], kinds[1]);
+        assert_eq!(kinds[3].error, "InsufficientFundsForRent");
+        assert_eq!(kinds[3].program, None);
+        let name = |_: &Pubkey| "jupiter".to_string();
+        assert_eq!(
+            kinds[0].describe(name, |_, _| None),
+            "Custom(6001) in jupiter"
+        );
+        assert_eq!(
+            kinds[0].describe(name, |_, code| (code == 6001)
+                .then(|| "Slippage".to_string())),
+            "Custom(6001) (Slippage) in jupiter"
+        );
+        let computation =
+            TransactionError::InstructionError(0, InstructionError::ComputationalBudgetExceeded);
+        assert_eq!(
+            FailureKind::new(&computation, |_| None).error,
+            "ComputationalBudgetExceeded"
+        );
+
+        let groups = group(kinds.clone());
+        assert_eq!(groups[0], (kinds[0].clone(), 2));
+        assert_eq!(groups.len(), 3);
+
+        let mut filter = FailureFilter::default();
+        let mut shown = vec![];
+        for _ in 0..5 {
+            filter = filter.next(&groups);
+            shown.push(
+                kinds
+                    .iter()
+                    .filter(|kind| filter.matches(Some(kind)))
+                    .count(),
+            );
+        }
+        // Failed, each kind, then all again.
+        assert_eq!(shown, [4, 2, 1, 1, 4]);
+        assert_eq!(filter, FailureFilter::All);
+        assert!(!FailureFilter::Failed.matches(None));
+    }
+}
@@ -0,0 +1,128 @@
+//! Finding which of the transactions at hand reference a pubkey or contain
+//! a byte pattern: the viewed one, those of a loaded entries file, and the
+//! snapshots of bookmarks.
+
+use {
+    solana_sdk::{pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction},
+    std::str::FromStr,
+};
+
+/// Where a transaction searched is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Viewed,
+    /// The transaction of the entries file at this index.
+    Entry(usize),
+    Bookmark,
+}
+
+impl Location {
+    pub fn name(&self) -> String {
+        match self {
+            Self::Viewed => "viewed".to_string(),
+            Self::Entry(index) => format!("entry transaction {index}"),
+            Self::Bookmark => "bookmark".to_string(),
+        }
+    }
+}
+
+pub struct Hit {
+    pub location: Location,
+    pub signature: Signature,
+    /// The offsets of each occurrence in the transaction bytes.
+    pub offsets: Vec<usize>,
+}
+
+/// The bytes of a query typed as a pubkey, or as hex optionally prefixed
+/// with `0x`.
+pub fn parse(text: &str) -> Result<Vec<u8>, String> {
+    let text = text.trim();
+    if let Ok(pubkey) = Pubkey::from_str(text) {
+        return Ok(pubkey.to_bytes().to_vec());
+    }
+    let hex = text.trim_start_matches("0x").replace(' ', "");
+    match hex::decode(&hex) {
+        Ok(bytes) if !bytes.is_empty() => Ok(bytes),
+        _ => Err(format!("{text} is neither a pubkey nor hex bytes")),
+    }
+}
+
+/// The offsets of every occurrence of `pattern` in `bytes`.
+pub fn occurrences(pattern: &[u8], bytes: &[u8]) -> Vec<usize> {
+    if pattern.is_empty() {
+        return vec![];
+    }
+    bytes
+        .windows(pattern.len())
+        .enumerate()
+        .filter(|(_, window)| *window == pattern)
+        .map(|(offset, _)| offset)
+        .collect()
+}
+
+/// The transactions containing `pattern`, in the order given.
+pub fn search<'a>(
+    pattern: &[u8],
+    transactions: impl IntoIterator<Item = (Location, &'a VersionedTransaction)>,
+) -> Vec<Hit> {
+    transactions
+        .into_iter()
+        .filter_map(|(location, transaction)| {
+            let bytes = bincode::serialize(transaction).ok()?;
+            let offsets = occurrences(pattern, &bytes);
+            (!offsets.is_empty()).then(|| Hit {
+                location,
+                signature: transaction.signatures.first().copied().unwrap_or_default(),
+                offsets,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash, signature::Keypair, signer::Signer, system_instruction,
+            transaction::Transaction,
+        },
+    };
+
+    #[test]
+    fn finds_pubkeys_and_byte_patterns() {
+        let transfer = |to: &Pubkey, lamports| {
+            let payer = Keypair::new();
+            let instruction = system_instruction::transfer(&payer.pubkey(), to, lamports);
+            VersionedTransaction::from(Transaction::new_signed_with_payer(
+                &[instruction],
+                Some(&payer.pubkey()),
+                &[&payer],
+                Hash::new_unique(),
+            ))
+        };
+        let recipient = Pubkey::new_unique();
+        let transactions = [
+            transfer(&recipient, 1),
+            transfer(&Pubkey::new_unique(), 0x0102_0304),
+            transfer(&recipient, 0x0102_0304),
+        ];
+        let locations = [Location::Viewed, Location::Entry(0), Location::Bookmark];
+
+        let pattern = parse(&recipient.to_string()).unwrap();
+        let hits = search(&pattern, locations.into_iter().zip(&transactions));
+        let found = hits.iter().map(|hit| hit.location).collect::<Vec<_>>();
+        assert_eq!(found, [Location::Viewed, Location::Bookmark]);
+        assert_eq!(hits[0].signature, transactions[0].signatures[0]);
+        assert_eq!(hits[0].offsets.len(), 1);
+
+        // Lamports are little endian.
+        let pattern = parse("0x04 03 02 01").unwrap();
+        let hits = search(&pattern, locations.into_iter().zip(&transactions));
+        let found = hits.iter().map(|hit| hit.location).collect::<Vec<_>>();
+        assert_eq!(found, [Location::Entry(0), Location::Bookmark]);
+
+        assert_eq!(occurrences(&[1, 1], &[1, 1, 1]), [0, 1]);
+        assert!(parse("xyz!").is_err());
+    }
+}
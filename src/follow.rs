@@ -0,0 +1,126 @@
+//! Following the latest transaction of an address, e.g. a bot or a keeper
+//! being iterated on: its newest signature is polled about once a slot and
+//! each newer transaction replaces the viewed one.
+
+use {
+    crate::rpc::RpcApi,
+    solana_client::rpc_config::RpcSignaturesForAddressConfig,
+    solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature},
+    std::{str::FromStr, time::Duration},
+};
+
+/// How long to wait between polls, about a slot.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(400);
+
+/// The newest transaction of an address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Latest {
+    pub signature: Signature,
+    pub slot: u64,
+}
+
+pub struct Follow {
+    pub address: Pubkey,
+    /// The transaction viewed last.
+    pub latest: Option<Latest>,
+    /// How many times a newer transaction replaced the viewed one.
+    pub num_updates: usize,
+    pub error: Option<String>,
+}
+
+impl Follow {
+    pub fn new(address: Pubkey) -> Self {
+        Self {
+            address,
+            latest: None,
+            num_updates: 0,
+            error: None,
+        }
+    }
+
+    /// Record the outcome of a poll. Returns the transaction to view if it
+    /// is newer than the one viewed. Transactions of earlier slots are
+    /// ignored, as endpoints behind a load balancer may lag one another.
+    pub fn record(&mut self, result: Result<Option<Latest>, String>) -> Option<Signature> {
+        let latest = match result {
+            Ok(latest) => {
+                self.error = None;
+                latest?
+            }
+            Err(err) => {
+                self.error = Some(err);
+                return None;
+            }
+        };
+        if let Some(viewed) = self.latest {
+            if latest.signature == viewed.signature || latest.slot < viewed.slot {
+                return None;
+            }
+            self.num_updates += 1;
+        }
+        self.latest = Some(latest);
+        Some(latest.signature)
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!("Following {} ({} updates", self.address, self.num_updates);
+        if let Some(latest) = self.latest {
+            summary = format!("{summary}, latest in slot {}", latest.slot);
+        }
+        summary = format!("{summary})");
+        if let Some(error) = &self.error {
+            summary = format!("{summary}: {error}");
+        }
+        summary
+    }
+}
+
+/// The newest confirmed transaction of the address, if it has any.
+pub fn poll(rpc: &dyn RpcApi, address: &Pubkey) -> Result<Option<Latest>, String> {
+    let config = RpcSignaturesForAddressConfig {
+        limit: Some(1),
+        commitment: Some(CommitmentConfig::confirmed()),
+        ..RpcSignaturesForAddressConfig::default()
+    };
+    let statuses = rpc
+        .get_signatures_for_address(address, config)
+        .map_err(|err| format!("Failed to get signatures for {address}: {err}"))?;
+    statuses
+        .first()
+        .map(|status| {
+            let signature = Signature::from_str(&status.signature)
+                .map_err(|err| format!("Invalid signature {}: {err}", status.signature))?;
+            Ok(Latest {
+                signature,
+                slot: status.slot,
+            })
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn views_only_newer_transactions() {
+        let latest = |slot| Latest {
+            signature: Signature::new_unique(),
+            slot,
+        };
+        let mut follow = Follow::new(Pubkey::new_unique());
+        assert_eq!(follow.record(Ok(None)), None);
+        let first = latest(10);
+        assert_eq!(follow.record(Ok(Some(first))), Some(first.signature));
+        assert_eq!(follow.record(Ok(Some(first))), None);
+        // A lagging endpoint may answer with an older transaction.
+        assert_eq!(follow.record(Ok(Some(latest(9)))), None);
+        assert_eq!(follow.record(Err("timed out".to_string())), None);
+        assert!(follow.summary().ends_with("latest in slot 10): timed out"));
+
+        let second = latest(11);
+        assert_eq!(follow.record(Ok(Some(second))), Some(second.signature));
+        assert_eq!(follow.num_updates, 1);
+        assert!(follow.error.is_none());
+    }
+}
@@ -0,0 +1,158 @@
+//! Formatting of numbers, amounts and timestamps according to the user's
+//! settings.
+
+use {
+    crate::{
+        address_book::AddressBook,
+        settings::{AmountUnit, Settings, TimeZone},
+    },
+    chrono::{DateTime, Local, Utc},
+    solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey},
+};
+
+/// Format an integer with the configured digit grouping, e.g. `1,234,567`.
+pub fn integer(settings: &Settings, value: u64) -> String {
+    let digits = value.to_string();
+    let Some(separator) = settings.digit_grouping.separator() else {
+        return digits;
+    };
+    let mut grouped = String::with_capacity(digits.len() * 4 / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Format an amount of lamports in the configured unit. SOL amounts keep
+/// every significant decimal, so no precision is lost.
+pub fn lamports(settings: &Settings, lamports: u64) -> String {
+    match settings.amount_unit {
+        AmountUnit::Lamports => format!("{} lamports", integer(settings, lamports)),
+        AmountUnit::Sol => {
+            let whole = integer(settings, lamports / LAMPORTS_PER_SOL);
+            let fraction = format!("{:09}", lamports % LAMPORTS_PER_SOL);
+            let fraction = fraction.trim_end_matches('0');
+            if fraction.is_empty() {
+                format!("{whole} SOL")
+            } else {
+                let decimal_point = settings.digit_grouping.decimal_point();
+                format!("{whole}{decimal_point}{fraction} SOL")
+            }
+        }
+    }
+}
+
+/// Format a byte count, e.g. `1,232 bytes`.
+pub fn bytes(settings: &Settings, bytes: usize) -> String {
+    match bytes {
+        1 => "1 byte".to_string(),
+        _ => format!("{} bytes", integer(settings, bytes as u64)),
+    }
+}
+
+/// Format a unix timestamp in the configured time zone.
+pub fn timestamp(settings: &Settings, unix_timestamp: i64) -> String {
+    let Some(utc) = DateTime::<Utc>::from_timestamp(unix_timestamp, 0) else {
+        return format!("invalid timestamp {unix_timestamp}");
+    };
+    match settings.time_zone {
+        TimeZone::Utc => utc.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        TimeZone::Local => utc
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S %:z")
+            .to_string(),
+    }
+}
+
+/// Format a pubkey as its name in the address book, or in the configured
+/// representation if it has none.
+pub fn pubkey(settings: &Settings, address_book: &AddressBook, pubkey: &Pubkey) -> String {
+    match address_book.name(pubkey) {
+        Some(name) => name.to_string(),
+        None => settings.pubkey_display.format(pubkey),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::settings::DigitGrouping};
+
+    fn settings(digit_grouping: DigitGrouping, amount_unit: AmountUnit) -> Settings {
+        let mut settings = Settings::default();
+        settings.digit_grouping = digit_grouping;
+        settings.amount_unit = amount_unit;
+        settings
+    }
+
+    #[test]
+    fn groups_digits_in_threes() {
+        let grouped =
+            |digit_grouping, value| integer(&settings(digit_grouping, AmountUnit::Lamports), value);
+        for value in [0, 7, 999] {
+            assert_eq!(grouped(DigitGrouping::Comma, value), value.to_string());
+        }
+        assert_eq!(grouped(DigitGrouping::Comma, 1_000), "1,000");
+        assert_eq!(grouped(DigitGrouping::Comma, 123_456), "123,456");
+        assert_eq!(
+            grouped(DigitGrouping::Comma, u64::MAX),
+            "18,446,744,073,709,551,615"
+        );
+        assert_eq!(
+            grouped(DigitGrouping::Period, u64::MAX),
+            "18.446.744.073.709.551.615"
+        );
+        assert_eq!(
+            grouped(DigitGrouping::Space, u64::MAX),
+            "18 446 744 073 709 551 615"
+        );
+        assert_eq!(
+            grouped(DigitGrouping::None, u64::MAX),
+            "18446744073709551615"
+        );
+    }
+
+    #[test]
+    fn formats_lamports_in_either_unit() {
+        let sol =
+            |digit_grouping, value| lamports(&settings(digit_grouping, AmountUnit::Sol), value);
+        assert_eq!(sol(DigitGrouping::Comma, 0), "0 SOL");
+        assert_eq!(sol(DigitGrouping::Comma, 1), "0.000000001 SOL");
+        assert_eq!(sol(DigitGrouping::Comma, 5_000), "0.000005 SOL");
+        assert_eq!(sol(DigitGrouping::Comma, LAMPORTS_PER_SOL), "1 SOL");
+        assert_eq!(
+            sol(DigitGrouping::Comma, 1_234 * LAMPORTS_PER_SOL + 500_000_000),
+            "1,234.5 SOL"
+        );
+        // The decimal point is a comma where the period groups digits.
+        assert_eq!(
+            sol(
+                DigitGrouping::Period,
+                1_234 * LAMPORTS_PER_SOL + 500_000_000
+            ),
+            "1.234,5 SOL"
+        );
+        assert_eq!(
+            sol(DigitGrouping::None, u64::MAX),
+            "18446744073.709551615 SOL"
+        );
+
+        let settings = settings(DigitGrouping::Space, AmountUnit::Lamports);
+        assert_eq!(lamports(&settings, 1_500_000), "1 500 000 lamports");
+        assert_eq!(bytes(&settings, 1), "1 byte");
+        assert_eq!(bytes(&settings, 1_232), "1 232 bytes");
+    }
+
+    #[test]
+    fn shows_numbers_as_before_by_default() {
+        let settings = Settings::default();
+        assert_eq!(integer(&settings, 1_232), "1232");
+        assert_eq!(lamports(&settings, 5_000), "5000 lamports");
+        assert_eq!(
+            timestamp(&settings, 1_700_000_000),
+            "2023-11-14 22:13:20 UTC"
+        );
+    }
+}
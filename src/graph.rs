@@ -0,0 +1,117 @@
+//! A graphviz DOT graph of which instructions read and write which accounts,
+//! for audit reports and postmortems.
+
+use {
+    solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction},
+    solana_transaction_status::UiLoadedAddresses,
+    std::{fmt::Write, str::FromStr},
+};
+
+/// The graph of the transaction, with accounts labelled by `label`. Accounts
+/// loaded from lookup tables are included when `loaded` is known.
+pub fn dot(
+    transaction: &VersionedTransaction,
+    loaded: Option<&UiLoadedAddresses>,
+    label: impl Fn(&Pubkey) -> String,
+) -> String {
+    let message = &transaction.message;
+    let static_keys = message.static_account_keys();
+    let parse = |addresses: &[String]| {
+        addresses
+            .iter()
+            .filter_map(|address| Pubkey::from_str(address).ok())
+            .collect::<Vec<_>>()
+    };
+    let (loaded_writable, loaded_readonly) = loaded
+        .map(|loaded| (parse(&loaded.writable), parse(&loaded.readonly)))
+        .unwrap_or_default();
+    let account_keys = static_keys
+        .iter()
+        .chain(&loaded_writable)
+        .chain(&loaded_readonly)
+        .collect::<Vec<_>>();
+    let is_writable = |index: usize| match index.checked_sub(static_keys.len()) {
+        None => message.is_maybe_writable(index),
+        Some(loaded_index) => loaded_index < loaded_writable.len(),
+    };
+
+    let mut dot = String::from("digraph transaction {\n    rankdir=LR;\n");
+    dot.push_str("    node [fontname=\"monospace\"];\n");
+    for (index, pubkey) in account_keys.iter().enumerate() {
+        let mut attributes = format!("label=\"{}\\n{pubkey}\", shape=box", escape(&label(pubkey)));
+        if message.is_signer(index) {
+            attributes.push_str(", peripheries=2");
+        }
+        if is_writable(index) {
+            attributes.push_str(", style=filled, fillcolor=\"#fde2e2\"");
+        }
+        let _ = writeln!(dot, "    a{index} [{attributes}];");
+    }
+    for (index, instruction) in message.instructions().iter().enumerate() {
+        let program = account_keys
+            .get(instruction.program_id_index as usize)
+            .map(|program_id| label(program_id))
+            .unwrap_or_else(|| "invalid program".to_string());
+        let _ = writeln!(
+            dot,
+            "    i{index} [label=\"#{index} {}\", shape=ellipse];",
+            escape(&program)
+        );
+        let _ = writeln!(
+            dot,
+            "    a{} -> i{index} [label=\"program\", style=bold];",
+            instruction.program_id_index
+        );
+        let mut seen = vec![];
+        for account_index in instruction.accounts.iter().map(|index| *index as usize) {
+            if account_index >= account_keys.len() || seen.contains(&account_index) {
+                continue;
+            }
+            seen.push(account_index);
+            let edge = if is_writable(account_index) {
+                format!("i{index} -> a{account_index} [label=\"writes\", color=red]")
+            } else {
+                format!("a{account_index} -> i{index} [label=\"reads\", style=dashed]")
+            };
+            let _ = writeln!(dot, "    {edge};");
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{hash::Hash, message::Message, system_instruction, transaction::Transaction},
+    };
+
+    #[test]
+    fn graphs_reads_and_writes() {
+        let (from, to) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instruction = system_instruction::transfer(&from, &to, 1);
+        let message = Message::new_with_blockhash(&[instruction], Some(&from), &Hash::default());
+        let transaction = VersionedTransaction::from(Transaction::new_unsigned(message));
+
+        let dot = dot(&transaction, None, |pubkey| {
+            if *pubkey == from {
+                "my \"wallet\"".to_string()
+            } else {
+                pubkey.to_string()
+            }
+        });
+        assert!(dot.starts_with("digraph transaction {"));
+        assert!(dot.contains(&format!(
+            "a0 [label=\"my \\\"wallet\\\"\\n{from}\", shape=box, peripheries=2, style=filled"
+        )));
+        assert!(dot.contains("a2 -> i0 [label=\"program\", style=bold];"));
+        assert!(dot.contains("i0 -> a0 [label=\"writes\", color=red];"));
+        assert!(dot.contains("i0 -> a1 [label=\"writes\", color=red];"));
+        assert!(dot.ends_with("}\n"));
+    }
+}
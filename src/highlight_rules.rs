@@ -0,0 +1,144 @@
+//! Highlight rules defined in the settings file, overlaid on the coloring of
+//! the byte grid to spot patterns across many transactions, e.g.
+//!
+//! ```json
+//! "highlight_rules": [
+//!     { "pattern": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA", "effect": "red" },
+//!     { "pattern": "0xe445a52e51cb9a1d", "discriminator": true, "effect": "blink" }
+//! ]
+//! ```
+
+use {
+    crate::{
+        find,
+        transaction_byte_sections::{section_starts, SectionKind, TransactionByteSection},
+    },
+    ratatui::style::{Color, Modifier, Style},
+    serde::{Deserialize, Serialize},
+    std::ops::Range,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighlightRule {
+    /// A pubkey, or hex bytes optionally prefixed with `0x`.
+    pub pattern: String,
+    /// Only match the start of instruction data, for discriminators.
+    #[serde(default)]
+    pub discriminator: bool,
+    pub effect: HighlightEffect,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HighlightEffect {
+    Red,
+    Yellow,
+    Green,
+    Cyan,
+    Magenta,
+    Bold,
+    Underlined,
+    Reversed,
+    Blink,
+}
+
+impl HighlightEffect {
+    pub fn style(self) -> Style {
+        let color = |color| Style::default().fg(Color::Black).bg(color);
+        match self {
+            Self::Red => color(Color::LightRed),
+            Self::Yellow => color(Color::LightYellow),
+            Self::Green => color(Color::LightGreen),
+            Self::Cyan => color(Color::LightCyan),
+            Self::Magenta => color(Color::LightMagenta),
+            Self::Bold => Style::default().add_modifier(Modifier::BOLD),
+            Self::Underlined => Style::default().add_modifier(Modifier::UNDERLINED),
+            Self::Reversed => Style::default().add_modifier(Modifier::REVERSED),
+            Self::Blink => Style::default().add_modifier(Modifier::SLOW_BLINK),
+        }
+    }
+}
+
+/// The ranges of bytes matched by each rule, with the style to overlay.
+/// Rules with patterns which do not parse are skipped.
+pub fn matches(
+    rules: &[HighlightRule],
+    sections: &[TransactionByteSection],
+) -> Vec<(Range<usize>, Style)> {
+    let bytes = sections
+        .iter()
+        .flat_map(|section| section.bytes.iter().copied())
+        .collect::<Vec<_>>();
+    let mut matches = vec![];
+    for rule in rules {
+        let Ok(pattern) = find::parse(&rule.pattern) else {
+            continue;
+        };
+        let style = rule.effect.style();
+        if rule.discriminator {
+            matches.extend(
+                section_starts(sections)
+                    .filter(|(_, section)| {
+                        matches!(section.kind, SectionKind::InstructionData(_))
+                            && section.bytes.starts_with(&pattern)
+                    })
+                    .map(|(start, _)| (start..start + pattern.len(), style)),
+            );
+        } else {
+            matches.extend(
+                find::occurrences(&pattern, &bytes)
+                    .into_iter()
+                    .map(|offset| (offset..offset + pattern.len(), style)),
+            );
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::transaction_byte_sections::get_transaction_byte_sections,
+        solana_sdk::{
+            hash::Hash,
+            instruction::Instruction,
+            message::Message,
+            pubkey::Pubkey,
+            transaction::{Transaction, VersionedTransaction},
+        },
+    };
+
+    #[test]
+    fn matches_pubkeys_and_discriminators() {
+        let (payer, program_id) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let instructions = [
+            Instruction::new_with_bytes(program_id, &[7, 7, 1], vec![]),
+            Instruction::new_with_bytes(program_id, &[1, 7, 7], vec![]),
+        ];
+        let message = Message::new_with_blockhash(&instructions, Some(&payer), &Hash::default());
+        let transaction = VersionedTransaction::from(Transaction::new_unsigned(message));
+        let mut sections = vec![];
+        get_transaction_byte_sections(&transaction, &mut sections);
+
+        let rules: Vec<HighlightRule> = serde_json::from_str(&format!(
+            r#"[
+                {{ "pattern": "{program_id}", "effect": "red" }},
+                {{ "pattern": "0x0707", "discriminator": true, "effect": "blink" }},
+                {{ "pattern": "not a pattern", "effect": "bold" }}
+            ]"#
+        ))
+        .unwrap();
+        let matches = matches(&rules, &sections);
+        // The signature, header and payer come before the program id.
+        let key_offset = 1 + 64 + 3 + 1 + 32;
+        assert_eq!(
+            matches[0],
+            (key_offset..key_offset + 32, HighlightEffect::Red.style())
+        );
+        // Only the data of the first instruction starts with the pattern.
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[1].1, HighlightEffect::Blink.style());
+        assert_eq!(matches[1].0.len(), 2);
+    }
+}
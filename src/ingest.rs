@@ -0,0 +1,133 @@
+use {
+    crate::message_format::MessageFormat,
+    serde::{Deserialize, Serialize},
+    solana_sdk::{short_vec, signature::Signature, transaction::VersionedTransaction},
+};
+
+/// Parse a transaction from its wire format. The bytes must hold exactly one
+/// transaction, serialized as it would serialize itself, so that the
+/// sections of the parsed transaction cover exactly the given bytes.
+pub fn parse_transaction(bytes: &[u8]) -> Result<VersionedTransaction, String> {
+    let transaction = bincode::deserialize::<VersionedTransaction>(bytes)
+        .map_err(|err| format!("Invalid transaction: {err}"))?;
+    check_canonical(bytes, transaction)
+}
+
+/// Parse a transaction written as hex, as pasted from logs or explorers,
+/// with or without a `0x` prefix and with any whitespace. None if the text
+/// is not hex.
+pub fn parse_hex_transaction(text: &str) -> Option<Result<VersionedTransaction, String>> {
+    let hex = text.split_whitespace().collect::<String>();
+    let hex = hex.strip_prefix("0x").unwrap_or(&hex);
+    if hex.is_empty() {
+        return None;
+    }
+    let bytes = hex::decode(hex).ok()?;
+    Some(parse_transaction(&bytes))
+}
+
+/// Parse a transaction whose message is in `format`, whatever its version
+/// byte suggests. See [`crate::message_format::candidate_formats`] for the
+/// formats a message may be in.
+pub fn parse_transaction_as(
+    bytes: &[u8],
+    format: &dyn MessageFormat,
+) -> Result<VersionedTransaction, String> {
+    #[derive(Serialize, Deserialize)]
+    struct Signatures(#[serde(with = "short_vec")] Vec<Signature>);
+
+    let Signatures(signatures) =
+        bincode::deserialize(bytes).map_err(|err| format!("Invalid transaction: {err}"))?;
+    let message_start = bincode::serialized_size(&Signatures(signatures.clone()))
+        .map_err(|err| format!("Invalid transaction: {err}"))? as usize;
+    let message = format.deserialize(&bytes[message_start..])?;
+    check_canonical(
+        bytes,
+        VersionedTransaction {
+            signatures,
+            message,
+        },
+    )
+}
+
+/// Reject transactions which do not serialize back into exactly `bytes`.
+fn check_canonical(
+    bytes: &[u8],
+    transaction: VersionedTransaction,
+) -> Result<VersionedTransaction, String> {
+    let serialized =
+        bincode::serialize(&transaction).map_err(|err| format!("Invalid transaction: {err}"))?;
+    if serialized.len() < bytes.len() {
+        return Err(format!(
+            "Invalid transaction: {} trailing bytes",
+            bytes.len() - serialized.len()
+        ));
+    }
+    if serialized != bytes {
+        return Err("Invalid transaction: non-canonical encoding".to_string());
+    }
+    Ok(transaction)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash,
+            message::{Message, VersionedMessage},
+            pubkey::Pubkey,
+            system_instruction,
+        },
+    };
+
+    fn transfer_bytes() -> Vec<u8> {
+        let payer = Pubkey::new_unique();
+        let instruction = system_instruction::transfer(&payer, &Pubkey::new_unique(), 1);
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::new_unique()],
+            message: VersionedMessage::Legacy(Message::new_with_blockhash(
+                &[instruction],
+                Some(&payer),
+                &Hash::new_unique(),
+            )),
+        };
+        bincode::serialize(&transaction).unwrap()
+    }
+
+    #[test]
+    fn parses_only_exactly_one_canonical_transaction() {
+        let bytes = transfer_bytes();
+        let transaction = parse_transaction(&bytes).unwrap();
+        assert_eq!(bincode::serialize(&transaction).unwrap(), bytes);
+
+        let mut trailing = bytes.clone();
+        trailing.extend([0, 0]);
+        assert_eq!(
+            parse_transaction(&trailing).err().as_deref(),
+            Some("Invalid transaction: 2 trailing bytes")
+        );
+        assert!(parse_transaction(&bytes[..bytes.len() - 1])
+            .unwrap_err()
+            .starts_with("Invalid transaction: "));
+
+        // A signature count of 1 in two bytes decodes, but not as written.
+        let mut padded = vec![0x81, 0x00];
+        padded.extend(&bytes[1..]);
+        assert!(parse_transaction(&padded).is_err());
+    }
+
+    #[test]
+    fn parses_hex_with_a_prefix_and_whitespace() {
+        let bytes = transfer_bytes();
+        let hex = hex::encode(&bytes);
+        let spaced = format!("0x{}\n  {}", &hex[..10], &hex[10..]);
+        let transaction = parse_hex_transaction(&spaced).unwrap().unwrap();
+        assert_eq!(bincode::serialize(&transaction).unwrap(), bytes);
+
+        assert!(parse_hex_transaction("").is_none());
+        assert!(parse_hex_transaction("0x").is_none());
+        assert!(parse_hex_transaction("5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnb").is_none());
+        assert!(parse_hex_transaction("00ff").unwrap().is_err());
+    }
+}
@@ -0,0 +1,87 @@
+//! The signatures and other inputs entered to view transactions, oldest
+//! first, persisted between sessions to recall with Up and Down.
+
+use {
+    crate::storage::{self, FileChange},
+    std::io,
+};
+
+const INPUT_HISTORY_FILE: &str = "input_history.json";
+/// Older inputs are forgotten.
+const MAX_HISTORY: usize = 100;
+/// Longer inputs, such as pasted transactions, are not recorded. Explorer
+/// links to a transaction fit.
+const MAX_INPUT_LEN: usize = 256;
+
+/// Unless loaded from the data directory nothing is stored, e.g. in tests.
+#[derive(Debug, Default)]
+pub struct InputHistory {
+    persisted: bool,
+    inputs: Vec<String>,
+}
+
+impl InputHistory {
+    pub fn in_data_dir() -> Self {
+        Self {
+            persisted: true,
+            inputs: storage::load_json(INPUT_HISTORY_FILE),
+        }
+    }
+
+    /// The change saving the history, if stored.
+    pub fn save(&self) -> Option<io::Result<FileChange>> {
+        self.persisted
+            .then(|| storage::save_json(INPUT_HISTORY_FILE, &self.inputs))
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.inputs.get(index).map(String::as_str)
+    }
+
+    pub fn num_inputs(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Record an input as the most recent, removing an earlier duplicate.
+    pub fn record(&mut self, input: &str) {
+        if input.len() > MAX_INPUT_LEN {
+            return;
+        }
+        self.inputs.retain(|existing| existing != input);
+        self.inputs.push(input.to_string());
+        let excess = self.inputs.len().saturating_sub(MAX_HISTORY);
+        self.inputs.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_inputs_without_duplicates() {
+        let mut history = InputHistory::default();
+        for input in ["a", "b", "a"] {
+            history.record(input);
+        }
+        assert_eq!(history.num_inputs(), 2);
+        assert_eq!(history.get(0), Some("b"));
+        assert_eq!(history.get(1), Some("a"));
+        for index in 0..MAX_HISTORY {
+            history.record(&index.to_string());
+        }
+        assert_eq!(history.num_inputs(), MAX_HISTORY);
+        assert_eq!(history.get(0), Some("0"));
+        // Nothing is written unless loaded from the data directory.
+        assert!(history.save().is_none());
+    }
+
+    #[test]
+    fn skips_inputs_too_long_to_recall() {
+        let mut history = InputHistory::default();
+        history.record(&"ab".repeat(MAX_INPUT_LEN));
+        assert_eq!(history.num_inputs(), 0);
+        history.record(&"a".repeat(MAX_INPUT_LEN));
+        assert_eq!(history.num_inputs(), 1);
+    }
+}
@@ -0,0 +1,249 @@
+//! What the Instructions sysvar holds for a transaction, and which of its
+//! instructions likely read it to introspect the others, as ed25519
+//! signature checks and flashloan guards do.
+
+use {
+    crate::runtime_accounts,
+    solana_sdk::{
+        bpf_loader_upgradeable, ed25519_program, pubkey::Pubkey, secp256k1_program, sysvar,
+        transaction::VersionedTransaction,
+    },
+};
+
+/// A field of the serialized sysvar.
+pub struct Field {
+    pub offset: usize,
+    pub len: usize,
+    pub description: String,
+}
+
+pub struct Introspection {
+    /// The instructions passed the sysvar, which likely introspect.
+    pub readers: Vec<usize>,
+    /// The precompile instructions, whose checks are usually confirmed by
+    /// a reader.
+    pub precompiles: Vec<usize>,
+    /// The content of the sysvar, ending with the index of the executing
+    /// instruction, which the runtime sets.
+    pub data: Vec<u8>,
+    pub fields: Vec<Field>,
+}
+
+impl Introspection {
+    /// The introspection of a transaction with all its `account_keys`, the
+    /// first `num_loaded_writable` of those loaded from lookup tables
+    /// writable, or `None` if it does not include the Instructions sysvar.
+    pub fn new(
+        transaction: &VersionedTransaction,
+        account_keys: &[Pubkey],
+        num_loaded_writable: usize,
+    ) -> Option<Self> {
+        let message = &transaction.message;
+        let sysvar_index = account_keys
+            .iter()
+            .position(|pubkey| *pubkey == sysvar::instructions::id())?;
+        let instructions = message.instructions();
+        let readers = instructions
+            .iter()
+            .enumerate()
+            .filter(|(_, instruction)| instruction.accounts.contains(&(sysvar_index as u8)))
+            .map(|(index, _)| index)
+            .collect();
+        let key = |index: usize| account_keys.get(index).copied().unwrap_or_default();
+        let precompiles = instructions
+            .iter()
+            .enumerate()
+            .filter(|(_, instruction)| {
+                let program_id = key(instruction.program_id_index as usize);
+                program_id == ed25519_program::id() || program_id == secp256k1_program::id()
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        // Writes to runtime accounts and programs are demoted, unless the
+        // upgradeable loader is present to upgrade them.
+        let num_static = message.static_account_keys().len();
+        let has_upgradeable_loader = account_keys.contains(&bpf_loader_upgradeable::id());
+        let is_writable = |index: usize| {
+            let maybe_writable = match index.checked_sub(num_static) {
+                None => message.is_maybe_writable(index),
+                Some(loaded_index) => loaded_index < num_loaded_writable,
+            };
+            let is_program = instructions
+                .iter()
+                .any(|instruction| instruction.program_id_index as usize == index);
+            maybe_writable
+                && runtime_accounts::classify(&key(index)).is_none()
+                && (!is_program || has_upgradeable_loader)
+        };
+
+        let mut data = vec![];
+        let mut fields = vec![];
+        let mut push = |data: &mut Vec<u8>, bytes: &[u8], description: String| {
+            fields.push(Field {
+                offset: data.len(),
+                len: bytes.len(),
+                description,
+            });
+            data.extend_from_slice(bytes);
+        };
+        let num_instructions = instructions.len() as u16;
+        push(
+            &mut data,
+            &num_instructions.to_le_bytes(),
+            format!("{num_instructions} instructions"),
+        );
+        let offsets_start = data.len();
+        for index in 0..instructions.len() {
+            push(&mut data, &[0, 0], format!("offset of #{index}"));
+        }
+        for (index, instruction) in instructions.iter().enumerate() {
+            let offset = data.len() as u16;
+            data[offsets_start + 2 * index..][..2].copy_from_slice(&offset.to_le_bytes());
+            let num_accounts = instruction.accounts.len() as u16;
+            push(
+                &mut data,
+                &num_accounts.to_le_bytes(),
+                format!("#{index}: {num_accounts} accounts"),
+            );
+            for account_index in instruction.accounts.iter().map(|index| *index as usize) {
+                let (is_signer, is_writable) =
+                    (message.is_signer(account_index), is_writable(account_index));
+                let flags = u8::from(is_signer) | (u8::from(is_writable) << 1);
+                let access = match (is_signer, is_writable) {
+                    (true, true) => "signer, writable",
+                    (true, false) => "signer",
+                    (false, true) => "writable",
+                    (false, false) => "readonly",
+                };
+                push(&mut data, &[flags], format!("#{index}: {access}"));
+                let pubkey = key(account_index);
+                push(&mut data, pubkey.as_ref(), format!("#{index}: {pubkey}"));
+            }
+            let program_id = key(instruction.program_id_index as usize);
+            push(
+                &mut data,
+                program_id.as_ref(),
+                format!("#{index}: program {program_id}"),
+            );
+            let data_len = instruction.data.len() as u16;
+            push(
+                &mut data,
+                &data_len.to_le_bytes(),
+                format!("#{index}: {data_len} bytes of data"),
+            );
+            if !instruction.data.is_empty() {
+                push(&mut data, &instruction.data, format!("#{index}: data"));
+            }
+        }
+        push(
+            &mut data,
+            &[0, 0],
+            "index of the executing instruction".to_string(),
+        );
+        Some(Self {
+            readers,
+            precompiles,
+            data,
+            fields,
+        })
+    }
+
+    /// A line per field, with its offset and bytes.
+    pub fn dump(&self) -> Vec<String> {
+        const MAX_BYTES: usize = 8;
+        self.fields
+            .iter()
+            .map(|field| {
+                let bytes = &self.data[field.offset..][..field.len];
+                let mut hex = bytes
+                    .iter()
+                    .take(MAX_BYTES)
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                if bytes.len() > MAX_BYTES {
+                    hex.push_str(" …");
+                }
+                format!(
+                    "{:04x}  {hex:<width$}  {}",
+                    field.offset,
+                    field.description,
+                    width = 3 * MAX_BYTES + 1
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash,
+            instruction::{AccountMeta, Instruction},
+            message::Message,
+            sysvar::instructions::{construct_instructions_data, BorrowedInstruction},
+            transaction::Transaction,
+        },
+    };
+
+    #[test]
+    fn serializes_like_the_runtime() {
+        let (payer, program_id) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let verify = Instruction::new_with_bytes(ed25519_program::id(), &[1, 2, 3], vec![]);
+        let guard = Instruction::new_with_bytes(
+            program_id,
+            &[4],
+            vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            ],
+        );
+        let message = Message::new_with_blockhash(&[verify, guard], Some(&payer), &Hash::default());
+        let transaction = VersionedTransaction::from(Transaction::new_unsigned(message.clone()));
+        let account_keys = message.account_keys.clone();
+
+        let introspection = Introspection::new(&transaction, &account_keys, 0).unwrap();
+        assert_eq!(introspection.readers, [1]);
+        assert_eq!(introspection.precompiles, [0]);
+        let instructions = message
+            .instructions
+            .iter()
+            .map(|instruction| BorrowedInstruction {
+                program_id: &account_keys[instruction.program_id_index as usize],
+                accounts: instruction
+                    .accounts
+                    .iter()
+                    .map(|index| {
+                        let index = *index as usize;
+                        solana_sdk::sysvar::instructions::BorrowedAccountMeta {
+                            pubkey: &account_keys[index],
+                            is_signer: message.is_signer(index),
+                            is_writable: message.is_writable(index),
+                        }
+                    })
+                    .collect(),
+                data: &instruction.data,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            introspection.data,
+            construct_instructions_data(&instructions)
+        );
+        assert_eq!(
+            introspection
+                .fields
+                .iter()
+                .map(|field| field.len)
+                .sum::<usize>(),
+            introspection.data.len()
+        );
+        assert!(introspection.dump()[0].starts_with("0000  02 00"));
+
+        let transfer = Message::new(&[], Some(&payer));
+        let transaction = VersionedTransaction::from(Transaction::new_unsigned(transfer));
+        assert!(Introspection::new(&transaction, &[payer], 0).is_none());
+    }
+}
@@ -0,0 +1,923 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// The group of keybindings active in a mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Context {
+    Global,
+    SignatureEntry,
+    Menu,
+    /// Asking whether to refetch the transaction after switching endpoints.
+    ConfirmRefetch,
+    FetchOptions,
+    ByteNavigation,
+    /// After `g` while navigating bytes, choosing the region to jump to.
+    Jump,
+    Prompt,
+    Edit,
+    /// Comparing the viewed transaction with another.
+    Diff,
+    HexWrite,
+    Template,
+    LedgerSigning,
+    AltPlan,
+    InnerTransaction,
+    CommitmentTimeline,
+    Broadcast,
+    Leaders,
+    Related,
+    Sandwich,
+    AccessMatrix,
+    InstructionsSysvar,
+    SlotTiming,
+    Simulation,
+    Preflight,
+    RpcConsole,
+    AddressBook,
+    Bookmarks,
+    Find,
+    Watch,
+    Subscriptions,
+    Narration,
+    SensitivityMap,
+    Strings,
+    Stats,
+    EntryPicker,
+    Help,
+    CommandPalette,
+}
+
+impl Context {
+    pub const ALL: [Self; 38] = [
+        Self::Global,
+        Self::SignatureEntry,
+        Self::Menu,
+        Self::ConfirmRefetch,
+        Self::FetchOptions,
+        Self::ByteNavigation,
+        Self::Jump,
+        Self::Prompt,
+        Self::Edit,
+        Self::Diff,
+        Self::HexWrite,
+        Self::Template,
+        Self::LedgerSigning,
+        Self::AltPlan,
+        Self::InnerTransaction,
+        Self::CommitmentTimeline,
+        Self::Broadcast,
+        Self::Leaders,
+        Self::Related,
+        Self::Sandwich,
+        Self::AccessMatrix,
+        Self::InstructionsSysvar,
+        Self::SlotTiming,
+        Self::Simulation,
+        Self::Preflight,
+        Self::RpcConsole,
+        Self::AddressBook,
+        Self::Bookmarks,
+        Self::Find,
+        Self::Watch,
+        Self::Subscriptions,
+        Self::Narration,
+        Self::SensitivityMap,
+        Self::Strings,
+        Self::Stats,
+        Self::EntryPicker,
+        Self::Help,
+        Self::CommandPalette,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Global => "Everywhere",
+            Self::SignatureEntry => "Signature entry",
+            Self::Menu => "Menu",
+            Self::ConfirmRefetch => "Refetch confirmation",
+            Self::FetchOptions => "Fetch options",
+            Self::ByteNavigation => "Byte navigation",
+            Self::Jump => "Jump to region",
+            Self::Prompt => "Prompts",
+            Self::Edit => "Edit",
+            Self::Diff => "Diff",
+            Self::HexWrite => "Hex write",
+            Self::Template => "Instruction data form",
+            Self::LedgerSigning => "Ledger signing",
+            Self::AltPlan => "Lookup table plan",
+            Self::InnerTransaction => "Wrapped transaction",
+            Self::CommitmentTimeline => "Commitment timeline",
+            Self::Broadcast => "Broadcast",
+            Self::Leaders => "Leaders",
+            Self::Related => "Related transactions",
+            Self::Sandwich => "MEV context",
+            Self::AccessMatrix => "Access matrix",
+            Self::InstructionsSysvar => "Instructions sysvar",
+            Self::SlotTiming => "Slot timing",
+            Self::Simulation => "Simulation",
+            Self::Preflight => "Preflight comparison",
+            Self::RpcConsole => "RPC console",
+            Self::AddressBook => "Address book",
+            Self::Bookmarks => "Bookmarks",
+            Self::Find => "Find across transactions",
+            Self::Watch => "Watch",
+            Self::Subscriptions => "Subscriptions",
+            Self::Narration => "Narration",
+            Self::SensitivityMap => "Sensitivity map",
+            Self::Strings => "Strings",
+            Self::Stats => "Stats",
+            Self::EntryPicker => "Entry picker",
+            Self::Help => "Help",
+            Self::CommandPalette => "Command palette",
+        }
+    }
+}
+
+/// Everything a key can do. Handlers match on actions rather than keys, so
+/// that the bindings below are the single source of truth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    ShowHelp,
+    OpenCommandPalette,
+    Back,
+    Confirm,
+    Previous,
+    Next,
+    Exit,
+    InspectBytes,
+    TrackCommitment,
+    WaitForLanding,
+    FollowAddress,
+    ShowFetchOptions,
+    ChangeOption,
+    OpenRpcConsole,
+    ShowBookmarks,
+    ShowFind,
+    ShowWatch,
+    AddWatchPattern,
+    RemoveWatchPattern,
+    ToggleAutoOpen,
+    ToggleStreamExports,
+    TogglePause,
+    ShowSubscriptions,
+    AddSubscription,
+    RemoveSubscription,
+    ToggleSubscription,
+    EditTags,
+    OpenMenu,
+    MenuLeft,
+    MenuRight,
+    CursorLeft,
+    CursorRight,
+    CursorUp,
+    CursorDown,
+    CursorFirst,
+    CursorLast,
+    ToggleSelection,
+    Annotate,
+    RemoveAnnotation,
+    ToggleBookmark,
+    StartEdit,
+    DiffTransaction,
+    ToggleMessageView,
+    PlanLookupTable,
+    DecodeWrappedTransaction,
+    Narrate,
+    MapSensitivity,
+    ShowStrings,
+    ShowStats,
+    Increase,
+    Decrease,
+    IncreaseMore,
+    DecreaseMore,
+    CycleLegendOrder,
+    CycleColoring,
+    CycleZoom,
+    TogglePresentation,
+    ExportLegend,
+    ExportGraph,
+    ExportByteTags,
+    ToggleInstructionOverlay,
+    PreviousSection,
+    NextSection,
+    StartJump,
+    JumpToSignatures,
+    JumpToHeader,
+    JumpToAccountKeys,
+    JumpToInstructions,
+    JumpToLookupTables,
+    ShowLeaders,
+    ShowRelated,
+    CycleFailureFilter,
+    ShowMevContext,
+    ShowAccessMatrix,
+    ShowInstructionsSysvar,
+    ComparePreflight,
+    ToggleVoteSummary,
+    Refetch,
+    ShowSlotTiming,
+    RecordSubmission,
+    NameAccount,
+    DeriveAddresses,
+    AddContact,
+    EditContact,
+    DeleteContact,
+    ImportKeypairs,
+    DeleteInstruction,
+    ReplaceAccountKey,
+    ReplaceInstructionData,
+    DataForm,
+    LoadSchema,
+    SetComputeUnitLimit,
+    SetComputeUnitPrice,
+    RefreshBlockhash,
+    ClearSignatures,
+    Sign,
+    LoadKeypair,
+    SignWithLedger,
+    BroadcastTransaction,
+    Simulate,
+    ScrollUp,
+    ScrollDown,
+    StartHexWrite,
+    Undo,
+    Redo,
+    PreviousInstruction,
+    NextInstruction,
+    SubmitPlan,
+    EditRewritten,
+    ExportNarration,
+    ExportBundle,
+}
+
+pub struct KeyBinding {
+    pub context: Context,
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+    pub action: Action,
+    pub description: &'static str,
+}
+
+impl KeyBinding {
+    /// The key as shown to the user, e.g. `Ctrl-r`.
+    pub fn key_name(&self) -> String {
+        let code = match self.code {
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::F(n) => format!("F{n}"),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            code => format!("{code:?}"),
+        };
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            format!("Ctrl-{code}")
+        } else {
+            code
+        }
+    }
+}
+
+const fn key(
+    context: Context,
+    code: KeyCode,
+    action: Action,
+    description: &'static str,
+) -> KeyBinding {
+    KeyBinding {
+        context,
+        code,
+        modifiers: KeyModifiers::NONE,
+        action,
+        description,
+    }
+}
+
+const fn ctrl(context: Context, c: char, action: Action, description: &'static str) -> KeyBinding {
+    KeyBinding {
+        context,
+        code: KeyCode::Char(c),
+        modifiers: KeyModifiers::CONTROL,
+        action,
+        description,
+    }
+}
+
+const fn cursor_bindings(context: Context) -> [KeyBinding; 6] {
+    [
+        key(context, KeyCode::Left, Action::CursorLeft, "move cursor"),
+        key(context, KeyCode::Right, Action::CursorRight, "move cursor"),
+        key(context, KeyCode::Up, Action::CursorUp, "move cursor"),
+        key(context, KeyCode::Down, Action::CursorDown, "move cursor"),
+        key(context, KeyCode::Home, Action::CursorFirst, "move cursor"),
+        key(context, KeyCode::End, Action::CursorLast, "move cursor"),
+    ]
+}
+
+use {
+    Action::*,
+    Context::*,
+    KeyCode::{BackTab, Char, Down, Enter, Esc, Left, PageDown, PageUp, Right, Tab, Up, F},
+};
+
+const BYTE_NAVIGATION_CURSOR: [KeyBinding; 6] = cursor_bindings(ByteNavigation);
+const EDIT_CURSOR: [KeyBinding; 6] = cursor_bindings(Edit);
+const HEX_WRITE_CURSOR: [KeyBinding; 6] = cursor_bindings(HexWrite);
+
+const BINDINGS: &[&[KeyBinding]] = &[
+    &[
+        ctrl(Global, 'p', OpenCommandPalette, "command palette"),
+        key(Global, F(1), ShowHelp, "help"),
+    ],
+    &[
+        key(SignatureEntry, Enter, Confirm, "fetch transaction"),
+        key(SignatureEntry, Tab, InspectBytes, "inspect bytes"),
+        ctrl(
+            SignatureEntry,
+            't',
+            TrackCommitment,
+            "track commitment timeline",
+        ),
+        ctrl(
+            SignatureEntry,
+            'w',
+            WaitForLanding,
+            "wait for a transaction not found to land",
+        ),
+        ctrl(SignatureEntry, 'o', ShowFetchOptions, "fetch options"),
+        ctrl(SignatureEntry, 'r', OpenRpcConsole, "RPC console"),
+        ctrl(SignatureEntry, 'b', ShowBookmarks, "bookmarks"),
+        ctrl(SignatureEntry, 'f', ShowFind, "find across transactions"),
+        ctrl(SignatureEntry, 's', ShowWatch, "watch new blocks"),
+        ctrl(
+            SignatureEntry,
+            'l',
+            FollowAddress,
+            "follow the latest transaction of an address",
+        ),
+        key(SignatureEntry, F(10), OpenMenu, "menu"),
+        key(SignatureEntry, Up, Previous, "previous input"),
+        key(SignatureEntry, Down, Next, "next input"),
+        key(SignatureEntry, Esc, Exit, "exit"),
+    ],
+    &[
+        key(Menu, Up, Previous, "move"),
+        key(Menu, Down, Next, "move"),
+        key(Menu, Left, MenuLeft, "move"),
+        key(Menu, Right, MenuRight, "move"),
+        key(Menu, Enter, Confirm, "select"),
+        key(Menu, Esc, Back, "close the menu"),
+    ],
+    &[
+        key(ConfirmRefetch, Enter, Confirm, "refetch"),
+        key(ConfirmRefetch, Char('y'), Confirm, "refetch"),
+        key(ConfirmRefetch, Esc, Back, "keep the displayed data"),
+        key(ConfirmRefetch, Char('n'), Back, "keep the displayed data"),
+    ],
+    &[
+        key(FetchOptions, Up, Previous, "choose option"),
+        key(FetchOptions, Down, Next, "choose option"),
+        key(FetchOptions, Char(' '), ChangeOption, "change"),
+        key(FetchOptions, Left, ChangeOption, "change"),
+        key(FetchOptions, Right, ChangeOption, "change"),
+        key(FetchOptions, Enter, Confirm, "fetch"),
+        key(FetchOptions, Esc, Back, "close"),
+    ],
+    &BYTE_NAVIGATION_CURSOR,
+    &[
+        key(ByteNavigation, Char('v'), ToggleSelection, "select"),
+        key(ByteNavigation, Char('a'), Annotate, "annotate"),
+        key(
+            ByteNavigation,
+            Char('x'),
+            RemoveAnnotation,
+            "remove annotation",
+        ),
+        key(ByteNavigation, Char('b'), ToggleBookmark, "bookmark"),
+        key(ByteNavigation, Char('t'), EditTags, "tag transaction"),
+        key(ByteNavigation, Char('e'), StartEdit, "edit"),
+        key(
+            ByteNavigation,
+            Char('d'),
+            DiffTransaction,
+            "diff with another transaction",
+        ),
+        key(ByteNavigation, Char('m'), ToggleMessageView, "message view"),
+        key(
+            ByteNavigation,
+            Char('V'),
+            ToggleVoteSummary,
+            "vote summary or bytes",
+        ),
+        key(
+            ByteNavigation,
+            Char('A'),
+            PlanLookupTable,
+            "plan lookup table",
+        ),
+        key(
+            ByteNavigation,
+            Char('D'),
+            DecodeWrappedTransaction,
+            "decode wrapped transaction",
+        ),
+        key(ByteNavigation, Char('n'), Narrate, "narrate"),
+        key(
+            ByteNavigation,
+            Char('B'),
+            ExportBundle,
+            "export session bundle",
+        ),
+        key(
+            ByteNavigation,
+            Char('F'),
+            MapSensitivity,
+            "corruption sensitivity map",
+        ),
+        key(ByteNavigation, Char('L'), ShowLeaders, "leaders"),
+        key(
+            ByteNavigation,
+            Char('R'),
+            ShowRelated,
+            "transactions touching the account key in nearby slots",
+        ),
+        key(
+            ByteNavigation,
+            Char('M'),
+            ShowMevContext,
+            "neighbors in the block writing the same accounts",
+        ),
+        key(
+            ByteNavigation,
+            Char('X'),
+            ShowAccessMatrix,
+            "accounts by instruction access matrix",
+        ),
+        key(
+            ByteNavigation,
+            Char('Y'),
+            ShowInstructionsSysvar,
+            "instructions sysvar introspection",
+        ),
+        key(
+            ByteNavigation,
+            Char('E'),
+            ComparePreflight,
+            "re-simulate and compare with the on-chain failure",
+        ),
+        key(ByteNavigation, Char('T'), ShowSlotTiming, "slot timing"),
+        key(ByteNavigation, Char('N'), NameAccount, "name account key"),
+        key(ByteNavigation, Char('P'), DeriveAddresses, "derive PDAs"),
+        key(ByteNavigation, Char('s'), ShowStrings, "strings in data"),
+        key(ByteNavigation, Char('S'), ShowStats, "stats"),
+        key(ByteNavigation, Char('o'), CycleLegendOrder, "legend order"),
+        key(
+            ByteNavigation,
+            Char('c'),
+            CycleColoring,
+            "color by field or program",
+        ),
+        key(ByteNavigation, Char('z'), CycleZoom, "zoom"),
+        key(
+            ByteNavigation,
+            Char('Z'),
+            TogglePresentation,
+            "presentation mode",
+        ),
+        key(ByteNavigation, Char('K'), ExportLegend, "export legend"),
+        key(
+            ByteNavigation,
+            Char('O'),
+            ExportByteTags,
+            "export bytes with per-byte field tags",
+        ),
+        key(
+            ByteNavigation,
+            Char('G'),
+            ExportGraph,
+            "export account graph as DOT",
+        ),
+        key(
+            ByteNavigation,
+            Char('I'),
+            ToggleInstructionOverlay,
+            "number instructions",
+        ),
+        key(
+            ByteNavigation,
+            Char('['),
+            PreviousSection,
+            "previous section",
+        ),
+        key(ByteNavigation, Char(']'), NextSection, "next section"),
+        key(
+            ByteNavigation,
+            Char('{'),
+            PreviousInstruction,
+            "previous instruction",
+        ),
+        key(
+            ByteNavigation,
+            Char('}'),
+            NextInstruction,
+            "next instruction",
+        ),
+        key(ByteNavigation, Char('g'), StartJump, "jump to region"),
+        key(
+            ByteNavigation,
+            Char('r'),
+            Refetch,
+            "refetch and show changes",
+        ),
+        key(ByteNavigation, Tab, Back, "back"),
+        key(ByteNavigation, Esc, Back, "back"),
+        key(ByteNavigation, Char('?'), ShowHelp, "help"),
+    ],
+    &[
+        key(Jump, Char('s'), JumpToSignatures, "signatures"),
+        key(Jump, Char('h'), JumpToHeader, "header"),
+        key(Jump, Char('k'), JumpToAccountKeys, "account keys"),
+        key(Jump, Char('i'), JumpToInstructions, "instructions"),
+        key(Jump, Char('l'), JumpToLookupTables, "lookup tables"),
+        key(Jump, Esc, Back, "cancel"),
+    ],
+    &[
+        key(Prompt, Enter, Confirm, "confirm"),
+        key(Prompt, Esc, Back, "cancel"),
+    ],
+    &EDIT_CURSOR,
+    &[
+        key(Edit, Char('m'), ToggleMessageView, "message view"),
+        key(Edit, Char('d'), DeleteInstruction, "delete instruction"),
+        key(Edit, Char('k'), ReplaceAccountKey, "replace key"),
+        key(Edit, Char('i'), ReplaceInstructionData, "replace data"),
+        key(Edit, Char('t'), DataForm, "data form"),
+        key(Edit, Char('T'), LoadSchema, "load schema"),
+        key(Edit, Char('c'), SetComputeUnitLimit, "compute unit limit"),
+        key(Edit, Char('p'), SetComputeUnitPrice, "compute unit price"),
+        key(Edit, Char('B'), RefreshBlockhash, "refresh blockhash"),
+        key(Edit, Char('C'), ClearSignatures, "clear signatures"),
+        key(Edit, Char('S'), Sign, "sign"),
+        key(Edit, Char('L'), LoadKeypair, "load keypair"),
+        key(Edit, Char('W'), SignWithLedger, "sign with Ledger"),
+        key(
+            Edit,
+            Char('s'),
+            BroadcastTransaction,
+            "broadcast to endpoints",
+        ),
+        key(Edit, Char('r'), Simulate, "simulate state changes"),
+        key(Edit, Char('h'), StartHexWrite, "hex write"),
+        key(Edit, Char('u'), Undo, "undo"),
+        ctrl(Edit, 'r', Redo, "redo"),
+        key(Edit, Esc, Back, "discard edits"),
+        key(Edit, Char('?'), ShowHelp, "help"),
+    ],
+    &HEX_WRITE_CURSOR,
+    &[key(HexWrite, Esc, Back, "stop writing")],
+    &[
+        key(Template, Up, Previous, "previous field"),
+        key(Template, BackTab, Previous, "previous field"),
+        key(Template, Down, Next, "next field"),
+        key(Template, Tab, Next, "next field"),
+        key(Template, PageUp, PreviousInstruction, "choose instruction"),
+        key(Template, PageDown, NextInstruction, "choose instruction"),
+        key(Template, Enter, Confirm, "apply"),
+        key(Template, Esc, Back, "cancel"),
+    ],
+    &[
+        key(LedgerSigning, Up, Previous, "choose derivation path"),
+        key(LedgerSigning, Down, Next, "choose derivation path"),
+        key(LedgerSigning, Enter, Confirm, "sign"),
+        key(LedgerSigning, Esc, Back, "cancel"),
+    ],
+    &[
+        key(AltPlan, Up, Previous, "choose transaction"),
+        key(AltPlan, Down, Next, "choose transaction"),
+        key(AltPlan, Char('s'), SubmitPlan, "submit setup transactions"),
+        key(
+            AltPlan,
+            Char('e'),
+            EditRewritten,
+            "edit rewritten transaction",
+        ),
+        key(AltPlan, Tab, Back, "back"),
+        key(AltPlan, Esc, Back, "back"),
+        key(AltPlan, Char('?'), ShowHelp, "help"),
+    ],
+    &[
+        key(Diff, Up, Previous, "scroll"),
+        key(Diff, Down, Next, "scroll"),
+        key(Diff, Esc, Back, "back"),
+        key(Diff, Char('?'), ShowHelp, "help"),
+    ],
+    &[
+        key(InnerTransaction, Esc, Back, "back"),
+        key(InnerTransaction, Char('?'), ShowHelp, "help"),
+    ],
+    &[
+        key(CommitmentTimeline, Esc, Back, "back, polling continues"),
+        key(CommitmentTimeline, Char('?'), ShowHelp, "help"),
+    ],
+    &[
+        key(Broadcast, Esc, Back, "back, sending continues"),
+        key(Broadcast, Char('?'), ShowHelp, "help"),
+    ],
+    &[
+        key(Leaders, Tab, Back, "back"),
+        key(Leaders, Esc, Back, "back"),
+        key(Leaders, Char('?'), ShowHelp, "help"),
+    ],
+    &[
+        key(Related, Up, Previous, "choose transaction"),
+        key(Related, Down, Next, "choose transaction"),
+        key(Related, Enter, Confirm, "view transaction"),
+        key(Related, Char('+'), Increase, "widen the window"),
+        key(Related, Char('-'), Decrease, "narrow the window"),
+        key(Related, Char('f'), CycleFailureFilter, "filter failures"),
+        key(Related, Tab, Back, "back"),
+        key(Related, Esc, Back, "back"),
+    ],
+    &[
+        key(Sandwich, Up, Previous, "choose transaction"),
+        key(Sandwich, Down, Next, "choose transaction"),
+        key(Sandwich, Enter, Confirm, "view transaction"),
+        key(Sandwich, Tab, Back, "back"),
+        key(Sandwich, Esc, Back, "back"),
+    ],
+    &[
+        key(AccessMatrix, Up, Previous, "choose account"),
+        key(AccessMatrix, Down, Next, "choose account"),
+        key(AccessMatrix, Left, CursorLeft, "choose instruction"),
+        key(AccessMatrix, Right, CursorRight, "choose instruction"),
+        key(AccessMatrix, Enter, Confirm, "jump to the index byte"),
+        key(AccessMatrix, Tab, Back, "back"),
+        key(AccessMatrix, Esc, Back, "back"),
+    ],
+    &[
+        key(InstructionsSysvar, Up, Previous, "scroll"),
+        key(InstructionsSysvar, Down, Next, "scroll"),
+        key(InstructionsSysvar, Tab, Back, "back"),
+        key(InstructionsSysvar, Esc, Back, "back"),
+    ],
+    &[
+        key(Preflight, Up, Previous, "scroll logs"),
+        key(Preflight, Down, Next, "scroll logs"),
+        key(Preflight, Tab, Back, "back"),
+        key(Preflight, Esc, Back, "back"),
+    ],
+    &[
+        key(
+            SlotTiming,
+            Char('r'),
+            RecordSubmission,
+            "record submission time",
+        ),
+        key(SlotTiming, Tab, Back, "back"),
+        key(SlotTiming, Esc, Back, "back"),
+        key(SlotTiming, Char('?'), ShowHelp, "help"),
+    ],
+    &[
+        key(Simulation, Up, Previous, "choose account"),
+        key(Simulation, Down, Next, "choose account"),
+        key(Simulation, PageUp, ScrollUp, "scroll data"),
+        key(Simulation, PageDown, ScrollDown, "scroll data"),
+        key(Simulation, Esc, Back, "back"),
+        key(Simulation, Char('?'), ShowHelp, "help"),
+    ],
+    &[
+        key(RpcConsole, Enter, Confirm, "send request"),
+        key(RpcConsole, Up, Previous, "previous request"),
+        key(RpcConsole, Down, Next, "next request"),
+        key(RpcConsole, PageUp, ScrollUp, "scroll response"),
+        key(RpcConsole, PageDown, ScrollDown, "scroll response"),
+        key(RpcConsole, Esc, Back, "back"),
+    ],
+    &[
+        key(AddressBook, Up, Previous, "choose contact"),
+        key(AddressBook, Down, Next, "choose contact"),
+        key(AddressBook, Char('a'), AddContact, "add contact"),
+        key(AddressBook, Char('e'), EditContact, "edit contact"),
+        key(AddressBook, Char('d'), DeleteContact, "delete contact"),
+        key(
+            AddressBook,
+            Char('i'),
+            ImportKeypairs,
+            "import keypair directory",
+        ),
+        key(AddressBook, Esc, Back, "back"),
+        key(AddressBook, Char('?'), ShowHelp, "help"),
+    ],
+    &[
+        key(Bookmarks, Up, Previous, "choose bookmark"),
+        key(Bookmarks, Down, Next, "choose bookmark"),
+        key(Bookmarks, Enter, Confirm, "fetch transaction"),
+        ctrl(Bookmarks, 't', EditTags, "edit tags"),
+        key(Bookmarks, Esc, Back, "back"),
+    ],
+    &[
+        key(Find, Up, Previous, "choose transaction"),
+        key(Find, Down, Next, "choose transaction"),
+        key(Find, Enter, Confirm, "jump to the first match"),
+        key(Find, Esc, Back, "back"),
+    ],
+    &[
+        key(Watch, Up, Previous, "choose transaction"),
+        key(Watch, Down, Next, "choose transaction"),
+        key(Watch, Enter, Confirm, "view transaction"),
+        key(Watch, Char('a'), AddWatchPattern, "watch for a pattern"),
+        key(
+            Watch,
+            Char('d'),
+            RemoveWatchPattern,
+            "remove the last pattern",
+        ),
+        key(
+            Watch,
+            Char('o'),
+            ToggleAutoOpen,
+            "open matches automatically",
+        ),
+        key(
+            Watch,
+            Char('x'),
+            ToggleStreamExports,
+            "save matches to disk",
+        ),
+        key(Watch, Char(' '), TogglePause, "pause or resume"),
+        key(Watch, Char('s'), ShowSubscriptions, "subscriptions"),
+        key(Watch, Char('f'), CycleFailureFilter, "filter failures"),
+        key(Watch, Esc, Back, "stop watching"),
+    ],
+    &[
+        key(Subscriptions, Up, Previous, "choose subscription"),
+        key(Subscriptions, Down, Next, "choose subscription"),
+        key(
+            Subscriptions,
+            Char(' '),
+            ToggleSubscription,
+            "enable or disable",
+        ),
+        key(
+            Subscriptions,
+            Char('a'),
+            AddSubscription,
+            "subscribe to an address",
+        ),
+        key(Subscriptions, Char('d'), RemoveSubscription, "unsubscribe"),
+        key(Subscriptions, Tab, Back, "back to transactions"),
+        key(Subscriptions, Esc, Back, "back to transactions"),
+    ],
+    &[
+        key(Narration, Up, Previous, "scroll"),
+        key(Narration, Down, Next, "scroll"),
+        key(Narration, Char('w'), ExportNarration, "write to file"),
+        key(Narration, Esc, Back, "back"),
+        key(Narration, Char('?'), ShowHelp, "help"),
+    ],
+    &[
+        key(SensitivityMap, Up, Previous, "scroll"),
+        key(SensitivityMap, Down, Next, "scroll"),
+        key(SensitivityMap, Esc, Back, "back"),
+        key(SensitivityMap, Char('?'), ShowHelp, "help"),
+    ],
+    &[
+        key(Strings, Up, Previous, "choose string"),
+        key(Strings, Down, Next, "choose string"),
+        key(Strings, Enter, Confirm, "go to bytes"),
+        key(Strings, Esc, Back, "back"),
+        key(Strings, Char('?'), ShowHelp, "help"),
+    ],
+    &[
+        key(Stats, Up, Previous, "choose quantity"),
+        key(Stats, Down, Next, "choose quantity"),
+        key(Stats, Char('+'), Increase, "add one"),
+        key(Stats, Right, Increase, "add one"),
+        key(Stats, Char('-'), Decrease, "remove one"),
+        key(Stats, Left, Decrease, "remove one"),
+        key(Stats, PageUp, IncreaseMore, "add ten"),
+        key(Stats, PageDown, DecreaseMore, "remove ten"),
+        key(Stats, Esc, Back, "back"),
+    ],
+    &[
+        key(EntryPicker, Up, Previous, "choose transaction"),
+        key(EntryPicker, Down, Next, "choose transaction"),
+        key(EntryPicker, Enter, Confirm, "view transaction"),
+        key(EntryPicker, Esc, Back, "back"),
+        key(EntryPicker, Char('?'), ShowHelp, "help"),
+    ],
+    &[
+        key(Help, Up, Previous, "scroll"),
+        key(Help, Down, Next, "scroll"),
+        key(Help, Esc, Back, "close help"),
+    ],
+    &[
+        key(CommandPalette, Up, Previous, "choose command"),
+        key(CommandPalette, Down, Next, "choose command"),
+        key(CommandPalette, Enter, Confirm, "run command"),
+        key(CommandPalette, Esc, Back, "close"),
+    ],
+];
+
+/// Every keybinding, in display order.
+pub fn all() -> impl Iterator<Item = &'static KeyBinding> {
+    BINDINGS.iter().flat_map(|bindings| bindings.iter())
+}
+
+/// The bindings of a single context.
+pub fn bindings(context: Context) -> impl Iterator<Item = &'static KeyBinding> {
+    all().filter(move |binding| binding.context == context)
+}
+
+/// The action bound to a key in a context, falling back to global bindings.
+pub fn action(context: Context, key_event: KeyEvent) -> Option<Action> {
+    // Shift is implied by the character for letters and symbols.
+    let modifiers = key_event.modifiers - KeyModifiers::SHIFT;
+    let matches =
+        |binding: &&KeyBinding| binding.code == key_event.code && binding.modifiers == modifiers;
+    bindings(context)
+        .find(matches)
+        .or_else(|| bindings(Global).find(matches))
+        .map(|binding| binding.action)
+}
+
+/// A one line summary of the bindings of a context, e.g. for a footer.
+/// Consecutive bindings with the same description are merged.
+pub fn summary(context: Context) -> String {
+    let global = match context {
+        Global | Help | CommandPalette => None,
+        _ => Some(Global),
+    };
+    let mut entries: Vec<(Vec<String>, &str)> = vec![];
+    for binding in bindings(context).chain(global.into_iter().flat_map(bindings)) {
+        match entries.last_mut() {
+            Some((keys, description)) if *description == binding.description => {
+                keys.push(binding.key_name())
+            }
+            _ => entries.push((vec![binding.key_name()], binding.description)),
+        }
+    }
+    entries
+        .iter()
+        .map(|(keys, description)| format!("<{}> {description}", keys.join("/")))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Whether the characters of the query appear in order in the text, ignoring
+/// case and whitespace in the query.
+pub fn fuzzy_matches(query: &str, text: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .all(|query_char| text.any(|text_char| text_char == query_char))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binds_each_key_once_per_context() {
+        for context in Context::ALL {
+            let mut keys = vec![];
+            for binding in bindings(context) {
+                let key = (binding.code, binding.modifiers);
+                assert!(
+                    !keys.contains(&key),
+                    "{} is bound twice in {}",
+                    binding.key_name(),
+                    context.name()
+                );
+                keys.push(key);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_the_characters_of_a_query_in_order() {
+        assert!(fuzzy_matches("", "fetch transaction"));
+        assert!(fuzzy_matches("ftr", "fetch transaction"));
+        assert!(fuzzy_matches("Fetch TR", "fetch transaction"));
+        assert!(!fuzzy_matches("tf", "fetch transaction"));
+        assert!(!fuzzy_matches("fetchh", "fetch transaction"));
+    }
+
+    #[test]
+    fn shows_and_handles_every_binding() {
+        // Handlers match on actions, so an action none of them names does
+        // nothing when its key is pressed.
+        let handlers = include_str!("main.rs");
+        for binding in all() {
+            assert!(
+                Context::ALL.contains(&binding.context),
+                "{} is missing from the help",
+                binding.context.name()
+            );
+            let action = format!("Action::{:?}", binding.action);
+            let is_handled = handlers.match_indices(&action).any(|(index, _)| {
+                !handlers[index + action.len()..].starts_with(|c: char| c.is_alphanumeric())
+            });
+            assert!(is_handled, "{action} is not handled");
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use {
+    solana_sdk::{
+        pubkey::Pubkey,
+        signature::Keypair,
+        signer::{
+            keypair::{keypair_from_seed_phrase_and_passphrase, read_keypair_file},
+            Signer,
+        },
+    },
+    std::path::Path,
+    zeroize::Zeroizing,
+};
+
+/// Where a loaded keypair came from. Only non-secret information is kept.
+pub enum KeypairSource {
+    File(String),
+    SeedPhrase,
+}
+
+struct LoadedKeypair {
+    source: KeypairSource,
+    keypair: Keypair,
+}
+
+/// Keypairs loaded for signing during this session.
+///
+/// Secrets only ever live in memory: nothing in the store is persisted, and
+/// only pubkeys and sources are exposed for display.
+#[derive(Default)]
+pub struct KeypairStore {
+    keypairs: Vec<LoadedKeypair>,
+}
+
+impl KeypairStore {
+    /// Load a keypair read from the JSON keypair file at `path`. Returns the
+    /// loaded pubkey.
+    pub fn load_file(&mut self, path: String, keypair: Keypair) -> Pubkey {
+        self.insert(KeypairSource::File(path), keypair)
+    }
+
+    /// Derive a keypair from a seed phrase and optional passphrase. Returns
+    /// the loaded pubkey.
+    pub fn load_seed_phrase(
+        &mut self,
+        seed_phrase: &str,
+        passphrase: &str,
+    ) -> Result<Pubkey, String> {
+        let seed_phrase =
+            Zeroizing::new(seed_phrase.split_whitespace().collect::<Vec<_>>().join(" "));
+        let keypair = keypair_from_seed_phrase_and_passphrase(&seed_phrase, passphrase)
+            .map_err(|err| format!("Invalid seed phrase: {err}"))?;
+        Ok(self.insert(KeypairSource::SeedPhrase, keypair))
+    }
+
+    /// Forget every loaded keypair.
+    pub fn clear(&mut self) {
+        self.keypairs.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keypairs.is_empty()
+    }
+
+    /// Pubkeys of the loaded keypairs along with where they came from.
+    pub fn signers(&self) -> impl Iterator<Item = (Pubkey, &KeypairSource)> {
+        self.keypairs
+            .iter()
+            .map(|loaded| (loaded.keypair.pubkey(), &loaded.source))
+    }
+
+    /// The loaded keypairs which match any of the given pubkeys.
+    pub fn keypairs_for<'a>(&'a self, pubkeys: &'a [Pubkey]) -> impl Iterator<Item = &'a Keypair> {
+        self.keypairs
+            .iter()
+            .map(|loaded| &loaded.keypair)
+            .filter(|keypair| pubkeys.contains(&keypair.pubkey()))
+    }
+
+    fn insert(&mut self, source: KeypairSource, keypair: Keypair) -> Pubkey {
+        let pubkey = keypair.pubkey();
+        // Replace rather than duplicate a previously loaded keypair.
+        self.keypairs
+            .retain(|loaded| loaded.keypair.pubkey() != pubkey);
+        self.keypairs.push(LoadedKeypair { source, keypair });
+        pubkey
+    }
+}
+
+/// Read a JSON keypair file (e.g. `id.json`). Returns the expanded path
+/// along with the keypair.
+pub fn read_file(path: &str) -> Result<(String, Keypair), String> {
+    let path = expand_home(path);
+    let keypair = read_keypair_file(&path)
+        .map_err(|err| format!("Failed to read keypair file {path}: {err}"))?;
+    Ok((path, keypair))
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest).display().to_string(),
+        _ => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_a_keypair_loaded_again() {
+        let mut store = KeypairStore::default();
+        let keypair = Keypair::new();
+        let copy = Keypair::from_bytes(&keypair.to_bytes()).unwrap();
+        store.load_file("~/old.json".to_string(), keypair);
+        let other = store.load_file("other.json".to_string(), Keypair::new());
+        let pubkey = store.load_file("new.json".to_string(), copy);
+
+        let signers = store
+            .signers()
+            .map(|(pubkey, source)| match source {
+                KeypairSource::File(path) => (pubkey, path.as_str()),
+                KeypairSource::SeedPhrase => (pubkey, "seed phrase"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(signers, [(other, "other.json"), (pubkey, "new.json")]);
+        store.clear();
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn finds_the_keypairs_of_signers() {
+        let mut store = KeypairStore::default();
+        let loaded = [Keypair::new(), Keypair::new(), Keypair::new()]
+            .into_iter()
+            .map(|keypair| store.load_file("id.json".to_string(), keypair))
+            .collect::<Vec<_>>();
+        let pubkeys = |signers: &[Pubkey]| {
+            store
+                .keypairs_for(signers)
+                .map(Keypair::pubkey)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            pubkeys(&[Pubkey::new_unique(), loaded[2], loaded[0]]),
+            [loaded[0], loaded[2]]
+        );
+        assert!(pubkeys(&[Pubkey::new_unique()]).is_empty());
+        assert!(pubkeys(&[]).is_empty());
+    }
+
+    #[test]
+    fn derives_the_same_keypair_however_the_words_are_spaced() {
+        let words = "rough mad tube mule year lottery cost arena grape bid dirt mushroom";
+        let mut store = KeypairStore::default();
+        let pubkey = store.load_seed_phrase(words, "").unwrap();
+        let spaced = format!("  {}\n", words.replace(' ', " \t  "));
+        assert_eq!(store.load_seed_phrase(&spaced, ""), Ok(pubkey));
+        assert_eq!(store.signers().count(), 1);
+        // The passphrase is part of the seed, and not normalized.
+        let with_passphrase = store.load_seed_phrase(words, "secret").unwrap();
+        assert_ne!(with_passphrase, pubkey);
+        assert_ne!(
+            store.load_seed_phrase(words, " secret"),
+            Ok(with_passphrase)
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Waiting for a just sent transaction to land: its status is polled with
+//! exponential backoff until the transaction can be fetched.
+
+use {
+    crate::rpc::RpcApi,
+    solana_sdk::{commitment_config::CommitmentConfig, signature::Signature},
+    solana_transaction_status::TransactionStatus,
+    std::time::{Duration, Instant},
+};
+
+const INITIAL_DELAY: Duration = Duration::from_millis(500);
+const MAX_DELAY: Duration = Duration::from_secs(8);
+/// Give up on signatures which do not land, e.g. dropped transactions.
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+pub struct Landing {
+    pub signature: Signature,
+    started: Instant,
+    num_polls: u32,
+    /// The outcome of the last poll.
+    pub status: Option<String>,
+}
+
+impl Landing {
+    pub fn new(signature: Signature) -> Self {
+        Self {
+            signature,
+            started: Instant::now(),
+            num_polls: 0,
+            status: None,
+        }
+    }
+
+    /// The delay before the next poll, doubling after each poll.
+    pub fn next_delay(&self) -> Duration {
+        if self.num_polls == 0 {
+            return Duration::ZERO;
+        }
+        INITIAL_DELAY
+            .saturating_mul(1 << (self.num_polls - 1).min(16))
+            .min(MAX_DELAY)
+    }
+
+    /// Record the outcome of a poll.
+    pub fn record(&mut self, status: String) {
+        self.num_polls += 1;
+        self.status = Some(status);
+    }
+
+    pub fn is_timed_out(&self) -> bool {
+        self.started.elapsed() > TIMEOUT
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "Waiting for {} to land ({}s, {} polls)",
+            self.signature,
+            self.started.elapsed().as_secs(),
+            self.num_polls
+        );
+        if let Some(status) = &self.status {
+            summary = format!("{summary}\n\nLast status: {status}");
+        }
+        summary
+    }
+}
+
+/// The status of `signature`, or `None` if the endpoint has not seen it.
+pub fn poll(rpc: &dyn RpcApi, signature: &Signature) -> Result<Option<TransactionStatus>, String> {
+    let statuses = rpc.get_signature_statuses(&[*signature])?;
+    Ok(statuses.into_iter().flatten().next())
+}
+
+/// Whether the transaction can be fetched, which needs it to be confirmed.
+pub fn is_queryable(status: &TransactionStatus) -> bool {
+    status.satisfies_commitment(CommitmentConfig::confirmed())
+}
+
+pub fn describe(status: Option<&TransactionStatus>) -> String {
+    let Some(status) = status else {
+        return "not found".to_string();
+    };
+    let level = status
+        .confirmation_status
+        .as_ref()
+        .map(|level| format!("{level:?}").to_lowercase())
+        .unwrap_or_else(|| "unknown commitment".to_string());
+    format!("{level} at slot {}", status.slot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_off_exponentially() {
+        let mut landing = Landing::new(Signature::default());
+        let mut delays = vec![];
+        for _ in 0..7 {
+            delays.push(landing.next_delay().as_millis());
+            landing.record("not found".to_string());
+        }
+        assert_eq!(delays, [0, 500, 1000, 2000, 4000, 8000, 8000]);
+    }
+}
@@ -0,0 +1,120 @@
+//! The leaders around a transaction: who leads the current and next slots,
+//! and who led the slot a transaction landed in, to investigate why
+//! transactions sent through some endpoints land late.
+
+use {
+    crate::rpc::RpcApi,
+    solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey},
+    std::{collections::HashMap, str::FromStr},
+};
+
+/// How many upcoming slots to show the leaders of, 4 slots per leader.
+const NUM_UPCOMING_SLOTS: u64 = 32;
+
+pub struct SlotLeader {
+    pub first_slot: u64,
+    pub last_slot: u64,
+    pub identity: Pubkey,
+}
+
+pub struct Leaders {
+    pub current_slot: u64,
+    /// Consecutive slots with the same leader, from the current slot.
+    pub upcoming: Vec<SlotLeader>,
+    /// The slot the transaction landed in, and its leader.
+    pub landed: Option<(u64, Result<Pubkey, String>)>,
+    /// The vote account of each identity with one.
+    vote_accounts: HashMap<Pubkey, Pubkey>,
+}
+
+impl Leaders {
+    /// Fetch the leaders of the upcoming slots and, if given, of the slot a
+    /// transaction landed in.
+    pub fn fetch(rpc: &dyn RpcApi, landed_slot: Option<u64>) -> Result<Self, String> {
+        let current_slot = rpc
+            .get_slot(CommitmentConfig::confirmed())
+            .map_err(|err| format!("Failed to get slot: {err}"))?;
+        let upcoming = rpc
+            .get_slot_leaders(current_slot, NUM_UPCOMING_SLOTS)
+            .map_err(|err| format!("Failed to get slot leaders: {err}"))?;
+        let vote_accounts = rpc
+            .get_vote_accounts()
+            .map_err(|err| format!("Failed to get vote accounts: {err}"))?;
+        let vote_accounts = vote_accounts
+            .current
+            .iter()
+            .chain(&vote_accounts.delinquent)
+            .filter_map(|vote_account| {
+                Some((
+                    Pubkey::from_str(&vote_account.node_pubkey).ok()?,
+                    Pubkey::from_str(&vote_account.vote_pubkey).ok()?,
+                ))
+            })
+            .collect();
+        Ok(Self {
+            current_slot,
+            upcoming: group_slots(current_slot, &upcoming),
+            landed: landed_slot.map(|slot| (slot, leader_of(rpc, slot))),
+            vote_accounts,
+        })
+    }
+
+    pub fn vote_account(&self, identity: &Pubkey) -> Option<&Pubkey> {
+        self.vote_accounts.get(identity)
+    }
+}
+
+fn group_slots(first_slot: u64, leaders: &[Pubkey]) -> Vec<SlotLeader> {
+    let mut grouped = Vec::<SlotLeader>::new();
+    for (slot, identity) in (first_slot..).zip(leaders) {
+        match grouped.last_mut() {
+            Some(last) if last.identity == *identity => last.last_slot = slot,
+            _ => grouped.push(SlotLeader {
+                first_slot: slot,
+                last_slot: slot,
+                identity: *identity,
+            }),
+        }
+    }
+    grouped
+}
+
+/// The leader of a past slot. `getSlotLeaders` only covers recent epochs, so
+/// fall back to the leader schedule of the slot's epoch.
+fn leader_of(rpc: &dyn RpcApi, slot: u64) -> Result<Pubkey, String> {
+    if let Some(identity) = rpc
+        .get_slot_leaders(slot, 1)
+        .ok()
+        .and_then(|leaders| leaders.into_iter().next())
+    {
+        return Ok(identity);
+    }
+    let epoch_schedule = rpc
+        .get_epoch_schedule()
+        .map_err(|err| format!("Failed to get epoch schedule: {err}"))?;
+    let first_slot = epoch_schedule.get_first_slot_in_epoch(epoch_schedule.get_epoch(slot));
+    let index = (slot - first_slot) as usize;
+    rpc.get_leader_schedule(slot)
+        .map_err(|err| format!("Failed to get leader schedule: {err}"))?
+        .ok_or_else(|| format!("No leader schedule for slot {slot}"))?
+        .into_iter()
+        .find(|(_, indexes)| indexes.contains(&index))
+        .and_then(|(identity, _)| Pubkey::from_str(&identity).ok())
+        .ok_or_else(|| format!("No leader for slot {slot}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_consecutive_slots_of_a_leader() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let grouped = group_slots(100, &[a, a, b, b, b, b, a]);
+        let ranges = grouped
+            .iter()
+            .map(|leader| (leader.first_slot, leader.last_slot, leader.identity))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [(100, 101, a), (102, 105, b), (106, 106, a)]);
+    }
+}
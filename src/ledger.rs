@@ -0,0 +1,87 @@
+use {
+    solana_remote_wallet::{
+        locator::Locator,
+        remote_keypair::{generate_remote_keypair, RemoteKeypair},
+        remote_wallet::maybe_wallet_manager,
+    },
+    solana_sdk::{
+        derivation_path::DerivationPath, pubkey::Pubkey, signature::Signature, signer::Signer,
+    },
+};
+
+const NUM_PICKER_ACCOUNTS: u32 = 5;
+
+/// Derivation paths offered by the path picker: the default path followed by
+/// the first few accounts, with and without a change index.
+pub fn derivation_paths() -> Vec<DerivationPath> {
+    std::iter::once(DerivationPath::default())
+        .chain((0..NUM_PICKER_ACCOUNTS).flat_map(|account| {
+            [
+                DerivationPath::new_bip44(Some(account), None),
+                DerivationPath::new_bip44(Some(account), Some(0)),
+            ]
+        }))
+        .collect()
+}
+
+/// Format a derivation path as `m/44'/501'/...`.
+pub fn format_derivation_path(derivation_path: &DerivationPath) -> String {
+    format!("{derivation_path:?}")
+}
+
+/// Connect to the first Ledger device and load the pubkey at the derivation
+/// path. Signing with the returned keypair requires confirmation on the
+/// device.
+pub fn connect(derivation_path: DerivationPath) -> Result<RemoteKeypair, String> {
+    let wallet_manager = maybe_wallet_manager()
+        .map_err(|err| format!("Failed to access USB devices: {err}"))?
+        .ok_or_else(|| "No Ledger device found".to_string())?;
+    let locator = Locator::new_from_path("usb://ledger")
+        .map_err(|err| format!("Invalid Ledger locator: {err}"))?;
+    generate_remote_keypair(locator, derivation_path, &wallet_manager, false, "ledger")
+        .map_err(|err| format!("Failed to connect to Ledger: {err}"))
+}
+
+/// Sign a message with the keypair at the derivation path, which must be
+/// `pubkey`, once confirmed on the device.
+pub fn sign(
+    derivation_path: DerivationPath,
+    pubkey: &Pubkey,
+    message: &[u8],
+) -> Result<Signature, String> {
+    let remote_keypair = connect(derivation_path)?;
+    if remote_keypair.pubkey != *pubkey {
+        return Err(format!(
+            "Ledger key changed from {pubkey} to {}",
+            remote_keypair.pubkey
+        ));
+    }
+    remote_keypair
+        .try_sign_message(message)
+        .map_err(|err| format!("Failed to sign with Ledger: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offers_the_default_path_and_the_first_accounts() {
+        let paths = derivation_paths()
+            .iter()
+            .map(format_derivation_path)
+            .collect::<Vec<_>>();
+        assert_eq!(paths.len(), 1 + 2 * NUM_PICKER_ACCOUNTS as usize);
+        assert_eq!(
+            paths[..5],
+            [
+                "m/44'/501'",
+                "m/44'/501'/0'",
+                "m/44'/501'/0'/0'",
+                "m/44'/501'/1'",
+                "m/44'/501'/1'/0'",
+            ]
+        );
+        assert_eq!(paths.last().unwrap(), "m/44'/501'/4'/0'");
+    }
+}
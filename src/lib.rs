@@ -0,0 +1,9 @@
+//! Transaction parsing and sectioning, independent of the terminal UI so that
+//! it can also be exercised by the fuzz targets.
+
+pub mod byte_tags;
+pub mod entries;
+pub mod ingest;
+pub mod message_format;
+pub mod transaction_byte_sections;
+pub mod transaction_diff;
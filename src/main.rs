@@ -1,257 +1,7870 @@
 use {
+    access_matrix::AccessMatrix,
+    address_book::AddressBook,
+    alignment::PaneAlignment,
+    alt_planner::AltPlan,
+    bookmarks::{native_program_name, Annotation, Bookmarks},
+    broadcast::Broadcast,
+    bundle::Bundle,
+    byte_budget::ByteBudget,
     byte_section_legend::ByteSectionLegend,
+    cassette::{Recorder, Replayer},
+    command::{Command, CommandRunner, Message},
+    commitment_timeline::{CommitmentPoll, CommitmentTimeline},
+    compute_budget::ComputeBudget,
+    decoders::{DecodedInstruction, InnerTransactionSource},
+    derivation::Derived,
+    edit::EditSession,
+    endpoint_health::{public_endpoint, EndpointHealth},
+    error_codes::ErrorCodes,
+    failures::{FailureFilter, FailureKind},
+    follow::{Follow, Latest},
+    input_history::InputHistory,
+    instructions_sysvar::Introspection,
+    keybindings::{Action, Context, KeyBinding},
+    keypairs::{KeypairSource, KeypairStore},
+    landing::Landing,
+    leaders::Leaders,
     menu::AppMenuItem,
+    metrics::Metrics,
+    minimap::Minimap,
+    ownership::{Link, Ownership},
+    preflight::Preflight,
     ratatui::{
         crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
-        layout::{Constraint, Direction, Layout},
+        layout::{Alignment, Constraint, Direction, Layout, Rect},
+        style::Modifier,
         style::{Color, Style},
-        text::Text,
-        widgets::{Block, Borders, Padding, Paragraph},
+        text::{Line, Span, Text},
+        widgets::{Block, Borders, Clear, List, ListState, Padding, Paragraph, Wrap},
         Frame,
     },
-    solana_client::{rpc_client::RpcClient, rpc_config::RpcTransactionConfig},
-    solana_sdk::{commitment_config::CommitmentConfig, signature::Signature},
-    solana_transaction_status::UiTransactionEncoding,
-    std::{io, str::FromStr},
-    transaction_byte_block::TransactionByteBlock,
-    transaction_byte_sections::{get_transaction_byte_sections, TransactionByteSection},
+    refetch_diff::{Fetched, RefetchChange},
+    related::Related,
+    reveal::Reveal,
+    rpc::{FetchOptions, MockRpc, RpcApi, RpcSource, DEFAULT_MOCK_DIR},
+    rpc_console::RpcHistory,
+    sandwich::MevContext,
+    schema::{ProgramPdas, Schema, TemplateForm},
+    security_txt::SecurityTxt,
+    sensitivity::SensitivityMap,
+    serde_json::Value,
+    settings::{AmountUnit, Coloring, Settings, TimeZone},
+    simulation::Simulation,
+    slot_timing::{Attempt, SlotTimings},
+    snapshots::{Snapshot, Snapshots},
+    solana_sdk::{
+        account::Account,
+        derivation_path::DerivationPath,
+        packet::PACKET_DATA_SIZE,
+        pubkey::Pubkey,
+        signature::Signature,
+        signer::{presigner::Presigner, Signer},
+        transaction::{TransactionError, VersionedTransaction},
+    },
+    solana_transaction_status::{
+        EncodedConfirmedTransactionWithStatusMeta, TransactionStatus, UiLoadedAddresses,
+        UiTransactionStatusMeta,
+    },
+    solana_transaction_tui::{
+        byte_tags,
+        entries::{self, EntryTransaction},
+        ingest,
+        transaction_byte_sections::{
+            self, get_transaction_byte_sections, message_sections, section_at, section_starts,
+            SectionCategory, SectionKind, TransactionByteSection,
+        },
+        transaction_diff::{self, Change, ChangeKind, TransactionDiff},
+    },
+    std::{
+        borrow::Cow,
+        cell::RefCell,
+        collections::{BTreeMap, HashMap, VecDeque},
+        io::{self, Read},
+        ops::Range,
+        path::PathBuf,
+        rc::Rc,
+        str::FromStr,
+        sync::Arc,
+        time::{Duration, Instant, SystemTime},
+    },
+    storage::FileChange,
+    stream_exports::StreamExports,
+    transaction_byte_block::{GridZoom, TransactionByteBlock, NUM_HEADER_LINES},
     tui_input::{backend::crossterm::EventHandler, Input},
     tui_menu::{Menu, MenuEvent, MenuState},
+    verified_builds::BuildStatus,
+    vote_summary::VoteSummary,
+    watch::{Stream, StreamedTransaction, Subscription},
+    zeroize::Zeroizing,
 };
 
+mod access_matrix;
+mod address_book;
+mod alignment;
+mod alt_planner;
+#[cfg(test)]
+mod app_tests;
+mod block_digits;
+#[cfg(feature = "blockstore")]
+mod blockstore;
+mod bookmarks;
+mod broadcast;
+mod bundle;
+mod byte_budget;
 mod byte_section_legend;
+mod cassette;
+mod command;
+mod commitment_timeline;
+mod compute_budget;
+mod converter;
+mod data_sizes;
+mod decoders;
+mod derivation;
+mod diff_report;
+mod edit;
+mod endpoint_health;
+mod error_codes;
+mod explorer_link;
+mod failures;
+mod find;
+mod follow;
+mod format;
+mod graph;
+mod highlight_rules;
+mod input_history;
+mod instructions_sysvar;
+mod keybindings;
+mod keypairs;
+mod landing;
+mod leaders;
+mod ledger;
 mod menu;
+mod metrics;
+mod minimap;
+mod narration;
+mod ownership;
+mod preflight;
+mod program_colors;
+mod refetch_diff;
+mod related;
+mod reveal;
+mod rpc;
+mod rpc_console;
+mod runtime_accounts;
+mod sandwich;
+mod schema;
+mod security_txt;
+mod sensitivity;
+mod settings;
+mod signature_check;
+mod simulation;
+mod slot_timing;
+mod snapshots;
+mod sns;
+mod storage;
+mod stream_exports;
+mod strings;
 mod transaction_byte_block;
-mod transaction_byte_sections;
 mod tui;
+mod varint;
+mod verified_builds;
+mod vote_summary;
+mod watch;
+
+const DEFAULT_ENDPOINT: &str = "https://api.mainnet-beta.solana.com";
+/// Operations taking at least this long signal their completion if the
+/// terminal is in the background.
+const LONG_OPERATION: Duration = Duration::from_secs(2);
 
 fn main() -> io::Result<()> {
-    let mut terminal = tui::init()?;
-    let app_result = TransactionApp {
-        exit: false,
-        mode: Mode::SignatureEntry,
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}");
+            eprintln!("Usage: solana-transaction-tui [OPTION]");
+            eprintln!("  --mock [<dir>]        answer with canned responses from a directory");
+            eprintln!("  --record <cassette>   record the traffic with the endpoint");
+            eprintln!("  --replay <cassette>   answer with the traffic of a recording");
+            eprintln!("  --ledger-dir <dir>    read from a local validator ledger");
+            eprintln!("  --metrics <path>      write fetch metrics to a file on exit");
+            eprintln!("  --import <bundle>     view the transaction of an exported bundle");
+            eprintln!("  --stdin               view the transaction piped in, in wire format");
+            eprintln!("  --diff-report <pairs> print a JSON report diffing each listed pair");
+            std::process::exit(2);
+        }
+    };
 
-        input: Input::new("".to_string()),
-        signature: SignatureStatus::None,
-        transaction_byte_sections: vec![],
+    if let Some(pairs) = args.diff_report {
+        let rpc = args.rpc_source.api(DEFAULT_ENDPOINT);
+        match diff_report::run(&pairs, rpc.as_ref()) {
+            Ok(report) => {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                std::process::exit(if report.all_identical() { 0 } else { 1 });
+            }
+            Err(err) => {
+                eprintln!("{err}");
+                std::process::exit(2);
+            }
+        }
+    }
 
-        menu: menu::menu(),
-        endpoint: "https://api.mainnet-beta.solana.com",
+    let mut terminal = tui::init()?;
+    let mut app = TransactionApp::new(Settings::load(), Bookmarks::load(), args.rpc_source);
+    app.slot_timings = SlotTimings::load();
+    app.rpc_history = RpcHistory::load();
+    app.input_history = InputHistory::in_data_dir();
+    app.address_book = AddressBook::load();
+    app.address_book_status = app
+        .address_book
+        .load_error()
+        .map(|err| format!("{err}, contacts are not saved"));
+    app.snapshots = Snapshots::in_data_dir();
+    app.watch.exports = StreamExports::in_data_dir();
+    if let Some(bundle) = args.bundle {
+        app.open_bundle(bundle);
     }
-    .run(&mut terminal);
+    if let Some(transaction) = args.piped {
+        app.open_transaction(transaction, "stdin".to_string());
+    }
+    let app_result = app.run(&mut terminal);
     tui::restore()?;
+    if let Some(path) = args.metrics_path {
+        if let Err(err) = std::fs::write(&path, app.metrics.render()) {
+            eprintln!("Failed to write metrics to {path}: {err}");
+        }
+    }
     app_result
 }
 
+struct Args {
+    rpc_source: RpcSource,
+    metrics_path: Option<String>,
+    bundle: Option<Bundle>,
+    /// The transaction piped in with `--stdin`.
+    piped: Option<VersionedTransaction>,
+    /// The contents of a pairs file, for a headless diff report.
+    diff_report: Option<String>,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut rpc_source = RpcSource::Endpoint;
+    let mut metrics_path = None;
+    let mut import_path = None;
+    let mut read_stdin = false;
+    let mut diff_report = None;
+    // The flag choosing the RPC source, as only one can be given.
+    let mut source_flag: Option<String> = None;
+    // The flag choosing the transaction viewed at start, likewise.
+    let mut transaction_flag: Option<String> = None;
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        let exclusive_flag = match arg.as_str() {
+            "--mock" | "--record" | "--replay" | "--ledger-dir" => Some(&mut source_flag),
+            "--import" | "--stdin" => Some(&mut transaction_flag),
+            _ => None,
+        };
+        if let Some(exclusive_flag) = exclusive_flag {
+            if let Some(previous) = exclusive_flag {
+                return Err(format!("{arg} cannot be combined with {previous}"));
+            }
+            *exclusive_flag = Some(arg.clone());
+        }
+        rpc_source = match arg.as_str() {
+            "--metrics" => {
+                metrics_path = Some(args.next().ok_or("--metrics requires a file path")?);
+                continue;
+            }
+            "--import" => {
+                import_path = Some(args.next().ok_or("--import requires a bundle path")?);
+                continue;
+            }
+            "--stdin" => {
+                read_stdin = true;
+                continue;
+            }
+            "--diff-report" => {
+                let path = args.next().ok_or("--diff-report requires a pairs file")?;
+                let pairs = std::fs::read_to_string(&path)
+                    .map_err(|err| format!("Failed to read {path}: {err}"))?;
+                diff_report = Some(pairs);
+                continue;
+            }
+            "--mock" => {
+                // The directory is optional, so a flag after it is not one.
+                let dir = args
+                    .next_if(|next| !next.starts_with("--"))
+                    .unwrap_or_else(|| DEFAULT_MOCK_DIR.to_string());
+                RpcSource::Mock(Arc::new(MockRpc::new(dir)))
+            }
+            "--record" => {
+                let path = args.next().ok_or("--record requires a cassette path")?;
+                let recorder = Recorder::create(&path)
+                    .map_err(|err| format!("Failed to create {path}: {err}"))?;
+                RpcSource::Record(Arc::new(recorder))
+            }
+            "--replay" => {
+                let path = args.next().ok_or("--replay requires a cassette path")?;
+                RpcSource::Replay(Arc::new(Replayer::load(path)?))
+            }
+            #[cfg(feature = "blockstore")]
+            "--ledger-dir" => {
+                let path = args
+                    .next()
+                    .ok_or("--ledger-dir requires a ledger directory")?;
+                RpcSource::Ledger(Arc::new(blockstore::LocalLedger::open(path)?))
+            }
+            #[cfg(not(feature = "blockstore"))]
+            "--ledger-dir" => return Err("--ledger-dir requires the blockstore feature".into()),
+            _ => return Err(format!("Unknown argument {arg}")),
+        };
+    }
+    // Read only once the arguments are known to be valid.
+    let bundle = match import_path {
+        Some(path) => {
+            let bytes =
+                std::fs::read(&path).map_err(|err| format!("Failed to read {path}: {err}"))?;
+            Some(Bundle::unarchive(&bytes)?)
+        }
+        None => None,
+    };
+    let piped = if read_stdin {
+        let mut bytes = vec![];
+        io::stdin()
+            .read_to_end(&mut bytes)
+            .map_err(|err| format!("Failed to read stdin: {err}"))?;
+        Some(ingest::parse_transaction(&bytes)?)
+    } else {
+        None
+    };
+    Ok(Args {
+        rpc_source,
+        metrics_path,
+        bundle,
+        piped,
+        diff_report,
+    })
+}
+
 enum Mode {
     SignatureEntry,
     MenuSelection,
+    /// Asking whether to refetch the loaded transaction from the endpoint
+    /// switched to.
+    ConfirmRefetch,
+    /// Choosing the options of fetches, with the selected one.
+    FetchOptions(usize),
+    ByteNavigation,
+    AnnotationEntry,
+    /// Entering the transaction to compare the viewed one with.
+    DiffSignatureEntry,
+    Diff,
+    Edit,
+    /// Overwriting bytes by typing hex digits, holding the high nibble of
+    /// the byte being typed.
+    HexWrite(Option<u8>),
+    EditValueEntry(EditField),
+    KeypairEntry(KeypairPrompt),
+    LedgerSigning(LedgerState),
+    Template(usize),
+    /// The lookup table planned for the transaction, or `None` while
+    /// planning, with the selected step.
+    AltPlan {
+        plan: Option<Box<AltPlan>>,
+        status: Option<String>,
+        step: usize,
+    },
+    /// The transaction wrapped by an instruction.
+    InnerTransaction {
+        title: String,
+        sections: Vec<TransactionByteSection>,
+    },
+    CommitmentTimeline,
+    Broadcast,
+    /// The leader schedule, or `None` while fetching.
+    Leaders(Option<Result<Leaders, String>>),
+    /// Transactions touching an account in nearby slots.
+    Related(Box<RelatedView>),
+    /// Neighbors of the transaction in its block, or `None` while fetching,
+    /// with the selected one.
+    Sandwich {
+        mev_context: Option<Result<MevContext, String>>,
+        selected: usize,
+    },
+    /// Accounts by instruction, with the selected account and instruction.
+    AccessMatrix(usize, usize),
+    /// The instructions sysvar of the transaction, with its scroll.
+    InstructionsSysvar(u16),
+    /// Submissions of the transaction, with the outcome of recording one.
+    SlotTiming(Option<String>),
+    SubmissionTimeEntry,
+    /// The simulation of the edited transaction, or `None` while
+    /// simulating, with the selected account.
+    Simulation {
+        simulation: Option<Result<Simulation, String>>,
+        selected: usize,
+        scroll: usize,
+    },
+    /// Comparing a re-simulation of the failed transaction, or `None`
+    /// while simulating, with its on-chain outcome.
+    Preflight {
+        preflight: Option<Result<Preflight, String>>,
+        scroll: u16,
+    },
+    /// Sending requests to the endpoint, with the request sent and its
+    /// response once received.
+    RpcConsole {
+        /// The history entry in the input, while going through the history.
+        history_index: Option<usize>,
+        response: Option<(String, Option<Result<Value, String>>)>,
+        scroll: usize,
+    },
+    AddressBook(usize),
+    /// Entering a contact, replacing the contact of the pubkey if any, with
+    /// the mode to return to.
+    ContactEntry(Option<Pubkey>, Box<Mode>),
+    /// Entering a keypair directory, with the mode to return to.
+    KeypairDirEntry(Box<Mode>),
+    /// Bookmarks matching the filter, with the selected one.
+    Bookmarks {
+        filter: Input,
+        selected: usize,
+    },
+    /// Entering the tags of the bookmark, with the mode to return to.
+    TagEntry(Signature, Box<Mode>),
+    /// Transactions matching the find query, with the selected one.
+    Find(Box<FindView>),
+    /// Transactions of new blocks, with the selected one.
+    Watch(usize),
+    /// Entering a pattern to watch for, returning to the selected
+    /// transaction.
+    WatchPatternEntry(usize),
+    /// The subscriptions of the watch, with the selected transaction and
+    /// subscription.
+    Subscriptions(usize, usize),
+    /// Entering an address to subscribe to, with the selected transaction.
+    SubscriptionEntry(usize),
+    Derivation,
+    /// Encoding and decoding compact-u16 and varints as they are typed.
+    VarintPlayground,
+    /// Converting between base58, base64, hex and byte arrays as they are
+    /// typed.
+    Converter,
+    /// Verifying a signature as it is typed.
+    SignatureCheck,
+    /// The narration of the transaction, with its scroll.
+    Narration(u16),
+    /// The sensitivity of every byte, or `None` while mapping.
+    SensitivityMap {
+        sensitivity_map: Option<SensitivityMap>,
+        scroll: usize,
+    },
+    /// Strings found in instruction data, with the selected one.
+    Strings(usize),
+    /// Statistics of the transaction, with the byte budget calculator and
+    /// its selected quantity.
+    Stats(ByteBudget, usize),
+    /// Waiting for the region to jump to after `g`.
+    Jump,
+    EntriesFileEntry,
+    LedgerSlotEntry,
+    EntryPicker(usize),
+    Help(Box<Help>),
+    CommandPalette(Box<Palette>),
+}
+
+impl Mode {
+    /// The keybindings active in this mode.
+    fn context(&self) -> Context {
+        match self {
+            Self::SignatureEntry => Context::SignatureEntry,
+            Self::MenuSelection => Context::Menu,
+            Self::ConfirmRefetch => Context::ConfirmRefetch,
+            Self::FetchOptions(_) => Context::FetchOptions,
+            Self::ByteNavigation => Context::ByteNavigation,
+            Self::Jump => Context::Jump,
+            Self::AnnotationEntry
+            | Self::DiffSignatureEntry
+            | Self::EditValueEntry(_)
+            | Self::KeypairEntry(_)
+            | Self::EntriesFileEntry
+            | Self::LedgerSlotEntry
+            | Self::SubmissionTimeEntry
+            | Self::ContactEntry(..)
+            | Self::KeypairDirEntry(_)
+            | Self::TagEntry(..)
+            | Self::WatchPatternEntry(_)
+            | Self::SubscriptionEntry(_)
+            | Self::Derivation
+            | Self::VarintPlayground
+            | Self::Converter
+            | Self::SignatureCheck => Context::Prompt,
+            Self::Edit => Context::Edit,
+            Self::Diff => Context::Diff,
+            Self::HexWrite(_) => Context::HexWrite,
+            Self::LedgerSigning(_) => Context::LedgerSigning,
+            Self::Template(_) => Context::Template,
+            Self::AltPlan { .. } => Context::AltPlan,
+            Self::InnerTransaction { .. } => Context::InnerTransaction,
+            Self::CommitmentTimeline => Context::CommitmentTimeline,
+            Self::Broadcast => Context::Broadcast,
+            Self::Leaders(_) => Context::Leaders,
+            Self::Related(_) => Context::Related,
+            Self::Sandwich { .. } => Context::Sandwich,
+            Self::AccessMatrix(..) => Context::AccessMatrix,
+            Self::InstructionsSysvar(_) => Context::InstructionsSysvar,
+            Self::SlotTiming(_) => Context::SlotTiming,
+            Self::Simulation { .. } => Context::Simulation,
+            Self::Preflight { .. } => Context::Preflight,
+            Self::RpcConsole { .. } => Context::RpcConsole,
+            Self::AddressBook(_) => Context::AddressBook,
+            Self::Bookmarks { .. } => Context::Bookmarks,
+            Self::Find(_) => Context::Find,
+            Self::Watch(_) => Context::Watch,
+            Self::Subscriptions(..) => Context::Subscriptions,
+            Self::Narration(_) => Context::Narration,
+            Self::SensitivityMap { .. } => Context::SensitivityMap,
+            Self::Strings(_) => Context::Strings,
+            Self::Stats(..) => Context::Stats,
+            Self::EntryPicker(_) => Context::EntryPicker,
+            Self::Help(_) => Context::Help,
+            Self::CommandPalette(_) => Context::CommandPalette,
+        }
+    }
+
+    /// The transaction selected in the watch view, while it is shown.
+    fn watch_selected(&self) -> Option<usize> {
+        match *self {
+            Self::Watch(selected)
+            | Self::WatchPatternEntry(selected)
+            | Self::Subscriptions(selected, _)
+            | Self::SubscriptionEntry(selected) => Some(selected),
+            _ => None,
+        }
+    }
+
+    /// The same watch mode with another transaction selected.
+    fn with_watch_selected(&self, selected: usize) -> Self {
+        match *self {
+            Self::WatchPatternEntry(_) => Self::WatchPatternEntry(selected),
+            Self::Subscriptions(_, subscription) => Self::Subscriptions(selected, subscription),
+            Self::SubscriptionEntry(_) => Self::SubscriptionEntry(selected),
+            _ => Self::Watch(selected),
+        }
+    }
+
+    /// The mode help or the command palette is shown over, or this mode.
+    fn underlying(&self) -> &Mode {
+        match self {
+            Self::Help(help) => &help.return_mode,
+            Self::CommandPalette(palette) => &palette.return_mode,
+            mode => mode,
+        }
+    }
+
+    /// The mode help or the command palette is shown over, or this mode, to
+    /// update with results arriving while they are open.
+    fn underlying_mut(&mut self) -> &mut Mode {
+        match self {
+            Self::Help(help) => &mut help.return_mode,
+            Self::CommandPalette(palette) => &mut palette.return_mode,
+            mode => mode,
+        }
+    }
+
+    /// The mode a prompt returns to, shown underneath it.
+    fn return_mode(&self) -> Option<&Mode> {
+        match self {
+            Self::ContactEntry(_, return_mode)
+            | Self::KeypairDirEntry(return_mode)
+            | Self::TagEntry(_, return_mode) => Some(return_mode),
+            _ => None,
+        }
+    }
+
+    /// The mode a prompt, help or the command palette returns to, or the
+    /// same mode for the others.
+    fn into_return_mode(self) -> Mode {
+        match self {
+            Self::ContactEntry(_, return_mode)
+            | Self::KeypairDirEntry(return_mode)
+            | Self::TagEntry(_, return_mode) => *return_mode,
+            Self::Help(help) => help.return_mode,
+            Self::CommandPalette(palette) => palette.return_mode,
+            mode => mode,
+        }
+    }
+}
+
+/// Where the cursor was in a transaction, restored when it is viewed again.
+#[derive(Clone, Copy, Default)]
+struct ViewPosition {
+    cursor: usize,
+    byte_scroll: usize,
+    selection_anchor: Option<usize>,
+}
+
+/// The keybindings shown over the mode help was opened from.
+struct Help {
+    return_mode: Mode,
+    query: Input,
+    scroll: u16,
+}
+
+/// The transactions touching an account around the loaded one.
+struct RelatedView {
+    /// The transactions found, or `None` while fetching.
+    related: Option<Result<Related, String>>,
+    /// The number of slots searched either side of the transaction.
+    window: u64,
+    filter: FailureFilter,
+    selected: usize,
+}
+
+impl RelatedView {
+    /// The transactions the failure filter shows.
+    fn visible(&self) -> Vec<&related::Candidate> {
+        let candidates = match &self.related {
+            Some(Ok(related)) => related.candidates.as_slice(),
+            _ => &[],
+        };
+        candidates
+            .iter()
+            .filter(|candidate| self.filter.matches(candidate.failure().as_ref()))
+            .collect()
+    }
+}
+
+/// The transactions matching the find query.
+struct FindView {
+    query: Input,
+    /// The transactions matching the query, or why it is invalid.
+    hits: Result<Vec<find::Hit>, String>,
+    /// The intact snapshots of bookmarks, loaded as the find opens.
+    snapshots: Vec<(Signature, Snapshot)>,
+    selected: usize,
+}
+
+/// The transactions of new blocks, kept while the streamed ones are viewed.
+#[derive(Default)]
+struct Watch {
+    stream: Stream,
+    /// Open the first transaction matching the watchlist as it streams in.
+    auto_open: bool,
+    status: Option<String>,
+    filter: FailureFilter,
+    exports: StreamExports,
+}
+
+/// The commands offered over the mode the palette was opened from.
+struct Palette {
+    return_mode: Mode,
+    query: Input,
+    selected: usize,
+    status: Option<String>,
+}
+
+impl Palette {
+    fn new(return_mode: Mode) -> Self {
+        Self {
+            return_mode,
+            query: Input::default(),
+            selected: 0,
+            status: None,
+        }
+    }
+
+    /// Whether a keybinding applies in the mode the palette was opened from.
+    fn is_available(&self, binding: &KeyBinding) -> bool {
+        binding.context == Context::Global || binding.context == self.return_mode.context()
+    }
+
+    /// The commands matching the query: every action once per mode, those
+    /// available in the mode opened from first, followed by the menu items.
+    fn commands(&self) -> Vec<(String, PaletteCommand)> {
+        let query = self.query.value();
+        let mut seen = vec![];
+        let mut bindings = keybindings::all()
+            .filter(|binding| {
+                !matches!(binding.context, Context::Help | Context::CommandPalette)
+                    && binding.action != Action::OpenCommandPalette
+            })
+            .filter(|binding| {
+                let is_new = !seen.contains(&(binding.context, binding.action));
+                seen.push((binding.context, binding.action));
+                is_new
+            })
+            .map(|binding| {
+                (
+                    format!("{} ({})", binding.description, binding.context.name()),
+                    binding,
+                )
+            })
+            .filter(|(label, _)| keybindings::fuzzy_matches(query, label))
+            .collect::<Vec<_>>();
+        bindings.sort_by_key(|(_, binding)| !self.is_available(binding));
+        bindings
+            .into_iter()
+            .map(|(label, binding)| (label, PaletteCommand::Key(binding)))
+            .chain(
+                menu::commands()
+                    .into_iter()
+                    .filter(|(label, _)| keybindings::fuzzy_matches(query, label))
+                    .map(|(label, item)| (label, PaletteCommand::Menu(item))),
+            )
+            .collect()
+    }
+}
+
+/// A command offered by the command palette.
+enum PaletteCommand {
+    Key(&'static KeyBinding),
+    Menu(AppMenuItem),
+}
+
+/// Progress of signing the edited transaction with a Ledger device.
+enum LedgerState {
+    SelectingPath(usize),
+    Connecting(DerivationPath),
+    AwaitingConfirmation(Pubkey),
+}
+
+/// Steps of loading a keypair through the prompt.
+enum KeypairPrompt {
+    File,
+    SeedPhrase,
+    /// Entering the passphrase of the seed phrase entered, which is wiped
+    /// from memory once the keypair is derived.
+    Passphrase(Zeroizing<String>),
+}
+
+impl KeypairPrompt {
+    /// Whether the entered value is secret and must not be displayed.
+    fn is_secret(&self) -> bool {
+        matches!(self, Self::SeedPhrase | Self::Passphrase(_))
+    }
 }
 
-enum SignatureStatus {
-    None,
-    Invalid,
+/// A value of the edited transaction which is entered through the prompt.
+#[derive(Clone, Copy)]
+enum EditField {
+    AccountKey(usize),
+    InstructionData(usize),
+    SchemaFile(usize),
+    ComputeUnitLimit,
+    ComputeUnitPrice,
+}
+
+/// A transaction fetched from the endpoint, with its status.
+struct LoadedTransaction {
+    signature: Signature,
+    transaction: VersionedTransaction,
+    meta: Option<UiTransactionStatusMeta>,
+    slot: Option<u64>,
+    block_time: Option<i64>,
+    sections: Vec<TransactionByteSection>,
+    /// Where the transaction was loaded from, if not the endpoint.
+    source: Option<String>,
+    /// The endpoint the transaction was fetched from.
+    endpoint: Option<&'static str>,
+    /// The options the transaction was fetched with, from the endpoint.
+    fetch_options: Option<FetchOptions>,
+    /// Accounts fetched while viewing the transaction.
+    accounts: BTreeMap<Pubkey, Account>,
+}
+
+impl LoadedTransaction {
+    fn fetched(&self) -> Fetched<'_> {
+        Fetched {
+            transaction: &self.transaction,
+            meta: self.meta.as_ref(),
+            slot: self.slot,
+            block_time: self.block_time,
+        }
+    }
+}
+
+/// What the main area shows. Each screen has its own renderer, while modes
+/// select the keybindings and overlays on top of it.
+enum Screen {
+    /// Nothing is loaded yet.
+    Input,
+    /// The transaction is being fetched in the background.
+    Loading(Signature),
+    /// The endpoint does not know the transaction, possibly not landed yet.
     NotFound(Signature),
-    Found(Signature),
+    /// Polling the status of the transaction until it can be fetched.
+    Landing(Landing),
+    Viewing(LoadedTransaction),
+    /// Editing a copy of the loaded transaction, with the outcome of the
+    /// last edit and the form of the instruction data schema loaded.
+    Edit {
+        loaded: LoadedTransaction,
+        session: Box<EditSession>,
+        status: Option<String>,
+        template_form: Option<TemplateForm>,
+    },
+    /// The viewed transaction compared with another, side by side.
+    Diff {
+        loaded: LoadedTransaction,
+        compared: Compared,
+        byte_scroll: usize,
+    },
+    Error(String),
+}
+
+/// The transaction the viewed one is compared with.
+enum Compared {
+    Fetching(Signature),
+    /// The transaction and its diff from the viewed one.
+    Fetched(Box<LoadedTransaction>, TransactionDiff),
+    Failed(String),
 }
 
 pub struct TransactionApp {
     exit: bool,
     mode: Mode,
+    screen: Screen,
+    focused: bool,
+    /// Events read while checking for focus changes, to be handled next.
+    pending_events: VecDeque<Event>,
+    /// Commands queued by updates, started once the update is done.
+    commands: VecDeque<Command>,
+    command_runner: CommandRunner,
+    settings: Settings,
+    /// The progress of the last file written in the background.
+    file_status: Option<String>,
 
-    // Useful for Mode::SignatureEntry
+    /// The signature entered, kept while viewing to refetch and bookmark.
     input: Input,
-    signature: SignatureStatus,
-    transaction_byte_sections: Vec<TransactionByteSection>,
+    input_history: InputHistory,
+    /// The history entry in the input, while going through the history.
+    input_history_index: Option<usize>,
+
+    /// Where the byte grid is, shared by the viewed, edited and compared
+    /// transactions.
+    cursor: usize,
+    selection_anchor: Option<usize>,
+    byte_scroll: usize,
+    bytes_per_line: usize,
+    /// The area of the terminal, which lays out the byte grid to scroll.
+    frame_area: Rect,
+    message_view: bool,
+    /// Show the bytes of vote transactions rather than their summary.
+    vote_bytes: bool,
+    bookmarks: Bookmarks,
+    /// The fetched bytes and status of bookmarked transactions.
+    snapshots: Snapshots,
+    /// The PDA seeds of the IDLs loaded.
+    idl_pdas: Vec<ProgramPdas>,
+    /// The names of custom error codes, from the settings and loaded IDLs.
+    error_codes: ErrorCodes,
+    /// What the account keys of a transaction which are PDAs derive from.
+    pda_labels: Option<(Signature, HashMap<Pubkey, String>)>,
+    /// The roles of the account keys which transfer hooks added.
+    hook_roles: Option<(Signature, HashMap<Pubkey, String>)>,
+    /// The copy displayed before a refetch, to compare the refetched one to.
+    refetching: Option<LoadedTransaction>,
+    /// What changed on the last refetch of a signature.
+    refetch_changes: Option<(Signature, Vec<RefetchChange>)>,
+    /// Who owns the account keys of a transaction, resolved over the network.
+    ownership: Option<(Signature, Result<Ownership, String>)>,
+    /// Whether the programs invoked are verified builds, checked once per
+    /// endpoint.
+    build_statuses: HashMap<Pubkey, Result<BuildStatus, String>>,
+    /// The security.txt of the programs invoked, fetched once per endpoint.
+    security_txts: HashMap<Pubkey, Result<Option<SecurityTxt>, String>>,
+    /// The `.sol` domains of the account keys of a transaction.
+    domains: Option<(Signature, HashMap<Pubkey, String>)>,
+    /// The staged reveal of the transaction just loaded, while in progress.
+    reveal: Option<Reveal>,
+    /// Show the bytes in large digits without the legend, for projecting.
+    presentation: bool,
+    /// Where the cursor was in each transaction viewed this session.
+    view_positions: HashMap<Signature, ViewPosition>,
+    /// Number the instructions on the byte grid, dimming other bytes.
+    instruction_overlay: bool,
+
+    /// The input of the single-line prompts.
+    prompt_input: Input,
+    /// Polled in the background, also while its view is closed.
+    commitment_timeline: Option<CommitmentTimeline>,
+    /// Records the slot timings of its outcomes, also while its view is closed.
+    broadcast: Option<Broadcast>,
+    slot_timings: SlotTimings,
+    rpc_history: RpcHistory,
+    address_book: AddressBook,
+    /// The outcome of the last change to the address book, from any of its
+    /// views.
+    address_book_status: Option<String>,
+
+    /// The address whose latest transaction is viewed as new ones confirm.
+    follow: Option<Follow>,
+
+    /// Kept while streaming, whichever view is open.
+    watch: Watch,
+    /// The requests derived this session, most recent first.
+    derivations: Vec<(String, Result<Vec<Derived>, String>)>,
 
-    // Useful for Mode::MenuSelection
-    menu: MenuState<AppMenuItem>,
+    /// The entries loaded last and where from, which find also searches.
+    entries_source: String,
+    entry_transactions: Vec<EntryTransaction>,
+    keypairs: KeypairStore,
+    /// Rendering only reads the menu, but its widget takes the state mutably.
+    menu: RefCell<MenuState<AppMenuItem>>,
     endpoint: &'static str,
+    /// The `getTransaction` config of fetches, for this session.
+    fetch_options: FetchOptions,
+    endpoint_health: EndpointHealth,
+    rpc_source: RpcSource,
+    metrics: Metrics,
 }
 
 impl TransactionApp {
+    fn new(settings: Settings, bookmarks: Bookmarks, rpc_source: RpcSource) -> Self {
+        // Say why the settings or bookmarks are not the user's.
+        let load_errors = [
+            settings
+                .load_error()
+                .map(|err| format!("{err}, using the default settings")),
+            bookmarks
+                .load_error()
+                .map(|err| format!("{err}, bookmarks and annotations are not saved")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        let mut app = Self {
+            exit: false,
+            mode: Mode::SignatureEntry,
+            screen: match load_errors.is_empty() {
+                true => Screen::Input,
+                false => Screen::Error(load_errors.join("\n")),
+            },
+            focused: true,
+            pending_events: VecDeque::new(),
+            commands: VecDeque::new(),
+            command_runner: CommandRunner::default(),
+            error_codes: ErrorCodes::new(&settings.error_codes),
+            settings,
+            file_status: None,
+
+            input: Input::new("".to_string()),
+            input_history: InputHistory::default(),
+            input_history_index: None,
+
+            cursor: 0,
+            selection_anchor: None,
+            byte_scroll: 0,
+            bytes_per_line: 1,
+            frame_area: Rect::default(),
+            message_view: false,
+            vote_bytes: false,
+            bookmarks,
+            snapshots: Snapshots::default(),
+            idl_pdas: vec![],
+            pda_labels: None,
+            hook_roles: None,
+            refetching: None,
+            refetch_changes: None,
+            ownership: None,
+            build_statuses: HashMap::new(),
+            security_txts: HashMap::new(),
+            domains: None,
+            reveal: None,
+            presentation: false,
+            view_positions: HashMap::new(),
+            instruction_overlay: false,
+
+            prompt_input: Input::new("".to_string()),
+
+            commitment_timeline: None,
+
+            broadcast: None,
+
+            slot_timings: SlotTimings::default(),
+
+            rpc_history: RpcHistory::default(),
+
+            address_book: AddressBook::default(),
+            address_book_status: None,
+
+            follow: None,
+            watch: Watch::default(),
+
+            derivations: vec![],
+
+            entries_source: String::new(),
+            entry_transactions: vec![],
+
+            keypairs: KeypairStore::default(),
+
+            menu: RefCell::new(menu::menu()),
+            endpoint: DEFAULT_ENDPOINT,
+            fetch_options: FetchOptions::default(),
+            endpoint_health: EndpointHealth::default(),
+            rpc_source,
+            metrics: Metrics::default(),
+        };
+        app.check_endpoint_health(Duration::ZERO);
+        app
+    }
+
     pub fn run(&mut self, terminal: &mut tui::Tui) -> io::Result<()> {
+        let size = terminal.size()?;
+        self.frame_area = Rect::new(0, 0, size.width, size.height);
         while !self.exit {
             terminal.draw(|frame| self.render_frame(frame))?;
-            self.handle_events()?;
+            for message in self.next_messages()? {
+                self.update(message);
+            }
+            while let Some(command) = self.commands.pop_front() {
+                self.command_runner.spawn(command);
+            }
         }
         Ok(())
     }
 
-    fn render_frame(&mut self, frame: &mut Frame) {
-        // Split the frame into sections.
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3),
-                Constraint::Length(3),
-                Constraint::Min(1),
-                Constraint::Length(3),
-            ])
-            .split(frame.area());
+    fn render_frame(&self, frame: &mut Frame) {
+        let chunks = frame_chunks(frame.area());
 
         let title_block = Block::default()
             .borders(Borders::ALL)
             .style(Style::default());
-        let title = Paragraph::new(Text::styled(
-            "Transaction Layout App",
-            Style::default().fg(Color::Green),
-        ))
-        .block(title_block);
+        let mut title_text = "Transaction Layout App".to_string();
+        if !self.keypairs.is_empty() {
+            let signers = self
+                .keypairs
+                .signers()
+                .map(|(pubkey, source)| match source {
+                    KeypairSource::File(path) => format!("{pubkey} ({path})"),
+                    KeypairSource::SeedPhrase => format!("{pubkey} (seed phrase)"),
+                })
+                .collect::<Vec<_>>();
+            title_text = format!("{title_text} | Signers: {}", signers.join(", "));
+        }
+        if let Some(follow) = &self.follow {
+            title_text = format!("{title_text} | {}", follow.summary());
+        }
+        if self.mode.watch_selected().is_some() {
+            let state = if self.watch.stream.paused {
+                format!("paused, {} buffered", self.watch.stream.pending.len())
+            } else {
+                "live".to_string()
+            };
+            title_text = format!(
+                "{title_text} | Watch: {state}, {} dropped",
+                self.watch.stream.num_dropped
+            );
+        }
+        let health_area = title_block.inner(chunks[0]);
+        let title = Paragraph::new(Text::styled(title_text, Style::default().fg(Color::Green)))
+            .block(title_block);
         frame.render_widget(title, chunks[0]);
+        let (health_text, health_color) = match &self.endpoint_health.report {
+            Some(report) if report.is_healthy() => (report.summary(), Color::Green),
+            Some(report) if report.health.is_ok() => (report.summary(), Color::Yellow),
+            Some(report) => (report.summary(), Color::Red),
+            None => ("checking...".to_string(), Color::DarkGray),
+        };
+        let (health_text, health_color) = match self.rpc_source.description() {
+            Some(description) if self.rpc_source.uses_endpoint() => (
+                format!("{} | {health_text} | {description}", self.endpoint),
+                health_color,
+            ),
+            Some(description) => (description, Color::Yellow),
+            None => (format!("{} | {health_text}", self.endpoint), health_color),
+        };
+        let health = Paragraph::new(Text::styled(health_text, Style::default().fg(health_color)))
+            .alignment(Alignment::Right);
+        frame.render_widget(health, health_area);
 
+        let (input, input_title) = match self.mode {
+            Mode::AnnotationEntry => (
+                &self.prompt_input,
+                format!("Annotation for bytes {}", format_range(&self.selection())),
+            ),
+            Mode::DiffSignatureEntry => (
+                &self.prompt_input,
+                "Diff With (signature or explorer link)".to_string(),
+            ),
+            Mode::EditValueEntry(EditField::AccountKey(index)) => {
+                (&self.prompt_input, format!("New Account Key ({index})"))
+            }
+            Mode::EditValueEntry(EditField::InstructionData(index)) => (
+                &self.prompt_input,
+                format!("Instruction ({index}) Data (hex)"),
+            ),
+            Mode::EditValueEntry(EditField::ComputeUnitLimit) => {
+                (&self.prompt_input, "Compute Unit Limit".to_string())
+            }
+            Mode::EditValueEntry(EditField::ComputeUnitPrice) => (
+                &self.prompt_input,
+                "Compute Unit Price (micro-lamports)".to_string(),
+            ),
+            Mode::EditValueEntry(EditField::SchemaFile(index)) => (
+                &self.prompt_input,
+                format!("Schema or IDL File for Instruction ({index})"),
+            ),
+            Mode::KeypairEntry(KeypairPrompt::File) => {
+                (&self.prompt_input, "Keypair File".to_string())
+            }
+            Mode::KeypairEntry(KeypairPrompt::SeedPhrase) => {
+                (&self.prompt_input, "Seed Phrase (hidden)".to_string())
+            }
+            Mode::EntriesFileEntry => (&self.prompt_input, "Entries File".to_string()),
+            Mode::LedgerSlotEntry => (&self.prompt_input, "Ledger Slot".to_string()),
+            Mode::ContactEntry(..) => {
+                let title = match &self.address_book_status {
+                    Some(status) => format!("Contact: {status}"),
+                    None => "Contact (pubkey or .sol domain, name, then #tags)".to_string(),
+                };
+                (&self.prompt_input, title)
+            }
+            Mode::KeypairDirEntry(_) => (&self.prompt_input, "Keypair Directory".to_string()),
+            Mode::TagEntry(..) => (&self.prompt_input, "Tags (space separated)".to_string()),
+            Mode::WatchPatternEntry(_) => (
+                &self.prompt_input,
+                "Watch for a Pubkey or Hex Bytes".to_string(),
+            ),
+            Mode::SubscriptionEntry(_) => {
+                (&self.prompt_input, "Subscribe to an Address".to_string())
+            }
+            Mode::Derivation => (
+                &self.prompt_input,
+                "Derive (program id then seeds, or ata <wallet> <mint>)".to_string(),
+            ),
+            Mode::VarintPlayground => (
+                &self.prompt_input,
+                "Number to encode, or hex bytes to decode".to_string(),
+            ),
+            Mode::Converter => (&self.prompt_input, "Bytes to convert".to_string()),
+            Mode::SignatureCheck => (
+                &self.prompt_input,
+                "Signature [pubkey] [message]".to_string(),
+            ),
+            Mode::RpcConsole { .. } => (
+                &self.prompt_input,
+                "RPC Request (method, then params as JSON)".to_string(),
+            ),
+            Mode::SubmissionTimeEntry => (
+                &self.prompt_input,
+                "Submission Time (RFC 3339 or unix seconds)".to_string(),
+            ),
+            Mode::KeypairEntry(KeypairPrompt::Passphrase(_)) => (
+                &self.prompt_input,
+                "Seed Phrase Passphrase (hidden, optional)".to_string(),
+            ),
+            _ => (&self.input, "Input Signature".to_string()),
+        };
         let width = chunks[1].width.max(3) - 3; // keep 2 for borders and 1 for cursor
-        let scroll = self.input.visual_scroll(width as usize);
-        let input = Paragraph::new(self.input.value())
+        let scroll = input.visual_scroll(width as usize);
+        let input_value = match self.mode {
+            Mode::KeypairEntry(ref prompt) if prompt.is_secret() => {
+                "*".repeat(input.value().chars().count())
+            }
+            _ => input.value().to_string(),
+        };
+        let input_paragraph = Paragraph::new(input_value)
             .style(Style::default().fg(Color::Yellow))
             .scroll((0, scroll as u16))
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Input Signature"),
-            );
-        frame.render_widget(input, chunks[1]);
-        // Make the cursor visible and ask tui-rs to put it at the specified coordinates after rendering
-        frame.set_cursor_position((
-            // Put cursor past the end of the input text
-            chunks[1].x + ((self.input.visual_cursor()).max(scroll) - scroll) as u16 + 1,
-            // Move one line down, from the border to the input line
-            chunks[1].y + 1,
-        ));
-
-        let middle_block_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Length(100), Constraint::Fill(1)])
-            .split(chunks[2]);
-        let bytes_block = Block::default()
-            .borders(Borders::ALL)
-            .padding(Padding::uniform(1))
-            .style(Style::default())
-            .title(match self.signature {
-                SignatureStatus::None => "".to_string(),
-                SignatureStatus::Invalid => "Invalid Signature".to_string(),
-                SignatureStatus::NotFound(signature) => format!("{signature} not found"),
-                SignatureStatus::Found(signature) => format!("{}", signature),
-            });
-
-        let byte_block =
-            TransactionByteBlock::new(&self.transaction_byte_sections).block(bytes_block);
-        frame.render_widget(&byte_block, middle_block_chunks[0]);
+            .block(Block::default().borders(Borders::ALL).title(input_title));
+        frame.render_widget(input_paragraph, chunks[1]);
+        if matches!(
+            self.mode,
+            Mode::SignatureEntry
+                | Mode::AnnotationEntry
+                | Mode::DiffSignatureEntry
+                | Mode::EditValueEntry(_)
+                | Mode::KeypairEntry(_)
+                | Mode::EntriesFileEntry
+                | Mode::LedgerSlotEntry
+                | Mode::SubmissionTimeEntry
+                | Mode::RpcConsole { .. }
+                | Mode::ContactEntry(..)
+                | Mode::KeypairDirEntry(_)
+                | Mode::TagEntry(..)
+                | Mode::WatchPatternEntry(_)
+                | Mode::SubscriptionEntry(_)
+                | Mode::Derivation
+                | Mode::VarintPlayground
+                | Mode::Converter
+                | Mode::SignatureCheck
+        ) {
+            // Make the cursor visible and ask tui-rs to put it at the specified coordinates after rendering
+            frame.set_cursor_position((
+                // Put cursor past the end of the input text
+                chunks[1].x + ((input.visual_cursor()).max(scroll) - scroll) as u16 + 1,
+                // Move one line down, from the border to the input line
+                chunks[1].y + 1,
+            ));
+        }
 
-        let legend_block = Block::default()
-            .borders(Borders::ALL)
-            .padding(Padding::uniform(1))
-            .title("Legend")
-            .style(Style::default());
-        let byte_section_legend =
-            ByteSectionLegend::new(&self.transaction_byte_sections).block(legend_block);
-        frame.render_widget(&byte_section_legend, middle_block_chunks[1]);
+        if let Mode::AltPlan { plan, status, step } = &self.mode {
+            self.render_alt_plan_view(frame, chunks[2], plan.as_deref(), status.as_deref(), *step);
+        } else if let Mode::InnerTransaction { title, sections } = &self.mode {
+            self.render_inner_transaction_view(frame, chunks[2], title, sections);
+        } else if let Mode::CommitmentTimeline = self.mode {
+            self.render_commitment_timeline_view(frame, chunks[2]);
+        } else if let Mode::Broadcast = self.mode {
+            self.render_broadcast_view(frame, chunks[2]);
+        } else if let Mode::Sandwich {
+            mev_context,
+            selected,
+        } = &self.mode
+        {
+            self.render_sandwich_view(frame, chunks[2], mev_context, *selected);
+        } else if let Mode::AccessMatrix(row, column) = self.mode {
+            self.render_access_matrix_view(frame, chunks[2], row, column);
+        } else if let Mode::Related(view) = &self.mode {
+            self.render_related_view(frame, chunks[2], view);
+        } else if let Mode::Leaders(leaders) = &self.mode {
+            self.render_leaders_view(frame, chunks[2], leaders);
+        } else if let Mode::Simulation {
+            simulation,
+            selected,
+            scroll,
+        } = &self.mode
+        {
+            self.render_simulation_view(frame, chunks[2], simulation, *selected, *scroll);
+        } else if let Mode::AddressBook(selected) = self.mode {
+            self.render_address_book_view(frame, chunks[2], selected);
+        } else if let Some(&Mode::AddressBook(selected)) = self.mode.return_mode() {
+            self.render_address_book_view(frame, chunks[2], selected);
+        } else if let Mode::SignatureCheck = self.mode {
+            self.render_signature_check_view(frame, chunks[2]);
+        } else if let Mode::Converter = self.mode {
+            self.render_converter_view(frame, chunks[2]);
+        } else if let Mode::VarintPlayground = self.mode {
+            self.render_varint_playground_view(frame, chunks[2]);
+        } else if let Mode::Derivation = self.mode {
+            self.render_derivation_view(frame, chunks[2]);
+        } else if let Mode::Bookmarks { filter, selected } = &self.mode {
+            self.render_bookmarks_view(frame, chunks[2], filter, *selected);
+        } else if let Mode::Find(find) = &self.mode {
+            self.render_find_view(frame, chunks[2], find);
+        } else if let Some(selected) = self.mode.watch_selected() {
+            self.render_watch_view(frame, chunks[2], selected);
+        } else if let Some(Mode::Bookmarks { filter, selected }) = self.mode.return_mode() {
+            self.render_bookmarks_view(frame, chunks[2], filter, *selected);
+        } else if let Mode::RpcConsole {
+            response, scroll, ..
+        } = &self.mode
+        {
+            self.render_rpc_console_view(frame, chunks[2], response.as_ref(), *scroll);
+        } else if let Mode::SlotTiming(status) = &self.mode {
+            self.render_slot_timing_view(frame, chunks[2], status.as_deref());
+        } else if let Mode::SubmissionTimeEntry = self.mode {
+            self.render_slot_timing_view(frame, chunks[2], None);
+        } else if let Mode::Narration(scroll) = self.mode {
+            self.render_narration_view(frame, chunks[2], scroll);
+        } else if let Mode::InstructionsSysvar(scroll) = self.mode {
+            self.render_instructions_sysvar_view(frame, chunks[2], scroll);
+        } else if let Mode::Preflight { preflight, scroll } = &self.mode {
+            self.render_preflight_view(frame, chunks[2], preflight, *scroll);
+        } else if let Mode::Strings(selected) = self.mode {
+            self.render_strings_view(frame, chunks[2], selected);
+        } else if let Mode::SensitivityMap {
+            sensitivity_map,
+            scroll,
+        } = &self.mode
+        {
+            self.render_sensitivity_map_view(frame, chunks[2], sensitivity_map.as_ref(), *scroll);
+        } else if let Mode::Stats(budget, selected) = self.mode {
+            self.render_stats_view(frame, chunks[2], &budget, selected);
+        } else if let Mode::EntryPicker(selected) = self.mode {
+            self.render_entry_picker(frame, chunks[2], selected);
+        } else {
+            self.render_screen(frame, chunks[2]);
+        }
 
         let footer_block = Block::default()
             .borders(Borders::ALL)
             .style(Style::default());
-        let footer = Paragraph::new(Text::styled(
-            "Press <Esc> to exit",
-            Style::default().fg(Color::Red),
-        ))
-        .block(footer_block);
+        let revealed = self.revealed_sections();
+        let footer = match self.reveal.as_ref().and_then(Reveal::num_revealed) {
+            // Flash the label of the section revealed last.
+            Some(num_revealed) => {
+                let label = revealed
+                    .iter()
+                    .rev()
+                    .find_map(|section| section.label.as_deref())
+                    .unwrap_or_default();
+                let total = self.loaded_sections().len();
+                Text::styled(
+                    format!("{label} ({num_revealed}/{total})"),
+                    Style::default().fg(Color::Yellow),
+                )
+            }
+            None => Text::styled(
+                keybindings::summary(self.mode.context()),
+                Style::default().fg(Color::Red),
+            ),
+        };
+        let footer = Paragraph::new(footer).block(footer_block);
         frame.render_widget(footer, chunks[3]);
 
-        // Render the menu
-        frame.render_stateful_widget(Menu::new(), chunks[0], &mut self.menu)
-    }
-
-    fn handle_events(&mut self) -> io::Result<()> {
-        match event::read()? {
-            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-                self.handle_key_event(key_event)
-            }
-            _ => {}
+        if let Mode::LedgerSigning(ledger_state) = self.mode.underlying() {
+            render_ledger_popup(frame, ledger_state, self.settings.monochrome);
+        }
+        if let (
+            &Mode::Template(index),
+            Screen::Edit {
+                template_form: Some(template_form),
+                ..
+            },
+        ) = (&self.mode, &self.screen)
+        {
+            render_template_popup(frame, template_form, index);
         }
 
-        Ok(())
-    }
+        // Render the menu
+        frame.render_stateful_widget(Menu::new(), chunks[0], &mut self.menu.borrow_mut());
 
-    fn handle_key_event(&mut self, key_event: KeyEvent) {
-        match self.mode {
-            Mode::SignatureEntry => self.signature_entry_handle_key_event(key_event),
-            Mode::MenuSelection => self.menu_selection_handle_key_event(key_event),
+        if let Mode::CommandPalette(palette) = &self.mode {
+            self.render_command_palette(frame, palette);
         }
-    }
-
-    fn signature_entry_handle_key_event(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Esc => self.exit(),
-            KeyCode::Enter => self.on_signature_entry(),
-            KeyCode::Up => {
-                self.mode = Mode::MenuSelection;
-                self.menu.activate()
-            }
-            _ => {
-                self.input.handle_event(&Event::Key(key_event));
-            }
+        if let Mode::FetchOptions(selected) = self.mode {
+            render_fetch_options_popup(
+                frame,
+                &self.fetch_options,
+                selected,
+                self.settings.monochrome,
+            );
+        }
+        if let (Mode::ConfirmRefetch, Some(loaded)) = (&self.mode, self.loaded()) {
+            render_refetch_popup(frame, &loaded.signature, self.endpoint);
+        }
+        if let Mode::Help(help) = &self.mode {
+            // Keep the footer visible below the help.
+            render_help(frame, chunks[0].union(chunks[2]), help);
         }
     }
 
-    fn menu_selection_handle_key_event(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Esc => {
-                self.mode = Mode::SignatureEntry;
-                self.menu.reset();
-            }
-            KeyCode::Enter => self.menu.select(),
-            KeyCode::Up => self.menu.up(),
-            KeyCode::Down => self.menu.down(),
-            KeyCode::Left => self.menu.left(),
-            KeyCode::Right => self.menu.right(),
-            _ => {}
-        }
+    fn render_command_palette(&self, frame: &mut Frame, palette: &Palette) {
+        let area = centered_rect(100, 24, frame.area());
+        frame.render_widget(Clear, area);
+        let palette_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(1)])
+            .split(area);
+        let search = Paragraph::new(palette.query.value())
+            .style(Style::default().fg(Color::Yellow))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Command Palette"),
+            );
+        frame.render_widget(search, palette_chunks[0]);
+        frame.set_cursor_position((
+            palette_chunks[0].x + palette.query.visual_cursor() as u16 + 1,
+            palette_chunks[0].y + 1,
+        ));
 
-        for event in self.menu.drain_events() {
-            match event {
-                MenuEvent::Selected(item) => match item {
-                    AppMenuItem::Endpoint(endpoint) => {
-                        self.endpoint = endpoint;
-                        self.mode = Mode::SignatureEntry;
-                        self.menu.reset();
+        let items = palette
+            .commands()
+            .into_iter()
+            .map(|(label, command)| {
+                let (key, style) = match command {
+                    PaletteCommand::Key(binding) if palette.is_available(binding) => {
+                        (binding.key_name(), Style::default())
                     }
-                },
-            }
+                    PaletteCommand::Key(binding) => {
+                        (binding.key_name(), Style::default().fg(Color::DarkGray))
+                    }
+                    PaletteCommand::Menu(_) => ("menu".to_string(), Style::default()),
+                };
+                Line::styled(format!("{label:<60} {key}"), style)
+            })
+            .collect::<Vec<_>>();
+        let mut list_block = Block::default().borders(Borders::ALL);
+        if let Some(status) = &palette.status {
+            list_block = list_block.title_bottom(status.as_str());
         }
+        let list = List::new(items)
+            .block(list_block)
+            .highlight_style(highlight_style(self.settings.monochrome));
+        frame.render_stateful_widget(
+            list,
+            palette_chunks[1],
+            &mut ListState::default().with_selected(Some(palette.selected)),
+        );
     }
 
-    fn exit(&mut self) {
-        self.exit = true;
+    /// Render the main area for the current screen.
+    fn render_screen(&self, frame: &mut Frame, area: Rect) {
+        let (text, color) = match &self.screen {
+            Screen::Viewing(_) => return self.render_transaction_view(frame, area),
+            Screen::Edit { .. } => return self.render_edit_view(frame, area),
+            Screen::Diff {
+                loaded,
+                compared,
+                byte_scroll,
+            } => return self.render_diff_view(frame, area, loaded, compared, *byte_scroll),
+            Screen::Input => (
+                "Enter a transaction signature or explorer link, or paste a transaction as hex"
+                    .to_string(),
+                Color::DarkGray,
+            ),
+            Screen::Loading(signature) => (format!("Loading {signature}..."), Color::Yellow),
+            Screen::NotFound(signature) => (
+                format!(
+                    "{signature} not found\n\nIf it was just sent, Ctrl+W waits for it to land"
+                ),
+                Color::Red,
+            ),
+            Screen::Landing(landing) => (landing.summary(), Color::Yellow),
+            Screen::Error(err) => (err.clone(), Color::Red),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::uniform(1));
+        let paragraph = Paragraph::new(Text::styled(text, Style::default().fg(color)))
+            .wrap(Wrap { trim: false })
+            .block(block);
+        frame.render_widget(paragraph, area);
     }
 
-    fn on_signature_entry(&mut self) {
-        self.signature = SignatureStatus::None;
-        self.transaction_byte_sections.clear();
+    /// Split the transaction view into the bytes and legend columns.
+    fn transaction_view_columns(&self, area: Rect) -> Rc<[Rect]> {
+        // The legend and details get no room in presentation mode.
+        let legend_width = if self.presentation {
+            Constraint::Length(0)
+        } else {
+            Constraint::Fill(1)
+        };
+        let bytes_width = if self.presentation {
+            Constraint::Fill(1)
+        } else {
+            Constraint::Length(100)
+        };
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([bytes_width, legend_width])
+            .split(area)
+    }
 
-        let text = self.input.value();
-        let maybe_signature = Signature::from_str(text);
-        self.input.reset(); // Clear the input field
+    /// Split the bytes column into the byte grid and its tooltip.
+    fn transaction_bytes_chunks(&self, column: Rect) -> Rc<[Rect]> {
+        let tooltip_height = if self.presentation { 0 } else { 1 };
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Fill(1), Constraint::Length(tooltip_height)])
+            .split(column)
+    }
 
-        let Ok(signature) = maybe_signature else {
-            self.signature = SignatureStatus::Invalid;
-            return;
-        };
+    fn transaction_zoom(&self) -> GridZoom {
+        if self.presentation {
+            GridZoom::Large
+        } else {
+            self.settings.zoom.into()
+        }
+    }
 
-        // Create client and get transaction details
-        let client = RpcClient::new(self.endpoint);
-        let config = RpcTransactionConfig {
-            encoding: Some(UiTransactionEncoding::Binary),
-            commitment: Some(CommitmentConfig::confirmed()),
-            max_supported_transaction_version: Some(0),
-        };
-        let Ok(transaction) = client.get_transaction_with_config(&signature, config) else {
-            self.signature = SignatureStatus::NotFound(signature);
+    fn render_transaction_view(&self, frame: &mut Frame, area: Rect) {
+        let Some(loaded) = self.loaded() else {
             return;
         };
-
-        self.signature = SignatureStatus::Found(signature);
-        if let Some(transaction) = transaction.transaction.transaction.decode() {
-            get_transaction_byte_sections(&transaction, &mut self.transaction_byte_sections);
+        let middle_block_chunks = self.transaction_view_columns(area);
+        let view_start = self.view_sections(&loaded.sections).0;
+        let mut title = loaded.signature.to_string();
+        if self.bookmarks.is_bookmarked(&loaded.signature) {
+            title = format!("{title} [bookmarked]");
         }
-    }
+        if let Some(slot) = loaded.slot {
+            title = format!("{title} | slot {}", format::integer(&self.settings, slot));
+        }
+        if let Some(block_time) = loaded.block_time {
+            title = format!(
+                "{title} at {}",
+                format::timestamp(&self.settings, block_time)
+            );
+        }
+        if let Some(meta) = &loaded.meta {
+            title = format!(
+                "{title} | fee {}",
+                format::lamports(&self.settings, meta.fee)
+            );
+        }
+        // The error, named if it is a known custom error, shown below the
+        // bytes as the title has no room left for it.
+        let failure = loaded
+            .meta
+            .as_ref()
+            .and_then(|meta| Some(self.describe_error(meta.err.as_ref()?)));
+        if failure.is_some() {
+            title = format!("{title} | failed");
+        }
+        if let Some(source) = &loaded.source {
+            title = format!("{title} | {source}");
+        }
+        if let Some(endpoint) = loaded.endpoint {
+            title = format!("{title} | from {endpoint}");
+            if endpoint != self.endpoint {
+                title = format!("{title} (not the current endpoint)");
+            }
+        }
+        if let Some(fetch_options) = &loaded.fetch_options {
+            title = format!("{title} | {}", fetch_options.summary());
+        }
+        if self.message_view {
+            let sections = self.view_sections(&loaded.sections).1;
+            title = format!(
+                "{title} | {}",
+                message_summary(&self.settings, &loaded.transaction, sections)
+            );
+        }
+        let legend_block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::uniform(1))
+            .title(format!(
+                "Legend | {} | colored {}",
+                self.settings.legend_order.name(),
+                self.settings.coloring.name()
+            ))
+            .style(Style::default());
+        let displayed = self.displayed_sections();
+        let byte_section_legend = self.transaction_legend(&displayed).block(legend_block);
+        let detail_lines = if self.is_navigating_bytes() {
+            self.detail_lines(&loaded.signature)
+        } else {
+            vec![]
+        };
+        let details_height = match detail_lines.len() {
+            0 => 0,
+            // Borders and padding.
+            num_lines => num_lines as u16 + 4,
+        };
+        let refetch_changes = self
+            .refetch_changes
+            .as_ref()
+            .filter(|(signature, _)| *signature == loaded.signature)
+            .map(|(_, changes)| changes.as_slice());
+        let changes_height = refetch_changes.map_or(0, |changes| changes.len().max(1) as u16 + 2);
+        let legend_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Length(changes_height),
+                Constraint::Length(details_height),
+            ])
+            .split(middle_block_chunks[1]);
+        frame.render_widget(&byte_section_legend, legend_chunks[0]);
+        if let Some(changes) = refetch_changes {
+            let mut lines = changes
+                .iter()
+                .map(|change| {
+                    let color = match change.kind() {
+                        _ if self.settings.monochrome => Color::Reset,
+                        ChangeKind::Added => Color::Green,
+                        ChangeKind::Removed => Color::Red,
+                        ChangeKind::Moved => Color::Cyan,
+                        ChangeKind::Modified => Color::Yellow,
+                    };
+                    Line::styled(change.to_string(), Style::default().fg(color))
+                })
+                .collect::<Vec<_>>();
+            if lines.is_empty() {
+                lines.push(Line::styled(
+                    "Nothing changed",
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .padding(Padding::horizontal(1))
+                .title("Changed since the last fetch");
+            frame.render_widget(List::new(lines).block(block), legend_chunks[1]);
+        }
+        if !detail_lines.is_empty() {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .padding(Padding::uniform(1))
+                .title("Details");
+            let details = Paragraph::new(detail_lines)
+                .wrap(Wrap { trim: false })
+                .block(block);
+            frame.render_widget(details, legend_chunks[2]);
+        }
+
+        if let Some(vote_summary) =
+            VoteSummary::of(&loaded.transaction).filter(|_| !self.vote_bytes)
+        {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .padding(Padding::uniform(1))
+                .title(title)
+                .title_bottom("<V> show bytes");
+            let summary = Paragraph::new(self.vote_summary_lines(&vote_summary))
+                .wrap(Wrap { trim: false })
+                .block(block);
+            frame.render_widget(summary, middle_block_chunks[0]);
+            return;
+        }
+
+        let bytes_chunks = self.transaction_bytes_chunks(middle_block_chunks[0]);
+        let num_bytes = self.revealed_num_bytes();
+        let zoom = self.transaction_zoom();
+        let mut bytes_block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::uniform(1))
+            .style(Style::default())
+            .title(title);
+        if let Some(failure) = failure {
+            let style = if self.settings.monochrome {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Red)
+            };
+            bytes_block = bytes_block
+                .title_bottom(Line::styled(format!("failed: {failure}"), style).right_aligned());
+        }
+        let bytes_inner = bytes_block.inner(bytes_chunks[0]);
+        match zoom {
+            GridZoom::Hex | GridZoom::Large => {}
+            GridZoom::Compact => {
+                bytes_block = bytes_block.title(Line::from("compact").right_aligned())
+            }
+            GridZoom::Overview => {
+                let bytes_per_cell = TransactionByteBlock::bytes_per_cell(
+                    zoom,
+                    bytes_inner.width,
+                    bytes_inner.height,
+                    num_bytes,
+                );
+                bytes_block = bytes_block.title(
+                    Line::from(format!("overview | {bytes_per_cell} B per cell")).right_aligned(),
+                );
+            }
+        }
+        let displayed = self.displayed_sections();
+        let sections = self.view_sections(&displayed).1;
+
+        let annotations = self.annotations();
+        if self.is_navigating_bytes() {
+            let notes = annotations
+                .iter()
+                .filter(|annotation| annotation.contains(self.cursor))
+                .map(|annotation| annotation.note.as_str())
+                .collect::<Vec<_>>();
+            let decoded = self.decode_instruction_at_cursor().map(|decoded| {
+                let text = format!("{}: {}", decoded.program, decoded.name);
+                match self.decoded_part_at_cursor(&decoded) {
+                    Some(part) => format!("{text} • {part}"),
+                    None => text,
+                }
+            });
+            let bottom_title = notes
+                .into_iter()
+                .chain(decoded.as_deref())
+                .chain(self.file_status.as_deref())
+                .collect::<Vec<_>>();
+            if !bottom_title.is_empty() {
+                bytes_block = bytes_block.title_bottom(bottom_title.join(" | "));
+            }
+            self.render_tooltip(frame, bytes_chunks[1]);
+        }
+
+        let mut rule_matches = self.decoded_field_styles();
+        rule_matches.extend(highlight_rules::matches(
+            &self.settings.highlight_rules,
+            self.loaded_sections(),
+        ));
+        let failed_instruction = Vec::from_iter(self.failed_instruction_bytes());
+        let byte_block = TransactionByteBlock::new(sections)
+            .block(bytes_block)
+            .base_offset(view_start)
+            .annotations(annotations)
+            .highlights(&failed_instruction)
+            .rule_matches(&rule_matches)
+            .scroll(self.byte_scroll)
+            .cursor(self.is_navigating_bytes().then_some(self.cursor))
+            .selection(self.selection_anchor.map(|_| self.selection()))
+            .monochrome(self.settings.monochrome)
+            .zoom(zoom)
+            .instruction_overlay(self.instruction_overlay);
+        frame.render_widget(&byte_block, bytes_chunks[0]);
+
+        // The minimap takes the right padding of the grid, next to its lines.
+        let visible_lines = TransactionByteBlock::visible_lines(zoom, bytes_inner.height);
+        let viewport_start = self.byte_scroll * self.bytes_per_line;
+        let minimap = Minimap::new(sections)
+            .viewport(viewport_start..viewport_start + visible_lines * self.bytes_per_line)
+            .monochrome(self.settings.monochrome);
+        let minimap_area = Rect::new(
+            bytes_inner.right(),
+            bytes_inner.y + NUM_HEADER_LINES,
+            1,
+            bytes_inner.height.saturating_sub(NUM_HEADER_LINES),
+        )
+        .intersection(bytes_chunks[0]);
+        frame.render_widget(&minimap, minimap_area);
+    }
+
+    /// The details of the account key under the cursor: its ownership chain,
+    /// i.e. its owner and the deployment of the program which owns it or
+    /// which it is, and the security.txt of programs.
+    fn detail_lines(&self, signature: &Signature) -> Vec<Line<'static>> {
+        let Some(pubkey) =
+            section_at(self.cursor_sections(), self.cursor).and_then(|section| section.pubkey())
+        else {
+            return vec![];
+        };
+        let chain = match &self.ownership {
+            Some((resolved, Ok(ownership))) if resolved == signature => {
+                ownership.chain(&pubkey).unwrap_or_default()
+            }
+            Some((resolved, Err(err))) if resolved == signature => {
+                return vec![Line::raw(err.clone())]
+            }
+            _ => vec![],
+        };
+        let name = |pubkey: &Pubkey| format::pubkey(&self.settings, &self.address_book, pubkey);
+        let links = chain.iter().enumerate().map(|(depth, link)| {
+            let text = match link {
+                Link::Missing => "does not exist".to_string(),
+                Link::Owner(owner) => format!("owned by {}", name(owner)),
+                Link::ProgramData { address, slot } => format!(
+                    "programdata {} deployed at slot {}",
+                    name(address),
+                    format::integer(&self.settings, *slot)
+                ),
+                Link::UpgradeAuthority(Some(authority)) => {
+                    format!("upgrade authority {}", name(authority))
+                }
+                Link::UpgradeAuthority(None) => "immutable, no upgrade authority".to_string(),
+            };
+            Line::raw(format!("{}└ {text}", "  ".repeat(depth)))
+        });
+        let security_txt = match self.security_txts.get(&pubkey) {
+            Some(Ok(Some(security_txt))) => std::iter::once("security.txt".to_string())
+                .chain(
+                    security_txt
+                        .short_fields()
+                        .map(|(key, value)| format!("  {key}: {value}")),
+                )
+                .collect(),
+            Some(Ok(None)) => vec!["no security.txt".to_string()],
+            Some(Err(err)) => vec![err.clone()],
+            None => vec![],
+        };
+        let domain = self
+            .loaded_domains()
+            .and_then(|domains| domains.get(&pubkey))
+            .map(|domain| Line::raw(format!("domain {domain}")));
+        if chain.is_empty() && security_txt.is_empty() && domain.is_none() {
+            return vec![];
+        }
+        std::iter::once(Line::raw(name(&pubkey)))
+            .chain(domain)
+            .chain(links)
+            .chain(security_txt.into_iter().map(Line::raw))
+            .collect()
+    }
+
+    fn vote_summary_lines(&self, vote_summary: &VoteSummary) -> Vec<Line<'static>> {
+        let slot = |slot: u64| format::integer(&self.settings, slot);
+        let pubkey = |pubkey: Option<Pubkey>| {
+            pubkey.map_or_else(
+                || "missing".to_string(),
+                |pubkey| format::pubkey(&self.settings, &self.address_book, &pubkey),
+            )
+        };
+        let slots = match (vote_summary.slots.first(), vote_summary.slots.last()) {
+            (Some(first), Some(last)) => format!(
+                "{} - {} ({} slots)",
+                slot(*first),
+                slot(*last),
+                vote_summary.slots.len()
+            ),
+            _ => "none".to_string(),
+        };
+        let mut fields = vec![
+            ("Instruction", vote_summary.instruction.to_string()),
+            ("Vote account", pubkey(vote_summary.vote_account)),
+            ("Authority", pubkey(vote_summary.authority)),
+            ("Voted slots", slots),
+            ("Root", vote_summary.root.map_or("none".to_string(), slot)),
+            ("Bank hash", vote_summary.hash.to_string()),
+            (
+                "Timestamp",
+                vote_summary
+                    .timestamp
+                    .map_or("none".to_string(), |timestamp| {
+                        format::timestamp(&self.settings, timestamp)
+                    }),
+            ),
+        ];
+        if let Some(switch_hash) = vote_summary.switch_hash {
+            fields.push(("Switched from", switch_hash.to_string()));
+        }
+        fields
+            .into_iter()
+            .map(|(name, value)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{name:<14}"),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(value),
+                ])
+            })
+            .collect()
+    }
+
+    fn render_commitment_timeline_view(&self, frame: &mut Frame, area: Rect) {
+        let Some(commitment_timeline) = &self.commitment_timeline else {
+            return;
+        };
+        let state = if commitment_timeline.is_done() {
+            "done"
+        } else {
+            "polling"
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .title(format!(
+                "Commitment Timeline: {} ({state})",
+                commitment_timeline.signature
+            ));
+        let entries = commitment_timeline
+            .entries
+            .iter()
+            .map(|entry| format!("+{:>7.2}s  {}", entry.elapsed.as_secs_f64(), entry.event))
+            .collect::<Vec<_>>();
+        frame.render_widget(List::new(entries).block(block), area);
+    }
+
+    fn render_broadcast_view(&self, frame: &mut Frame, area: Rect) {
+        let Some(broadcast) = &self.broadcast else {
+            return;
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .title(format!(
+                "Broadcast: {} ({}/{} done)",
+                broadcast.signature,
+                broadcast.num_done(),
+                broadcast.outcomes.len()
+            ));
+        let width = broadcast
+            .outcomes
+            .iter()
+            .map(|(endpoint, _)| endpoint.len())
+            .max()
+            .unwrap_or_default();
+        let lines = broadcast
+            .outcomes
+            .iter()
+            .map(|(endpoint, outcome)| format!("{endpoint:<width$}  {}", outcome.summary()))
+            .collect::<Vec<_>>();
+        frame.render_widget(List::new(lines).block(block), area);
+    }
+
+    fn render_sandwich_view(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        mev_context: &Option<Result<MevContext, String>>,
+        selected: usize,
+    ) {
+        let block = Block::default().borders(Borders::ALL);
+        let context = match mev_context {
+            None => {
+                let fetching =
+                    Paragraph::new("Fetching the block...").block(block.title("MEV Context"));
+                frame.render_widget(fetching, area);
+                return;
+            }
+            Some(Err(err)) => {
+                let error = Paragraph::new(err.as_str())
+                    .style(Style::default().fg(Color::Red))
+                    .block(block.title("MEV Context"));
+                frame.render_widget(error, area);
+                return;
+            }
+            Some(Ok(context)) => context,
+        };
+        let pubkey = |pubkey: &Pubkey| format::pubkey(&self.settings, &self.address_book, pubkey);
+        let mut title = format!(
+            "Transactions within {} of the transaction in slot {} writing its accounts ({})",
+            sandwich::NUM_NEIGHBORS,
+            format::integer(&self.settings, context.slot),
+            context.neighbors.len()
+        );
+        if !context.sandwichers.is_empty() {
+            let sandwichers = context
+                .sandwichers
+                .iter()
+                .map(pubkey)
+                .collect::<Vec<_>>()
+                .join(", ");
+            title = format!("{title} | possibly sandwiched by {sandwichers}");
+        }
+        let sandwich_style = if self.settings.monochrome {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Red)
+        };
+        let neighbors = context
+            .neighbors
+            .iter()
+            .map(|neighbor| {
+                let shared = neighbor
+                    .shared
+                    .iter()
+                    .map(pubkey)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut spans = vec![Span::raw(format!(
+                    "{:+}  {}  payer {}  writes {shared}",
+                    neighbor.position,
+                    neighbor.signature,
+                    pubkey(&neighbor.fee_payer)
+                ))];
+                if neighbor.failed {
+                    spans.push(Span::raw(" (failed)"));
+                }
+                if neighbor.sandwiching {
+                    let side = if neighbor.position < 0 {
+                        "front"
+                    } else {
+                        "back"
+                    };
+                    spans.push(Span::styled(format!(" sandwich {side}"), sandwich_style));
+                }
+                Line::from(spans)
+            })
+            .collect::<Vec<_>>();
+        let neighbors = List::new(neighbors)
+            .block(block.title(title))
+            .highlight_style(highlight_style(self.settings.monochrome));
+        frame.render_stateful_widget(
+            neighbors,
+            area,
+            &mut ListState::default().with_selected(Some(selected)),
+        );
+    }
+
+    fn render_access_matrix_view(&self, frame: &mut Frame, area: Rect, row: usize, column: usize) {
+        let block = Block::default().borders(Borders::ALL).title(
+            "Access matrix | W write, R read, S signer, P program, runtime accounts highlighted | \
+             Enter to jump to the index byte",
+        );
+        let Some(matrix) = self.access_matrix() else {
+            frame.render_widget(Paragraph::new("No transaction loaded").block(block), area);
+            return;
+        };
+        const CELL_WIDTH: usize = 4;
+        let account_keys = self.account_keys();
+        let labels = account_keys
+            .iter()
+            .map(|pubkey| format::pubkey(&self.settings, &self.address_book, pubkey))
+            .collect::<Vec<_>>();
+        let label_width = labels
+            .iter()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0);
+        let label_width = label_width.clamp(8, 32);
+
+        // Scroll so the selected cell stays visible.
+        let inner_width = usize::from(area.width.saturating_sub(2));
+        let num_columns = (inner_width.saturating_sub(label_width + 1) / CELL_WIDTH).max(1);
+        let first_column = (column + 1).saturating_sub(num_columns);
+        let columns = first_column..(first_column + num_columns).min(matrix.num_instructions());
+        let num_rows = usize::from(area.height.saturating_sub(3)).max(1);
+        let first_row = (row + 1).saturating_sub(num_rows);
+
+        let header = columns
+            .clone()
+            .map(|index| format!("{:<CELL_WIDTH$}", format!("#{index}")))
+            .collect::<String>();
+        let mut lines = vec![Line::from(format!("{:label_width$} {header}", ""))];
+        let selected_style = highlight_style(self.settings.monochrome);
+        let runtime_style = if self.settings.monochrome {
+            Style::default().add_modifier(Modifier::ITALIC)
+        } else {
+            Style::default().fg(Color::Magenta)
+        };
+        for (index, cells) in matrix
+            .rows
+            .iter()
+            .enumerate()
+            .skip(first_row)
+            .take(num_rows)
+        {
+            let label: String = labels[index].chars().take(label_width).collect();
+            let label = format!("{label:label_width$} ");
+            let mut spans = vec![match runtime_accounts::classify(&account_keys[index]) {
+                Some(_) => Span::styled(label, runtime_style),
+                None => Span::raw(label),
+            }];
+            for (cell_column, cell) in cells
+                .iter()
+                .enumerate()
+                .take(columns.end)
+                .skip(columns.start)
+            {
+                let symbol = cell.map_or("·", |cell| cell.symbol());
+                let text = format!("{symbol:<CELL_WIDTH$}");
+                spans.push(if (index, cell_column) == (row, column) {
+                    Span::styled(text, selected_style)
+                } else {
+                    Span::raw(text)
+                });
+            }
+            lines.push(Line::from(spans));
+        }
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn render_related_view(&self, frame: &mut Frame, area: Rect, view: &RelatedView) {
+        let block = Block::default().borders(Borders::ALL);
+        let related = match &view.related {
+            None => {
+                let fetching = Paragraph::new(format!(
+                    "Fetching transactions within {} slots...",
+                    view.window
+                ))
+                .block(block.title("Related Transactions"));
+                frame.render_widget(fetching, area);
+                return;
+            }
+            Some(Err(err)) => {
+                let error = Paragraph::new(err.as_str())
+                    .style(Style::default().fg(Color::Red))
+                    .block(block.title("Related Transactions"));
+                frame.render_widget(error, area);
+                return;
+            }
+            Some(Ok(related)) => related,
+        };
+        let slot = |slot: u64| format::integer(&self.settings, slot);
+        let mut title = format!(
+            "Transactions touching {} in slots {} to {} ({})",
+            format::pubkey(&self.settings, &self.address_book, &related.address),
+            slot(related.slot.saturating_sub(related.window)),
+            slot(related.slot + related.window),
+            related.candidates.len()
+        );
+        if related.truncated {
+            title = format!("{title}, stopped paging before the first slot");
+        }
+        let name = |pubkey: &Pubkey| self.account_name(pubkey);
+        let error_name = |program: &Pubkey, code| self.error_name(program, code);
+        if view.filter != FailureFilter::All {
+            title = format!(
+                "{title} | showing {}",
+                view.filter.describe(name, error_name)
+            );
+        }
+        let area = self.render_failure_groups(
+            frame,
+            area,
+            &view.filter,
+            related
+                .candidates
+                .iter()
+                .filter_map(related::Candidate::failure),
+        );
+        let viewed = self.current_signature();
+        let failed_style = Style::default().fg(Color::Red);
+        let candidates = view
+            .visible()
+            .into_iter()
+            .map(|candidate| {
+                let mut spans = vec![Span::raw(format!(
+                    "slot {}  {}",
+                    slot(candidate.slot),
+                    candidate.signature
+                ))];
+                if let Some(failure) = candidate.failure() {
+                    spans.push(Span::styled(
+                        format!(" failed: {}", failure.describe(name, error_name)),
+                        failed_style,
+                    ));
+                }
+                if Some(candidate.signature) == viewed {
+                    spans.push(Span::raw(" (viewed)"));
+                }
+                Line::from(spans)
+            })
+            .collect::<Vec<_>>();
+        let candidates = List::new(candidates)
+            .block(block.title(title))
+            .highlight_style(highlight_style(self.settings.monochrome));
+        frame.render_stateful_widget(
+            candidates,
+            area,
+            &mut ListState::default().with_selected(Some(view.selected)),
+        );
+    }
+
+    fn render_leaders_view(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        leaders: &Option<Result<Leaders, String>>,
+    ) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1));
+        let leaders = match leaders {
+            None => {
+                let fetching = Paragraph::new("Fetching leaders...").block(block.title("Leaders"));
+                frame.render_widget(fetching, area);
+                return;
+            }
+            Some(Err(err)) => {
+                let error = Paragraph::new(err.as_str())
+                    .style(Style::default().fg(Color::Red))
+                    .block(block.title("Leaders"));
+                frame.render_widget(error, area);
+                return;
+            }
+            Some(Ok(leaders)) => leaders,
+        };
+        let slot = |slot: u64| format::integer(&self.settings, slot);
+        let pubkey = |pubkey: &Pubkey| format::pubkey(&self.settings, &self.address_book, pubkey);
+        let identity = |identity: &Pubkey| match leaders.vote_account(identity) {
+            Some(vote_account) => format!("{} (vote {})", pubkey(identity), pubkey(vote_account)),
+            None => pubkey(identity),
+        };
+
+        let mut lines = vec![];
+        if let Some((landed_slot, leader)) = &leaders.landed {
+            let leader = match leader {
+                Ok(leader) => identity(leader),
+                Err(err) => err.clone(),
+            };
+            lines.push(Line::raw(format!(
+                "Landed in slot {}, {} slots ago, led by {leader}",
+                slot(*landed_slot),
+                slot(leaders.current_slot.saturating_sub(*landed_slot)),
+            )));
+            lines.push(Line::raw(""));
+        }
+        lines.extend(leaders.upcoming.iter().map(|leader| {
+            let line = format!(
+                "{:>13} - {:<13} {}",
+                slot(leader.first_slot),
+                slot(leader.last_slot),
+                identity(&leader.identity)
+            );
+            if leader.first_slot == leaders.current_slot {
+                Line::styled(line, Style::default().add_modifier(Modifier::BOLD))
+            } else {
+                Line::raw(line)
+            }
+        }));
+        let title = format!(
+            "Leaders from the current slot {}",
+            slot(leaders.current_slot)
+        );
+        frame.render_widget(Paragraph::new(lines).block(block.title(title)), area);
+    }
+
+    fn render_simulation_view(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        simulation: &Option<Result<Simulation, String>>,
+        selected: usize,
+        scroll: usize,
+    ) {
+        let block = Block::default().borders(Borders::ALL);
+        let simulation = match simulation {
+            None => {
+                frame.render_widget(Paragraph::new("Simulating...").block(block), area);
+                return;
+            }
+            Some(Err(err)) => {
+                let error = Paragraph::new(err.as_str())
+                    .style(Style::default().fg(Color::Red))
+                    .wrap(Wrap { trim: false })
+                    .block(block.title("Simulation"));
+                frame.render_widget(error, area);
+                return;
+            }
+            Some(Ok(simulation)) => simulation,
+        };
+        let mut title = match &simulation.err {
+            Some(err) => format!("Simulation failed: {err}"),
+            None => "Simulation succeeded".to_string(),
+        };
+        if let Some(units_consumed) = simulation.units_consumed {
+            title = format!(
+                "{title} | {} CUs",
+                format::integer(&self.settings, units_consumed)
+            );
+        }
+        let simulation_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(simulation.accounts.len().clamp(1, 8) as u16 + 2),
+                Constraint::Min(1),
+                Constraint::Length(8),
+            ])
+            .split(area);
+
+        let accounts = simulation
+            .accounts
+            .iter()
+            .map(|change| {
+                let state = match (&change.before, &change.after) {
+                    (_, _) if !change.is_changed() => "unchanged",
+                    (None, Some(_)) => "created",
+                    (Some(_), None) => "closed",
+                    _ => "changed",
+                };
+                format!(
+                    "{} {state}",
+                    format::pubkey(&self.settings, &self.address_book, &change.pubkey)
+                )
+            })
+            .collect::<Vec<_>>();
+        let accounts = List::new(accounts)
+            .block(block.title(title))
+            .highlight_style(highlight_style(self.settings.monochrome));
+        frame.render_stateful_widget(
+            accounts,
+            simulation_chunks[0],
+            &mut ListState::default().with_selected(Some(selected)),
+        );
+
+        if let Some(change) = simulation.accounts.get(selected) {
+            let data_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(simulation_chunks[1]);
+            let data = |account: &Option<Account>| {
+                account
+                    .as_ref()
+                    .map(|account| account.data.clone())
+                    .unwrap_or_default()
+            };
+            let (before, after) = (data(&change.before), data(&change.after));
+            let panes = [
+                (
+                    "Before",
+                    &change.before,
+                    &before,
+                    edit::differing_ranges(&after, &before),
+                ),
+                (
+                    "After",
+                    &change.after,
+                    &after,
+                    edit::differing_ranges(&before, &after),
+                ),
+            ];
+            for (area, (name, account, data, highlights)) in data_chunks.iter().zip(panes) {
+                let title = match account {
+                    Some(account) => format!(
+                        "{name}: {}, owner {}, {}",
+                        format::lamports(&self.settings, account.lamports),
+                        format::pubkey(&self.settings, &self.address_book, &account.owner),
+                        format::bytes(&self.settings, account.data.len())
+                    ),
+                    None => format!("{name}: no account"),
+                };
+                // The byte block only uses the bytes and color of sections.
+                let sections = [TransactionByteSection {
+                    label: None,
+                    bytes: data.clone(),
+                    color: Color::Reset,
+                    kind: SectionKind::InstructionData(0),
+                }];
+                let byte_block = TransactionByteBlock::new(&sections)
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .padding(Padding::horizontal(1))
+                            .title(title),
+                    )
+                    .highlights(&highlights)
+                    .scroll(scroll)
+                    .monochrome(self.settings.monochrome);
+                frame.render_widget(&byte_block, *area);
+            }
+        }
+
+        let logs = Paragraph::new(simulation.logs.join("\n"))
+            .scroll((
+                simulation
+                    .logs
+                    .len()
+                    .saturating_sub(simulation_chunks[2].height as usize - 2)
+                    as u16,
+                0,
+            ))
+            .block(Block::default().borders(Borders::ALL).title("Logs"));
+        frame.render_widget(logs, simulation_chunks[2]);
+    }
+
+    fn render_varint_playground_view(&self, frame: &mut Frame, area: Rect) {
+        render_utility_view(
+            frame,
+            area,
+            "Compact-u16 and Varint Playground",
+            varint::evaluate(self.prompt_input.value()),
+            "Type a decimal number to see its encodings, or hex bytes to decode them.",
+        );
+    }
+
+    fn render_signature_check_view(&self, frame: &mut Frame, area: Rect) {
+        let input = self.prompt_input.value();
+        let lines = if input.trim().is_empty() {
+            vec![]
+        } else {
+            let transaction = self.loaded().map(|loaded| &loaded.transaction);
+            signature_check::verify(input, transaction).unwrap_or_else(|err| vec![err])
+        };
+        render_utility_view(
+            frame,
+            area,
+            "Signature Verification",
+            lines,
+            "Type a signature, with a pubkey and a message (0x hex or base64) to use instead of the viewed transaction's.",
+        );
+    }
+
+    fn render_converter_view(&self, frame: &mut Frame, area: Rect) {
+        render_utility_view(
+            frame,
+            area,
+            "Converter",
+            converter::evaluate(self.prompt_input.value()),
+            "Type base58, base64, hex or a byte array such as [1, 2, 3] to convert it.",
+        );
+    }
+
+    fn render_derivation_view(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .title("Derived Addresses");
+        if self.derivations.is_empty() {
+            let hint = Paragraph::new(
+                "Seeds are pubkeys, hex prefixed with 0x, integers such as u64:5, or text.",
+            )
+            .wrap(Wrap { trim: false })
+            .block(block);
+            frame.render_widget(hint, area);
+            return;
+        }
+        let account_keys = self.account_keys();
+        let found_style = if self.settings.monochrome {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Green)
+        };
+        let mut lines = vec![];
+        for (request, derived) in &self.derivations {
+            lines.push(Line::styled(
+                request.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            let derived = match derived {
+                Ok(derived) => derived,
+                Err(err) => {
+                    lines.push(Line::styled(
+                        format!("  {err}"),
+                        Style::default().fg(Color::Red),
+                    ));
+                    continue;
+                }
+            };
+            for derived in derived {
+                let line = format!(
+                    "  {}: {} (bump {})",
+                    derived.description, derived.address, derived.bump
+                );
+                match account_keys.iter().position(|key| *key == derived.address) {
+                    Some(index) => lines.push(Line::styled(
+                        format!("{line}, account key ({index})"),
+                        found_style,
+                    )),
+                    None => lines.push(Line::raw(format!("{line}, not in the transaction"))),
+                }
+            }
+        }
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn render_bookmarks_view(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        filter: &Input,
+        selected: usize,
+    ) {
+        let bookmarks_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(1)])
+            .split(area);
+        let filter_paragraph = Paragraph::new(filter.value())
+            .style(Style::default().fg(Color::Yellow))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Filter by Tag"),
+            );
+        frame.render_widget(filter_paragraph, bookmarks_chunks[0]);
+        if let Mode::Bookmarks { .. } = self.mode {
+            frame.set_cursor_position((
+                bookmarks_chunks[0].x + filter.visual_cursor() as u16 + 1,
+                bookmarks_chunks[0].y + 1,
+            ));
+        }
+
+        let bookmarks = self.bookmarks.filtered(filter.value());
+        let title = format!("Bookmarks ({})", bookmarks.len());
+        let program_style = Style::default().add_modifier(Modifier::DIM);
+        let bookmarks = bookmarks
+            .into_iter()
+            .map(|(signature, bookmark)| {
+                let mut spans = vec![Span::raw(signature.to_string())];
+                if !bookmark.annotations.is_empty() {
+                    spans.push(Span::raw(format!(
+                        " ({} annotations)",
+                        bookmark.annotations.len()
+                    )));
+                }
+                spans.extend(
+                    bookmark
+                        .tags
+                        .iter()
+                        .map(|tag| Span::raw(format!(" #{tag}"))),
+                );
+                spans.extend(
+                    bookmark
+                        .program_tags
+                        .iter()
+                        .map(|tag| Span::styled(format!(" #{tag}"), program_style)),
+                );
+                Line::from(spans)
+            })
+            .collect::<Vec<_>>();
+        let bookmarks = List::new(bookmarks)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(highlight_style(self.settings.monochrome));
+        frame.render_stateful_widget(
+            bookmarks,
+            bookmarks_chunks[1],
+            &mut ListState::default().with_selected(Some(selected)),
+        );
+    }
+
+    fn render_find_view(&self, frame: &mut Frame, area: Rect, find: &FindView) {
+        let find_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(1)])
+            .split(area);
+        let query = Paragraph::new(find.query.value())
+            .style(Style::default().fg(Color::Yellow))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Find a Pubkey or Hex Bytes"),
+            );
+        frame.render_widget(query, find_chunks[0]);
+        frame.set_cursor_position((
+            find_chunks[0].x + find.query.visual_cursor() as u16 + 1,
+            find_chunks[0].y + 1,
+        ));
+
+        let (title, hits) = match &find.hits {
+            Ok(hits) => (
+                format!(
+                    "Found in {} of the viewed, entry and bookmarked transactions",
+                    hits.len()
+                ),
+                hits.as_slice(),
+            ),
+            Err(err) => (err.clone(), [].as_slice()),
+        };
+        let hits = hits
+            .iter()
+            .map(|hit| {
+                let offsets = hit
+                    .offsets
+                    .iter()
+                    .map(|offset| format!("{offset:#x}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Line::raw(format!(
+                    "{} ({}): at {offsets}",
+                    hit.signature,
+                    hit.location.name()
+                ))
+            })
+            .collect::<Vec<_>>();
+        let hits = List::new(hits)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(highlight_style(self.settings.monochrome));
+        frame.render_stateful_widget(
+            hits,
+            find_chunks[1],
+            &mut ListState::default().with_selected(Some(find.selected)),
+        );
+    }
+
+    /// The name of an account from the address book or of a native program,
+    /// or else its pubkey.
+    fn account_name(&self, pubkey: &Pubkey) -> String {
+        self.address_book
+            .name(pubkey)
+            .or_else(|| native_program_name(pubkey))
+            .map(str::to_string)
+            .unwrap_or_else(|| self.settings.pubkey_display.format(pubkey))
+    }
+
+    /// The description of an error of the loaded transaction, naming custom
+    /// error codes.
+    fn describe_error(&self, err: &TransactionError) -> String {
+        let message = self.loaded().map(|loaded| &loaded.transaction.message);
+        self.error_codes.describe(err, |index| {
+            let message = message?;
+            let instruction = message.instructions().get(index as usize)?;
+            let program_index = instruction.program_id_index as usize;
+            message.static_account_keys().get(program_index).copied()
+        })
+    }
+
+    fn error_name(&self, program_id: &Pubkey, code: u32) -> Option<String> {
+        self.error_codes
+            .resolve(program_id, code)
+            .map(str::to_string)
+    }
+
+    /// Render the kinds of failures of a list below it while it is filtered.
+    /// Returns the area left for the list.
+    fn render_failure_groups(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        filter: &FailureFilter,
+        failures: impl IntoIterator<Item = FailureKind>,
+    ) -> Rect {
+        if *filter == FailureFilter::All {
+            return area;
+        }
+        let groups = failures::group(failures);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(1),
+                Constraint::Length(groups.len().clamp(1, 6) as u16 + 2),
+            ])
+            .split(area);
+        let name = |pubkey: &Pubkey| self.account_name(pubkey);
+        let error_name = |program: &Pubkey, code| self.error_name(program, code);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Failures by error");
+        if groups.is_empty() {
+            frame.render_widget(Paragraph::new("No failures").block(block), chunks[1]);
+            return chunks[0];
+        }
+        let selected = groups.iter().position(
+            |(kind, _)| matches!(filter, FailureFilter::Kind(filtered) if filtered == kind),
+        );
+        let groups = groups
+            .iter()
+            .map(|(kind, count)| format!("{count:>6}  {}", kind.describe(name, error_name)))
+            .collect::<Vec<_>>();
+        let groups = List::new(groups)
+            .block(block)
+            .highlight_style(highlight_style(self.settings.monochrome));
+        frame.render_stateful_widget(
+            groups,
+            chunks[1],
+            &mut ListState::default().with_selected(selected),
+        );
+        chunks[0]
+    }
+
+    fn render_watch_view(&self, frame: &mut Frame, area: Rect, selected: usize) {
+        let mut area = area;
+        let subscription = match self.mode {
+            Mode::Subscriptions(_, subscription) => Some(subscription),
+            Mode::SubscriptionEntry(_) => Some(self.settings.subscriptions.len()),
+            _ => None,
+        };
+        if subscription.is_some() || !self.settings.subscriptions.is_empty() {
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Length(60), Constraint::Min(1)])
+                .split(area);
+            self.render_subscriptions_pane(frame, chunks[0], subscription);
+            area = chunks[1];
+        }
+        let area = self.render_failure_groups(
+            frame,
+            area,
+            &self.watch.filter,
+            self.watch
+                .stream
+                .transactions
+                .iter()
+                .filter_map(StreamedTransaction::failure),
+        );
+        let name = |pubkey: &Pubkey| self.account_name(pubkey);
+        let error_name = |program: &Pubkey, code| self.error_name(program, code);
+        let watchlist = if self.settings.watchlist.is_empty() {
+            "nothing".to_string()
+        } else {
+            self.settings.watchlist.join(", ")
+        };
+        let mut title = format!(
+            "Watching new blocks for {watchlist} | {} matches",
+            self.watch.stream.num_matches
+        );
+        if let Some(slot) = self.watch.stream.last_slot {
+            title = format!("{title} | slot {}", format::integer(&self.settings, slot));
+        }
+        if self.watch.auto_open {
+            title = format!("{title} | opening matches");
+        }
+        if self.settings.export_stream_matches {
+            title = format!("{title} | saving matches");
+        }
+        if self.watch.filter != FailureFilter::All {
+            title = format!(
+                "{title} | showing {}",
+                self.watch.filter.describe(name, error_name)
+            );
+        }
+        let mut block = Block::default().borders(Borders::ALL).title(title);
+        if let Some(status) = self
+            .watch
+            .stream
+            .error
+            .as_ref()
+            .or(self.watch.status.as_ref())
+        {
+            block = block.title_bottom(status.as_str());
+        }
+        if self.watch.stream.transactions.is_empty() {
+            let waiting = Paragraph::new("Waiting for the next block...").block(block);
+            frame.render_widget(waiting, area);
+            return;
+        }
+        let match_style = if self.settings.monochrome {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Red)
+        };
+        let transactions = self
+            .watch_visible()
+            .into_iter()
+            .map(|index| {
+                let streamed = &self.watch.stream.transactions[index];
+                let mut spans = vec![Span::raw(format!(
+                    "{}  {}",
+                    format::integer(&self.settings, streamed.slot),
+                    streamed.signature()
+                ))];
+                if let Some(failure) = streamed.failure() {
+                    spans.push(Span::raw(format!(
+                        " failed: {}",
+                        failure.describe(name, error_name)
+                    )));
+                }
+                if !streamed.matches.is_empty() {
+                    spans.push(Span::styled(
+                        format!("  matches {}", streamed.matches.join(", ")),
+                        match_style,
+                    ));
+                }
+                Line::from(spans)
+            })
+            .collect::<Vec<_>>();
+        let transactions = List::new(transactions)
+            .block(block)
+            .highlight_style(highlight_style(self.settings.monochrome));
+        frame.render_stateful_widget(
+            transactions,
+            area,
+            &mut ListState::default().with_selected(Some(selected)),
+        );
+    }
+
+    /// The subscriptions of the watch, with the selected one while focused.
+    fn render_subscriptions_pane(&self, frame: &mut Frame, area: Rect, selected: Option<usize>) {
+        let subscriptions = &self.settings.subscriptions;
+        let streaming = if watch::enabled(subscriptions).is_some() {
+            "enabled"
+        } else {
+            "all transactions"
+        };
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Subscriptions ({streaming})"));
+        if selected.is_some() {
+            block = block.border_style(Style::default().fg(Color::Yellow));
+        }
+        if subscriptions.is_empty() {
+            let hint = Paragraph::new("Add an address or program to only watch its transactions.")
+                .wrap(Wrap { trim: false })
+                .block(block);
+            frame.render_widget(hint, area);
+            return;
+        }
+        let subscriptions = subscriptions
+            .iter()
+            .map(|subscription| {
+                let address = &subscription.address;
+                let name = self.account_name(address);
+                let count = self
+                    .watch
+                    .stream
+                    .subscription_counts
+                    .get(address)
+                    .copied()
+                    .unwrap_or_default();
+                let checkbox = if subscription.enabled { "[x]" } else { "[ ]" };
+                format!(
+                    "{checkbox} {name}  {}",
+                    format::integer(&self.settings, count as u64)
+                )
+            })
+            .collect::<Vec<_>>();
+        let subscriptions = List::new(subscriptions)
+            .block(block)
+            .highlight_style(highlight_style(self.settings.monochrome));
+        frame.render_stateful_widget(
+            subscriptions,
+            area,
+            &mut ListState::default().with_selected(selected),
+        );
+    }
+
+    fn render_address_book_view(&self, frame: &mut Frame, area: Rect, selected: usize) {
+        let contacts = self.address_book.contacts();
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .title(format!("Address book ({})", contacts.len()));
+        if let Some(status) = &self.address_book_status {
+            block = block.title_bottom(status.as_str());
+        }
+        if contacts.is_empty() {
+            let hint = Paragraph::new(
+                "No contacts yet. Add one, or import the keypairs of a Solana CLI config directory.",
+            )
+            .wrap(Wrap { trim: false })
+            .block(block);
+            frame.render_widget(hint, area);
+            return;
+        }
+        let name_width = contacts
+            .iter()
+            .map(|(_, contact)| contact.name.chars().count())
+            .max()
+            .unwrap_or_default();
+        let contacts = contacts
+            .into_iter()
+            .map(|(pubkey, contact)| {
+                let tags = contact.tags.iter().map(|tag| format!(" #{tag}"));
+                format!(
+                    "{:<name_width$}  {}{}",
+                    contact.name,
+                    self.settings.pubkey_display.format(&pubkey),
+                    tags.collect::<String>()
+                )
+            })
+            .collect::<Vec<_>>();
+        let contacts = List::new(contacts)
+            .block(block)
+            .highlight_style(highlight_style(self.settings.monochrome));
+        frame.render_stateful_widget(
+            contacts,
+            area,
+            &mut ListState::default().with_selected(Some(selected)),
+        );
+    }
+
+    fn render_rpc_console_view(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        response: Option<&(String, Option<Result<Value, String>>)>,
+        scroll: usize,
+    ) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1));
+        let Some((request, response)) = response else {
+            let hint = Paragraph::new(format!(
+                "Requests go to {}, e.g. getAccountInfo [\"<address>\", {{\"encoding\": \"base64\"}}]",
+                self.endpoint
+            ))
+            .wrap(Wrap { trim: false })
+            .block(block.title("Response"));
+            frame.render_widget(hint, area);
+            return;
+        };
+        let block = block.title(format!("Response to {request}"));
+        let response = match response {
+            None => Paragraph::new("Awaiting response..."),
+            Some(Err(err)) => Paragraph::new(err.as_str())
+                .style(Style::default().fg(Color::Red))
+                .wrap(Wrap { trim: false }),
+            Some(Ok(value)) => {
+                Paragraph::new(rpc_console::highlight(value, self.settings.monochrome))
+            }
+        };
+        let response = response.scroll((scroll as u16, 0)).block(block);
+        frame.render_widget(response, area);
+    }
+
+    fn render_slot_timing_view(&self, frame: &mut Frame, area: Rect, status: Option<&str>) {
+        let Some(signature) = self.current_signature() else {
+            return;
+        };
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .title(format!("Slot Timing: {signature}"));
+        if let Some(status) = status {
+            block = block.title_bottom(status);
+        }
+        let attempts = self.slot_timings.attempts(&signature);
+        if attempts.is_empty() {
+            let hint = Paragraph::new(
+                "No submissions recorded. Press <r> to record when the transaction was submitted, \
+                 or broadcast it from the edit view.",
+            )
+            .wrap(Wrap { trim: false })
+            .block(block);
+            frame.render_widget(hint, area);
+            return;
+        }
+        let lines = attempts
+            .iter()
+            .map(|attempt| {
+                let submitted = format::timestamp(&self.settings, attempt.submitted_at / 1000);
+                let via = attempt.endpoint.as_deref().unwrap_or("recorded");
+                let landed = match (attempt.slots_elapsed(), attempt.wall_time()) {
+                    (Some((slots, estimated)), Some(wall_time)) => format!(
+                        "landed after {}{} slots, {:.3}s",
+                        if estimated { "~" } else { "" },
+                        format::integer(&self.settings, slots),
+                        wall_time.as_secs_f64()
+                    ),
+                    _ => "did not land".to_string(),
+                };
+                format!("{submitted}  {landed}  via {via}")
+            })
+            .collect::<Vec<_>>();
+        frame.render_widget(List::new(lines).block(block), area);
+    }
+
+    fn render_narration_view(&self, frame: &mut Frame, area: Rect, scroll: u16) {
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .title("Narration");
+        if let Some(file_status) = &self.file_status {
+            block = block.title_bottom(file_status.as_str());
+        }
+        let lines = narration::narrate(self.loaded_sections(), &self.settings, &self.address_book)
+            .into_iter()
+            .map(Line::raw)
+            .collect::<Vec<_>>();
+        let narration = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0))
+            .block(block);
+        frame.render_widget(narration, area);
+    }
+
+    fn render_preflight_view(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        preflight: &Option<Result<Preflight, String>>,
+        scroll: u16,
+    ) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .title("Preflight vs on-chain");
+        let preflight = match preflight {
+            None => {
+                let simulating = Paragraph::new("Simulating...").block(block);
+                frame.render_widget(simulating, area);
+                return;
+            }
+            Some(Err(err)) => {
+                let error = Paragraph::new(err.as_str())
+                    .style(Style::default().fg(Color::Red))
+                    .block(block);
+                frame.render_widget(error, area);
+                return;
+            }
+            Some(Ok(preflight)) => preflight,
+        };
+        let Some(loaded) = self.loaded() else {
+            return;
+        };
+        let Some((meta, on_chain)) = loaded
+            .meta
+            .as_ref()
+            .and_then(|meta| Some((meta, meta.err.as_ref()?)))
+        else {
+            return;
+        };
+        let on_chain_logs =
+            Option::<&Vec<String>>::from(meta.log_messages.as_ref()).map_or(&[][..], Vec::as_slice);
+        let units = |units: Option<u64>| {
+            units.map_or("unknown".to_string(), |units| {
+                format::integer(&self.settings, units)
+            })
+        };
+        let simulated = preflight
+            .err
+            .as_ref()
+            .map_or("succeeded".to_string(), |err| self.describe_error(err));
+        let mut simulated_at = format!(
+            "Simulated at slot {}",
+            format::integer(&self.settings, preflight.slot)
+        );
+        if let Some(slot) = loaded.slot {
+            simulated_at = format!(
+                "{simulated_at}, {} slots after the transaction landed",
+                format::integer(&self.settings, preflight.slot.saturating_sub(slot))
+            );
+        }
+        let divergence = match preflight::first_divergence(on_chain_logs, &preflight.logs) {
+            None => "The logs are identical".to_string(),
+            Some(line) => format!("The logs diverge from line {}", line + 1),
+        };
+        let summary = [
+            format!("On chain:  {}", self.describe_error(on_chain)),
+            format!("Simulated: {simulated}"),
+            preflight.verdict(on_chain).to_string(),
+            simulated_at,
+            format!(
+                "Compute units: {} on chain, {} simulated",
+                units(Option::<&u64>::from(meta.compute_units_consumed.as_ref()).copied()),
+                units(preflight.units_consumed)
+            ),
+            divergence,
+        ];
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(summary.len() as u16 + 2),
+                Constraint::Min(1),
+            ])
+            .split(area);
+        let summary = Paragraph::new(summary.map(Line::raw).to_vec())
+            .wrap(Wrap { trim: false })
+            .block(block);
+        frame.render_widget(summary, chunks[0]);
+
+        let log_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+            .split(chunks[1]);
+        let differing_style = if self.settings.monochrome {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Red)
+        };
+        let logs = |logs: &[String], other: &[String], title| {
+            let lines = logs
+                .iter()
+                .enumerate()
+                .map(|(index, log)| {
+                    if other.get(index) == Some(log) {
+                        Line::raw(log.clone())
+                    } else {
+                        Line::styled(log.clone(), differing_style)
+                    }
+                })
+                .collect::<Vec<_>>();
+            Paragraph::new(lines)
+                .scroll((scroll, 0))
+                .block(Block::default().borders(Borders::ALL).title(title))
+        };
+        frame.render_widget(
+            logs(on_chain_logs, &preflight.logs, "On-chain logs"),
+            log_chunks[0],
+        );
+        frame.render_widget(
+            logs(&preflight.logs, on_chain_logs, "Simulated logs"),
+            log_chunks[1],
+        );
+    }
+
+    fn render_instructions_sysvar_view(&self, frame: &mut Frame, area: Rect, scroll: u16) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .title("Instructions sysvar");
+        let introspection = self.loaded().and_then(|loaded| {
+            let num_loaded_writable = loaded
+                .meta
+                .as_ref()
+                .and_then(|meta| Option::<&UiLoadedAddresses>::from(meta.loaded_addresses.as_ref()))
+                .map_or(0, |loaded_addresses| loaded_addresses.writable.len());
+            Introspection::new(
+                &loaded.transaction,
+                &self.account_keys(),
+                num_loaded_writable,
+            )
+        });
+        let Some(introspection) = introspection else {
+            let absent = Paragraph::new(
+                "The transaction does not include the Instructions sysvar, so no instruction \
+                 can introspect the others",
+            )
+            .wrap(Wrap { trim: false })
+            .block(block);
+            frame.render_widget(absent, area);
+            return;
+        };
+        let instruction_names = |indexes: &[usize]| {
+            indexes
+                .iter()
+                .map(|index| format!("#{index}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut lines = vec![];
+        if introspection.readers.is_empty() {
+            lines.push("No instruction is passed the sysvar".to_string());
+        } else {
+            lines.push(format!(
+                "Likely introspecting, as they are passed the sysvar: {}",
+                instruction_names(&introspection.readers)
+            ));
+        }
+        if !introspection.precompiles.is_empty() {
+            lines.push(format!(
+                "Precompiles, usually checked through the sysvar: {}",
+                instruction_names(&introspection.precompiles)
+            ));
+        }
+        lines.push(String::new());
+        lines.push(format!(
+            "Content of the sysvar ({} bytes):",
+            introspection.data.len()
+        ));
+        lines.extend(introspection.dump());
+        let lines = lines.into_iter().map(Line::raw).collect::<Vec<_>>();
+        let paragraph = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0))
+            .block(block);
+        frame.render_widget(paragraph, area);
+    }
+
+    fn render_entry_picker(&self, frame: &mut Frame, area: Rect, selected: usize) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .title(format!(
+                "{} | {} transactions",
+                self.entries_source,
+                format::integer(&self.settings, self.entry_transactions.len() as u64)
+            ));
+        let items = self
+            .entry_transactions
+            .iter()
+            .map(|located| {
+                let signature = located
+                    .transaction
+                    .signatures
+                    .first()
+                    .map(ToString::to_string)
+                    .unwrap_or_default();
+                format!(
+                    "entry {:>5} transaction {:>4}  offset {:#08x}  {signature}",
+                    located.entry_index, located.index, located.offset
+                )
+            })
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(highlight_style(self.settings.monochrome));
+        frame.render_stateful_widget(
+            list,
+            area,
+            &mut ListState::default().with_selected(Some(selected)),
+        );
+    }
+
+    fn render_strings_view(&self, frame: &mut Frame, area: Rect, selected: usize) {
+        let strings = strings::extract(self.loaded_sections());
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .title(format!(
+                "Strings in instruction data | {} found",
+                strings.len()
+            ));
+        if strings.is_empty() {
+            let empty = Paragraph::new("No printable strings in the instruction data").block(block);
+            frame.render_widget(empty, area);
+            return;
+        }
+        let items = strings
+            .iter()
+            .map(|string| {
+                let mut item = format!(
+                    "instruction {:>2}  offset {:>4}  {:<6}  {}",
+                    string.instruction,
+                    string.offset,
+                    string.kind.name(),
+                    string.text
+                );
+                if let Some(decoded) = string.decoded() {
+                    item = format!("{item}  → {decoded}");
+                }
+                item
+            })
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(highlight_style(self.settings.monochrome));
+        frame.render_stateful_widget(
+            list,
+            area,
+            &mut ListState::default().with_selected(Some(selected)),
+        );
+    }
+
+    /// Statistics of the loaded transaction, with the data size of each
+    /// instruction.
+    fn render_stats_view(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        budget: &ByteBudget,
+        selected: usize,
+    ) {
+        let Some(loaded) = self.loaded() else {
+            return;
+        };
+        let stats_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Fill(1), Constraint::Length(48)])
+            .split(area);
+        self.render_byte_budget(
+            frame,
+            stats_chunks[1],
+            &loaded.transaction,
+            budget,
+            selected,
+        );
+        let area = stats_chunks[0];
+        let sizes =
+            data_sizes::instruction_sizes(&loaded.transaction, &self.settings, &self.address_book);
+        let num_bytes = loaded
+            .sections
+            .iter()
+            .map(|section| section.bytes.len() as u64)
+            .sum::<u64>();
+        let data_len = sizes.iter().map(|size| size.data_len as u64).sum::<u64>();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::uniform(1))
+            .title(format!(
+                "Stats | {} instructions | {} of {} bytes in instruction data",
+                sizes.len(),
+                format::integer(&self.settings, data_len),
+                format::integer(&self.settings, num_bytes),
+            ));
+        if sizes.is_empty() {
+            frame.render_widget(Paragraph::new("No instructions").block(block), area);
+            return;
+        }
+        let chart = data_sizes::bar_chart(&sizes, self.settings.monochrome).block(block);
+        frame.render_widget(chart, area);
+    }
+
+    /// The byte budget calculator, projecting the size of the transaction
+    /// with the quantities adjusted.
+    fn render_byte_budget(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        transaction: &VersionedTransaction,
+        budget: &ByteBudget,
+        selected: usize,
+    ) {
+        let adjusted = budget.adjusted_counts(transaction);
+        let mut lines = byte_budget::LABELS
+            .iter()
+            .enumerate()
+            .map(|(index, label)| {
+                let line = format!(
+                    "{label:<25}{:>6} ({})",
+                    format::integer(&self.settings, adjusted[index] as u64),
+                    budget.delta_text(index)
+                );
+                if index == selected {
+                    Line::styled(line, highlight_style(self.settings.monochrome))
+                } else {
+                    Line::raw(line)
+                }
+            })
+            .collect::<Vec<_>>();
+        let projected = budget.projected_size(transaction);
+        let color = if projected > PACKET_DATA_SIZE {
+            Color::Red
+        } else {
+            Color::Green
+        };
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            format!(
+                "Projected size {}/{}",
+                format::integer(&self.settings, projected as u64),
+                format::bytes(&self.settings, PACKET_DATA_SIZE)
+            ),
+            Style::default().fg(color),
+        ));
+        let remaining = PACKET_DATA_SIZE as i64 - projected as i64;
+        lines.push(Line::styled(
+            if remaining < 0 {
+                format!("{} bytes over", -remaining)
+            } else {
+                format!("{remaining} bytes left")
+            },
+            Style::default().fg(color),
+        ));
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::uniform(1))
+            .title("Byte budget")
+            .title_bottom("<+>/<-> adjust");
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn render_sensitivity_map_view(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        sensitivity_map: Option<&SensitivityMap>,
+        scroll: usize,
+    ) {
+        let Some(sensitivity_map) = sensitivity_map else {
+            let text = "Mapping the sensitivity of every byte…";
+            frame.render_widget(Paragraph::new(text).block(Block::bordered()), area);
+            return;
+        };
+        let middle_block_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(100), Constraint::Fill(1)])
+            .split(area);
+        let bytes_block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::uniform(1))
+            .title(format!(
+                "Corruption Sensitivity | {} single byte mutations: {} parse, {} sanitize",
+                format::integer(&self.settings, sensitivity_map.num_mutations as u64),
+                format::integer(&self.settings, sensitivity_map.num_parsed as u64),
+                format::integer(&self.settings, sensitivity_map.num_sanitized as u64),
+            ));
+        let byte_block = TransactionByteBlock::new(&sensitivity_map.sections)
+            .block(bytes_block)
+            .scroll(scroll)
+            .monochrome(self.settings.monochrome);
+        frame.render_widget(&byte_block, middle_block_chunks[0]);
+
+        let legend_block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::uniform(1))
+            .title("Legend");
+        let byte_section_legend = ByteSectionLegend::new(&sensitivity_map.sections)
+            .block(legend_block)
+            .monochrome(self.settings.monochrome);
+        frame.render_widget(&byte_section_legend, middle_block_chunks[1]);
+    }
+
+    fn render_inner_transaction_view(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        title: &str,
+        sections: &[TransactionByteSection],
+    ) {
+        let middle_block_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(100), Constraint::Fill(1)])
+            .split(area);
+        let bytes_block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::uniform(1))
+            .title(title);
+        let byte_block = TransactionByteBlock::new(sections)
+            .block(bytes_block)
+            .monochrome(self.settings.monochrome);
+        frame.render_widget(&byte_block, middle_block_chunks[0]);
+
+        let legend_block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::uniform(1))
+            .title("Legend");
+        let byte_section_legend = ByteSectionLegend::new(sections)
+            .block(legend_block)
+            .pubkey_display(self.settings.pubkey_display)
+            .address_book(&self.address_book)
+            .monochrome(self.settings.monochrome);
+        frame.render_widget(&byte_section_legend, middle_block_chunks[1]);
+    }
+
+    fn render_alt_plan_view(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        alt_plan: Option<&AltPlan>,
+        status: Option<&str>,
+        step: usize,
+    ) {
+        let alt_plan_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(40), Constraint::Fill(1)])
+            .split(area);
+        let mut steps_block = Block::default()
+            .borders(Borders::ALL)
+            .title("Lookup Table Plan");
+        if let Some(status) = status {
+            steps_block = steps_block.title_bottom(status);
+        }
+        let Some(alt_plan) = alt_plan else {
+            frame.render_widget(steps_block, area);
+            return;
+        };
+
+        let steps = std::iter::once(format!(
+            "Table {}",
+            format::pubkey(&self.settings, &self.address_book, &alt_plan.lookup_table)
+        ))
+        .chain(
+            alt_plan
+                .steps()
+                .enumerate()
+                .map(|(index, step)| format!("{index}: {}", step.description)),
+        )
+        .collect::<Vec<_>>();
+        let list = List::new(steps)
+            .block(steps_block)
+            .highlight_style(highlight_style(self.settings.monochrome));
+        frame.render_stateful_widget(
+            list,
+            alt_plan_chunks[0],
+            &mut ListState::default().with_selected(Some(step + 1)),
+        );
+
+        let selected = alt_plan.step(step);
+        let num_bytes = selected
+            .sections
+            .iter()
+            .map(|section| section.bytes.len())
+            .sum::<usize>();
+        let bytes_block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::uniform(1))
+            .title(format!(
+                "{} ({}/{})",
+                selected.description,
+                format::integer(&self.settings, num_bytes as u64),
+                format::bytes(&self.settings, PACKET_DATA_SIZE)
+            ));
+        let byte_block = TransactionByteBlock::new(&selected.sections)
+            .block(bytes_block)
+            .monochrome(self.settings.monochrome);
+        frame.render_widget(&byte_block, alt_plan_chunks[1]);
+    }
+
+    fn render_edit_view(&self, frame: &mut Frame, area: Rect) {
+        let Screen::Edit {
+            session: edit_session,
+            status,
+            ..
+        } = &self.screen
+        else {
+            return;
+        };
+        let num_bytes = |sections: &[TransactionByteSection]| {
+            sections
+                .iter()
+                .map(|section| section.bytes.len())
+                .sum::<usize>()
+        };
+
+        let (applied, undone) = edit_session.history();
+        let (edit_view_chunks, edit_block_chunks) = edit_view_layout(area);
+        let (original_start, original_sections) =
+            self.view_sections(edit_session.original_sections());
+        let (edited_start, edited_sections) = self.view_sections(edit_session.edited_sections());
+        let original_block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::uniform(1))
+            .title(format!(
+                "Original ({})",
+                format::bytes(&self.settings, num_bytes(edit_session.original_sections()))
+            ));
+        let compute_budget = ComputeBudget::from_message(&edit_session.edited().message);
+        let mut edited_title = format!(
+            "Edited ({}/{}) | {} CUs at {} micro-lamports, priority fee {}",
+            format::integer(
+                &self.settings,
+                num_bytes(edit_session.edited_sections()) as u64
+            ),
+            format::bytes(&self.settings, PACKET_DATA_SIZE),
+            format::integer(&self.settings, compute_budget.unit_limit.into()),
+            format::integer(&self.settings, compute_budget.unit_price),
+            format::lamports(&self.settings, compute_budget.priority_fee()),
+        );
+        if self.message_view {
+            edited_title = format!(
+                "{edited_title} | {}",
+                message_summary(&self.settings, edit_session.edited(), edited_sections)
+            );
+        }
+        let mut edited_block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::uniform(1))
+            .title(edited_title);
+        if let Some(edit_status) = status {
+            edited_block = edited_block.title_bottom(edit_status.as_str());
+        }
+
+        // Both panes scroll together, with gaps keeping corresponding
+        // sections side by side. The cursor moves over the edited bytes and
+        // is mirrored onto the corresponding original byte.
+        let alignment = PaneAlignment::new(original_sections, edited_sections, edit_session.diff());
+        let absolute_gaps = |gaps: &[(usize, usize)], start: usize| {
+            gaps.iter()
+                .map(|(offset, count)| (start + offset, *count))
+                .collect::<Vec<_>>()
+        };
+        let original_gaps = absolute_gaps(alignment.left.gaps(), original_start);
+        let edited_gaps = absolute_gaps(alignment.right.gaps(), edited_start);
+        let cursor_cell = self.edit_cursor_cell(&alignment, edited_start);
+        let original_cursor = alignment
+            .left
+            .offset_at(cursor_cell)
+            .map(|offset| original_start + offset);
+
+        let original_byte_block = TransactionByteBlock::new(original_sections)
+            .block(original_block)
+            .base_offset(original_start)
+            .highlights(edit_session.differences())
+            .gaps(&original_gaps)
+            .scroll(self.byte_scroll)
+            .cursor(original_cursor)
+            .monochrome(self.settings.monochrome);
+        let edited_byte_block = TransactionByteBlock::new(edited_sections)
+            .block(edited_block)
+            .base_offset(edited_start)
+            .highlights(edit_session.differences())
+            .gaps(&edited_gaps)
+            .scroll(self.byte_scroll)
+            .cursor(Some(self.cursor))
+            .monochrome(self.settings.monochrome);
+        frame.render_widget(&original_byte_block, edit_block_chunks[0]);
+        frame.render_widget(&edited_byte_block, edit_block_chunks[1]);
+        self.render_tooltip(frame, edit_view_chunks[1]);
+
+        let bottom_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(edit_view_chunks[2]);
+
+        // Show the most recent edits, with undone edits dimmed below them.
+        let history_block = Block::default()
+            .borders(Borders::ALL)
+            .title("Edit History (<u> undo, <Ctrl-r> redo)");
+        let history_height = history_block.inner(bottom_chunks[0]).height as usize;
+        let history_lines = applied
+            .iter()
+            .map(|description| Line::styled(*description, Style::default()))
+            .chain(undone.iter().map(|description| {
+                Line::styled(*description, Style::default().fg(Color::DarkGray))
+            }))
+            .collect::<Vec<_>>();
+        let first_line = applied
+            .len()
+            .saturating_sub(history_height.saturating_sub(1));
+        let history = List::new(history_lines.into_iter().skip(first_line)).block(history_block);
+        frame.render_widget(history, bottom_chunks[0]);
+
+        self.render_changes(frame, bottom_chunks[1], &edit_session.diff().changes);
+    }
+
+    /// The changes of a structural diff, colored by their kind.
+    fn render_changes(&self, frame: &mut Frame, area: Rect, changes: &[Change]) {
+        let changes_list = List::new(changes.iter().map(|change| {
+            let color = match change.kind {
+                _ if self.settings.monochrome => Color::Reset,
+                ChangeKind::Added => Color::Green,
+                ChangeKind::Removed => Color::Red,
+                ChangeKind::Moved => Color::Cyan,
+                ChangeKind::Modified => Color::Yellow,
+            };
+            Line::styled(change.to_string(), Style::default().fg(color))
+        }))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Changes ({})", changes.len())),
+        );
+        frame.render_widget(changes_list, area);
+    }
+
+    fn render_diff_view(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        loaded: &LoadedTransaction,
+        compared: &Compared,
+        byte_scroll: usize,
+    ) {
+        let (diff_view_chunks, pane_chunks) = diff_view_layout(area);
+        let pane_block = |loaded: &LoadedTransaction| {
+            let num_bytes = loaded
+                .sections
+                .iter()
+                .map(|section| section.bytes.len())
+                .sum();
+            Block::default()
+                .borders(Borders::ALL)
+                .padding(Padding::uniform(1))
+                .title(format!(
+                    "{} ({})",
+                    loaded.signature,
+                    format::bytes(&self.settings, num_bytes)
+                ))
+        };
+        let (other, diff) = match compared {
+            Compared::Fetched(other, diff) => (&**other, diff),
+            Compared::Fetching(_) | Compared::Failed(_) => {
+                let (text, color) = match compared {
+                    Compared::Failed(err) => (err.clone(), Color::Red),
+                    _ => (
+                        "Fetching the transaction to compare with...".to_string(),
+                        Color::Yellow,
+                    ),
+                };
+                let byte_block = TransactionByteBlock::new(&loaded.sections)
+                    .block(pane_block(loaded))
+                    .scroll(byte_scroll)
+                    .monochrome(self.settings.monochrome);
+                frame.render_widget(&byte_block, pane_chunks[0]);
+                let paragraph = Paragraph::new(Text::styled(text, Style::default().fg(color)))
+                    .wrap(Wrap { trim: false })
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .padding(Padding::uniform(1)),
+                    );
+                frame.render_widget(paragraph, pane_chunks[1]);
+                return;
+            }
+        };
+
+        // As in the edit view, gaps keep corresponding sections side by side.
+        let alignment = PaneAlignment::new(&loaded.sections, &other.sections, diff);
+        let bytes = |loaded: &LoadedTransaction| {
+            loaded
+                .sections
+                .iter()
+                .flat_map(|section| section.bytes.iter().copied())
+                .collect::<Vec<_>>()
+        };
+        let (left_differences, right_differences) =
+            alignment.differing_ranges(&bytes(loaded), &bytes(other));
+        let panes = [
+            (loaded, &left_differences, alignment.left.gaps()),
+            (other, &right_differences, alignment.right.gaps()),
+        ];
+        for (area, (loaded, differences, gaps)) in pane_chunks.iter().zip(panes) {
+            let byte_block = TransactionByteBlock::new(&loaded.sections)
+                .block(pane_block(loaded))
+                .highlights(differences)
+                .gaps(gaps)
+                .scroll(byte_scroll)
+                .monochrome(self.settings.monochrome);
+            frame.render_widget(&byte_block, *area);
+        }
+        self.render_changes(frame, diff_view_chunks[1], &diff.changes);
+    }
+
+    /// The cell of the edited pane in which the cursor is shown.
+    fn edit_cursor_cell(&self, alignment: &PaneAlignment, edited_start: usize) -> usize {
+        alignment
+            .right
+            .cell_of(self.cursor.saturating_sub(edited_start))
+            .unwrap_or_default()
+    }
+
+    /// A single line describing the byte under the cursor and its section.
+    fn render_tooltip(&self, frame: &mut Frame, area: Rect) {
+        let mut tooltip = narration::tooltip(
+            self.cursor_sections(),
+            self.cursor,
+            &self.settings,
+            &self.address_book,
+        )
+        .unwrap_or_default();
+        match self.mode {
+            Mode::HexWrite(Some(high_nibble)) => {
+                tooltip = format!("writing {high_nibble:x}_ • {tooltip}");
+            }
+            Mode::HexWrite(None) => tooltip = format!("type hex digits to overwrite • {tooltip}"),
+            _ => {}
+        }
+        frame.render_widget(Paragraph::new(tooltip), area);
+    }
+
+    /// The byte grid shown in `area`, if any: its inner area, the cell in
+    /// which the cursor is shown, its zoom and its number of bytes.
+    fn byte_grid(&self, area: Rect) -> Option<(Rect, usize, GridZoom, usize)> {
+        match &self.screen {
+            Screen::Viewing(_) => {
+                let loaded = self.loaded()?;
+                if VoteSummary::of(&loaded.transaction).is_some() && !self.vote_bytes {
+                    return None;
+                }
+                let view_start = self.view_sections(&loaded.sections).0;
+                let column = self.transaction_view_columns(area)[0];
+                let bytes_area = self.transaction_bytes_chunks(column)[0];
+                Some((
+                    grid_inner(bytes_area),
+                    self.cursor.saturating_sub(view_start),
+                    self.transaction_zoom(),
+                    self.revealed_num_bytes(),
+                ))
+            }
+            Screen::Edit { .. } => {
+                let edit_session = self.edit_session()?;
+                let (_, original_sections) = self.view_sections(edit_session.original_sections());
+                let (edited_start, edited_sections) =
+                    self.view_sections(edit_session.edited_sections());
+                let alignment =
+                    PaneAlignment::new(original_sections, edited_sections, edit_session.diff());
+                let edited_area = edit_view_layout(area).1[1];
+                Some((
+                    grid_inner(edited_area),
+                    self.edit_cursor_cell(&alignment, edited_start),
+                    GridZoom::Hex,
+                    0,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Keep the cursor within the visible lines of the byte grid, so the
+    /// grid renders scrolled to wherever the cursor moved.
+    fn scroll_to_cursor(&mut self) {
+        let area = frame_chunks(self.frame_area)[2];
+        let Some((bytes_inner, cursor_cell, zoom, num_bytes)) = self.byte_grid(area) else {
+            return;
+        };
+        self.bytes_per_line = TransactionByteBlock::bytes_per_line(
+            zoom,
+            bytes_inner.width,
+            bytes_inner.height,
+            num_bytes,
+        )
+        .max(1);
+        let visible_lines = TransactionByteBlock::visible_lines(zoom, bytes_inner.height).max(1);
+        let cursor_line = cursor_cell / self.bytes_per_line;
+        if cursor_line < self.byte_scroll {
+            self.byte_scroll = cursor_line;
+        } else if cursor_line >= self.byte_scroll + visible_lines {
+            self.byte_scroll = cursor_line + 1 - visible_lines;
+        }
+    }
+
+    /// Wait for the next terminal event, unless commands already finished.
+    fn next_messages(&mut self) -> io::Result<Vec<Message>> {
+        let messages = self.command_runner.finished();
+        if !messages.is_empty() {
+            return Ok(messages);
+        }
+        // Wake up periodically to redraw with results of background work,
+        // more often while commands are running.
+        if self
+            .reveal
+            .as_ref()
+            .is_some_and(|reveal| reveal.num_revealed().is_none())
+        {
+            self.reveal = None;
+        }
+        let timeout = if self.command_runner.is_busy() || self.reveal.is_some() {
+            Duration::from_millis(50)
+        } else {
+            Duration::from_millis(250)
+        };
+        let event = match self.pending_events.pop_front() {
+            Some(event) => event,
+            None if event::poll(timeout)? => event::read()?,
+            None => return Ok(vec![]),
+        };
+        Ok(vec![Message::Event(event)])
+    }
+
+    /// Update the state on a message, queueing the commands it requires.
+    fn update(&mut self, message: Message) {
+        self.handle_message(message);
+        self.scroll_to_cursor();
+    }
+
+    fn handle_message(&mut self, message: Message) {
+        match message {
+            Message::Event(event) => self.handle_event(event),
+            Message::TransactionFetched {
+                signature,
+                result,
+                elapsed,
+            } => self.on_transaction_fetched(signature, result, elapsed),
+            Message::LandingPolled { signature, result } => {
+                self.on_landing_polled(signature, result)
+            }
+            Message::LatestPolled { address, result } => self.on_latest_polled(address, result),
+            Message::CommitmentPolled { signature, poll } => {
+                self.on_commitment_polled(signature, poll)
+            }
+            Message::EndpointChecked { generation, report } => {
+                if self.endpoint_health.record(generation, report) {
+                    self.check_endpoint_health(endpoint_health::CHECK_INTERVAL);
+                }
+            }
+            Message::SlotEntriesFetched { slot, result } => {
+                self.show_entries(format!("slot {slot}"), result);
+            }
+            Message::StoredTransactionFetched {
+                signature,
+                title,
+                result,
+                accounts,
+                elapsed,
+            } => self.on_stored_transaction_fetched(signature, title, result, accounts, elapsed),
+            Message::FileChanged { name, result } => self.on_file_changed(name, result),
+            Message::EntriesFileRead { path, result } => self.show_entries(path, result),
+            Message::SnapshotLoaded { signature, result } => {
+                self.on_snapshot_loaded(signature, result)
+            }
+            Message::SnapshotsLoaded { snapshots } => {
+                if let Mode::Find(find) = self.mode.underlying_mut() {
+                    find.snapshots = snapshots;
+                    self.update_find_hits();
+                }
+            }
+            Message::SchemaLoaded { index, result } => self.on_schema_loaded(index, result),
+            Message::KeypairFileRead { result } => {
+                let status = match result {
+                    Ok((path, keypair)) => {
+                        format!("Loaded keypair {}", self.keypairs.load_file(path, keypair))
+                    }
+                    Err(err) => err,
+                };
+                self.set_edit_status(status);
+            }
+            Message::KeypairDirRead { dir, result } => {
+                self.address_book_status = Some(match result {
+                    Ok(keypairs) => {
+                        let num_added = self.address_book.import_keypairs(keypairs);
+                        self.save_address_book();
+                        format!("Imported {num_added} contacts from {}", dir.display())
+                    }
+                    Err(err) => err,
+                });
+            }
+            Message::SensitivityMapped {
+                signature,
+                sensitivity_map,
+                elapsed,
+            } => {
+                if self.current_signature() == Some(signature) {
+                    if let Mode::SensitivityMap {
+                        sensitivity_map: map,
+                        ..
+                    } = self.mode.underlying_mut()
+                    {
+                        *map = Some(sensitivity_map);
+                    }
+                    self.finish_long_operation("Sensitivity map finished", elapsed);
+                }
+            }
+            Message::LedgerConnected {
+                derivation_path,
+                result,
+            } => self.on_ledger_connected(derivation_path, result),
+            Message::LedgerSigned { pubkey, result } => self.on_ledger_signed(pubkey, result),
+            Message::FileWritten { path, result } => {
+                self.file_status = Some(match result {
+                    Ok(()) => format!("Wrote {path}"),
+                    Err(err) => format!("Failed to write {path}: {err}"),
+                });
+            }
+            Message::LeadersFetched { result } => {
+                if let Mode::Leaders(leaders) = self.mode.underlying_mut() {
+                    *leaders = Some(result);
+                }
+            }
+            Message::MevContextFetched { signature, result } => {
+                if self.current_signature() == Some(signature) {
+                    if let Mode::Sandwich { mev_context, .. } = self.mode.underlying_mut() {
+                        *mev_context = Some(result);
+                    }
+                }
+            }
+            Message::PreflightSimulated { signature, result } => {
+                if self.current_signature() == Some(signature) {
+                    if let Mode::Preflight { preflight, .. } = self.mode.underlying_mut() {
+                        *preflight = Some(result);
+                    }
+                }
+            }
+            Message::WatchPolled {
+                generation,
+                result,
+                elapsed,
+            } => self.on_watch_polled(generation, result, elapsed),
+            Message::StreamMatchesExported { result } => self.on_stream_matches_exported(result),
+            Message::RelatedFetched { result } => {
+                let Mode::Related(view) = self.mode.underlying_mut() else {
+                    return;
+                };
+                // A window changed while fetching is fetched again.
+                let is_current = match &result {
+                    Ok(related) => related.window == view.window,
+                    Err(_) => true,
+                };
+                if is_current {
+                    view.related = Some(result);
+                }
+            }
+            Message::Simulated { result } => {
+                if let Mode::Simulation { simulation, .. } = self.mode.underlying_mut() {
+                    *simulation = Some(result);
+                }
+            }
+            Message::LookupTablePlanned { signature, result } => {
+                if self.current_signature() != Some(signature) {
+                    return;
+                }
+                let Mode::AltPlan { plan, status, .. } = self.mode.underlying_mut() else {
+                    return;
+                };
+                match result {
+                    Ok(alt_plan) => {
+                        *plan = Some(alt_plan);
+                        *status = None;
+                    }
+                    Err(err) => *status = Some(err),
+                }
+            }
+            Message::LookupTableSubmitted {
+                lookup_table,
+                result,
+                elapsed,
+            } => {
+                if let Mode::AltPlan { status, .. } = self.mode.underlying_mut() {
+                    *status = Some(match result {
+                        Ok(()) => {
+                            format!(
+                                "Created lookup table {lookup_table}, usable from the next slot"
+                            )
+                        }
+                        Err(err) => err,
+                    });
+                }
+                self.finish_long_operation("Lookup table submission finished", elapsed);
+            }
+            Message::BlockhashFetched { result } => match result {
+                Ok(blockhash) => {
+                    self.apply_edit(|edit_session| edit_session.set_recent_blockhash(blockhash))
+                }
+                Err(err) => self.set_edit_status(err),
+            },
+            Message::PdasLabeled {
+                signature,
+                labels,
+                hook_roles,
+            } => {
+                self.pda_labels = Some((signature, labels));
+                self.hook_roles = Some((signature, hook_roles));
+            }
+            Message::OwnershipResolved { signature, result } => {
+                self.ownership = Some((signature, result))
+            }
+            Message::BuildsChecked { statuses } => self.build_statuses.extend(statuses),
+            Message::SecurityTxtsFetched { results } => self.security_txts.extend(results),
+            Message::DomainsResolved { signature, result } => match result {
+                Ok(domains) => self.domains = Some((signature, domains)),
+                Err(err) => self.file_status = Some(err),
+            },
+            Message::DomainResolved { domain, result } => self.domain_resolved(domain, result),
+            Message::RpcResponded { result } => {
+                if let Mode::RpcConsole {
+                    response: Some((_, response)),
+                    ..
+                } = self.mode.underlying_mut()
+                {
+                    *response = Some(result);
+                }
+            }
+            Message::Broadcasted {
+                signature,
+                endpoint,
+                outcome,
+            } => {
+                let Some(broadcast) = &mut self.broadcast else {
+                    return;
+                };
+                if broadcast.signature != signature {
+                    return;
+                }
+                let attempt = outcome.attempt(&endpoint, broadcast.submitted_at);
+                broadcast.record(&endpoint, outcome);
+                if let Some(attempt) = attempt {
+                    self.slot_timings.record(&signature, attempt);
+                    self.persist("slot timings", self.slot_timings.save());
+                }
+            }
+        }
+    }
+
+    fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::FocusGained => {
+                self.focused = true;
+                let _ = tui::set_title(tui::APP_TITLE);
+            }
+            Event::FocusLost => self.focused = false,
+            Event::Resize(width, height) => self.frame_area = Rect::new(0, 0, width, height),
+            // Pasted text arrives whole, so its newlines do not submit it.
+            Event::Paste(text) if matches!(self.mode, Mode::SignatureEntry) => {
+                let value = format!("{}{text}", self.input.value());
+                self.input = self.input.clone().with_value(value);
+            }
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                // Any key skips the rest of a reveal.
+                self.reveal = None;
+                self.handle_key_event(key_event)
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        let action = keybindings::action(self.mode.context(), key_event);
+        let is_overlay = matches!(self.mode, Mode::Help(_) | Mode::CommandPalette(_));
+        if action == Some(Action::OpenCommandPalette) && !is_overlay {
+            let return_mode = std::mem::replace(&mut self.mode, Mode::SignatureEntry);
+            self.mode = Mode::CommandPalette(Box::new(Palette::new(return_mode)));
+            return;
+        }
+        if action == Some(Action::ShowHelp) && !is_overlay {
+            let return_mode = std::mem::replace(&mut self.mode, Mode::SignatureEntry);
+            self.mode = Mode::Help(Box::new(Help {
+                return_mode,
+                query: Input::default(),
+                scroll: 0,
+            }));
+            return;
+        }
+        match self.mode {
+            Mode::SignatureEntry => self.signature_entry_handle_key_event(key_event, action),
+            Mode::MenuSelection => self.menu_selection_handle_action(action),
+            Mode::ConfirmRefetch => self.confirm_refetch_handle_action(action),
+            Mode::FetchOptions(selected) => self.fetch_options_handle_action(action, selected),
+            Mode::ByteNavigation => self.byte_navigation_handle_action(action),
+            Mode::Jump => {
+                // Any other key cancels the jump.
+                self.mode = Mode::ByteNavigation;
+                if let Some(action) = action {
+                    self.move_cursor(action);
+                }
+            }
+            Mode::AnnotationEntry => self.annotation_entry_handle_key_event(key_event, action),
+            Mode::DiffSignatureEntry => match action {
+                Some(Action::Back) => {
+                    self.prompt_input.reset();
+                    self.mode = Mode::ByteNavigation;
+                }
+                Some(Action::Confirm) => self.start_diff(),
+                _ => {
+                    self.prompt_input.handle_event(&Event::Key(key_event));
+                }
+            },
+            Mode::Diff => self.diff_handle_action(action),
+            Mode::Edit => self.edit_handle_action(action),
+            Mode::HexWrite(high_nibble) => {
+                self.hex_write_handle_key_event(key_event, action, high_nibble)
+            }
+            Mode::EditValueEntry(field) => {
+                self.edit_value_entry_handle_key_event(key_event, action, field)
+            }
+            Mode::KeypairEntry(_) => self.keypair_entry_handle_key_event(key_event, action),
+            Mode::LedgerSigning(_) => self.ledger_signing_handle_action(action),
+            Mode::Template(index) => self.template_handle_key_event(key_event, action, index),
+            Mode::AltPlan { .. } => self.alt_plan_handle_action(action),
+            Mode::InnerTransaction { .. } => {
+                if action == Some(Action::Back) {
+                    self.mode = Mode::ByteNavigation;
+                }
+            }
+            Mode::CommitmentTimeline => {
+                if action == Some(Action::Back) {
+                    self.mode = Mode::SignatureEntry;
+                }
+            }
+            Mode::Broadcast => {
+                if action == Some(Action::Back) {
+                    self.mode = Mode::Edit;
+                }
+            }
+            Mode::Leaders(_) => {
+                if action == Some(Action::Back) {
+                    self.mode = Mode::ByteNavigation;
+                }
+            }
+            Mode::Related(_) => self.related_handle_action(action),
+            Mode::Sandwich { .. } => self.sandwich_handle_action(action),
+            Mode::AccessMatrix(row, column) => {
+                self.access_matrix_handle_action(action, row, column)
+            }
+            Mode::Simulation { .. } => self.simulation_handle_action(action),
+            Mode::RpcConsole { .. } => self.rpc_console_handle_key_event(key_event, action),
+            Mode::AddressBook(selected) => self.address_book_handle_action(action, selected),
+            Mode::Bookmarks { .. } => self.bookmarks_handle_key_event(key_event, action),
+            Mode::Find(_) => self.find_handle_key_event(key_event, action),
+            Mode::Watch(selected) => self.watch_handle_action(action, selected),
+            Mode::WatchPatternEntry(selected) => match action {
+                Some(Action::Back) => {
+                    self.prompt_input.reset();
+                    self.mode = Mode::Watch(selected);
+                }
+                Some(Action::Confirm) => self.add_watch_pattern(selected),
+                _ => {
+                    self.prompt_input.handle_event(&Event::Key(key_event));
+                }
+            },
+            Mode::Subscriptions(selected, subscription) => {
+                self.subscriptions_handle_action(action, selected, subscription)
+            }
+            Mode::SubscriptionEntry(selected) => match action {
+                Some(Action::Back) => {
+                    self.prompt_input.reset();
+                    let last = self.settings.subscriptions.len().saturating_sub(1);
+                    self.mode = Mode::Subscriptions(selected, last);
+                }
+                Some(Action::Confirm) => self.add_subscription(selected),
+                _ => {
+                    self.prompt_input.handle_event(&Event::Key(key_event));
+                }
+            },
+            Mode::Derivation => self.derivation_handle_key_event(key_event, action),
+            Mode::VarintPlayground | Mode::Converter | Mode::SignatureCheck => match action {
+                Some(Action::Back) => {
+                    self.prompt_input.reset();
+                    self.mode = Mode::SignatureEntry;
+                }
+                Some(Action::Confirm) => self.prompt_input.reset(),
+                _ => {
+                    self.prompt_input.handle_event(&Event::Key(key_event));
+                }
+            },
+            Mode::TagEntry(signature, _) => {
+                self.tag_entry_handle_key_event(key_event, action, signature)
+            }
+            Mode::ContactEntry(replacing, _) => {
+                self.contact_entry_handle_key_event(key_event, action, replacing)
+            }
+            Mode::KeypairDirEntry(_) => self.keypair_dir_entry_handle_key_event(key_event, action),
+            Mode::SlotTiming(_) => match action {
+                Some(Action::Back) => self.mode = Mode::ByteNavigation,
+                Some(Action::RecordSubmission) => {
+                    self.prompt_input.reset();
+                    self.mode = Mode::SubmissionTimeEntry;
+                }
+                _ => {}
+            },
+            Mode::SubmissionTimeEntry => {
+                self.submission_time_entry_handle_key_event(key_event, action)
+            }
+            Mode::Narration(scroll) => self.narration_handle_action(action, scroll),
+            Mode::InstructionsSysvar(scroll) => match action {
+                Some(Action::Back) => self.mode = Mode::ByteNavigation,
+                Some(Action::Previous) => {
+                    self.mode = Mode::InstructionsSysvar(scroll.saturating_sub(1))
+                }
+                Some(Action::Next) => self.mode = Mode::InstructionsSysvar(scroll + 1),
+                _ => {}
+            },
+            Mode::Preflight { ref mut scroll, .. } => match action {
+                Some(Action::Back) => self.mode = Mode::ByteNavigation,
+                Some(Action::Previous) => *scroll = scroll.saturating_sub(1),
+                Some(Action::Next) => *scroll += 1,
+                _ => {}
+            },
+            Mode::SensitivityMap { ref mut scroll, .. } => match action {
+                Some(Action::Back) => self.mode = Mode::ByteNavigation,
+                Some(Action::Previous) => *scroll = scroll.saturating_sub(1),
+                Some(Action::Next) => *scroll += 1,
+                _ => {}
+            },
+            Mode::Strings(selected) => self.strings_handle_action(action, selected),
+            Mode::Stats(..) => self.stats_handle_action(action),
+            Mode::EntriesFileEntry => self.entries_file_entry_handle_key_event(key_event, action),
+            Mode::LedgerSlotEntry => self.ledger_slot_entry_handle_key_event(key_event, action),
+            Mode::EntryPicker(selected) => self.entry_picker_handle_action(action, selected),
+            Mode::Help(_) => self.help_handle_key_event(key_event, action),
+            Mode::CommandPalette(_) => self.command_palette_handle_key_event(key_event, action),
+        }
+    }
+
+    fn entries_file_entry_handle_key_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        match action {
+            Some(Action::Back) => self.mode = Mode::SignatureEntry,
+            Some(Action::Confirm) => {
+                let path = self.prompt_input.value().trim().to_string();
+                self.prompt_input.reset();
+                self.commands.push_back(Command::ReadEntriesFile { path });
+            }
+            _ => {
+                self.prompt_input.handle_event(&Event::Key(key_event));
+            }
+        }
+    }
+
+    fn simulation_handle_action(&mut self, action: Option<Action>) {
+        let Mode::Simulation {
+            simulation,
+            selected,
+            scroll,
+        } = &mut self.mode
+        else {
+            return;
+        };
+        let num_accounts = match simulation {
+            Some(Ok(simulation)) => simulation.accounts.len(),
+            _ => 0,
+        };
+        match action {
+            Some(Action::Back) => self.mode = Mode::Edit,
+            Some(Action::Previous) => {
+                *scroll = 0;
+                *selected = selected.saturating_sub(1);
+            }
+            Some(Action::Next) => {
+                *scroll = 0;
+                *selected = (*selected + 1).min(num_accounts.saturating_sub(1));
+            }
+            Some(Action::ScrollUp) => *scroll = scroll.saturating_sub(1),
+            Some(Action::ScrollDown) => *scroll += 1,
+            _ => {}
+        }
+    }
+
+    fn derivation_handle_key_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        match action {
+            Some(Action::Back) => {
+                self.prompt_input.reset();
+                self.mode = Mode::ByteNavigation;
+            }
+            Some(Action::Confirm) => {
+                let request = self.prompt_input.value().trim().to_string();
+                let derived = derivation::derive(&request);
+                self.derivations.insert(0, (request, derived));
+                self.prompt_input.reset();
+            }
+            _ => {
+                self.prompt_input.handle_event(&Event::Key(key_event));
+            }
+        }
+    }
+
+    fn bookmarks_handle_key_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        let Mode::Bookmarks { filter, selected } = &mut self.mode else {
+            return;
+        };
+        let bookmarks = self.bookmarks.filtered(filter.value());
+        let signature = bookmarks.get(*selected).map(|(signature, _)| *signature);
+        let num_bookmarks = bookmarks.len();
+        match action {
+            Some(Action::Back) => self.mode = Mode::SignatureEntry,
+            Some(Action::Previous) => *selected = selected.saturating_sub(1),
+            Some(Action::Next) => *selected = (*selected + 1).min(num_bookmarks.saturating_sub(1)),
+            Some(Action::Confirm) => {
+                if let Some(signature) = signature {
+                    self.input = Input::new(signature.to_string());
+                    self.mode = Mode::SignatureEntry;
+                    self.on_signature_entry();
+                }
+            }
+            Some(Action::EditTags) => {
+                if let Some(signature) = signature {
+                    let bookmarks = std::mem::replace(&mut self.mode, Mode::SignatureEntry);
+                    self.start_tag_entry(signature, bookmarks);
+                }
+            }
+            _ => {
+                filter.handle_event(&Event::Key(key_event));
+                *selected = 0;
+            }
+        }
+    }
+
+    fn find_handle_key_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        let Mode::Find(find) = &mut self.mode else {
+            return;
+        };
+        let num_hits = find.hits.as_ref().map(Vec::len).unwrap_or(0);
+        match action {
+            Some(Action::Back) => self.mode = Mode::SignatureEntry,
+            Some(Action::Previous) => find.selected = find.selected.saturating_sub(1),
+            Some(Action::Next) => {
+                find.selected = (find.selected + 1).min(num_hits.saturating_sub(1))
+            }
+            Some(Action::Confirm) => {
+                let Some(hit) = find
+                    .hits
+                    .as_ref()
+                    .ok()
+                    .and_then(|hits| hits.get(find.selected))
+                else {
+                    return;
+                };
+                let (location, signature, offset) = (hit.location, hit.signature, hit.offsets[0]);
+                let snapshot = find
+                    .snapshots
+                    .iter()
+                    .find(|(bookmarked, _)| *bookmarked == signature)
+                    .map(|(_, snapshot)| snapshot.clone());
+                let is_open = match location {
+                    find::Location::Viewed => self.loaded().is_some(),
+                    find::Location::Entry(index) => {
+                        self.open_entry_transaction(index);
+                        true
+                    }
+                    find::Location::Bookmark => {
+                        self.remember_view_position();
+                        snapshot.is_some_and(|snapshot| self.open_snapshot(signature, snapshot))
+                    }
+                };
+                if is_open {
+                    self.message_view = false;
+                    self.cursor = offset;
+                    self.selection_anchor = None;
+                    self.mode = Mode::ByteNavigation;
+                }
+            }
+            _ => {
+                find.query.handle_event(&Event::Key(key_event));
+                find.selected = 0;
+                self.update_find_hits();
+            }
+        }
+    }
+
+    /// Watch the transactions of new blocks, from the latest.
+    fn start_watch(&mut self) {
+        self.watch.stream.last_slot = None;
+        self.watch.stream.error = None;
+        self.watch.stream.generation += 1;
+        self.watch.status = None;
+        self.commands.push_back(Command::PollWatch {
+            rpc: self.rpc(),
+            generation: self.watch.stream.generation,
+            after_slot: None,
+            delay: Duration::ZERO,
+        });
+        self.mode = Mode::Watch(0);
+    }
+
+    fn on_watch_polled(
+        &mut self,
+        generation: u64,
+        result: Result<watch::Batch, String>,
+        elapsed: Duration,
+    ) {
+        self.metrics.record_poll(elapsed, result.is_ok());
+        // Leaving the watch stops polling, and a restarted watch has polls
+        // of its own.
+        let Some(selected) = self.mode.watch_selected() else {
+            return;
+        };
+        if generation != self.watch.stream.generation {
+            return;
+        }
+        match result {
+            Ok(batch) => {
+                for streamed in &batch.transactions {
+                    let num_bytes = bincode::serialized_size(&streamed.transaction).unwrap_or(0);
+                    self.metrics.record_transaction(num_bytes as usize);
+                }
+                for _ in 0..batch.num_undecoded {
+                    self.metrics.record_decode_failure();
+                }
+                let watchlist = watch::compile(&self.settings.watchlist);
+                let subscribed = watch::enabled(&self.settings.subscriptions);
+                let watch::Pushed {
+                    num_added,
+                    num_matches,
+                } = self
+                    .watch
+                    .stream
+                    .push(batch, subscribed.as_deref(), &watchlist);
+                if num_matches > 0 && self.settings.export_stream_matches {
+                    self.export_stream_matches(num_added);
+                }
+                // Keep the selected transaction selected as newer ones arrive.
+                if selected > 0 && !self.watch.stream.paused {
+                    let num_shown = self.num_watch_visible(num_added);
+                    self.mode = self.mode.with_watch_selected(selected + num_shown);
+                }
+                if num_matches > 0
+                    && self.watch.auto_open
+                    && !self.watch.stream.paused
+                    && matches!(self.mode, Mode::Watch(_))
+                {
+                    let first_match = self
+                        .watch
+                        .stream
+                        .transactions
+                        .iter()
+                        .take(num_added)
+                        .rposition(|streamed| !streamed.matches.is_empty());
+                    if let Some(index) = first_match {
+                        self.open_streamed_transaction(index);
+                        return;
+                    }
+                }
+            }
+            Err(err) => self.watch.stream.error = Some(err),
+        }
+        self.commands.push_back(Command::PollWatch {
+            rpc: self.rpc(),
+            generation,
+            after_slot: self.watch.stream.last_slot,
+            delay: watch::POLL_INTERVAL,
+        });
+    }
+
+    /// Save the matches among the `num_added` newest streamed transactions.
+    fn export_stream_matches(&mut self, num_added: usize) {
+        let retention = self
+            .settings
+            .stream_match_retention
+            .unwrap_or(stream_exports::DEFAULT_RETENTION);
+        let matches = self
+            .watch
+            .stream
+            .arrivals()
+            .iter()
+            .take(num_added)
+            .filter(|streamed| !streamed.matches.is_empty())
+            .cloned()
+            .collect();
+        self.commands.push_back(Command::ExportStreamMatches {
+            exports: self.watch.exports.clone(),
+            matches,
+            now: SystemTime::now(),
+            retention,
+        });
+    }
+
+    fn on_stream_matches_exported(&mut self, result: Result<(), String>) {
+        if let Err(err) = result {
+            self.watch.status = Some(format!("Failed to save matches: {err}"));
+        }
+    }
+
+    /// The indices of the watched transactions the failure filter shows.
+    fn watch_visible(&self) -> Vec<usize> {
+        self.watch
+            .stream
+            .transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, streamed)| self.watch.filter.matches(streamed.failure().as_ref()))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// How many of the `num_newest` watched transactions the filter shows.
+    fn num_watch_visible(&self, num_newest: usize) -> usize {
+        self.watch
+            .stream
+            .transactions
+            .iter()
+            .take(num_newest)
+            .filter(|streamed| self.watch.filter.matches(streamed.failure().as_ref()))
+            .count()
+    }
+
+    fn watch_handle_action(&mut self, action: Option<Action>, selected: usize) {
+        let visible = self.watch_visible();
+        let last = visible.len().saturating_sub(1);
+        match action {
+            Some(Action::Back) => self.mode = Mode::SignatureEntry,
+            Some(Action::Previous) => self.mode = Mode::Watch(selected.saturating_sub(1)),
+            Some(Action::Next) => self.mode = Mode::Watch((selected + 1).min(last)),
+            Some(Action::Confirm) => {
+                if let Some(&index) = visible.get(selected) {
+                    self.open_streamed_transaction(index);
+                }
+            }
+            Some(Action::CycleFailureFilter) => {
+                let groups = failures::group(
+                    self.watch
+                        .stream
+                        .transactions
+                        .iter()
+                        .filter_map(StreamedTransaction::failure),
+                );
+                self.watch.filter = self.watch.filter.next(&groups);
+                self.mode = Mode::Watch(0);
+            }
+            Some(Action::AddWatchPattern) => {
+                self.prompt_input.reset();
+                self.mode = Mode::WatchPatternEntry(selected);
+            }
+            Some(Action::RemoveWatchPattern) => {
+                if let Some(pattern) = self.settings.watchlist.pop() {
+                    self.watch.status = Some(format!("No longer watching for {pattern}"));
+                    self.save_settings();
+                }
+            }
+            Some(Action::ToggleAutoOpen) => self.watch.auto_open = !self.watch.auto_open,
+            Some(Action::TogglePause) => {
+                let num_resumed = self.watch.stream.toggle_pause();
+                // Keep the selected transaction selected.
+                if selected > 0 {
+                    self.mode = Mode::Watch(selected + self.num_watch_visible(num_resumed));
+                }
+            }
+            Some(Action::ShowSubscriptions) => self.mode = Mode::Subscriptions(selected, 0),
+            Some(Action::ToggleStreamExports) => {
+                self.settings.export_stream_matches = !self.settings.export_stream_matches;
+                self.watch.status = match &self.watch.exports.dir {
+                    Some(dir) if self.settings.export_stream_matches => {
+                        Some(format!("Saving matches to {}", dir.display()))
+                    }
+                    _ => None,
+                };
+                self.save_settings();
+            }
+            _ => {}
+        }
+    }
+
+    fn subscriptions_handle_action(
+        &mut self,
+        action: Option<Action>,
+        selected: usize,
+        subscription: usize,
+    ) {
+        let last = self.settings.subscriptions.len().saturating_sub(1);
+        match action {
+            Some(Action::Back) => self.mode = Mode::Watch(selected),
+            Some(Action::Previous) => {
+                self.mode = Mode::Subscriptions(selected, subscription.saturating_sub(1))
+            }
+            Some(Action::Next) => {
+                self.mode = Mode::Subscriptions(selected, (subscription + 1).min(last))
+            }
+            Some(Action::AddSubscription) => {
+                self.prompt_input.reset();
+                self.mode = Mode::SubscriptionEntry(selected);
+            }
+            Some(Action::ToggleSubscription) => {
+                if let Some(entry) = self.settings.subscriptions.get_mut(subscription) {
+                    entry.enabled = !entry.enabled;
+                    self.save_settings();
+                }
+            }
+            Some(Action::RemoveSubscription)
+                if subscription < self.settings.subscriptions.len() =>
+            {
+                let removed = self.settings.subscriptions.remove(subscription);
+                self.watch
+                    .stream
+                    .subscription_counts
+                    .remove(&removed.address);
+                self.mode = Mode::Subscriptions(selected, subscription.min(last.saturating_sub(1)));
+                self.save_settings();
+            }
+            _ => {}
+        }
+    }
+
+    fn add_subscription(&mut self, selected: usize) {
+        let address = match Pubkey::from_str(self.prompt_input.value().trim()) {
+            Ok(address) => address,
+            Err(err) => {
+                self.watch.status = Some(format!("Invalid address: {err}"));
+                return;
+            }
+        };
+        let subscriptions = &mut self.settings.subscriptions;
+        let index = match subscriptions
+            .iter()
+            .position(|entry| entry.address == address)
+        {
+            Some(index) => index,
+            None => {
+                subscriptions.push(Subscription {
+                    address,
+                    enabled: true,
+                });
+                subscriptions.len() - 1
+            }
+        };
+        self.watch.status = Some(format!("Subscribed to {address}"));
+        self.save_settings();
+        self.prompt_input.reset();
+        self.mode = Mode::Subscriptions(selected, index);
+    }
+
+    fn add_watch_pattern(&mut self, selected: usize) {
+        let pattern = self.prompt_input.value().trim().to_string();
+        if let Err(err) = find::parse(&pattern) {
+            self.watch.status = Some(err);
+            return;
+        }
+        self.watch.status = Some(format!("Watching for {pattern}"));
+        self.settings.watchlist.push(pattern);
+        self.save_settings();
+        self.prompt_input.reset();
+        self.mode = Mode::Watch(selected);
+    }
+
+    fn open_streamed_transaction(&mut self, index: usize) {
+        let Some(streamed) = self.watch.stream.transactions.get(index) else {
+            return;
+        };
+        let transaction = streamed.transaction.clone();
+        let mut sections = vec![];
+        get_transaction_byte_sections(&transaction, &mut sections);
+        let mut source = format!("watched in slot {}", streamed.slot);
+        if !streamed.matches.is_empty() {
+            source = format!("{source}, matching {}", streamed.matches.join(", "));
+        }
+        let loaded = LoadedTransaction {
+            signature: streamed.signature(),
+            transaction,
+            meta: streamed.meta.clone(),
+            slot: Some(streamed.slot),
+            block_time: streamed.block_time,
+            sections,
+            source: Some(source),
+            endpoint: Some(self.endpoint),
+            fetch_options: None,
+            accounts: BTreeMap::new(),
+        };
+        self.remember_view_position();
+        self.screen = Screen::Viewing(loaded);
+        self.restore_view_position();
+        self.start_reveal();
+        self.label_pdas();
+        self.resolve_ownership();
+        self.look_up_programs();
+        self.resolve_domains();
+        self.mode = Mode::ByteNavigation;
+    }
+
+    /// Search the viewed transaction, those of the entries file and the
+    /// snapshots of bookmarks for the find query.
+    fn update_find_hits(&mut self) {
+        let Mode::Find(find) = self.mode.underlying() else {
+            return;
+        };
+        let hits = self.find_hits(find);
+        if let Mode::Find(find) = self.mode.underlying_mut() {
+            find.hits = hits;
+        }
+    }
+
+    fn find_hits(&self, find: &FindView) -> Result<Vec<find::Hit>, String> {
+        let query = find.query.value();
+        if query.trim().is_empty() {
+            return Ok(vec![]);
+        }
+        let pattern = find::parse(query)?;
+        let viewed = self.loaded().map(|loaded| &loaded.transaction);
+        let bookmarked = find
+            .snapshots
+            .iter()
+            .filter(|(signature, _)| Some(*signature) != self.current_signature())
+            .filter_map(|(_, snapshot)| snapshot.decode().ok().map(|(transaction, _)| transaction))
+            .collect::<Vec<_>>();
+        let transactions = viewed
+            .map(|transaction| (find::Location::Viewed, transaction))
+            .into_iter()
+            .chain(
+                self.entry_transactions
+                    .iter()
+                    .enumerate()
+                    .map(|(index, located)| (find::Location::Entry(index), &located.transaction)),
+            )
+            .chain(
+                bookmarked
+                    .iter()
+                    .map(|transaction| (find::Location::Bookmark, transaction)),
+            );
+        Ok(find::search(&pattern, transactions))
+    }
+
+    /// Fetch the transactions touching `address` within `window` slots of
+    /// the loaded one, showing those `filter` matches.
+    fn fetch_related(&mut self, address: Pubkey, window: u64, filter: FailureFilter) {
+        let Some(slot) = self.loaded().and_then(|loaded| loaded.slot) else {
+            return;
+        };
+        self.commands.push_back(Command::FetchRelated {
+            rpc: self.rpc(),
+            address,
+            slot,
+            window,
+        });
+        self.mode = Mode::Related(Box::new(RelatedView {
+            related: None,
+            window,
+            filter,
+            selected: 0,
+        }));
+    }
+
+    /// Re-simulate the failed transaction, to compare the outcome with the
+    /// one recorded on chain.
+    fn compare_preflight(&mut self) {
+        let Some(loaded) = self.loaded() else {
+            return;
+        };
+        if loaded
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.err.as_ref())
+            .is_none()
+        {
+            self.file_status = Some("The transaction did not fail".to_string());
+            return;
+        }
+        let command = Command::SimulatePreflight {
+            rpc: self.rpc(),
+            signature: loaded.signature,
+            transaction: Box::new(loaded.transaction.clone()),
+        };
+        self.commands.push_back(command);
+        self.mode = Mode::Preflight {
+            preflight: None,
+            scroll: 0,
+        };
+    }
+
+    fn sandwich_handle_action(&mut self, action: Option<Action>) {
+        let Mode::Sandwich {
+            mev_context,
+            selected,
+        } = &mut self.mode
+        else {
+            return;
+        };
+        let neighbors = match mev_context {
+            Some(Ok(context)) => context.neighbors.as_slice(),
+            _ => &[],
+        };
+        let signature = neighbors.get(*selected).map(|neighbor| neighbor.signature);
+        let last = neighbors.len().saturating_sub(1);
+        match action {
+            Some(Action::Back) => self.mode = Mode::ByteNavigation,
+            Some(Action::Previous) => *selected = selected.saturating_sub(1),
+            Some(Action::Next) => *selected = (*selected + 1).min(last),
+            Some(Action::Confirm) => {
+                if let Some(signature) = signature {
+                    self.input = Input::new(signature.to_string());
+                    self.mode = Mode::SignatureEntry;
+                    self.on_signature_entry();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn access_matrix_handle_action(&mut self, action: Option<Action>, row: usize, column: usize) {
+        let Some(matrix) = self.access_matrix() else {
+            self.mode = Mode::ByteNavigation;
+            return;
+        };
+        let last_row = matrix.rows.len().saturating_sub(1);
+        let last_column = matrix.num_instructions().saturating_sub(1);
+        match action {
+            Some(Action::Back) => self.mode = Mode::ByteNavigation,
+            Some(Action::Previous) => self.mode = Mode::AccessMatrix(row.saturating_sub(1), column),
+            Some(Action::Next) => self.mode = Mode::AccessMatrix((row + 1).min(last_row), column),
+            Some(Action::CursorLeft) => {
+                self.mode = Mode::AccessMatrix(row, column.saturating_sub(1))
+            }
+            Some(Action::CursorRight) => {
+                self.mode = Mode::AccessMatrix(row, (column + 1).min(last_column))
+            }
+            Some(Action::Confirm) => {
+                let offset = self.loaded().and_then(|loaded| {
+                    matrix.byte_offset(&loaded.transaction, &loaded.sections, row, column)
+                });
+                match offset {
+                    Some(offset) => {
+                        self.message_view = false;
+                        self.cursor = offset;
+                        self.selection_anchor = None;
+                        self.mode = Mode::ByteNavigation;
+                    }
+                    None => {
+                        self.file_status =
+                            Some(format!("Instruction #{column} does not use this account"))
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn related_handle_action(&mut self, action: Option<Action>) {
+        let Mode::Related(view) = &mut self.mode else {
+            return;
+        };
+        let candidates = view.visible();
+        let signature = candidates
+            .get(view.selected)
+            .map(|candidate| candidate.signature);
+        let last = candidates.len().saturating_sub(1);
+        let (window, filter) = (view.window, view.filter.clone());
+        match action {
+            Some(Action::CycleFailureFilter) => {
+                let groups = match &view.related {
+                    Some(Ok(related)) => failures::group(
+                        related
+                            .candidates
+                            .iter()
+                            .filter_map(related::Candidate::failure),
+                    ),
+                    _ => vec![],
+                };
+                view.filter = view.filter.next(&groups);
+                view.selected = 0;
+            }
+            Some(Action::Back) => self.mode = Mode::ByteNavigation,
+            Some(Action::Previous) => view.selected = view.selected.saturating_sub(1),
+            Some(Action::Next) => view.selected = (view.selected + 1).min(last),
+            Some(Action::Confirm) => {
+                if let Some(signature) = signature {
+                    self.input = Input::new(signature.to_string());
+                    self.mode = Mode::SignatureEntry;
+                    self.on_signature_entry();
+                }
+            }
+            Some(action @ (Action::Increase | Action::Decrease)) => {
+                let Some(address) = section_at(self.cursor_sections(), self.cursor)
+                    .and_then(TransactionByteSection::pubkey)
+                else {
+                    return;
+                };
+                let window = match action {
+                    Action::Increase => window.saturating_mul(2),
+                    _ => (window / 2).max(1),
+                };
+                self.fetch_related(address, window, filter);
+            }
+            _ => {}
+        }
+    }
+
+    fn start_tag_entry(&mut self, signature: Signature, return_mode: Mode) {
+        let tags = self
+            .bookmarks
+            .get(&signature)
+            .map(|bookmark| bookmark.tags.join(" "))
+            .unwrap_or_default();
+        self.prompt_input = Input::new(tags);
+        self.mode = Mode::TagEntry(signature, Box::new(return_mode));
+    }
+
+    fn tag_entry_handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        action: Option<Action>,
+        signature: Signature,
+    ) {
+        match action {
+            Some(Action::Back) => self.return_from_overlay(),
+            Some(Action::Confirm) => {
+                let tags = bookmarks::parse_tags(self.prompt_input.value());
+                self.bookmarks.set_tags(&signature, tags);
+                self.save_bookmarks();
+                self.prompt_input.reset();
+                self.return_from_overlay();
+            }
+            _ => {
+                self.prompt_input.handle_event(&Event::Key(key_event));
+            }
+        }
+    }
+
+    fn address_book_handle_action(&mut self, action: Option<Action>, selected: usize) {
+        let contacts = self.address_book.contacts();
+        let contact = contacts
+            .get(selected)
+            .map(|(pubkey, contact)| (*pubkey, address_book::contact_text(pubkey, contact)));
+        let num_contacts = contacts.len();
+        let return_mode = Mode::AddressBook(selected);
+        match action {
+            Some(Action::Back) => self.mode = Mode::SignatureEntry,
+            Some(Action::Previous) => self.mode = Mode::AddressBook(selected.saturating_sub(1)),
+            Some(Action::Next) => {
+                self.mode = Mode::AddressBook((selected + 1).min(num_contacts.saturating_sub(1)))
+            }
+            Some(Action::AddContact) => self.start_contact_entry(None, String::new(), return_mode),
+            Some(Action::EditContact) => {
+                if let Some((pubkey, text)) = contact {
+                    self.start_contact_entry(Some(pubkey), text, return_mode);
+                }
+            }
+            Some(Action::DeleteContact) => {
+                if let Some((pubkey, _)) = contact {
+                    self.address_book.remove(&pubkey);
+                    self.save_address_book();
+                    self.mode = Mode::AddressBook(selected.min(num_contacts.saturating_sub(2)));
+                }
+            }
+            Some(Action::ImportKeypairs) => self.start_keypair_dir_entry(return_mode),
+            _ => {}
+        }
+    }
+
+    fn start_contact_entry(&mut self, replacing: Option<Pubkey>, text: String, return_mode: Mode) {
+        self.prompt_input = Input::new(text);
+        self.address_book_status = None;
+        self.mode = Mode::ContactEntry(replacing, Box::new(return_mode));
+    }
+
+    fn start_keypair_dir_entry(&mut self, return_mode: Mode) {
+        let dir = address_book::default_keypair_dir()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default();
+        self.prompt_input = Input::new(dir);
+        self.address_book_status = None;
+        self.mode = Mode::KeypairDirEntry(Box::new(return_mode));
+    }
+
+    fn contact_entry_handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        action: Option<Action>,
+        replacing: Option<Pubkey>,
+    ) {
+        match action {
+            Some(Action::Back) => {
+                self.address_book_status = None;
+                self.return_from_overlay();
+            }
+            Some(Action::Confirm) => {
+                let text = self.prompt_input.value().to_string();
+                match text.split_whitespace().next() {
+                    Some(domain) if sns::is_domain(domain) => {
+                        self.address_book_status = Some(format!("Resolving {domain}"));
+                        self.commands.push_back(Command::ResolveDomain {
+                            rpc: self.rpc(),
+                            domain: domain.to_string(),
+                        });
+                    }
+                    _ => self.add_contact(&text, replacing),
+                }
+            }
+            _ => {
+                self.prompt_input.handle_event(&Event::Key(key_event));
+            }
+        }
+    }
+
+    fn add_contact(&mut self, text: &str, replacing: Option<Pubkey>) {
+        match address_book::parse_contact(text) {
+            // Stay in the prompt to correct the contact.
+            Err(err) => self.address_book_status = Some(err),
+            Ok((pubkey, contact)) => {
+                if let Some(replaced) = replacing {
+                    self.address_book.remove(&replaced);
+                }
+                self.address_book.insert(&pubkey, contact);
+                self.save_address_book();
+                self.address_book_status = None;
+                self.prompt_input.reset();
+                self.return_from_overlay();
+            }
+        }
+    }
+
+    /// Add the contact typed with a domain in place of its pubkey, named
+    /// after the domain unless a name was typed, if it is still being typed.
+    fn domain_resolved(&mut self, domain: String, result: Result<Pubkey, String>) {
+        let Mode::ContactEntry(replacing, _) = self.mode else {
+            return;
+        };
+        let text = self.prompt_input.value().to_string();
+        let mut words = text.split_whitespace();
+        if words.next() != Some(domain.as_str()) {
+            return;
+        }
+        match result {
+            Ok(owner) => {
+                let rest = words.collect::<Vec<_>>();
+                let text = if rest.iter().any(|word| !word.starts_with('#')) {
+                    format!("{owner} {}", rest.join(" "))
+                } else {
+                    format!("{owner} {domain} {}", rest.join(" "))
+                };
+                self.add_contact(&text, replacing);
+            }
+            Err(err) => self.address_book_status = Some(err),
+        }
+    }
+
+    fn keypair_dir_entry_handle_key_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        match action {
+            Some(Action::Back) => self.return_from_overlay(),
+            Some(Action::Confirm) => {
+                let dir = PathBuf::from(self.prompt_input.value().trim());
+                self.prompt_input.reset();
+                self.address_book_status = Some(format!("Reading {}…", dir.display()));
+                self.commands.push_back(Command::ReadKeypairDir { dir });
+                self.return_from_overlay();
+            }
+            _ => {
+                self.prompt_input.handle_event(&Event::Key(key_event));
+            }
+        }
+    }
+
+    /// Leave the prompt, help or command palette for the mode it was opened
+    /// from.
+    fn return_from_overlay(&mut self) {
+        let mode = std::mem::replace(&mut self.mode, Mode::SignatureEntry);
+        self.mode = mode.into_return_mode();
+    }
+
+    fn save_address_book(&mut self) {
+        self.persist("address book", self.address_book.save());
+    }
+
+    fn save_settings(&mut self) {
+        self.persist("settings", self.settings.save());
+    }
+
+    /// Apply the change persisting the `name`d state in a command.
+    fn persist(&mut self, name: &'static str, change: io::Result<FileChange>) {
+        match change {
+            Ok(change) => self
+                .commands
+                .push_back(Command::ChangeFile { name, change }),
+            Err(err) => self.on_file_changed(name, Err(err.to_string())),
+        }
+    }
+
+    fn on_file_changed(&mut self, name: &'static str, result: Result<(), String>) {
+        // Failing to persist should not interrupt the session, though the
+        // address book reports it where contacts are edited, and bookmarks
+        // below the view.
+        match (name, result) {
+            ("address book", Err(err)) => {
+                self.address_book_status = Some(format!("Failed to save the address book: {err}"));
+            }
+            ("bookmarks", Err(err)) => {
+                self.file_status = Some(format!("Failed to save bookmarks: {err}"));
+            }
+            _ => {}
+        }
+    }
+
+    fn rpc_console_handle_key_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        let Mode::RpcConsole {
+            history_index,
+            response,
+            scroll,
+        } = &mut self.mode
+        else {
+            return;
+        };
+        let num_requests = self.rpc_history.num_requests();
+        let index = match action {
+            Some(Action::Back) => {
+                self.prompt_input.reset();
+                self.mode = Mode::SignatureEntry;
+                return;
+            }
+            Some(Action::Confirm) => {
+                let request = self.prompt_input.value().trim().to_string();
+                let (method, params) = match rpc_console::parse_request(&request) {
+                    Ok(parsed) => parsed,
+                    Err(err) => {
+                        *response = Some((request, Some(Err(err))));
+                        return;
+                    }
+                };
+                *history_index = None;
+                *response = Some((request.clone(), None));
+                *scroll = 0;
+                self.commands.push_back(Command::RpcCall {
+                    rpc: self.rpc(),
+                    method,
+                    params,
+                });
+                self.rpc_history.record(&request);
+                self.persist("RPC history", self.rpc_history.save());
+                self.prompt_input.reset();
+                return;
+            }
+            Some(Action::ScrollUp) => {
+                *scroll = scroll.saturating_sub(1);
+                return;
+            }
+            Some(Action::ScrollDown) => {
+                *scroll += 1;
+                return;
+            }
+            Some(Action::Previous) if num_requests > 0 => {
+                Some(history_index.unwrap_or(num_requests).saturating_sub(1))
+            }
+            Some(Action::Next) => history_index
+                .map(|index| index + 1)
+                .filter(|index| *index < num_requests),
+            _ => {
+                self.prompt_input.handle_event(&Event::Key(key_event));
+                return;
+            }
+        };
+        // Going past the most recent request clears the input.
+        *history_index = index;
+        let request = index.and_then(|index| self.rpc_history.get(index));
+        self.prompt_input = Input::new(request.unwrap_or_default().to_string());
+    }
+
+    fn submission_time_entry_handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        action: Option<Action>,
+    ) {
+        match action {
+            Some(Action::Back) => self.mode = Mode::SlotTiming(None),
+            Some(Action::Confirm) => {
+                let submitted_at = slot_timing::parse_submission_time(self.prompt_input.value());
+                self.prompt_input.reset();
+                let status = match (submitted_at, self.loaded()) {
+                    (Err(err), _) => Some(err),
+                    (Ok(_), None) => None,
+                    (Ok(submitted_at), Some(loaded)) => {
+                        let signature = loaded.signature;
+                        let attempt = Attempt {
+                            submitted_at,
+                            endpoint: None,
+                            submitted_slot: None,
+                            landed_slot: loaded.slot,
+                            landed_at: loaded.block_time.map(|block_time| block_time * 1000),
+                        };
+                        self.slot_timings.record(&signature, attempt);
+                        self.persist("slot timings", self.slot_timings.save());
+                        None
+                    }
+                };
+                self.mode = Mode::SlotTiming(status);
+            }
+            _ => {
+                self.prompt_input.handle_event(&Event::Key(key_event));
+            }
+        }
+    }
+
+    fn ledger_slot_entry_handle_key_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        match action {
+            Some(Action::Back) => self.mode = Mode::SignatureEntry,
+            Some(Action::Confirm) => {
+                let Ok(slot) = self.prompt_input.value().trim().parse() else {
+                    return;
+                };
+                self.prompt_input.reset();
+                self.mode = Mode::SignatureEntry;
+                self.commands.push_back(Command::FetchSlotEntries {
+                    rpc: self.rpc(),
+                    slot,
+                });
+            }
+            _ => {
+                self.prompt_input.handle_event(&Event::Key(key_event));
+            }
+        }
+    }
+
+    /// Offer the transactions of an entries blob in the entry picker.
+    fn show_entries(&mut self, source: String, blob: Result<Vec<u8>, String>) {
+        match blob.and_then(|blob| entries::parse_entries(&blob)) {
+            Ok(located) if located.is_empty() => {
+                self.screen = Screen::Error(format!("{source} holds no transactions"));
+                self.mode = Mode::SignatureEntry;
+            }
+            Ok(located) => {
+                self.entries_source = source;
+                self.entry_transactions = located;
+                self.mode = Mode::EntryPicker(0);
+            }
+            Err(err) => {
+                self.screen = Screen::Error(err);
+                self.mode = Mode::SignatureEntry;
+            }
+        }
+    }
+
+    fn stats_handle_action(&mut self, action: Option<Action>) {
+        let Mode::Stats(budget, selected) = &mut self.mode else {
+            return;
+        };
+        let last = byte_budget::LABELS.len() - 1;
+        match action {
+            Some(Action::Back) => self.mode = Mode::ByteNavigation,
+            Some(Action::Previous) => *selected = selected.saturating_sub(1),
+            Some(Action::Next) => *selected = (*selected + 1).min(last),
+            Some(Action::Increase) => budget.adjust(*selected, 1),
+            Some(Action::Decrease) => budget.adjust(*selected, -1),
+            Some(Action::IncreaseMore) => budget.adjust(*selected, 10),
+            Some(Action::DecreaseMore) => budget.adjust(*selected, -10),
+            _ => {}
+        }
+    }
+
+    fn strings_handle_action(&mut self, action: Option<Action>, selected: usize) {
+        let strings = strings::extract(self.loaded_sections());
+        let last = strings.len().saturating_sub(1);
+        match action {
+            Some(Action::Back) => self.mode = Mode::ByteNavigation,
+            Some(Action::Previous) => self.mode = Mode::Strings(selected.saturating_sub(1)),
+            Some(Action::Next) => self.mode = Mode::Strings((selected + 1).min(last)),
+            Some(Action::Confirm) => {
+                if let Some(string) = strings.get(selected) {
+                    self.cursor = string.offset;
+                    self.selection_anchor = None;
+                    self.mode = Mode::ByteNavigation;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn entry_picker_handle_action(&mut self, action: Option<Action>, selected: usize) {
+        let last = self.entry_transactions.len().saturating_sub(1);
+        match action {
+            Some(Action::Back) => self.mode = Mode::SignatureEntry,
+            Some(Action::Previous) => self.mode = Mode::EntryPicker(selected.saturating_sub(1)),
+            Some(Action::Next) => self.mode = Mode::EntryPicker((selected + 1).min(last)),
+            Some(Action::Confirm) => {
+                self.open_entry_transaction(selected);
+                self.mode = Mode::ByteNavigation;
+            }
+            _ => {}
+        }
+    }
+
+    /// View the transaction of the entries file at `index`.
+    fn open_entry_transaction(&mut self, index: usize) {
+        let Some(located) = self.entry_transactions.get(index) else {
+            return;
+        };
+        let transaction = located.transaction.clone();
+        let mut sections = vec![];
+        get_transaction_byte_sections(&transaction, &mut sections);
+        let source = format!(
+            "{} entry {} transaction {} at offset {:#x}",
+            self.entries_source, located.entry_index, located.index, located.offset
+        );
+        self.remember_view_position();
+        self.screen = Screen::Viewing(LoadedTransaction {
+            signature: transaction.signatures.first().copied().unwrap_or_default(),
+            transaction,
+            meta: None,
+            slot: None,
+            block_time: None,
+            sections,
+            source: Some(source),
+            endpoint: None,
+            fetch_options: None,
+            accounts: BTreeMap::new(),
+        });
+        self.restore_view_position();
+        self.start_reveal();
+        self.label_pdas();
+        self.resolve_ownership();
+        self.look_up_programs();
+        self.resolve_domains();
+    }
+
+    fn command_palette_handle_key_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        let Mode::CommandPalette(palette) = &mut self.mode else {
+            return;
+        };
+        match action {
+            Some(Action::Back) => self.return_from_overlay(),
+            Some(Action::Previous) => palette.selected = palette.selected.saturating_sub(1),
+            Some(Action::Next) => {
+                let num_commands = palette.commands().len();
+                palette.selected = (palette.selected + 1).min(num_commands.saturating_sub(1));
+            }
+            Some(Action::Confirm) => {
+                let Some((_, command)) = palette.commands().into_iter().nth(palette.selected)
+                else {
+                    return;
+                };
+                match command {
+                    PaletteCommand::Key(binding) if palette.is_available(binding) => {
+                        // Run the command as if its key was pressed in the
+                        // mode the palette was opened from.
+                        self.return_from_overlay();
+                        self.handle_key_event(KeyEvent::new(binding.code, binding.modifiers));
+                    }
+                    PaletteCommand::Key(binding) => {
+                        palette.status =
+                            Some(format!("Only available in {}", binding.context.name()));
+                    }
+                    PaletteCommand::Menu(item) => {
+                        self.return_from_overlay();
+                        self.perform_menu_item(item);
+                    }
+                }
+            }
+            _ => {
+                palette.query.handle_event(&Event::Key(key_event));
+                palette.selected = 0;
+                palette.status = None;
+            }
+        }
+    }
+
+    fn signature_entry_handle_key_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        match action {
+            Some(Action::Exit) => self.exit(),
+            Some(Action::Confirm) => self.on_signature_entry(),
+            Some(Action::InspectBytes) if self.num_bytes() > 0 => self.mode = Mode::ByteNavigation,
+            Some(Action::TrackCommitment) => self.track_commitment_timeline(),
+            Some(Action::WaitForLanding) => self.wait_for_landing(),
+            Some(Action::FollowAddress) => self.follow_address(),
+            Some(Action::ShowFetchOptions) => self.mode = Mode::FetchOptions(0),
+            Some(Action::ShowBookmarks) => {
+                self.mode = Mode::Bookmarks {
+                    filter: Input::default(),
+                    selected: 0,
+                }
+            }
+            Some(Action::ShowFind) => {
+                self.commands.push_back(Command::LoadSnapshots {
+                    snapshots: self.snapshots.clone(),
+                    signatures: self
+                        .bookmarks
+                        .filtered("")
+                        .into_iter()
+                        .map(|(signature, _)| signature)
+                        .collect(),
+                });
+                self.mode = Mode::Find(Box::new(FindView {
+                    query: Input::default(),
+                    hits: Ok(vec![]),
+                    snapshots: vec![],
+                    selected: 0,
+                }));
+            }
+            Some(Action::ShowWatch) => self.start_watch(),
+            Some(Action::OpenRpcConsole) => {
+                self.prompt_input.reset();
+                self.mode = Mode::RpcConsole {
+                    history_index: None,
+                    response: None,
+                    scroll: 0,
+                };
+            }
+            Some(Action::OpenMenu) => {
+                self.mode = Mode::MenuSelection;
+                self.menu.get_mut().activate()
+            }
+            // Without a history there is nothing to recall.
+            Some(Action::Previous) if self.input_history.num_inputs() == 0 => {}
+            Some(Action::Previous) => {
+                // The oldest input stays once reached.
+                let index = self
+                    .input_history_index
+                    .unwrap_or(self.input_history.num_inputs())
+                    .saturating_sub(1);
+                self.recall_input(Some(index));
+            }
+            Some(Action::Next) if self.input_history_index.is_some() => {
+                let num_inputs = self.input_history.num_inputs();
+                // Going past the most recent input clears it.
+                let index = self
+                    .input_history_index
+                    .map(|index| index + 1)
+                    .filter(|index| *index < num_inputs);
+                self.recall_input(index);
+            }
+            _ => {
+                self.input.handle_event(&Event::Key(key_event));
+            }
+        }
+    }
+
+    fn help_handle_key_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        let Mode::Help(help) = &mut self.mode else {
+            return;
+        };
+        match action {
+            Some(Action::Back) => self.return_from_overlay(),
+            Some(Action::Previous) => help.scroll = help.scroll.saturating_sub(1),
+            Some(Action::Next) => help.scroll += 1,
+            _ => {
+                help.query.handle_event(&Event::Key(key_event));
+                help.scroll = 0;
+            }
+        }
+    }
+
+    /// Start tracking the entered signature across commitment levels, or
+    /// show the existing timeline if no signature is entered.
+    fn track_commitment_timeline(&mut self) {
+        let text = self.input.value().trim();
+        if !text.is_empty() {
+            let Ok(signature) = Signature::from_str(text) else {
+                self.screen = Screen::Error("Invalid signature".to_string());
+                return;
+            };
+            self.commitment_timeline = Some(CommitmentTimeline::new(signature));
+            self.commands.push_back(Command::PollCommitment {
+                rpc: self.rpc(),
+                signature,
+                delay: Duration::ZERO,
+            });
+            self.input.reset();
+        }
+        if self.commitment_timeline.is_some() {
+            self.mode = Mode::CommitmentTimeline;
+        }
+    }
+
+    /// Poll the status of the transaction which was not found until it
+    /// lands.
+    fn wait_for_landing(&mut self) {
+        let Screen::NotFound(signature) = self.screen else {
+            return;
+        };
+        self.commands.push_back(Command::PollLanding {
+            rpc: self.rpc(),
+            signature,
+            delay: Duration::ZERO,
+        });
+        self.screen = Screen::Landing(Landing::new(signature));
+    }
+
+    fn on_landing_polled(
+        &mut self,
+        signature: Signature,
+        result: Result<Option<TransactionStatus>, String>,
+    ) {
+        // Another signature may have been entered while waiting.
+        let Screen::Landing(landing) = &mut self.screen else {
+            return;
+        };
+        if landing.signature != signature {
+            return;
+        }
+        match result {
+            Ok(Some(status)) if landing::is_queryable(&status) => {
+                self.fetch_transaction(signature);
+                return;
+            }
+            Ok(status) => landing.record(landing::describe(status.as_ref())),
+            Err(err) => landing.record(format!("request failed: {err}")),
+        }
+        if landing.is_timed_out() {
+            self.screen = Screen::Error(format!("{signature} did not land"));
+            return;
+        }
+        self.commands.push_back(Command::PollLanding {
+            rpc: self.rpc_source.api(self.endpoint),
+            signature,
+            delay: landing.next_delay(),
+        });
+    }
+
+    /// Follow the latest transaction of the entered address, or stop
+    /// following if no address is entered.
+    fn follow_address(&mut self) {
+        let text = self.input.value().trim();
+        if text.is_empty() {
+            self.follow = None;
+            return;
+        }
+        let Ok(address) = Pubkey::from_str(text) else {
+            self.screen = Screen::Error("Invalid address".to_string());
+            return;
+        };
+        self.input.reset();
+        self.follow = Some(Follow::new(address));
+        self.commands.push_back(Command::PollLatest {
+            rpc: self.rpc(),
+            address,
+            delay: Duration::ZERO,
+        });
+    }
+
+    fn on_latest_polled(&mut self, address: Pubkey, result: Result<Option<Latest>, String>) {
+        // Following may have stopped, or moved to another address.
+        let Some(follow) = self
+            .follow
+            .as_mut()
+            .filter(|follow| follow.address == address)
+        else {
+            return;
+        };
+        if let Some(signature) = follow.record(result) {
+            self.remember_view_position();
+            self.fetch_transaction(signature);
+        }
+        self.commands.push_back(Command::PollLatest {
+            rpc: self.rpc(),
+            address,
+            delay: follow::POLL_INTERVAL,
+        });
+    }
+
+    fn on_commitment_polled(&mut self, signature: Signature, poll: CommitmentPoll) {
+        // Ignore polls of a timeline which has since been replaced.
+        let Some(commitment_timeline) = self
+            .commitment_timeline
+            .as_mut()
+            .filter(|commitment_timeline| commitment_timeline.signature == signature)
+        else {
+            return;
+        };
+        commitment_timeline.apply(poll);
+        if commitment_timeline.is_done() {
+            let elapsed = commitment_timeline.elapsed();
+            self.finish_long_operation("Commitment timeline finished", elapsed);
+        } else {
+            self.commands.push_back(Command::PollCommitment {
+                rpc: self.rpc(),
+                signature,
+                delay: commitment_timeline::POLL_INTERVAL,
+            });
+        }
+    }
+
+    /// Signal the completion of an operation which may have taken long
+    /// enough for the terminal to be sent to the background.
+    fn finish_long_operation(&mut self, description: &str, elapsed: Duration) {
+        if elapsed < LONG_OPERATION {
+            return;
+        }
+        // Focus changes during a blocking operation are still queued.
+        while let Ok(true) = event::poll(Duration::ZERO) {
+            match event::read() {
+                Ok(Event::FocusGained) => self.focused = true,
+                Ok(Event::FocusLost) => self.focused = false,
+                Ok(event) => self.pending_events.push_back(event),
+                Err(_) => break,
+            }
+        }
+        if self.focused {
+            return;
+        }
+        let _ = tui::set_title(&format!("{description} | {}", tui::APP_TITLE));
+        if self.settings.desktop_notifications {
+            let _ = tui::notify(description);
+        }
+    }
+
+    fn menu_selection_handle_action(&mut self, action: Option<Action>) {
+        match action {
+            Some(Action::Back) => {
+                self.mode = Mode::SignatureEntry;
+                self.menu.get_mut().reset();
+            }
+            Some(Action::Confirm) => self.menu.get_mut().select(),
+            Some(Action::Previous) => self.menu.get_mut().up(),
+            Some(Action::Next) => self.menu.get_mut().down(),
+            Some(Action::MenuLeft) => self.menu.get_mut().left(),
+            Some(Action::MenuRight) => self.menu.get_mut().right(),
+            _ => {}
+        }
+
+        for event in self.menu.get_mut().drain_events() {
+            match event {
+                MenuEvent::Selected(item) => {
+                    self.mode = Mode::SignatureEntry;
+                    self.menu.get_mut().reset();
+                    self.perform_menu_item(item);
+                }
+            }
+        }
+    }
+
+    fn perform_menu_item(&mut self, item: AppMenuItem) {
+        match item {
+            AppMenuItem::Endpoint(endpoint) => {
+                self.switch_endpoint(endpoint);
+                let is_stale = self
+                    .loaded()
+                    .and_then(|loaded| loaded.endpoint)
+                    .is_some_and(|fetched_from| fetched_from != endpoint);
+                if is_stale && self.rpc_source.uses_endpoint() {
+                    self.mode = Mode::ConfirmRefetch;
+                }
+            }
+            AppMenuItem::LoadEntriesFile => {
+                self.prompt_input.reset();
+                self.mode = Mode::EntriesFileEntry;
+            }
+            AppMenuItem::OpenVarintPlayground => {
+                self.prompt_input.reset();
+                self.mode = Mode::VarintPlayground;
+            }
+            AppMenuItem::OpenConverter => {
+                self.prompt_input.reset();
+                self.mode = Mode::Converter;
+            }
+            AppMenuItem::OpenSignatureCheck => {
+                self.prompt_input.reset();
+                self.mode = Mode::SignatureCheck;
+            }
+            AppMenuItem::LoadLedgerSlot => {
+                self.prompt_input.reset();
+                self.mode = Mode::LedgerSlotEntry;
+            }
+            AppMenuItem::LoadKeypairFile => self.start_keypair_entry(KeypairPrompt::File),
+            AppMenuItem::EnterSeedPhrase => self.start_keypair_entry(KeypairPrompt::SeedPhrase),
+            AppMenuItem::ForgetKeypairs => self.keypairs.clear(),
+            AppMenuItem::EditAddressBook => {
+                self.address_book_status = None;
+                self.mode = Mode::AddressBook(0);
+            }
+            AppMenuItem::ImportKeypairDirectory => {
+                self.start_keypair_dir_entry(Mode::AddressBook(0))
+            }
+            AppMenuItem::ToggleDesktopNotifications => {
+                self.settings.desktop_notifications = !self.settings.desktop_notifications;
+            }
+            AppMenuItem::CycleDigitGrouping => {
+                self.settings.digit_grouping = self.settings.digit_grouping.next();
+            }
+            AppMenuItem::ToggleAmountUnit => {
+                self.settings.amount_unit = match self.settings.amount_unit {
+                    AmountUnit::Sol => AmountUnit::Lamports,
+                    AmountUnit::Lamports => AmountUnit::Sol,
+                };
+            }
+            AppMenuItem::ToggleMonochrome => {
+                self.settings.monochrome = !self.settings.monochrome;
+            }
+            AppMenuItem::ToggleRevealOnLoad => {
+                self.settings.reveal_on_load = !self.settings.reveal_on_load;
+            }
+            AppMenuItem::CyclePubkeyDisplay => {
+                self.settings.pubkey_display = self.settings.pubkey_display.next();
+            }
+            AppMenuItem::ToggleTimeZone => {
+                self.settings.time_zone = match self.settings.time_zone {
+                    TimeZone::Utc => TimeZone::Local,
+                    TimeZone::Local => TimeZone::Utc,
+                };
+            }
+        }
+        if item.is_setting() {
+            self.save_settings();
+        }
+    }
+
+    /// Move the byte cursor. Returns false if the action is not a movement.
+    fn move_cursor(&mut self, action: Action) -> bool {
+        let first_byte = self.view_sections(self.cursor_sections()).0;
+        let last_byte = self.num_bytes().saturating_sub(1);
+        match action {
+            Action::CursorLeft => self.cursor = self.cursor.saturating_sub(1),
+            Action::CursorRight => self.cursor = (self.cursor + 1).min(last_byte),
+            Action::CursorUp => self.cursor = self.cursor.saturating_sub(self.bytes_per_line),
+            Action::CursorDown => self.cursor = (self.cursor + self.bytes_per_line).min(last_byte),
+            Action::CursorFirst => self.cursor = first_byte,
+            Action::CursorLast => self.cursor = last_byte,
+            Action::PreviousSection
+            | Action::NextSection
+            | Action::PreviousInstruction
+            | Action::NextInstruction
+            | Action::JumpToSignatures
+            | Action::JumpToHeader
+            | Action::JumpToAccountKeys
+            | Action::JumpToInstructions
+            | Action::JumpToLookupTables => {
+                if let Some(landmark) = self.landmark(action) {
+                    self.cursor = landmark;
+                }
+            }
+            _ => return false,
+        }
+        self.cursor = self
+            .cursor
+            .max(self.view_sections(self.cursor_sections()).0);
+        true
+    }
+
+    /// The offset a landmark action moves the cursor to: the start of the
+    /// previous or next section or instruction, or of a region.
+    fn landmark(&self, action: Action) -> Option<usize> {
+        let sections = self.cursor_sections();
+        let instruction_starts = || {
+            let mut previous_instruction = None;
+            section_starts(sections).filter_map(move |(start, section)| {
+                let instruction = section.kind.instruction_index();
+                let is_start = instruction.is_some() && instruction != previous_instruction;
+                previous_instruction = instruction;
+                is_start.then_some(start)
+            })
+        };
+        let region_start = |category| {
+            section_starts(sections)
+                .find(|(_, section)| section.kind.category() == category)
+                .map(|(start, _)| start)
+        };
+        let cursor = self.cursor;
+        match action {
+            Action::PreviousSection => section_starts(sections)
+                .map(|(start, _)| start)
+                .filter(|start| *start < cursor)
+                .last(),
+            Action::NextSection => section_starts(sections)
+                .map(|(start, _)| start)
+                .find(|start| *start > cursor),
+            Action::PreviousInstruction => {
+                instruction_starts().filter(|start| *start < cursor).last()
+            }
+            Action::NextInstruction => instruction_starts().find(|start| *start > cursor),
+            Action::JumpToSignatures => region_start(SectionCategory::Signatures),
+            Action::JumpToHeader => region_start(SectionCategory::Header),
+            Action::JumpToAccountKeys => region_start(SectionCategory::AccountKeys),
+            Action::JumpToInstructions => region_start(SectionCategory::Instructions),
+            Action::JumpToLookupTables => region_start(SectionCategory::LookupTables),
+            _ => None,
+        }
+    }
+
+    fn byte_navigation_handle_action(&mut self, action: Option<Action>) {
+        let Some(action) = action else {
+            return;
+        };
+        if self.move_cursor(action) {
+            return;
+        }
+        match action {
+            Action::Back => {
+                self.selection_anchor = None;
+                self.mode = Mode::SignatureEntry;
+            }
+            Action::ToggleMessageView => self.toggle_message_view(),
+            Action::ToggleVoteSummary => self.vote_bytes = !self.vote_bytes,
+            Action::Refetch => self.refetch(),
+            Action::StartEdit => {
+                if let Some(loaded) = self.loaded() {
+                    self.start_edit(loaded.transaction.clone());
+                }
+            }
+            Action::DiffTransaction if matches!(self.screen, Screen::Viewing(_)) => {
+                self.prompt_input.reset();
+                self.mode = Mode::DiffSignatureEntry;
+            }
+            Action::ToggleSelection => {
+                self.selection_anchor = match self.selection_anchor {
+                    Some(_) => None,
+                    None => Some(self.cursor),
+                };
+            }
+            Action::Annotate if self.current_signature().is_some() => {
+                self.prompt_input.reset();
+                self.mode = Mode::AnnotationEntry;
+            }
+            Action::RemoveAnnotation => {
+                if let Some(signature) = self.current_signature() {
+                    if self
+                        .bookmarks
+                        .remove_annotations_at(&signature, self.cursor)
+                    {
+                        self.save_bookmarks();
+                    }
+                }
+            }
+            Action::DeriveAddresses => {
+                self.prompt_input.reset();
+                self.mode = Mode::Derivation;
+            }
+            Action::EditTags => {
+                if let Some(signature) = self.current_signature() {
+                    self.start_tag_entry(signature, Mode::ByteNavigation);
+                }
+            }
+            Action::ToggleBookmark => {
+                if let Some(signature) = self.current_signature() {
+                    if self.bookmarks.toggle(&signature) {
+                        self.save_snapshot();
+                    } else {
+                        if let Some(change) = self.snapshots.remove(&signature) {
+                            self.persist("snapshot", Ok(change));
+                        }
+                    }
+                    self.save_bookmarks();
+                }
+            }
+            Action::DecodeWrappedTransaction => {
+                if let Some(decoded) = self.decode_instruction_at_cursor() {
+                    self.show_inner_transaction(decoded);
+                }
+            }
+            Action::MapSensitivity => {
+                if let Some(loaded) = self.loaded() {
+                    self.commands.push_back(Command::MapSensitivity {
+                        signature: loaded.signature,
+                        transaction: Box::new(loaded.transaction.clone()),
+                    });
+                    self.mode = Mode::SensitivityMap {
+                        sensitivity_map: None,
+                        scroll: 0,
+                    };
+                }
+            }
+            Action::ShowStrings if self.loaded().is_some() => self.mode = Mode::Strings(0),
+            Action::ShowStats if self.loaded().is_some() => {
+                self.mode = Mode::Stats(ByteBudget::default(), 0)
+            }
+            Action::CycleZoom => {
+                self.settings.zoom = self.settings.zoom.next();
+                self.save_settings();
+            }
+            Action::TogglePresentation => self.presentation = !self.presentation,
+            Action::ExportLegend => self.export_legend(),
+            Action::ExportGraph => self.export_graph(),
+            Action::ExportByteTags => self.export_byte_tags(),
+            Action::StartJump => self.mode = Mode::Jump,
+            Action::ToggleInstructionOverlay => {
+                self.instruction_overlay = !self.instruction_overlay
+            }
+            Action::CycleLegendOrder => {
+                self.settings.legend_order = self.settings.legend_order.next();
+                self.save_settings();
+            }
+            Action::CycleColoring => {
+                self.settings.coloring = self.settings.coloring.next();
+                self.save_settings();
+            }
+            Action::ExportBundle => self.export_bundle(),
+            Action::ShowSlotTiming if self.loaded().is_some() => self.mode = Mode::SlotTiming(None),
+            Action::NameAccount => {
+                let Some(pubkey) = section_at(self.cursor_sections(), self.cursor)
+                    .and_then(TransactionByteSection::pubkey)
+                else {
+                    self.file_status = Some("Move the cursor onto an account key".to_string());
+                    return;
+                };
+                match self.address_book.get(&pubkey) {
+                    Some(contact) => {
+                        let text = address_book::contact_text(&pubkey, contact);
+                        self.start_contact_entry(Some(pubkey), text, Mode::ByteNavigation)
+                    }
+                    None => {
+                        self.start_contact_entry(None, format!("{pubkey} "), Mode::ByteNavigation)
+                    }
+                }
+            }
+            Action::ShowAccessMatrix => self.mode = Mode::AccessMatrix(0, 0),
+            Action::ShowMevContext => {
+                let Some((slot, signature)) = self
+                    .loaded()
+                    .and_then(|loaded| Some((loaded.slot?, loaded.signature)))
+                else {
+                    self.file_status = Some("The slot of the transaction is unknown".to_string());
+                    return;
+                };
+                self.commands.push_back(Command::FetchMevContext {
+                    rpc: self.rpc(),
+                    slot,
+                    signature,
+                });
+                self.mode = Mode::Sandwich {
+                    mev_context: None,
+                    selected: 0,
+                };
+            }
+            Action::ShowRelated => {
+                let Some(address) = section_at(self.cursor_sections(), self.cursor)
+                    .and_then(TransactionByteSection::pubkey)
+                else {
+                    self.file_status = Some("Move the cursor onto an account key".to_string());
+                    return;
+                };
+                if self.loaded().and_then(|loaded| loaded.slot).is_none() {
+                    self.file_status = Some("The slot of the transaction is unknown".to_string());
+                    return;
+                }
+                self.fetch_related(address, related::DEFAULT_WINDOW, FailureFilter::default());
+            }
+            Action::ShowLeaders => {
+                self.commands.push_back(Command::FetchLeaders {
+                    rpc: self.rpc(),
+                    landed_slot: self.loaded().and_then(|loaded| loaded.slot),
+                });
+                self.mode = Mode::Leaders(None);
+            }
+            Action::ShowInstructionsSysvar if self.loaded().is_some() => {
+                self.mode = Mode::InstructionsSysvar(0);
+            }
+            Action::ComparePreflight => self.compare_preflight(),
+            Action::Narrate if self.loaded().is_some() => {
+                self.file_status = None;
+                self.mode = Mode::Narration(0);
+            }
+            Action::PlanLookupTable if self.loaded().is_some() => {
+                self.selection_anchor = None;
+                self.plan_lookup_table();
+            }
+            _ => {}
+        }
+    }
+
+    fn annotation_entry_handle_key_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        match action {
+            Some(Action::Back) => self.mode = Mode::ByteNavigation,
+            Some(Action::Confirm) => {
+                let note = self.prompt_input.value().trim().to_string();
+                if let (Some(signature), false) = (self.current_signature(), note.is_empty()) {
+                    let range = self.selection();
+                    self.bookmarks.annotate(
+                        &signature,
+                        Annotation {
+                            start: range.start,
+                            end: range.end,
+                            note,
+                        },
+                    );
+                    self.save_bookmarks();
+                }
+                self.prompt_input.reset();
+                self.selection_anchor = None;
+                self.mode = Mode::ByteNavigation;
+            }
+            _ => {
+                self.prompt_input.handle_event(&Event::Key(key_event));
+            }
+        }
+    }
+
+    /// Fetch the entered transaction to compare the viewed one with.
+    fn start_diff(&mut self) {
+        let text = self.prompt_input.value().trim().to_string();
+        self.prompt_input.reset();
+        let Screen::Viewing(loaded) = std::mem::replace(&mut self.screen, Screen::Input) else {
+            return;
+        };
+        let compared = match self.parse_signature(&text) {
+            Ok(signature) => {
+                self.commands.push_back(Command::FetchTransaction {
+                    rpc: self.rpc(),
+                    signature,
+                    options: self.fetch_options,
+                });
+                Compared::Fetching(signature)
+            }
+            Err(err) => Compared::Failed(err),
+        };
+        self.screen = Screen::Diff {
+            loaded,
+            compared,
+            byte_scroll: 0,
+        };
+        self.mode = Mode::Diff;
+    }
+
+    fn diff_handle_action(&mut self, action: Option<Action>) {
+        let Screen::Diff { byte_scroll, .. } = &mut self.screen else {
+            return;
+        };
+        match action {
+            Some(Action::Back) => {
+                if let Screen::Diff { loaded, .. } =
+                    std::mem::replace(&mut self.screen, Screen::Input)
+                {
+                    self.screen = Screen::Viewing(loaded);
+                }
+                self.mode = Mode::ByteNavigation;
+            }
+            Some(Action::Previous) => *byte_scroll = byte_scroll.saturating_sub(1),
+            Some(Action::Next) => *byte_scroll += 1,
+            _ => {}
+        }
+    }
+
+    fn edit_handle_action(&mut self, action: Option<Action>) {
+        let Some(action) = action else {
+            return;
+        };
+        if self.move_cursor(action) {
+            return;
+        }
+        if action == Action::Back {
+            self.stop_edit();
+            self.cursor = self.cursor.min(self.num_bytes().saturating_sub(1));
+            self.mode = Mode::ByteNavigation;
+            return;
+        }
+        let Some(kind) = self.cursor_section_kind() else {
+            return;
+        };
+        match action {
+            Action::Undo => self.undo_redo(EditSession::undo, "Undid"),
+            Action::Redo => self.undo_redo(EditSession::redo, "Redid"),
+            Action::ToggleMessageView => self.toggle_message_view(),
+            Action::RefreshBlockhash => self.refresh_blockhash(),
+            Action::ClearSignatures => self.apply_edit(EditSession::clear_signatures),
+            Action::StartHexWrite => self.mode = Mode::HexWrite(None),
+            Action::Sign => self.sign_with_loaded_keypairs(),
+            Action::BroadcastTransaction => self.broadcast_edited(),
+            Action::Simulate => {
+                let Some(edit_session) = self.edit_session() else {
+                    return;
+                };
+                self.commands.push_back(Command::Simulate {
+                    rpc: self.rpc(),
+                    transaction: Box::new(edit_session.edited().clone()),
+                });
+                self.mode = Mode::Simulation {
+                    simulation: None,
+                    selected: 0,
+                    scroll: 0,
+                };
+            }
+            Action::LoadKeypair => self.start_keypair_entry(KeypairPrompt::File),
+            Action::SignWithLedger => {
+                self.mode = Mode::LedgerSigning(LedgerState::SelectingPath(0));
+            }
+            Action::DeleteInstruction => {
+                let Some(index) = kind.instruction_index() else {
+                    self.set_edit_status("Cursor is not on an instruction".to_string());
+                    return;
+                };
+                self.apply_edit(|edit_session| edit_session.remove_instruction(index));
+                self.cursor = self.cursor.min(self.num_bytes().saturating_sub(1));
+            }
+            Action::ReplaceAccountKey => {
+                let SectionKind::StaticAccountKey(index) = kind else {
+                    self.set_edit_status("Cursor is not on an account key".to_string());
+                    return;
+                };
+                self.prompt_input.reset();
+                self.mode = Mode::EditValueEntry(EditField::AccountKey(index));
+            }
+            Action::ReplaceInstructionData => {
+                let Some(index) = kind.instruction_index() else {
+                    self.set_edit_status("Cursor is not on an instruction".to_string());
+                    return;
+                };
+                self.prompt_input.reset();
+                self.mode = Mode::EditValueEntry(EditField::InstructionData(index));
+            }
+            Action::SetComputeUnitLimit => {
+                let Some(edit_session) = self.edit_session() else {
+                    return;
+                };
+                let compute_budget = ComputeBudget::from_message(&edit_session.edited().message);
+                self.prompt_input = Input::new(compute_budget.unit_limit.to_string());
+                self.mode = Mode::EditValueEntry(EditField::ComputeUnitLimit);
+            }
+            Action::SetComputeUnitPrice => {
+                let Some(edit_session) = self.edit_session() else {
+                    return;
+                };
+                let compute_budget = ComputeBudget::from_message(&edit_session.edited().message);
+                self.prompt_input = Input::new(compute_budget.unit_price.to_string());
+                self.mode = Mode::EditValueEntry(EditField::ComputeUnitPrice);
+            }
+            Action::DataForm | Action::LoadSchema => {
+                let Some(index) = kind.instruction_index() else {
+                    self.set_edit_status("Cursor is not on an instruction".to_string());
+                    return;
+                };
+                match &mut self.screen {
+                    Screen::Edit {
+                        template_form: Some(template_form),
+                        ..
+                    } if action == Action::DataForm => {
+                        template_form.select_instruction(template_form.instruction_index);
+                        self.mode = Mode::Template(index);
+                    }
+                    _ => {
+                        self.prompt_input.reset();
+                        self.mode = Mode::EditValueEntry(EditField::SchemaFile(index));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn hex_write_handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        action: Option<Action>,
+        high_nibble: Option<u8>,
+    ) {
+        if let Some(action) = action {
+            if action == Action::Back {
+                self.mode = Mode::Edit;
+            } else if self.move_cursor(action) {
+                self.mode = Mode::HexWrite(None);
+            }
+            return;
+        }
+        let KeyCode::Char(c) = key_event.code else {
+            return;
+        };
+        let Some(nibble) = c.to_digit(16) else {
+            return;
+        };
+        let nibble = nibble as u8;
+        let Some(high_nibble) = high_nibble else {
+            self.mode = Mode::HexWrite(Some(nibble));
+            return;
+        };
+        let offset = self.cursor;
+        self.apply_edit(|edit_session| edit_session.set_byte(offset, high_nibble << 4 | nibble));
+        self.mode = Mode::HexWrite(None);
+        if matches!(self.screen, Screen::Edit { status: None, .. }) {
+            self.move_cursor(Action::CursorRight);
+        }
+    }
+
+    fn edit_value_entry_handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        action: Option<Action>,
+        field: EditField,
+    ) {
+        match action {
+            Some(Action::Back) => self.mode = Mode::Edit,
+            Some(Action::Confirm) => {
+                let value = self.prompt_input.value().trim().to_string();
+                match field {
+                    EditField::AccountKey(index) => match Pubkey::from_str(&value) {
+                        Ok(pubkey) => self.apply_edit(|edit_session| {
+                            edit_session.replace_account_key(index, pubkey)
+                        }),
+                        Err(_) => self.set_edit_status(format!("Invalid pubkey: {value}")),
+                    },
+                    EditField::InstructionData(index) => match hex::decode(&value) {
+                        Ok(data) => self.apply_edit(|edit_session| {
+                            edit_session.set_instruction_data(index, data)
+                        }),
+                        Err(err) => self.set_edit_status(format!("Invalid hex: {err}")),
+                    },
+                    EditField::ComputeUnitLimit => match value.parse() {
+                        Ok(units) => self
+                            .apply_edit(|edit_session| edit_session.set_compute_unit_limit(units)),
+                        Err(err) => self.set_edit_status(format!("Invalid limit: {err}")),
+                    },
+                    EditField::ComputeUnitPrice => match value.parse() {
+                        Ok(micro_lamports) => self.apply_edit(|edit_session| {
+                            edit_session.set_compute_unit_price(micro_lamports)
+                        }),
+                        Err(err) => self.set_edit_status(format!("Invalid price: {err}")),
+                    },
+                    EditField::SchemaFile(index) => {
+                        self.set_edit_status(format!("Loading {value}…"));
+                        self.commands
+                            .push_back(Command::LoadSchema { path: value, index });
+                    }
+                }
+                self.prompt_input.reset();
+                self.mode = Mode::Edit;
+            }
+            _ => {
+                self.prompt_input.handle_event(&Event::Key(key_event));
+            }
+        }
+    }
+
+    fn template_handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        action: Option<Action>,
+        index: usize,
+    ) {
+        let Screen::Edit {
+            status,
+            template_form: Some(template_form),
+            ..
+        } = &mut self.screen
+        else {
+            self.mode = Mode::Edit;
+            return;
+        };
+        match action {
+            Some(Action::Back) => self.mode = Mode::Edit,
+            Some(Action::Previous) => template_form.focus = template_form.focus.saturating_sub(1),
+            Some(Action::Next) => {
+                template_form.focus =
+                    (template_form.focus + 1).min(template_form.inputs.len().saturating_sub(1))
+            }
+            Some(Action::PreviousInstruction) => {
+                template_form.select_instruction(template_form.instruction_index.saturating_sub(1))
+            }
+            Some(Action::NextInstruction) => {
+                template_form.select_instruction(template_form.instruction_index + 1)
+            }
+            Some(Action::Confirm) => match template_form.encode() {
+                Ok(data) => {
+                    self.apply_edit(|edit_session| edit_session.set_instruction_data(index, data));
+                    self.mode = Mode::Edit;
+                }
+                Err(err) => *status = Some(err),
+            },
+            _ => {
+                if let Some(input) = template_form.focused_input() {
+                    input.handle_event(&Event::Key(key_event));
+                }
+            }
+        }
+    }
+
+    /// Show the transaction wrapped by a multisig instruction, fetching the
+    /// accounts it is stored in if needed.
+    fn narration_handle_action(&mut self, action: Option<Action>, scroll: u16) {
+        match action {
+            Some(Action::Back) => self.mode = Mode::ByteNavigation,
+            Some(Action::Previous) => self.mode = Mode::Narration(scroll.saturating_sub(1)),
+            Some(Action::Next) => self.mode = Mode::Narration(scroll + 1),
+            Some(Action::ExportNarration) => self.export_narration(),
+            _ => {}
+        }
+    }
+
+    /// Write the narration to `<signature>.txt` in the working directory.
+    fn export_narration(&mut self) {
+        let Some(signature) = self.current_signature() else {
+            return;
+        };
+        let path = format!("{signature}.txt");
+        let mut narration =
+            narration::narrate(self.loaded_sections(), &self.settings, &self.address_book)
+                .join("\n");
+        narration.push('\n');
+        self.file_status = Some(format!("Writing {path}..."));
+        self.commands.push_back(Command::WriteFile {
+            path,
+            contents: narration.into_bytes(),
+        });
+    }
+
+    /// The legend of sections of the loaded transaction, naming their
+    /// pubkeys with everything resolved about them.
+    fn transaction_legend<'a>(
+        &'a self,
+        sections: &'a [TransactionByteSection],
+    ) -> ByteSectionLegend<'a> {
+        let byte_section_legend = ByteSectionLegend::new(sections)
+            .pubkey_display(self.settings.pubkey_display)
+            .address_book(&self.address_book)
+            .build_statuses(&self.build_statuses)
+            .order(self.settings.legend_order)
+            .monochrome(self.settings.monochrome);
+        let byte_section_legend = match (&self.pda_labels, self.current_signature()) {
+            (Some((signature, pda_labels)), Some(current)) if *signature == current => {
+                byte_section_legend.pda_labels(pda_labels)
+            }
+            _ => byte_section_legend,
+        };
+        let byte_section_legend = match (&self.hook_roles, self.current_signature()) {
+            (Some((signature, hook_roles)), Some(current)) if *signature == current => {
+                byte_section_legend.hook_roles(hook_roles)
+            }
+            _ => byte_section_legend,
+        };
+        match self.loaded_domains() {
+            Some(domains) => byte_section_legend.domains(domains),
+            None => byte_section_legend,
+        }
+    }
+
+    /// Write the legend, as text and HTML, to `<signature>-legend.txt` and
+    /// `<signature>-legend.html` in the working directory.
+    fn export_legend(&mut self) {
+        let Some(signature) = self.current_signature() else {
+            return;
+        };
+        let legend = self.transaction_legend(self.loaded_sections());
+        let files = [
+            (format!("{signature}-legend.txt"), legend.export_text()),
+            (format!("{signature}-legend.html"), legend.export_html()),
+        ];
+        self.file_status = Some(format!("Writing {}...", files[0].0));
+        for (path, contents) in files {
+            self.commands.push_back(Command::WriteFile {
+                path,
+                contents: contents.into_bytes(),
+            });
+        }
+    }
+
+    /// Write the transaction's bytes to `<signature>.bin`, the tag of each
+    /// byte to `<signature>.tags`, and both to `<signature>-tags.json`, in
+    /// the working directory.
+    fn export_byte_tags(&mut self) {
+        let Some(signature) = self.current_signature() else {
+            return;
+        };
+        let sections = self.loaded_sections();
+        let bytes = sections
+            .iter()
+            .flat_map(|section| section.bytes.iter().copied())
+            .collect();
+        let files = [
+            (format!("{signature}.bin"), bytes),
+            (format!("{signature}.tags"), byte_tags::tags(sections)),
+            (
+                format!("{signature}-tags.json"),
+                byte_tags::export_json(sections).into_bytes(),
+            ),
+        ];
+        self.file_status = Some(format!("Writing {}...", files[0].0));
+        for (path, contents) in files {
+            self.commands
+                .push_back(Command::WriteFile { path, contents });
+        }
+    }
+
+    /// Write the graph of accounts and instructions to `<signature>.dot` in
+    /// the working directory.
+    fn export_graph(&mut self) {
+        let Some(loaded) = self.loaded() else {
+            return;
+        };
+        let loaded_addresses = loaded
+            .meta
+            .as_ref()
+            .and_then(|meta| Option::<&UiLoadedAddresses>::from(meta.loaded_addresses.as_ref()));
+        let dot = graph::dot(&loaded.transaction, loaded_addresses, |pubkey| {
+            self.address_book
+                .name(pubkey)
+                .or_else(|| native_program_name(pubkey))
+                .map(str::to_string)
+                .unwrap_or_else(|| self.settings.pubkey_display.format(pubkey))
+        });
+        let path = format!("{}.dot", loaded.signature);
+        self.file_status = Some(format!("Writing {path}..."));
+        self.commands.push_back(Command::WriteFile {
+            path,
+            contents: dot.into_bytes(),
+        });
+    }
+
+    /// Write the loaded transaction with everything needed to view it
+    /// offline to `<signature>.tar.zst` in the working directory.
+    fn export_bundle(&mut self) {
+        let Some(loaded) = self.loaded() else {
+            return;
+        };
+        let bundle = Bundle {
+            signature: loaded.signature,
+            transaction: loaded.transaction.clone(),
+            meta: loaded.meta.clone(),
+            slot: loaded.slot,
+            block_time: loaded.block_time,
+            source: loaded.source.clone(),
+            accounts: loaded.accounts.clone(),
+            annotations: self.annotations().to_vec(),
+        };
+        let path = format!("{}.tar.zst", loaded.signature);
+        match bundle.archive() {
+            Ok(contents) => {
+                self.file_status = Some(format!("Writing {path}..."));
+                self.commands
+                    .push_back(Command::WriteFile { path, contents });
+            }
+            Err(err) => self.file_status = Some(format!("Failed to export bundle: {err}")),
+        }
+    }
+
+    /// View a transaction given as bytes rather than fetched, e.g. piped in.
+    fn open_transaction(&mut self, transaction: VersionedTransaction, source: String) {
+        let mut sections = vec![];
+        get_transaction_byte_sections(&transaction, &mut sections);
+        self.remember_view_position();
+        self.screen = Screen::Viewing(LoadedTransaction {
+            signature: transaction.signatures.first().copied().unwrap_or_default(),
+            transaction,
+            meta: None,
+            slot: None,
+            block_time: None,
+            sections,
+            source: Some(source),
+            endpoint: None,
+            fetch_options: None,
+            accounts: BTreeMap::new(),
+        });
+        self.restore_view_position();
+        self.mode = Mode::ByteNavigation;
+        self.start_reveal();
+        self.label_pdas();
+        self.resolve_ownership();
+        self.look_up_programs();
+        self.resolve_domains();
+    }
+
+    /// View the transaction of a bundle, adding its annotations to the
+    /// bookmarks.
+    fn open_bundle(&mut self, bundle: Bundle) {
+        let bookmarked = self.bookmarks.annotations(&bundle.signature).to_vec();
+        for annotation in bundle.annotations {
+            let is_bookmarked = bookmarked.iter().any(|bookmarked| {
+                bookmarked.range() == annotation.range() && bookmarked.note == annotation.note
+            });
+            if !is_bookmarked {
+                self.bookmarks.annotate(&bundle.signature, annotation);
+            }
+        }
+        self.save_bookmarks();
+        let mut sections = vec![];
+        get_transaction_byte_sections(&bundle.transaction, &mut sections);
+        self.remember_view_position();
+        self.screen = Screen::Viewing(LoadedTransaction {
+            signature: bundle.signature,
+            transaction: bundle.transaction,
+            meta: bundle.meta,
+            slot: bundle.slot,
+            block_time: bundle.block_time,
+            sections,
+            source: bundle.source,
+            endpoint: None,
+            fetch_options: None,
+            accounts: bundle.accounts,
+        });
+        self.restore_view_position();
+        self.mode = Mode::ByteNavigation;
+        self.start_reveal();
+        self.label_pdas();
+        self.resolve_ownership();
+        self.look_up_programs();
+        self.resolve_domains();
+    }
+
+    /// Remember where the cursor is in the loaded transaction, before another
+    /// replaces it.
+    fn remember_view_position(&mut self) {
+        if let Some(signature) = self.current_signature() {
+            let position = ViewPosition {
+                cursor: self.cursor,
+                byte_scroll: self.byte_scroll,
+                selection_anchor: self.selection_anchor,
+            };
+            self.view_positions.insert(signature, position);
+        }
+    }
+
+    /// Put the cursor back where it was when the transaction just loaded was
+    /// last viewed, or else select the instruction it failed in, or put the
+    /// cursor at its start.
+    fn restore_view_position(&mut self) {
+        let failed_instruction = self.failed_instruction_bytes().map(|bytes| ViewPosition {
+            cursor: bytes.start,
+            byte_scroll: 0,
+            selection_anchor: Some(bytes.end - 1),
+        });
+        let position = self
+            .current_signature()
+            .and_then(|signature| self.view_positions.get(&signature))
+            .copied()
+            .or(failed_instruction)
+            .unwrap_or_default();
+        let last_byte = self.num_bytes().saturating_sub(1);
+        self.cursor = position.cursor.min(last_byte);
+        self.byte_scroll = position.byte_scroll;
+        self.selection_anchor = position
+            .selection_anchor
+            .map(|anchor| anchor.min(last_byte));
+    }
+
+    /// Reveal the sections of the transaction just loaded one by one, if
+    /// enabled.
+    fn start_reveal(&mut self) {
+        let num_sections = self.loaded_sections().len();
+        self.reveal =
+            (self.settings.reveal_on_load && num_sections > 1).then(|| Reveal::new(num_sections));
+    }
+
+    /// The sections revealed so far, colored as configured.
+    fn displayed_sections(&self) -> Cow<'_, [TransactionByteSection]> {
+        let sections = self.revealed_sections();
+        match (self.settings.coloring, self.loaded()) {
+            (Coloring::Program, Some(loaded)) => Cow::Owned(program_colors::color_by_program(
+                sections,
+                &loaded.transaction,
+                |pubkey| {
+                    self.address_book
+                        .name(pubkey)
+                        .or_else(|| native_program_name(pubkey))
+                        .map(str::to_string)
+                        .unwrap_or_else(|| self.settings.pubkey_display.format(pubkey))
+                },
+            )),
+            _ => Cow::Borrowed(sections),
+        }
+    }
+
+    /// The sections of the loaded transaction revealed so far.
+    fn revealed_sections(&self) -> &[TransactionByteSection] {
+        let sections = self.loaded_sections();
+        match self.reveal.as_ref().and_then(Reveal::num_revealed) {
+            Some(num_revealed) => &sections[..num_revealed.min(sections.len())],
+            None => sections,
+        }
+    }
+
+    fn revealed_num_bytes(&self) -> usize {
+        self.view_sections(self.revealed_sections())
+            .1
+            .iter()
+            .map(|section| section.bytes.len())
+            .sum()
+    }
+
+    /// Label the PDAs among the loaded transaction's account keys, in the
+    /// background since it tries every pair of keys as the seeds of an
+    /// associated token address.
+    fn label_pdas(&mut self) {
+        let Some(loaded) = self.loaded() else {
+            return;
+        };
+        self.commands.push_back(Command::LabelPdas {
+            signature: loaded.signature,
+            account_keys: self.account_keys(),
+            instructions: loaded.transaction.message.instructions().to_vec(),
+            programs: self.idl_pdas.clone(),
+        });
+    }
+
+    /// Resolve the ownership chains of the loaded transaction's account keys.
+    fn resolve_ownership(&mut self) {
+        let Some(loaded) = self.loaded() else {
+            return;
+        };
+        self.commands.push_back(Command::ResolveOwnership {
+            signature: loaded.signature,
+            rpc: self.rpc(),
+            account_keys: self.account_keys(),
+        });
+    }
+
+    /// Look up the `.sol` domains of the loaded transaction's account keys.
+    fn resolve_domains(&mut self) {
+        let Some(loaded) = self.loaded() else {
+            return;
+        };
+        self.commands.push_back(Command::ResolveDomains {
+            signature: loaded.signature,
+            rpc: self.rpc(),
+            account_keys: self.account_keys(),
+        });
+    }
+
+    /// The `.sol` domains of the loaded transaction's account keys, once
+    /// resolved.
+    fn loaded_domains(&self) -> Option<&HashMap<Pubkey, String>> {
+        let loaded = self.loaded()?;
+        match &self.domains {
+            Some((signature, domains)) if *signature == loaded.signature => Some(domains),
+            _ => None,
+        }
+    }
+
+    /// The programs the loaded transaction invokes, other than builtins,
+    /// which have no deployment of their own to look up.
+    fn deployed_programs(&self) -> Vec<Pubkey> {
+        let Some(loaded) = self.loaded() else {
+            return vec![];
+        };
+        let message = &loaded.transaction.message;
+        let mut program_ids = message
+            .instructions()
+            .iter()
+            .filter_map(|instruction| {
+                message
+                    .static_account_keys()
+                    .get(instruction.program_id_index as usize)
+            })
+            .filter(|program_id| native_program_name(program_id).is_none())
+            .copied()
+            .collect::<Vec<_>>();
+        program_ids.sort();
+        program_ids.dedup();
+        program_ids
+    }
+
+    /// Check whether the programs the loaded transaction invokes are
+    /// verified builds and fetch their security.txt, skipping programs
+    /// already looked up.
+    fn look_up_programs(&mut self) {
+        let program_ids = self.deployed_programs();
+        let unchecked = program_ids
+            .iter()
+            .filter(|program_id| !self.build_statuses.contains_key(program_id))
+            .copied()
+            .collect::<Vec<_>>();
+        // The registry is not an RPC source, so it is only asked alongside
+        // the endpoint, and only of mainnet programs.
+        if !unchecked.is_empty()
+            && self.rpc_source.uses_endpoint()
+            && verified_builds::covers(self.endpoint)
+        {
+            self.commands.push_back(Command::CheckVerifiedBuilds {
+                program_ids: unchecked,
+            });
+        }
+        let unfetched = program_ids
+            .into_iter()
+            .filter(|program_id| !self.security_txts.contains_key(program_id))
+            .collect::<Vec<_>>();
+        if !unfetched.is_empty() {
+            self.commands.push_back(Command::FetchSecurityTxts {
+                rpc: self.rpc(),
+                program_ids: unfetched,
+            });
+        }
+    }
+
+    fn show_inner_transaction(&mut self, decoded: DecodedInstruction) {
+        let title = format!("{}: {}", decoded.program, decoded.name);
+        match decoded.inner {
+            Some(InnerTransactionSource::Inline(transaction)) => {
+                self.show_wrapped_transaction(&title, Ok(transaction))
+            }
+            Some(InnerTransactionSource::Accounts(stored)) => {
+                self.mode = Mode::InnerTransaction {
+                    title: format!("{title} | fetching…"),
+                    sections: vec![],
+                };
+                let Some(loaded) = self.loaded() else {
+                    return;
+                };
+                self.commands.push_back(Command::FetchStoredTransaction {
+                    rpc: self.rpc(),
+                    signature: loaded.signature,
+                    title: title.clone(),
+                    stored,
+                    accounts: loaded.accounts.clone(),
+                });
+            }
+            None => self.show_wrapped_transaction(
+                &title,
+                Err("Instruction does not wrap a transaction".to_string()),
+            ),
+        }
+    }
+
+    fn on_stored_transaction_fetched(
+        &mut self,
+        signature: Signature,
+        title: String,
+        result: Result<VersionedTransaction, String>,
+        accounts: BTreeMap<Pubkey, Account>,
+        elapsed: Duration,
+    ) {
+        // Another transaction may have been loaded while fetching.
+        let Some(loaded) = self
+            .loaded_mut()
+            .filter(|loaded| loaded.signature == signature)
+        else {
+            return;
+        };
+        // Keep the fetched accounts so that exported bundles hold them.
+        loaded.accounts.extend(accounts);
+        if !matches!(self.mode.underlying(), Mode::InnerTransaction { .. }) {
+            return;
+        }
+        self.show_wrapped_transaction(&title, result);
+        self.finish_long_operation("Wrapped transaction loaded", elapsed);
+    }
+
+    fn show_wrapped_transaction(
+        &mut self,
+        title: &str,
+        result: Result<VersionedTransaction, String>,
+    ) {
+        let mut sections = vec![];
+        let title = match result {
+            Ok(transaction) => {
+                get_transaction_byte_sections(&transaction, &mut sections);
+                format!("{title} | wrapped transaction")
+            }
+            Err(err) => format!("{title} | {err}"),
+        };
+        *self.mode.underlying_mut() = Mode::InnerTransaction { title, sections };
+    }
+
+    fn on_schema_loaded(&mut self, index: usize, result: Result<Schema, String>) {
+        if self.edit_session().is_none() || !matches!(self.mode, Mode::Edit) {
+            return;
+        }
+        let schema = match result {
+            Ok(schema) => schema,
+            Err(err) => {
+                self.set_edit_status(err);
+                return;
+            }
+        };
+        if let Some(program_id) = schema.program_id() {
+            self.error_codes.insert_idl(program_id, schema.errors());
+        }
+        if let Some(pdas) = schema.pdas() {
+            self.idl_pdas
+                .retain(|loaded| loaded.program_id != pdas.program_id);
+            self.idl_pdas.push(pdas);
+            self.label_pdas();
+        }
+        if let Screen::Edit {
+            status,
+            template_form,
+            ..
+        } = &mut self.screen
+        {
+            *status = None;
+            *template_form = Some(TemplateForm::new(schema));
+        }
+        self.prompt_input.reset();
+        self.mode = Mode::Template(index);
+    }
+
+    fn alt_plan_handle_action(&mut self, action: Option<Action>) {
+        let Mode::AltPlan { plan, step, .. } = &mut self.mode else {
+            return;
+        };
+        let num_steps = plan.as_deref().map_or(1, AltPlan::num_steps);
+        match action {
+            Some(Action::Back) => self.mode = Mode::ByteNavigation,
+            Some(Action::Previous) => *step = step.saturating_sub(1),
+            Some(Action::Next) => *step = (*step + 1).min(num_steps - 1),
+            Some(Action::SubmitPlan) => self.submit_alt_plan(),
+            Some(Action::EditRewritten) => {
+                if let Some(alt_plan) = plan {
+                    let transaction = alt_plan.rewritten.transaction.clone();
+                    self.start_edit(transaction);
+                    self.cursor = 0;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Plan a lookup table holding the accounts of the loaded transaction.
+    fn plan_lookup_table(&mut self) {
+        let Some(loaded) = self.loaded() else {
+            return;
+        };
+        self.commands.push_back(Command::PlanLookupTable {
+            rpc: self.rpc(),
+            signature: loaded.signature,
+            transaction: Box::new(loaded.transaction.clone()),
+        });
+        self.mode = Mode::AltPlan {
+            plan: None,
+            status: Some("Planning…".to_string()),
+            step: 0,
+        };
+    }
+
+    /// Sign the setup transactions of the plan with the loaded authority
+    /// keypair and submit them in order.
+    fn submit_alt_plan(&mut self) {
+        let Mode::AltPlan {
+            plan: Some(alt_plan),
+            status,
+            ..
+        } = &mut self.mode
+        else {
+            return;
+        };
+        let authority = [alt_plan.authority];
+        let Some(keypair) = self.keypairs.keypairs_for(&authority).next() else {
+            *status = Some(format!(
+                "Load the keypair for {} to submit",
+                alt_plan.authority
+            ));
+            return;
+        };
+        self.commands.push_back(Command::SubmitLookupTable {
+            rpc: self.rpc_source.api(self.endpoint),
+            lookup_table: alt_plan.lookup_table,
+            setup: alt_plan
+                .setup
+                .iter()
+                .map(|step| step.transaction.message.clone())
+                .collect(),
+            authority: Box::new(keypair.insecure_clone()),
+            started: Instant::now(),
+        });
+        *status = Some("Submitting…".to_string());
+    }
+
+    fn start_keypair_entry(&mut self, prompt: KeypairPrompt) {
+        self.prompt_input.reset();
+        self.mode = Mode::KeypairEntry(prompt);
+    }
+
+    fn keypair_entry_handle_key_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        let return_mode = if self.edit_session().is_some() {
+            Mode::Edit
+        } else {
+            Mode::SignatureEntry
+        };
+        match action {
+            Some(Action::Back) => {
+                self.prompt_input.reset();
+                self.mode = return_mode;
+            }
+            Some(Action::Confirm) => {
+                let value = Zeroizing::new(self.prompt_input.value().to_string());
+                self.prompt_input.reset();
+                let Mode::KeypairEntry(prompt) = std::mem::replace(&mut self.mode, return_mode)
+                else {
+                    return;
+                };
+                let result = match prompt {
+                    KeypairPrompt::File => {
+                        self.commands.push_back(Command::ReadKeypairFile {
+                            path: value.trim().to_string(),
+                        });
+                        return;
+                    }
+                    KeypairPrompt::SeedPhrase => {
+                        self.mode = Mode::KeypairEntry(KeypairPrompt::Passphrase(value));
+                        return;
+                    }
+                    KeypairPrompt::Passphrase(seed_phrase) => {
+                        self.keypairs.load_seed_phrase(&seed_phrase, &value)
+                    }
+                };
+                self.set_edit_status(match result {
+                    Ok(pubkey) => format!("Loaded keypair {pubkey}"),
+                    Err(err) => err,
+                });
+            }
+            _ => {
+                self.prompt_input.handle_event(&Event::Key(key_event));
+            }
+        }
+    }
+
+    /// Sign the edited transaction with every loaded keypair which is a
+    /// required signer.
+    fn sign_with_loaded_keypairs(&mut self) {
+        let Some(edit_session) = self.edit_session() else {
+            return;
+        };
+        let required_signers = edit_session.required_signers().to_vec();
+        let keypairs = self
+            .keypairs
+            .keypairs_for(&required_signers)
+            .map(|keypair| keypair as &dyn Signer)
+            .collect::<Vec<_>>();
+        if keypairs.is_empty() {
+            self.set_edit_status(
+                "No loaded keypair is a required signer, press <L> to load one".to_string(),
+            );
+            return;
+        }
+        let Screen::Edit {
+            session, status, ..
+        } = &mut self.screen
+        else {
+            return;
+        };
+        *status = match session.sign(&keypairs) {
+            Ok(()) => None,
+            Err(err) => Some(format!("Edit failed: {err}")),
+        };
+    }
+
+    /// Send the signed edited transaction to every broadcast endpoint at once.
+    fn broadcast_edited(&mut self) {
+        let Some(edit_session) = self.edit_session() else {
+            return;
+        };
+        let transaction = edit_session.edited().clone();
+        if transaction.verify_with_results().contains(&false) {
+            self.set_edit_status("Sign the transaction before broadcasting".to_string());
+            return;
+        }
+        let endpoints = if self.settings.broadcast_endpoints.is_empty() {
+            vec![self.endpoint.to_string()]
+        } else {
+            self.settings.broadcast_endpoints.clone()
+        };
+        let started = Instant::now();
+        for endpoint in &endpoints {
+            self.commands.push_back(Command::Broadcast {
+                rpc: self.rpc_source.api(endpoint),
+                endpoint: endpoint.clone(),
+                transaction: Box::new(transaction.clone()),
+                started,
+            });
+        }
+        self.broadcast = Some(Broadcast::new(transaction.signatures[0], &endpoints));
+        self.mode = Mode::Broadcast;
+    }
+
+    fn ledger_signing_handle_action(&mut self, action: Option<Action>) {
+        // Other states wait on the device, in commands.
+        let Mode::LedgerSigning(LedgerState::SelectingPath(index)) = self.mode else {
+            return;
+        };
+        let derivation_paths = ledger::derivation_paths();
+        match action {
+            Some(Action::Back) => self.mode = Mode::Edit,
+            Some(Action::Previous) => {
+                self.mode = Mode::LedgerSigning(LedgerState::SelectingPath(index.saturating_sub(1)))
+            }
+            Some(Action::Next) => {
+                self.mode = Mode::LedgerSigning(LedgerState::SelectingPath(
+                    (index + 1).min(derivation_paths.len() - 1),
+                ))
+            }
+            Some(Action::Confirm) => {
+                let derivation_path = derivation_paths[index].clone();
+                self.commands.push_back(Command::ConnectLedger {
+                    derivation_path: derivation_path.clone(),
+                });
+                self.mode = Mode::LedgerSigning(LedgerState::Connecting(derivation_path));
+            }
+            _ => {}
+        }
+    }
+
+    fn on_ledger_connected(
+        &mut self,
+        derivation_path: DerivationPath,
+        result: Result<Pubkey, String>,
+    ) {
+        if !matches!(
+            self.mode.underlying(),
+            Mode::LedgerSigning(LedgerState::Connecting(_))
+        ) {
+            return;
+        }
+        let Some(edit_session) = self.edit_session() else {
+            return;
+        };
+        let is_required_signer = |pubkey| edit_session.required_signers().contains(pubkey);
+        match result {
+            Ok(pubkey) if is_required_signer(&pubkey) => {
+                self.commands.push_back(Command::SignWithLedger {
+                    derivation_path,
+                    pubkey,
+                    message: edit_session.edited().message.serialize(),
+                });
+                *self.mode.underlying_mut() =
+                    Mode::LedgerSigning(LedgerState::AwaitingConfirmation(pubkey));
+                return;
+            }
+            Ok(pubkey) => {
+                self.set_edit_status(format!("Ledger key {pubkey} is not a required signer"))
+            }
+            Err(err) => self.set_edit_status(err),
+        }
+        *self.mode.underlying_mut() = Mode::Edit;
+    }
+
+    fn on_ledger_signed(&mut self, pubkey: Pubkey, result: Result<Signature, String>) {
+        if !matches!(
+            self.mode.underlying(),
+            Mode::LedgerSigning(LedgerState::AwaitingConfirmation(_))
+        ) {
+            return;
+        }
+        match result {
+            Ok(signature) => self.apply_edit(|edit_session| {
+                edit_session.sign(&[&Presigner::new(&pubkey, &signature)])
+            }),
+            Err(err) => self.set_edit_status(err),
+        }
+        *self.mode.underlying_mut() = Mode::Edit;
+    }
+
+    /// Replace the recent blockhash of the edited transaction with the latest
+    /// blockhash from the endpoint, or with the stored nonce if the
+    /// transaction uses a durable nonce.
+    fn refresh_blockhash(&mut self) {
+        let Some(edit_session) = self.edit_session() else {
+            return;
+        };
+        self.commands.push_back(Command::FetchBlockhash {
+            rpc: self.rpc(),
+            nonce_account: edit_session.nonce_account(),
+        });
+        self.set_edit_status("Fetching the blockhash…".to_string());
+    }
+
+    fn undo_redo(&mut self, undo_redo: fn(&mut EditSession) -> Option<String>, verb: &str) {
+        let Screen::Edit {
+            session, status, ..
+        } = &mut self.screen
+        else {
+            return;
+        };
+        *status = Some(match undo_redo(session) {
+            Some(description) => format!("{verb}: {description}"),
+            None => "Nothing to do".to_string(),
+        });
+        self.cursor = self.cursor.min(self.num_bytes().saturating_sub(1));
+    }
+
+    fn apply_edit(&mut self, edit: impl FnOnce(&mut EditSession) -> Result<(), edit::EditError>) {
+        let Screen::Edit {
+            session, status, ..
+        } = &mut self.screen
+        else {
+            return;
+        };
+        *status = match edit(session) {
+            Ok(()) => None,
+            Err(err) => Some(format!("Edit failed: {err}")),
+        };
+    }
+
+    fn exit(&mut self) {
+        self.exit = true;
+    }
+
+    /// The API commands send requests to.
+    fn rpc(&self) -> Arc<dyn RpcApi> {
+        self.rpc_source.api(self.endpoint)
+    }
+
+    /// The loaded transaction, while viewing or editing it.
+    fn loaded(&self) -> Option<&LoadedTransaction> {
+        match &self.screen {
+            Screen::Viewing(loaded) | Screen::Edit { loaded, .. } => Some(loaded),
+            _ => None,
+        }
+    }
+
+    fn loaded_mut(&mut self) -> Option<&mut LoadedTransaction> {
+        match &mut self.screen {
+            Screen::Viewing(loaded) | Screen::Edit { loaded, .. } => Some(loaded),
+            _ => None,
+        }
+    }
+
+    /// The sections of the loaded transaction, empty if nothing is loaded.
+    fn loaded_sections(&self) -> &[TransactionByteSection] {
+        self.loaded().map_or(&[], |loaded| &loaded.sections)
+    }
+
+    /// The bytes of the instruction the loaded transaction failed in.
+    fn failed_instruction_bytes(&self) -> Option<Range<usize>> {
+        let meta = self.loaded()?.meta.as_ref()?;
+        let Some(TransactionError::InstructionError(index, _)) = meta.err else {
+            return None;
+        };
+        let mut bytes = section_starts(self.loaded_sections())
+            .filter(|(_, section)| section.kind.instruction_index() == Some(index as usize))
+            .map(|(start, section)| start..start + section.bytes.len());
+        let first = bytes.next()?;
+        Some(bytes.fold(first, |all, bytes| all.start..bytes.end))
+    }
+
+    /// Show `status` under the edited transaction, while editing.
+    fn set_edit_status(&mut self, status: String) {
+        if let Screen::Edit {
+            status: edit_status,
+            ..
+        } = &mut self.screen
+        {
+            *edit_status = Some(status);
+        }
+    }
+
+    fn edit_session(&self) -> Option<&EditSession> {
+        match &self.screen {
+            Screen::Edit { session, .. } => Some(session),
+            _ => None,
+        }
+    }
+
+    /// Start editing `transaction`, keeping the loaded transaction to
+    /// return to.
+    fn start_edit(&mut self, transaction: VersionedTransaction) {
+        self.screen = match std::mem::replace(&mut self.screen, Screen::Input) {
+            Screen::Viewing(loaded) => Screen::Edit {
+                loaded,
+                session: Box::new(EditSession::new(transaction)),
+                status: None,
+                template_form: None,
+            },
+            // Keep the schema loaded for the previous edit.
+            Screen::Edit {
+                loaded,
+                template_form,
+                ..
+            } => Screen::Edit {
+                loaded,
+                session: Box::new(EditSession::new(transaction)),
+                status: None,
+                template_form,
+            },
+            screen => screen,
+        };
+        if let Screen::Edit { .. } = self.screen {
+            self.selection_anchor = None;
+            self.mode = Mode::Edit;
+        }
+    }
+
+    /// Discard the edit session and return to viewing the loaded
+    /// transaction.
+    fn stop_edit(&mut self) {
+        self.screen = match std::mem::replace(&mut self.screen, Screen::Input) {
+            Screen::Edit { loaded, .. } => Screen::Viewing(loaded),
+            screen => screen,
+        };
+    }
+
+    fn is_navigating_bytes(&self) -> bool {
+        matches!(
+            self.mode,
+            Mode::ByteNavigation | Mode::AnnotationEntry | Mode::Jump
+        )
+    }
+
+    /// The sections the cursor moves over: the edited transaction while
+    /// editing, otherwise the loaded transaction.
+    fn cursor_sections(&self) -> &[TransactionByteSection] {
+        match &self.screen {
+            Screen::Edit { session, .. } => session.edited_sections(),
+            _ => self.loaded_sections(),
+        }
+    }
+
+    fn toggle_message_view(&mut self) {
+        self.message_view = !self.message_view;
+        self.cursor = self
+            .cursor
+            .max(self.view_sections(self.cursor_sections()).0);
+    }
+
+    /// The sections to render: only the message (what signers sign) in
+    /// message view, otherwise all sections. Also returns the offset of the
+    /// first returned byte.
+    fn view_sections<'a>(
+        &self,
+        sections: &'a [TransactionByteSection],
+    ) -> (usize, &'a [TransactionByteSection]) {
+        if self.message_view {
+            message_sections(sections)
+        } else {
+            (0, sections)
+        }
+    }
+
+    fn cursor_section_kind(&self) -> Option<SectionKind> {
+        section_at(self.cursor_sections(), self.cursor).map(|section| section.kind)
+    }
+
+    /// How each instruction of the loaded transaction uses each account.
+    fn access_matrix(&self) -> Option<AccessMatrix> {
+        let loaded = self.loaded()?;
+        let num_loaded_writable = loaded
+            .meta
+            .as_ref()
+            .and_then(|meta| Option::<&UiLoadedAddresses>::from(meta.loaded_addresses.as_ref()))
+            .map_or(0, |loaded_addresses| loaded_addresses.writable.len());
+        Some(AccessMatrix::new(
+            &loaded.transaction,
+            self.account_keys().len(),
+            num_loaded_writable,
+        ))
+    }
+
+    /// All account keys of the loaded transaction: the static keys followed
+    /// by the keys loaded from lookup tables, when known.
+    fn account_keys(&self) -> Vec<Pubkey> {
+        let Some(loaded) = self.loaded() else {
+            return vec![];
+        };
+        let mut account_keys = loaded.transaction.message.static_account_keys().to_vec();
+        if let Some(loaded_addresses) = loaded
+            .meta
+            .as_ref()
+            .and_then(|meta| Option::<&UiLoadedAddresses>::from(meta.loaded_addresses.as_ref()))
+        {
+            account_keys.extend(
+                loaded_addresses
+                    .writable
+                    .iter()
+                    .chain(&loaded_addresses.readonly)
+                    .filter_map(|address| Pubkey::from_str(address).ok()),
+            );
+        }
+        account_keys
+    }
+
+    /// Decode the loaded transaction's instruction under the cursor, if it
+    /// belongs to a known program.
+    fn decode_instruction_at_cursor(&self) -> Option<DecodedInstruction> {
+        self.decode_instruction(self.cursor_section_kind()?.instruction_index()?)
+    }
+
+    /// Decode an instruction of the loaded transaction, if it belongs to a
+    /// known program.
+    fn decode_instruction(&self, index: usize) -> Option<DecodedInstruction> {
+        let Screen::Viewing(loaded) = &self.screen else {
+            return None;
+        };
+        let instruction = loaded.transaction.message.instructions().get(index)?;
+        let account_keys = self.account_keys();
+        let accounts = instruction
+            .accounts
+            .iter()
+            .map(|index| account_keys.get(*index as usize).copied())
+            .collect::<Vec<_>>();
+        let program_id = account_keys.get(instruction.program_id_index as usize)?;
+        decoders::decode_instruction(program_id, &accounts, &instruction.data)
+    }
+
+    /// The data field or account role of the decoded instruction under the
+    /// cursor.
+    fn decoded_part_at_cursor(&self, decoded: &DecodedInstruction) -> Option<String> {
+        let (start, section) = section_starts(self.cursor_sections())
+            .find(|(start, section)| self.cursor < start + section.bytes.len())?;
+        let position = self.cursor - start;
+        match section.kind {
+            SectionKind::InstructionData(_) => decoded.field_at(position).map(str::to_string),
+            SectionKind::InstructionAccounts(_) => decoded.account_names.get(position).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Every other field of the data of decoded instructions in bold, so
+    /// the boundaries of the fields show in the grid.
+    fn decoded_field_styles(&self) -> Vec<(Range<usize>, Style)> {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        section_starts(self.loaded_sections())
+            .filter_map(|(start, section)| match section.kind {
+                SectionKind::InstructionData(index) => {
+                    Some((start, self.decode_instruction(index)?))
+                }
+                _ => None,
+            })
+            .flat_map(|(start, decoded)| {
+                decoded
+                    .fields
+                    .into_iter()
+                    .skip(1)
+                    .step_by(2)
+                    .map(move |field| (start + field.range.start..start + field.range.end, bold))
+            })
+            .collect()
+    }
+
+    fn num_bytes(&self) -> usize {
+        self.cursor_sections()
+            .iter()
+            .map(|section| section.bytes.len())
+            .sum()
+    }
+
+    fn current_signature(&self) -> Option<Signature> {
+        self.loaded().map(|loaded| loaded.signature)
+    }
+
+    fn annotations(&self) -> &[Annotation] {
+        self.current_signature()
+            .map(|signature| self.bookmarks.annotations(&signature))
+            .unwrap_or_default()
+    }
+
+    /// The selected range of bytes, or just the cursor byte if no selection
+    /// is active.
+    fn selection(&self) -> Range<usize> {
+        let anchor = self.selection_anchor.unwrap_or(self.cursor);
+        anchor.min(self.cursor)..anchor.max(self.cursor) + 1
+    }
+
+    fn save_bookmarks(&mut self) {
+        // Keep the program tags of the loaded transaction current, e.g. with
+        // names added to the address book since it was bookmarked.
+        if let Some(loaded) = self.loaded() {
+            let program_tags = bookmarks::program_tags(&loaded.transaction, &self.address_book);
+            let signature = loaded.signature;
+            self.bookmarks.set_program_tags(&signature, program_tags);
+        }
+        self.persist("bookmarks", self.bookmarks.save());
+    }
+
+    fn fetch_options_handle_action(&mut self, action: Option<Action>, selected: usize) {
+        let options = &mut self.fetch_options;
+        match action {
+            Some(Action::Back) => self.mode = Mode::SignatureEntry,
+            Some(Action::Previous) => self.mode = Mode::FetchOptions(selected.saturating_sub(1)),
+            Some(Action::Next) => {
+                self.mode = Mode::FetchOptions((selected + 1).min(NUM_FETCH_OPTIONS - 1))
+            }
+            Some(Action::ChangeOption) => match selected {
+                0 => options.encoding = options.encoding.next(),
+                1 => options.loaded_addresses = !options.loaded_addresses,
+                _ => options.rewards = !options.rewards,
+            },
+            Some(Action::Confirm) => {
+                self.mode = Mode::SignatureEntry;
+                if !self.input.value().trim().is_empty() {
+                    self.on_signature_entry();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn confirm_refetch_handle_action(&mut self, action: Option<Action>) {
+        match action {
+            Some(Action::Back) => self.mode = Mode::SignatureEntry,
+            Some(Action::Confirm) => {
+                self.mode = Mode::SignatureEntry;
+                let Some(signature) = self.loaded().map(|loaded| loaded.signature) else {
+                    return;
+                };
+                self.remember_view_position();
+                self.fetch_transaction(signature);
+            }
+            _ => {}
+        }
+    }
+
+    /// Show an entry of the input history in the input, or clear it.
+    fn recall_input(&mut self, index: Option<usize>) {
+        self.input_history_index = index;
+        let input = index.and_then(|index| self.input_history.get(index));
+        self.input = Input::new(input.unwrap_or_default().to_string());
+    }
+
+    fn on_signature_entry(&mut self) {
+        let text = self.input.value().to_string();
+        self.input.reset(); // Clear the input field
+
+        // Viewing another transaction stops following.
+        self.follow = None;
+        self.input_history_index = None;
+        if !text.trim().is_empty() {
+            self.input_history.record(text.trim());
+            if let Some(change) = self.input_history.save() {
+                self.persist("input history", change);
+            }
+        }
+
+        if Signature::from_str(&text).is_err() {
+            if let Some(parsed) = ingest::parse_hex_transaction(&text) {
+                match parsed {
+                    Ok(transaction) => self.open_transaction(transaction, "hex".to_string()),
+                    Err(err) => self.screen = Screen::Error(err),
+                }
+                return;
+            }
+        }
+        let maybe_signature = self.parse_signature(&text);
+
+        self.remember_view_position();
+        self.cursor = 0;
+        self.selection_anchor = None;
+        self.byte_scroll = 0;
+
+        match maybe_signature {
+            Ok(signature) => self.open_or_fetch(signature),
+            Err(err) => self.screen = Screen::Error(err),
+        }
+    }
+
+    /// Parse a signature or an explorer link, switching to the cluster of the
+    /// link.
+    fn parse_signature(&mut self, text: &str) -> Result<Signature, String> {
+        match explorer_link::parse(text) {
+            Some(link) => link.map(|link| {
+                self.use_cluster(link.endpoint);
+                link.signature
+            }),
+            None => Signature::from_str(text).map_err(|_| "Invalid signature".to_string()),
+        }
+    }
+
+    /// Fetch from the public endpoint of a cluster, unless the endpoint in
+    /// use is already on that cluster.
+    fn use_cluster(&mut self, cluster_endpoint: &'static str) {
+        if public_endpoint(self.endpoint) != cluster_endpoint {
+            self.switch_endpoint(cluster_endpoint);
+        }
+    }
+
+    fn switch_endpoint(&mut self, endpoint: &'static str) {
+        self.endpoint = endpoint;
+        // The same program id may be another program on another cluster.
+        self.build_statuses.clear();
+        self.security_txts.clear();
+        self.endpoint_health.restart();
+        self.check_endpoint_health(Duration::ZERO);
+    }
+
+    /// Check the health of the active endpoint after `delay`, unless requests
+    /// do not reach it.
+    fn check_endpoint_health(&mut self, delay: Duration) {
+        if !self.rpc_source.uses_endpoint() {
+            return;
+        }
+        let reference = self
+            .settings
+            .reference_endpoint
+            .as_deref()
+            .filter(|reference| *reference != self.endpoint)
+            .map(|reference| self.rpc_source.api(reference));
+        self.commands.push_back(Command::CheckEndpointHealth {
+            rpc: self.rpc(),
+            reference,
+            generation: self.endpoint_health.generation,
+            delay,
+        });
+    }
+
+    /// View the snapshot of a bookmarked transaction rather than fetching it,
+    /// so it renders as it did when bookmarked.
+    fn open_or_fetch(&mut self, signature: Signature) {
+        if !self.bookmarks.is_bookmarked(&signature) || self.snapshots.dir.is_none() {
+            self.fetch_transaction(signature);
+            return;
+        }
+        self.commands.push_back(Command::LoadSnapshot {
+            snapshots: self.snapshots.clone(),
+            signature,
+        });
+        self.screen = Screen::Loading(signature);
+    }
+
+    fn on_snapshot_loaded(
+        &mut self,
+        signature: Signature,
+        result: Option<Result<Snapshot, String>>,
+    ) {
+        // Another signature may have been entered while loading.
+        if !matches!(self.screen, Screen::Loading(loading) if loading == signature) {
+            return;
+        }
+        let opened = match result {
+            Some(Ok(snapshot)) => self.open_snapshot(signature, snapshot),
+            Some(Err(err)) => {
+                self.file_status = Some(format!("Ignoring the snapshot of {signature}: {err}"));
+                false
+            }
+            None => false,
+        };
+        if !opened {
+            self.fetch_transaction(signature);
+        }
+    }
+
+    /// View the snapshot of a bookmarked transaction. Returns false if it is
+    /// not intact.
+    fn open_snapshot(&mut self, signature: Signature, snapshot: Snapshot) -> bool {
+        let (transaction, meta) = match snapshot.decode() {
+            Ok(decoded) => decoded,
+            Err(err) => {
+                self.file_status = Some(format!("Ignoring the snapshot of {signature}: {err}"));
+                return false;
+            }
+        };
+        let mut sections = vec![];
+        get_transaction_byte_sections(&transaction, &mut sections);
+        self.screen = Screen::Viewing(LoadedTransaction {
+            signature,
+            transaction,
+            meta,
+            slot: snapshot.slot,
+            block_time: snapshot.block_time,
+            sections,
+            source: Some("bookmarked snapshot".to_string()),
+            endpoint: None,
+            fetch_options: None,
+            accounts: BTreeMap::new(),
+        });
+        self.restore_view_position();
+        self.start_reveal();
+        self.label_pdas();
+        self.resolve_ownership();
+        self.look_up_programs();
+        self.resolve_domains();
+        true
+    }
+
+    /// Save a snapshot of the loaded transaction, if it is bookmarked.
+    fn save_snapshot(&mut self) {
+        let Some(loaded) = self.loaded() else {
+            return;
+        };
+        if self.bookmarks.is_bookmarked(&loaded.signature) {
+            let snapshot = Snapshot::new(
+                &loaded.transaction,
+                loaded.meta.as_ref(),
+                loaded.slot,
+                loaded.block_time,
+            );
+            if let Some(change) = self.snapshots.save(&loaded.signature, &snapshot) {
+                self.persist("snapshot", change);
+            }
+        }
+    }
+
+    /// Fetch the viewed transaction again, to show what changed since.
+    fn refetch(&mut self) {
+        let Screen::Viewing(loaded) = &self.screen else {
+            return;
+        };
+        let signature = loaded.signature;
+        self.remember_view_position();
+        let Screen::Viewing(loaded) = std::mem::replace(&mut self.screen, Screen::Input) else {
+            return;
+        };
+        self.refetching = Some(loaded);
+        self.mode = Mode::SignatureEntry;
+        self.fetch_transaction(signature);
+    }
+
+    /// Fetch a transaction in the background with the fetch options.
+    fn fetch_transaction(&mut self, signature: Signature) {
+        self.commands.push_back(Command::FetchTransaction {
+            rpc: self.rpc(),
+            signature,
+            options: self.fetch_options,
+        });
+        self.screen = Screen::Loading(signature);
+    }
+
+    /// Decode a fetched transaction into its sections.
+    fn decode_fetched(
+        &mut self,
+        signature: Signature,
+        transaction: EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Result<LoadedTransaction, String> {
+        let uses_endpoint = self.rpc_source.uses_endpoint();
+        let Some(decoded) = transaction.transaction.transaction.decode() else {
+            self.metrics.record_decode_failure();
+            return Err(format!("Failed to decode {signature}"));
+        };
+        let mut sections = vec![];
+        get_transaction_byte_sections(&decoded, &mut sections);
+        let num_bytes = sections.iter().map(|section| section.bytes.len()).sum();
+        self.metrics.record_transaction(num_bytes);
+        Ok(LoadedTransaction {
+            signature,
+            transaction: decoded,
+            meta: transaction.transaction.meta,
+            slot: Some(transaction.slot),
+            block_time: transaction.block_time,
+            sections,
+            source: None,
+            endpoint: uses_endpoint.then_some(self.endpoint),
+            fetch_options: uses_endpoint.then_some(self.fetch_options),
+            accounts: BTreeMap::new(),
+        })
+    }
+
+    fn on_transaction_fetched(
+        &mut self,
+        signature: Signature,
+        result: Result<Box<EncodedConfirmedTransactionWithStatusMeta>, String>,
+        elapsed: Duration,
+    ) {
+        self.metrics.record_fetch(elapsed, result.is_ok());
+        if matches!(
+            &self.screen,
+            Screen::Diff { compared, .. }
+                if matches!(compared, Compared::Fetching(fetching) if *fetching == signature)
+        ) {
+            let other = result.and_then(|transaction| self.decode_fetched(signature, *transaction));
+            if let Screen::Diff {
+                loaded, compared, ..
+            } = &mut self.screen
+            {
+                *compared = match other {
+                    Ok(other) => {
+                        let diff = TransactionDiff::new(&loaded.transaction, &other.transaction);
+                        Compared::Fetched(Box::new(other), diff)
+                    }
+                    Err(err) => Compared::Failed(err),
+                };
+            }
+            return;
+        }
+        // Another signature may have been entered while fetching.
+        if !matches!(self.screen, Screen::Loading(loading) if loading == signature) {
+            return;
+        }
+        let refetching = self
+            .refetching
+            .take()
+            .filter(|refetching| refetching.signature == signature);
+        let Ok(transaction) = result else {
+            self.screen = Screen::NotFound(signature);
+            self.finish_long_operation("Transaction not found", elapsed);
+            return;
+        };
+        self.screen = match self.decode_fetched(signature, *transaction) {
+            Ok(loaded) => Screen::Viewing(loaded),
+            Err(err) => Screen::Error(err),
+        };
+        if let (Some(refetching), Some(loaded)) = (refetching, self.loaded()) {
+            let changes = refetch_diff::diff(&refetching.fetched(), &loaded.fetched());
+            self.refetch_changes = Some((signature, changes));
+        }
+        self.save_snapshot();
+        self.restore_view_position();
+        self.start_reveal();
+        self.label_pdas();
+        self.resolve_ownership();
+        self.look_up_programs();
+        self.resolve_domains();
+        self.finish_long_operation("Transaction loaded", elapsed);
+    }
+}
+
+fn format_range(range: &Range<usize>) -> String {
+    if range.len() == 1 {
+        format!("{}", range.start)
+    } else {
+        format!("{}..{}", range.start, range.end)
+    }
+}
+
+fn render_ledger_popup(frame: &mut Frame, ledger_state: &LedgerState, monochrome: bool) {
+    let area = centered_rect(60, 16, frame.area());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .title("Sign with Ledger");
+    frame.render_widget(Clear, area);
+    match ledger_state {
+        LedgerState::SelectingPath(index) => {
+            let paths = ledger::derivation_paths()
+                .iter()
+                .map(ledger::format_derivation_path)
+                .collect::<Vec<_>>();
+            let list = List::new(paths)
+                .block(block.title_bottom("Derivation path"))
+                .highlight_style(highlight_style(monochrome));
+            frame.render_stateful_widget(
+                list,
+                area,
+                &mut ListState::default().with_selected(Some(*index)),
+            );
+        }
+        LedgerState::Connecting(derivation_path) => {
+            let text = format!(
+                "Connecting to Ledger at {}...",
+                ledger::format_derivation_path(derivation_path)
+            );
+            frame.render_widget(Paragraph::new(text).block(block), area);
+        }
+        LedgerState::AwaitingConfirmation(pubkey) => {
+            let text =
+                format!("Signer: {pubkey}\n\nConfirm the transaction on your Ledger device...");
+            frame.render_widget(Paragraph::new(text).block(block), area);
+        }
+    }
+}
+
+/// The options of the fetch options popup.
+const NUM_FETCH_OPTIONS: usize = 3;
+
+/// Render the options of fetches, applied to the next ones.
+fn render_fetch_options_popup(
+    frame: &mut Frame,
+    options: &FetchOptions,
+    selected: usize,
+    monochrome: bool,
+) {
+    let area = centered_rect(60, NUM_FETCH_OPTIONS as u16 + 4, frame.area());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .title("Fetch options")
+        .title_bottom("Enter fetches the signature entered");
+    let on_off = |on: bool| if on { "on" } else { "off" };
+    let items = [
+        format!("Encoding: {}", options.encoding.name()),
+        format!(
+            "Loaded addresses: {} (maxSupportedTransactionVersion)",
+            on_off(options.loaded_addresses)
+        ),
+        format!(
+            "Rewards: {} (dropped once fetched)",
+            on_off(options.rewards)
+        ),
+    ];
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(highlight_style(monochrome));
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(
+        list,
+        area,
+        &mut ListState::default().with_selected(Some(selected)),
+    );
+}
+
+/// Ask whether to refetch the displayed transaction from `endpoint`.
+fn render_refetch_popup(frame: &mut Frame, signature: &Signature, endpoint: &str) {
+    let area = centered_rect(96, 8, frame.area());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .title("Endpoint changed");
+    let text = format!("Refetch {signature}\nfrom {endpoint}?\n\nEnter/y: refetch, Esc/n: keep");
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(text).wrap(Wrap { trim: false }).block(block),
+        area,
+    );
+}
+
+/// Render the instruction data form, with the encoded bytes updated live.
+/// Full screen list of every keybinding, grouped by mode and filtered by
+/// the search query.
+fn render_help(frame: &mut Frame, area: Rect, help: &Help) {
+    frame.render_widget(Clear, area);
+    let help_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(area);
+    let query = help.query.value();
+    let search = Paragraph::new(query)
+        .style(Style::default().fg(Color::Yellow))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Search Keybindings"),
+        );
+    frame.render_widget(search, help_chunks[0]);
+    frame.set_cursor_position((
+        help_chunks[0].x + help.query.visual_cursor() as u16 + 1,
+        help_chunks[0].y + 1,
+    ));
+
+    let mut lines = vec![];
+    for context in Context::ALL {
+        let bindings = keybindings::bindings(context)
+            .filter(|binding| {
+                let text = format!(
+                    "{} {} {}",
+                    context.name(),
+                    binding.key_name(),
+                    binding.description
+                );
+                keybindings::fuzzy_matches(query, &text)
+            })
+            .collect::<Vec<_>>();
+        if bindings.is_empty() {
+            continue;
+        }
+        lines.push(Line::styled(
+            context.name(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.extend(bindings.iter().map(|binding| {
+            Line::raw(format!(
+                "  {:<10} {}",
+                binding.key_name(),
+                binding.description
+            ))
+        }));
+    }
+    let help = Paragraph::new(lines).scroll((help.scroll, 0)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .title("Help"),
+    );
+    frame.render_widget(help, help_chunks[1]);
+}
+
+fn render_template_popup(frame: &mut Frame, template_form: &TemplateForm, index: usize) {
+    let instruction = template_form.instruction();
+    let fields = instruction.fields().collect::<Vec<_>>();
+    let area = centered_rect(80, fields.len() as u16 + 7, frame.area());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .title(format!("Instruction ({index}) Data: {}", instruction.name));
+    let inner = block.inner(area);
+    frame.render_widget(Clear, area);
+
+    let mut lines = vec![];
+    let mut cursor_position = None;
+    for (field_index, (field, input)) in fields.iter().zip(&template_form.inputs).enumerate() {
+        let label = format!("{} ({}): ", field.name, field.field_type.describe());
+        let style = if field_index == template_form.focus {
+            cursor_position = Some((
+                inner.x + (label.len() + input.visual_cursor()) as u16,
+                inner.y + field_index as u16,
+            ));
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::styled(format!("{label}{}", input.value()), style));
+    }
+    if fields.is_empty() {
+        lines.push(Line::raw("No fields"));
+    }
+    lines.push(Line::raw(""));
+    lines.push(match template_form.encode() {
+        Ok(data) => Line::styled(
+            format!("Data ({} bytes): {}", data.len(), hex::encode(&data)),
+            Style::default().fg(Color::Green),
+        ),
+        Err(err) => Line::styled(err, Style::default().fg(Color::Red)),
+    });
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(block);
+    frame.render_widget(paragraph, area);
+    if let Some(cursor_position) = cursor_position {
+        frame.set_cursor_position(cursor_position);
+    }
+}
+
+/// The output lines of a utility computed from the prompt input, or a hint
+/// of what to type while there are none.
+fn render_utility_view(
+    frame: &mut Frame,
+    area: Rect,
+    title: &str,
+    lines: Vec<String>,
+    hint: &'static str,
+) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .padding(Padding::horizontal(1))
+        .title(title.to_string());
+    let text = if lines.is_empty() {
+        Text::styled(hint, Style::default().fg(Color::DarkGray))
+    } else {
+        Text::from(lines.into_iter().map(Line::raw).collect::<Vec<_>>())
+    };
+    let paragraph = Paragraph::new(text).block(block).wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+/// A rectangle of at most `width` x `height` centered within `area`.
+/// Split the frame into the title, input, main and footer sections.
+fn frame_chunks(area: Rect) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(3),
+        ])
+        .split(area)
+}
+
+/// Split the edit view into its rows, then its top row into the original
+/// and edited panes.
+fn edit_view_layout(area: Rect) -> (Rc<[Rect]>, Rc<[Rect]>) {
+    let edit_view_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),
+            Constraint::Length(1),
+            Constraint::Length(6),
+        ])
+        .split(area);
+    let edit_block_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(edit_view_chunks[0]);
+    (edit_view_chunks, edit_block_chunks)
+}
+
+/// Split the diff view into the panes and the changes, then the panes into
+/// the viewed and the compared transaction.
+fn diff_view_layout(area: Rect) -> (Rc<[Rect]>, Rc<[Rect]>) {
+    let diff_view_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(8)])
+        .split(area);
+    let pane_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(diff_view_chunks[0]);
+    (diff_view_chunks, pane_chunks)
+}
+
+/// The inside of the bordered and padded block around a byte grid.
+fn grid_inner(area: Rect) -> Rect {
+    Block::default()
+        .borders(Borders::ALL)
+        .padding(Padding::uniform(1))
+        .inner(area)
+}
+
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// The style of the selected item of a list.
+fn highlight_style(monochrome: bool) -> Style {
+    if monochrome {
+        Style::default().add_modifier(Modifier::REVERSED)
+    } else {
+        Style::default().fg(Color::Black).bg(Color::White)
+    }
+}
+
+/// Describe the message of a transaction: the exact bytes which are signed,
+/// and the message hash.
+fn message_summary(
+    settings: &Settings,
+    transaction: &VersionedTransaction,
+    message_sections: &[TransactionByteSection],
+) -> String {
+    let message_len = message_sections
+        .iter()
+        .map(|section| section.bytes.len())
+        .sum::<usize>();
+    format!(
+        "Signed message: {}, hash {}",
+        format::bytes(settings, message_len),
+        transaction.message.hash()
+    )
 }
@@ -3,25 +3,148 @@ use tui_menu::{MenuItem, MenuState};
 #[derive(Debug, Clone)]
 pub enum AppMenuItem {
     Endpoint(&'static str),
+    LoadEntriesFile,
+    LoadLedgerSlot,
+    LoadKeypairFile,
+    EnterSeedPhrase,
+    ForgetKeypairs,
+    EditAddressBook,
+    ImportKeypairDirectory,
+    ToggleDesktopNotifications,
+    CycleDigitGrouping,
+    ToggleAmountUnit,
+    ToggleTimeZone,
+    CyclePubkeyDisplay,
+    ToggleMonochrome,
+    ToggleRevealOnLoad,
+    OpenVarintPlayground,
+    OpenConverter,
+    OpenSignatureCheck,
+}
+
+impl AppMenuItem {
+    /// Whether the item changes a persisted setting.
+    pub fn is_setting(&self) -> bool {
+        matches!(
+            self,
+            Self::ToggleDesktopNotifications
+                | Self::CycleDigitGrouping
+                | Self::ToggleAmountUnit
+                | Self::ToggleTimeZone
+                | Self::CyclePubkeyDisplay
+                | Self::ToggleMonochrome
+                | Self::ToggleRevealOnLoad
+        )
+    }
+}
+
+/// The menu groups and their items.
+fn groups() -> Vec<(&'static str, Vec<(&'static str, AppMenuItem)>)> {
+    vec![
+        (
+            "Endpoint",
+            vec![
+                (
+                    "mainnet-beta",
+                    AppMenuItem::Endpoint("https://api.mainnet-beta.solana.com"),
+                ),
+                (
+                    "testnet",
+                    AppMenuItem::Endpoint("https://api.testnet.solana.com"),
+                ),
+                (
+                    "devnet",
+                    AppMenuItem::Endpoint("https://api.devnet.solana.com"),
+                ),
+            ],
+        ),
+        (
+            "Load",
+            vec![
+                ("entries file", AppMenuItem::LoadEntriesFile),
+                ("ledger slot", AppMenuItem::LoadLedgerSlot),
+            ],
+        ),
+        (
+            "Keypairs",
+            vec![
+                ("load keypair file", AppMenuItem::LoadKeypairFile),
+                ("enter seed phrase", AppMenuItem::EnterSeedPhrase),
+                ("forget all", AppMenuItem::ForgetKeypairs),
+            ],
+        ),
+        (
+            "Address book",
+            vec![
+                ("edit contacts", AppMenuItem::EditAddressBook),
+                (
+                    "import keypair directory",
+                    AppMenuItem::ImportKeypairDirectory,
+                ),
+            ],
+        ),
+        (
+            "Tools",
+            vec![
+                (
+                    "compact-u16 and varint playground",
+                    AppMenuItem::OpenVarintPlayground,
+                ),
+                (
+                    "base58, base64 and hex converter",
+                    AppMenuItem::OpenConverter,
+                ),
+                ("signature verifier", AppMenuItem::OpenSignatureCheck),
+            ],
+        ),
+        (
+            "Settings",
+            vec![
+                (
+                    "toggle desktop notifications",
+                    AppMenuItem::ToggleDesktopNotifications,
+                ),
+                ("cycle digit grouping", AppMenuItem::CycleDigitGrouping),
+                ("toggle SOL/lamports", AppMenuItem::ToggleAmountUnit),
+                ("toggle UTC/local time", AppMenuItem::ToggleTimeZone),
+                (
+                    "cycle pubkey display (base58/truncated/hex)",
+                    AppMenuItem::CyclePubkeyDisplay,
+                ),
+                ("toggle monochrome mode", AppMenuItem::ToggleMonochrome),
+                (
+                    "toggle reveal of loaded transactions",
+                    AppMenuItem::ToggleRevealOnLoad,
+                ),
+            ],
+        ),
+    ]
 }
 
 pub fn menu() -> MenuState<AppMenuItem> {
-    let items = vec![MenuItem::group(
-        "Endpoint",
-        vec![
-            MenuItem::item(
-                "mainnet-beta",
-                AppMenuItem::Endpoint("https://api.mainnet-beta.solana.com"),
-            ),
-            MenuItem::item(
-                "testnet",
-                AppMenuItem::Endpoint("https://api.testnet.solana.com"),
-            ),
-            MenuItem::item(
-                "devnet",
-                AppMenuItem::Endpoint("https://api.devnet.solana.com"),
-            ),
-        ],
-    )];
+    let items = groups()
+        .into_iter()
+        .map(|(group, items)| {
+            MenuItem::group(
+                group,
+                items
+                    .into_iter()
+                    .map(|(name, item)| MenuItem::item(name, item))
+                    .collect(),
+            )
+        })
+        .collect();
     MenuState::new(items)
 }
+
+/// Every menu item, labelled with its group, e.g. `Endpoint: devnet`.
+pub fn commands() -> Vec<(String, AppMenuItem)> {
+    groups()
+        .into_iter()
+        .flat_map(|(group, items)| {
+            items
+                .into_iter()
+                .map(move |(name, item)| (format!("{group}: {name}"), item))
+        })
+        .collect()
+}
@@ -0,0 +1,244 @@
+//! Wire formats of transaction messages. The section splitter walks the
+//! fields a format lists, so supporting another message version, or an
+//! experimental format, means adding an implementation here.
+
+use solana_sdk::{
+    instruction::CompiledInstruction,
+    message::{
+        legacy,
+        v0::{self, MessageAddressTableLookup},
+        VersionedMessage, MESSAGE_VERSION_PREFIX,
+    },
+    pubkey::Pubkey,
+};
+
+/// A field of a message, in the order it is serialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageField {
+    VersionByte,
+    Header,
+    StaticAccountKeys,
+    RecentBlockhash,
+    Instructions,
+    AddressTableLookups,
+}
+
+/// The variable length fields of a message, as its format lays them out.
+pub struct MessageParts<'a> {
+    pub static_account_keys: &'a [Pubkey],
+    pub instructions: &'a [CompiledInstruction],
+    pub address_table_lookups: &'a [MessageAddressTableLookup],
+}
+
+pub trait MessageFormat: Sync {
+    fn name(&self) -> &'static str;
+
+    /// Whether a message of this format can start with `first_byte`.
+    fn accepts_first_byte(&self, first_byte: u8) -> bool;
+
+    /// The fields of a message of this format, in serialized order.
+    fn fields(&self) -> &'static [MessageField];
+
+    /// Deserialize a message of this format from the start of `bytes`.
+    fn deserialize(&self, bytes: &[u8]) -> Result<VersionedMessage, String>;
+
+    /// The fields of `message`, or `None` if it is not of this format.
+    fn parts<'a>(&self, message: &'a VersionedMessage) -> Option<MessageParts<'a>>;
+}
+
+pub struct Legacy;
+
+impl MessageFormat for Legacy {
+    fn name(&self) -> &'static str {
+        "legacy"
+    }
+
+    fn accepts_first_byte(&self, _first_byte: u8) -> bool {
+        // The first byte is the number of required signatures.
+        true
+    }
+
+    fn fields(&self) -> &'static [MessageField] {
+        &[
+            MessageField::Header,
+            MessageField::StaticAccountKeys,
+            MessageField::RecentBlockhash,
+            MessageField::Instructions,
+        ]
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<VersionedMessage, String> {
+        bincode::deserialize::<legacy::Message>(bytes)
+            .map(VersionedMessage::Legacy)
+            .map_err(|err| format!("Invalid legacy message: {err}"))
+    }
+
+    fn parts<'a>(&self, message: &'a VersionedMessage) -> Option<MessageParts<'a>> {
+        let VersionedMessage::Legacy(message) = message else {
+            return None;
+        };
+        Some(MessageParts {
+            static_account_keys: &message.account_keys,
+            instructions: &message.instructions,
+            address_table_lookups: &[],
+        })
+    }
+}
+
+pub struct V0;
+
+impl MessageFormat for V0 {
+    fn name(&self) -> &'static str {
+        "v0"
+    }
+
+    fn accepts_first_byte(&self, first_byte: u8) -> bool {
+        first_byte == MESSAGE_VERSION_PREFIX
+    }
+
+    fn fields(&self) -> &'static [MessageField] {
+        &[
+            MessageField::VersionByte,
+            MessageField::Header,
+            MessageField::StaticAccountKeys,
+            MessageField::RecentBlockhash,
+            MessageField::Instructions,
+            MessageField::AddressTableLookups,
+        ]
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<VersionedMessage, String> {
+        match bytes.split_first() {
+            Some((&MESSAGE_VERSION_PREFIX, bytes)) => bincode::deserialize::<v0::Message>(bytes)
+                .map(VersionedMessage::V0)
+                .map_err(|err| format!("Invalid v0 message: {err}")),
+            _ => Err("Invalid v0 message: missing version byte".to_string()),
+        }
+    }
+
+    fn parts<'a>(&self, message: &'a VersionedMessage) -> Option<MessageParts<'a>> {
+        let VersionedMessage::V0(message) = message else {
+            return None;
+        };
+        Some(MessageParts {
+            static_account_keys: &message.account_keys,
+            instructions: &message.instructions,
+            address_table_lookups: &message.address_table_lookups,
+        })
+    }
+}
+
+/// Every supported format, the most specific first.
+pub const FORMATS: &[&dyn MessageFormat] = &[&V0, &Legacy];
+
+/// The format of a parsed message, if one is supported.
+pub fn format_of(message: &VersionedMessage) -> Option<&'static dyn MessageFormat> {
+    FORMATS
+        .iter()
+        .copied()
+        .find(|format| format.parts(message).is_some())
+}
+
+/// The formats a message starting with `first_byte` may be in, the one
+/// chosen by default first. More than one means the version byte is
+/// ambiguous, such as a legacy message with 128 or more signers.
+pub fn candidate_formats(first_byte: u8) -> Vec<&'static dyn MessageFormat> {
+    FORMATS
+        .iter()
+        .copied()
+        .filter(|format| format.accepts_first_byte(first_byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::ingest,
+        solana_sdk::{
+            hash::Hash, message::MessageHeader, signature::Signature,
+            transaction::VersionedTransaction,
+        },
+    };
+
+    fn names(formats: &[&dyn MessageFormat]) -> Vec<&'static str> {
+        formats.iter().map(|format| format.name()).collect()
+    }
+
+    #[test]
+    fn chooses_formats_of_ambiguous_version_bytes() {
+        assert_eq!(names(&candidate_formats(1)), ["legacy"]);
+        assert_eq!(
+            names(&candidate_formats(MESSAGE_VERSION_PREFIX)),
+            ["v0", "legacy"]
+        );
+
+        // A legacy message with 128 signers starts with the version prefix,
+        // yet is still legacy once parsed.
+        let legacy = VersionedMessage::Legacy(legacy::Message {
+            header: MessageHeader {
+                num_required_signatures: 128,
+                ..MessageHeader::default()
+            },
+            account_keys: vec![Pubkey::new_unique(); 128],
+            recent_blockhash: Hash::new_unique(),
+            instructions: vec![],
+        });
+        assert_eq!(legacy.serialize()[0], MESSAGE_VERSION_PREFIX);
+        assert_eq!(
+            format_of(&legacy).map(|format| format.name()),
+            Some("legacy")
+        );
+        assert!(V0.parts(&legacy).is_none());
+        assert_eq!(
+            Legacy.parts(&legacy).unwrap().static_account_keys.len(),
+            128
+        );
+
+        let v0 = VersionedMessage::V0(v0::Message::default());
+        assert_eq!(format_of(&v0).map(|format| format.name()), Some("v0"));
+        assert!(Legacy.parts(&v0).is_none());
+    }
+
+    #[test]
+    fn deserializes_messages_of_each_format() {
+        let v0 = VersionedMessage::V0(v0::Message {
+            account_keys: vec![Pubkey::new_unique()],
+            ..v0::Message::default()
+        });
+        let bytes = v0.serialize();
+        assert_eq!(V0.deserialize(&bytes).unwrap(), v0);
+        assert_eq!(
+            V0.deserialize(&bytes[1..]).err().as_deref(),
+            Some("Invalid v0 message: missing version byte")
+        );
+        assert!(Legacy
+            .deserialize(&[1, 0])
+            .unwrap_err()
+            .starts_with("Invalid legacy message: "));
+    }
+
+    #[test]
+    fn parses_a_transaction_in_a_forced_format() {
+        // 128 signers make a legacy message look versioned.
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default(); 128],
+            message: VersionedMessage::Legacy(legacy::Message {
+                header: MessageHeader {
+                    num_required_signatures: 128,
+                    ..MessageHeader::default()
+                },
+                account_keys: vec![Pubkey::new_unique(); 128],
+                recent_blockhash: Hash::new_unique(),
+                instructions: vec![],
+            }),
+        };
+        let bytes = bincode::serialize(&transaction).unwrap();
+        assert!(ingest::parse_transaction(&bytes).is_err());
+        assert_eq!(
+            ingest::parse_transaction_as(&bytes, &Legacy).unwrap(),
+            transaction
+        );
+        assert!(ingest::parse_transaction_as(&bytes, &V0).is_err());
+    }
+}
@@ -0,0 +1,184 @@
+//! Counters and histograms of the transactions fetched in a session, written
+//! on exit in the Prometheus text format so a run can serve as a lightweight
+//! monitoring probe.
+
+use std::{fmt::Write, time::Duration};
+
+const PREFIX: &str = "solana_transaction_tui";
+
+/// A histogram with fixed upper bounds for its buckets.
+struct Histogram {
+    bounds: &'static [f64],
+    /// The number of observations per bucket, not cumulative.
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: vec![0; bounds.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        if let Some(bucket) = self.bounds.iter().position(|bound| value <= *bound) {
+            self.counts[bucket] += 1;
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {PREFIX}_{name} {help}");
+        let _ = writeln!(out, "# TYPE {PREFIX}_{name} histogram");
+        let mut cumulative = 0;
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            cumulative += count;
+            let _ = writeln!(out, "{PREFIX}_{name}_bucket{{le=\"{bound}\"}} {cumulative}");
+        }
+        let _ = writeln!(out, "{PREFIX}_{name}_bucket{{le=\"+Inf\"}} {}", self.count);
+        let _ = writeln!(out, "{PREFIX}_{name}_sum {}", self.sum);
+        let _ = writeln!(out, "{PREFIX}_{name}_count {}", self.count);
+    }
+}
+
+fn render_counter(out: &mut String, name: &str, help: &str, value: u64) {
+    let _ = writeln!(out, "# HELP {PREFIX}_{name} {help}");
+    let _ = writeln!(out, "# TYPE {PREFIX}_{name} counter");
+    let _ = writeln!(out, "{PREFIX}_{name} {value}");
+}
+
+pub struct Metrics {
+    transactions_seen: u64,
+    decode_failures: u64,
+    fetch_failures: u64,
+    poll_failures: u64,
+    transaction_size: Histogram,
+    fetch_latency: Histogram,
+    poll_latency: Histogram,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            transactions_seen: 0,
+            decode_failures: 0,
+            fetch_failures: 0,
+            poll_failures: 0,
+            transaction_size: Histogram::new(&[128.0, 256.0, 512.0, 768.0, 1024.0, 1232.0]),
+            fetch_latency: Histogram::new(&[0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]),
+            poll_latency: Histogram::new(&[0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0]),
+        }
+    }
+}
+
+impl Metrics {
+    /// Record a response to a fetch, successful or not.
+    pub fn record_fetch(&mut self, elapsed: Duration, succeeded: bool) {
+        self.fetch_latency.observe(elapsed.as_secs_f64());
+        if !succeeded {
+            self.fetch_failures += 1;
+        }
+    }
+
+    /// Record a response to a watch poll, successful or not.
+    pub fn record_poll(&mut self, elapsed: Duration, succeeded: bool) {
+        self.poll_latency.observe(elapsed.as_secs_f64());
+        if !succeeded {
+            self.poll_failures += 1;
+        }
+    }
+
+    pub fn record_transaction(&mut self, num_bytes: usize) {
+        self.transactions_seen += 1;
+        self.transaction_size.observe(num_bytes as f64);
+    }
+
+    pub fn record_decode_failure(&mut self) {
+        self.decode_failures += 1;
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        render_counter(
+            &mut out,
+            "transactions_seen_total",
+            "Transactions fetched or streamed and decoded.",
+            self.transactions_seen,
+        );
+        render_counter(
+            &mut out,
+            "decode_failures_total",
+            "Fetched or streamed transactions which failed to decode.",
+            self.decode_failures,
+        );
+        render_counter(
+            &mut out,
+            "fetch_failures_total",
+            "Fetches which returned an error.",
+            self.fetch_failures,
+        );
+        render_counter(
+            &mut out,
+            "poll_failures_total",
+            "Watch polls which returned an error.",
+            self.poll_failures,
+        );
+        self.transaction_size.render(
+            &mut out,
+            "transaction_size_bytes",
+            "Serialized size of fetched or streamed transactions.",
+        );
+        self.fetch_latency.render(
+            &mut out,
+            "fetch_latency_seconds",
+            "Time until a fetch returned.",
+        );
+        self.poll_latency.render(
+            &mut out,
+            "poll_latency_seconds",
+            "Time until a watch poll returned.",
+        );
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_cumulative_buckets() {
+        let mut metrics = Metrics::default();
+        metrics.record_transaction(200);
+        metrics.record_transaction(300);
+        metrics.record_transaction(2000);
+        metrics.record_fetch(Duration::from_millis(80), false);
+
+        let rendered = metrics.render();
+        let lines = rendered.lines().collect::<Vec<_>>();
+        assert!(lines.contains(&"solana_transaction_tui_transactions_seen_total 3"));
+        assert!(lines.contains(&"solana_transaction_tui_fetch_failures_total 1"));
+        assert!(
+            lines.contains(&"solana_transaction_tui_transaction_size_bytes_bucket{le=\"128\"} 0")
+        );
+        assert!(
+            lines.contains(&"solana_transaction_tui_transaction_size_bytes_bucket{le=\"256\"} 1")
+        );
+        assert!(
+            lines.contains(&"solana_transaction_tui_transaction_size_bytes_bucket{le=\"1232\"} 2")
+        );
+        assert!(
+            lines.contains(&"solana_transaction_tui_transaction_size_bytes_bucket{le=\"+Inf\"} 3")
+        );
+        assert!(lines.contains(&"solana_transaction_tui_transaction_size_bytes_sum 2500"));
+        assert!(
+            lines.contains(&"solana_transaction_tui_fetch_latency_seconds_bucket{le=\"0.1\"} 1")
+        );
+    }
+}
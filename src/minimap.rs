@@ -0,0 +1,128 @@
+//! A one column map of the sections of a whole transaction, next to the byte
+//! grid, marking the bytes in view to keep the global context while scrolled.
+
+use {
+    crate::transaction_byte_sections::TransactionByteSection,
+    ratatui::{
+        buffer::Buffer,
+        layout::Rect,
+        style::{Color, Style},
+        widgets::Widget,
+    },
+    std::ops::Range,
+};
+
+const IN_VIEW: &str = "█";
+const OUT_OF_VIEW: &str = "░";
+
+pub struct Minimap<'a> {
+    sections: &'a [TransactionByteSection],
+    viewport: Range<usize>,
+    monochrome: bool,
+}
+
+impl<'a> Minimap<'a> {
+    pub fn new(sections: &'a [TransactionByteSection]) -> Self {
+        Self {
+            sections,
+            viewport: 0..0,
+            monochrome: false,
+        }
+    }
+
+    /// The offsets of the bytes in view, relative to the first section.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn viewport(mut self, viewport: Range<usize>) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    /// Only marks the bytes in view, without colors.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn monochrome(mut self, monochrome: bool) -> Self {
+        self.monochrome = monochrome;
+        self
+    }
+
+    /// The color of the section most of the bytes in `range` belong to, the
+    /// first on ties.
+    fn dominant_color(&self, range: &Range<usize>) -> Color {
+        let mut start = 0;
+        let mut dominant = (0, Color::Reset);
+        for section in self.sections {
+            let end = start + section.bytes.len();
+            let overlap = end.min(range.end).saturating_sub(start.max(range.start));
+            if overlap > dominant.0 {
+                dominant = (overlap, section.color);
+            }
+            start = end;
+        }
+        dominant.1
+    }
+}
+
+impl<'a> Widget for &Minimap<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let len_bytes = self.sections.iter().map(|s| s.bytes.len()).sum::<usize>();
+        if len_bytes == 0 || area.height == 0 || area.width == 0 {
+            return;
+        }
+        let bytes_per_row = len_bytes.div_ceil(area.height as usize);
+        for row in 0..area.height {
+            let start = row as usize * bytes_per_row;
+            if start >= len_bytes {
+                break;
+            }
+            let range = start..(start + bytes_per_row).min(len_bytes);
+            let in_view = range.start < self.viewport.end && self.viewport.start < range.end;
+            let glyph = if in_view { IN_VIEW } else { OUT_OF_VIEW };
+            let style = if self.monochrome {
+                Style::default()
+            } else {
+                Style::default().fg(self.dominant_color(&range))
+            };
+            buf.set_string(area.x, area.y + row, glyph, style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_transaction_tui::transaction_byte_sections::SectionKind};
+
+    fn section(len: usize, color: Color) -> TransactionByteSection {
+        TransactionByteSection {
+            label: None,
+            bytes: vec![0; len],
+            color,
+            kind: SectionKind::RecentBlockhash,
+        }
+    }
+
+    #[test]
+    fn maps_sections_and_marks_the_viewport() {
+        let sections = [
+            section(3, Color::Red),
+            section(5, Color::Blue),
+            section(2, Color::Green),
+        ];
+        let minimap = Minimap::new(&sections).viewport(4..8);
+        let area = Rect::new(0, 0, 1, 4);
+        let mut buf = Buffer::empty(area);
+        minimap.render(area, &mut buf);
+
+        // Each row stands for 3 bytes, the last for the 10th only.
+        let rows = (0..4)
+            .map(|y| (buf[(0, y)].symbol().to_string(), buf[(0, y)].fg))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            [
+                (OUT_OF_VIEW.to_string(), Color::Red),
+                (IN_VIEW.to_string(), Color::Blue),
+                (IN_VIEW.to_string(), Color::Blue),
+                (OUT_OF_VIEW.to_string(), Color::Green),
+            ]
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use {
+    crate::{
+        address_book::AddressBook,
+        format,
+        settings::Settings,
+        transaction_byte_sections::{section_at, SectionKind, TransactionByteSection},
+    },
+    solana_sdk::{hash::Hash, short_vec::ShortU16, signature::Signature},
+};
+
+/// Describe the transaction section by section, one line each, e.g.
+/// `Section 7: Static Account Key (1), 32 bytes at offset 98, value ...`.
+/// Unlike the byte grid this reads linearly, for screen readers and for
+/// pasting into chat.
+pub fn narrate(
+    sections: &[TransactionByteSection],
+    settings: &Settings,
+    address_book: &AddressBook,
+) -> Vec<String> {
+    let total_bytes = sections
+        .iter()
+        .map(|section| section.bytes.len())
+        .sum::<usize>();
+    let sections = sections
+        .iter()
+        .filter(|section| !section.bytes.is_empty())
+        .collect::<Vec<_>>();
+    let mut lines = vec![format!(
+        "Transaction of {} in {} sections.",
+        format::bytes(settings, total_bytes),
+        sections.len()
+    )];
+    let mut offset = 0;
+    for (index, section) in sections.into_iter().enumerate() {
+        lines.push(format!(
+            "Section {}: {}, {} at offset {}, value {}.",
+            index + 1,
+            label(section),
+            format::bytes(settings, section.bytes.len()),
+            format::integer(settings, offset as u64),
+            value(section, settings, address_book)
+        ));
+        offset += section.bytes.len();
+    }
+    lines
+}
+
+/// Describe the byte at `offset` and its section on a single line, e.g.
+/// `offset 0x41 (65) • Static Account Key (2) • 32 bytes • 9xQe…`.
+pub fn tooltip(
+    sections: &[TransactionByteSection],
+    offset: usize,
+    settings: &Settings,
+    address_book: &AddressBook,
+) -> Option<String> {
+    let section = section_at(sections, offset)?;
+    Some(format!(
+        "offset {offset:#04x} ({offset}) • {} • {} • {}",
+        label(section),
+        format::bytes(settings, section.bytes.len()),
+        value(section, settings, address_book)
+    ))
+}
+
+fn label(section: &TransactionByteSection) -> String {
+    match (section.kind, &section.label) {
+        (SectionKind::InstructionProgramIdIndex(index), _) => {
+            format!("Instruction ({index}) Program Id Index")
+        }
+        (kind, Some(label)) => match kind.instruction_index() {
+            Some(index) => format!("{label} ({index})"),
+            None => label.clone(),
+        },
+        (_, None) => "Unlabelled".to_string(),
+    }
+}
+
+fn value(
+    section: &TransactionByteSection,
+    settings: &Settings,
+    address_book: &AddressBook,
+) -> String {
+    let bytes = section.bytes.as_slice();
+    match section.kind {
+        SectionKind::SignatureCount
+        | SectionKind::StaticAccountKeysCount
+        | SectionKind::InstructionsCount
+        | SectionKind::InstructionAccountsCount(_)
+        | SectionKind::InstructionDataLength(_)
+        | SectionKind::AddressTableLookupsCount
+        | SectionKind::AddressTableLookupWriteCount(_)
+        | SectionKind::AddressTableLookupReadCount(_) => bincode::deserialize::<ShortU16>(bytes)
+            .map(|count| count.0.to_string())
+            .unwrap_or_else(|_| hex::encode(bytes)),
+        SectionKind::Signature(_) => Signature::try_from(bytes)
+            .map(|signature| signature.to_string())
+            .unwrap_or_else(|_| hex::encode(bytes)),
+        SectionKind::VersionByte => format!("version {}", bytes[0] & 0x7f),
+        SectionKind::StaticAccountKey(_) | SectionKind::AddressTableLookupAddress(_) => section
+            .pubkey()
+            .map(|pubkey| format::pubkey(settings, address_book, &pubkey))
+            .unwrap_or_else(|| hex::encode(bytes)),
+        SectionKind::RecentBlockhash => <[u8; 32]>::try_from(bytes)
+            .map(|hash| Hash::new_from_array(hash).to_string())
+            .unwrap_or_else(|_| hex::encode(bytes)),
+        SectionKind::InstructionAccounts(_)
+        | SectionKind::AddressTableLookupWriteSet(_)
+        | SectionKind::AddressTableLookupReadSet(_) => {
+            let indexes = bytes.iter().map(u8::to_string).collect::<Vec<_>>();
+            format!("account indexes {}", indexes.join(", "))
+        }
+        SectionKind::InstructionData(_) => format!("0x{}", hex::encode(bytes)),
+        SectionKind::NumRequiredSignatures
+        | SectionKind::NumReadonlySignedAccounts
+        | SectionKind::NumReadonlyUnsignedAccounts
+        | SectionKind::InstructionProgramIdIndex(_) => bytes[0].to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            address_book::parse_contact, transaction_byte_sections::get_transaction_byte_sections,
+        },
+        solana_sdk::{
+            message::{Message, VersionedMessage},
+            pubkey::Pubkey,
+            system_instruction,
+            transaction::VersionedTransaction,
+        },
+    };
+
+    /// The sections of a transfer between fixed accounts.
+    fn sections() -> Vec<TransactionByteSection> {
+        let payer = Pubkey::new_from_array([1; 32]);
+        let instruction = system_instruction::transfer(&payer, &Pubkey::new_from_array([2; 32]), 5);
+        let message = Message::new_with_blockhash(
+            &[instruction],
+            Some(&payer),
+            &Hash::new_from_array([3; 32]),
+        );
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::from([4; 64])],
+            message: VersionedMessage::Legacy(message),
+        };
+        let mut sections = vec![];
+        get_transaction_byte_sections(&transaction, &mut sections);
+        sections
+    }
+
+    #[test]
+    fn narrates_each_section_in_order() {
+        let lines = narrate(&sections(), &Settings::default(), &AddressBook::default());
+        assert_eq!(lines.len(), 17);
+        assert_eq!(lines[0], "Transaction of 215 bytes in 16 sections.");
+        assert_eq!(
+            lines[1],
+            "Section 1: Signature Count, 1 byte at offset 0, value 1."
+        );
+        assert_eq!(
+            lines[9],
+            "Section 9: Static Account Key (2), 32 bytes at offset 133, value \
+             11111111111111111111111111111111."
+        );
+        assert_eq!(
+            lines[12],
+            "Section 12: Instruction (0) Program Id Index, 1 byte at offset 198, value 2."
+        );
+        assert_eq!(
+            lines[14],
+            "Section 14: Instruction Accounts (0), 2 bytes at offset 200, value account indexes \
+             0, 1."
+        );
+        assert_eq!(
+            lines[16],
+            "Section 16: Instruction Data (0), 12 bytes at offset 203, value \
+             0x020000000500000000000000."
+        );
+    }
+
+    #[test]
+    fn describes_the_byte_under_the_cursor() {
+        let mut address_book = AddressBook::default();
+        let (payer, contact) =
+            parse_contact(&format!("{} payer", Pubkey::new_from_array([1; 32]))).unwrap();
+        address_book.insert(&payer, contact);
+        let tooltip = |offset| tooltip(&sections(), offset, &Settings::default(), &address_book);
+        assert_eq!(
+            tooltip(70).as_deref(),
+            Some("offset 0x46 (70) • Static Account Key (0) • 32 bytes • payer")
+        );
+        assert_eq!(
+            tooltip(65).as_deref(),
+            Some("offset 0x41 (65) • num_required_signatures • 1 byte • 1")
+        );
+        assert_eq!(tooltip(215), None);
+    }
+}
@@ -0,0 +1,214 @@
+//! Resolving who owns the account keys of a transaction: their owner program
+//! and, for upgradeable programs, their programdata account and upgrade
+//! authority, to verify which deployment of a program a transaction invokes.
+
+use {
+    crate::rpc::RpcApi,
+    solana_client::rpc_request::MAX_MULTIPLE_ACCOUNTS,
+    solana_sdk::{
+        account::Account,
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        clock::Slot,
+        pubkey::Pubkey,
+    },
+    std::collections::{BTreeSet, HashMap},
+};
+
+pub struct ProgramData {
+    pub address: Pubkey,
+    /// The slot the program was last deployed at.
+    pub slot: Slot,
+    /// `None` if the program is immutable.
+    pub upgrade_authority: Option<Pubkey>,
+}
+
+struct AccountOwner {
+    owner: Pubkey,
+    /// Only for upgradeable programs.
+    program_data: Option<ProgramData>,
+}
+
+/// A link of the ownership chain of an account.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Link {
+    /// The account does not exist.
+    Missing,
+    Owner(Pubkey),
+    /// Of the account if it is a program, else of its owner.
+    ProgramData {
+        address: Pubkey,
+        slot: Slot,
+    },
+    UpgradeAuthority(Option<Pubkey>),
+}
+
+#[derive(Default)]
+pub struct Ownership {
+    /// `None` for accounts which do not exist.
+    accounts: HashMap<Pubkey, Option<AccountOwner>>,
+}
+
+impl Ownership {
+    /// Fetch the accounts, then the programs owning them, then the
+    /// programdata accounts of the upgradeable programs among both. Only the
+    /// start of the data of each account is requested, as the rest is not
+    /// needed.
+    pub fn resolve(rpc: &dyn RpcApi, pubkeys: &[Pubkey]) -> Result<Self, String> {
+        let mut accounts = fetch(rpc, pubkeys)?;
+        let owners = accounts
+            .values()
+            .flatten()
+            .map(|account| account.owner)
+            .filter(|owner| !accounts.contains_key(owner))
+            .collect::<BTreeSet<_>>();
+        accounts.extend(fetch(rpc, &owners.into_iter().collect::<Vec<_>>())?);
+        let program_data_addresses = accounts
+            .values()
+            .flatten()
+            .filter_map(program_data_address)
+            .filter(|address| !accounts.contains_key(address))
+            .collect::<BTreeSet<_>>();
+        accounts.extend(fetch(
+            rpc,
+            &program_data_addresses.into_iter().collect::<Vec<_>>(),
+        )?);
+        Ok(Self::from_accounts(&accounts))
+    }
+
+    fn from_accounts(accounts: &HashMap<Pubkey, Option<Account>>) -> Self {
+        let program_data = |account: &Account| {
+            let address = program_data_address(account)?;
+            let program_data = accounts.get(&address)?.as_ref()?;
+            match bincode::deserialize(&program_data.data).ok()? {
+                UpgradeableLoaderState::ProgramData {
+                    slot,
+                    upgrade_authority_address,
+                } => Some(ProgramData {
+                    address,
+                    slot,
+                    upgrade_authority: upgrade_authority_address,
+                }),
+                _ => None,
+            }
+        };
+        let accounts = accounts
+            .iter()
+            .map(|(pubkey, account)| {
+                let owner = account.as_ref().map(|account| AccountOwner {
+                    owner: account.owner,
+                    program_data: program_data(account),
+                });
+                (*pubkey, owner)
+            })
+            .collect();
+        Self { accounts }
+    }
+
+    /// The ownership chain of an account, or `None` if it was not resolved.
+    pub fn chain(&self, pubkey: &Pubkey) -> Option<Vec<Link>> {
+        let Some(account) = self.accounts.get(pubkey)? else {
+            return Some(vec![Link::Missing]);
+        };
+        let mut chain = vec![Link::Owner(account.owner)];
+        let program_data = account.program_data.as_ref().or_else(|| {
+            let owner = self.accounts.get(&account.owner)?.as_ref()?;
+            owner.program_data.as_ref()
+        });
+        if let Some(program_data) = program_data {
+            chain.push(Link::ProgramData {
+                address: program_data.address,
+                slot: program_data.slot,
+            });
+            chain.push(Link::UpgradeAuthority(program_data.upgrade_authority));
+        }
+        Some(chain)
+    }
+}
+
+/// The programdata address of an upgradeable program account.
+pub fn program_data_address(account: &Account) -> Option<Pubkey> {
+    if account.owner != bpf_loader_upgradeable::id() {
+        return None;
+    }
+    match bincode::deserialize(&account.data).ok()? {
+        UpgradeableLoaderState::Program {
+            programdata_address,
+        } => Some(programdata_address),
+        _ => None,
+    }
+}
+
+fn fetch(rpc: &dyn RpcApi, pubkeys: &[Pubkey]) -> Result<HashMap<Pubkey, Option<Account>>, String> {
+    let length = UpgradeableLoaderState::size_of_programdata_metadata();
+    let mut accounts = HashMap::new();
+    for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let fetched = rpc.get_multiple_account_prefixes(chunk, length)?;
+        accounts.extend(chunk.iter().copied().zip(fetched));
+    }
+    Ok(accounts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(owner: Pubkey, state: Option<UpgradeableLoaderState>) -> Option<Account> {
+        let data = state.map(|state| bincode::serialize(&state).unwrap());
+        Some(Account {
+            owner,
+            data: data.unwrap_or_default(),
+            ..Account::default()
+        })
+    }
+
+    #[test]
+    fn chains_accounts_to_the_deployment_of_their_owner() {
+        let (program, program_data, authority) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let (data_account, missing) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let loader = bpf_loader_upgradeable::id();
+        let accounts = HashMap::from([
+            (
+                program,
+                account(
+                    loader,
+                    Some(UpgradeableLoaderState::Program {
+                        programdata_address: program_data,
+                    }),
+                ),
+            ),
+            (
+                program_data,
+                account(
+                    loader,
+                    Some(UpgradeableLoaderState::ProgramData {
+                        slot: 42,
+                        upgrade_authority_address: Some(authority),
+                    }),
+                ),
+            ),
+            (data_account, account(program, None)),
+            (missing, None),
+        ]);
+        let ownership = Ownership::from_accounts(&accounts);
+
+        let deployment = [
+            Link::ProgramData {
+                address: program_data,
+                slot: 42,
+            },
+            Link::UpgradeAuthority(Some(authority)),
+        ];
+        let chain = ownership.chain(&data_account).unwrap();
+        assert_eq!(chain[0], Link::Owner(program));
+        assert_eq!(chain[1..], deployment);
+        let chain = ownership.chain(&program).unwrap();
+        assert_eq!(chain[0], Link::Owner(loader));
+        assert_eq!(chain[1..], deployment);
+        assert_eq!(ownership.chain(&missing), Some(vec![Link::Missing]));
+        assert_eq!(ownership.chain(&Pubkey::new_unique()), None);
+    }
+}
@@ -0,0 +1,102 @@
+//! Re-simulating a failed transaction to compare the outcome with the one
+//! recorded on chain, to diagnose transactions which passed preflight but
+//! failed once landed.
+//!
+//! RPC nodes simulate against their latest bank rather than the ancestor of
+//! the transaction's slot, so the simulation sees the state as of its own
+//! slot, which is reported along with it.
+
+use {
+    crate::rpc::RpcApi,
+    solana_client::rpc_config::RpcSimulateTransactionConfig,
+    solana_sdk::{
+        commitment_config::CommitmentConfig,
+        transaction::{TransactionError, VersionedTransaction},
+    },
+};
+
+pub struct Preflight {
+    /// The slot of the bank the transaction was simulated against.
+    pub slot: u64,
+    pub err: Option<TransactionError>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+}
+
+impl Preflight {
+    /// Simulate the transaction as preflight would, without verifying its
+    /// signatures and with a recent blockhash, as its own has expired.
+    pub fn run(rpc: &dyn RpcApi, transaction: &VersionedTransaction) -> Result<Self, String> {
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let response = rpc
+            .simulate_transaction(transaction, config)
+            .map_err(|err| format!("Simulation failed: {err}"))?;
+        Ok(Self {
+            slot: response.context.slot,
+            err: response.value.err,
+            logs: response.value.logs.unwrap_or_default(),
+            units_consumed: response.value.units_consumed,
+        })
+    }
+
+    /// How the simulation compares with the error recorded on chain.
+    pub fn verdict(&self, on_chain: &TransactionError) -> &'static str {
+        match &self.err {
+            None => {
+                "Succeeds now: the state it failed on has changed since, or it depended on \
+                 transactions landing before it in its block"
+            }
+            Some(err) if err == on_chain => {
+                "Fails the same way now: the state it failed on persists"
+            }
+            Some(_) => "Fails differently now: the state has changed since",
+        }
+    }
+}
+
+/// The index of the first line where the logs differ, if they do.
+pub fn first_divergence(on_chain: &[String], simulated: &[String]) -> Option<usize> {
+    let common = on_chain
+        .iter()
+        .zip(simulated)
+        .position(|(on_chain, simulated)| on_chain != simulated);
+    common.or_else(|| {
+        (on_chain.len() != simulated.len()).then(|| on_chain.len().min(simulated.len()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk::instruction::InstructionError};
+
+    #[test]
+    fn compares_with_the_on_chain_outcome() {
+        let logs = |lines: &[&str]| lines.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let on_chain = logs(&["invoke [1]", "Slippage exceeded", "failed"]);
+        assert_eq!(first_divergence(&on_chain, &on_chain), None);
+        let simulated = logs(&["invoke [1]", "success"]);
+        assert_eq!(first_divergence(&on_chain, &simulated), Some(1));
+        assert_eq!(first_divergence(&on_chain, &on_chain[..2]), Some(2));
+
+        let custom = |code| TransactionError::InstructionError(0, InstructionError::Custom(code));
+        let mut preflight = Preflight {
+            slot: 100,
+            err: None,
+            logs: simulated,
+            units_consumed: Some(1000),
+        };
+        assert!(preflight.verdict(&custom(1)).starts_with("Succeeds now"));
+        preflight.err = Some(custom(1));
+        assert!(preflight
+            .verdict(&custom(1))
+            .starts_with("Fails the same way"));
+        assert!(preflight
+            .verdict(&custom(2))
+            .starts_with("Fails differently"));
+    }
+}
@@ -0,0 +1,170 @@
+//! Coloring the bytes of a transaction by the program they belong to rather
+//! than by field, so that multi-protocol transactions are segmented by
+//! protocol: each program gets a hue, and the fields of its instructions
+//! shades of it.
+
+use {
+    crate::transaction_byte_sections::{SectionKind, TransactionByteSection},
+    ratatui::style::Color,
+    solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction},
+};
+
+/// The color of bytes which belong to no program, or to several.
+const SHARED_COLOR: Color = Color::Rgb(64, 64, 64);
+
+/// The sections recolored by program, with the labels of instruction
+/// sections prefixed by the name of their program.
+pub fn color_by_program(
+    sections: &[TransactionByteSection],
+    transaction: &VersionedTransaction,
+    name: impl Fn(&Pubkey) -> String,
+) -> Vec<TransactionByteSection> {
+    let message = &transaction.message;
+    let account_keys = message.static_account_keys();
+    let instructions = message.instructions();
+    // Programs take hues in the order they are first invoked.
+    let mut programs = Vec::<usize>::new();
+    for instruction in instructions {
+        let program_index = instruction.program_id_index as usize;
+        if !programs.contains(&program_index) {
+            programs.push(program_index);
+        }
+    }
+    let hue = |program_index: usize| {
+        let position = programs.iter().position(|index| *index == program_index)?;
+        // The golden angle spreads any number of hues apart.
+        Some((position as f64 * 137.5) % 360.0)
+    };
+    let program_of = |instruction_index: usize| {
+        instructions
+            .get(instruction_index)
+            .map(|instruction| instruction.program_id_index as usize)
+    };
+    // An account key takes the hue of the program it is, or of the only
+    // program whose instructions use it.
+    let key_program = |key_index: usize| {
+        if programs.contains(&key_index) {
+            return Some(key_index);
+        }
+        let mut users = instructions
+            .iter()
+            .filter(|instruction| instruction.accounts.contains(&(key_index as u8)))
+            .map(|instruction| instruction.program_id_index as usize);
+        let user = users.next()?;
+        users.all(|other| other == user).then_some(user)
+    };
+
+    sections
+        .iter()
+        .map(|section| {
+            let (program_index, lightness) = match section.kind {
+                SectionKind::StaticAccountKey(index) => (key_program(index), 0.30),
+                SectionKind::InstructionProgramIdIndex(index) => (program_of(index), 0.30),
+                SectionKind::InstructionAccountsCount(index) => (program_of(index), 0.38),
+                SectionKind::InstructionAccounts(index) => (program_of(index), 0.46),
+                SectionKind::InstructionDataLength(index) => (program_of(index), 0.54),
+                SectionKind::InstructionData(index) => (program_of(index), 0.62),
+                _ => (None, 0.0),
+            };
+            let color = program_index
+                .and_then(hue)
+                .map_or(SHARED_COLOR, |hue| hsl(hue, 0.6, lightness));
+            let label = match (section.kind.instruction_index(), &section.label) {
+                (Some(_), Some(label)) => {
+                    let program = program_index
+                        .and_then(|index| account_keys.get(index))
+                        .map_or_else(|| "invalid program".to_string(), &name);
+                    Some(format!("{program}: {label}"))
+                }
+                (_, label) => label.clone(),
+            };
+            TransactionByteSection {
+                label,
+                bytes: section.bytes.clone(),
+                color,
+                kind: section.kind,
+            }
+        })
+        .collect()
+}
+
+/// The color of a hue in degrees, with saturation and lightness from 0 to 1.
+fn hsl(hue: f64, saturation: f64, lightness: f64) -> Color {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |value: f64| ((value + m) * 255.0).round() as u8;
+    Color::Rgb(channel(r), channel(g), channel(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::transaction_byte_sections::get_transaction_byte_sections,
+        solana_sdk::{
+            hash::Hash,
+            instruction::Instruction,
+            message::Message,
+            system_instruction,
+            transaction::{Transaction, VersionedTransaction},
+        },
+    };
+
+    #[test]
+    fn shades_each_program_in_its_own_hue() {
+        let (payer, to, memo) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let instructions = [
+            system_instruction::transfer(&payer, &to, 1),
+            Instruction::new_with_bytes(memo, b"hi", vec![]),
+        ];
+        let message = Message::new_with_blockhash(&instructions, Some(&payer), &Hash::default());
+        let transaction = VersionedTransaction::from(Transaction::new_unsigned(message));
+        let mut sections = vec![];
+        get_transaction_byte_sections(&transaction, &mut sections);
+
+        let colored = color_by_program(&sections, &transaction, |pubkey| {
+            if *pubkey == memo {
+                "memo".to_string()
+            } else {
+                "system".to_string()
+            }
+        });
+        let color = |kind| {
+            colored
+                .iter()
+                .find(|section| section.kind == kind)
+                .unwrap()
+                .color
+        };
+        assert_eq!(
+            color(SectionKind::InstructionProgramIdIndex(0)),
+            hsl(0.0, 0.6, 0.30)
+        );
+        assert_eq!(color(SectionKind::InstructionData(0)), hsl(0.0, 0.6, 0.62));
+        assert_eq!(
+            color(SectionKind::InstructionData(1)),
+            hsl(137.5, 0.6, 0.62)
+        );
+        // The recipient is only used by the transfer.
+        assert_eq!(color(SectionKind::StaticAccountKey(1)), hsl(0.0, 0.6, 0.30));
+        assert_eq!(color(SectionKind::RecentBlockhash), SHARED_COLOR);
+        assert!(colored
+            .iter()
+            .any(|section| section.label.as_deref() == Some("memo: Instruction Data")));
+
+        assert_eq!(hsl(120.0, 1.0, 0.5), Color::Rgb(0, 255, 0));
+    }
+}
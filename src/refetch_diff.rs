@@ -0,0 +1,191 @@
+//! What changed between two fetches of the same signature: the transaction,
+//! compared structurally, and its status, which progresses as the
+//! transaction is confirmed and finalized.
+
+use {
+    solana_sdk::transaction::VersionedTransaction,
+    solana_transaction_status::{
+        option_serializer::OptionSerializer, UiLoadedAddresses, UiTransactionStatusMeta,
+    },
+    solana_transaction_tui::transaction_diff::{Change, ChangeKind, TransactionDiff},
+    std::fmt,
+};
+
+/// A fetched copy of a transaction.
+pub struct Fetched<'a> {
+    pub transaction: &'a VersionedTransaction,
+    pub meta: Option<&'a UiTransactionStatusMeta>,
+    pub slot: Option<u64>,
+    pub block_time: Option<i64>,
+}
+
+pub enum RefetchChange {
+    Transaction(Change),
+    /// A field of the status, with its values before and after.
+    Status {
+        field: &'static str,
+        before: Option<String>,
+        after: Option<String>,
+    },
+}
+
+impl RefetchChange {
+    pub fn kind(&self) -> ChangeKind {
+        match self {
+            Self::Transaction(change) => change.kind,
+            Self::Status { before: None, .. } => ChangeKind::Added,
+            Self::Status { after: None, .. } => ChangeKind::Removed,
+            Self::Status { .. } => ChangeKind::Modified,
+        }
+    }
+}
+
+impl fmt::Display for RefetchChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Transaction(change) => write!(f, "{change}"),
+            Self::Status {
+                field,
+                before,
+                after,
+            } => {
+                let value = |value: &Option<String>| value.clone().unwrap_or("none".to_string());
+                write!(f, "{field}: {} -> {}", value(before), value(after))
+            }
+        }
+    }
+}
+
+/// The changes from the copy displayed `before` to the refetched one.
+pub fn diff(before: &Fetched, after: &Fetched) -> Vec<RefetchChange> {
+    let mut changes = TransactionDiff::new(before.transaction, after.transaction)
+        .changes
+        .into_iter()
+        .map(RefetchChange::Transaction)
+        .collect::<Vec<_>>();
+    let (before_fields, after_fields) = (status_fields(before), status_fields(after));
+    for ((field, before), (_, after)) in before_fields.into_iter().zip(after_fields) {
+        if before != after {
+            changes.push(RefetchChange::Status {
+                field,
+                before,
+                after,
+            });
+        }
+    }
+    changes
+}
+
+fn status_fields(fetched: &Fetched) -> [(&'static str, Option<String>); 9] {
+    let meta = fetched.meta;
+    let count = |len: Option<usize>| len.map(|len| len.to_string());
+    [
+        ("slot", fetched.slot.map(|slot| slot.to_string())),
+        (
+            "block time",
+            fetched.block_time.map(|block_time| block_time.to_string()),
+        ),
+        (
+            "status",
+            meta.map(|meta| match &meta.err {
+                Some(err) => format!("failed: {err}"),
+                None => "succeeded".to_string(),
+            }),
+        ),
+        ("fee", meta.map(|meta| meta.fee.to_string())),
+        (
+            "compute units",
+            meta.and_then(|meta| {
+                Option::<&u64>::from(meta.compute_units_consumed.as_ref()).map(u64::to_string)
+            }),
+        ),
+        (
+            "log messages",
+            count(meta.and_then(|meta| len(&meta.log_messages))),
+        ),
+        (
+            "inner instructions",
+            count(meta.and_then(|meta| len(&meta.inner_instructions))),
+        ),
+        (
+            "loaded addresses",
+            count(meta.and_then(|meta| {
+                Option::<&UiLoadedAddresses>::from(meta.loaded_addresses.as_ref())
+                    .map(|addresses| addresses.writable.len() + addresses.readonly.len())
+            })),
+        ),
+        ("rewards", count(meta.and_then(|meta| len(&meta.rewards)))),
+    ]
+}
+
+fn len<T>(values: &OptionSerializer<Vec<T>>) -> Option<usize> {
+    Option::<&Vec<T>>::from(values.as_ref()).map(Vec::len)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash, pubkey::Pubkey, signature::Keypair, signer::Signer, system_instruction,
+            transaction::Transaction,
+        },
+    };
+
+    fn meta(fee: u64, log_messages: Vec<String>) -> UiTransactionStatusMeta {
+        UiTransactionStatusMeta {
+            err: None,
+            status: Ok(()),
+            fee,
+            pre_balances: vec![],
+            post_balances: vec![],
+            inner_instructions: OptionSerializer::None,
+            log_messages: OptionSerializer::Some(log_messages),
+            pre_token_balances: OptionSerializer::None,
+            post_token_balances: OptionSerializer::None,
+            rewards: OptionSerializer::None,
+            loaded_addresses: OptionSerializer::Skip,
+            return_data: OptionSerializer::Skip,
+            compute_units_consumed: OptionSerializer::Skip,
+        }
+    }
+
+    #[test]
+    fn reports_status_progress() {
+        let payer = Keypair::new();
+        let instruction = system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+        let transaction = VersionedTransaction::from(Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[&payer],
+            Hash::new_unique(),
+        ));
+        let (first_meta, second_meta) = (meta(5000, vec![]), meta(5000, vec!["log".to_string()]));
+        let fetched = |meta, slot| Fetched {
+            transaction: &transaction,
+            meta,
+            slot,
+            block_time: None,
+        };
+
+        let changes = diff(&fetched(None, None), &fetched(Some(&first_meta), Some(42)));
+        let changes = changes.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            [
+                "slot: none -> 42",
+                "status: none -> succeeded",
+                "fee: none -> 5000",
+                "log messages: none -> 0",
+            ]
+        );
+
+        let changes = diff(
+            &fetched(Some(&first_meta), Some(42)),
+            &fetched(Some(&second_meta), Some(42)),
+        );
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind(), ChangeKind::Modified);
+        assert_eq!(changes[0].to_string(), "log messages: 0 -> 1");
+    }
+}
@@ -0,0 +1,163 @@
+//! Other transactions touching an account in the slots around a transaction,
+//! to pivot between the steps of MEV or exploit sequences.
+//!
+//! `getSignaturesForAddress` pages backwards from a signature rather than a
+//! slot, so paging starts from a signature of the first block after the
+//! window.
+
+use {
+    crate::{failures::FailureKind, rpc::RpcApi},
+    solana_client::{
+        rpc_config::{RpcBlockConfig, RpcSignaturesForAddressConfig},
+        rpc_response::RpcConfirmedTransactionStatusWithSignature,
+    },
+    solana_sdk::{
+        commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature,
+        transaction::TransactionError,
+    },
+    solana_transaction_status::TransactionDetails,
+    std::str::FromStr,
+};
+
+pub const DEFAULT_WINDOW: u64 = 25;
+const PAGE_SIZE: usize = 1000;
+/// Busy accounts have too many transactions to list them all.
+const MAX_PAGES: usize = 5;
+
+pub struct Candidate {
+    pub signature: Signature,
+    pub slot: u64,
+    pub err: Option<TransactionError>,
+}
+
+impl Candidate {
+    /// What the transaction failed with, if it failed. Statuses do not say
+    /// which program failed.
+    pub fn failure(&self) -> Option<FailureKind> {
+        Some(FailureKind::new(self.err.as_ref()?, |_| None))
+    }
+}
+
+pub struct Related {
+    pub address: Pubkey,
+    pub slot: u64,
+    /// The number of slots searched either side of `slot`.
+    pub window: u64,
+    /// The transactions found, oldest first.
+    pub candidates: Vec<Candidate>,
+    /// Whether paging stopped before the start of the window.
+    pub truncated: bool,
+}
+
+impl Related {
+    pub fn fetch(
+        rpc: &dyn RpcApi,
+        address: Pubkey,
+        slot: u64,
+        window: u64,
+    ) -> Result<Self, String> {
+        let (first_slot, last_slot) = (slot.saturating_sub(window), slot + window);
+        let mut before = anchor(rpc, last_slot + 1);
+        let mut candidates = vec![];
+        let mut truncated = true;
+        for _ in 0..MAX_PAGES {
+            let config = RpcSignaturesForAddressConfig {
+                before: before.map(|signature| signature.to_string()),
+                limit: Some(PAGE_SIZE),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..RpcSignaturesForAddressConfig::default()
+            };
+            let page = rpc
+                .get_signatures_for_address(&address, config)
+                .map_err(|err| format!("Failed to get signatures for {address}: {err}"))?;
+            let reached_start = in_window(&page, first_slot, last_slot, &mut candidates);
+            if reached_start || page.len() < PAGE_SIZE {
+                truncated = false;
+                break;
+            }
+            before = page
+                .last()
+                .and_then(|status| Signature::from_str(&status.signature).ok());
+        }
+        candidates.reverse();
+        Ok(Self {
+            address,
+            slot,
+            window,
+            candidates,
+            truncated,
+        })
+    }
+}
+
+/// A signature of the first block from `slot`, to page backwards from, or
+/// `None` to page from the latest transaction.
+fn anchor(rpc: &dyn RpcApi, slot: u64) -> Option<Signature> {
+    let block_slot = *rpc.get_blocks_with_limit(slot, 1).ok()?.first()?;
+    let config = RpcBlockConfig {
+        transaction_details: Some(TransactionDetails::Signatures),
+        rewards: Some(false),
+        max_supported_transaction_version: Some(0),
+        ..RpcBlockConfig::default()
+    };
+    let block = rpc.get_block(block_slot, config).ok()?;
+    Signature::from_str(block.signatures?.first()?).ok()
+}
+
+/// Add the statuses of a page, newest first, which are within the window.
+/// Returns whether the page reaches past the start of the window.
+fn in_window(
+    page: &[RpcConfirmedTransactionStatusWithSignature],
+    first_slot: u64,
+    last_slot: u64,
+    candidates: &mut Vec<Candidate>,
+) -> bool {
+    for status in page {
+        if status.slot < first_slot {
+            return true;
+        }
+        if status.slot > last_slot {
+            continue;
+        }
+        if let Ok(signature) = Signature::from_str(&status.signature) {
+            candidates.push(Candidate {
+                signature,
+                slot: status.slot,
+                err: status.err.clone(),
+            });
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_statuses_within_the_window() {
+        let status = |slot, err| RpcConfirmedTransactionStatusWithSignature {
+            signature: Signature::new_unique().to_string(),
+            slot,
+            err,
+            memo: None,
+            block_time: None,
+            confirmation_status: None,
+        };
+        let mut candidates = vec![];
+        let page = [
+            status(120, None),
+            status(110, Some(TransactionError::AccountInUse)),
+            status(100, None),
+        ];
+        assert!(!in_window(&page, 90, 110, &mut candidates));
+        let page = [status(95, None), status(89, None), status(80, None)];
+        assert!(in_window(&page, 90, 110, &mut candidates));
+
+        let found = candidates
+            .iter()
+            .map(|candidate| (candidate.slot, candidate.err.is_some()))
+            .collect::<Vec<_>>();
+        assert_eq!(found, [(110, true), (100, false), (95, false)]);
+    }
+}
@@ -0,0 +1,59 @@
+//! The staged reveal of a newly loaded transaction: its sections appear in
+//! order, with their labels flashed in the status bar, to follow the layout of
+//! a transaction in live demos.
+
+use std::time::{Duration, Instant};
+
+/// The time each section is shown alone, for transactions with few sections.
+const MAX_STEP: Duration = Duration::from_millis(150);
+/// Larger transactions are revealed faster, to take no longer than this.
+const MAX_DURATION: Duration = Duration::from_secs(3);
+
+pub struct Reveal {
+    started: Instant,
+    num_sections: usize,
+}
+
+impl Reveal {
+    pub fn new(num_sections: usize) -> Self {
+        Self {
+            started: Instant::now(),
+            num_sections,
+        }
+    }
+
+    fn step(&self) -> Duration {
+        MAX_STEP.min(MAX_DURATION / self.num_sections.max(1) as u32)
+    }
+
+    /// The number of sections shown `elapsed` after the start, or `None` once
+    /// all of them are.
+    fn num_revealed_at(&self, elapsed: Duration) -> Option<usize> {
+        let step = self.step().as_millis().max(1);
+        let num_revealed = (elapsed.as_millis() / step) as usize + 1;
+        (num_revealed < self.num_sections).then_some(num_revealed)
+    }
+
+    /// The number of sections shown so far, or `None` once all of them are.
+    pub fn num_revealed(&self) -> Option<usize> {
+        self.num_revealed_at(self.started.elapsed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reveals_sections_in_steps() {
+        let reveal = Reveal::new(4);
+        assert_eq!(reveal.num_revealed_at(Duration::ZERO), Some(1));
+        assert_eq!(reveal.num_revealed_at(MAX_STEP * 2), Some(3));
+        assert_eq!(reveal.num_revealed_at(MAX_STEP * 3), None);
+
+        // 1000 sections take no longer than the maximum duration.
+        let reveal = Reveal::new(1000);
+        assert_eq!(reveal.num_revealed_at(MAX_DURATION / 2), Some(501));
+        assert_eq!(reveal.num_revealed_at(MAX_DURATION), None);
+    }
+}
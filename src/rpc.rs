@@ -0,0 +1,621 @@
+#[cfg(feature = "blockstore")]
+use crate::blockstore::LocalLedger;
+use {
+    crate::cassette::{Recorder, Recording, Replayer, Replaying},
+    base64::{prelude::BASE64_STANDARD, Engine},
+    serde::de::DeserializeOwned,
+    serde_json::{json, Value},
+    solana_account_decoder::{UiAccount, UiAccountEncoding, UiDataSliceConfig},
+    solana_client::{
+        rpc_client::RpcClient,
+        rpc_config::{
+            RpcAccountInfoConfig, RpcBlockConfig, RpcSendTransactionConfig,
+            RpcSignaturesForAddressConfig, RpcSimulateTransactionConfig, RpcTransactionConfig,
+        },
+        rpc_request::RpcRequest,
+        rpc_response::{
+            Response, RpcBlockhash, RpcConfirmedTransactionStatusWithSignature, RpcLeaderSchedule,
+            RpcSimulateTransactionResult, RpcVoteAccountStatus,
+        },
+    },
+    solana_sdk::{
+        account::Account, commitment_config::CommitmentConfig, epoch_schedule::EpochSchedule,
+        hash::Hash, pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction,
+    },
+    solana_transaction_status::{
+        option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+        TransactionStatus, UiConfirmedBlock, UiTransactionEncoding,
+    },
+    std::{path::PathBuf, str::FromStr, sync::Arc},
+};
+
+/// Directory of canned responses used by `--mock` if none is given.
+pub const DEFAULT_MOCK_DIR: &str = "mock";
+
+/// A request to the endpoint, with the params the features send.
+#[derive(Debug, Clone)]
+pub enum Request {
+    GetTransaction {
+        signature: Signature,
+        config: RpcTransactionConfig,
+    },
+    GetSignatureStatuses {
+        signatures: Vec<Signature>,
+    },
+    GetSlot {
+        commitment: CommitmentConfig,
+    },
+    GetLatestBlockhash {
+        commitment: CommitmentConfig,
+    },
+    GetMultipleAccounts {
+        pubkeys: Vec<Pubkey>,
+        config: RpcAccountInfoConfig,
+    },
+    /// A transaction serialized as base64.
+    SendTransaction {
+        transaction: String,
+        config: RpcSendTransactionConfig,
+    },
+    /// A transaction serialized as base64.
+    SimulateTransaction {
+        transaction: String,
+        config: RpcSimulateTransactionConfig,
+    },
+    GetSignaturesForAddress {
+        address: Pubkey,
+        config: RpcSignaturesForAddressConfig,
+    },
+    GetSlotLeaders {
+        start_slot: u64,
+        limit: u64,
+    },
+    GetVoteAccounts {
+        commitment: CommitmentConfig,
+    },
+    GetEpochSchedule,
+    GetLeaderSchedule {
+        slot: u64,
+        commitment: CommitmentConfig,
+    },
+    GetBlocks {
+        start_slot: u64,
+        end_slot: Option<u64>,
+        commitment: CommitmentConfig,
+    },
+    GetBlocksWithLimit {
+        start_slot: u64,
+        limit: usize,
+        commitment: CommitmentConfig,
+    },
+    GetBlock {
+        slot: u64,
+        config: RpcBlockConfig,
+    },
+    GetHealth,
+    /// A method typed in the RPC console.
+    Custom {
+        method: String,
+        params: Value,
+    },
+}
+
+impl Request {
+    pub fn method(&self) -> &str {
+        match self {
+            Self::Custom { method, .. } => method,
+            request => request.static_method().unwrap_or_default(),
+        }
+    }
+
+    /// The method of the requests other than `Custom`, whose method is
+    /// typed at runtime.
+    fn static_method(&self) -> Option<&'static str> {
+        let method = match self {
+            Self::GetTransaction { .. } => "getTransaction",
+            Self::GetSignatureStatuses { .. } => "getSignatureStatuses",
+            Self::GetSlot { .. } => "getSlot",
+            Self::GetLatestBlockhash { .. } => "getLatestBlockhash",
+            Self::GetMultipleAccounts { .. } => "getMultipleAccounts",
+            Self::SendTransaction { .. } => "sendTransaction",
+            Self::SimulateTransaction { .. } => "simulateTransaction",
+            Self::GetSignaturesForAddress { .. } => "getSignaturesForAddress",
+            Self::GetSlotLeaders { .. } => "getSlotLeaders",
+            Self::GetVoteAccounts { .. } => "getVoteAccounts",
+            Self::GetEpochSchedule => "getEpochSchedule",
+            Self::GetLeaderSchedule { .. } => "getLeaderSchedule",
+            Self::GetBlocks { .. } => "getBlocks",
+            Self::GetBlocksWithLimit { .. } => "getBlocksWithLimit",
+            Self::GetBlock { .. } => "getBlock",
+            Self::GetHealth => "getHealth",
+            Self::Custom { .. } => return None,
+        };
+        Some(method)
+    }
+
+    /// The params of the JSON-RPC request.
+    pub fn params(&self) -> Value {
+        let to_strings =
+            |items: &[Signature]| items.iter().map(ToString::to_string).collect::<Vec<_>>();
+        match self {
+            Self::GetTransaction { signature, config } => json!([signature.to_string(), config]),
+            Self::GetSignatureStatuses { signatures } => json!([to_strings(signatures)]),
+            Self::GetSlot { commitment }
+            | Self::GetLatestBlockhash { commitment }
+            | Self::GetVoteAccounts { commitment } => json!([commitment]),
+            Self::GetMultipleAccounts { pubkeys, config } => {
+                let pubkeys = pubkeys.iter().map(ToString::to_string).collect::<Vec<_>>();
+                json!([pubkeys, config])
+            }
+            Self::SendTransaction {
+                transaction,
+                config,
+            } => json!([transaction, config]),
+            Self::SimulateTransaction {
+                transaction,
+                config,
+            } => json!([transaction, config]),
+            Self::GetSignaturesForAddress { address, config } => {
+                json!([address.to_string(), config])
+            }
+            Self::GetSlotLeaders { start_slot, limit } => json!([start_slot, limit]),
+            Self::GetEpochSchedule | Self::GetHealth => Value::Null,
+            Self::GetLeaderSchedule { slot, commitment } => json!([slot, commitment]),
+            Self::GetBlocks {
+                start_slot,
+                end_slot,
+                commitment,
+            } => json!([start_slot, end_slot, commitment]),
+            Self::GetBlocksWithLimit {
+                start_slot,
+                limit,
+                commitment,
+            } => json!([start_slot, limit, commitment]),
+            Self::GetBlock { slot, config } => json!([slot, config]),
+            Self::Custom { params, .. } => params.clone(),
+        }
+    }
+}
+
+/// The endpoint requests made by commands, so they can be answered without
+/// a network. Sources answer `send`, and the other requests are built on
+/// it, so every source serves every request the features make.
+pub trait RpcApi: Send + Sync {
+    /// The result of the response to a request.
+    fn send(&self, request: Request) -> Result<Value, String>;
+
+    fn get_transaction(
+        &self,
+        signature: &Signature,
+        options: &FetchOptions,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, String> {
+        self.get_transaction_with_commitment(signature, options, CommitmentConfig::confirmed())
+    }
+
+    fn get_transaction_with_commitment(
+        &self,
+        signature: &Signature,
+        options: &FetchOptions,
+        commitment: CommitmentConfig,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, String> {
+        let config = RpcTransactionConfig {
+            encoding: Some(options.encoding.encoding()),
+            commitment: Some(commitment),
+            max_supported_transaction_version: options.max_supported_transaction_version(),
+        };
+        let request = Request::GetTransaction {
+            signature: *signature,
+            config,
+        };
+        call::<Option<_>>(self, request)?
+            .ok_or_else(|| format!("Transaction {signature} not found"))
+    }
+
+    /// The entries of a slot as an entries blob. The RPC API has no request
+    /// for entries, so only a local ledger serves them.
+    fn get_slot_entries(&self, slot: u64) -> Result<Vec<u8>, String> {
+        Err(format!(
+            "Entries of slot {slot} are only available from a local ledger"
+        ))
+    }
+
+    /// The statuses of signatures, `None` for those the endpoint has not seen.
+    fn get_signature_statuses(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<Option<TransactionStatus>>, String> {
+        let request = Request::GetSignatureStatuses {
+            signatures: signatures.to_vec(),
+        };
+        Ok(call::<Response<_>>(self, request)?.value)
+    }
+
+    fn get_slot(&self, commitment: CommitmentConfig) -> Result<u64, String> {
+        call(self, Request::GetSlot { commitment })
+    }
+
+    fn get_latest_blockhash(&self, commitment: CommitmentConfig) -> Result<Hash, String> {
+        let response: Response<RpcBlockhash> =
+            call(self, Request::GetLatestBlockhash { commitment })?;
+        Hash::from_str(&response.value.blockhash)
+            .map_err(|err| format!("Invalid blockhash {}: {err}", response.value.blockhash))
+    }
+
+    /// Accounts by pubkey, `None` for those which do not exist.
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>, String> {
+        get_accounts(self, pubkeys, None)
+    }
+
+    /// Accounts by pubkey with only the first `length` bytes of their data.
+    fn get_multiple_account_prefixes(
+        &self,
+        pubkeys: &[Pubkey],
+        length: usize,
+    ) -> Result<Vec<Option<Account>>, String> {
+        get_accounts(self, pubkeys, Some(UiDataSliceConfig { offset: 0, length }))
+    }
+
+    fn send_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        config: RpcSendTransactionConfig,
+    ) -> Result<Signature, String> {
+        let request = Request::SendTransaction {
+            transaction: serialize(transaction)?,
+            config: RpcSendTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                ..config
+            },
+        };
+        let signature: String = call(self, request)?;
+        Signature::from_str(&signature)
+            .map_err(|err| format!("Invalid signature {signature}: {err}"))
+    }
+
+    fn simulate_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        config: RpcSimulateTransactionConfig,
+    ) -> Result<Response<RpcSimulateTransactionResult>, String> {
+        let request = Request::SimulateTransaction {
+            transaction: serialize(transaction)?,
+            config: RpcSimulateTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                ..config
+            },
+        };
+        call(self, request)
+    }
+
+    /// The statuses of the transactions of an address, newest first.
+    fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        config: RpcSignaturesForAddressConfig,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, String> {
+        let request = Request::GetSignaturesForAddress {
+            address: *address,
+            config,
+        };
+        call(self, request)
+    }
+
+    fn get_slot_leaders(&self, start_slot: u64, limit: u64) -> Result<Vec<Pubkey>, String> {
+        let leaders: Vec<String> = call(self, Request::GetSlotLeaders { start_slot, limit })?;
+        leaders
+            .iter()
+            .map(|leader| {
+                Pubkey::from_str(leader).map_err(|err| format!("Invalid leader {leader}: {err}"))
+            })
+            .collect()
+    }
+
+    fn get_vote_accounts(&self) -> Result<RpcVoteAccountStatus, String> {
+        let commitment = CommitmentConfig::confirmed();
+        call(self, Request::GetVoteAccounts { commitment })
+    }
+
+    fn get_epoch_schedule(&self) -> Result<EpochSchedule, String> {
+        call(self, Request::GetEpochSchedule)
+    }
+
+    /// The leader schedule of the epoch of `slot`, if the endpoint has it.
+    fn get_leader_schedule(&self, slot: u64) -> Result<Option<RpcLeaderSchedule>, String> {
+        let commitment = CommitmentConfig::confirmed();
+        call(self, Request::GetLeaderSchedule { slot, commitment })
+    }
+
+    /// The slots of the blocks from `start_slot` to `end_slot`, or to the
+    /// latest if `None`.
+    fn get_blocks(&self, start_slot: u64, end_slot: Option<u64>) -> Result<Vec<u64>, String> {
+        let request = Request::GetBlocks {
+            start_slot,
+            end_slot,
+            commitment: CommitmentConfig::confirmed(),
+        };
+        call(self, request)
+    }
+
+    fn get_blocks_with_limit(&self, start_slot: u64, limit: usize) -> Result<Vec<u64>, String> {
+        let request = Request::GetBlocksWithLimit {
+            start_slot,
+            limit,
+            commitment: CommitmentConfig::confirmed(),
+        };
+        call(self, request)
+    }
+
+    fn get_block(&self, slot: u64, config: RpcBlockConfig) -> Result<UiConfirmedBlock, String> {
+        call(self, Request::GetBlock { slot, config })
+    }
+
+    fn get_health(&self) -> Result<(), String> {
+        self.send(Request::GetHealth).map(|_| ())
+    }
+}
+
+/// Send a request and deserialize the result of its response.
+fn call<T: DeserializeOwned>(rpc: &(impl RpcApi + ?Sized), request: Request) -> Result<T, String> {
+    let method = request.method().to_string();
+    let result = rpc.send(request)?;
+    serde_json::from_value(result).map_err(|err| format!("Invalid {method} response: {err}"))
+}
+
+fn get_accounts(
+    rpc: &(impl RpcApi + ?Sized),
+    pubkeys: &[Pubkey],
+    data_slice: Option<UiDataSliceConfig>,
+) -> Result<Vec<Option<Account>>, String> {
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        data_slice,
+        commitment: Some(CommitmentConfig::confirmed()),
+        min_context_slot: None,
+    };
+    let request = Request::GetMultipleAccounts {
+        pubkeys: pubkeys.to_vec(),
+        config,
+    };
+    let response: Response<Vec<Option<UiAccount>>> =
+        call(rpc, request).map_err(|err| format!("Failed to get accounts: {err}"))?;
+    response
+        .value
+        .into_iter()
+        .map(|account| {
+            account
+                .map(|account| account.decode().ok_or("Failed to decode an account"))
+                .transpose()
+                .map_err(ToString::to_string)
+        })
+        .collect()
+}
+
+fn serialize(transaction: &VersionedTransaction) -> Result<String, String> {
+    let bytes = bincode::serialize(transaction)
+        .map_err(|err| format!("Failed to serialize the transaction: {err}"))?;
+    Ok(BASE64_STANDARD.encode(bytes))
+}
+
+impl RpcApi for RpcClient {
+    fn send(&self, request: Request) -> Result<Value, String> {
+        // Custom requests take a static method name. Those typed in the
+        // console are few, so leaking their names is negligible.
+        let method = request
+            .static_method()
+            .unwrap_or_else(|| Box::leak(request.method().to_string().into_boxed_str()));
+        RpcClient::send(self, RpcRequest::Custom { method }, request.params())
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// The `getTransaction` config of fetches, to reproduce how an RPC client
+/// sees a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchOptions {
+    pub encoding: FetchEncoding,
+    /// Request versioned transactions, whose meta holds the addresses loaded
+    /// from lookup tables. Fetching one fails otherwise.
+    pub loaded_addresses: bool,
+    /// Keep the rewards of the meta. `getTransaction` has no option for
+    /// them, so they are dropped once fetched.
+    pub rewards: bool,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            encoding: FetchEncoding::default(),
+            loaded_addresses: true,
+            rewards: true,
+        }
+    }
+}
+
+impl FetchOptions {
+    pub fn max_supported_transaction_version(&self) -> Option<u8> {
+        self.loaded_addresses.then_some(0)
+    }
+
+    /// Drop what the options exclude from a fetched transaction.
+    pub fn apply(&self, transaction: &mut EncodedConfirmedTransactionWithStatusMeta) {
+        if let (false, Some(meta)) = (self.rewards, &mut transaction.transaction.meta) {
+            meta.rewards = OptionSerializer::Skip;
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = self.encoding.name().to_string();
+        if self.loaded_addresses {
+            summary.push_str(", loaded addresses");
+        }
+        if self.rewards {
+            summary.push_str(", rewards");
+        }
+        summary
+    }
+}
+
+/// The encodings the transaction can be decoded from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FetchEncoding {
+    /// The deprecated `binary` encoding.
+    #[default]
+    Base58,
+    Base64,
+}
+
+impl FetchEncoding {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Base58 => Self::Base64,
+            Self::Base64 => Self::Base58,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Base58 => "base58",
+            Self::Base64 => "base64",
+        }
+    }
+
+    pub fn encoding(self) -> UiTransactionEncoding {
+        match self {
+            Self::Base58 => UiTransactionEncoding::Binary,
+            Self::Base64 => UiTransactionEncoding::Base64,
+        }
+    }
+}
+
+/// Where requests are answered.
+pub enum RpcSource {
+    /// The active endpoint.
+    Endpoint,
+    /// Canned responses, with `--mock`.
+    Mock(Arc<MockRpc>),
+    /// The active endpoint, recording each exchange, with `--record`.
+    Record(Arc<Recorder>),
+    /// The responses of a recorded session, with `--replay`.
+    Replay(Arc<Replayer>),
+    /// A local validator ledger, with `--ledger-dir`.
+    #[cfg(feature = "blockstore")]
+    Ledger(Arc<LocalLedger>),
+}
+
+impl RpcSource {
+    pub fn api(&self, endpoint: &str) -> Arc<dyn RpcApi> {
+        match self {
+            Self::Endpoint => Arc::new(RpcClient::new(endpoint.to_string())),
+            Self::Mock(mock_rpc) => mock_rpc.clone(),
+            Self::Record(recorder) => Arc::new(Recording {
+                endpoint: endpoint.to_string(),
+                client: RpcClient::new(endpoint.to_string()),
+                recorder: recorder.clone(),
+            }),
+            Self::Replay(replayer) => Arc::new(Replaying {
+                endpoint: endpoint.to_string(),
+                replayer: replayer.clone(),
+            }),
+            #[cfg(feature = "blockstore")]
+            Self::Ledger(ledger) => ledger.clone(),
+        }
+    }
+
+    /// Whether requests reach the endpoint.
+    pub fn uses_endpoint(&self) -> bool {
+        matches!(self, Self::Endpoint | Self::Record(_))
+    }
+
+    pub fn description(&self) -> Option<String> {
+        match self {
+            Self::Endpoint => None,
+            Self::Mock(mock_rpc) => Some(format!("mock responses from {}", mock_rpc.dir.display())),
+            Self::Record(recorder) => Some(format!("recording to {}", recorder.path.display())),
+            Self::Replay(replayer) => Some(format!("replaying {}", replayer.path.display())),
+            #[cfg(feature = "blockstore")]
+            Self::Ledger(ledger) => Some(format!("ledger at {}", ledger.path.display())),
+        }
+    }
+}
+
+/// Answers with canned results read from JSON files in a directory: the
+/// `getTransaction` result of each signature from `<signature>.json`, and the
+/// result of any other method from `<method>.json`, whatever its params.
+pub struct MockRpc {
+    pub dir: PathBuf,
+}
+
+impl MockRpc {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl RpcApi for MockRpc {
+    fn send(&self, request: Request) -> Result<Value, String> {
+        let name = match &request {
+            Request::GetTransaction { signature, .. } => signature.to_string(),
+            request => request.method().to_string(),
+        };
+        let path = self.dir.join(format!("{name}.json"));
+        let json = std::fs::read_to_string(&path)
+            .map_err(|_| format!("No canned response at {}", path.display()))?;
+        serde_json::from_str(&json)
+            .map_err(|err| format!("Invalid canned response {}: {err}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_serves_canned_results_of_each_method() {
+        let dir = std::env::temp_dir().join(format!("mock-rpc-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("getSlot.json"), "42").unwrap();
+        let status = r#"{"context": {"slot": 43}, "value": [{"slot": 41, "confirmations": null,
+            "err": null, "status": {"Ok": null}, "confirmationStatus": "finalized"}]}"#;
+        std::fs::write(dir.join("getSignatureStatuses.json"), status).unwrap();
+
+        let mock_rpc = MockRpc::new(&dir);
+        let slot = mock_rpc.get_slot(CommitmentConfig::confirmed());
+        let statuses = mock_rpc.get_signature_statuses(&[Signature::new_unique()]);
+        let blockhash = mock_rpc.get_latest_blockhash(CommitmentConfig::finalized());
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(slot, Ok(42));
+        let status = statuses.unwrap().pop().flatten().unwrap();
+        assert_eq!(status.slot, 41);
+        assert!(status.satisfies_commitment(CommitmentConfig::finalized()));
+        assert!(blockhash.unwrap_err().contains("getLatestBlockhash.json"));
+    }
+
+    #[test]
+    fn requests_carry_the_params_of_the_rpc_api() {
+        let signature = Signature::new_unique();
+        let request = Request::GetSignatureStatuses {
+            signatures: vec![signature],
+        };
+        assert_eq!(request.method(), "getSignatureStatuses");
+        assert_eq!(request.params(), json!([[signature.to_string()]]));
+        let request = Request::GetBlocks {
+            start_slot: 10,
+            end_slot: None,
+            commitment: CommitmentConfig::confirmed(),
+        };
+        assert_eq!(
+            request.params(),
+            json!([10, null, {"commitment": "confirmed"}])
+        );
+        let request = Request::Custom {
+            method: "getBalance".to_string(),
+            params: json!(["11111111111111111111111111111111"]),
+        };
+        assert_eq!(request.method(), "getBalance");
+        assert_eq!(
+            request.params(),
+            json!(["11111111111111111111111111111111"])
+        );
+        assert_eq!(Request::GetHealth.params(), Value::Null);
+    }
+}
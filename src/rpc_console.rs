@@ -0,0 +1,174 @@
+//! A console for JSON-RPC methods the structured views do not cover, with the
+//! requests sent persisted as a history between sessions.
+
+use {
+    crate::{
+        rpc::{Request, RpcApi},
+        storage::{self, FileChange},
+    },
+    ratatui::{
+        style::{Color, Style},
+        text::{Line, Span},
+    },
+    serde::{Deserialize, Serialize},
+    serde_json::Value,
+    std::io,
+};
+
+const RPC_HISTORY_FILE: &str = "rpc_history.json";
+/// Older requests are forgotten.
+const MAX_HISTORY: usize = 100;
+
+/// Parse a request typed as a method name optionally followed by its params
+/// as a JSON array or object, e.g. `getBalance ["<address>"]`.
+pub fn parse_request(text: &str) -> Result<(String, Value), String> {
+    let text = text.trim();
+    let (method, params) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    if method.is_empty() {
+        return Err("Enter a method, optionally followed by its params".to_string());
+    }
+    let params = match params.trim() {
+        "" => Value::Null,
+        params => serde_json::from_str(params).map_err(|err| format!("Invalid params: {err}"))?,
+    };
+    if !matches!(params, Value::Null | Value::Array(_) | Value::Object(_)) {
+        return Err("Params must be a JSON array or object".to_string());
+    }
+    Ok((method.to_string(), params))
+}
+
+/// Send a request, returning the result of the response.
+pub fn call(rpc: &dyn RpcApi, method: String, params: Value) -> Result<Value, String> {
+    rpc.send(Request::Custom { method, params })
+}
+
+/// Pretty print a JSON value, colored by token.
+pub fn highlight(value: &Value, monochrome: bool) -> Vec<Line<'static>> {
+    let pretty = serde_json::to_string_pretty(value).unwrap_or_default();
+    pretty
+        .lines()
+        .map(|line| {
+            if monochrome {
+                return Line::raw(line.to_string());
+            }
+            Line::from(
+                tokens(line)
+                    .into_iter()
+                    .map(|(token, color)| Span::styled(token, Style::default().fg(color)))
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect()
+}
+
+/// Split a line of pretty printed JSON into colored tokens.
+fn tokens(line: &str) -> Vec<(String, Color)> {
+    let mut tokens = vec![];
+    let mut chars = line.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let mut end = start + c.len_utf8();
+        let color = match c {
+            '"' => {
+                let mut escaped = false;
+                for (index, c) in chars.by_ref() {
+                    end = index + c.len_utf8();
+                    match c {
+                        '"' if !escaped => break,
+                        '\\' => escaped = !escaped,
+                        _ => escaped = false,
+                    }
+                }
+                // Object keys are followed by a colon.
+                if line[end..].starts_with(':') {
+                    Color::Cyan
+                } else {
+                    Color::Green
+                }
+            }
+            '-' | '0'..='9' | 't' | 'f' | 'n' => {
+                while let Some((index, c)) =
+                    chars.next_if(|(_, c)| c.is_ascii_alphanumeric() || ".+-".contains(*c))
+                {
+                    end = index + c.len_utf8();
+                }
+                match c {
+                    't' | 'f' | 'n' => Color::Magenta,
+                    _ => Color::Yellow,
+                }
+            }
+            _ => Color::Reset,
+        };
+        tokens.push((line[start..end].to_string(), color));
+    }
+    tokens
+}
+
+/// The requests sent, oldest first.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RpcHistory {
+    requests: Vec<String>,
+}
+
+impl RpcHistory {
+    pub fn load() -> Self {
+        storage::load_json(RPC_HISTORY_FILE)
+    }
+
+    pub fn save(&self) -> io::Result<FileChange> {
+        storage::save_json(RPC_HISTORY_FILE, self)
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.requests.get(index).map(String::as_str)
+    }
+
+    pub fn num_requests(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Record a request as the most recent, removing an earlier duplicate.
+    pub fn record(&mut self, request: &str) {
+        self.requests.retain(|existing| existing != request);
+        self.requests.push(request.to_string());
+        let excess = self.requests.len().saturating_sub(MAX_HISTORY);
+        self.requests.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    #[test]
+    fn parses_requests_and_highlights_responses() {
+        assert_eq!(
+            parse_request("getSlot").unwrap(),
+            ("getSlot".to_string(), Value::Null)
+        );
+        assert_eq!(
+            parse_request(" getBalance  [\"11111111111111111111111111111111\"] ").unwrap(),
+            (
+                "getBalance".to_string(),
+                json!(["11111111111111111111111111111111"])
+            )
+        );
+        assert!(parse_request("getBalance 5").is_err());
+        assert!(parse_request("getBalance [").is_err());
+
+        let tokens = tokens(r#"  "key \"a\"": [-1.5e3, "value", true, null],"#);
+        let colored = tokens
+            .into_iter()
+            .filter(|(_, color)| *color != Color::Reset)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            colored,
+            [
+                (r#""key \"a\"""#.to_string(), Color::Cyan),
+                ("-1.5e3".to_string(), Color::Yellow),
+                (r#""value""#.to_string(), Color::Green),
+                ("true".to_string(), Color::Magenta),
+                ("null".to_string(), Color::Magenta),
+            ]
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Sysvars, the native loader and the other accounts reserved by the
+//! runtime, whose presence or absence is often what a CPI permission error
+//! is about. Writes to them are demoted to reads.
+
+use solana_sdk::{
+    bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, feature, native_loader, pubkey,
+    pubkey::Pubkey, stake, system_program, sysvar, vote,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuntimeAccount {
+    Sysvar(&'static str),
+    NativeLoader,
+    /// A builtin program.
+    Reserved,
+}
+
+impl RuntimeAccount {
+    pub fn tag(&self) -> String {
+        match self {
+            Self::Sysvar(name) => format!("sysvar {name}"),
+            Self::NativeLoader => "native loader".to_string(),
+            Self::Reserved => "reserved".to_string(),
+        }
+    }
+}
+
+/// The kind of runtime account `pubkey` is, if any.
+pub fn classify(pubkey: &Pubkey) -> Option<RuntimeAccount> {
+    let sysvars = [
+        (sysvar::clock::id(), "clock"),
+        (sysvar::epoch_rewards::id(), "epoch rewards"),
+        (sysvar::epoch_schedule::id(), "epoch schedule"),
+        #[allow(deprecated)]
+        (sysvar::fees::id(), "fees"),
+        (sysvar::instructions::id(), "instructions"),
+        (sysvar::last_restart_slot::id(), "last restart slot"),
+        #[allow(deprecated)]
+        (sysvar::recent_blockhashes::id(), "recent blockhashes"),
+        (sysvar::rent::id(), "rent"),
+        (sysvar::rewards::id(), "rewards"),
+        (sysvar::slot_hashes::id(), "slot hashes"),
+        (sysvar::slot_history::id(), "slot history"),
+        (sysvar::stake_history::id(), "stake history"),
+    ];
+    if let Some((_, name)) = sysvars.iter().find(|(id, _)| id == pubkey) {
+        return Some(RuntimeAccount::Sysvar(name));
+    }
+    if *pubkey == native_loader::id() {
+        return Some(RuntimeAccount::NativeLoader);
+    }
+    let reserved = [
+        system_program::id(),
+        vote::program::id(),
+        stake::program::id(),
+        feature::id(),
+        bpf_loader::id(),
+        bpf_loader_deprecated::id(),
+        bpf_loader_upgradeable::id(),
+        pubkey!("StakeConfig11111111111111111111111111111111"),
+        pubkey!("Config1111111111111111111111111111111111111"),
+    ];
+    reserved
+        .contains(pubkey)
+        .then_some(RuntimeAccount::Reserved)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk::message::legacy::is_builtin_key_or_sysvar};
+
+    #[test]
+    fn classifies_the_accounts_the_runtime_demotes() {
+        assert_eq!(
+            classify(&sysvar::clock::id()),
+            Some(RuntimeAccount::Sysvar("clock"))
+        );
+        assert_eq!(
+            classify(&native_loader::id()),
+            Some(RuntimeAccount::NativeLoader)
+        );
+        assert_eq!(
+            classify(&system_program::id()),
+            Some(RuntimeAccount::Reserved)
+        );
+        assert_eq!(classify(&Pubkey::new_unique()), None);
+
+        for id in sysvar::ALL_IDS.iter() {
+            assert!(is_builtin_key_or_sysvar(id));
+            assert!(matches!(classify(id), Some(RuntimeAccount::Sysvar(_))));
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! The transactions of a block around a transaction which write to the same
+//! accounts, flagging sandwiches: a fee payer writing to an account of the
+//! transaction both just before and just after it.
+
+use {
+    crate::rpc::RpcApi,
+    solana_client::rpc_config::RpcBlockConfig,
+    solana_sdk::{
+        commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature,
+        transaction::VersionedTransaction, vote,
+    },
+    solana_transaction_status::{
+        option_serializer::OptionSerializer, TransactionDetails, UiTransactionEncoding,
+    },
+    std::str::FromStr,
+};
+
+/// How many non-vote transactions either side of the transaction to search.
+pub const NUM_NEIGHBORS: usize = 5;
+
+/// A transaction of the block, with what is needed of its status.
+pub struct BlockTransaction {
+    pub transaction: VersionedTransaction,
+    /// The accounts loaded from lookup tables as writable.
+    pub loaded_writable: Vec<Pubkey>,
+    pub failed: bool,
+}
+
+impl BlockTransaction {
+    fn fee_payer(&self) -> Pubkey {
+        self.transaction.message.static_account_keys()[0]
+    }
+
+    fn writable(&self) -> Vec<Pubkey> {
+        let message = &self.transaction.message;
+        let static_writable = message
+            .static_account_keys()
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| message.is_maybe_writable(*index))
+            .map(|(_, pubkey)| *pubkey);
+        static_writable
+            .chain(self.loaded_writable.iter().copied())
+            .collect()
+    }
+
+    fn is_vote(&self) -> bool {
+        let message = &self.transaction.message;
+        message.instructions().iter().any(|instruction| {
+            instruction.program_id(message.static_account_keys()) == &vote::program::id()
+        })
+    }
+}
+
+pub struct Neighbor {
+    /// The position relative to the transaction among non-vote transactions,
+    /// negative for those before it.
+    pub position: isize,
+    pub signature: Signature,
+    pub fee_payer: Pubkey,
+    /// The accounts written by both this and the transaction.
+    pub shared: Vec<Pubkey>,
+    pub failed: bool,
+    /// Whether this is the front or back of a sandwich.
+    pub sandwiching: bool,
+}
+
+pub struct MevContext {
+    pub slot: u64,
+    /// The neighbors writing to an account of the transaction, in block order.
+    pub neighbors: Vec<Neighbor>,
+    /// The fee payers with a neighbor on both sides.
+    pub sandwichers: Vec<Pubkey>,
+}
+
+impl MevContext {
+    pub fn fetch(rpc: &dyn RpcApi, slot: u64, signature: Signature) -> Result<Self, String> {
+        let config = RpcBlockConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            transaction_details: Some(TransactionDetails::Full),
+            rewards: Some(false),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let block = rpc
+            .get_block(slot, config)
+            .map_err(|err| format!("Failed to get block {slot}: {err}"))?;
+        let transactions = block
+            .transactions
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|encoded| {
+                let transaction = encoded.transaction.decode()?;
+                let meta = encoded.meta;
+                let loaded_writable = match meta.as_ref().map(|meta| &meta.loaded_addresses) {
+                    Some(OptionSerializer::Some(loaded)) => loaded
+                        .writable
+                        .iter()
+                        .filter_map(|pubkey| Pubkey::from_str(pubkey).ok())
+                        .collect(),
+                    _ => vec![],
+                };
+                Some(BlockTransaction {
+                    transaction,
+                    loaded_writable,
+                    failed: meta.is_some_and(|meta| meta.err.is_some()),
+                })
+            })
+            .collect::<Vec<_>>();
+        Self::analyze(slot, signature, &transactions)
+    }
+
+    /// Find the neighbors of the transaction with `signature` in a block.
+    pub fn analyze(
+        slot: u64,
+        signature: Signature,
+        block: &[BlockTransaction],
+    ) -> Result<Self, String> {
+        let non_votes = block
+            .iter()
+            .filter(|transaction| !transaction.is_vote())
+            .collect::<Vec<_>>();
+        let index = non_votes
+            .iter()
+            .position(|transaction| transaction.transaction.signatures.first() == Some(&signature))
+            .ok_or_else(|| format!("{signature} is not in block {slot}"))?;
+        let target = non_votes[index];
+        let (target_writable, target_payer) = (target.writable(), target.fee_payer());
+
+        let first = index.saturating_sub(NUM_NEIGHBORS);
+        let last = (index + NUM_NEIGHBORS).min(non_votes.len() - 1);
+        let mut neighbors = (first..=last)
+            .filter(|position| *position != index)
+            .filter_map(|position| {
+                let neighbor = non_votes[position];
+                let shared = neighbor
+                    .writable()
+                    .into_iter()
+                    .filter(|pubkey| target_writable.contains(pubkey))
+                    .collect::<Vec<_>>();
+                (!shared.is_empty()).then(|| Neighbor {
+                    position: position as isize - index as isize,
+                    signature: neighbor.transaction.signatures[0],
+                    fee_payer: neighbor.fee_payer(),
+                    shared,
+                    failed: neighbor.failed,
+                    sandwiching: false,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut sandwichers = Vec::<Pubkey>::new();
+        for neighbor in neighbors.iter().filter(|neighbor| neighbor.position < 0) {
+            let payer = neighbor.fee_payer;
+            let is_sandwich = payer != target_payer
+                && neighbors
+                    .iter()
+                    .any(|other| other.position > 0 && other.fee_payer == payer);
+            if is_sandwich && !sandwichers.contains(&payer) {
+                sandwichers.push(payer);
+            }
+        }
+        for neighbor in &mut neighbors {
+            neighbor.sandwiching = sandwichers.contains(&neighbor.fee_payer);
+        }
+        Ok(Self {
+            slot,
+            neighbors,
+            sandwichers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash,
+            instruction::{AccountMeta, Instruction},
+            signature::Keypair,
+            signer::Signer,
+            transaction::Transaction,
+        },
+    };
+
+    fn swap(payer: &Keypair, pool: Pubkey) -> BlockTransaction {
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new(pool, false)],
+        );
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[payer],
+            Hash::new_unique(),
+        );
+        BlockTransaction {
+            transaction: transaction.into(),
+            loaded_writable: vec![],
+            failed: false,
+        }
+    }
+
+    #[test]
+    fn flags_sandwiches() {
+        let (attacker, victim, other) = (Keypair::new(), Keypair::new(), Keypair::new());
+        let (pool, other_pool) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let block = [
+            swap(&other, other_pool),
+            swap(&attacker, pool),
+            swap(&victim, pool),
+            swap(&other, pool),
+            swap(&attacker, pool),
+        ];
+        let signature = block[2].transaction.signatures[0];
+
+        let context = MevContext::analyze(7, signature, &block).unwrap();
+        let neighbors = context
+            .neighbors
+            .iter()
+            .map(|neighbor| (neighbor.position, neighbor.sandwiching))
+            .collect::<Vec<_>>();
+        assert_eq!(neighbors, [(-1, true), (1, false), (2, true)]);
+        assert_eq!(context.sandwichers, [attacker.pubkey()]);
+        assert_eq!(context.neighbors[0].shared, [pool]);
+
+        assert!(MevContext::analyze(7, Signature::default(), &block).is_err());
+    }
+}
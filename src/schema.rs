@@ -0,0 +1,467 @@
+use {
+    serde::Deserialize,
+    solana_sdk::{hash::hash, pubkey::Pubkey},
+    std::{collections::BTreeMap, fs, str::FromStr},
+    tui_input::Input,
+};
+
+/// Instruction layouts for a program, loaded either from a simple schema
+/// file or from an Anchor IDL.
+///
+/// A simple schema looks like:
+///
+/// ```json
+/// {
+///   "instructions": [
+///     {
+///       "name": "transfer",
+///       "discriminator": "02000000",
+///       "fields": [{ "name": "lamports", "type": "u64" }]
+///     }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct Schema {
+    pub instructions: Vec<InstructionSchema>,
+    // Present in Anchor IDLs, which use sighash discriminators by default.
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    metadata: Option<serde_json::Value>,
+    /// The program id, in Anchor IDLs since 0.30.
+    #[serde(default)]
+    address: Option<String>,
+    /// The program's custom errors, in Anchor IDLs.
+    #[serde(default)]
+    errors: Vec<ErrorSchema>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorSchema {
+    code: u32,
+    name: String,
+    #[serde(default)]
+    msg: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InstructionSchema {
+    pub name: String,
+    #[serde(default)]
+    discriminator: Option<Discriminator>,
+    #[serde(default)]
+    fields: Vec<FieldSchema>,
+    // Anchor IDLs call the fields `args`.
+    #[serde(default)]
+    args: Vec<FieldSchema>,
+    /// The instruction's accounts in Anchor IDLs, some with PDA seeds. Kept
+    /// untyped since their layout varies across Anchor versions.
+    #[serde(default)]
+    accounts: Vec<serde_json::Value>,
+}
+
+/// A seed of a PDA in an Anchor IDL.
+#[derive(Debug, Clone)]
+pub enum Seed {
+    Const(Vec<u8>),
+    /// The pubkey of the instruction's account of that name.
+    Account(String),
+}
+
+/// The accounts of an instruction which are PDAs of the program.
+#[derive(Debug, Clone)]
+pub struct InstructionPdas {
+    pub discriminator: Vec<u8>,
+    /// The names of the instruction's accounts, in order.
+    pub accounts: Vec<String>,
+    /// The name of each PDA account and its seeds.
+    pub pdas: Vec<(String, Vec<Seed>)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProgramPdas {
+    pub program_id: Pubkey,
+    pub instructions: Vec<InstructionPdas>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Discriminator {
+    Hex(String),
+    Bytes(Vec<u8>),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FieldSchema {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: FieldType,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum FieldType {
+    Primitive(String),
+    Enum {
+        #[serde(rename = "enum")]
+        variants: Vec<String>,
+    },
+    Unsupported(serde_json::Value),
+}
+
+impl FieldType {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Primitive(name) => name.clone(),
+            Self::Enum { variants } => variants.join("|"),
+            Self::Unsupported(value) => format!("unsupported {value}"),
+        }
+    }
+}
+
+impl Schema {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents =
+            fs::read(path).map_err(|err| format!("Failed to read schema {path}: {err}"))?;
+        let schema: Self = serde_json::from_slice(&contents)
+            .map_err(|err| format!("Invalid schema {path}: {err}"))?;
+        if schema.instructions.is_empty() {
+            return Err(format!("Schema {path} has no instructions"));
+        }
+        Ok(schema)
+    }
+
+    /// The program id of an Anchor IDL.
+    pub fn program_id(&self) -> Option<Pubkey> {
+        let program_id = self
+            .address
+            .as_deref()
+            .or_else(|| self.metadata.as_ref()?.get("address")?.as_str())?;
+        Pubkey::from_str(program_id).ok()
+    }
+
+    /// The names of the program's custom error codes, with their messages.
+    pub fn errors(&self) -> BTreeMap<u32, String> {
+        self.errors
+            .iter()
+            .map(|error| {
+                let name = match &error.msg {
+                    Some(msg) => format!("{}: {msg}", error.name),
+                    None => error.name.clone(),
+                };
+                (error.code, name)
+            })
+            .collect()
+    }
+
+    fn is_anchor_idl(&self) -> bool {
+        self.version.is_some() || self.metadata.is_some()
+    }
+
+    /// The PDA seeds of the instructions' accounts, if the schema is an
+    /// Anchor IDL with a program id. Seeds from instruction arguments, and
+    /// PDAs of other programs, are not supported and skipped.
+    pub fn pdas(&self) -> Option<ProgramPdas> {
+        let program_id = self.program_id()?;
+        let instructions = self
+            .instructions
+            .iter()
+            .filter_map(|instruction| {
+                let discriminator = instruction.discriminator(self.is_anchor_idl()).ok()?;
+                let accounts = instruction
+                    .accounts
+                    .iter()
+                    .map(|account| account["name"].as_str().unwrap_or_default().to_string())
+                    .collect();
+                let pdas = instruction
+                    .accounts
+                    .iter()
+                    .filter_map(|account| {
+                        let name = account["name"].as_str()?.to_string();
+                        let pda = account.get("pda")?;
+                        if pda.get("program").is_some() {
+                            return None;
+                        }
+                        let seeds = pda["seeds"].as_array()?.iter().map(seed);
+                        Some((name, seeds.collect::<Option<Vec<_>>>()?))
+                    })
+                    .collect::<Vec<_>>();
+                Some(InstructionPdas {
+                    discriminator,
+                    accounts,
+                    pdas,
+                })
+            })
+            .collect();
+        Some(ProgramPdas {
+            program_id,
+            instructions,
+        })
+    }
+
+    /// Encode the instruction's data from the entered field values.
+    pub fn encode(
+        &self,
+        instruction: &InstructionSchema,
+        values: &[&str],
+    ) -> Result<Vec<u8>, String> {
+        let mut data = instruction.discriminator(self.is_anchor_idl())?;
+        let mut values = values.iter();
+        for field in instruction.fields() {
+            let value = values
+                .next()
+                .ok_or_else(|| format!("{}: missing value", field.name))?;
+            encode_field(&field.field_type, value.trim(), &mut data)
+                .map_err(|err| format!("{}: {err}", field.name))?;
+        }
+        Ok(data)
+    }
+}
+
+impl InstructionSchema {
+    pub fn fields(&self) -> impl Iterator<Item = &FieldSchema> {
+        self.fields.iter().chain(self.args.iter())
+    }
+
+    fn discriminator(&self, is_anchor_idl: bool) -> Result<Vec<u8>, String> {
+        match &self.discriminator {
+            Some(Discriminator::Hex(discriminator)) => hex::decode(discriminator)
+                .map_err(|err| format!("Invalid discriminator {discriminator}: {err}")),
+            Some(Discriminator::Bytes(discriminator)) => Ok(discriminator.clone()),
+            None if is_anchor_idl => Ok(anchor_discriminator(&self.name).to_vec()),
+            None => Ok(vec![]),
+        }
+    }
+}
+
+/// A seed of an Anchor IDL: a constant given as bytes or, before Anchor 0.30,
+/// as a string, or an account given by name.
+fn seed(seed: &serde_json::Value) -> Option<Seed> {
+    match seed["kind"].as_str()? {
+        "const" => match &seed["value"] {
+            serde_json::Value::String(value) => Some(Seed::Const(value.as_bytes().to_vec())),
+            value => Some(Seed::Const(serde_json::from_value(value.clone()).ok()?)),
+        },
+        // Paths into the account's data, e.g. `pool.mint`, are unsupported.
+        "account" => {
+            let path = seed["path"].as_str()?;
+            (!path.contains('.')).then(|| Seed::Account(path.to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// The Anchor sighash discriminator: the first 8 bytes of
+/// `sha256("global:<snake_case_name>")`.
+pub fn anchor_discriminator(name: &str) -> [u8; 8] {
+    let mut snake_case_name = String::new();
+    for (index, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if index > 0 {
+                snake_case_name.push('_');
+            }
+            snake_case_name.extend(c.to_lowercase());
+        } else {
+            snake_case_name.push(c);
+        }
+    }
+    let sighash = hash(format!("global:{snake_case_name}").as_bytes());
+    sighash.to_bytes()[..8].try_into().unwrap()
+}
+
+/// Borsh-encode a single field value.
+fn encode_field(field_type: &FieldType, value: &str, data: &mut Vec<u8>) -> Result<(), String> {
+    macro_rules! encode_int {
+        ($int:ty) => {
+            data.extend(
+                value
+                    .parse::<$int>()
+                    .map_err(|err| format!("{err}"))?
+                    .to_le_bytes(),
+            )
+        };
+    }
+
+    match field_type {
+        FieldType::Primitive(name) => match name.as_str() {
+            "u8" => encode_int!(u8),
+            "u16" => encode_int!(u16),
+            "u32" => encode_int!(u32),
+            "u64" => encode_int!(u64),
+            "u128" => encode_int!(u128),
+            "i8" => encode_int!(i8),
+            "i16" => encode_int!(i16),
+            "i32" => encode_int!(i32),
+            "i64" => encode_int!(i64),
+            "i128" => encode_int!(i128),
+            "bool" => data.push(match value {
+                "true" | "1" => 1,
+                "false" | "0" => 0,
+                _ => return Err("expected true or false".to_string()),
+            }),
+            "pubkey" | "publicKey" => data.extend(
+                Pubkey::from_str(value)
+                    .map_err(|err| format!("{err}"))?
+                    .to_bytes(),
+            ),
+            "string" => {
+                data.extend((value.len() as u32).to_le_bytes());
+                data.extend(value.as_bytes());
+            }
+            "bytes" => {
+                let bytes = hex::decode(value).map_err(|err| format!("{err}"))?;
+                data.extend((bytes.len() as u32).to_le_bytes());
+                data.extend(bytes);
+            }
+            _ => return Err(format!("unsupported type {name}")),
+        },
+        FieldType::Enum { variants } => {
+            let variant = variants
+                .iter()
+                .position(|variant| variant == value)
+                .ok_or_else(|| format!("expected one of {}", variants.join(", ")))?;
+            data.push(variant as u8);
+        }
+        FieldType::Unsupported(value) => return Err(format!("unsupported type {value}")),
+    }
+    Ok(())
+}
+
+/// State of the form used to build instruction data from a schema.
+pub struct TemplateForm {
+    pub schema: Schema,
+    pub instruction_index: usize,
+    pub inputs: Vec<Input>,
+    pub focus: usize,
+}
+
+impl TemplateForm {
+    pub fn new(schema: Schema) -> Self {
+        let mut form = Self {
+            schema,
+            instruction_index: 0,
+            inputs: vec![],
+            focus: 0,
+        };
+        form.select_instruction(0);
+        form
+    }
+
+    pub fn instruction(&self) -> &InstructionSchema {
+        &self.schema.instructions[self.instruction_index]
+    }
+
+    /// Switch to another instruction of the schema, resetting the fields.
+    pub fn select_instruction(&mut self, instruction_index: usize) {
+        self.instruction_index = instruction_index.min(self.schema.instructions.len() - 1);
+        self.inputs = self
+            .instruction()
+            .fields()
+            .map(|_| Input::default())
+            .collect();
+        self.focus = 0;
+    }
+
+    pub fn focused_input(&mut self) -> Option<&mut Input> {
+        self.inputs.get_mut(self.focus)
+    }
+
+    /// The encoded instruction data for the current field values.
+    pub fn encode(&self) -> Result<Vec<u8>, String> {
+        let values = self.inputs.iter().map(Input::value).collect::<Vec<_>>();
+        self.schema.encode(self.instruction(), &values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    fn schema(value: serde_json::Value) -> Schema {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn encodes_fields_after_the_discriminator() {
+        let schema = schema(json!({
+            "instructions": [{
+                "name": "transfer",
+                "discriminator": "02000000",
+                "fields": [
+                    { "name": "lamports", "type": "u64" },
+                    { "name": "memo", "type": "string" },
+                    { "name": "side", "type": { "enum": ["bid", "ask"] } },
+                ]
+            }]
+        }));
+        let data = schema
+            .encode(&schema.instructions[0], &["5", "hi", "ask"])
+            .unwrap();
+        assert_eq!(
+            data,
+            [
+                &[2, 0, 0, 0][..],
+                &5u64.to_le_bytes(),
+                &[2, 0, 0, 0, b'h', b'i', 1]
+            ]
+            .concat()
+        );
+        assert_eq!(
+            schema.encode(&schema.instructions[0], &["-1", "", "bid"]),
+            Err("lamports: invalid digit found in string".to_string())
+        );
+    }
+
+    #[test]
+    fn uses_sighash_discriminators_in_anchor_idls() {
+        let schema = schema(json!({
+            "version": "0.1.0",
+            "instructions": [{
+                "name": "initializePool",
+                "args": [{ "name": "fee", "type": "u16" }]
+            }]
+        }));
+        let data = schema.encode(&schema.instructions[0], &["3"]).unwrap();
+        assert_eq!(data[..8], anchor_discriminator("initialize_pool"));
+        assert_eq!(data[8..], [3, 0]);
+    }
+
+    #[test]
+    fn rejects_unknown_types() {
+        let schema = schema(json!({
+            "instructions": [{
+                "name": "swap",
+                "fields": [
+                    { "name": "amount", "type": "f32" },
+                    { "name": "route", "type": { "vec": "u8" } },
+                ]
+            }]
+        }));
+        let instruction = &schema.instructions[0];
+        assert_eq!(
+            schema.encode(instruction, &["1.5", "[]"]),
+            Err("amount: unsupported type f32".to_string())
+        );
+        let route = instruction.fields().nth(1).unwrap();
+        assert_eq!(route.field_type.describe(), r#"unsupported {"vec":"u8"}"#);
+    }
+
+    #[test]
+    fn rejects_fewer_values_than_fields() {
+        let schema = schema(json!({
+            "instructions": [{
+                "name": "transfer",
+                "discriminator": [2, 0, 0, 0],
+                "fields": [
+                    { "name": "lamports", "type": "u64" },
+                    { "name": "flag", "type": "bool" },
+                ]
+            }]
+        }));
+        assert_eq!(
+            schema.encode(&schema.instructions[0], &["5"]),
+            Err("flag: missing value".to_string())
+        );
+    }
+}
@@ -0,0 +1,108 @@
+//! The security.txt programs embed in their binary, as written by the
+//! `solana-security-txt` crate, telling who to contact about a program.
+
+use {
+    crate::{ownership, rpc::RpcApi},
+    solana_sdk::{bpf_loader_upgradeable::UpgradeableLoaderState, pubkey::Pubkey},
+};
+
+const BEGIN: &[u8] = b"=======BEGIN SECURITY.TXT V1=======\0";
+const END: &[u8] = b"=======END SECURITY.TXT V1=======\0";
+/// Fields holding keys or prose, too long for a panel.
+const LONG_FIELDS: [&str; 2] = ["encryption", "acknowledgements"];
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SecurityTxt {
+    /// The fields in the order the program lists them, e.g. `contacts`.
+    pub fields: Vec<(String, String)>,
+}
+
+impl SecurityTxt {
+    /// Find the security.txt in a program binary: null terminated keys and
+    /// values between markers.
+    pub fn parse(binary: &[u8]) -> Option<Self> {
+        let start = find(binary, BEGIN)? + BEGIN.len();
+        let end = start + find(&binary[start..], END)?;
+        let mut parts = binary[start..end].split(|byte| *byte == 0);
+        let mut fields = vec![];
+        while let (Some(key), Some(value)) = (parts.next(), parts.next()) {
+            fields.push((
+                String::from_utf8_lossy(key).into_owned(),
+                String::from_utf8_lossy(value).into_owned(),
+            ));
+        }
+        Some(Self { fields })
+    }
+
+    /// Fetch the binary of a program, from its programdata account if it is
+    /// upgradeable, and find its security.txt.
+    pub fn fetch(rpc: &dyn RpcApi, program_id: &Pubkey) -> Result<Option<Self>, String> {
+        let get_account = |pubkey: &Pubkey| {
+            rpc.get_multiple_accounts(&[*pubkey])
+                .map_err(|err| format!("Failed to get {pubkey}: {err}"))?
+                .into_iter()
+                .flatten()
+                .next()
+                .ok_or_else(|| format!("Account {pubkey} not found"))
+        };
+        let program = get_account(program_id)?;
+        let binary = match ownership::program_data_address(&program) {
+            Some(address) => {
+                let program_data = get_account(&address)?;
+                let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+                program_data
+                    .data
+                    .get(metadata_len..)
+                    .unwrap_or_default()
+                    .to_vec()
+            }
+            None => program.data,
+        };
+        Ok(Self::parse(&binary))
+    }
+
+    /// The fields short enough to show.
+    pub fn short_fields(&self) -> impl Iterator<Item = &(String, String)> {
+        self.fields
+            .iter()
+            .filter(|(key, _)| !LONG_FIELDS.contains(&key.as_str()))
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_security_txt_of_a_binary() {
+        let binary = [
+            b"\x7fELF...".as_slice(),
+            BEGIN,
+            b"name\0Example\0contacts\0email:security@example.com,discord:example\0",
+            b"encryption\0-----BEGIN PGP PUBLIC KEY BLOCK-----\0",
+            END,
+            b"...",
+        ]
+        .concat();
+        let security_txt = SecurityTxt::parse(&binary).unwrap();
+        let short_fields = security_txt.short_fields().cloned().collect::<Vec<_>>();
+        assert_eq!(
+            short_fields,
+            [
+                ("name".to_string(), "Example".to_string()),
+                (
+                    "contacts".to_string(),
+                    "email:security@example.com,discord:example".to_string()
+                ),
+            ]
+        );
+        assert_eq!(security_txt.fields.len(), 3);
+        assert_eq!(SecurityTxt::parse(b"\x7fELF without one"), None);
+    }
+}
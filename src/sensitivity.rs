@@ -0,0 +1,185 @@
+use {
+    crate::{
+        ingest,
+        transaction_byte_sections::{get_transaction_byte_sections, TransactionByteSection},
+    },
+    ratatui::style::Color,
+    solana_sdk::transaction::VersionedTransaction,
+};
+
+/// How a byte of a transaction reacts to being corrupted, summarizing the
+/// outcomes of all of its mutations.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Sensitivity {
+    NeverParses,
+    ParsesOnly,
+    SometimesSanitizes,
+    AlwaysSanitizes,
+}
+
+impl Sensitivity {
+    fn label(self) -> &'static str {
+        match self {
+            Self::NeverParses => "Mutations never parse",
+            Self::ParsesOnly => "Mutations parse but never sanitize",
+            Self::SometimesSanitizes => "Some mutations sanitize",
+            Self::AlwaysSanitizes => "Every mutation sanitizes",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Self::NeverParses => Color::Red,
+            Self::ParsesOnly => Color::Magenta,
+            Self::SometimesSanitizes => Color::Yellow,
+            Self::AlwaysSanitizes => Color::Green,
+        }
+    }
+}
+
+/// The outcome of mutating every byte of a transaction, one at a time.
+pub struct SensitivityMap {
+    /// Runs of bytes with the same sensitivity within each original section,
+    /// labelled and colored by sensitivity.
+    pub sections: Vec<TransactionByteSection>,
+    pub num_mutations: usize,
+    pub num_parsed: usize,
+    pub num_sanitized: usize,
+}
+
+/// Flip each bit of each byte, and set each byte to `0x00` and `0xff`,
+/// checking whether the mutated bytes still parse as a transaction which
+/// serializes back to them, and whether that transaction sanitizes.
+pub fn map(transaction: &VersionedTransaction) -> SensitivityMap {
+    let mut original_sections = vec![];
+    get_transaction_byte_sections(transaction, &mut original_sections);
+    let mut bytes = bincode::serialize(transaction).unwrap();
+
+    let mut sensitivity_map = SensitivityMap {
+        sections: vec![],
+        num_mutations: 0,
+        num_parsed: 0,
+        num_sanitized: 0,
+    };
+    let mut offset = 0;
+    for original_section in &original_sections {
+        let mut previous = None;
+        for _ in &original_section.bytes {
+            let original = bytes[offset];
+            let mutations = (0..8)
+                .map(|bit| original ^ (1 << bit))
+                .chain([0x00, 0xff].into_iter().filter(|value| *value != original));
+            let (mut num_mutations, mut num_parsed, mut num_sanitized) = (0, 0, 0);
+            for mutation in mutations {
+                bytes[offset] = mutation;
+                num_mutations += 1;
+                let Ok(mutated) = ingest::parse_transaction(&bytes) else {
+                    continue;
+                };
+                num_parsed += 1;
+                if mutated.sanitize().is_ok() {
+                    num_sanitized += 1;
+                }
+            }
+            bytes[offset] = original;
+            offset += 1;
+
+            sensitivity_map.num_mutations += num_mutations;
+            sensitivity_map.num_parsed += num_parsed;
+            sensitivity_map.num_sanitized += num_sanitized;
+            let sensitivity = if num_parsed == 0 {
+                Sensitivity::NeverParses
+            } else if num_sanitized == 0 {
+                Sensitivity::ParsesOnly
+            } else if num_sanitized < num_mutations {
+                Sensitivity::SometimesSanitizes
+            } else {
+                Sensitivity::AlwaysSanitizes
+            };
+            if previous == Some(sensitivity) {
+                let section = sensitivity_map.sections.last_mut().unwrap();
+                section.bytes.push(original);
+            } else {
+                sensitivity_map.sections.push(TransactionByteSection {
+                    label: Some(sensitivity.label().to_string()),
+                    bytes: vec![original],
+                    color: sensitivity.color(),
+                    kind: original_section.kind,
+                });
+            }
+            previous = Some(sensitivity);
+        }
+    }
+    sensitivity_map
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash,
+            message::{Message, VersionedMessage},
+            pubkey::Pubkey,
+            signature::Signature,
+            system_instruction,
+        },
+    };
+
+    fn transfer() -> VersionedTransaction {
+        let payer = Pubkey::new_unique();
+        let instruction = system_instruction::transfer(&payer, &Pubkey::new_unique(), 1);
+        let message =
+            Message::new_with_blockhash(&[instruction], Some(&payer), &Hash::new_unique());
+        VersionedTransaction {
+            signatures: vec![Signature::from([7; 64])],
+            message: VersionedMessage::Legacy(message),
+        }
+    }
+
+    /// The label of the section holding the byte at `offset`.
+    fn label_at(sensitivity_map: &SensitivityMap, offset: usize) -> &str {
+        let mut start = 0;
+        for section in &sensitivity_map.sections {
+            start += section.bytes.len();
+            if offset < start {
+                return section.label.as_deref().unwrap();
+            }
+        }
+        panic!("offset {offset} is past the transaction");
+    }
+
+    #[test]
+    fn maps_every_byte_by_how_its_mutations_fare() {
+        let transaction = transfer();
+        let bytes = bincode::serialize(&transaction).unwrap();
+        let sensitivity_map = map(&transaction);
+        let mapped = sensitivity_map
+            .sections
+            .iter()
+            .flat_map(|section| section.bytes.iter().copied())
+            .collect::<Vec<_>>();
+        assert_eq!(mapped, bytes);
+        // Runs of bytes reacting alike are one section.
+        assert!(sensitivity_map.sections.len() < bytes.len() / 4);
+
+        // Eight bit flips, and setting each byte to the extremes it is not.
+        let expected_mutations = bytes
+            .iter()
+            .map(|byte| 8 + usize::from(*byte != 0x00) + usize::from(*byte != 0xff))
+            .sum::<usize>();
+        assert_eq!(sensitivity_map.num_mutations, expected_mutations);
+        assert!(sensitivity_map.num_sanitized <= sensitivity_map.num_parsed);
+        assert!(sensitivity_map.num_parsed < sensitivity_map.num_mutations);
+
+        // Signatures are not verified, so any signature sanitizes.
+        for offset in 1..65 {
+            assert_eq!(
+                label_at(&sensitivity_map, offset),
+                "Every mutation sanitizes"
+            );
+        }
+        // Fewer or more signatures than the header requires do not sanitize.
+        assert_ne!(label_at(&sensitivity_map, 0), "Every mutation sanitizes");
+    }
+}
@@ -0,0 +1,403 @@
+use {
+    crate::{
+        error_codes::{self, ErrorMap},
+        highlight_rules::HighlightRule,
+        storage::{self, FileChange},
+        watch::Subscription,
+    },
+    serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize},
+    solana_sdk::pubkey::Pubkey,
+    std::io,
+};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// User preferences, persisted between sessions.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Emit a desktop notification when a long operation finishes while the
+    /// terminal is in the background.
+    pub desktop_notifications: bool,
+    /// How the digits of large numbers are grouped.
+    pub digit_grouping: DigitGrouping,
+    /// Whether amounts are shown in SOL or in lamports.
+    pub amount_unit: AmountUnit,
+    /// Whether timestamps are shown in UTC or in local time.
+    pub time_zone: TimeZone,
+    /// How pubkeys are rendered.
+    pub pubkey_display: PubkeyDisplay,
+    /// Distinguish sections by text attributes and glyphs rather than
+    /// background colors, for monochrome terminals and colorblind users.
+    pub monochrome: bool,
+    /// The endpoints a signed transaction is broadcast to, the active
+    /// endpoint if empty.
+    pub broadcast_endpoints: Vec<String>,
+    /// The endpoint whose slot that of the active endpoint is compared with,
+    /// to show how far it lags. Not compared with itself.
+    pub reference_endpoint: Option<String>,
+    /// How the labels of the legend are ordered.
+    pub legend_order: LegendOrder,
+    /// How many bytes the byte grid fits on screen. Presentation mode's
+    /// zoom is transient and never saved here.
+    #[serde(deserialize_with = "default_if_invalid")]
+    pub zoom: Zoom,
+    /// Whether bytes are colored by field or by program.
+    pub coloring: Coloring,
+    /// Patterns to highlight over the coloring, edited in the settings file.
+    /// Invalid rules are skipped rather than failing the whole file.
+    #[serde(deserialize_with = "skip_invalid")]
+    pub highlight_rules: Vec<HighlightRule>,
+    /// The pubkeys or hex bytes flagged in watched transactions.
+    pub watchlist: Vec<String>,
+    /// The addresses and programs whose transactions are watched, all
+    /// transactions if none is enabled.
+    pub subscriptions: Vec<Subscription>,
+    /// Save the watched transactions matching the watchlist to disk.
+    pub export_stream_matches: bool,
+    /// How many saved matches are kept per pattern, 100 if unset.
+    pub stream_match_retention: Option<usize>,
+    /// Names of the custom error codes of programs by program id, edited
+    /// in the settings file. They take precedence over those of IDLs.
+    /// Invalid entries are skipped rather than failing the whole file.
+    #[serde(deserialize_with = "error_codes::deserialize_lenient")]
+    pub error_codes: ErrorMap,
+    /// Reveal the sections of a loaded transaction one by one, flashing
+    /// their labels, to follow its layout in demos.
+    pub reveal_on_load: bool,
+    /// Why the settings file failed to load, in which case the defaults are
+    /// used and the file is not saved over.
+    #[serde(skip)]
+    load_error: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigitGrouping {
+    #[default]
+    None,
+    /// `1,234,567.89`
+    Comma,
+    /// `1.234.567,89`
+    Period,
+    /// `1 234 567.89`
+    Space,
+}
+
+impl DigitGrouping {
+    pub fn next(self) -> Self {
+        match self {
+            Self::None => Self::Comma,
+            Self::Comma => Self::Period,
+            Self::Period => Self::Space,
+            Self::Space => Self::None,
+        }
+    }
+
+    pub fn separator(self) -> Option<char> {
+        match self {
+            Self::None => None,
+            Self::Comma => Some(','),
+            Self::Period => Some('.'),
+            Self::Space => Some(' '),
+        }
+    }
+
+    pub fn decimal_point(self) -> char {
+        match self {
+            Self::Period => ',',
+            _ => '.',
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AmountUnit {
+    Sol,
+    #[default]
+    Lamports,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeZone {
+    #[default]
+    Utc,
+    Local,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PubkeyDisplay {
+    #[default]
+    Base58,
+    /// The first and last 4 base58 characters, e.g. `Vote…1111`.
+    Truncated,
+    Hex,
+}
+
+impl PubkeyDisplay {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Base58 => Self::Truncated,
+            Self::Truncated => Self::Hex,
+            Self::Hex => Self::Base58,
+        }
+    }
+
+    pub fn format(self, pubkey: &Pubkey) -> String {
+        match self {
+            Self::Base58 => pubkey.to_string(),
+            Self::Truncated => {
+                let base58 = pubkey.to_string();
+                format!("{}…{}", &base58[..4], &base58[base58.len() - 4..])
+            }
+            Self::Hex => hex::encode(pubkey),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LegendOrder {
+    /// The order of the sections in the transaction.
+    #[default]
+    Document,
+    /// Most bytes first, with the bytes of each label.
+    Size,
+    /// Grouped by the part of the transaction, with subtotals.
+    Category,
+}
+
+impl LegendOrder {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Document => Self::Size,
+            Self::Size => Self::Category,
+            Self::Category => Self::Document,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Document => "transaction order",
+            Self::Size => "by size",
+            Self::Category => "by category",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Zoom {
+    /// Each byte in hex.
+    #[default]
+    Hex,
+    /// Each byte as a single colored cell.
+    Compact,
+    /// Each cell standing for as many bytes as needed to fit the transaction.
+    Overview,
+}
+
+impl Zoom {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Hex => Self::Compact,
+            Self::Compact => Self::Overview,
+            Self::Overview => Self::Hex,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Hex => "hex",
+            Self::Compact => "compact",
+            Self::Overview => "overview",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Coloring {
+    /// Each field of the transaction in its own color.
+    #[default]
+    Field,
+    /// The fields of each program's instructions in shades of one hue.
+    Program,
+}
+
+impl Coloring {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Field => Self::Program,
+            Self::Program => Self::Field,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Field => "by field",
+            Self::Program => "by program",
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        storage::try_load_json(SETTINGS_FILE).unwrap_or_else(|err| Self {
+            load_error: Some(err),
+            ..Self::default()
+        })
+    }
+
+    pub fn load_error(&self) -> Option<&str> {
+        self.load_error.as_deref()
+    }
+
+    pub fn save(&self) -> io::Result<FileChange> {
+        if let Some(err) = &self.load_error {
+            return Err(io::Error::other(format!("{err}, not saving over it")));
+        }
+        storage::save_json(SETTINGS_FILE, self)
+    }
+}
+
+/// Deserialize a value, or its default if it is invalid, e.g. a variant
+/// which only another build knows.
+fn default_if_invalid<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + Default,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).unwrap_or_default())
+}
+
+/// Deserialize the elements of a list which are valid, skipping the others.
+fn skip_invalid<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let values = Vec::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(values
+        .into_iter()
+        .filter_map(|value| serde_json::from_value(value).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::highlight_rules::HighlightEffect, std::collections::BTreeMap};
+
+    #[test]
+    fn skips_invalid_highlight_rules() {
+        let settings: Settings = serde_json::from_str(
+            r#"{
+                "monochrome": true,
+                "highlight_rules": [
+                    { "pattern": "0x01", "effect": "red" },
+                    { "pattern": "0x02", "effect": "purple" },
+                    { "pattern": "0x03" },
+                    { "pattern": "0x04", "discriminator": true, "effect": "blink" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(settings.monochrome);
+        let rules = settings
+            .highlight_rules
+            .iter()
+            .map(|rule| (rule.pattern.as_str(), rule.effect))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rules,
+            [
+                ("0x01", HighlightEffect::Red),
+                ("0x04", HighlightEffect::Blink)
+            ]
+        );
+    }
+
+    #[test]
+    fn refuses_to_save_over_a_file_which_failed_to_load() {
+        let settings = Settings {
+            load_error: Some("Invalid settings.json".to_string()),
+            ..Settings::default()
+        };
+        let err = settings.save().unwrap_err();
+        assert_eq!(err.to_string(), "Invalid settings.json, not saving over it");
+    }
+
+    #[test]
+    fn formats_pubkeys_in_each_display() {
+        let pubkey = Pubkey::new_from_array([1; 32]);
+        let displays = [
+            PubkeyDisplay::Base58,
+            PubkeyDisplay::Truncated,
+            PubkeyDisplay::Hex,
+        ];
+        let formatted = displays.map(|display| display.format(&pubkey));
+        assert_eq!(
+            formatted,
+            [
+                "4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi".to_string(),
+                "4vJ9…kLKi".to_string(),
+                "01".repeat(32),
+            ]
+        );
+        let cycled = displays.map(PubkeyDisplay::next);
+        assert_eq!(
+            cycled,
+            [
+                PubkeyDisplay::Truncated,
+                PubkeyDisplay::Hex,
+                PubkeyDisplay::Base58
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_invalid_error_codes() {
+        let path = std::env::temp_dir().join(format!("settings-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"{
+                "monochrome": true,
+                "error_codes": {
+                    "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4": {
+                        "6001": "SlippageToleranceExceeded",
+                        "0x1772": "NotANumber",
+                        "6003": 3
+                    },
+                    "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc": "not a map"
+                }
+            }"#,
+        )
+        .unwrap();
+        let settings = storage::try_load_json_at::<Settings>(&path);
+        std::fs::remove_file(&path).unwrap();
+        let settings = settings.unwrap();
+        assert!(settings.monochrome);
+        let codes = BTreeMap::from([(6001, "SlippageToleranceExceeded".to_string())]);
+        assert_eq!(
+            settings.error_codes,
+            ErrorMap::from([(
+                "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".to_string(),
+                codes
+            )])
+        );
+    }
+
+    #[test]
+    fn defaults_unknown_zooms() {
+        let settings: Settings =
+            serde_json::from_str(r#"{ "zoom": "large", "monochrome": true }"#).unwrap();
+        assert_eq!(settings.zoom, Zoom::Hex);
+        assert!(settings.monochrome);
+        let settings: Settings = serde_json::from_str(r#"{ "zoom": "overview" }"#).unwrap();
+        assert_eq!(settings.zoom, Zoom::Overview);
+    }
+}
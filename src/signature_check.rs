@@ -0,0 +1,148 @@
+//! Verifying a signature against a pubkey and message, to debug custom
+//! signing code against a known transaction. Solana signs the serialized
+//! message itself rather than a hash of it.
+
+use {
+    base64::{engine::general_purpose::STANDARD, Engine},
+    solana_sdk::{pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction},
+    std::str::FromStr,
+};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Request {
+    pub signature: Option<Signature>,
+    pub pubkey: Option<Pubkey>,
+    /// The message bytes, if not those of the viewed transaction.
+    pub message: Option<Vec<u8>>,
+}
+
+/// Parse a signature, pubkey and message typed in any order, the message
+/// as hex prefixed with `0x` or as base64.
+pub fn parse(text: &str) -> Result<Request, String> {
+    let mut request = Request::default();
+    for word in text.split_whitespace() {
+        if let Ok(signature) = Signature::from_str(word) {
+            request.signature = Some(signature);
+        } else if let Ok(pubkey) = Pubkey::from_str(word) {
+            request.pubkey = Some(pubkey);
+        } else if let Some(hex) = word.strip_prefix("0x") {
+            let message =
+                hex::decode(hex).map_err(|err| format!("Invalid hex message {word}: {err}"))?;
+            request.message = Some(message);
+        } else {
+            let message = STANDARD
+                .decode(word)
+                .map_err(|_| format!("{word} is not a signature, pubkey or message"))?;
+            request.message = Some(message);
+        }
+    }
+    Ok(request)
+}
+
+/// Verify the request, against the viewed transaction's message and
+/// signers where the request does not give them.
+pub fn verify(
+    text: &str,
+    transaction: Option<&VersionedTransaction>,
+) -> Result<Vec<String>, String> {
+    let request = parse(text)?;
+    let signature = request.signature.ok_or("Type the signature to verify")?;
+    let (message, message_source) = match (request.message, transaction) {
+        (Some(message), _) => (message, "the typed message"),
+        (None, Some(transaction)) => (
+            transaction.message.serialize(),
+            "the viewed transaction's message",
+        ),
+        (None, None) => return Err("Type the message, or view a transaction".to_string()),
+    };
+    let signers = transaction
+        .map(|transaction| {
+            let message = &transaction.message;
+            let num_signers = message.header().num_required_signatures as usize;
+            message.static_account_keys()[..num_signers].to_vec()
+        })
+        .unwrap_or_default();
+    let pubkeys = match request.pubkey {
+        Some(pubkey) => vec![pubkey],
+        None if signers.is_empty() => {
+            return Err("Type the pubkey, or view a transaction".to_string())
+        }
+        None => signers.clone(),
+    };
+
+    let mut lines = vec![format!(
+        "Verifying against {message_source} ({} bytes)",
+        message.len()
+    )];
+    for pubkey in &pubkeys {
+        let outcome = if signature.verify(pubkey.as_ref(), &message) {
+            "verifies"
+        } else {
+            "does not verify"
+        };
+        let signer = match signers.iter().position(|signer| signer == pubkey) {
+            Some(index) => format!(", signer #{index}"),
+            None => String::new(),
+        };
+        lines.push(format!("  {outcome} for {pubkey}{signer}"));
+    }
+    if let Some(transaction) = transaction {
+        lines.push(
+            match transaction
+                .signatures
+                .iter()
+                .position(|other| *other == signature)
+            {
+                Some(index) => format!("The signature is signature #{index} of the transaction"),
+                None => "The signature is not among the transaction's signatures".to_string(),
+            },
+        );
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash, signature::Keypair, signer::Signer, system_instruction,
+            transaction::Transaction,
+        },
+    };
+
+    #[test]
+    fn finds_the_signer_of_a_signature() {
+        let (payer, other) = (Keypair::new(), Keypair::new());
+        let instruction = system_instruction::transfer(&payer.pubkey(), &other.pubkey(), 1);
+        let transaction = VersionedTransaction::from(Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[&payer],
+            Hash::new_unique(),
+        ));
+        let signature = transaction.signatures[0];
+
+        let lines = verify(&signature.to_string(), Some(&transaction)).unwrap();
+        assert_eq!(
+            lines[1..],
+            [
+                format!("  verifies for {}, signer #0", payer.pubkey()),
+                "The signature is signature #0 of the transaction".to_string(),
+            ]
+        );
+
+        let message = transaction.message.serialize();
+        let text = format!("0x{} {} {signature}", hex::encode(message), other.pubkey());
+        let lines = verify(&text, None).unwrap();
+        assert_eq!(
+            lines,
+            [
+                "Verifying against the typed message (150 bytes)".to_string(),
+                format!("  does not verify for {}", other.pubkey()),
+            ]
+        );
+
+        assert!(verify("nonsense!", None).is_err());
+    }
+}
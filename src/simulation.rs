@@ -0,0 +1,227 @@
+//! Simulating a transaction and comparing the state of its writable accounts
+//! before and after, to inspect the state changes it makes.
+
+use {
+    crate::rpc::RpcApi,
+    solana_account_decoder::UiAccountEncoding,
+    solana_client::rpc_config::{
+        RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
+    },
+    solana_sdk::{
+        account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey,
+        transaction::VersionedTransaction,
+    },
+};
+
+pub struct AccountChange {
+    pub pubkey: Pubkey,
+    /// `None` if the account does not exist.
+    pub before: Option<Account>,
+    pub after: Option<Account>,
+}
+
+impl AccountChange {
+    pub fn is_changed(&self) -> bool {
+        self.before != self.after
+    }
+}
+
+pub struct Simulation {
+    pub err: Option<String>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    /// The writable static accounts of the transaction.
+    pub accounts: Vec<AccountChange>,
+}
+
+impl Simulation {
+    /// Fetch the writable accounts of the transaction, then simulate it on
+    /// the same commitment, requesting their state after. Signatures are not
+    /// verified and the blockhash is replaced, so unsigned edits simulate.
+    pub fn run(rpc: &dyn RpcApi, transaction: &VersionedTransaction) -> Result<Self, String> {
+        let commitment = CommitmentConfig::confirmed();
+        let message = &transaction.message;
+        let addresses = message
+            .static_account_keys()
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| message.is_maybe_writable(*index))
+            .map(|(_, pubkey)| *pubkey)
+            .collect::<Vec<_>>();
+        let before = rpc.get_multiple_accounts(&addresses)?;
+
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(commitment),
+            accounts: Some(RpcSimulateTransactionAccountsConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                addresses: addresses.iter().map(ToString::to_string).collect(),
+            }),
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let result = rpc
+            .simulate_transaction(transaction, config)
+            .map_err(|err| format!("Simulation failed: {err}"))?
+            .value;
+        let after = result.accounts.unwrap_or_default();
+        let accounts = addresses
+            .into_iter()
+            .zip(before)
+            .enumerate()
+            .map(|(index, (pubkey, before))| AccountChange {
+                pubkey,
+                before,
+                after: after
+                    .get(index)
+                    .cloned()
+                    .flatten()
+                    .and_then(|account| account.decode()),
+            })
+            .collect();
+        Ok(Self {
+            err: result.err.map(|err| err.to_string()),
+            logs: result.logs.unwrap_or_default(),
+            units_consumed: result.units_consumed,
+            accounts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::rpc::Request,
+        serde_json::{json, Value},
+        solana_account_decoder::UiAccount,
+        solana_sdk::{
+            hash::Hash,
+            instruction::{AccountMeta, Instruction},
+            message::{Message, VersionedMessage},
+            signature::Signature,
+            system_program,
+        },
+    };
+
+    /// Answers the account fetch with `before` and the simulation with
+    /// `after`, or with `err` if set.
+    struct Chain {
+        before: Vec<Option<Account>>,
+        after: Vec<Option<Account>>,
+        err: Option<String>,
+    }
+
+    fn encode(accounts: &[Option<Account>]) -> Value {
+        let encoded = accounts
+            .iter()
+            .map(|account| {
+                account.as_ref().map(|account| {
+                    UiAccount::encode(
+                        &Pubkey::default(),
+                        account,
+                        UiAccountEncoding::Base64,
+                        None,
+                        None,
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        json!(encoded)
+    }
+
+    impl RpcApi for Chain {
+        fn send(&self, request: Request) -> Result<Value, String> {
+            match request {
+                Request::GetMultipleAccounts { pubkeys, .. } => {
+                    assert_eq!(pubkeys.len(), self.before.len());
+                    Ok(json!({"context": {"slot": 1}, "value": encode(&self.before)}))
+                }
+                Request::SimulateTransaction { config, .. } => {
+                    assert!(!config.sig_verify && config.replace_recent_blockhash);
+                    if let Some(err) = &self.err {
+                        return Err(err.clone());
+                    }
+                    Ok(json!({
+                        "context": {"slot": 1},
+                        "value": {
+                            "err": {"InstructionError": [0, {"Custom": 1}]},
+                            "logs": ["Program log: out of funds"],
+                            "accounts": encode(&self.after),
+                            "unitsConsumed": 450,
+                        },
+                    }))
+                }
+                request => Err(format!("unexpected {}", request.method())),
+            }
+        }
+    }
+
+    /// A transaction paid by a new account, writing one account and reading
+    /// another.
+    fn transaction() -> VersionedTransaction {
+        let instruction = Instruction::new_with_bytes(
+            system_program::id(),
+            &[],
+            vec![
+                AccountMeta::new(Pubkey::new_unique(), false),
+                AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            ],
+        );
+        let message = Message::new_with_blockhash(
+            &[instruction],
+            Some(&Pubkey::new_unique()),
+            &Hash::new_unique(),
+        );
+        VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::Legacy(message),
+        }
+    }
+
+    #[test]
+    fn compares_the_writable_accounts_before_and_after() {
+        let payer = Account::new(1_000, 0, &system_program::id());
+        let written = Account::new(5, 8, &Pubkey::new_unique());
+        let chain = Chain {
+            before: vec![Some(payer.clone()), None],
+            after: vec![Some(payer), Some(written.clone())],
+            err: None,
+        };
+        let transaction = transaction();
+        let simulation = Simulation::run(&chain, &transaction).unwrap();
+        assert_eq!(
+            simulation.err.as_deref(),
+            Some("Error processing Instruction 0: custom program error: 0x1")
+        );
+        assert_eq!(simulation.logs, ["Program log: out of funds"]);
+        assert_eq!(simulation.units_consumed, Some(450));
+
+        let keys = transaction.message.static_account_keys();
+        let accounts = &simulation.accounts;
+        assert_eq!(
+            accounts
+                .iter()
+                .map(|change| change.pubkey)
+                .collect::<Vec<_>>(),
+            keys[..2]
+        );
+        assert!(!accounts[0].is_changed());
+        assert!(accounts[1].is_changed());
+        assert_eq!(accounts[1].before, None);
+        assert_eq!(accounts[1].after, Some(written));
+    }
+
+    #[test]
+    fn reports_failed_simulations() {
+        let chain = Chain {
+            before: vec![None, None],
+            after: vec![],
+            err: Some("Transaction too large".to_string()),
+        };
+        assert_eq!(
+            Simulation::run(&chain, &transaction()).err().as_deref(),
+            Some("Simulation failed: Transaction too large")
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! How long transactions took to land after they were submitted, one record
+//! per submission attempt, persisted between sessions.
+
+use {
+    crate::storage::{self, FileChange},
+    chrono::DateTime,
+    serde::{Deserialize, Serialize},
+    solana_sdk::signature::Signature,
+    std::{collections::BTreeMap, io, time::Duration},
+};
+
+const SLOT_TIMINGS_FILE: &str = "slot_timings.json";
+/// The target slot time, to estimate the slots elapsed when the slot at
+/// submission is unknown.
+const SLOT_DURATION: Duration = Duration::from_millis(400);
+
+/// One submission of a transaction and when it landed, if it did. Times are
+/// unix timestamps in milliseconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attempt {
+    pub submitted_at: i64,
+    /// The endpoint submitted to, if the tool submitted the transaction.
+    pub endpoint: Option<String>,
+    /// The slot of the endpoint at submission, if the tool submitted it.
+    pub submitted_slot: Option<u64>,
+    pub landed_slot: Option<u64>,
+    pub landed_at: Option<i64>,
+}
+
+impl Attempt {
+    pub fn wall_time(&self) -> Option<Duration> {
+        let millis = self.landed_at? - self.submitted_at;
+        Some(Duration::from_millis(millis.max(0) as u64))
+    }
+
+    /// The slots elapsed until landing, and whether they are estimated from
+    /// the wall time rather than counted.
+    pub fn slots_elapsed(&self) -> Option<(u64, bool)> {
+        match (self.submitted_slot, self.landed_slot) {
+            (Some(submitted_slot), Some(landed_slot)) => {
+                Some((landed_slot.saturating_sub(submitted_slot), false))
+            }
+            _ => {
+                let wall_time = self.wall_time()?;
+                Some((
+                    (wall_time.as_millis() / SLOT_DURATION.as_millis()) as u64,
+                    true,
+                ))
+            }
+        }
+    }
+}
+
+/// Parse a submission time given as RFC 3339 or as unix seconds, into unix
+/// milliseconds.
+pub fn parse_submission_time(text: &str) -> Result<i64, String> {
+    let text = text.trim();
+    if let Ok(seconds) = text.parse::<i64>() {
+        return Ok(seconds * 1000);
+    }
+    DateTime::parse_from_rfc3339(text)
+        .map(|time| time.timestamp_millis())
+        .map_err(|err| format!("Invalid submission time {text}: {err}"))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SlotTimings {
+    attempts: BTreeMap<String, Vec<Attempt>>,
+}
+
+impl SlotTimings {
+    pub fn load() -> Self {
+        storage::load_json(SLOT_TIMINGS_FILE)
+    }
+
+    pub fn save(&self) -> io::Result<FileChange> {
+        storage::save_json(SLOT_TIMINGS_FILE, self)
+    }
+
+    pub fn attempts(&self, signature: &Signature) -> &[Attempt] {
+        self.attempts
+            .get(&signature.to_string())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn record(&mut self, signature: &Signature, attempt: Attempt) {
+        self.attempts
+            .entry(signature.to_string())
+            .or_default()
+            .push(attempt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_or_estimates_slots() {
+        let submitted_at = parse_submission_time("2024-08-30T12:00:00Z").unwrap();
+        assert_eq!(parse_submission_time("1725019200").unwrap(), submitted_at);
+        let mut attempt = Attempt {
+            submitted_at,
+            endpoint: None,
+            submitted_slot: None,
+            landed_slot: Some(287_000_010),
+            landed_at: Some(submitted_at + 2_000),
+        };
+        assert_eq!(attempt.wall_time(), Some(Duration::from_secs(2)));
+        assert_eq!(attempt.slots_elapsed(), Some((5, true)));
+        attempt.submitted_slot = Some(287_000_004);
+        assert_eq!(attempt.slots_elapsed(), Some((6, false)));
+    }
+}
@@ -0,0 +1,153 @@
+//! Snapshots of the bytes and status fetched for bookmarked transactions, so
+//! they reopen exactly as they were even once the endpoint has pruned them.
+//! Each snapshot carries a hash of its contents to detect corruption.
+
+use {
+    crate::storage::{self, FileChange},
+    base64::{engine::general_purpose::STANDARD, Engine},
+    serde::{Deserialize, Serialize},
+    solana_sdk::{hash::hashv, signature::Signature, transaction::VersionedTransaction},
+    solana_transaction_status::UiTransactionStatusMeta,
+    std::{fs, io, path::PathBuf},
+};
+
+const SNAPSHOTS_DIR: &str = "snapshots";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub slot: Option<u64>,
+    pub block_time: Option<i64>,
+    /// The transaction as base64 of its wire format.
+    transaction: String,
+    /// The status as fetched, as JSON.
+    meta: Option<String>,
+    /// The SHA-256 of the transaction bytes and status JSON.
+    hash: String,
+}
+
+impl Snapshot {
+    pub fn new(
+        transaction: &VersionedTransaction,
+        meta: Option<&UiTransactionStatusMeta>,
+        slot: Option<u64>,
+        block_time: Option<i64>,
+    ) -> Self {
+        let bytes = bincode::serialize(transaction).unwrap();
+        let meta = meta.map(|meta| serde_json::to_string(meta).unwrap());
+        Self {
+            slot,
+            block_time,
+            transaction: STANDARD.encode(&bytes),
+            hash: content_hash(&bytes, meta.as_deref()),
+            meta,
+        }
+    }
+
+    /// The transaction and status, once checked against the hash.
+    pub fn decode(
+        &self,
+    ) -> Result<(VersionedTransaction, Option<UiTransactionStatusMeta>), String> {
+        let bytes = STANDARD
+            .decode(&self.transaction)
+            .map_err(|err| format!("invalid transaction bytes: {err}"))?;
+        if content_hash(&bytes, self.meta.as_deref()) != self.hash {
+            return Err("its contents do not match its hash".to_string());
+        }
+        let transaction = bincode::deserialize(&bytes)
+            .map_err(|err| format!("invalid transaction bytes: {err}"))?;
+        let meta = self
+            .meta
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()
+            .map_err(|err| format!("invalid status: {err}"))?;
+        Ok((transaction, meta))
+    }
+}
+
+fn content_hash(bytes: &[u8], meta: Option<&str>) -> String {
+    hashv(&[bytes, meta.unwrap_or_default().as_bytes()]).to_string()
+}
+
+/// The directory of snapshots, one file per signature. Without a directory
+/// nothing is stored, e.g. in tests.
+#[derive(Debug, Default, Clone)]
+pub struct Snapshots {
+    pub dir: Option<PathBuf>,
+}
+
+impl Snapshots {
+    pub fn in_data_dir() -> Self {
+        Self {
+            dir: storage::data_dir().map(|dir| dir.join(SNAPSHOTS_DIR)),
+        }
+    }
+
+    fn path(&self, signature: &Signature) -> Option<PathBuf> {
+        Some(self.dir.as_ref()?.join(format!("{signature}.json")))
+    }
+
+    /// The change saving the snapshot of the signature, if stored.
+    pub fn save(
+        &self,
+        signature: &Signature,
+        snapshot: &Snapshot,
+    ) -> Option<io::Result<FileChange>> {
+        let path = self.path(signature)?;
+        Some(FileChange::write_json(path, snapshot))
+    }
+
+    /// The snapshot of the signature, if one was saved.
+    pub fn load(&self, signature: &Signature) -> Option<Result<Snapshot, String>> {
+        let contents = fs::read(self.path(signature)?).ok()?;
+        Some(serde_json::from_slice(&contents).map_err(|err| format!("invalid snapshot: {err}")))
+    }
+
+    pub fn remove(&self, signature: &Signature) -> Option<FileChange> {
+        self.path(signature).map(FileChange::Remove)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash, pubkey::Pubkey, signature::Keypair, signer::Signer, system_instruction,
+            transaction::Transaction,
+        },
+    };
+
+    #[test]
+    fn detects_corrupted_snapshots() {
+        let payer = Keypair::new();
+        let instruction = system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+        let transaction = VersionedTransaction::from(Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[&payer],
+            Hash::new_unique(),
+        ));
+        let dir = std::env::temp_dir().join(format!("snapshots-{}", Pubkey::new_unique()));
+        let snapshots = Snapshots {
+            dir: Some(dir.clone()),
+        };
+        let signature = transaction.signatures[0];
+        assert!(snapshots.load(&signature).is_none());
+
+        let snapshot = Snapshot::new(&transaction, None, Some(42), None);
+        let change = snapshots.save(&signature, &snapshot).unwrap().unwrap();
+        change.apply().unwrap();
+        let loaded = snapshots.load(&signature).unwrap().unwrap();
+        assert_eq!(loaded.slot, Some(42));
+        assert_eq!(loaded.decode().unwrap().0, transaction);
+
+        let mut corrupted = loaded;
+        corrupted.transaction = STANDARD.encode([0; 8]);
+        assert!(corrupted.decode().is_err());
+
+        snapshots.remove(&signature).unwrap().apply().unwrap();
+        assert!(snapshots.load(&signature).is_none());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
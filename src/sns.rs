@@ -0,0 +1,177 @@
+//! Resolving `.sol` domains of the Solana Name Service, both ways: domains to
+//! the pubkeys owning them, and pubkeys to their primary domain.
+
+use {
+    crate::rpc::RpcApi,
+    solana_client::rpc_request::MAX_MULTIPLE_ACCOUNTS,
+    solana_sdk::{account::Account, hash::hashv, pubkey, pubkey::Pubkey},
+    std::collections::HashMap,
+};
+
+const NAME_PROGRAM_ID: Pubkey = pubkey!("namesLPneVptA9Z5rqUDD9tMTWEJwofgaYwp8cawRkX");
+/// The parent of `.sol` domains.
+const SOL_TLD: Pubkey = pubkey!("58PwtjSDuFHuUkYjH9BYnnQKHfwo9reZhC2zMJv9JPkx");
+/// The class of the records mapping domain accounts back to their names.
+const REVERSE_LOOKUP_CLASS: Pubkey = pubkey!("33m47vH6Eav6jr5Ry86XjhRft2jRBLDnDgPSHoquXi2Z");
+/// Holds the primary, "favourite", domain of each owner.
+const NAME_OFFERS_PROGRAM_ID: Pubkey = pubkey!("85iDfUvr3HJyLM2zcq5BXSiDvUWfw6cSE1FfNBo8Ap29");
+const HASH_PREFIX: &str = "SPL Name Service";
+/// The parent, owner and class of a name record, before its data.
+const NAME_RECORD_HEADER_LEN: usize = 96;
+
+fn name_account_key(name: &str, class: Option<&Pubkey>, parent: Option<&Pubkey>) -> Pubkey {
+    let hashed_name = hashv(&[HASH_PREFIX.as_bytes(), name.as_bytes()]);
+    let zeros = Pubkey::default();
+    Pubkey::find_program_address(
+        &[
+            hashed_name.as_ref(),
+            class.unwrap_or(&zeros).as_ref(),
+            parent.unwrap_or(&zeros).as_ref(),
+        ],
+        &NAME_PROGRAM_ID,
+    )
+    .0
+}
+
+/// The account of a domain, e.g. `bonfida.sol`.
+fn domain_key(domain: &str) -> Pubkey {
+    let name = domain.strip_suffix(".sol").unwrap_or(domain);
+    name_account_key(name, None, Some(&SOL_TLD))
+}
+
+fn reverse_key(domain_key: &Pubkey) -> Pubkey {
+    name_account_key(&domain_key.to_string(), Some(&REVERSE_LOOKUP_CLASS), None)
+}
+
+fn favourite_domain_key(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"favourite_domain", owner.as_ref()],
+        &NAME_OFFERS_PROGRAM_ID,
+    )
+    .0
+}
+
+fn record_owner(account: &Account) -> Option<Pubkey> {
+    Pubkey::try_from(account.data.get(32..64)?).ok()
+}
+
+/// The name of a reverse record: a length prefixed string after the header.
+fn reverse_name(account: &Account) -> Option<String> {
+    let data = account.data.get(NAME_RECORD_HEADER_LEN..)?;
+    let len = u32::from_le_bytes(data.get(..4)?.try_into().ok()?) as usize;
+    String::from_utf8(data.get(4..4 + len)?.to_vec()).ok()
+}
+
+/// The domain a favourite domain account points to.
+fn favourite_domain(account: &Account) -> Option<Pubkey> {
+    Pubkey::try_from(account.data.get(1..33)?).ok()
+}
+
+/// Whether text typed as an address is a `.sol` domain.
+pub fn is_domain(text: &str) -> bool {
+    text.len() > ".sol".len() && text.ends_with(".sol")
+}
+
+/// The owner of a domain, e.g. `bonfida.sol`.
+pub fn resolve(rpc: &dyn RpcApi, domain: &str) -> Result<Pubkey, String> {
+    let account = fetch(rpc, &[domain_key(domain)])?.pop().flatten();
+    account
+        .as_ref()
+        .and_then(record_owner)
+        .ok_or_else(|| format!("{domain} is not registered"))
+}
+
+/// The primary domains of the pubkeys which have one, and the domains of the
+/// pubkeys which are domain accounts.
+pub fn reverse_lookup(
+    rpc: &dyn RpcApi,
+    pubkeys: &[Pubkey],
+) -> Result<HashMap<Pubkey, String>, String> {
+    let favourite_keys = pubkeys.iter().map(favourite_domain_key).collect::<Vec<_>>();
+    let favourites = fetch(rpc, &favourite_keys)?;
+    // Each pubkey may be a domain account, or own a favourite domain.
+    let candidates = pubkeys
+        .iter()
+        .map(|pubkey| (*pubkey, *pubkey))
+        .chain(
+            pubkeys
+                .iter()
+                .zip(&favourites)
+                .filter_map(|(owner, favourite)| {
+                    Some((*owner, favourite_domain(favourite.as_ref()?)?))
+                }),
+        )
+        .collect::<Vec<_>>();
+    let reverse_keys = candidates
+        .iter()
+        .map(|(_, domain_key)| reverse_key(domain_key))
+        .collect::<Vec<_>>();
+    let reverse_records = fetch(rpc, &reverse_keys)?;
+    let favourite_domain_keys = candidates
+        .iter()
+        .filter(|(pubkey, domain_key)| pubkey != domain_key)
+        .map(|(_, domain_key)| *domain_key)
+        .collect::<Vec<_>>();
+    // A favourite domain may have been transferred since it was set.
+    let domain_owners = favourite_domain_keys
+        .iter()
+        .copied()
+        .zip(fetch(rpc, &favourite_domain_keys)?)
+        .filter_map(|(domain_key, account)| Some((domain_key, record_owner(account.as_ref()?)?)))
+        .collect::<HashMap<_, _>>();
+
+    let mut domains = HashMap::new();
+    for ((pubkey, domain_key), reverse_record) in candidates.into_iter().zip(reverse_records) {
+        let Some(name) = reverse_record.as_ref().and_then(reverse_name) else {
+            continue;
+        };
+        if pubkey != domain_key && domain_owners.get(&domain_key) != Some(&pubkey) {
+            continue;
+        }
+        domains.entry(pubkey).or_insert(format!("{name}.sol"));
+    }
+    Ok(domains)
+}
+
+fn fetch(rpc: &dyn RpcApi, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>, String> {
+    let mut accounts = Vec::with_capacity(pubkeys.len());
+    for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        accounts.extend(
+            rpc.get_multiple_accounts(chunk)
+                .map_err(|err| format!("Failed to get name records: {err}"))?,
+        );
+    }
+    Ok(accounts)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::str::FromStr};
+
+    #[test]
+    fn derives_name_records() {
+        assert_eq!(
+            domain_key("bonfida.sol"),
+            Pubkey::from_str("Crf8hzfthWGbGbLTVCiqRqV5MVnbpHB1L9KQMd6gsinb").unwrap()
+        );
+        assert_eq!(domain_key("bonfida"), domain_key("bonfida.sol"));
+        assert!(is_domain("bonfida.sol"));
+        assert!(!is_domain(".sol"));
+
+        let owner = Pubkey::new_unique();
+        let data = [
+            [0; 32].as_slice(),
+            owner.as_ref(),
+            REVERSE_LOOKUP_CLASS.as_ref(),
+            &7u32.to_le_bytes(),
+            b"bonfida",
+        ]
+        .concat();
+        let account = Account {
+            data,
+            ..Account::default()
+        };
+        assert_eq!(record_owner(&account), Some(owner));
+        assert_eq!(reverse_name(&account).as_deref(), Some("bonfida"));
+    }
+}
@@ -0,0 +1,77 @@
+use {
+    serde::{de::DeserializeOwned, Serialize},
+    std::{
+        fs, io,
+        path::{Path, PathBuf},
+    },
+};
+
+/// Directory used to persist application data between sessions.
+pub fn data_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".solana-transaction-tui"))
+}
+
+/// Load a JSON file from the data directory, falling back to the default
+/// value if the file does not exist or cannot be parsed.
+pub fn load_json<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    data_dir()
+        .and_then(|dir| fs::read(dir.join(file_name)).ok())
+        .and_then(|contents| serde_json::from_slice(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Load a JSON file from the data directory, defaulting if it does not
+/// exist, but failing if it cannot be read or parsed, for files the user
+/// edits by hand which must not be saved over.
+pub fn try_load_json<T: DeserializeOwned + Default>(file_name: &str) -> Result<T, String> {
+    match data_dir() {
+        Some(dir) => try_load_json_at(&dir.join(file_name)),
+        None => Ok(T::default()),
+    }
+}
+
+pub fn try_load_json_at<T: DeserializeOwned + Default>(path: &Path) -> Result<T, String> {
+    let contents = match fs::read(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(err) => return Err(format!("Failed to read {}: {err}", path.display())),
+    };
+    serde_json::from_slice(&contents).map_err(|err| format!("Invalid {}: {err}", path.display()))
+}
+
+/// A change to a file, prepared while updating and applied by a command so
+/// that updating does not wait on the file system.
+#[derive(Debug)]
+pub enum FileChange {
+    Write { path: PathBuf, contents: Vec<u8> },
+    Remove(PathBuf),
+}
+
+impl FileChange {
+    /// The change writing a value as JSON to `path`.
+    pub fn write_json<T: Serialize>(path: PathBuf, value: &T) -> io::Result<Self> {
+        let contents = serde_json::to_vec_pretty(value)?;
+        Ok(Self::Write { path, contents })
+    }
+
+    pub fn apply(self) -> io::Result<()> {
+        match self {
+            Self::Write { path, contents } => {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::write(path, contents)
+            }
+            Self::Remove(path) => match fs::remove_file(path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+                _ => Ok(()),
+            },
+        }
+    }
+}
+
+/// The change saving a value as JSON into the data directory.
+pub fn save_json<T: Serialize>(file_name: &str, value: &T) -> io::Result<FileChange> {
+    let dir = data_dir().ok_or_else(|| io::Error::other("HOME is not set"))?;
+    FileChange::write_json(dir.join(file_name), value)
+}
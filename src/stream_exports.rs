@@ -0,0 +1,197 @@
+//! Saving the streamed transactions matching the watchlist for offline
+//! analysis, in a directory per pattern. Each match is saved as its raw bytes
+//! and its status, named by the time it was saved, and only the latest
+//! matches of a pattern are kept.
+
+use {
+    crate::{storage, watch::StreamedTransaction},
+    serde::Serialize,
+    solana_sdk::hash::hash,
+    solana_transaction_status::UiTransactionStatusMeta,
+    std::{
+        fs, io,
+        path::{Path, PathBuf},
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+const STREAM_EXPORTS_DIR: &str = "stream-matches";
+/// How many matches of a pattern are kept when the settings do not say.
+pub const DEFAULT_RETENTION: usize = 100;
+
+/// The status of a saved match, next to its raw bytes.
+#[derive(Serialize)]
+struct Status<'a> {
+    slot: u64,
+    block_time: Option<i64>,
+    meta: Option<&'a UiTransactionStatusMeta>,
+}
+
+/// The directory of saved matches. Without a directory nothing is saved,
+/// e.g. in tests.
+#[derive(Debug, Default, Clone)]
+pub struct StreamExports {
+    pub dir: Option<PathBuf>,
+}
+
+impl StreamExports {
+    pub fn in_data_dir() -> Self {
+        Self {
+            dir: storage::data_dir().map(|dir| dir.join(STREAM_EXPORTS_DIR)),
+        }
+    }
+
+    /// The directory of the matches of a pattern, named by the pattern. The
+    /// characters unsafe in file names are replaced, and a hash of the
+    /// pattern appended, so that patterns differing only in those do not
+    /// share a directory.
+    pub fn pattern_dir(&self, pattern: &str) -> Option<PathBuf> {
+        let mut name = pattern
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        if name != pattern {
+            let digest = hash(pattern.as_bytes());
+            name = format!("{name}-{}", hex::encode(&digest.as_ref()[..4]));
+        }
+        Some(self.dir.as_ref()?.join(name))
+    }
+
+    /// Save a transaction under each pattern it matches, then drop the oldest
+    /// matches beyond `retention`.
+    pub fn save(
+        &self,
+        streamed: &StreamedTransaction,
+        now: SystemTime,
+        retention: usize,
+    ) -> io::Result<()> {
+        let millis = now
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let name = format!("{millis:013}-{}", streamed.signature());
+        let bytes = bincode::serialize(&streamed.transaction).map_err(io::Error::other)?;
+        let status = serde_json::to_vec_pretty(&Status {
+            slot: streamed.slot,
+            block_time: streamed.block_time,
+            meta: streamed.meta.as_ref(),
+        })?;
+        for pattern in &streamed.matches {
+            let Some(dir) = self.pattern_dir(pattern) else {
+                return Ok(());
+            };
+            fs::create_dir_all(&dir)?;
+            fs::write(dir.join(format!("{name}.bin")), &bytes)?;
+            fs::write(dir.join(format!("{name}.json")), &status)?;
+            prune(&dir, retention)?;
+        }
+        Ok(())
+    }
+}
+
+/// Remove the oldest matches of a directory beyond `retention`, relying on
+/// the names starting with the time they were saved.
+fn prune(dir: &Path, retention: usize) -> io::Result<()> {
+    let mut names = fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            (path.extension()? == "bin").then(|| path.file_stem()?.to_owned().into_string().ok())?
+        })
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    let num_expired = names.len().saturating_sub(retention);
+    for name in &names[..num_expired] {
+        fs::remove_file(dir.join(format!("{name}.bin")))?;
+        let _ = fs::remove_file(dir.join(format!("{name}.json")));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash,
+            pubkey::Pubkey,
+            signature::Keypair,
+            signer::Signer,
+            system_instruction,
+            transaction::{Transaction, VersionedTransaction},
+        },
+        std::time::Duration,
+    };
+
+    #[test]
+    fn keeps_the_latest_matches_of_each_pattern() {
+        let streamed = || {
+            let payer = Keypair::new();
+            let instruction =
+                system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+            StreamedTransaction {
+                slot: 7,
+                block_time: None,
+                transaction: Transaction::new_signed_with_payer(
+                    &[instruction],
+                    Some(&payer.pubkey()),
+                    &[&payer],
+                    Hash::new_unique(),
+                )
+                .into(),
+                meta: None,
+                matches: vec!["0xdead".to_string()],
+            }
+        };
+        let dir = std::env::temp_dir().join(format!("stream-matches-{}", Pubkey::new_unique()));
+        let exports = StreamExports {
+            dir: Some(dir.clone()),
+        };
+        let transactions = [streamed(), streamed(), streamed()];
+        for (index, streamed) in transactions.iter().enumerate() {
+            let now = UNIX_EPOCH + Duration::from_secs(index as u64);
+            exports.save(streamed, now, 2).unwrap();
+        }
+
+        let pattern_dir = exports.pattern_dir("0xdead").unwrap();
+        assert_eq!(pattern_dir, dir.join("0xdead"));
+        let mut names = fs::read_dir(&pattern_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        // The first match expired.
+        assert_eq!(
+            names,
+            [
+                format!("0000000001000-{}.bin", transactions[1].signature()),
+                format!("0000000001000-{}.json", transactions[1].signature()),
+                format!("0000000002000-{}.bin", transactions[2].signature()),
+                format!("0000000002000-{}.json", transactions[2].signature()),
+            ]
+        );
+        let bytes = fs::read(pattern_dir.join(&names[2])).unwrap();
+        assert_eq!(
+            bincode::deserialize::<VersionedTransaction>(&bytes).unwrap(),
+            transactions[2].transaction
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn separates_patterns_differing_in_replaced_characters() {
+        let exports = StreamExports {
+            dir: Some(PathBuf::from("matches")),
+        };
+        let dir = |pattern| exports.pattern_dir(pattern).unwrap();
+        assert_ne!(dir("a-b"), dir("a.b"));
+        assert_ne!(dir("a-b"), dir("a_b"));
+        let name = dir("a-b")
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(name.starts_with("a_b-"), "{name}");
+        assert_eq!(dir("a-b"), dir("a-b"));
+    }
+}
@@ -0,0 +1,179 @@
+//! Printable strings embedded in instruction data, like the `strings` tool
+//! finds in binaries, which often tell what an unknown instruction does.
+
+use {
+    base64::{engine::general_purpose::STANDARD, Engine},
+    solana_transaction_tui::transaction_byte_sections::{SectionKind, TransactionByteSection},
+};
+
+/// Shorter runs of printable bytes are mostly coincidence.
+const MIN_LEN: usize = 4;
+/// Shorter base64 is indistinguishable from words.
+const MIN_BASE64_LEN: usize = 16;
+const URL_SCHEMES: [&str; 3] = ["https://", "http://", "ipfs://"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringKind {
+    Text,
+    Url,
+    /// Base64, see [`FoundString::decoded`].
+    Base64,
+}
+
+impl StringKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Url => "URL",
+            Self::Base64 => "base64",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FoundString {
+    pub instruction: usize,
+    /// The offset of the string in the transaction.
+    pub offset: usize,
+    pub kind: StringKind,
+    pub text: String,
+}
+
+impl FoundString {
+    /// What base64 strings decode to, as text if printable, else as hex.
+    pub fn decoded(&self) -> Option<String> {
+        if self.kind != StringKind::Base64 {
+            return None;
+        }
+        let bytes = STANDARD.decode(&self.text).ok()?;
+        Some(match String::from_utf8(bytes) {
+            Ok(text) if text.chars().all(|c| !c.is_control() || c.is_whitespace()) => text,
+            Ok(text) => hex::encode(text.into_bytes()),
+            Err(err) => hex::encode(err.into_bytes()),
+        })
+    }
+}
+
+/// The strings in the instruction data of a transaction, in order.
+pub fn extract(sections: &[TransactionByteSection]) -> Vec<FoundString> {
+    let mut strings = vec![];
+    let mut section_start = 0;
+    for section in sections {
+        if let SectionKind::InstructionData(instruction) = section.kind {
+            for (start, text) in printable_runs(&section.bytes) {
+                strings.extend(classify(text).into_iter().map(|(offset, kind, text)| {
+                    FoundString {
+                        instruction,
+                        offset: section_start + start + offset,
+                        kind,
+                        text: text.to_string(),
+                    }
+                }));
+            }
+        }
+        section_start += section.bytes.len();
+    }
+    strings
+}
+
+/// The runs of printable ASCII at least [`MIN_LEN`] long, with their start.
+fn printable_runs(bytes: &[u8]) -> Vec<(usize, &str)> {
+    let mut runs = vec![];
+    let mut start = 0;
+    for (index, byte) in bytes.iter().chain([&0]).enumerate() {
+        if byte.is_ascii_graphic() || *byte == b' ' {
+            continue;
+        }
+        if index - start >= MIN_LEN {
+            // Printable ASCII is valid UTF-8.
+            runs.push((start, std::str::from_utf8(&bytes[start..index]).unwrap()));
+        }
+        start = index + 1;
+    }
+    runs
+}
+
+/// The URLs in a run of printable text, else the run as base64 or text,
+/// each with its offset in the run.
+fn classify(run: &str) -> Vec<(usize, StringKind, &str)> {
+    let mut urls = vec![];
+    let mut rest = 0;
+    while let Some((start, _)) = URL_SCHEMES
+        .iter()
+        .filter_map(|scheme| Some((run[rest..].find(scheme)? + rest, scheme)))
+        .min()
+    {
+        let end = run[start..]
+            .find(|c: char| c.is_whitespace() || "\"'<>".contains(c))
+            .map_or(run.len(), |len| start + len);
+        urls.push((start, StringKind::Url, &run[start..end]));
+        rest = end;
+    }
+    if !urls.is_empty() {
+        return urls;
+    }
+    let kind = if is_base64(run) {
+        StringKind::Base64
+    } else {
+        StringKind::Text
+    };
+    vec![(0, kind, run)]
+}
+
+fn is_base64(text: &str) -> bool {
+    text.len() >= MIN_BASE64_LEN
+        && text.len().is_multiple_of(4)
+        && text
+            .trim_end_matches('=')
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/')
+        && STANDARD.decode(text).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_text_urls_and_base64() {
+        let data = [
+            [1, 0, 0, 0].as_slice(),
+            b"Hello world",
+            &[0xff, b'a', b'b', 0],
+            b"see https://example.com/x.json now",
+            &[7],
+            b"aGVsbG8gZnJvbSBiYXNlNjQ=",
+        ]
+        .concat();
+        let sections = [
+            TransactionByteSection {
+                kind: SectionKind::InstructionDataLength(0),
+                bytes: vec![data.len() as u8],
+                label: None,
+                color: Default::default(),
+            },
+            TransactionByteSection {
+                kind: SectionKind::InstructionData(0),
+                bytes: data,
+                label: None,
+                color: Default::default(),
+            },
+        ];
+
+        let strings = extract(&sections);
+        let found = strings
+            .iter()
+            .map(|string| (string.offset, string.kind, string.text.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                (5, StringKind::Text, "Hello world"),
+                (24, StringKind::Url, "https://example.com/x.json"),
+                (55, StringKind::Base64, "aGVsbG8gZnJvbSBiYXNlNjQ="),
+            ]
+        );
+        assert_eq!(strings[2].decoded().as_deref(), Some("hello from base64"));
+        assert_eq!(strings[0].decoded(), None);
+    }
+}
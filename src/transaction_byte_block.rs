@@ -1,18 +1,72 @@
 use {
-    crate::transaction_byte_sections::TransactionByteSection,
+    crate::{
+        block_digits, bookmarks::Annotation, settings::Zoom,
+        transaction_byte_sections::TransactionByteSection,
+    },
     ratatui::{
         buffer::Buffer,
         layout::{Constraint, Direction, Layout, Rect},
         prelude::BlockExt,
-        style::Style,
+        style::{Color, Modifier, Style},
         text::Text,
         widgets::{Block, Widget},
     },
+    std::{iter, ops::Range},
 };
 
+/// Number of header lines rendered above the bytes.
+pub const NUM_HEADER_LINES: u16 = 2;
+
+/// Attributes cycled through by consecutive sections in monochrome mode.
+const SECTION_MODIFIERS: [Modifier; 3] = [Modifier::UNDERLINED, Modifier::BOLD, Modifier::REVERSED];
+
+/// Glyphs cycled through by consecutive sections in monochrome mode, when
+/// each byte is a single cell.
+const SECTION_SHADES: [char; 3] = ['░', '▒', '▓'];
+
+/// Columns between the offsets labeling cells which are not in hex.
+const BLOCK_LABEL_SPACING: usize = 8;
+
+/// Columns taken by each byte in large digits, spacing included.
+const LARGE_CELL_WIDTH: u16 = 2 * block_digits::DIGIT_WIDTH as u16 + 3;
+/// Lines taken by each line of bytes in large digits, spacing included.
+const LARGE_LINE_HEIGHT: u16 = 3;
+
+/// How the grid draws its bytes: at a zoom of the settings, or in large
+/// digits for presentation mode, which is never saved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridZoom {
+    Hex,
+    Compact,
+    Overview,
+    /// Each byte in hex digits two lines tall.
+    Large,
+}
+
+impl From<Zoom> for GridZoom {
+    fn from(zoom: Zoom) -> Self {
+        match zoom {
+            Zoom::Hex => Self::Hex,
+            Zoom::Compact => Self::Compact,
+            Zoom::Overview => Self::Overview,
+        }
+    }
+}
+
 pub struct TransactionByteBlock<'a> {
     sections: &'a [TransactionByteSection],
     block: Option<Block<'a>>,
+    cursor: Option<usize>,
+    selection: Option<Range<usize>>,
+    annotations: &'a [Annotation],
+    highlights: &'a [Range<usize>],
+    rule_matches: &'a [(Range<usize>, Style)],
+    gaps: &'a [(usize, usize)],
+    base_offset: usize,
+    scroll: usize,
+    monochrome: bool,
+    zoom: GridZoom,
+    instruction_overlay: bool,
 }
 
 impl<'a> TransactionByteBlock<'a> {
@@ -20,6 +74,17 @@ impl<'a> TransactionByteBlock<'a> {
         Self {
             sections: transaction_byte_sections,
             block: None,
+            cursor: None,
+            selection: None,
+            annotations: &[],
+            highlights: &[],
+            rule_matches: &[],
+            gaps: &[],
+            base_offset: 0,
+            scroll: 0,
+            monochrome: false,
+            zoom: GridZoom::Hex,
+            instruction_overlay: false,
         }
     }
 
@@ -34,67 +99,607 @@ impl<'a> TransactionByteBlock<'a> {
         self
     }
 
+    /// Highlights the byte at `cursor`.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn cursor(mut self, cursor: Option<usize>) -> Self {
+        self.cursor = cursor;
+        self
+    }
+
+    /// Highlights the selected range of bytes.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn selection(mut self, selection: Option<Range<usize>>) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Underlines the annotated ranges of bytes.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn annotations(mut self, annotations: &'a [Annotation]) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// Emphasizes the highlighted ranges of bytes, e.g. bytes changed by an
+    /// edit.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn highlights(mut self, highlights: &'a [Range<usize>]) -> Self {
+        self.highlights = highlights;
+        self
+    }
+
+    /// Overlays the style of each range matched by a highlight rule.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn rule_matches(mut self, rule_matches: &'a [(Range<usize>, Style)]) -> Self {
+        self.rule_matches = rule_matches;
+        self
+    }
+
+    /// Renders runs of empty cells, as `(offset, count)` sorted by offset,
+    /// before the byte at each offset (or after the last byte), to line the
+    /// bytes up with another pane.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn gaps(mut self, gaps: &'a [(usize, usize)]) -> Self {
+        self.gaps = gaps;
+        self
+    }
+
+    /// The offset of the first section's first byte within the transaction,
+    /// used when only a suffix of the sections is rendered. Cursor, selection,
+    /// annotation and highlight offsets are relative to the transaction.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn base_offset(mut self, base_offset: usize) -> Self {
+        self.base_offset = base_offset;
+        self
+    }
+
+    /// Skips the first `scroll` lines of bytes.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn scroll(mut self, scroll: usize) -> Self {
+        self.scroll = scroll;
+        self
+    }
+
+    /// Distinguishes sections by text attributes and boundary glyphs instead
+    /// of background colors.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn monochrome(mut self, monochrome: bool) -> Self {
+        self.monochrome = monochrome;
+        self
+    }
+
+    /// Numbers the first byte of each instruction and dims the bytes which
+    /// are not part of one.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn instruction_overlay(mut self, instruction_overlay: bool) -> Self {
+        self.instruction_overlay = instruction_overlay;
+        self
+    }
+
+    /// Shows `zoom` bytes per cell.
+    #[must_use = "method moves the value of self and returns the modified value"]
+    pub fn zoom(mut self, zoom: GridZoom) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    /// Number of cells rendered on each line for an inner area of `width`.
+    pub fn cells_per_line(zoom: GridZoom, width: u16) -> usize {
+        match zoom {
+            GridZoom::Hex => (width / 3) as usize,
+            GridZoom::Compact | GridZoom::Overview => width as usize,
+            GridZoom::Large => (width / LARGE_CELL_WIDTH) as usize,
+        }
+    }
+
+    /// Number of bytes each cell stands for, so that in the overview all
+    /// `num_bytes` fit in an inner area of `width` by `height`.
+    pub fn bytes_per_cell(zoom: GridZoom, width: u16, height: u16, num_bytes: usize) -> usize {
+        match zoom {
+            GridZoom::Hex | GridZoom::Compact | GridZoom::Large => 1,
+            GridZoom::Overview => {
+                let num_cells =
+                    Self::cells_per_line(zoom, width) * Self::visible_lines(zoom, height);
+                num_bytes.div_ceil(num_cells.max(1)).max(1)
+            }
+        }
+    }
+
+    /// Number of bytes rendered on each line for an inner area of `width` by
+    /// `height`.
+    pub fn bytes_per_line(zoom: GridZoom, width: u16, height: u16, num_bytes: usize) -> usize {
+        Self::cells_per_line(zoom, width) * Self::bytes_per_cell(zoom, width, height, num_bytes)
+    }
+
+    /// Number of lines of bytes rendered for an inner area of `height`.
+    pub fn visible_lines(zoom: GridZoom, height: u16) -> usize {
+        let height = height.saturating_sub(NUM_HEADER_LINES);
+        match zoom {
+            GridZoom::Hex | GridZoom::Compact | GridZoom::Overview => height as usize,
+            // The last line of bytes needs no spacing below.
+            GridZoom::Large => ((height + 1) / LARGE_LINE_HEIGHT) as usize,
+        }
+    }
+
     fn render_inner(&self, area: Rect, buf: &mut Buffer) {
         let len_bytes = self.sections.iter().map(|s| s.bytes.len()).sum::<usize>();
         if len_bytes == 0 {
             return;
         }
 
-        // Go byte by byte and render them.
-        // Make sure to only render as many bytes as can fit in the area.
-        let bytes_per_line = (area.width / 3) as usize;
-        let num_lines = 2
-            + len_bytes / bytes_per_line
-            + if len_bytes % bytes_per_line == 0 {
-                0
-            } else {
-                1
-            };
+        // Make sure to only render as many cells as can fit in the area.
+        let cells_per_line = Self::cells_per_line(self.zoom, area.width);
+        let visible_lines = Self::visible_lines(self.zoom, area.height);
+        if cells_per_line == 0 || visible_lines == 0 {
+            return;
+        }
 
+        let bytes = self
+            .sections
+            .iter()
+            .filter(|section| !section.bytes.is_empty())
+            .enumerate()
+            .flat_map(|(section_index, section)| {
+                let last = section.bytes.len() - 1;
+                section
+                    .bytes
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, byte)| ByteCell {
+                        offset: 0,
+                        byte: *byte,
+                        section_index,
+                        section,
+                        is_section_end: index == last,
+                        instruction_start: None,
+                    })
+            })
+            .enumerate();
+        let mut gaps = self.gaps.iter().peekable();
+        let mut cells = vec![];
+        let mut previous_instruction = None;
+        for (index, byte) in bytes {
+            let offset = self.base_offset + index;
+            while let Some((_, count)) = gaps.next_if(|(gap_offset, _)| *gap_offset <= offset) {
+                cells.extend(iter::repeat_n(None, *count));
+            }
+            let instruction = byte.section.kind.instruction_index();
+            let instruction_start = instruction.filter(|_| instruction != previous_instruction);
+            previous_instruction = instruction;
+            cells.push(Some(ByteCell {
+                offset,
+                instruction_start,
+                ..byte
+            }));
+        }
+        for (_, count) in gaps {
+            cells.extend(iter::repeat_n(None, *count));
+        }
+
+        match self.zoom {
+            GridZoom::Hex => self.render_hex(area, buf, &cells, cells_per_line, visible_lines),
+            GridZoom::Compact | GridZoom::Overview => {
+                let bytes_per_cell =
+                    Self::bytes_per_cell(self.zoom, area.width, area.height, cells.len());
+                self.render_blocks(area, buf, &cells, bytes_per_cell, cells_per_line);
+            }
+            GridZoom::Large => self.render_large(area, buf, &cells, cells_per_line, visible_lines),
+        }
+    }
+
+    fn render_hex(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        cells: &[Option<ByteCell>],
+        bytes_per_line: usize,
+        visible_lines: usize,
+    ) {
         // Split the current area into lines.
         let lines = Layout::default()
             .direction(Direction::Vertical)
-            .constraints((0..num_lines).map(|_| Constraint::Length(1)))
+            .constraints((0..area.height).map(|_| Constraint::Length(1)))
             .split(area);
         let line_layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints((0..bytes_per_line).map(|_| Constraint::Length(3)));
 
-        let mut byte_index = 0;
-        let mut line_index = 0;
-
-        let mut current_line_layout = line_layout.split(lines[line_index]);
-
         // Render the header
+        let header_layout = line_layout.split(lines[0]);
         for index in 0..bytes_per_line {
             let byte_text = Text::styled(format!("{:02} ", index), Style::default());
-            byte_text.render(current_line_layout[index], buf);
+            byte_text.render(header_layout[index], buf);
         }
         // Render a line of ----
-        line_index += 1;
-        current_line_layout = line_layout.split(lines[line_index]);
+        let separator_layout = line_layout.split(lines[1]);
         for index in 0..bytes_per_line {
             let byte_text = Text::styled("---", Style::default());
-            byte_text.render(current_line_layout[index], buf);
-        }
-        line_index += 1;
-        current_line_layout = line_layout.split(lines[line_index]);
-
-        for section in self.sections.iter() {
-            for byte in section.bytes.iter() {
-                let byte_text =
-                    Text::styled(format!("{:02x} ", byte), Style::default().bg(section.color));
-                byte_text.render(current_line_layout[byte_index % bytes_per_line], buf);
-
-                // Update the byte index and line index
-                byte_index += 1;
-                if byte_index % bytes_per_line == 0 && byte_index < len_bytes {
-                    line_index += 1;
-                    current_line_layout = line_layout.split(lines[line_index]);
+            byte_text.render(separator_layout[index], buf);
+        }
+
+        let first_cell = self.scroll * bytes_per_line;
+        let byte_lines = (0..visible_lines)
+            .map(|line_index| line_layout.split(lines[NUM_HEADER_LINES as usize + line_index]))
+            .collect::<Vec<_>>();
+        let cells = cells
+            .iter()
+            .enumerate()
+            .skip(first_cell)
+            .take(visible_lines * bytes_per_line);
+        for (index, cell) in cells {
+            let relative_offset = index - first_cell;
+            let Some(cell) = cell else {
+                Text::styled("·· ", Style::default().fg(Color::DarkGray)).render(
+                    byte_lines[relative_offset / bytes_per_line][relative_offset % bytes_per_line],
+                    buf,
+                );
+                continue;
+            };
+            let byte_text = if let Some(label) = self.instruction_label(cell) {
+                let style = if self.monochrome {
+                    self.monochrome_byte_style(cell.offset, cell.section_index)
+                } else {
+                    self.byte_style(cell.offset, cell.section)
+                };
+                Text::styled(format!("{label:<3}"), style.add_modifier(Modifier::BOLD))
+            } else if self.monochrome {
+                Text::styled(
+                    format!(
+                        "{:02x}{}",
+                        cell.byte,
+                        self.marker(cell.offset, cell.is_section_end)
+                    ),
+                    self.overlay_style(
+                        cell,
+                        self.monochrome_byte_style(cell.offset, cell.section_index),
+                    ),
+                )
+            } else {
+                Text::styled(
+                    format!("{:02x} ", cell.byte),
+                    self.overlay_style(cell, self.byte_style(cell.offset, cell.section)),
+                )
+            };
+            byte_text.render(
+                byte_lines[relative_offset / bytes_per_line][relative_offset % bytes_per_line],
+                buf,
+            );
+        }
+    }
+
+    /// Renders each byte in hex digits two lines tall.
+    fn render_large(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        cells: &[Option<ByteCell>],
+        bytes_per_line: usize,
+        visible_lines: usize,
+    ) {
+        for column in 0..bytes_per_line {
+            let x = area.x + column as u16 * LARGE_CELL_WIDTH;
+            buf.set_string(x, area.y, format!("{column:02}"), Style::default());
+        }
+        buf.set_string(
+            area.x,
+            area.y + 1,
+            "-".repeat(bytes_per_line * LARGE_CELL_WIDTH as usize),
+            Style::default(),
+        );
+
+        let first_cell = self.scroll * bytes_per_line;
+        let cells = cells
+            .iter()
+            .skip(first_cell)
+            .take(visible_lines * bytes_per_line);
+        for (index, cell) in cells.enumerate() {
+            let x = area.x + (index % bytes_per_line) as u16 * LARGE_CELL_WIDTH;
+            let y = area.y + NUM_HEADER_LINES + (index / bytes_per_line) as u16 * LARGE_LINE_HEIGHT;
+            let Some(cell) = cell else {
+                buf.set_string(x, y + 1, "·· ", Style::default().fg(Color::DarkGray));
+                continue;
+            };
+            let style = if self.monochrome {
+                let marker = self.marker(cell.offset, cell.is_section_end);
+                buf.set_string(
+                    x + LARGE_CELL_WIDTH - 2,
+                    y + 1,
+                    marker.to_string(),
+                    Style::default(),
+                );
+                self.monochrome_byte_style(cell.offset, cell.section_index)
+            } else {
+                self.byte_style(cell.offset, cell.section)
+            };
+            let style = self.overlay_style(cell, style);
+            for (line, text) in block_digits::byte(cell.byte).into_iter().enumerate() {
+                buf.set_string(x, y + line as u16, text, style);
+            }
+            // Below the first byte, in the spacing between lines of bytes.
+            if let Some(label) = self.instruction_label(cell) {
+                if y + 2 < area.bottom() {
+                    buf.set_string(
+                        x,
+                        y + 2,
+                        label,
+                        Style::default().add_modifier(Modifier::BOLD),
+                    );
                 }
             }
         }
     }
+
+    /// Renders each run of `bytes_per_cell` cells as one glyph, in the color
+    /// of the section most of its bytes belong to.
+    fn render_blocks(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        cells: &[Option<ByteCell>],
+        bytes_per_cell: usize,
+        cells_per_line: usize,
+    ) {
+        // Label every few columns with the offset of their first byte.
+        for column in (0..cells_per_line).step_by(BLOCK_LABEL_SPACING) {
+            let label = (column * bytes_per_cell).to_string();
+            if column + label.len() <= cells_per_line {
+                buf.set_string(area.x + column as u16, area.y, label, Style::default());
+            }
+        }
+        buf.set_string(
+            area.x,
+            area.y + 1,
+            "-".repeat(cells_per_line),
+            Style::default(),
+        );
+
+        let block_lines = area.height as usize - NUM_HEADER_LINES as usize;
+        let mut labels = vec![];
+        let blocks = cells
+            .chunks(bytes_per_cell)
+            .skip(self.scroll * cells_per_line)
+            .take(block_lines * cells_per_line);
+        for (index, block) in blocks.enumerate() {
+            let x = area.x + (index % cells_per_line) as u16;
+            let y = area.y + NUM_HEADER_LINES + (index / cells_per_line) as u16;
+            let bytes = block.iter().flatten().collect::<Vec<_>>();
+            let Some(dominant) = dominant_section(&bytes) else {
+                buf.set_string(x, y, "·", Style::default().fg(Color::DarkGray));
+                continue;
+            };
+            let (glyph, style) = if self.monochrome {
+                self.monochrome_block(&bytes, dominant.section_index)
+            } else {
+                ('▄', self.block_style(&bytes, dominant.section))
+            };
+            buf.set_string(x, y, glyph.to_string(), self.overlay_style(dominant, style));
+            labels.extend(
+                bytes
+                    .iter()
+                    .find_map(|byte| self.instruction_label(byte))
+                    .map(|label| (x, y, label)),
+            );
+        }
+        // Labels span several cells, so they go over the blocks.
+        for (x, y, label) in labels {
+            let width = (area.right() - x) as usize;
+            buf.set_stringn(
+                x,
+                y,
+                label,
+                width,
+                Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            );
+        }
+    }
+
+    /// The number marking the first byte of an instruction in the overlay.
+    fn instruction_label(&self, cell: &ByteCell) -> Option<String> {
+        let instruction = cell
+            .instruction_start
+            .filter(|_| self.instruction_overlay)?;
+        Some(format!("#{instruction}"))
+    }
+
+    /// Dims bytes outside of instructions in the overlay, except the cursor.
+    fn overlay_style(&self, cell: &ByteCell, style: Style) -> Style {
+        let is_dimmed = self.instruction_overlay
+            && cell.section.kind.instruction_index().is_none()
+            && self.cursor != Some(cell.offset);
+        if is_dimmed {
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::DIM)
+        } else {
+            style
+        }
+    }
+
+    /// The color of a block is the section's, so the half-block glyph leaves
+    /// a line of background between lines of blocks.
+    fn block_style(&self, bytes: &[&ByteCell], section: &TransactionByteSection) -> Style {
+        let offsets = || bytes.iter().map(|byte| byte.offset);
+        let mut style = Style::default().fg(section.color);
+        if offsets().any(|offset| {
+            self.annotations
+                .iter()
+                .any(|annotation| annotation.contains(offset))
+        }) {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        if offsets().any(|offset| {
+            self.highlights
+                .iter()
+                .any(|highlight| highlight.contains(&offset))
+        }) {
+            style = style.fg(Color::LightRed);
+        }
+        for (range, rule_style) in self.rule_matches {
+            if offsets().any(|offset| range.contains(&offset)) {
+                // Blocks are drawn in the foreground color.
+                style = style.patch(Style {
+                    fg: rule_style.bg,
+                    bg: None,
+                    ..*rule_style
+                });
+            }
+        }
+        if offsets().any(|offset| {
+            self.selection
+                .as_ref()
+                .is_some_and(|selection| selection.contains(&offset))
+        }) {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        if offsets().any(|offset| self.cursor == Some(offset)) {
+            style = style.fg(Color::White).remove_modifier(Modifier::REVERSED);
+        }
+        style
+    }
+
+    /// The glyph of a block in monochrome mode: the shade of its section,
+    /// or a mark for the cursor and edited bytes.
+    fn monochrome_block(&self, bytes: &[&ByteCell], section_index: usize) -> (char, Style) {
+        let glyph = if bytes.iter().any(|byte| self.cursor == Some(byte.offset)) {
+            '█'
+        } else if bytes.iter().any(|byte| {
+            self.highlights
+                .iter()
+                .any(|highlight| highlight.contains(&byte.offset))
+        }) {
+            '*'
+        } else {
+            SECTION_SHADES[section_index % SECTION_SHADES.len()]
+        };
+        let style = if bytes.iter().any(|byte| {
+            self.selection
+                .as_ref()
+                .is_some_and(|selection| selection.contains(&byte.offset))
+        }) {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+        };
+        (glyph, style)
+    }
+
+    fn byte_style(&self, offset: usize, section: &TransactionByteSection) -> Style {
+        let mut style = Style::default().bg(section.color);
+        if self
+            .annotations
+            .iter()
+            .any(|annotation| annotation.contains(offset))
+        {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        if self
+            .highlights
+            .iter()
+            .any(|highlight| highlight.contains(&offset))
+        {
+            style = style.fg(Color::LightRed).add_modifier(Modifier::BOLD);
+        }
+        style = self.rule_style(offset, style);
+        if self
+            .selection
+            .as_ref()
+            .is_some_and(|selection| selection.contains(&offset))
+        {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        if self.cursor == Some(offset) {
+            style = style.fg(Color::Black).bg(Color::White);
+        }
+        style
+    }
+
+    /// The glyph after a byte in monochrome mode, marking the cursor, edited
+    /// bytes and the end of each section.
+    fn marker(&self, offset: usize, is_section_end: bool) -> char {
+        if self.cursor == Some(offset) {
+            '<'
+        } else if self
+            .highlights
+            .iter()
+            .any(|highlight| highlight.contains(&offset))
+        {
+            '*'
+        } else if is_section_end {
+            '│'
+        } else {
+            ' '
+        }
+    }
+
+    /// Patch `style` with the styles of the rules matching the byte.
+    fn rule_style(&self, offset: usize, style: Style) -> Style {
+        self.rule_matches
+            .iter()
+            .filter(|(range, _)| range.contains(&offset))
+            .fold(style, |style, (_, rule_style)| style.patch(*rule_style))
+    }
+
+    fn monochrome_byte_style(&self, offset: usize, section_index: usize) -> Style {
+        let mut style = Style::default()
+            .add_modifier(SECTION_MODIFIERS[section_index % SECTION_MODIFIERS.len()]);
+        if self
+            .annotations
+            .iter()
+            .any(|annotation| annotation.contains(offset))
+        {
+            style = style.add_modifier(Modifier::ITALIC);
+        }
+        // Without colors, rules always embolden.
+        for (range, rule_style) in self.rule_matches {
+            if range.contains(&offset) {
+                style = style.add_modifier(Modifier::BOLD | rule_style.add_modifier);
+            }
+        }
+        // Invert the selection relative to the section's own attributes.
+        if self
+            .selection
+            .as_ref()
+            .is_some_and(|selection| selection.contains(&offset))
+        {
+            style = if style.add_modifier.contains(Modifier::REVERSED) {
+                style.remove_modifier(Modifier::REVERSED)
+            } else {
+                style.add_modifier(Modifier::REVERSED)
+            };
+        }
+        style
+    }
+}
+
+/// A byte of a section, with its offset in the transaction.
+#[derive(Clone, Copy)]
+struct ByteCell<'a> {
+    offset: usize,
+    byte: u8,
+    section_index: usize,
+    section: &'a TransactionByteSection,
+    is_section_end: bool,
+    /// The instruction this byte is the first of.
+    instruction_start: Option<usize>,
+}
+
+/// The byte of the section most of `bytes` belong to, the first on ties.
+fn dominant_section<'b, 'a>(bytes: &[&'b ByteCell<'a>]) -> Option<&'b ByteCell<'a>> {
+    let mut counts: Vec<(&ByteCell, usize)> = vec![];
+    for byte in bytes {
+        match counts
+            .iter_mut()
+            .find(|(first, _)| first.section_index == byte.section_index)
+        {
+            Some((_, count)) => *count += 1,
+            None => counts.push((byte, 1)),
+        }
+    }
+    counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(byte, _)| byte)
 }
 
 impl<'a> Widget for &TransactionByteBlock<'a> {
@@ -104,3 +709,136 @@ impl<'a> Widget for &TransactionByteBlock<'a> {
         self.render_inner(inner, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_transaction_tui::transaction_byte_sections::SectionKind};
+
+    fn section(len: usize, color: Color) -> TransactionByteSection {
+        TransactionByteSection {
+            label: None,
+            bytes: vec![0; len],
+            color,
+            kind: SectionKind::RecentBlockhash,
+        }
+    }
+
+    #[test]
+    fn overlay_numbers_instructions() {
+        let instruction_section = |kind| TransactionByteSection {
+            kind,
+            ..section(1, Color::Blue)
+        };
+        let sections = [
+            section(2, Color::Red),
+            instruction_section(SectionKind::InstructionProgramIdIndex(0)),
+            instruction_section(SectionKind::InstructionData(0)),
+            instruction_section(SectionKind::InstructionProgramIdIndex(1)),
+        ];
+        let area = Rect::new(0, 0, 15, 3);
+        let mut buf = Buffer::empty(area);
+        let byte_block = TransactionByteBlock::new(&sections).instruction_overlay(true);
+        (&byte_block).render(area, &mut buf);
+
+        let line = (0..area.width)
+            .map(|x| buf[(x, 2)].symbol())
+            .collect::<String>();
+        assert_eq!(line, "00 00 #0 00 #1 ");
+        assert_eq!(buf[(0, 2)].fg, Color::DarkGray);
+        assert_eq!(buf[(9, 2)].bg, Color::Blue);
+    }
+
+    #[test]
+    fn styles_a_suffix_by_its_offsets_within_the_transaction() {
+        // The message of a transaction whose signatures take 65 bytes.
+        let sections = [section(2, Color::Red), section(2, Color::Blue)];
+        let highlight = 67..68;
+        let area = Rect::new(0, 0, 15, 3);
+        let mut buf = Buffer::empty(area);
+        let byte_block = TransactionByteBlock::new(&sections)
+            .base_offset(65)
+            .cursor(Some(66))
+            .highlights(std::slice::from_ref(&highlight));
+        (&byte_block).render(area, &mut buf);
+
+        assert_eq!(buf[(0, 2)].bg, Color::Red);
+        assert_eq!(buf[(3, 2)].bg, Color::White);
+        assert_eq!(buf[(6, 2)].fg, Color::LightRed);
+        assert_eq!(buf[(9, 2)].fg, Color::Reset);
+    }
+
+    #[test]
+    fn monochrome_marks_sections_with_attributes_and_glyphs() {
+        let sections = [
+            section(2, Color::Red),
+            section(0, Color::Green),
+            section(1, Color::Blue),
+            section(1, Color::Red),
+        ];
+        let area = Rect::new(0, 0, 15, 3);
+        let mut buf = Buffer::empty(area);
+        let byte_block = TransactionByteBlock::new(&sections)
+            .monochrome(true)
+            .cursor(Some(1))
+            .selection(Some(3..4));
+        (&byte_block).render(area, &mut buf);
+
+        let line = (0..12).map(|x| buf[(x, 2)].symbol()).collect::<String>();
+        assert_eq!(line, "00 00<00│00│");
+        // Empty sections take no attribute of their own.
+        let modifiers = [0, 3, 6].map(|x| buf[(x, 2)].modifier);
+        assert_eq!(
+            modifiers,
+            [Modifier::UNDERLINED, Modifier::UNDERLINED, Modifier::BOLD]
+        );
+        assert!(buf[(0, 2)].bg == Color::Reset && buf[(6, 2)].bg == Color::Reset);
+        // The selection is inverted against the reversed third section.
+        assert_eq!(buf[(9, 2)].modifier, Modifier::empty());
+    }
+
+    #[test]
+    fn overview_colors_cells_by_dominant_section() {
+        let sections = [
+            section(10, Color::Red),
+            section(2, Color::Blue),
+            section(12, Color::Green),
+        ];
+        let (width, height) = (4, 4);
+        assert_eq!(
+            TransactionByteBlock::bytes_per_line(GridZoom::Overview, width, height, 24),
+            12
+        );
+        assert_eq!(
+            TransactionByteBlock::bytes_per_line(GridZoom::Compact, width, height, 24),
+            4
+        );
+
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        let byte_block = TransactionByteBlock::new(&sections)
+            .cursor(Some(4))
+            .zoom(GridZoom::Overview);
+        (&byte_block).render(area, &mut buf);
+        let colors = (NUM_HEADER_LINES..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|position| buf[position].fg)
+            .collect::<Vec<_>>();
+        // Each cell stands for 3 bytes, the fourth mostly for the second
+        // section.
+        assert_eq!(
+            colors,
+            [
+                Color::Red,
+                Color::White,
+                Color::Red,
+                Color::Blue,
+                Color::Green,
+                Color::Green,
+                Color::Green,
+                Color::Green,
+            ]
+        );
+        assert_eq!(buf[(0, 2)].symbol(), "▄");
+        assert_eq!(buf[(0, 0)].symbol(), "0");
+    }
+}
@@ -1,18 +1,165 @@
 use {
+    crate::message_format::{self, MessageField, MessageParts},
     ratatui::style::Color,
     solana_sdk::{
-        hash::Hash,
-        pubkey::Pubkey,
-        short_vec::ShortU16,
-        signature::Signature,
-        transaction::{TransactionVersion, VersionedTransaction},
+        hash::Hash, pubkey::Pubkey, short_vec::ShortU16, signature::Signature,
+        transaction::VersionedTransaction,
     },
 };
 
+#[derive(Clone)]
 pub struct TransactionByteSection {
     pub label: Option<String>,
     pub bytes: Vec<u8>,
     pub color: Color,
+    pub kind: SectionKind,
+}
+
+impl TransactionByteSection {
+    /// The pubkey held by an account key or lookup table address section.
+    pub fn pubkey(&self) -> Option<Pubkey> {
+        match self.kind {
+            SectionKind::StaticAccountKey(_) | SectionKind::AddressTableLookupAddress(_) => {
+                Pubkey::try_from(self.bytes.as_slice()).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Identifies which field of the transaction a section corresponds to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SectionKind {
+    SignatureCount,
+    Signature(usize),
+    VersionByte,
+    NumRequiredSignatures,
+    NumReadonlySignedAccounts,
+    NumReadonlyUnsignedAccounts,
+    StaticAccountKeysCount,
+    StaticAccountKey(usize),
+    RecentBlockhash,
+    InstructionsCount,
+    InstructionProgramIdIndex(usize),
+    InstructionAccountsCount(usize),
+    InstructionAccounts(usize),
+    InstructionDataLength(usize),
+    InstructionData(usize),
+    AddressTableLookupsCount,
+    AddressTableLookupAddress(usize),
+    AddressTableLookupWriteCount(usize),
+    AddressTableLookupWriteSet(usize),
+    AddressTableLookupReadCount(usize),
+    AddressTableLookupReadSet(usize),
+}
+
+/// The part of the transaction a section belongs to, for size reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SectionCategory {
+    Signatures,
+    Header,
+    AccountKeys,
+    Instructions,
+    LookupTables,
+}
+
+impl SectionCategory {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Signatures => "Signatures",
+            Self::Header => "Header",
+            Self::AccountKeys => "Account keys",
+            Self::Instructions => "Instructions",
+            Self::LookupTables => "Address table lookups",
+        }
+    }
+}
+
+impl SectionKind {
+    pub fn category(&self) -> SectionCategory {
+        match self {
+            Self::SignatureCount | Self::Signature(_) => SectionCategory::Signatures,
+            Self::VersionByte
+            | Self::NumRequiredSignatures
+            | Self::NumReadonlySignedAccounts
+            | Self::NumReadonlyUnsignedAccounts
+            | Self::RecentBlockhash => SectionCategory::Header,
+            Self::StaticAccountKeysCount | Self::StaticAccountKey(_) => {
+                SectionCategory::AccountKeys
+            }
+            Self::InstructionsCount
+            | Self::InstructionProgramIdIndex(_)
+            | Self::InstructionAccountsCount(_)
+            | Self::InstructionAccounts(_)
+            | Self::InstructionDataLength(_)
+            | Self::InstructionData(_) => SectionCategory::Instructions,
+            Self::AddressTableLookupsCount
+            | Self::AddressTableLookupAddress(_)
+            | Self::AddressTableLookupWriteCount(_)
+            | Self::AddressTableLookupWriteSet(_)
+            | Self::AddressTableLookupReadCount(_)
+            | Self::AddressTableLookupReadSet(_) => SectionCategory::LookupTables,
+        }
+    }
+
+    /// The index of the instruction this section belongs to, if any.
+    pub fn instruction_index(&self) -> Option<usize> {
+        match self {
+            Self::InstructionProgramIdIndex(index)
+            | Self::InstructionAccountsCount(index)
+            | Self::InstructionAccounts(index)
+            | Self::InstructionDataLength(index)
+            | Self::InstructionData(index) => Some(*index),
+            _ => None,
+        }
+    }
+}
+
+/// Each section with the offset of its first byte.
+pub fn section_starts(
+    sections: &[TransactionByteSection],
+) -> impl Iterator<Item = (usize, &TransactionByteSection)> {
+    sections.iter().scan(0, |offset, section| {
+        let start = *offset;
+        *offset += section.bytes.len();
+        Some((start, section))
+    })
+}
+
+/// Find the section containing the byte at `offset`.
+pub fn section_at(
+    sections: &[TransactionByteSection],
+    offset: usize,
+) -> Option<&TransactionByteSection> {
+    let mut section_start = 0;
+    for section in sections {
+        let section_end = section_start + section.bytes.len();
+        if (section_start..section_end).contains(&offset) {
+            return Some(section);
+        }
+        section_start = section_end;
+    }
+    None
+}
+
+/// Split off the signature sections, returning the byte offset at which the
+/// message starts along with the sections making up the message. The message
+/// bytes are exactly what signers sign.
+pub fn message_sections(sections: &[TransactionByteSection]) -> (usize, &[TransactionByteSection]) {
+    let num_signature_sections = sections
+        .iter()
+        .take_while(|section| {
+            matches!(
+                section.kind,
+                SectionKind::SignatureCount | SectionKind::Signature(_)
+            )
+        })
+        .count();
+    let message_start = sections[..num_signature_sections]
+        .iter()
+        .map(|section| section.bytes.len())
+        .sum();
+    (message_start, &sections[num_signature_sections..])
 }
 
 thread_local! {
@@ -29,14 +176,27 @@ pub fn get_transaction_byte_sections(
     // Get the transaction raw bytes
     let bytes = bincode::serialize(&transaction).unwrap();
 
-    // Split the bytes into sections by content.
+    // Split the bytes into sections by content, following the fields of the
+    // message format.
     let mut offset = 0;
     add_signature_sections(transaction, &bytes, sections, &mut offset);
-    add_message_header_sections(transaction, &bytes, sections, &mut offset);
-    add_static_account_keys_sections(transaction, &bytes, sections, &mut offset);
-    add_recent_blockhash_section(transaction, &bytes, sections, &mut offset);
-    add_instructions_sections(transaction, &bytes, sections, &mut offset);
-    add_message_address_table_lookups_sections(transaction, &bytes, sections, &mut offset);
+    // Only the signatures are split from a message of no supported format.
+    let Some((format, parts)) = message_format::format_of(&transaction.message)
+        .and_then(|format| Some((format, format.parts(&transaction.message)?)))
+    else {
+        return;
+    };
+    for field in format.fields() {
+        let add_sections = match field {
+            MessageField::VersionByte => add_version_byte_section,
+            MessageField::Header => add_message_header_sections,
+            MessageField::StaticAccountKeys => add_static_account_keys_sections,
+            MessageField::RecentBlockhash => add_recent_blockhash_section,
+            MessageField::Instructions => add_instructions_sections,
+            MessageField::AddressTableLookups => add_message_address_table_lookups_sections,
+        };
+        add_sections(&parts, &bytes, sections, &mut offset);
+    }
 }
 
 fn add_signature_sections(
@@ -45,77 +205,93 @@ fn add_signature_sections(
     sections: &mut Vec<TransactionByteSection>,
     offset: &mut usize,
 ) {
+    let num_signatures_bytes =
+        bincode::serialized_size(&ShortU16(transaction.signatures.len() as u16)).unwrap() as usize;
     sections.push(TransactionByteSection {
         label: Some("Signature Count".to_owned()),
-        bytes: get_bytes(bytes, offset, 1),
+        bytes: get_bytes(bytes, offset, num_signatures_bytes),
         color: COLOR_SET.with(|color_set| color_set.signature_count_color),
+        kind: SectionKind::SignatureCount,
     });
 
     for (index, _signature) in transaction.signatures.iter().enumerate() {
         sections.push(TransactionByteSection {
             label: Some(format!("Signature ({index})")),
             bytes: get_bytes(bytes, offset, core::mem::size_of::<Signature>()),
-            color: COLOR_SET.with(|color_set| color_set.static_account_key_colors[index]),
+            color: COLOR_SET.with(|color_set| color_set.account_key_color(index)),
+            kind: SectionKind::Signature(index),
         })
     }
 }
 
+fn add_version_byte_section(
+    _parts: &MessageParts,
+    bytes: &[u8],
+    sections: &mut Vec<TransactionByteSection>,
+    offset: &mut usize,
+) {
+    sections.push(TransactionByteSection {
+        label: Some("Version Byte".to_owned()),
+        bytes: get_bytes(bytes, offset, 1),
+        color: COLOR_SET.with(|color_set| color_set.version_byte_color),
+        kind: SectionKind::VersionByte,
+    });
+}
+
 fn add_message_header_sections(
-    transaction: &VersionedTransaction,
+    _parts: &MessageParts,
     bytes: &[u8],
     sections: &mut Vec<TransactionByteSection>,
     offset: &mut usize,
 ) {
-    match transaction.version() {
-        TransactionVersion::Legacy(_) => {}
-        TransactionVersion::Number(_) => {
-            sections.push(TransactionByteSection {
-                label: Some("Version Byte".to_owned()),
-                bytes: get_bytes(bytes, offset, 1),
-                color: COLOR_SET.with(|color_set| color_set.version_byte_color),
-            });
-        }
-    }
     sections.push(TransactionByteSection {
         label: Some("num_required_signatures".to_owned()),
         bytes: get_bytes(bytes, offset, 1),
         color: COLOR_SET.with(|color_set| color_set.num_required_signatures_color),
+        kind: SectionKind::NumRequiredSignatures,
     });
     sections.push(TransactionByteSection {
         label: Some("num_readonly_signed_accounts".to_owned()),
         bytes: get_bytes(bytes, offset, 1),
         color: COLOR_SET.with(|color_set| color_set.num_readonly_signed_accounts_color),
+        kind: SectionKind::NumReadonlySignedAccounts,
     });
     sections.push(TransactionByteSection {
         label: Some("num_readonly_unsigned_accounts".to_owned()),
         bytes: get_bytes(bytes, offset, 1),
         color: COLOR_SET.with(|color_set| color_set.num_readonly_unsigned_accounts_color),
+        kind: SectionKind::NumReadonlyUnsignedAccounts,
     });
 }
 
 fn add_static_account_keys_sections(
-    transaction: &VersionedTransaction,
+    parts: &MessageParts,
     bytes: &[u8],
     sections: &mut Vec<TransactionByteSection>,
     offset: &mut usize,
 ) {
+    let num_account_keys_bytes =
+        bincode::serialized_size(&ShortU16(parts.static_account_keys.len() as u16)).unwrap()
+            as usize;
     sections.push(TransactionByteSection {
         label: Some("Static Account Keys Count".to_owned()),
-        bytes: get_bytes(bytes, offset, 1),
+        bytes: get_bytes(bytes, offset, num_account_keys_bytes),
         color: Color::Yellow,
+        kind: SectionKind::StaticAccountKeysCount,
     });
 
-    for (index, _account_key) in transaction.message.static_account_keys().iter().enumerate() {
+    for (index, _account_key) in parts.static_account_keys.iter().enumerate() {
         sections.push(TransactionByteSection {
             label: Some(format!("Static Account Key ({index})")),
             bytes: get_bytes(bytes, offset, core::mem::size_of::<Pubkey>()),
-            color: COLOR_SET.with(|color_set| color_set.static_account_key_colors[index]),
+            color: COLOR_SET.with(|color_set| color_set.account_key_color(index)),
+            kind: SectionKind::StaticAccountKey(index),
         });
     }
 }
 
 fn add_recent_blockhash_section(
-    _transaction: &VersionedTransaction,
+    _parts: &MessageParts,
     bytes: &[u8],
     sections: &mut Vec<TransactionByteSection>,
     offset: &mut usize,
@@ -125,26 +301,27 @@ fn add_recent_blockhash_section(
         label: Some("Recent Blockhash".to_owned()),
         bytes: recent_blockhash_bytes,
         color: COLOR_SET.with(|color_set| color_set.recent_blockhash_color),
+        kind: SectionKind::RecentBlockhash,
     });
 }
 
 fn add_instructions_sections(
-    transaction: &VersionedTransaction,
+    parts: &MessageParts,
     bytes: &[u8],
     sections: &mut Vec<TransactionByteSection>,
     offset: &mut usize,
 ) {
     let num_instructions_count_bytes =
-        bincode::serialized_size(&ShortU16(transaction.message.instructions().len() as u16))
-            .unwrap() as usize;
+        bincode::serialized_size(&ShortU16(parts.instructions.len() as u16)).unwrap() as usize;
     let num_instructions_count_bytes = get_bytes(bytes, offset, num_instructions_count_bytes);
     sections.push(TransactionByteSection {
         label: Some("Number of Instructions".to_owned()),
         bytes: num_instructions_count_bytes,
         color: COLOR_SET.with(|color_set| color_set.num_instructions_color),
+        kind: SectionKind::InstructionsCount,
     });
 
-    for instruction in transaction.message.instructions() {
+    for (instruction_index, instruction) in parts.instructions.iter().enumerate() {
         let program_id_index = instruction.program_id_index as usize;
         sections.push(TransactionByteSection {
             label: None, // color corresponds to the program id
@@ -156,6 +333,7 @@ fn add_instructions_sections(
                     .copied()
                     .unwrap_or(Color::White)
             }),
+            kind: SectionKind::InstructionProgramIdIndex(instruction_index),
         });
 
         let num_accounts_bytes =
@@ -166,12 +344,14 @@ fn add_instructions_sections(
             label: Some("Instruction Number of Accounts".to_owned()),
             bytes: num_accounts_bytes,
             color: COLOR_SET.with(|color_set| color_set.instruction_num_accounts_color),
+            kind: SectionKind::InstructionAccountsCount(instruction_index),
         });
         let accounts_bytes = get_bytes(bytes, offset, instruction.accounts.len());
         sections.push(TransactionByteSection {
             label: Some("Instruction Accounts".to_owned()),
             bytes: accounts_bytes,
             color: COLOR_SET.with(|color_set| color_set.instruction_accounts_color),
+            kind: SectionKind::InstructionAccounts(instruction_index),
         });
 
         let data_length_bytes =
@@ -181,25 +361,25 @@ fn add_instructions_sections(
             label: Some("Instruction Data Length".to_owned()),
             bytes: data_length_bytes,
             color: COLOR_SET.with(|color_set| color_set.instruction_data_length_color),
+            kind: SectionKind::InstructionDataLength(instruction_index),
         });
         let data = get_bytes(bytes, offset, instruction.data.len());
         sections.push(TransactionByteSection {
             label: Some("Instruction Data".to_owned()),
             bytes: data,
             color: COLOR_SET.with(|color_set| color_set.instruction_data_color),
+            kind: SectionKind::InstructionData(instruction_index),
         });
     }
 }
 
 fn add_message_address_table_lookups_sections(
-    transaction: &VersionedTransaction,
+    parts: &MessageParts,
     bytes: &[u8],
     sections: &mut Vec<TransactionByteSection>,
     offset: &mut usize,
 ) {
-    let Some(address_table_lookups) = transaction.message.address_table_lookups() else {
-        return;
-    };
+    let address_table_lookups = parts.address_table_lookups;
 
     let num_address_table_lookups_bytes =
         bincode::serialized_size(&ShortU16(address_table_lookups.len() as u16)).unwrap() as usize;
@@ -208,43 +388,53 @@ fn add_message_address_table_lookups_sections(
         label: Some("Message Address Table Lookups Count".to_owned()),
         bytes: num_address_table_lookups_bytes,
         color: COLOR_SET.with(|color_set| color_set.atl_count_color),
+        kind: SectionKind::AddressTableLookupsCount,
     });
 
-    for _atl in address_table_lookups {
+    for (atl_index, atl) in address_table_lookups.iter().enumerate() {
         // Address
         let address = get_bytes(bytes, offset, core::mem::size_of::<Pubkey>());
         sections.push(TransactionByteSection {
             label: Some("Message Address Table Lookup Address".to_owned()),
             bytes: address,
             color: COLOR_SET.with(|color_set| color_set.atl_address_color),
+            kind: SectionKind::AddressTableLookupAddress(atl_index),
         });
 
         // Write
-        let write_count_bytes = get_bytes(bytes, offset, 1);
-        let write_count = write_count_bytes[0] as usize;
+        let write_count = atl.writable_indexes.len();
+        let write_count_bytes =
+            bincode::serialized_size(&ShortU16(write_count as u16)).unwrap() as usize;
+        let write_count_bytes = get_bytes(bytes, offset, write_count_bytes);
         sections.push(TransactionByteSection {
             label: Some("Message Address Table Lookup Write Count".to_owned()),
             bytes: write_count_bytes,
             color: COLOR_SET.with(|color_set| color_set.atl_write_count_color),
+            kind: SectionKind::AddressTableLookupWriteCount(atl_index),
         });
         sections.push(TransactionByteSection {
             label: Some("Message Address Table Lookup Write Set".to_owned()),
             bytes: get_bytes(bytes, offset, write_count),
             color: COLOR_SET.with(|color_set| color_set.atl_write_set_color),
+            kind: SectionKind::AddressTableLookupWriteSet(atl_index),
         });
 
         // Read
-        let read_count_bytes = get_bytes(bytes, offset, 1);
-        let read_count = read_count_bytes[0] as usize;
+        let read_count = atl.readonly_indexes.len();
+        let read_count_bytes =
+            bincode::serialized_size(&ShortU16(read_count as u16)).unwrap() as usize;
+        let read_count_bytes = get_bytes(bytes, offset, read_count_bytes);
         sections.push(TransactionByteSection {
             label: Some("Message Address Table Lookup Read Count".to_owned()),
             bytes: read_count_bytes,
             color: COLOR_SET.with(|color_set| color_set.atl_read_count_color),
+            kind: SectionKind::AddressTableLookupReadCount(atl_index),
         });
         sections.push(TransactionByteSection {
             label: Some("Message Address Table Lookup Read Set".to_owned()),
             bytes: get_bytes(bytes, offset, read_count),
             color: COLOR_SET.with(|color_set| color_set.atl_read_set_color),
+            kind: SectionKind::AddressTableLookupReadSet(atl_index),
         });
     }
 }
@@ -344,6 +534,12 @@ struct TransactionColorSet {
 }
 
 impl TransactionColorSet {
+    /// Color for the account key (or signature) at `index`, cycling through
+    /// the palette if there are more keys than colors.
+    fn account_key_color(&self, index: usize) -> Color {
+        self.static_account_key_colors[index % self.static_account_key_colors.len()]
+    }
+
     fn new() -> Self {
         const NUM_NON_ACCOUNT_COLORS: usize = 17;
         let color_set = generate_color_set();
@@ -371,3 +567,188 @@ impl TransactionColorSet {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::ingest,
+        proptest::{collection::vec, prelude::*},
+        solana_sdk::{
+            instruction::CompiledInstruction,
+            message::{
+                legacy,
+                v0::{self, MessageAddressTableLookup},
+                MessageHeader, VersionedMessage,
+            },
+        },
+    };
+
+    fn pubkey() -> impl Strategy<Value = Pubkey> {
+        any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+    }
+
+    fn instruction(num_account_keys: usize) -> impl Strategy<Value = CompiledInstruction> {
+        // Indexes are a u8, even with more account keys than fit in one.
+        let index = (0..num_account_keys.min(256)).prop_map(|index| index as u8);
+        (index.clone(), vec(index, 0..140), vec(any::<u8>(), 0..300)).prop_map(
+            |(program_id_index, accounts, data)| CompiledInstruction {
+                program_id_index,
+                accounts,
+                data,
+            },
+        )
+    }
+
+    fn address_table_lookup() -> impl Strategy<Value = MessageAddressTableLookup> {
+        (pubkey(), vec(any::<u8>(), 0..140), vec(any::<u8>(), 0..140)).prop_map(
+            |(account_key, writable_indexes, readonly_indexes)| MessageAddressTableLookup {
+                account_key,
+                writable_indexes,
+                readonly_indexes,
+            },
+        )
+    }
+
+    /// Transactions with consistent headers, signature counts and indexes,
+    /// including counts large enough to need multi-byte compact-u16 encoding.
+    fn transaction() -> impl Strategy<Value = VersionedTransaction> {
+        (1..140usize, 0..140usize, any::<bool>())
+            .prop_flat_map(|(num_signers, num_unsigned, is_v0)| {
+                let num_account_keys = num_signers + num_unsigned;
+                (
+                    Just(is_v0),
+                    0..num_signers as u8,
+                    0..=num_unsigned as u8,
+                    vec(pubkey(), num_account_keys),
+                    any::<[u8; 32]>(),
+                    vec(instruction(num_account_keys), 0..6),
+                    vec(address_table_lookup(), 0..3),
+                    vec(any::<[u8; 64]>(), num_signers),
+                )
+            })
+            .prop_map(
+                |(
+                    is_v0,
+                    num_readonly_signed_accounts,
+                    num_readonly_unsigned_accounts,
+                    account_keys,
+                    recent_blockhash,
+                    instructions,
+                    address_table_lookups,
+                    signatures,
+                )| {
+                    let header = MessageHeader {
+                        num_required_signatures: signatures.len() as u8,
+                        num_readonly_signed_accounts,
+                        num_readonly_unsigned_accounts,
+                    };
+                    let recent_blockhash = Hash::new_from_array(recent_blockhash);
+                    let message = if is_v0 {
+                        VersionedMessage::V0(v0::Message {
+                            header,
+                            account_keys,
+                            recent_blockhash,
+                            instructions,
+                            address_table_lookups,
+                        })
+                    } else {
+                        VersionedMessage::Legacy(legacy::Message {
+                            header,
+                            account_keys,
+                            recent_blockhash,
+                            instructions,
+                        })
+                    };
+                    VersionedTransaction {
+                        signatures: signatures.into_iter().map(Signature::from).collect(),
+                        message,
+                    }
+                },
+            )
+    }
+
+    proptest! {
+        #[test]
+        fn sections_cover_serialization(transaction in transaction()) {
+            let mut sections = vec![];
+            get_transaction_byte_sections(&transaction, &mut sections);
+
+            let section_bytes = sections
+                .iter()
+                .flat_map(|section| section.bytes.iter().copied())
+                .collect::<Vec<_>>();
+            let bytes = bincode::serialize(&transaction).unwrap();
+            prop_assert_eq!(&section_bytes, &bytes);
+
+            // Legacy messages with 128 or more signers look versioned, and
+            // only parse as their format when it is forced.
+            let format = message_format::format_of(&transaction.message).unwrap();
+            let parsed = ingest::parse_transaction_as(&bytes, format).unwrap();
+            prop_assert_eq!(bincode::serialize(&parsed).unwrap(), bytes);
+
+            let (message_start, message_sections) = message_sections(&sections);
+            let message_bytes = message_sections
+                .iter()
+                .flat_map(|section| section.bytes.iter().copied())
+                .collect::<Vec<_>>();
+            prop_assert_eq!(message_start, suffix_offset(&sections, message_sections));
+            prop_assert_eq!(message_bytes, transaction.message.serialize());
+        }
+    }
+
+    #[test]
+    fn categorizes_the_bytes_of_each_part() {
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(v0::Message {
+                header: MessageHeader {
+                    num_required_signatures: 1,
+                    num_readonly_signed_accounts: 0,
+                    num_readonly_unsigned_accounts: 1,
+                },
+                account_keys: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+                recent_blockhash: Hash::new_unique(),
+                instructions: vec![CompiledInstruction::new_from_raw_parts(
+                    1,
+                    vec![1, 2],
+                    vec![0],
+                )],
+                address_table_lookups: vec![MessageAddressTableLookup {
+                    account_key: Pubkey::new_unique(),
+                    writable_indexes: vec![0],
+                    readonly_indexes: vec![],
+                }],
+            }),
+        };
+        let mut sections = vec![];
+        get_transaction_byte_sections(&transaction, &mut sections);
+
+        let mut sizes = std::collections::BTreeMap::new();
+        for section in &sections {
+            *sizes.entry(section.kind.category()).or_insert(0) += section.bytes.len();
+        }
+        assert_eq!(
+            sizes.into_iter().collect::<Vec<_>>(),
+            [
+                (SectionCategory::Signatures, 1 + 64),
+                // The version, the counts of the header and the blockhash.
+                (SectionCategory::Header, 1 + 3 + 32),
+                (SectionCategory::AccountKeys, 1 + 2 * 32),
+                (SectionCategory::Instructions, 1 + 1 + 1 + 1 + 1 + 2),
+                (SectionCategory::LookupTables, 1 + 32 + 1 + 1 + 1),
+            ]
+        );
+    }
+
+    /// The offset of the first of `suffix`, a suffix of `sections`.
+    fn suffix_offset(
+        sections: &[TransactionByteSection],
+        suffix: &[TransactionByteSection],
+    ) -> usize {
+        sections[..sections.len() - suffix.len()]
+            .iter()
+            .map(|section| section.bytes.len())
+            .sum()
+    }
+}
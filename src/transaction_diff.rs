@@ -0,0 +1,405 @@
+//! Structural diff of two transactions. Rather than comparing bytes at the
+//! same offsets, it pairs account keys by pubkey and instructions by their
+//! content, so an inserted key or instruction shows as added instead of
+//! shifting everything after it.
+
+use {
+    crate::{message_format, transaction_byte_sections::SectionKind},
+    solana_sdk::{message::VersionedMessage, pubkey::Pubkey, transaction::VersionedTransaction},
+    std::fmt,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    /// The same content at a different position, relative to the rest.
+    Moved,
+    Modified,
+}
+
+/// The part of a transaction which changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Signatures,
+    Version,
+    Header,
+    AccountKey(Pubkey),
+    RecentBlockhash,
+    Instruction,
+    AddressTableLookup(Pubkey),
+}
+
+/// A change from the left transaction to the right one, with the index of
+/// the changed item on each side, if it has one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub field: Field,
+    pub left: Option<usize>,
+    pub right: Option<usize>,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self.kind {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Moved => "moved",
+            ChangeKind::Modified => "modified",
+        };
+        let field = match self.field {
+            Field::Signatures => "signatures".to_string(),
+            Field::Version => "version".to_string(),
+            Field::Header => "header".to_string(),
+            Field::AccountKey(pubkey) => format!("account key {pubkey}"),
+            Field::RecentBlockhash => "recent blockhash".to_string(),
+            Field::Instruction => "instruction".to_string(),
+            Field::AddressTableLookup(pubkey) => format!("lookup table {pubkey}"),
+        };
+        write!(f, "{kind} {field}")?;
+        match (self.left, self.right) {
+            (Some(left), Some(right)) if left != right => write!(f, " ({left} -> {right})"),
+            (Some(index), _) | (_, Some(index)) => write!(f, " ({index})"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+pub struct TransactionDiff {
+    pub changes: Vec<Change>,
+    /// The left index of each right account key, if it has a counterpart.
+    key_pairs: Vec<Option<usize>>,
+    /// The left index of each right instruction, if it has a counterpart.
+    instruction_pairs: Vec<Option<usize>>,
+}
+
+impl TransactionDiff {
+    pub fn new(left: &VersionedTransaction, right: &VersionedTransaction) -> Self {
+        let mut changes = vec![];
+        if left.signatures != right.signatures {
+            changes.push(modified(Field::Signatures));
+        }
+        let (left_message, right_message) = (&left.message, &right.message);
+        let format_name = |message| message_format::format_of(message).map(|format| format.name());
+        if format_name(left_message) != format_name(right_message) {
+            changes.push(modified(Field::Version));
+        }
+        if left_message.header() != right_message.header() {
+            changes.push(modified(Field::Header));
+        }
+
+        let left_keys = left_message.static_account_keys();
+        let right_keys = right_message.static_account_keys();
+        let key_pairs = pair(left_keys, right_keys, |_, _| false);
+        changes.extend(key_pairs.iter().filter_map(|paired| {
+            let pubkey = paired.left.map_or_else(
+                || right_keys[paired.right.unwrap()],
+                |index| left_keys[index],
+            );
+            paired.change(Field::AccountKey(pubkey))
+        }));
+
+        if left_message.recent_blockhash() != right_message.recent_blockhash() {
+            changes.push(modified(Field::RecentBlockhash));
+        }
+
+        let left_instructions = resolved_instructions(left_message);
+        let right_instructions = resolved_instructions(right_message);
+        let instruction_pairs = pair(&left_instructions, &right_instructions, |left, right| {
+            left.program_id == right.program_id
+        });
+        changes.extend(
+            instruction_pairs
+                .iter()
+                .filter_map(|paired| paired.change(Field::Instruction)),
+        );
+
+        let lookups = |message: &VersionedMessage| {
+            message
+                .address_table_lookups()
+                .unwrap_or_default()
+                .iter()
+                .map(|lookup| {
+                    (
+                        lookup.account_key,
+                        lookup.writable_indexes.clone(),
+                        lookup.readonly_indexes.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let (left_lookups, right_lookups) = (lookups(left_message), lookups(right_message));
+        changes.extend(
+            pair(&left_lookups, &right_lookups, |left, right| {
+                left.0 == right.0
+            })
+            .iter()
+            .filter_map(|paired| {
+                let (address, ..) = paired.left.map_or_else(
+                    || &right_lookups[paired.right.unwrap()],
+                    |index| &left_lookups[index],
+                );
+                paired.change(Field::AddressTableLookup(*address))
+            }),
+        );
+
+        Self {
+            changes,
+            key_pairs: counterparts(&key_pairs, right_keys.len()),
+            instruction_pairs: counterparts(&instruction_pairs, right_instructions.len()),
+        }
+    }
+
+    /// The kind of the left section corresponding to a right section, so
+    /// that sections of paired keys and instructions match. Sections of
+    /// added keys and instructions get an index no left section has.
+    pub fn left_kind(&self, right_kind: SectionKind) -> SectionKind {
+        let left_index = |pairs: &[Option<usize>], index: usize| {
+            pairs
+                .get(index)
+                .copied()
+                .flatten()
+                .unwrap_or(usize::MAX - index)
+        };
+        match right_kind {
+            SectionKind::StaticAccountKey(index) => {
+                SectionKind::StaticAccountKey(left_index(&self.key_pairs, index))
+            }
+            SectionKind::InstructionProgramIdIndex(index) => {
+                SectionKind::InstructionProgramIdIndex(left_index(&self.instruction_pairs, index))
+            }
+            SectionKind::InstructionAccountsCount(index) => {
+                SectionKind::InstructionAccountsCount(left_index(&self.instruction_pairs, index))
+            }
+            SectionKind::InstructionAccounts(index) => {
+                SectionKind::InstructionAccounts(left_index(&self.instruction_pairs, index))
+            }
+            SectionKind::InstructionDataLength(index) => {
+                SectionKind::InstructionDataLength(left_index(&self.instruction_pairs, index))
+            }
+            SectionKind::InstructionData(index) => {
+                SectionKind::InstructionData(left_index(&self.instruction_pairs, index))
+            }
+            kind => kind,
+        }
+    }
+}
+
+fn modified(field: Field) -> Change {
+    Change {
+        kind: ChangeKind::Modified,
+        field,
+        left: None,
+        right: None,
+    }
+}
+
+/// An instruction with its account indexes resolved to static account keys
+/// where possible, so that instructions compare by what they refer to.
+#[derive(PartialEq)]
+struct ResolvedInstruction {
+    program_id: Option<Pubkey>,
+    accounts: Vec<Result<Pubkey, u8>>,
+    data: Vec<u8>,
+}
+
+fn resolved_instructions(message: &VersionedMessage) -> Vec<ResolvedInstruction> {
+    let keys = message.static_account_keys();
+    let resolve = |index: u8| keys.get(index as usize).copied().ok_or(index);
+    message
+        .instructions()
+        .iter()
+        .map(|instruction| ResolvedInstruction {
+            program_id: resolve(instruction.program_id_index).ok(),
+            accounts: instruction.accounts.iter().copied().map(resolve).collect(),
+            data: instruction.data.clone(),
+        })
+        .collect()
+}
+
+/// An item of either side paired with its counterpart, if any.
+struct Paired {
+    left: Option<usize>,
+    right: Option<usize>,
+    /// `None` if unchanged.
+    kind: Option<ChangeKind>,
+}
+
+impl Paired {
+    fn change(&self, field: Field) -> Option<Change> {
+        Some(Change {
+            kind: self.kind?,
+            field,
+            left: self.left,
+            right: self.right,
+        })
+    }
+}
+
+/// Pair equal items, keeping their order where possible: a longest common
+/// subsequence is unchanged, and other equal items moved. Remaining items
+/// for which `is_modified` holds are paired in order as modified, and the
+/// rest are removed from the left or added on the right.
+fn pair<T: PartialEq>(
+    left: &[T],
+    right: &[T],
+    is_modified: impl Fn(&T, &T) -> bool,
+) -> Vec<Paired> {
+    // common[i][j] is the length of the longest common subsequence of
+    // left[i..] and right[j..].
+    let mut common = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            common[i][j] = if left[i] == right[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut pairs = vec![];
+    let mut left_paired = vec![false; left.len()];
+    let mut right_paired = vec![false; right.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        if left[i] == right[j] {
+            left_paired[i] = true;
+            right_paired[j] = true;
+            pairs.push(Paired {
+                left: Some(i),
+                right: Some(j),
+                kind: None,
+            });
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    for kind in [ChangeKind::Moved, ChangeKind::Modified] {
+        for i in 0..left.len() {
+            if left_paired[i] {
+                continue;
+            }
+            let matches = |j: &usize| {
+                !right_paired[*j]
+                    && match kind {
+                        ChangeKind::Moved => left[i] == right[*j],
+                        _ => is_modified(&left[i], &right[*j]),
+                    }
+            };
+            if let Some(j) = (0..right.len()).find(matches) {
+                left_paired[i] = true;
+                right_paired[j] = true;
+                pairs.push(Paired {
+                    left: Some(i),
+                    right: Some(j),
+                    kind: Some(kind),
+                });
+            }
+        }
+    }
+    pairs.extend(
+        (0..left.len())
+            .filter(|i| !left_paired[*i])
+            .map(|i| Paired {
+                left: Some(i),
+                right: None,
+                kind: Some(ChangeKind::Removed),
+            }),
+    );
+    pairs.extend(
+        (0..right.len())
+            .filter(|j| !right_paired[*j])
+            .map(|j| Paired {
+                left: None,
+                right: Some(j),
+                kind: Some(ChangeKind::Added),
+            }),
+    );
+    pairs.sort_by_key(|paired| (paired.right.or(paired.left), paired.left));
+    pairs
+}
+
+fn counterparts(pairs: &[Paired], num_right: usize) -> Vec<Option<usize>> {
+    let mut counterparts = vec![None; num_right];
+    for paired in pairs {
+        if let (Some(left), Some(right)) = (paired.left, paired.right) {
+            counterparts[right] = Some(left);
+        }
+    }
+    counterparts
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash,
+            instruction::{AccountMeta, Instruction},
+            message::Message,
+        },
+    };
+
+    fn transaction(instructions: &[Instruction]) -> VersionedTransaction {
+        let payer = Pubkey::new_from_array([1; 32]);
+        VersionedTransaction {
+            signatures: vec![Default::default()],
+            message: VersionedMessage::Legacy(Message::new_with_blockhash(
+                instructions,
+                Some(&payer),
+                &Hash::default(),
+            )),
+        }
+    }
+
+    #[test]
+    fn classifies_added_moved_and_modified() {
+        let program_id = Pubkey::new_from_array([2; 32]);
+        let other_program_id = Pubkey::new_from_array([3; 32]);
+        let account = Pubkey::new_from_array([4; 32]);
+        let first = Instruction::new_with_bytes(program_id, &[1], vec![]);
+        let second = Instruction::new_with_bytes(program_id, &[2], vec![]);
+        let third = Instruction::new_with_bytes(other_program_id, &[3], vec![]);
+        let left = transaction(&[first.clone(), second, third.clone()]);
+        let right = transaction(&[
+            third,
+            first,
+            Instruction::new_with_bytes(
+                program_id,
+                &[2, 2],
+                vec![AccountMeta::new(account, false)],
+            ),
+        ]);
+
+        let diff = TransactionDiff::new(&left, &right);
+        let changes = diff
+            .changes
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        // Keys are sorted by role, so the new writable account goes before
+        // the programs and pushes them along.
+        assert_eq!(
+            changes,
+            [
+                format!("added account key {account} (1)"),
+                "moved instruction (0 -> 1)".to_string(),
+                "modified instruction (1 -> 2)".to_string(),
+            ]
+        );
+        assert_eq!(
+            diff.left_kind(SectionKind::InstructionData(0)),
+            SectionKind::InstructionData(2)
+        );
+        assert_eq!(
+            diff.left_kind(SectionKind::StaticAccountKey(2)),
+            SectionKind::StaticAccountKey(1)
+        );
+    }
+}
@@ -2,16 +2,22 @@ use {
     ratatui::{
         backend::CrosstermBackend,
         crossterm::{
+            event::{
+                DisableBracketedPaste, DisableFocusChange, EnableBracketedPaste, EnableFocusChange,
+            },
             execute,
             terminal::{
                 disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+                SetTitle,
             },
         },
         Terminal,
     },
-    std::io::{self, stdout, Stdout},
+    std::io::{self, stdout, Stdout, Write},
 };
 
+pub const APP_TITLE: &str = "solana-transaction-tui";
+
 /// A type alias for the terminal type used in this application
 pub type Tui = Terminal<CrosstermBackend<Stdout>>;
 
@@ -22,14 +28,70 @@ pub fn init() -> io::Result<Tui> {
         eprintln!("{info}");
     }));
 
-    execute!(stdout(), EnterAlternateScreen)?;
+    execute!(
+        stdout(),
+        EnterAlternateScreen,
+        EnableFocusChange,
+        EnableBracketedPaste
+    )?;
     enable_raw_mode()?;
     Terminal::new(CrosstermBackend::new(stdout()))
 }
 
 /// Restore the terminal to its original state
 pub fn restore() -> io::Result<()> {
-    execute!(stdout(), LeaveAlternateScreen)?;
+    execute!(
+        stdout(),
+        LeaveAlternateScreen,
+        DisableFocusChange,
+        DisableBracketedPaste
+    )?;
     disable_raw_mode()?;
     Ok(())
 }
+
+/// Set the title of the terminal window
+pub fn set_title(title: &str) -> io::Result<()> {
+    execute!(stdout(), SetTitle(title))
+}
+
+/// Ask the terminal emulator to show a desktop notification. VTE based
+/// terminals understand OSC 777, most others OSC 9.
+pub fn notify(body: &str) -> io::Result<()> {
+    let mut stdout = stdout();
+    let is_vte = std::env::var_os("VTE_VERSION").is_some();
+    write!(stdout, "{}", notification(body, is_vte))?;
+    stdout.flush()
+}
+
+/// The escape sequence of a notification. Control characters would end the
+/// sequence early, so they are left out of the body.
+fn notification(body: &str, is_vte: bool) -> String {
+    let body = body.chars().filter(|c| !c.is_control()).collect::<String>();
+    if is_vte {
+        format!("\x1b]777;notify;{APP_TITLE};{body}\x07")
+    } else {
+        format!("\x1b]9;{body}\x07")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notifies_in_the_sequence_of_the_terminal() {
+        assert_eq!(
+            notification("Transaction loaded", true),
+            "\x1b]777;notify;solana-transaction-tui;Transaction loaded\x07"
+        );
+        assert_eq!(
+            notification("Transaction loaded", false),
+            "\x1b]9;Transaction loaded\x07"
+        );
+        assert_eq!(
+            notification("Not\x07 found\n", false),
+            "\x1b]9;Not found\x07"
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! A playground for the variable length integer encodings of Solana types:
+//! compact-u16, the length prefix of the transaction format, and LEB128
+//! varints, which `serde_varint` uses for fields such as vote lockouts.
+
+use solana_sdk::short_vec::{self, ShortU16};
+
+pub fn encode_compact_u16(value: u16) -> Vec<u8> {
+    bincode::serialize(&ShortU16(value)).unwrap()
+}
+
+pub fn encode_varint(mut value: u64) -> Vec<u8> {
+    let mut bytes = vec![];
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+/// The value at the start of `bytes`, with the number of bytes it takes.
+pub fn decode_compact_u16(bytes: &[u8]) -> Result<(u16, usize), String> {
+    short_vec::decode_shortu16_len(bytes)
+        .map(|(value, len)| (value as u16, len))
+        .map_err(|_| "not a valid compact-u16".to_string())
+}
+
+/// The value at the start of `bytes`, with the number of bytes it takes.
+pub fn decode_varint(bytes: &[u8]) -> Result<(u64, usize), String> {
+    let mut value = 0u64;
+    for (index, byte) in bytes.iter().enumerate().take(10) {
+        let bits = (byte & 0x7f) as u64;
+        let shift = 7 * index as u32;
+        if bits.checked_shl(shift).map(|shifted| shifted >> shift) != Some(bits) {
+            return Err("overflows a u64".to_string());
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok((value, index + 1));
+        }
+    }
+    Err("ends before the last byte".to_string())
+}
+
+/// The encodings of the input as a decimal number, and its decodings as
+/// hex bytes, for whichever reading is valid.
+pub fn evaluate(input: &str) -> Vec<String> {
+    let input = input.trim();
+    let mut lines = vec![];
+    if let Ok(value) = input.parse::<u64>() {
+        lines.push(format!("{value} as a number"));
+        lines.push(match u16::try_from(value) {
+            Ok(value) => format!(
+                "  compact-u16  {}",
+                describe_bytes(&encode_compact_u16(value))
+            ),
+            Err(_) => format!("  compact-u16  out of range, at most {}", u16::MAX),
+        });
+        lines.push(format!(
+            "  varint       {}",
+            describe_bytes(&encode_varint(value))
+        ));
+    }
+    let hex = input.trim_start_matches("0x").replace(' ', "");
+    if let Some(bytes) = hex::decode(&hex).ok().filter(|bytes| !bytes.is_empty()) {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(format!("{} as hex bytes", hex::encode(&bytes)));
+        lines.push(format!(
+            "  compact-u16  {}",
+            describe_decoded(decode_compact_u16(&bytes).map(|(value, len)| (value as u64, len)))
+        ));
+        lines.push(format!(
+            "  varint       {}",
+            describe_decoded(decode_varint(&bytes))
+        ));
+    }
+    lines
+}
+
+fn describe_bytes(bytes: &[u8]) -> String {
+    let hex = bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    match bytes.len() {
+        1 => format!("{hex} (1 byte)"),
+        len => format!("{hex} ({len} bytes)"),
+    }
+}
+
+fn describe_decoded(decoded: Result<(u64, usize), String>) -> String {
+    match decoded {
+        Ok((value, 1)) => format!("{value} (1 byte)"),
+        Ok((value, len)) => format!("{value} ({len} bytes)"),
+        Err(err) => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_and_decodes_both_ways() {
+        assert_eq!(encode_compact_u16(0x3fff), [0xff, 0x7f]);
+        assert_eq!(encode_compact_u16(0x4000), [0x80, 0x80, 0x01]);
+        assert_eq!(decode_compact_u16(&[0x80, 0x80, 0x01]), Ok((0x4000, 3)));
+        // Alias encodings are rejected.
+        assert!(decode_compact_u16(&[0x80, 0x00]).is_err());
+        for value in [0, 127, 128, 300, u64::MAX] {
+            let bytes = encode_varint(value);
+            assert_eq!(decode_varint(&bytes), Ok((value, bytes.len())));
+        }
+        assert!(decode_varint(&[0x80]).is_err());
+
+        assert_eq!(
+            evaluate("300"),
+            [
+                "300 as a number",
+                "  compact-u16  ac 02 (2 bytes)",
+                "  varint       ac 02 (2 bytes)",
+            ]
+        );
+        assert_eq!(
+            evaluate("ac 02"),
+            [
+                "ac02 as hex bytes",
+                "  compact-u16  300 (2 bytes)",
+                "  varint       300 (2 bytes)",
+            ]
+        );
+    }
+}
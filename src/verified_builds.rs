@@ -0,0 +1,111 @@
+//! Whether the programs a transaction invokes are verified builds of their
+//! source, according to the OtterSec verified builds registry, to review what
+//! a transaction runs before signing it.
+
+use {
+    crate::endpoint_health::public_endpoint, serde::Deserialize, solana_sdk::pubkey::Pubkey,
+    std::time::Duration,
+};
+
+const VERIFY_API: &str = "https://verify.osec.io/status";
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// Hosts of local validators, whose programs the registry cannot know.
+const LOCAL_HOSTS: [&str; 2] = ["localhost", "127.0.0.1"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BuildStatus {
+    pub is_verified: bool,
+    /// The repository, and usually commit, the program was verified against.
+    #[serde(default)]
+    pub repo_url: Option<String>,
+}
+
+impl BuildStatus {
+    /// A short mark for the legend, e.g. `verified build of <repo>`.
+    pub fn mark(&self) -> String {
+        let repo = self.repo_url.as_deref().filter(|repo| !repo.is_empty());
+        match (self.is_verified, repo) {
+            (true, Some(repo)) => format!("✓ verified build of {repo}"),
+            (true, None) => "✓ verified build".to_string(),
+            (false, _) => "✗ unverified build".to_string(),
+        }
+    }
+}
+
+/// Whether the registry knows the programs of an endpoint, as it only
+/// verifies mainnet deployments.
+pub fn covers(endpoint: &str) -> bool {
+    public_endpoint(endpoint) == "https://api.mainnet-beta.solana.com"
+        && !LOCAL_HOSTS.iter().any(|host| endpoint.contains(host))
+}
+
+/// Query the registry for the build status of each program.
+pub fn check(program_ids: &[Pubkey]) -> Vec<(Pubkey, Result<BuildStatus, String>)> {
+    let client = match reqwest::blocking::Client::builder()
+        .timeout(TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(err) => {
+            let err = format!("Failed to create an HTTP client: {err}");
+            return program_ids
+                .iter()
+                .map(|program_id| (*program_id, Err(err.clone())))
+                .collect();
+        }
+    };
+    program_ids
+        .iter()
+        .map(|program_id| (*program_id, status(&client, program_id)))
+        .collect()
+}
+
+fn status(client: &reqwest::blocking::Client, program_id: &Pubkey) -> Result<BuildStatus, String> {
+    let body = client
+        .get(format!("{VERIFY_API}/{program_id}"))
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.text())
+        .map_err(|err| format!("Failed to check the build of {program_id}: {err}"))?;
+    serde_json::from_str(&body)
+        .map_err(|err| format!("Invalid build status of {program_id}: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_build_statuses() {
+        let verified = serde_json::from_str::<BuildStatus>(
+            r#"{
+                "is_verified": true,
+                "message": "On chain program verified",
+                "on_chain_hash": "ab12",
+                "executable_hash": "ab12",
+                "last_verified_at": "2024-08-30T12:00:00Z",
+                "repo_url": "https://github.com/owner/repo/tree/0123abc"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            verified.mark(),
+            "✓ verified build of https://github.com/owner/repo/tree/0123abc"
+        );
+        let unverified = serde_json::from_str::<BuildStatus>(
+            r#"{"is_verified": false, "message": "On chain program not verified", "repo_url": ""}"#,
+        )
+        .unwrap();
+        assert_eq!(unverified.mark(), "✗ unverified build");
+    }
+
+    #[test]
+    fn covers_only_mainnet_endpoints() {
+        assert!(covers("https://api.mainnet-beta.solana.com"));
+        assert!(covers("https://rpc.example.com/?api-key=1"));
+        assert!(!covers("https://api.devnet.solana.com"));
+        assert!(!covers("https://api.testnet.solana.com"));
+        assert!(!covers("http://localhost:8899"));
+        assert!(!covers("http://127.0.0.1:8899"));
+    }
+}
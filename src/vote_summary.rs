@@ -0,0 +1,141 @@
+//! A compact summary of vote transactions, which are mostly tower state that
+//! says little as bytes.
+
+use solana_sdk::{
+    clock::{Slot, UnixTimestamp},
+    hash::Hash,
+    pubkey::Pubkey,
+    transaction::VersionedTransaction,
+    vote::{self, instruction::VoteInstruction, state::VoteStateUpdate},
+};
+
+pub struct VoteSummary {
+    pub instruction: &'static str,
+    pub vote_account: Option<Pubkey>,
+    pub authority: Option<Pubkey>,
+    /// The voted slots, oldest first.
+    pub slots: Vec<Slot>,
+    pub root: Option<Slot>,
+    pub hash: Hash,
+    pub timestamp: Option<UnixTimestamp>,
+    /// The hash of the fork switched from, for votes with a switching proof.
+    pub switch_hash: Option<Hash>,
+}
+
+impl VoteSummary {
+    /// Summarize a transaction with a single vote instruction, as validators
+    /// send them.
+    pub fn of(transaction: &VersionedTransaction) -> Option<Self> {
+        let message = &transaction.message;
+        let [instruction] = message.instructions() else {
+            return None;
+        };
+        let keys = message.static_account_keys();
+        if keys.get(instruction.program_id_index as usize) != Some(&vote::program::id()) {
+            return None;
+        }
+        let account = |index: Option<&u8>| keys.get(*index? as usize).copied();
+        // The vote account comes first and the authority last, whichever
+        // sysvars are in between.
+        let vote_account = account(instruction.accounts.first());
+        let authority = account(instruction.accounts.last());
+
+        let update = |instruction, update: VoteStateUpdate, switch_hash| {
+            let slots = update.slots();
+            (
+                instruction,
+                slots,
+                update.root,
+                update.hash,
+                update.timestamp,
+                switch_hash,
+            )
+        };
+        let (instruction, slots, root, hash, timestamp, switch_hash) =
+            match bincode::deserialize::<VoteInstruction>(&instruction.data).ok()? {
+                VoteInstruction::Vote(vote) => {
+                    ("vote", vote.slots, None, vote.hash, vote.timestamp, None)
+                }
+                VoteInstruction::VoteSwitch(vote, switch_hash) => (
+                    "vote switch",
+                    vote.slots,
+                    None,
+                    vote.hash,
+                    vote.timestamp,
+                    Some(switch_hash),
+                ),
+                VoteInstruction::UpdateVoteState(vote_state_update) => {
+                    update("update vote state", vote_state_update, None)
+                }
+                VoteInstruction::UpdateVoteStateSwitch(vote_state_update, switch_hash) => update(
+                    "update vote state switch",
+                    vote_state_update,
+                    Some(switch_hash),
+                ),
+                VoteInstruction::CompactUpdateVoteState(vote_state_update) => {
+                    update("compact update vote state", vote_state_update, None)
+                }
+                VoteInstruction::CompactUpdateVoteStateSwitch(vote_state_update, switch_hash) => {
+                    update(
+                        "compact update vote state switch",
+                        vote_state_update,
+                        Some(switch_hash),
+                    )
+                }
+                _ => return None,
+            };
+        Some(Self {
+            instruction,
+            vote_account,
+            authority,
+            slots,
+            root,
+            hash,
+            timestamp,
+            switch_hash,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            instruction::Instruction,
+            message::{Message, VersionedMessage},
+            system_instruction,
+            vote::instruction as vote_instruction,
+        },
+    };
+
+    fn transaction(instruction: Instruction) -> VersionedTransaction {
+        let payer = Pubkey::new_unique();
+        VersionedTransaction {
+            signatures: vec![Default::default()],
+            message: VersionedMessage::Legacy(Message::new(&[instruction], Some(&payer))),
+        }
+    }
+
+    #[test]
+    fn summarizes_compact_vote_state_updates() {
+        let (vote_account, authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut update =
+            VoteStateUpdate::from(vec![(287_000_000, 3), (287_000_001, 2), (287_000_002, 1)]);
+        update.root = Some(286_999_968);
+        update.timestamp = Some(1_725_019_200);
+        let instruction =
+            vote_instruction::compact_update_vote_state(&vote_account, &authority, update);
+
+        let summary = VoteSummary::of(&transaction(instruction)).unwrap();
+        assert_eq!(summary.instruction, "compact update vote state");
+        assert_eq!(summary.vote_account, Some(vote_account));
+        assert_eq!(summary.authority, Some(authority));
+        assert_eq!(summary.slots, [287_000_000, 287_000_001, 287_000_002]);
+        assert_eq!(summary.root, Some(286_999_968));
+        assert_eq!(summary.timestamp, Some(1_725_019_200));
+
+        let transfer = system_instruction::transfer(&authority, &vote_account, 1);
+        assert!(VoteSummary::of(&transaction(transfer)).is_none());
+    }
+}
@@ -0,0 +1,403 @@
+//! Watching the transactions of new blocks as they are confirmed, or only
+//! those of the subscribed addresses and programs, flagging those which
+//! contain a pattern of the watchlist, as a lightweight on-chain tripwire.
+
+use {
+    crate::{failures::FailureKind, find, rpc::RpcApi},
+    serde::{Deserialize, Serialize},
+    solana_client::rpc_config::RpcBlockConfig,
+    solana_sdk::{
+        commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature,
+        transaction::VersionedTransaction, vote,
+    },
+    solana_transaction_status::{
+        TransactionDetails, UiLoadedAddresses, UiTransactionEncoding, UiTransactionStatusMeta,
+    },
+    std::{
+        collections::{HashMap, VecDeque},
+        str::FromStr,
+        time::Duration,
+    },
+};
+
+/// How long to wait between polls for new blocks, about a slot.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(400);
+/// At most this many blocks are fetched per poll, so falling behind catches
+/// up over several polls.
+const MAX_BLOCKS_PER_POLL: usize = 4;
+/// The stream keeps this many of the latest transactions.
+const MAX_TRANSACTIONS: usize = 1000;
+/// At most this many transactions are buffered while paused, or taken from a
+/// single poll, so bursts do not stall the app. The oldest are dropped.
+pub const MAX_PENDING: usize = 500;
+
+/// An address or program whose transactions are streamed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Subscription {
+    pub address: Pubkey,
+    pub enabled: bool,
+}
+
+/// The addresses of the enabled subscriptions, or `None` to stream every
+/// transaction.
+pub fn enabled(subscriptions: &[Subscription]) -> Option<Vec<Pubkey>> {
+    let addresses = subscriptions
+        .iter()
+        .filter(|subscription| subscription.enabled)
+        .map(|subscription| subscription.address)
+        .collect::<Vec<_>>();
+    (!addresses.is_empty()).then_some(addresses)
+}
+
+#[derive(Clone)]
+pub struct StreamedTransaction {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub transaction: VersionedTransaction,
+    pub meta: Option<UiTransactionStatusMeta>,
+    /// The patterns of the watchlist the transaction contains.
+    pub matches: Vec<String>,
+}
+
+impl StreamedTransaction {
+    pub fn signature(&self) -> Signature {
+        self.transaction
+            .signatures
+            .first()
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// What the transaction failed with, if it failed.
+    pub fn failure(&self) -> Option<FailureKind> {
+        let err = self.meta.as_ref()?.err.as_ref()?;
+        let message = &self.transaction.message;
+        Some(FailureKind::new(err, |index| {
+            let instruction = message.instructions().get(index as usize)?;
+            let program_index = instruction.program_id_index as usize;
+            message.static_account_keys().get(program_index).copied()
+        }))
+    }
+
+    /// Whether the transaction references the address, including through
+    /// its lookup tables.
+    pub fn references(&self, address: &Pubkey) -> bool {
+        if self
+            .transaction
+            .message
+            .static_account_keys()
+            .contains(address)
+        {
+            return true;
+        }
+        self.meta
+            .as_ref()
+            .and_then(|meta| Option::<&UiLoadedAddresses>::from(meta.loaded_addresses.as_ref()))
+            .is_some_and(|loaded_addresses| {
+                loaded_addresses
+                    .writable
+                    .iter()
+                    .chain(&loaded_addresses.readonly)
+                    .any(|loaded| Pubkey::from_str(loaded).as_ref() == Ok(address))
+            })
+    }
+}
+
+/// The transactions of the blocks after a slot.
+pub struct Batch {
+    /// The last slot fetched.
+    pub last_slot: u64,
+    pub transactions: Vec<StreamedTransaction>,
+    /// The transactions of the blocks which failed to decode.
+    pub num_undecoded: usize,
+}
+
+/// Fetch the non-vote transactions of the blocks after `after_slot`, or of
+/// the latest block if `None`.
+pub fn poll(rpc: &dyn RpcApi, after_slot: Option<u64>) -> Result<Batch, String> {
+    let latest = rpc
+        .get_slot(CommitmentConfig::confirmed())
+        .map_err(|err| format!("Failed to get the slot: {err}"))?;
+    let slots = match after_slot {
+        None => vec![latest],
+        Some(after_slot) if after_slot >= latest => vec![],
+        Some(after_slot) => rpc
+            .get_blocks(after_slot + 1, Some(latest))
+            .map_err(|err| format!("Failed to get blocks after {after_slot}: {err}"))?,
+    };
+    let slots = &slots[..slots.len().min(MAX_BLOCKS_PER_POLL)];
+    let mut transactions = vec![];
+    let mut num_undecoded = 0;
+    for &slot in slots {
+        let config = RpcBlockConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            transaction_details: Some(TransactionDetails::Full),
+            rewards: Some(false),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let block = rpc
+            .get_block(slot, config)
+            .map_err(|err| format!("Failed to get block {slot}: {err}"))?;
+        for encoded in block.transactions.unwrap_or_default() {
+            let Some(transaction) = encoded.transaction.decode() else {
+                num_undecoded += 1;
+                continue;
+            };
+            if !is_vote(&transaction) {
+                transactions.push(StreamedTransaction {
+                    slot,
+                    block_time: block.block_time,
+                    transaction,
+                    meta: encoded.meta,
+                    matches: vec![],
+                });
+            }
+        }
+    }
+    Ok(Batch {
+        last_slot: slots
+            .last()
+            .copied()
+            .unwrap_or(after_slot.unwrap_or(latest)),
+        transactions,
+        num_undecoded,
+    })
+}
+
+fn is_vote(transaction: &VersionedTransaction) -> bool {
+    let message = &transaction.message;
+    message.instructions().iter().any(|instruction| {
+        instruction.program_id(message.static_account_keys()) == &vote::program::id()
+    })
+}
+
+/// The patterns of the watchlist with their bytes, skipping those which do
+/// not parse.
+pub fn compile(watchlist: &[String]) -> Vec<(String, Vec<u8>)> {
+    watchlist
+        .iter()
+        .filter_map(|pattern| Some((pattern.clone(), find::parse(pattern).ok()?)))
+        .collect()
+}
+
+/// What a batch added to the stream.
+pub struct Pushed {
+    pub num_added: usize,
+    /// How many of those added match the watchlist.
+    pub num_matches: usize,
+}
+
+/// The latest streamed transactions, newest first.
+#[derive(Default)]
+pub struct Stream {
+    pub transactions: VecDeque<StreamedTransaction>,
+    /// The transactions streamed while paused, newest first, shown once
+    /// resumed.
+    pub pending: VecDeque<StreamedTransaction>,
+    pub paused: bool,
+    /// The transactions dropped for lack of room in the buffer.
+    pub num_dropped: usize,
+    /// The last slot fetched, from which the next poll continues.
+    pub last_slot: Option<u64>,
+    /// Bumped each time the watch starts, so that polls still in flight from
+    /// an earlier watch are dropped rather than polling alongside.
+    pub generation: u64,
+    pub error: Option<String>,
+    pub num_matches: usize,
+    /// How many transactions each subscription streamed.
+    pub subscription_counts: HashMap<Pubkey, usize>,
+}
+
+impl Stream {
+    /// Add the transactions of a batch referencing one of the `subscribed`
+    /// addresses, or all if `None`, flagging those matching the watchlist.
+    /// The newest `num_added` of the arrivals are those of the batch.
+    pub fn push(
+        &mut self,
+        batch: Batch,
+        subscribed: Option<&[Pubkey]>,
+        watchlist: &[(String, Vec<u8>)],
+    ) -> Pushed {
+        self.last_slot = Some(batch.last_slot);
+        self.error = None;
+        let mut transactions = batch.transactions;
+        if let Some(subscribed) = subscribed {
+            transactions.retain(|streamed| {
+                let mut referenced = false;
+                for address in subscribed {
+                    if streamed.references(address) {
+                        *self.subscription_counts.entry(*address).or_default() += 1;
+                        referenced = true;
+                    }
+                }
+                referenced
+            });
+        }
+        let num_dropped = transactions.len().saturating_sub(MAX_PENDING);
+        transactions.drain(..num_dropped);
+        self.num_dropped += num_dropped;
+        let num_added = transactions.len();
+        let mut num_matches = 0;
+        for mut streamed in transactions {
+            if !watchlist.is_empty() {
+                let bytes = bincode::serialize(&streamed.transaction).unwrap_or_default();
+                streamed.matches = watchlist
+                    .iter()
+                    .filter(|(_, pattern)| !find::occurrences(pattern, &bytes).is_empty())
+                    .map(|(text, _)| text.clone())
+                    .collect();
+            }
+            if !streamed.matches.is_empty() {
+                num_matches += 1;
+            }
+            self.arrivals_mut().push_front(streamed);
+        }
+        if self.paused {
+            self.num_dropped += self.pending.len().saturating_sub(MAX_PENDING);
+            self.pending.truncate(MAX_PENDING);
+        } else {
+            self.transactions.truncate(MAX_TRANSACTIONS);
+        }
+        self.num_matches += num_matches;
+        Pushed {
+            num_added,
+            num_matches,
+        }
+    }
+
+    /// Where new transactions go, newest first: the buffer while paused.
+    pub fn arrivals(&self) -> &VecDeque<StreamedTransaction> {
+        if self.paused {
+            &self.pending
+        } else {
+            &self.transactions
+        }
+    }
+
+    fn arrivals_mut(&mut self) -> &mut VecDeque<StreamedTransaction> {
+        if self.paused {
+            &mut self.pending
+        } else {
+            &mut self.transactions
+        }
+    }
+
+    /// Pause, or resume showing the transactions buffered since. Returns
+    /// the number shown.
+    pub fn toggle_pause(&mut self) -> usize {
+        self.paused = !self.paused;
+        if self.paused {
+            return 0;
+        }
+        let num_resumed = self.pending.len();
+        while let Some(streamed) = self.pending.pop_back() {
+            self.transactions.push_front(streamed);
+        }
+        self.transactions.truncate(MAX_TRANSACTIONS);
+        num_resumed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash, pubkey::Pubkey, signature::Keypair, signer::Signer, system_instruction,
+            transaction::Transaction,
+        },
+    };
+
+    #[test]
+    fn flags_transactions_matching_the_watchlist() {
+        let streamed = |to: &Pubkey| {
+            let payer = Keypair::new();
+            let instruction = system_instruction::transfer(&payer.pubkey(), to, 1);
+            StreamedTransaction {
+                slot: 7,
+                block_time: None,
+                transaction: Transaction::new_signed_with_payer(
+                    &[instruction],
+                    Some(&payer.pubkey()),
+                    &[&payer],
+                    Hash::new_unique(),
+                )
+                .into(),
+                meta: None,
+                matches: vec![],
+            }
+        };
+        // Random, as the bytes of unique pubkeys can recur in unique
+        // blockhashes.
+        let watched = Keypair::new().pubkey();
+        let watchlist = compile(&[watched.to_string(), "not a pattern".to_string()]);
+        assert_eq!(watchlist.len(), 1);
+
+        let mut stream = Stream::default();
+        let batch = Batch {
+            last_slot: 7,
+            transactions: vec![streamed(&Pubkey::new_unique()), streamed(&watched)],
+            num_undecoded: 0,
+        };
+        assert_eq!(stream.push(batch, None, &watchlist).num_matches, 1);
+        assert_eq!(stream.last_slot, Some(7));
+        // Newest first.
+        assert_eq!(stream.transactions[0].matches, [watched.to_string()]);
+        assert!(stream.transactions[1].matches.is_empty());
+
+        // Only the transactions of the subscriptions are streamed.
+        let batch = Batch {
+            last_slot: 8,
+            transactions: vec![streamed(&Pubkey::new_unique()), streamed(&watched)],
+            num_undecoded: 0,
+        };
+        let subscriptions = [
+            Subscription {
+                address: watched,
+                enabled: true,
+            },
+            Subscription {
+                address: Pubkey::new_unique(),
+                enabled: false,
+            },
+        ];
+        let subscribed = enabled(&subscriptions).unwrap();
+        assert_eq!(subscribed, [watched]);
+        assert_eq!(stream.push(batch, Some(&subscribed), &[]).num_added, 1);
+        assert_eq!(stream.subscription_counts[&watched], 1);
+        assert!(stream.transactions[0].references(&watched));
+    }
+
+    #[test]
+    fn buffers_while_paused_and_drops_the_oldest() {
+        let streamed = |slot| StreamedTransaction {
+            slot,
+            block_time: None,
+            transaction: VersionedTransaction::default(),
+            meta: None,
+            matches: vec![],
+        };
+        let batch = |slot, len| Batch {
+            last_slot: slot,
+            transactions: (0..len).map(|_| streamed(slot)).collect(),
+            num_undecoded: 0,
+        };
+        let mut stream = Stream::default();
+        stream.push(batch(1, 1), None, &[]);
+        assert_eq!(stream.toggle_pause(), 0);
+        stream.push(batch(2, MAX_PENDING - 1), None, &[]);
+        stream.push(batch(3, 2), None, &[]);
+        assert_eq!(stream.transactions.len(), 1);
+        assert_eq!(stream.pending.len(), MAX_PENDING);
+        assert_eq!(stream.num_dropped, 1);
+
+        assert_eq!(stream.toggle_pause(), MAX_PENDING);
+        assert_eq!(stream.transactions.len(), MAX_PENDING + 1);
+        assert_eq!(stream.transactions[0].slot, 3);
+        assert_eq!(stream.transactions[MAX_PENDING].slot, 1);
+        // A burst larger than the buffer keeps its newest transactions.
+        stream.push(batch(4, MAX_PENDING + 5), None, &[]);
+        assert_eq!(stream.num_dropped, 6);
+    }
+}